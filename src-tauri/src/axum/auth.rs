@@ -0,0 +1,351 @@
+//! Bearer-token authentication with per-key scopes
+//!
+//! Every request except the public paths below must carry
+//! `Authorization: Bearer <key>`. The key's scopes are checked against the
+//! scope required by the route before the handler runs.
+//!
+//! The desktop app's own admin key is issued at startup, so a fresh install
+//! has a key before the first request. Keys for other tools
+//! are created from the app with `create_api_key`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::Response,
+};
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::axum::error::ApiError;
+use crate::axum::state::AppState;
+use crate::database::DatabaseConnection;
+use crate::models::ApiScope;
+use crate::repository::ApiKeyRepository;
+use crate::sys::error::AppError;
+
+/// Paths reachable without a key. Clip images are loaded through `<img>`
+/// tags, which cannot send an Authorization header; share links carry their
+/// own one-time token.
+const PUBLIC_PATH_PREFIXES: &[&str] = &[
+    "/api/health",
+    "/swagger-ui",
+    "/api-docs",
+    "/clips/images",
+    "/share/",
+];
+
+/// How often a key's usage is written back; requests in between are only
+/// counted in memory
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Scope a request needs, derived from its method and path. Deleting a paper
+/// also deletes its files, so it needs an admin key.
+pub fn required_scope(method: &Method, path: &str) -> ApiScope {
+    if path.starts_with("/api/papers/import") {
+        return ApiScope::Import;
+    }
+    if *method == Method::DELETE
+        && path
+            .strip_prefix("/api/papers/")
+            .is_some_and(|id| !id.contains('/'))
+    {
+        return ApiScope::Admin;
+    }
+
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => ApiScope::Read,
+        _ => ApiScope::Write,
+    }
+}
+
+fn is_public_path(path: &str) -> bool {
+    PUBLIC_PATH_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// Axum middleware enforcing API key authentication and scopes
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let path = request.uri().path().to_string();
+    if is_public_path(&path) {
+        return Ok(next.run(request).await);
+    }
+
+    let token = bearer_token(request.headers())
+        .ok_or_else(|| ApiError(AppError::authentication("Missing bearer token")))?;
+
+    let key = ApiKeyRepository::find_active_by_key(&state.db, token)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| ApiError(AppError::authentication("Invalid or revoked API key")))?;
+
+    let required = required_scope(request.method(), &path);
+    if !key.allows(required) {
+        warn!(
+            "API key '{}' denied {} {} (requires '{}' scope)",
+            key.name,
+            request.method(),
+            path,
+            required
+        );
+        return Err(ApiError(AppError::permission(format!(
+            "{} {} requires '{}' scope",
+            request.method(),
+            path,
+            required
+        ))));
+    }
+
+    if let Some(requests) = state.key_usage.record(key.id) {
+        ApiKeyRepository::record_usage(&state.db, key.id, requests)
+            .await
+            .map_err(ApiError)?;
+    }
+
+    Ok(next.run(request).await)
+}
+
+struct PendingUsage {
+    requests: i64,
+    flushed_at: Option<Instant>,
+}
+
+/// Requests per key not written to the database yet. A key's first request
+/// is written at once, later ones at most every [`USAGE_FLUSH_INTERVAL`].
+#[derive(Clone, Default)]
+pub struct ApiKeyUsage {
+    pending: Arc<std::sync::Mutex<HashMap<i64, PendingUsage>>>,
+}
+
+impl ApiKeyUsage {
+    /// Count a request; returns the requests to write when the key is due
+    pub fn record(&self, key_id: i64) -> Option<i64> {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        let usage = pending.entry(key_id).or_insert(PendingUsage {
+            requests: 0,
+            flushed_at: None,
+        });
+        usage.requests += 1;
+        if usage
+            .flushed_at
+            .is_some_and(|at| at.elapsed() < USAGE_FLUSH_INTERVAL)
+        {
+            return None;
+        }
+
+        usage.flushed_at = Some(Instant::now());
+        Some(std::mem::take(&mut usage.requests))
+    }
+}
+
+/// Name of the API key holding the desktop app's own token
+pub const APP_KEY_NAME: &str = "desktop app";
+
+/// The desktop app's token for this run. Only its hash is stored; the
+/// plaintext stays in memory and is replaced on every start.
+#[derive(Clone, Default)]
+pub struct AppTokenState {
+    token: Arc<Mutex<Option<String>>>,
+}
+
+impl AppTokenState {
+    /// The token for this run, issued on first use
+    pub async fn get(&self, db: &DatabaseConnection) -> crate::sys::error::Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(token) = token.as_ref() {
+            return Ok(token.clone());
+        }
+
+        let issued = ApiKeyRepository::rotate_key(db, APP_KEY_NAME, &[ApiScope::Admin]).await?;
+        *token = Some(issued.clone());
+        Ok(issued)
+    }
+
+    /// Replace the token; the previous one stops working immediately
+    pub async fn regenerate(&self, db: &DatabaseConnection) -> crate::sys::error::Result<String> {
+        let mut token = self.token.lock().await;
+        let issued = ApiKeyRepository::rotate_key(db, APP_KEY_NAME, &[ApiScope::Admin]).await?;
+        *token = Some(issued.clone());
+        Ok(issued)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request as HttpRequest;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::axum::routes::create_router;
+    use crate::database::connection::init_memory_connection;
+    use crate::testing::test_app_dirs;
+
+    async fn test_state(dir: &std::path::Path) -> AppState {
+        AppState::new(init_memory_connection().await, test_app_dirs(dir))
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/api/papers"), ApiScope::Read);
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/papers/1"),
+            ApiScope::Admin
+        );
+        assert_eq!(
+            required_scope(&Method::DELETE, "/api/papers/1/labels/2"),
+            ApiScope::Write
+        );
+        assert_eq!(
+            required_scope(&Method::POST, "/api/papers/import-html"),
+            ApiScope::Import
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_key_rejected_from_delete_route() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+        let (_, key) = ApiKeyRepository::create(&state.db, "dashboard", &[ApiScope::Read])
+            .await
+            .unwrap();
+
+        let response = create_router(state)
+            .oneshot(
+                HttpRequest::builder()
+                    .method(Method::DELETE)
+                    .uri("/api/papers/1")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", key))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_write_key_rejected_from_paper_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+        let (_, key) = ApiKeyRepository::create(&state.db, "sync", &[ApiScope::Write])
+            .await
+            .unwrap();
+
+        let response = create_router(state)
+            .oneshot(
+                HttpRequest::builder()
+                    .method(Method::DELETE)
+                    .uri("/api/papers/1")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", key))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_usage_is_written_at_most_once_per_interval() {
+        let usage = ApiKeyUsage::default();
+        assert_eq!(usage.record(1), Some(1));
+        assert_eq!(usage.record(1), None);
+        assert_eq!(usage.record(1), None);
+        assert_eq!(usage.record(2), Some(1));
+
+        usage
+            .pending
+            .lock()
+            .unwrap()
+            .get_mut(&1)
+            .unwrap()
+            .flushed_at = Instant::now().checked_sub(USAGE_FLUSH_INTERVAL);
+        assert_eq!(usage.record(1), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_missing_key_is_unauthorized() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+
+        let response = create_router(state)
+            .oneshot(
+                HttpRequest::builder()
+                    .uri("/api/papers")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_app_token_is_stable_per_run_and_regenerated() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(dir.path()).await;
+        let app_token = AppTokenState::default();
+
+        let token = app_token.get(&state.db).await.unwrap();
+        assert_eq!(app_token.get(&state.db).await.unwrap(), token);
+        let stored = ApiKeyRepository::find_active_by_key(&state.db, &token)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_ne!(stored.key_prefix, token);
+
+        let router = create_router(state.clone());
+        let status = |token: String| {
+            let router = router.clone();
+            async move {
+                router
+                    .oneshot(
+                        HttpRequest::builder()
+                            .uri("/api/papers")
+                            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+        assert_eq!(status(token.clone()).await, axum::http::StatusCode::OK);
+
+        // A new run, or regenerating, replaces the secret of the same key
+        let next = AppTokenState::default().get(&state.db).await.unwrap();
+        assert_ne!(next, token);
+        assert_eq!(status(token).await, axum::http::StatusCode::UNAUTHORIZED);
+        let regenerated = app_token.regenerate(&state.db).await.unwrap();
+        assert_eq!(status(next).await, axum::http::StatusCode::UNAUTHORIZED);
+        assert_eq!(status(regenerated).await, axum::http::StatusCode::OK);
+        assert_eq!(
+            ApiKeyRepository::find_all(&state.db).await.unwrap().len(),
+            1
+        );
+    }
+}
@@ -13,19 +13,27 @@ impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         let (status, error_type) = match &self.0 {
             AppError::NotFound { .. } => (StatusCode::NOT_FOUND, "NOT_FOUND"),
+            AppError::Conflict { .. } => (StatusCode::CONFLICT, "CONFLICT"),
             AppError::ValidationError { .. } => (StatusCode::BAD_REQUEST, "VALIDATION_ERROR"),
             AppError::InvalidInput { .. } => (StatusCode::BAD_REQUEST, "INVALID_INPUT"),
+            AppError::AuthenticationError { .. } => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
+            AppError::PermissionError { .. } => (StatusCode::FORBIDDEN, "FORBIDDEN"),
+            AppError::Timeout { .. } => (StatusCode::GATEWAY_TIMEOUT, "TIMEOUT"),
             AppError::SurrealDbError { .. } => (StatusCode::INTERNAL_SERVER_ERROR, "DATABASE_ERROR"),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR"),
         };
 
-        let body = Json(json!({
+        let mut body = json!({
             "success": false,
             "error": error_type,
             "message": self.0.to_string()
-        }));
+        });
+        // Point clients at the resource that is already there
+        if let AppError::Conflict { resource_id, .. } = &self.0 {
+            body["id"] = json!(resource_id);
+        }
 
-        (status, body).into_response()
+        (status, Json(body)).into_response()
     }
 }
 
@@ -1,26 +1,26 @@
 //! API handlers for clipping operations
 
-use std::fs;
-use std::path::PathBuf;
-
-use ammonia::clean;
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     Json,
 };
-use regex::Regex;
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
-use tracing::{info, warn};
 use utoipa::ToSchema;
 
 use crate::axum::error::ApiError;
 use crate::axum::state::AppState;
-use crate::models::{Clipping, CreateClipping, UpdateClipping};
+use crate::command::clip_command::{create_clip_from_request, CreateClipRequest};
+use crate::models::Clipping;
 use crate::repository::ClippingRepository;
+use crate::service::activity_service::{self, ACTION_CREATED, ENTITY_CLIP};
 use crate::sys::error::AppError;
 
+/// Largest request body accepted by `POST /api/clips`
+pub const MAX_CLIP_PAYLOAD_BYTES: usize = 10 * 1024 * 1024;
+
 /// Query parameters for list_clips endpoint
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ListClipsQuery {
@@ -159,94 +159,6 @@ pub struct CreateClippingResponse {
     pub image_paths: Vec<String>,
 }
 
-async fn download_image(url: &str, clip_id: &str, files_dir: &str) -> Result<String, AppError> {
-    let filename = extract_filename_from_url(url);
-    let clip_dir = PathBuf::from(files_dir)
-        .join("clips")
-        .join(clip_id)
-        .join("images");
-    fs::create_dir_all(&clip_dir).map_err(|e| {
-        AppError::file_system(
-            clip_dir.display().to_string(),
-            format!("Failed to create images directory: {}", e),
-        )
-    })?;
-    let response = reqwest::get(url)
-        .await
-        .map_err(|e| AppError::generic(format!("Failed to download image from {}: {}", url, e)))?;
-    if !response.status().is_success() {
-        return Err(AppError::generic(format!(
-            "Failed to download image from {}: HTTP {}",
-            url,
-            response.status()
-        )));
-    }
-    let bytes = response.bytes().await.map_err(|e| {
-        AppError::generic(format!("Failed to read image bytes from {}: {}", url, e))
-    })?;
-    let local_path = clip_dir.join(&filename);
-    fs::write(&local_path, bytes).map_err(|e| {
-        AppError::file_system(
-            local_path.display().to_string(),
-            format!("Failed to write image: {}", e),
-        )
-    })?;
-    info!("Downloaded image from {} to {:?}", url, local_path);
-    Ok(format!("/clips/images/{}/images/{}", clip_id, filename))
-}
-
-fn extract_filename_from_url(url: &str) -> String {
-    if let Some(parsed) = url.split('?').next() {
-        if let Some(filename) = parsed.split('/').next_back() {
-            if !filename.is_empty() {
-                if filename.contains('.') {
-                    return filename.to_string();
-                }
-                use sha1::{Digest, Sha1};
-                let mut hasher = Sha1::new();
-                hasher.update(url.as_bytes());
-                return format!("{:x}.jpg", hasher.finalize());
-            }
-        }
-    }
-    use sha1::{Digest, Sha1};
-    let mut hasher = Sha1::new();
-    hasher.update(url.as_bytes());
-    format!("{:x}.jpg", hasher.finalize())
-}
-
-async fn process_markdown_images(
-    content: String,
-    clip_id: &str,
-    files_dir: &str,
-) -> Result<(String, Vec<String>), AppError> {
-    let image_regex = Regex::new(r"!\[.*?\]\((https?://[^\)]+)\)")
-        .map_err(|e| AppError::generic(format!("Failed to compile regex: {}", e)))?;
-    let mut updated_content = content.clone();
-    let mut image_paths = Vec::new();
-    let mut offset: i64 = 0;
-    for cap in image_regex.captures_iter(content.as_str()) {
-        if let Some(url_match) = cap.get(1) {
-            let url = url_match.as_str();
-            let start = url_match.start();
-            let end = url_match.end();
-            match download_image(url, clip_id, files_dir).await {
-                Ok(local_path) => {
-                    let adjusted_start = start + offset as usize;
-                    let adjusted_end = end + offset as usize;
-                    updated_content.replace_range(adjusted_start..adjusted_end, &local_path);
-                    offset += local_path.len() as i64 - (end - start) as i64;
-                    image_paths.push(local_path);
-                }
-                Err(e) => {
-                    warn!("Failed to download image from {}: {}, skipping", url, e);
-                }
-            }
-        }
-    }
-    Ok((updated_content, image_paths))
-}
-
 #[utoipa::path(
     post,
     path = "/api/clips",
@@ -255,76 +167,130 @@ async fn process_markdown_images(
     responses(
         (status = 201, description = "Clipping created successfully", body = CreateClippingResponse),
         (status = 400, description = "Invalid request data"),
+        (status = 409, description = "A clipping with this URL already exists; the body carries its `id`"),
+        (status = 413, description = "Payload larger than 10 MB"),
         (status = 500, description = "Internal server error")
     )
 )]
-#[instrument(skip(state))]
+#[instrument(skip(state, payload))]
 pub async fn create_clip(
     State(state): State<AppState>,
     Json(payload): Json<CreateClippingRequest>,
-) -> Result<(StatusCode, Json<CreateClippingResponse>), ApiError> {
-    info!("Creating clipping: {}", payload.title);
-    let sanitized_content = clean(&payload.content);
-    let create_clipping = CreateClipping {
-        title: payload.title.clone(),
-        url: payload.url.clone(),
-        content: Some(sanitized_content.clone()),
-        source_domain: Some(payload.source_domain.clone()),
-        author: payload.author.clone(),
-        published_date: payload.published_date.clone(),
-        excerpt: payload.excerpt.clone(),
-        thumbnail_url: payload.thumbnail_url.clone(),
-        tags: payload.tags.clone(),
-        image_paths: Vec::new(),
-    };
-    let clipping = ClippingRepository::create_clipping(&state.db, create_clipping)
+) -> Result<Response, ApiError> {
+    if let Some(existing) = ClippingRepository::find_by_url(&state.db, &payload.url)
         .await
-        .map_err(ApiError)?;
-    let clip_id = clipping.id.to_string();
-    let (processed_content, image_paths) =
-        process_markdown_images(sanitized_content, &clip_id, &state.app_dirs.files)
-            .await
-            .map_err(|e| {
-                AppError::file_system(&clip_id, format!("Failed to process images: {}", e))
-            })?;
-    let update_clipping = UpdateClipping {
-        title: None,
-        url: None,
-        content: Some(processed_content.clone()),
-        source_domain: None,
-        author: None,
-        published_date: None,
-        excerpt: None,
-        thumbnail_url: None,
-        read_status: None,
-        notes: None,
-        tags: None,
-        image_paths: Some(image_paths.clone()),
+        .map_err(ApiError)?
+    {
+        return Err(already_clipped(existing.id));
+    }
+
+    let url = payload.url.clone();
+    let request = CreateClipRequest {
+        title: payload.title,
+        url: payload.url,
+        content: payload.content,
+        source_domain: payload.source_domain,
+        author: payload.author,
+        published_date: payload.published_date,
+        excerpt: payload.excerpt,
+        thumbnail_url: payload.thumbnail_url,
+        tags: payload.tags,
     };
-    let clip_id_num = clip_id.parse::<i64>()
-        .map_err(|_| AppError::validation("id", "Invalid clipping id format"))?;
-    let updated = ClippingRepository::update_clipping(&state.db, clip_id_num, update_clipping)
-        .await
-        .map_err(ApiError)?;
-    if updated.is_none() {
-        return Err(ApiError(AppError::generic(
-            "Failed to update clipping with image paths".to_string(),
-        )));
+
+    let created = match create_clip_from_request(&state.db, &state.app_dirs.files, request).await {
+        Ok(created) => created,
+        Err(e) => {
+            // Another request may have stored the same URL in the meantime
+            if let Ok(Some(existing)) = ClippingRepository::find_by_url(&state.db, &url).await {
+                return Err(already_clipped(existing.id));
+            }
+            return Err(ApiError(e));
+        }
+    };
+    if let Ok(clip_id) = created.id.parse::<i64>() {
+        activity_service::record(&state.db, ENTITY_CLIP, clip_id, ACTION_CREATED).await;
     }
-    info!(
-        "Successfully created clipping {} with {} images",
-        clip_id,
-        image_paths.len()
-    );
+
     Ok((
         StatusCode::CREATED,
         Json(CreateClippingResponse {
-            id: clip_id,
-            title: payload.title,
-            url: payload.url,
-            content: processed_content,
-            source_domain: payload.source_domain,
-            image_paths,
+            id: created.id,
+            title: created.title,
+            url: created.url,
+            content: created.content.unwrap_or_default(),
+            source_domain: created.source_domain.unwrap_or_default(),
+            image_paths: created.image_paths,
         }),
-    ))
+    )
+        .into_response())
+}
+
+fn already_clipped(existing_id: i64) -> ApiError {
+    ApiError(AppError::conflict("Clipping", existing_id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Method, Request as HttpRequest};
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::axum::routes::create_router;
+    use crate::database::connection::init_memory_connection;
+    use crate::models::{ApiScope, CreateClipping};
+    use crate::repository::ApiKeyRepository;
+    use crate::testing::test_app_dirs;
+
+    #[tokio::test]
+    async fn test_create_clip_with_existing_url_is_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = AppState::new(init_memory_connection().await, test_app_dirs(dir.path()));
+
+        let (_, key) = ApiKeyRepository::create(&state.db, "clipper", &[ApiScope::Write])
+            .await
+            .unwrap();
+        let existing = ClippingRepository::create_clipping(
+            &state.db,
+            CreateClipping {
+                title: "Example".to_string(),
+                url: "https://example.com/post".to_string(),
+                content: None,
+                source_domain: None,
+                author: None,
+                published_date: None,
+                excerpt: None,
+                thumbnail_url: None,
+                tags: Vec::new(),
+                image_paths: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let body = json!({
+            "title": "Example again",
+            "url": "https://example.com/post",
+            "content": "<p>Hello</p>",
+            "source_domain": "example.com",
+        });
+        let response = create_router(state)
+            .oneshot(
+                HttpRequest::builder()
+                    .method(Method::POST)
+                    .uri("/api/clips")
+                    .header(header::AUTHORIZATION, format!("Bearer {}", key))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["id"], existing.id.to_string());
+    }
 }
@@ -3,3 +3,5 @@ pub mod clips;
 pub mod health;
 pub mod labels;
 pub mod papers;
+pub mod share;
+pub mod v1;
@@ -1,39 +1,61 @@
 use axum::{
     body::Bytes,
-    extract::{Path, Query, State},
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
-use sha1::{Digest, Sha1};
 use tauri::Emitter;
+use tower::ServiceExt;
+use tower_http::services::ServeFile;
 use tracing::info;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::axum::error::ApiError;
 use crate::axum::state::AppState;
-use crate::models::CreatePaper;
+use crate::command::paper as paper_commands;
+use crate::models::{AuthorDetails, CreatePaper, Paper, UpdatePaper};
 use crate::papers::importer::html::{extract_paper_from_html, HtmlImportError};
-use crate::repository::{AuthorRepository, LabelRepository, PaperRepository};
-use crate::sys::config::AppConfig;
+use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository, PaperRepository};
+use crate::service::activity_service::{
+    self, ACTION_DELETED, ACTION_IMPORTED, ACTION_UPDATED, ENTITY_PAPER,
+};
+use crate::service::attachment_service::{find_pdf_path, new_attachment_dir, resolve_within};
+use crate::sys::config::{AppConfig, LlmProvider};
 use crate::sys::error::AppError;
 
+/// Query parameters for list_papers endpoint
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListPapersQuery {
+    /// Maximum number of papers to return
+    pub limit: Option<u64>,
+    /// Number of papers to skip
+    pub offset: Option<u64>,
+}
+
 /// List all papers
 ///
-/// Returns a list of all papers in the database with basic metadata.
+/// Returns papers in the database with basic metadata. Use `limit` and
+/// `offset` to page through large libraries.
 #[utoipa::path(
     get,
     path = "/api/papers",
     tag = "papers",
+    params(ListPapersQuery),
     responses(
         (status = 200, description = "List of papers", body = Vec<serde_json::Value>)
     )
 )]
 pub async fn list_papers(
     State(state): State<AppState>,
+    Query(params): Query<ListPapersQuery>,
 ) -> Result<Json<Vec<serde_json::Value>>, ApiError> {
-    let papers = PaperRepository::find_all(&state.db)
-        .await
-        .map_err(ApiError)?;
+    let papers =
+        PaperRepository::find_all_paginated(&state.db, params.offset.unwrap_or(0), params.limit)
+            .await
+            .map_err(ApiError)?;
 
     let result: Vec<serde_json::Value> = papers
         .into_iter()
@@ -54,9 +76,66 @@ pub async fn list_papers(
     Ok(Json(result))
 }
 
+/// Full paper detail returned by the get and update endpoints
+pub(crate) async fn paper_detail(
+    state: &AppState,
+    paper: Paper,
+) -> Result<serde_json::Value, ApiError> {
+    let authors: Vec<String> = AuthorRepository::get_paper_authors(&state.db, paper.id)
+        .await
+        .map_err(ApiError)?
+        .iter()
+        .map(|a| a.full_name())
+        .collect();
+    let labels: Vec<serde_json::Value> = LabelRepository::get_paper_labels(&state.db, paper.id)
+        .await
+        .map_err(ApiError)?
+        .into_iter()
+        .map(|l| serde_json::json!({ "id": l.id.to_string(), "name": l.name, "color": l.color }))
+        .collect();
+    let category_ids = PaperRepository::get_category_ids(&state.db, paper.id)
+        .await
+        .map_err(ApiError)?;
+    let categories: Vec<serde_json::Value> =
+        CategoryRepository::find_by_ids(&state.db, &category_ids)
+            .await
+            .map_err(ApiError)?
+            .into_iter()
+            .map(|c| serde_json::json!({ "id": c.id.to_string(), "name": c.name }))
+            .collect();
+
+    Ok(serde_json::json!({
+        "id": paper.id.to_string(),
+        "title": paper.title,
+        "abstract": paper.abstract_text,
+        "doi": paper.doi,
+        "publication_year": paper.publication_year,
+        "journal_name": paper.journal_name,
+        "conference_name": paper.conference_name,
+        "volume": paper.volume,
+        "issue": paper.issue,
+        "pages": paper.pages,
+        "url": paper.url,
+        "notes": paper.notes,
+        "read_status": paper.read_status,
+        "publisher": paper.publisher,
+        "issn": paper.issn,
+        "language": paper.language,
+        "authors": authors,
+        "labels": labels,
+        "categories": categories,
+    }))
+}
+
+pub(crate) fn parse_paper_id(id: &str) -> Result<i64, ApiError> {
+    id.parse::<i64>()
+        .map_err(|_| ApiError(AppError::validation("id", "Invalid paper id format")))
+}
+
 /// Get a paper by ID
 ///
-/// Returns detailed information about a specific paper including notes and read status.
+/// Returns detailed information about a specific paper including notes,
+/// read status, authors, labels and categories.
 #[utoipa::path(
     get,
     path = "/api/papers/{id}",
@@ -73,28 +152,404 @@ pub async fn get_paper(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<serde_json::Value>, ApiError> {
+    let paper_id = parse_paper_id(&id)?;
+
+    let paper = PaperRepository::find_by_id(&state.db, paper_id)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| ApiError(AppError::not_found("Paper", id)))?;
+
+    Ok(Json(paper_detail(&state, paper).await?))
+}
+
+/// Query parameters for the related papers endpoint
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RelatedPapersQuery {
+    /// Maximum number of papers to return (default 10, at most 50)
+    pub limit: Option<usize>,
+}
+
+/// Get papers related to a paper
+///
+/// Returns similar papers, best first, each with a `score` and a `reason`
+/// naming shared authors, shared labels or text similarity. The paper
+/// itself and trashed papers are excluded.
+#[utoipa::path(
+    get,
+    path = "/api/papers/{id}/related",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID"),
+        RelatedPapersQuery
+    ),
+    responses(
+        (status = 200, description = "Related papers", body = Vec<serde_json::Value>),
+        (status = 404, description = "Paper not found")
+    )
+)]
+pub async fn get_related_papers(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<RelatedPapersQuery>,
+) -> Result<Json<Vec<paper_commands::RelatedPaperDto>>, ApiError> {
+    let related =
+        paper_commands::find_related_papers(&state.db, &state.app_dirs.config, &id, params.limit)
+            .await
+            .map_err(ApiError)?;
+
+    Ok(Json(related))
+}
+
+/// Request body for updating paper details. Omitted fields are left unchanged.
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct UpdatePaperRequest {
+    pub title: Option<String>,
+    #[serde(rename = "abstract")]
+    pub abstract_text: Option<String>,
+    pub doi: Option<String>,
+    pub publication_year: Option<i32>,
+    pub journal_name: Option<String>,
+    pub conference_name: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+    pub url: Option<String>,
+    pub read_status: Option<String>,
+    pub notes: Option<String>,
+    pub publisher: Option<String>,
+    pub issn: Option<String>,
+    pub language: Option<String>,
+}
+
+/// Update paper details
+///
+/// Updates the given fields of a paper and returns the updated paper.
+#[utoipa::path(
+    put,
+    path = "/api/papers/{id}",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID")
+    ),
+    request_body = UpdatePaperRequest,
+    responses(
+        (status = 200, description = "Updated paper", body = serde_json::Value),
+        (status = 400, description = "Invalid request"),
+        (status = 404, description = "Paper not found")
+    )
+)]
+pub async fn update_paper(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<UpdatePaperRequest>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let paper_id = parse_paper_id(&id)?;
+
+    if payload
+        .title
+        .as_deref()
+        .is_some_and(|t| t.trim().is_empty())
+    {
+        return Err(ApiError(AppError::validation(
+            "title",
+            "Title cannot be empty",
+        )));
+    }
+
+    let paper = PaperRepository::update(
+        &state.db,
+        paper_id,
+        UpdatePaper {
+            title: payload.title,
+            abstract_text: payload.abstract_text,
+            doi: payload.doi,
+            publication_year: payload.publication_year,
+            publication_date: None,
+            journal_name: payload.journal_name,
+            conference_name: payload.conference_name,
+            volume: payload.volume,
+            issue: payload.issue,
+            pages: payload.pages,
+            url: payload.url,
+            read_status: payload.read_status,
+            notes: payload.notes,
+            attachment_path: None,
+            publisher: payload.publisher,
+            issn: payload.issn,
+            language: payload.language,
+        },
+    )
+    .await
+    .map_err(ApiError)?;
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_UPDATED).await;
+
+    info!("Paper {} updated via API", paper_id);
+    Ok(Json(paper_detail(&state, paper).await?))
+}
+
+/// Request body for attaching a label to a paper
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddPaperLabelRequest {
+    /// ID of an existing label
+    pub label_id: String,
+}
+
+/// Add a label to a paper
+#[utoipa::path(
+    post,
+    path = "/api/papers/{id}/labels",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID")
+    ),
+    request_body = AddPaperLabelRequest,
+    responses(
+        (status = 204, description = "Label added"),
+        (status = 404, description = "Paper or label not found")
+    )
+)]
+pub async fn add_paper_label(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(payload): Json<AddPaperLabelRequest>,
+) -> Result<StatusCode, ApiError> {
+    let paper_id = parse_paper_id(&id)?;
+    let label_id = payload
+        .label_id
+        .parse::<i64>()
+        .map_err(|_| ApiError(AppError::validation("label_id", "Invalid label id format")))?;
+
+    PaperRepository::find_by_id(&state.db, paper_id)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| ApiError(AppError::not_found("Paper", id)))?;
+    LabelRepository::find_by_id(&state.db, label_id)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| ApiError(AppError::not_found("Label", payload.label_id)))?;
+
+    LabelRepository::add_to_paper(&state.db, paper_id, label_id)
+        .await
+        .map_err(ApiError)?;
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_UPDATED).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a label from a paper
+#[utoipa::path(
+    delete,
+    path = "/api/papers/{id}/labels/{label_id}",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID"),
+        ("label_id" = String, Path, description = "Label ID")
+    ),
+    responses(
+        (status = 204, description = "Label removed"),
+        (status = 400, description = "Invalid ID")
+    )
+)]
+pub async fn remove_paper_label(
+    State(state): State<AppState>,
+    Path((id, label_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let paper_id = parse_paper_id(&id)?;
+    let label_id = label_id
+        .parse::<i64>()
+        .map_err(|_| ApiError(AppError::validation("label_id", "Invalid label id format")))?;
+
+    LabelRepository::remove_from_paper(&state.db, paper_id, label_id)
+        .await
+        .map_err(ApiError)?;
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_UPDATED).await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn parse_optional_category_id(category_id: Option<&str>) -> Result<Option<i64>, ApiError> {
+    category_id
+        .map(|id| {
+            id.parse::<i64>().map_err(|_| {
+                ApiError(AppError::validation(
+                    "category_id",
+                    "Invalid category id format",
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Request body for importing a paper by DOI
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportDoiRequest {
+    pub doi: String,
+    /// Category ID to assign to the imported paper
+    pub category_id: Option<String>,
+}
+
+/// Import a paper by DOI
+///
+/// Fetches metadata for the DOI and adds the paper to the library. Returns
+/// `already_exists = true` without changes when the DOI is already present.
+#[utoipa::path(
+    post,
+    path = "/api/papers/import/doi",
+    tag = "papers",
+    request_body = ImportDoiRequest,
+    responses(
+        (status = 200, description = "Import result", body = serde_json::Value),
+        (status = 400, description = "Invalid DOI"),
+        (status = 404, description = "DOI not found")
+    )
+)]
+pub async fn import_paper_by_doi(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportDoiRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    info!("Importing paper by DOI via API: {}", payload.doi);
+
+    let category_id = parse_optional_category_id(payload.category_id.as_deref())?;
+    let result =
+        paper_commands::import_doi(&state.db, &state.app_dirs.config, &payload.doi, category_id)
+            .await
+            .map_err(ApiError)?;
+
+    Ok(Json(result))
+}
+
+/// Request body for importing a paper by arXiv ID
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ImportArxivRequest {
+    pub arxiv_id: String,
+    /// Category ID to assign to the imported paper
+    pub category_id: Option<String>,
+}
+
+/// Import a paper by arXiv ID
+///
+/// Fetches metadata for the arXiv ID, adds the paper and downloads its PDF.
+#[utoipa::path(
+    post,
+    path = "/api/papers/import/arxiv",
+    tag = "papers",
+    request_body = ImportArxivRequest,
+    responses(
+        (status = 200, description = "Import result", body = serde_json::Value),
+        (status = 400, description = "Invalid arXiv ID"),
+        (status = 404, description = "arXiv ID not found")
+    )
+)]
+pub async fn import_paper_by_arxiv_id(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportArxivRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    info!("Importing paper by arXiv ID via API: {}", payload.arxiv_id);
+
+    let category_id = parse_optional_category_id(payload.category_id.as_deref())?;
+    let result = paper_commands::import_arxiv(
+        &state.db,
+        &state.app_dirs.files,
+        &state.download_registry,
+        &payload.arxiv_id,
+        category_id,
+    )
+    .await
+    .map_err(ApiError)?;
+
+    Ok(Json(result))
+}
+
+/// Move a paper to the trash
+///
+/// Soft-deletes the paper; it can be restored from the trash in the app.
+#[utoipa::path(
+    delete,
+    path = "/api/papers/{id}",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID")
+    ),
+    responses(
+        (status = 204, description = "Paper moved to trash"),
+        (status = 404, description = "Paper not found")
+    )
+)]
+pub async fn delete_paper(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
     let paper_id = id
         .parse::<i64>()
         .map_err(|_| ApiError(AppError::validation("id", "Invalid paper id format")))?;
 
-    let paper = PaperRepository::find_by_id(&state.db, paper_id)
+    PaperRepository::soft_delete(&state.db, paper_id)
         .await
         .map_err(ApiError)?;
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_DELETED).await;
+
+    info!("Paper {} moved to trash via API", paper_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Stream a paper's PDF
+///
+/// Serves the PDF attachment from disk with `Content-Length` and HTTP range
+/// support, so viewers can fetch pages lazily instead of loading a base64 blob.
+#[utoipa::path(
+    get,
+    path = "/api/papers/{id}/pdf",
+    tag = "papers",
+    params(
+        ("id" = String, Path, description = "Paper ID")
+    ),
+    responses(
+        (status = 200, description = "PDF file", content_type = "application/pdf"),
+        (status = 206, description = "Requested byte range of the PDF", content_type = "application/pdf"),
+        (status = 404, description = "Paper or PDF attachment not found")
+    )
+)]
+pub async fn get_paper_pdf(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    request: Request,
+) -> Result<Response, ApiError> {
+    let paper_id = id
+        .parse::<i64>()
+        .map_err(|_| ApiError(AppError::validation("id", "Invalid paper id format")))?;
+
+    let paper = PaperRepository::find_by_id(&state.db, paper_id)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| ApiError(AppError::not_found("Paper", id.clone())))?;
 
-    match paper {
-        Some(p) => Ok(Json(serde_json::json!({
-            "id": p.id.to_string(),
-            "title": p.title,
-            "abstract": p.abstract_text,
-            "doi": p.doi,
-            "publication_year": p.publication_year,
-            "journal_name": p.journal_name,
-            "url": p.url,
-            "notes": p.notes,
-            "read_status": p.read_status,
-        }))),
-        None => Err(ApiError(AppError::not_found("Paper", id))),
+    let pdf_path = find_pdf_path(&state.db, &state.app_dirs.files, &paper)
+        .await
+        .map_err(ApiError)?
+        .ok_or_else(|| {
+            ApiError(AppError::not_found(
+                "PDF attachment",
+                format!("paper_id={}", id),
+            ))
+        })?;
+    let pdf_path =
+        resolve_within(std::path::Path::new(&state.app_dirs.files), &pdf_path).map_err(ApiError)?;
+
+    // ServeFile handles Range, If-Modified-Since and HEAD requests
+    let mut response = ServeFile::new(pdf_path)
+        .oneshot(request)
+        .await
+        .unwrap_or_else(|never| match never {})
+        .into_response();
+    if response.status().is_success() {
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/pdf"),
+        );
     }
+
+    Ok(response)
 }
 
 /// Response for HTML import
@@ -228,10 +683,9 @@ pub async fn import_paper_from_html(
     // 2. Find default or first LLM provider
     let provider = config
         .system
-        .llm_providers
-        .iter()
-        .find(|p| p.is_default)
-        .or_else(|| config.system.llm_providers.first())
+        .default_llm_provider()
+        .cloned()
+        .map(LlmProvider::with_stored_api_key)
         .ok_or_else(|| {
             ApiError(AppError::validation(
                 "llm_provider",
@@ -240,7 +694,7 @@ pub async fn import_paper_from_html(
         })?;
 
     // 3. Extract metadata from HTML using AI
-    let metadata = match extract_paper_from_html(&html, provider).await {
+    let metadata = match extract_paper_from_html(&html, &provider).await {
         Ok(m) => {
             info!("Extracted metadata from LLM: {:?}", m);
             m
@@ -300,10 +754,8 @@ pub async fn import_paper_from_html(
         }
     }
 
-    // 6. Calculate attachment path hash
-    let mut hasher = Sha1::new();
-    hasher.update(metadata.title.as_bytes());
-    let hash_string = format!("{:x}", hasher.finalize());
+    // 6. Name the attachment directory
+    let hash_string = new_attachment_dir();
 
     // 7. Create paper
     let paper = PaperRepository::create(
@@ -326,6 +778,7 @@ pub async fn import_paper_from_html(
             publisher: None,
             issn: None,
             language: None,
+            isbn: None,
         },
     )
     .await
@@ -340,15 +793,20 @@ pub async fn import_paper_from_html(
             continue;
         }
 
-        let author = AuthorRepository::create_or_find(&state.db, author_name.trim(), None)
-            .await
-            .map_err(ApiError)?;
+        let author = AuthorRepository::create_or_find(
+            &state.db,
+            author_name.trim(),
+            &AuthorDetails::default(),
+        )
+        .await
+        .map_err(ApiError)?;
         let author_id = author.id;
         PaperRepository::add_author(&state.db, paper_id, author_id, order as i32)
             .await
             .map_err(ApiError)?;
     }
 
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
     info!(
         "Successfully imported paper from HTML: {} (id: {})",
         paper.title, paper_id
@@ -451,10 +909,8 @@ pub async fn import_paper_from_zotero(
         .and_then(|d| d.split('/').next())
         .and_then(|y| y.parse::<i32>().ok());
 
-    // 4. Calculate attachment path hash
-    let mut hasher = Sha1::new();
-    hasher.update(payload.title.as_bytes());
-    let hash_string = format!("{:x}", hasher.finalize());
+    // 4. Name the attachment directory
+    let hash_string = new_attachment_dir();
 
     // 5. Create paper
     let paper = PaperRepository::create(
@@ -475,6 +931,7 @@ pub async fn import_paper_from_zotero(
             publisher: payload.publisher.clone(),
             issn: payload.issn.clone(),
             language: payload.language.clone(),
+            isbn: None,
         },
     )
     .await
@@ -504,7 +961,7 @@ pub async fn import_paper_from_zotero(
                 &state.db,
                 creator.first_name.as_deref(),
                 creator.last_name.as_deref(),
-                None,
+                &AuthorDetails::default(),
             )
             .await
             .map_err(ApiError)?;
@@ -536,6 +993,7 @@ pub async fn import_paper_from_zotero(
                     crate::models::CreateLabel {
                         name: tag_name.to_string(),
                         color: "#607D8B".to_string(),
+                        parent_id: None,
                     },
                 )
                 .await
@@ -559,6 +1017,7 @@ pub async fn import_paper_from_zotero(
         }
     }
 
+    activity_service::record(&state.db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
     info!(
         "Successfully imported paper from Zotero: {} (id: {})",
         paper.title, paper_id
@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{Html, IntoResponse, Response},
+};
+
+use crate::axum::error::ApiError;
+use crate::axum::state::AppState;
+use crate::sys::error::AppError;
+
+/// Content-Security-Policy for shared pages: inline styles only, no scripts
+/// or external requests, even if escaping were ever bypassed.
+const SHARE_CSP: &str = "default-src 'none'; style-src 'unsafe-inline'";
+
+/// Open a shared page
+///
+/// Serves a page created with a share command. Each link works once and
+/// only until it expires.
+#[utoipa::path(
+    get,
+    path = "/share/{token}",
+    tag = "share",
+    params(
+        ("token" = String, Path, description = "One-time share token")
+    ),
+    responses(
+        (status = 200, description = "Shared HTML page", content_type = "text/html"),
+        (status = 404, description = "Link expired or already used")
+    )
+)]
+pub async fn get_shared_page(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<Response, ApiError> {
+    let html = state
+        .share_registry
+        .take(&token)
+        .ok_or_else(|| ApiError(AppError::not_found("Share link", "expired or already used")))?;
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_SECURITY_POLICY, SHARE_CSP),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        Html(html),
+    )
+        .into_response())
+}
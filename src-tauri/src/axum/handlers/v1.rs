@@ -0,0 +1,308 @@
+//! Versioned read API for external tools
+//!
+//! `/api/v1/...` routes return paginated JSON with a stable shape for
+//! scripts and integrations. The unversioned routes serve the desktop app
+//! and the browser extension and change along with them.
+
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::axum::error::ApiError;
+use crate::axum::handlers::papers::{paper_detail, parse_paper_id};
+use crate::axum::state::AppState;
+use crate::models::Paper;
+use crate::repository::{PaperRepository, SearchRepository};
+use crate::sys::error::AppError;
+
+pub const DEFAULT_PAGE_SIZE: u64 = 20;
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// Search hits ranked before paging; matches beyond this are not returned
+pub const MAX_SEARCH_RESULTS: u64 = 1000;
+
+/// Pagination parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// Page number, starting at 1 (default 1)
+    pub page: Option<u64>,
+    /// Papers per page (default 20, at most 100)
+    pub page_size: Option<u64>,
+}
+
+/// Search and pagination parameters
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchPapersQuery {
+    /// Search words, matched against title, abstract, labels and
+    /// attachments. Every word must match; words are matched literally.
+    pub q: String,
+    /// Page number, starting at 1 (default 1)
+    pub page: Option<u64>,
+    /// Papers per page (default 20, at most 100)
+    pub page_size: Option<u64>,
+}
+
+/// Paper metadata returned in lists and search results
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaperSummary {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "abstract")]
+    pub abstract_text: Option<String>,
+    pub doi: Option<String>,
+    pub publication_year: Option<i32>,
+    pub journal_name: Option<String>,
+    pub url: Option<String>,
+    pub read_status: String,
+    /// BM25 relevance, only set on search results; lower is a better match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+}
+
+impl PaperSummary {
+    fn new(paper: Paper, score: Option<f64>) -> Self {
+        Self {
+            id: paper.id.to_string(),
+            title: paper.title,
+            abstract_text: paper.abstract_text,
+            doi: paper.doi,
+            publication_year: paper.publication_year,
+            journal_name: paper.journal_name,
+            url: paper.url,
+            read_status: paper.read_status,
+            score,
+        }
+    }
+}
+
+/// One page of papers
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PaperPage {
+    pub items: Vec<PaperSummary>,
+    /// Papers across all pages
+    pub total: u64,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+/// Validate paging parameters and fill in defaults
+fn page_bounds(page: Option<u64>, page_size: Option<u64>) -> Result<(u64, u64), ApiError> {
+    let page = page.unwrap_or(1);
+    let page_size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if page == 0 {
+        return Err(ApiError(AppError::validation("page", "Pages start at 1")));
+    }
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(ApiError(AppError::validation(
+            "page_size",
+            format!("Page size must be between 1 and {}", MAX_PAGE_SIZE),
+        )));
+    }
+    Ok((page, page_size))
+}
+
+/// List papers
+///
+/// Returns non-trashed papers, newest first, one page at a time.
+#[utoipa::path(
+    get,
+    path = "/api/v1/papers",
+    operation_id = "v1_list_papers",
+    tag = "v1",
+    params(PageQuery),
+    responses(
+        (status = 200, description = "One page of papers", body = PaperPage),
+        (status = 400, description = "Invalid paging parameters"),
+        (status = 401, description = "Missing or invalid API token")
+    ),
+    security(("bearer" = []))
+)]
+pub async fn list_papers(
+    State(state): State<AppState>,
+    Query(params): Query<PageQuery>,
+) -> Result<Json<PaperPage>, ApiError> {
+    let (page, page_size) = page_bounds(params.page, params.page_size)?;
+
+    let total = PaperRepository::count(&state.db).await.map_err(ApiError)? as u64;
+    let papers = PaperRepository::find_all_paginated(&state.db, (page - 1) * page_size, page_size)
+        .await
+        .map_err(ApiError)?;
+
+    Ok(Json(PaperPage {
+        items: papers
+            .into_iter()
+            .map(|p| PaperSummary::new(p, None))
+            .collect(),
+        total,
+        page,
+        page_size,
+    }))
+}
+
+/// Search papers
+///
+/// Full-text search over non-trashed papers, best match first. At most
+/// 1000 matches are ranked; `total` counts those.
+#[utoipa::path(
+    get,
+    path = "/api/v1/papers/search",
+    operation_id = "v1_search_papers",
+    tag = "v1",
+    params(SearchPapersQuery),
+    responses(
+        (status = 200, description = "One page of matching papers", body = PaperPage),
+        (status = 400, description = "Empty query or invalid paging parameters"),
+        (status = 401, description = "Missing or invalid API token")
+    ),
+    security(("bearer" = []))
+)]
+pub async fn search_papers(
+    State(state): State<AppState>,
+    Query(params): Query<SearchPapersQuery>,
+) -> Result<Json<PaperPage>, ApiError> {
+    let (page, page_size) = page_bounds(params.page, params.page_size)?;
+    let query = params.q.trim();
+    if query.is_empty() {
+        return Err(ApiError(AppError::validation("q", "Search query is empty")));
+    }
+
+    let results = SearchRepository::fts_search_words(&state.db, query, Some(MAX_SEARCH_RESULTS))
+        .await
+        .map_err(ApiError)?;
+    let total = results.len() as u64;
+    let items = results
+        .into_iter()
+        .skip(((page - 1) * page_size) as usize)
+        .take(page_size as usize)
+        .map(|(paper, score)| PaperSummary::new(Paper::from(paper), Some(score)))
+        .collect();
+
+    Ok(Json(PaperPage {
+        items,
+        total,
+        page,
+        page_size,
+    }))
+}
+
+/// Get a paper by ID
+///
+/// Returns every metadata field of the paper with its notes, authors and
+/// labels.
+#[utoipa::path(
+    get,
+    path = "/api/v1/papers/{id}",
+    operation_id = "v1_get_paper",
+    tag = "v1",
+    params(
+        ("id" = String, Path, description = "Paper ID")
+    ),
+    responses(
+        (status = 200, description = "Paper details", body = serde_json::Value),
+        (status = 401, description = "Missing or invalid API token"),
+        (status = 404, description = "Paper not found")
+    ),
+    security(("bearer" = []))
+)]
+pub async fn get_paper(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let paper_id = parse_paper_id(&id)?;
+
+    let paper = PaperRepository::find_by_id(&state.db, paper_id)
+        .await
+        .map_err(ApiError)?
+        .filter(|p| p.deleted_at.is_none())
+        .ok_or_else(|| ApiError(AppError::not_found("Paper", id)))?;
+
+    Ok(Json(paper_detail(&state, paper).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::{to_bytes, Body};
+    use axum::http::{header, Request, StatusCode};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::axum::auth::AppTokenState;
+    use crate::axum::routes::create_router;
+    use crate::database::connection::init_memory_connection;
+    use crate::testing::{test_app_dirs, PaperFixture};
+
+    use super::*;
+
+    async fn router(dir: &std::path::Path) -> (Router, AppState, String) {
+        let state = AppState::new(init_memory_connection().await, test_app_dirs(dir));
+        let token = AppTokenState::default().get(&state.db).await.unwrap();
+        (create_router(state.clone()), state, token)
+    }
+
+    async fn get(router: &Router, token: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_list_search_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let (router, state, token) = router(dir.path()).await;
+        for title in [
+            "Attention Is All You Need",
+            "Deep Residual Learning",
+            "Dropout",
+            "O'Brien's Survey of Search",
+        ] {
+            PaperFixture::new(title).insert(&state.db).await;
+        }
+
+        let (status, page) = get(&router, &token, "/api/v1/papers?page=2&page_size=2").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 4);
+        assert_eq!(page["items"].as_array().unwrap().len(), 2);
+
+        let (status, page) = get(&router, &token, "/api/v1/papers/search?q=residual").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 1);
+        let id = page["items"][0]["id"].as_str().unwrap().to_string();
+        assert_eq!(page["items"][0]["title"], "Deep Residual Learning");
+
+        // Apostrophes and quotes are searched for, not parsed
+        let (status, page) = get(&router, &token, "/api/v1/papers/search?q=O%27Brien").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["items"][0]["title"], "O'Brien's Survey of Search");
+        let (status, page) = get(&router, &token, "/api/v1/papers/search?q=%22survey").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 0);
+
+        let (status, paper) = get(&router, &token, &format!("/api/v1/papers/{}", id)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(paper["title"], "Deep Residual Learning");
+
+        let (status, _) = get(&router, &token, "/api/v1/papers?page=0").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get(&router, &token, "/api/v1/papers/search?q=%20").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = get(&router, "wrong", "/api/v1/papers").await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -1,4 +1,5 @@
-use utoipa::OpenApi;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::axum::handlers;
@@ -9,6 +10,14 @@ use crate::axum::handlers;
         handlers::health::health_check,
         handlers::papers::list_papers,
         handlers::papers::get_paper,
+        handlers::papers::get_related_papers,
+        handlers::papers::update_paper,
+        handlers::papers::delete_paper,
+        handlers::papers::add_paper_label,
+        handlers::papers::remove_paper_label,
+        handlers::papers::import_paper_by_doi,
+        handlers::papers::import_paper_by_arxiv_id,
+        handlers::papers::get_paper_pdf,
         handlers::papers::import_paper_from_html,
         handlers::papers::import_paper_from_zotero,
         handlers::categories::list_categories,
@@ -19,8 +28,18 @@ use crate::axum::handlers;
         handlers::clips::create_clip,
         handlers::clips::list_clips,
         handlers::clips::get_clip,
+        handlers::share::get_shared_page,
+        handlers::v1::list_papers,
+        handlers::v1::search_papers,
+        handlers::v1::get_paper,
     ),
     components(schemas(
+        handlers::papers::ListPapersQuery,
+        handlers::papers::RelatedPapersQuery,
+        handlers::papers::UpdatePaperRequest,
+        handlers::papers::AddPaperLabelRequest,
+        handlers::papers::ImportDoiRequest,
+        handlers::papers::ImportArxivRequest,
         handlers::papers::ImportHtmlResponse,
         handlers::papers::ImportZoteroQuery,
         handlers::papers::ZoteroCreator,
@@ -34,13 +53,20 @@ use crate::axum::handlers;
         handlers::categories::CategoryResponse,
         handlers::categories::SelectedCategoryResponse,
         handlers::categories::SetSelectedCategoryRequest,
+        handlers::v1::PageQuery,
+        handlers::v1::SearchPapersQuery,
+        handlers::v1::PaperSummary,
+        handlers::v1::PaperPage,
     )),
+    modifiers(&BearerAuth),
     tags(
         (name = "health", description = "Health check endpoints"),
         (name = "papers", description = "Paper management endpoints"),
         (name = "categories", description = "Category management endpoints"),
         (name = "labels", description = "Label management endpoints"),
         (name = "clips", description = "Web clipping management endpoints"),
+        (name = "share", description = "Temporary share links"),
+        (name = "v1", description = "Versioned read API for external tools"),
     ),
     info(
         title = "Xuan Brain API",
@@ -50,6 +76,21 @@ use crate::axum::handlers;
 )]
 pub struct ApiDoc;
 
+/// Documents the `Authorization: Bearer <token>` header checked by
+/// `auth::require_api_key`
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+            );
+        }
+    }
+}
+
 pub fn create_swagger_ui() -> SwaggerUi {
     SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi())
 }
@@ -1,9 +1,12 @@
-use axum::{routing::get, routing::post, Router};
+use axum::{
+    extract::DefaultBodyLimit, middleware, routing::delete, routing::get, routing::post, Router,
+};
 use std::path::PathBuf;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::ServeDir;
 use tower_http::trace::TraceLayer;
 
+use crate::axum::auth::require_api_key;
 use crate::axum::handlers;
 use crate::axum::openapi::create_swagger_ui;
 use crate::axum::state::AppState;
@@ -26,10 +29,41 @@ pub fn create_router(state: AppState) -> Router {
         // Clips
         .route("/api/clips", get(handlers::clips::list_clips))
         .route("/api/clips/{id}", get(handlers::clips::get_clip))
-        .route("/api/clips", post(handlers::clips::create_clip))
+        .route(
+            "/api/clips",
+            post(handlers::clips::create_clip).layer(DefaultBodyLimit::max(
+                handlers::clips::MAX_CLIP_PAYLOAD_BYTES,
+            )),
+        )
         // Papers
         .route("/api/papers", get(handlers::papers::list_papers))
-        .route("/api/papers/{id}", get(handlers::papers::get_paper))
+        .route(
+            "/api/papers/{id}",
+            get(handlers::papers::get_paper)
+                .put(handlers::papers::update_paper)
+                .delete(handlers::papers::delete_paper),
+        )
+        .route("/api/papers/{id}/pdf", get(handlers::papers::get_paper_pdf))
+        .route(
+            "/api/papers/{id}/related",
+            get(handlers::papers::get_related_papers),
+        )
+        .route(
+            "/api/papers/{id}/labels",
+            post(handlers::papers::add_paper_label),
+        )
+        .route(
+            "/api/papers/{id}/labels/{label_id}",
+            delete(handlers::papers::remove_paper_label),
+        )
+        .route(
+            "/api/papers/import/doi",
+            post(handlers::papers::import_paper_by_doi),
+        )
+        .route(
+            "/api/papers/import/arxiv",
+            post(handlers::papers::import_paper_by_arxiv_id),
+        )
         .route(
             "/api/papers/import-html",
             post(handlers::papers::import_paper_from_html),
@@ -55,8 +89,18 @@ pub fn create_router(state: AppState) -> Router {
         )
         // Labels
         .route("/api/labels", get(handlers::labels::list_labels))
+        // Versioned API for external tools
+        .route("/api/v1/papers", get(handlers::v1::list_papers))
+        .route("/api/v1/papers/search", get(handlers::v1::search_papers))
+        .route("/api/v1/papers/{id}", get(handlers::v1::get_paper))
+        // Temporary share links
+        .route("/share/{token}", get(handlers::share::get_shared_page))
         // Swagger UI (always available for debugging)
         .merge(create_swagger_ui())
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            require_api_key,
+        ))
         .layer(cors)
         .layer(TraceLayer::new_for_http())
         .with_state(state)
@@ -1,17 +1,30 @@
+use std::io::ErrorKind;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 
-use tauri::AppHandle;
-use tracing::info;
+use tauri::{AppHandle, Emitter};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
 
 use crate::axum::routes::create_router;
-use crate::axum::state::{AppState, SelectedCategoryState};
+use crate::axum::state::{ApiServerInfo, ApiServerState, AppState, SelectedCategoryState};
 use crate::database::DatabaseConnection;
+use crate::service::download_service::DownloadRegistry;
+use crate::service::share_service::ShareRegistry;
+use crate::sys::config::{ApiConfig, AppConfig};
 use crate::sys::dirs::AppDirs;
 
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 3030;
 
+/// How many ports after the configured one are tried before giving up
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+/// File in the config directory telling browser extensions where the
+/// server is listening
+pub const DISCOVERY_FILE: &str = "api-server.json";
+
 pub fn start_axum_server(db: Arc<DatabaseConnection>, app_dirs: AppDirs) {
     let addr: SocketAddr = format!("{}:{}", DEFAULT_HOST, DEFAULT_PORT)
         .parse()
@@ -43,28 +56,204 @@ pub fn start_axum_server_with_handle(
     app_dirs: AppDirs,
     app_handle: AppHandle,
     selected_category: SelectedCategoryState,
+    share_registry: ShareRegistry,
+    download_registry: DownloadRegistry,
+    server_state: ApiServerState,
 ) {
-    let addr: SocketAddr = format!("{}:{}", DEFAULT_HOST, DEFAULT_PORT)
-        .parse()
-        .expect("Invalid API server address");
+    let api_config = match AppConfig::load(&app_dirs.config) {
+        Ok(config) => config.api,
+        Err(e) => {
+            tracing::error!("Failed to load API server config, using defaults: {}", e);
+            Default::default()
+        }
+    };
 
-    let state = AppState::new_with_selected_category(db, app_dirs, app_handle, selected_category);
-    let app = create_router(state);
+    if !api_config.enabled {
+        info!("Axum API server is disabled in settings");
+        remove_discovery_file(&app_dirs.config);
+        server_state.set(ApiServerInfo {
+            enabled: false,
+            host: api_config.host,
+            ..Default::default()
+        });
+        return;
+    }
 
-    info!("Starting Axum API server on {}", addr);
-    info!("Swagger UI available at http://{}/swagger-ui/", addr);
+    let config_dir = app_dirs.config.clone();
+    let state =
+        AppState::new_with_selected_category(db, app_dirs, app_handle.clone(), selected_category)
+            .with_share_registry(share_registry)
+            .with_download_registry(download_registry);
+    let app = create_router(state);
 
     tauri::async_runtime::spawn(async move {
-        let listener = match tokio::net::TcpListener::bind(addr).await {
+        let listener = match bind_api_listener(&api_config).await {
             Ok(l) => l,
             Err(e) => {
                 tracing::error!("Failed to bind Axum server: {}", e);
+                server_state.set(ApiServerInfo {
+                    enabled: true,
+                    host: api_config.host,
+                    ..Default::default()
+                });
+                let _ = app_handle.emit("api-server:failed", e.to_string());
+                return;
+            }
+        };
+
+        let port = match listener.local_addr() {
+            Ok(addr) => addr.port(),
+            Err(e) => {
+                tracing::error!("Failed to read Axum server address: {}", e);
                 return;
             }
         };
+        if api_config.port == 0 {
+            if api_config.last_port != Some(port) {
+                if let Err(e) = remember_port(&config_dir, port) {
+                    warn!("Failed to save API server port {}: {}", port, e);
+                }
+            }
+        } else if port != api_config.port {
+            warn!(
+                "Port {} is in use, API server falling back to {}",
+                api_config.port, port
+            );
+        }
+
+        let server_info = ApiServerInfo {
+            enabled: true,
+            running: true,
+            host: api_config.host,
+            port: Some(port),
+        };
+        info!("Starting Axum API server on {}:{}", server_info.host, port);
+        info!(
+            "Swagger UI available at http://{}:{}/swagger-ui/",
+            server_info.host, port
+        );
+
+        server_state.set(server_info.clone());
+        if let Err(e) = write_discovery_file(&config_dir, &server_info) {
+            warn!("Failed to write {}: {}", DISCOVERY_FILE, e);
+        }
+        let _ = app_handle.emit("api-server:started", &server_info);
 
         if let Err(e) = axum::serve(listener, app).await {
             tracing::error!("Axum server error: {}", e);
         }
+
+        server_state.set(ApiServerInfo {
+            running: false,
+            ..server_info
+        });
+        remove_discovery_file(&config_dir);
+        let _ = app_handle.emit("api-server:stopped", ());
     });
 }
+
+/// Bind the API server's listener. With `port` 0 the port picked on the
+/// previous start is tried first, then any free port.
+pub async fn bind_api_listener(config: &ApiConfig) -> std::io::Result<TcpListener> {
+    if config.port != 0 {
+        return bind_with_fallback(&config.host, config.port).await;
+    }
+
+    if let Some(last_port) = config.last_port {
+        match TcpListener::bind((config.host.as_str(), last_port)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) => warn!(
+                "Previous API server port {} is unavailable: {}",
+                last_port, e
+            ),
+        }
+    }
+    TcpListener::bind((config.host.as_str(), 0)).await
+}
+
+/// Save the port picked for `api.port = 0` so the next start reuses it
+fn remember_port(config_dir: &str, port: u16) -> crate::sys::error::Result<()> {
+    let mut config = AppConfig::load(config_dir)?;
+    config.api.last_port = Some(port);
+    config.save(config_dir)
+}
+
+/// Bind `host:port`, moving on to the following ports while they are in use
+pub async fn bind_with_fallback(host: &str, port: u16) -> std::io::Result<TcpListener> {
+    let mut last_error = None;
+
+    for offset in 0..=PORT_FALLBACK_ATTEMPTS {
+        let Some(candidate) = port.checked_add(offset) else {
+            break;
+        };
+
+        match TcpListener::bind((host, candidate)).await {
+            Ok(listener) => return Ok(listener),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                warn!("Port {} is already in use", candidate);
+                last_error = Some(e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(ErrorKind::AddrInUse, format!("No free port from {}", port))
+    }))
+}
+
+fn write_discovery_file(config_dir: &str, info: &ApiServerInfo) -> std::io::Result<()> {
+    let content = serde_json::json!({
+        "host": info.host,
+        "port": info.port,
+        "base_url": info.base_url(),
+        "pid": std::process::id(),
+    });
+    std::fs::write(
+        Path::new(config_dir).join(DISCOVERY_FILE),
+        serde_json::to_string_pretty(&content)?,
+    )
+}
+
+fn remove_discovery_file(config_dir: &str) {
+    let _ = std::fs::remove_file(Path::new(config_dir).join(DISCOVERY_FILE));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bind_with_fallback_skips_taken_port() {
+        let taken = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let taken_port = taken.local_addr().unwrap().port();
+
+        let listener = bind_with_fallback("127.0.0.1", taken_port).await.unwrap();
+        let bound_port = listener.local_addr().unwrap().port();
+
+        assert_ne!(bound_port, taken_port);
+        assert!(bound_port > taken_port && bound_port <= taken_port + PORT_FALLBACK_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn test_random_port_is_reused() {
+        let free_port = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = ApiConfig {
+            port: 0,
+            last_port: Some(free_port),
+            ..Default::default()
+        };
+
+        let listener = bind_api_listener(&config).await.unwrap();
+        assert_eq!(listener.local_addr().unwrap().port(), free_port);
+
+        // Falls back to any free port while the previous one is taken
+        let other = bind_api_listener(&config).await.unwrap();
+        assert_ne!(other.local_addr().unwrap().port(), free_port);
+    }
+}
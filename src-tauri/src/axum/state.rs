@@ -1,9 +1,13 @@
 use std::sync::atomic::{AtomicI64, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
+use serde::Serialize;
 use tauri::AppHandle;
 
+use crate::axum::auth::ApiKeyUsage;
 use crate::database::DatabaseConnection;
+use crate::service::download_service::DownloadRegistry;
+use crate::service::share_service::ShareRegistry;
 use crate::sys::dirs::AppDirs;
 
 /// Shared state for selected category ID
@@ -38,6 +42,50 @@ impl SelectedCategoryState {
     }
 }
 
+/// Where the local API server ended up listening
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ApiServerInfo {
+    pub enabled: bool,
+    pub running: bool,
+    pub host: String,
+    /// Port actually bound, which may differ from the configured one
+    pub port: Option<u16>,
+}
+
+impl ApiServerInfo {
+    /// Base URL of the running server, e.g. for building share links
+    pub fn base_url(&self) -> Option<String> {
+        match (self.running, self.port) {
+            (true, Some(port)) => Some(format!("http://{}:{}", self.host, port)),
+            _ => None,
+        }
+    }
+}
+
+/// Shared API server status, filled in once the listener is bound
+#[derive(Clone, Default)]
+pub struct ApiServerState {
+    info: Arc<Mutex<ApiServerInfo>>,
+}
+
+impl ApiServerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> ApiServerInfo {
+        self.info.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn set(&self, info: ApiServerInfo) {
+        *self.info.lock().unwrap_or_else(|e| e.into_inner()) = info;
+    }
+
+    pub fn base_url(&self) -> Option<String> {
+        self.get().base_url()
+    }
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
@@ -45,6 +93,12 @@ pub struct AppState {
     pub app_handle: Option<Arc<AppHandle>>,
     /// Shared selected category state
     pub selected_category: SelectedCategoryState,
+    /// One-time share links served under `/share/{token}`
+    pub share_registry: ShareRegistry,
+    /// Running downloads, also listed by the Tauri commands
+    pub download_registry: DownloadRegistry,
+    /// API key requests not written to the database yet
+    pub key_usage: ApiKeyUsage,
 }
 
 impl AppState {
@@ -54,6 +108,9 @@ impl AppState {
             app_dirs,
             app_handle: None,
             selected_category: SelectedCategoryState::new(),
+            share_registry: ShareRegistry::new(),
+            download_registry: DownloadRegistry::new(),
+            key_usage: ApiKeyUsage::default(),
         }
     }
 
@@ -67,6 +124,9 @@ impl AppState {
             app_dirs,
             app_handle: Some(Arc::new(app_handle)),
             selected_category: SelectedCategoryState::new(),
+            share_registry: ShareRegistry::new(),
+            download_registry: DownloadRegistry::new(),
+            key_usage: ApiKeyUsage::default(),
         }
     }
 
@@ -82,6 +142,21 @@ impl AppState {
             app_dirs,
             app_handle: Some(Arc::new(app_handle)),
             selected_category,
+            share_registry: ShareRegistry::new(),
+            download_registry: DownloadRegistry::new(),
+            key_usage: ApiKeyUsage::default(),
         }
     }
+
+    /// Use a share registry shared with the Tauri commands
+    pub fn with_share_registry(mut self, share_registry: ShareRegistry) -> Self {
+        self.share_registry = share_registry;
+        self
+    }
+
+    /// Use a download registry shared with the Tauri commands
+    pub fn with_download_registry(mut self, download_registry: DownloadRegistry) -> Self {
+        self.download_registry = download_registry;
+        self
+    }
 }
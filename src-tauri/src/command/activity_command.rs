@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::command::paper::{papers_to_list_dtos, PaperListDto};
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::{ActivityRepository, PaperRepository};
+use crate::service::activity_service::ACTION_VIEWED;
+use crate::sys::error::Result;
+
+/// Entries returned when the caller does not ask for a number
+const DEFAULT_ACTIVITY_LIMIT: u32 = 20;
+
+/// Most entries returned
+const MAX_ACTIVITY_LIMIT: u32 = 200;
+
+#[derive(Serialize)]
+pub struct ActivityDto {
+    pub id: String,
+    /// `paper` or `clip`
+    pub entity_type: String,
+    pub entity_id: String,
+    /// `viewed`, `imported`, `created`, `updated` or `deleted`
+    pub action: String,
+    pub created_at: String,
+}
+
+fn clamp_limit(limit: Option<u32>) -> u64 {
+    limit
+        .unwrap_or(DEFAULT_ACTIVITY_LIMIT)
+        .clamp(1, MAX_ACTIVITY_LIMIT) as u64
+}
+
+/// Most recent activity across papers and clips, newest first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_recent_activity(
+    db: State<'_, Arc<DatabaseConnection>>,
+    limit: Option<u32>,
+) -> Result<Vec<ActivityDto>> {
+    let entries = ActivityRepository::find_recent(&db, clamp_limit(limit)).await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ActivityDto {
+            id: entry.id.to_string(),
+            entity_type: entry.entity_type,
+            entity_id: entry.entity_id.to_string(),
+            action: entry.action,
+            created_at: entry.created_at.to_rfc3339(),
+        })
+        .collect())
+}
+
+/// Papers most recently opened in the detail view, last viewed first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_recently_viewed_papers(
+    db: State<'_, Arc<DatabaseConnection>>,
+    limit: Option<u32>,
+) -> Result<Vec<PaperListDto>> {
+    let paper_ids =
+        ActivityRepository::recently_viewed_paper_ids(&db, ACTION_VIEWED, clamp_limit(limit))
+            .await?;
+
+    let mut by_id: HashMap<i64, Paper> = PaperRepository::find_by_ids(&db, &paper_ids)
+        .await?
+        .into_iter()
+        .map(|paper| (paper.id, paper))
+        .collect();
+    let papers: Vec<Paper> = paper_ids
+        .iter()
+        .filter_map(|paper_id| by_id.remove(paper_id))
+        .collect();
+    info!("Found {} recently viewed papers", papers.len());
+
+    papers_to_list_dtos(&db, papers).await
+}
@@ -0,0 +1,113 @@
+//! Tauri commands for library maintenance and database recovery
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::service::backup_service::{
+    configured_backup_dir, default_backup_dir, latest_backup, stage_restore,
+};
+use crate::service::database_recovery_service::{self, DatabaseStatus, DatabaseStatusState};
+use crate::service::integrity_service::{self, IntegrityReportDto};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Check the database for dangling references, orphaned authors and
+/// keywords, missing attachment files and stale cached counts. Only reports;
+/// nothing is repaired.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn check_database_integrity(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<IntegrityReportDto> {
+    integrity_service::check_integrity(&db, &app_dirs.files).await
+}
+
+#[derive(Serialize)]
+pub struct DatabaseStatusDto {
+    #[serde(flatten)]
+    pub status: DatabaseStatus,
+    /// Newest backup in the configured backup directory, offered for
+    /// restore when the database is unavailable
+    pub latest_backup: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DatabaseRepairDto {
+    /// Where the unusable database was moved
+    pub moved_to: String,
+    /// Backup staged to be restored on the next start
+    pub restored_backup: Option<String>,
+}
+
+fn backup_dir(app_dirs: &AppDirs) -> PathBuf {
+    let config = AppConfig::load(&app_dirs.config).unwrap_or_default();
+    configured_backup_dir(app_dirs, &config)
+}
+
+/// Get whether the database opened at startup. When it did not, the app
+/// runs without a library and the error explains why.
+#[tauri::command]
+#[instrument(skip(status, app_dirs))]
+pub async fn get_database_status(
+    status: State<'_, DatabaseStatusState>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<DatabaseStatusDto> {
+    Ok(DatabaseStatusDto {
+        status: status.get(),
+        latest_backup: latest_backup(&backup_dir(&app_dirs))
+            .map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// Move an unusable database aside and, with `restore_latest_backup`,
+/// stage the newest backup to replace it. Only allowed when the database
+/// failed to open. The frontend should call `restart_app` afterwards; the
+/// next start opens the backup or an empty library.
+#[tauri::command]
+#[instrument(skip(status, app_dirs))]
+pub async fn repair_database(
+    status: State<'_, DatabaseStatusState>,
+    app_dirs: State<'_, AppDirs>,
+    restore_latest_backup: bool,
+) -> Result<DatabaseRepairDto> {
+    if status.get().available {
+        return Err(AppError::validation(
+            "database",
+            "The database is working; nothing to repair",
+        ));
+    }
+
+    let backup = if restore_latest_backup {
+        let dir = backup_dir(&app_dirs);
+        Some(latest_backup(&dir).ok_or_else(|| {
+            AppError::not_found("Database backup", dir.to_string_lossy().to_string())
+        })?)
+    } else {
+        None
+    };
+
+    let data_dir = PathBuf::from(&app_dirs.data);
+    let moved_to = database_recovery_service::move_database_aside(&data_dir, Utc::now())?;
+
+    if let Some(backup) = &backup {
+        let allowed_dirs = vec![default_backup_dir(&app_dirs), backup_dir(&app_dirs)];
+        stage_restore(&data_dir, backup, &allowed_dirs)?;
+    }
+
+    info!(
+        "Repaired database: moved to {:?}, restoring {:?}",
+        moved_to, backup
+    );
+    Ok(DatabaseRepairDto {
+        moved_to: moved_to.to_string_lossy().to_string(),
+        restored_backup: backup.map(|p| p.to_string_lossy().to_string()),
+    })
+}
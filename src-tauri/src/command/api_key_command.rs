@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::axum::auth::AppTokenState;
+use crate::database::DatabaseConnection;
+use crate::models::{ApiKey, ApiScope};
+use crate::repository::ApiKeyRepository;
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub request_count: i64,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+}
+
+impl From<ApiKey> for ApiKeyResponse {
+    fn from(key: ApiKey) -> Self {
+        Self {
+            id: key.id.to_string(),
+            name: key.name,
+            key_prefix: key.key_prefix,
+            scopes: key.scopes,
+            request_count: key.request_count,
+            last_used_at: key.last_used_at.map(|t| t.to_rfc3339()),
+            created_at: key.created_at.to_rfc3339(),
+            revoked_at: key.revoked_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Response for a newly created key. `key` is the plaintext secret and is
+/// only ever returned here.
+#[derive(Serialize)]
+pub struct CreatedApiKeyResponse {
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn create_api_key(
+    db: State<'_, Arc<DatabaseConnection>>,
+    name: String,
+    scopes: Vec<String>,
+) -> Result<CreatedApiKeyResponse> {
+    info!("Creating API key '{}' with scopes {:?}", name, scopes);
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation("name", "API key name cannot be empty"));
+    }
+
+    let mut parsed: Vec<ApiScope> = Vec::new();
+    for scope in &scopes {
+        let scope = scope
+            .parse::<ApiScope>()
+            .map_err(|e| AppError::validation("scopes", e))?;
+        if !parsed.contains(&scope) {
+            parsed.push(scope);
+        }
+    }
+
+    let (api_key, key) = ApiKeyRepository::create(&db, name, &parsed).await?;
+
+    Ok(CreatedApiKeyResponse {
+        api_key: ApiKeyResponse::from(api_key),
+        key,
+    })
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn list_api_keys(db: State<'_, Arc<DatabaseConnection>>) -> Result<Vec<ApiKeyResponse>> {
+    info!("Listing API keys");
+    let keys = ApiKeyRepository::find_all(&db).await?;
+    Ok(keys.into_iter().map(ApiKeyResponse::from).collect())
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn revoke_api_key(db: State<'_, Arc<DatabaseConnection>>, id: String) -> Result<()> {
+    info!("Revoking API key {}", id);
+
+    let id_num = id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("id", "Invalid id format"))?;
+
+    ApiKeyRepository::revoke(&db, id_num).await
+}
+
+/// Replace the desktop app's API token. The old token stops working and the
+/// new one is returned.
+#[tauri::command]
+#[instrument(skip(db, app_token))]
+pub async fn regenerate_api_token(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_token: State<'_, AppTokenState>,
+) -> Result<String> {
+    info!("Regenerating app API token");
+    app_token.regenerate(&db).await
+}
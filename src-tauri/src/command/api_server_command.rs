@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::instrument;
+
+use crate::axum::auth::AppTokenState;
+use crate::axum::state::ApiServerState;
+use crate::database::DatabaseConnection;
+use crate::sys::error::Result;
+
+/// Where the local API server listens and the token to call it with
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiServerStatusDto {
+    /// Port actually bound; 0 while the server is not running
+    pub port: u16,
+    /// e.g. `http://127.0.0.1:3030`; empty while the server is not running
+    pub base_url: String,
+    pub is_running: bool,
+    /// Bearer token of the desktop app
+    pub api_token: String,
+}
+
+/// Get the bound port, base URL and running state of the local API server
+/// together with the app's token, so the frontend can call it directly.
+///
+/// `port` is the port actually bound, which differs from the configured
+/// `api.port` when that one was taken. Changes to the `api` settings apply
+/// after `restart_app`.
+#[tauri::command]
+#[instrument(skip(db, server_state, app_token))]
+pub async fn get_api_server_status(
+    db: State<'_, Arc<DatabaseConnection>>,
+    server_state: State<'_, ApiServerState>,
+    app_token: State<'_, AppTokenState>,
+) -> Result<ApiServerStatusDto> {
+    let info = server_state.get();
+    let base_url = info.base_url();
+
+    Ok(ApiServerStatusDto {
+        port: base_url.as_ref().and(info.port).unwrap_or(0),
+        is_running: base_url.is_some(),
+        base_url: base_url.unwrap_or_default(),
+        api_token: app_token.get(&db).await?,
+    })
+}
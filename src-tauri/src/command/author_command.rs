@@ -0,0 +1,309 @@
+//! Tauri commands for browsing and editing authors
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::command::paper::{papers_to_dtos, PaperDto};
+use crate::database::DatabaseConnection;
+use crate::models::{normalize_orcid, Author, AuthorNameParser};
+use crate::repository::{AuthorRepository, PaperRepository};
+use crate::service::author_service;
+use crate::sys::error::{AppError, Result};
+
+/// Maximum number of authors returned by `search_authors`
+const AUTHOR_SEARCH_LIMIT: u64 = 50;
+
+/// Authors returned by `list_authors` when no limit is given
+const DEFAULT_LIST_LIMIT: u32 = 100;
+
+/// Largest limit accepted by `list_authors`
+const MAX_LIST_LIMIT: u32 = 1000;
+
+#[derive(Serialize)]
+pub struct AuthorDto {
+    pub id: String,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub full_name: String,
+    pub affiliation: Option<String>,
+    pub email: Option<String>,
+    pub orcid: Option<String>,
+}
+
+impl From<Author> for AuthorDto {
+    fn from(author: Author) -> Self {
+        Self {
+            id: author.id.to_string(),
+            full_name: author.full_name(),
+            first_name: author.first_name,
+            last_name: author.last_name,
+            affiliation: author.affiliation,
+            email: author.email,
+            orcid: author.orcid,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AuthorDetailDto {
+    #[serde(flatten)]
+    pub author: AuthorDto,
+    pub created_at: String,
+}
+
+/// Author with the number of papers (not in the trash) they appear on
+#[derive(Serialize)]
+pub struct AuthorWithCountDto {
+    #[serde(flatten)]
+    pub author: AuthorDto,
+    pub paper_count: u64,
+}
+
+#[derive(Serialize)]
+pub struct AuthorPapersDto {
+    pub author: AuthorDetailDto,
+    pub papers: Vec<PaperDto>,
+    pub paper_count: u64,
+}
+
+fn parse_author_id(id: &str) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| AppError::validation("author_id", "Invalid author id format"))
+}
+
+/// Get an author together with all of their papers, newest first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_author_papers(
+    db: State<'_, Arc<DatabaseConnection>>,
+    author_id: String,
+) -> Result<AuthorPapersDto> {
+    info!("Getting papers for author {}", author_id);
+
+    let author = AuthorRepository::find_by_id(&db, parse_author_id(&author_id)?)
+        .await?
+        .ok_or_else(|| AppError::not_found("Author", author_id.clone()))?;
+
+    let papers = PaperRepository::find_by_author(&db, author.id).await?;
+    let papers = papers_to_dtos(&db, papers).await?;
+
+    Ok(AuthorPapersDto {
+        author: AuthorDetailDto {
+            created_at: author.created_at.to_rfc3339(),
+            author: AuthorDto::from(author),
+        },
+        paper_count: papers.len() as u64,
+        papers,
+    })
+}
+
+/// Search authors by name, best matches first. Each word matches as a prefix,
+/// so "joh smi" finds "John Smith".
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_authors(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+) -> Result<Vec<AuthorDto>> {
+    let authors = AuthorRepository::search(&db, &query, AUTHOR_SEARCH_LIMIT).await?;
+    info!("Found {} authors for '{}'", authors.len(), query);
+    Ok(authors.into_iter().map(AuthorDto::from).collect())
+}
+
+/// List authors with their paper counts. With a `query` the best name
+/// matches come first, otherwise the authors with the most papers.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn list_authors(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<AuthorWithCountDto>> {
+    let limit = limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if limit == 0 || limit > MAX_LIST_LIMIT {
+        return Err(AppError::validation(
+            "limit",
+            format!("Must be between 1 and {}", MAX_LIST_LIMIT),
+        ));
+    }
+
+    let counts = AuthorRepository::count_papers(&db).await?;
+    let count_of = |author: &Author| counts.get(&author.id).copied().unwrap_or(0);
+
+    let authors = match query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        Some(query) => AuthorRepository::search(&db, query, limit as u64).await?,
+        None => {
+            let mut authors = AuthorRepository::find_all(&db).await?;
+            authors.sort_by(|a, b| {
+                count_of(b)
+                    .cmp(&count_of(a))
+                    .then_with(|| a.full_name().cmp(&b.full_name()))
+            });
+            authors.truncate(limit as usize);
+            authors
+        }
+    };
+
+    Ok(authors
+        .into_iter()
+        .map(|author| AuthorWithCountDto {
+            paper_count: count_of(&author),
+            author: AuthorDto::from(author),
+        })
+        .collect())
+}
+
+/// Change an author's name on every paper, keeping their affiliation and
+/// email
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn rename_author(
+    db: State<'_, Arc<DatabaseConnection>>,
+    author_id: String,
+    new_name: String,
+) -> Result<AuthorDto> {
+    info!("Renaming author {} to '{}'", author_id, new_name);
+
+    if new_name.trim().is_empty() {
+        return Err(AppError::validation(
+            "new_name",
+            "Author name cannot be empty",
+        ));
+    }
+
+    let author = AuthorRepository::rename(
+        &db,
+        parse_author_id(&author_id)?,
+        AuthorNameParser::parse(&new_name),
+    )
+    .await?;
+    Ok(AuthorDto::from(author))
+}
+
+/// Merge duplicate authors into one: their papers move to the primary
+/// author and the duplicates are deleted
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn merge_authors(
+    db: State<'_, Arc<DatabaseConnection>>,
+    primary_id: String,
+    duplicate_ids: Vec<String>,
+) -> Result<AuthorDto> {
+    info!(
+        "Merging {} authors into {}",
+        duplicate_ids.len(),
+        primary_id
+    );
+
+    let primary_id_num = parse_author_id(&primary_id)?;
+    let mut duplicate_id_nums = Vec::with_capacity(duplicate_ids.len());
+    for id in &duplicate_ids {
+        let id = parse_author_id(id)?;
+        if id == primary_id_num {
+            return Err(AppError::validation(
+                "duplicate_ids",
+                "An author cannot be merged into itself",
+            ));
+        }
+        if !duplicate_id_nums.contains(&id) {
+            duplicate_id_nums.push(id);
+        }
+    }
+
+    let author = AuthorRepository::merge(&db, primary_id_num, &duplicate_id_nums).await?;
+    Ok(AuthorDto::from(author))
+}
+
+/// Groups of authors that are probably the same person, such as "J. Smith"
+/// and "John Smith". The author with the most papers comes first in each
+/// group.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn suggest_author_duplicates(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<Vec<AuthorWithCountDto>>> {
+    let groups = author_service::suggest_duplicates(&db).await?;
+    info!(
+        "Found {} groups of possible duplicate authors",
+        groups.len()
+    );
+
+    Ok(groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|(author, paper_count)| AuthorWithCountDto {
+                    author: AuthorDto::from(author),
+                    paper_count,
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Update an author's name, affiliation, email and ORCID iD. `name` is
+/// split into first and last name the same way imported names are. The
+/// ORCID iD may be given as an orcid.org URL.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn update_author(
+    db: State<'_, Arc<DatabaseConnection>>,
+    id: String,
+    name: String,
+    affiliation: Option<String>,
+    email: Option<String>,
+    orcid: Option<String>,
+) -> Result<AuthorDto> {
+    info!("Updating author {}", id);
+
+    if name.trim().is_empty() {
+        return Err(AppError::validation("name", "Author name cannot be empty"));
+    }
+
+    let blank_to_none = |value: Option<String>| {
+        value
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let email = blank_to_none(email);
+    if email.as_deref().is_some_and(|e| !e.contains('@')) {
+        return Err(AppError::validation("email", "Invalid email address"));
+    }
+
+    let author_id = parse_author_id(&id)?;
+    let orcid = match blank_to_none(orcid) {
+        Some(value) => {
+            let orcid = normalize_orcid(&value)
+                .ok_or_else(|| AppError::validation("orcid", "Invalid ORCID iD"))?;
+            if let Some(other) = AuthorRepository::find_by_orcid(&db, &orcid).await? {
+                if other.id != author_id {
+                    return Err(AppError::validation(
+                        "orcid",
+                        format!(
+                            "ORCID iD already belongs to {}; merge the authors instead",
+                            other.full_name()
+                        ),
+                    ));
+                }
+            }
+            Some(orcid)
+        }
+        None => None,
+    };
+
+    let author = AuthorRepository::update(
+        &db,
+        author_id,
+        AuthorNameParser::parse(&name),
+        blank_to_none(affiliation),
+        email,
+        orcid,
+    )
+    .await?;
+
+    Ok(AuthorDto::from(author))
+}
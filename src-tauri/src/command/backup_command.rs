@@ -0,0 +1,305 @@
+//! Tauri commands for database backup and restore
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument, warn};
+
+use crate::database::connection::open_database_file;
+use crate::database::migration::schema_version;
+use crate::database::DatabaseConnection;
+use crate::service::backup_service::{
+    configured_backup_dir, create_backup, default_backup_dir, stage_restore,
+};
+use crate::service::library_archive_service::{
+    ensure_outside_library, validate_library_archive, write_library_archive,
+};
+use crate::service::library_restore_service::{
+    ensure_compatible_schema, extract_database, merge_library, replace_library, MergeReport,
+    RestoreMode,
+};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::{AppDirs, MigrationPhase, MigrationStatus};
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct LibraryBackupResultDto {
+    pub path: String,
+    pub size_bytes: u64,
+    pub file_count: usize,
+    pub app_version: String,
+    pub schema_version: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize)]
+pub struct BackupValidationDto {
+    pub valid: bool,
+    pub app_version: String,
+    pub schema_version: String,
+    pub created_at: String,
+    pub file_count: usize,
+    /// Archive entries whose size or checksum differ from the manifest
+    pub mismatched: Vec<String>,
+    /// Manifest entries missing from the archive
+    pub missing: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct LibraryRestoreResultDto {
+    pub mode: RestoreMode,
+    /// Database snapshot taken before a replace, restorable with
+    /// `restore_database`
+    pub safety_backup_path: Option<String>,
+    /// What a merge added, skipped and failed to copy
+    pub merge: Option<MergeReport>,
+}
+
+#[derive(Serialize)]
+pub struct BackupResultDto {
+    pub path: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+/// Write a timestamped snapshot of the database into `target_dir`
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn backup_database(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    target_dir: String,
+) -> Result<BackupResultDto> {
+    info!("Backing up database to {}", target_dir);
+
+    let target_dir = if target_dir.trim().is_empty() {
+        let config = AppConfig::load(&app_dirs.config)?;
+        configured_backup_dir(&app_dirs, &config)
+    } else {
+        PathBuf::from(target_dir)
+    };
+
+    let path = create_backup(&db, &target_dir).await?;
+    let metadata = std::fs::metadata(&path)
+        .map_err(|e| AppError::file_system(path.to_string_lossy().to_string(), e.to_string()))?;
+
+    Ok(BackupResultDto {
+        path: path.to_string_lossy().to_string(),
+        size_bytes: metadata.len(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+/// Stage a backup to replace the database. The restore is applied on the
+/// next start, so the frontend should call `restart_app` afterwards.
+///
+/// Only backups inside the default or configured backup directory are accepted.
+#[tauri::command]
+#[instrument(skip(app_dirs))]
+pub async fn restore_database(app_dirs: State<'_, AppDirs>, backup_path: String) -> Result<()> {
+    info!("Restoring database from {}", backup_path);
+
+    let config = AppConfig::load(&app_dirs.config)?;
+    let allowed_dirs = vec![
+        default_backup_dir(&app_dirs),
+        configured_backup_dir(&app_dirs, &config),
+    ];
+
+    stage_restore(
+        &PathBuf::from(&app_dirs.data),
+        &PathBuf::from(&backup_path),
+        &allowed_dirs,
+    )
+}
+
+/// Write the whole library (database snapshot, config and attachments) to a
+/// zip archive at `target_path`, with a manifest of per-file checksums.
+/// Progress is emitted as `library-backup-progress` with the same payload as
+/// data folder migration.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn export_library_backup(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    target_path: String,
+) -> Result<LibraryBackupResultDto> {
+    info!("Exporting library backup to {}", target_path);
+
+    let target = PathBuf::from(&target_path);
+    if target.is_dir() {
+        return Err(AppError::validation(
+            "target_path",
+            "Backup path is a directory",
+        ));
+    }
+    ensure_outside_library(&target, &app_dirs)?;
+
+    // Archive a consistent snapshot rather than the live database file
+    let snapshot_dir = PathBuf::from(&app_dirs.cache).join("library-backup");
+    let snapshot = create_backup(&db, &snapshot_dir).await?;
+
+    let dirs = app_dirs.inner().clone();
+    let schema = schema_version();
+    let archive_target = target.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        write_library_archive(&snapshot, &dirs, &schema, &archive_target, |status| {
+            let _ = app.emit("library-backup-progress", status);
+        })
+    })
+    .await
+    .map_err(|e| AppError::generic(format!("Library backup task failed: {}", e)));
+
+    if let Err(e) = std::fs::remove_dir_all(&snapshot_dir) {
+        warn!("Failed to remove backup snapshot {:?}: {}", snapshot_dir, e);
+    }
+    let manifest = result??;
+
+    let metadata = std::fs::metadata(&target)
+        .map_err(|e| AppError::file_system(target_path.clone(), e.to_string()))?;
+
+    Ok(LibraryBackupResultDto {
+        path: target_path,
+        size_bytes: metadata.len(),
+        file_count: manifest.entries.len(),
+        app_version: manifest.app_version,
+        schema_version: manifest.schema_version,
+        created_at: manifest.created_at,
+    })
+}
+
+/// Check a library archive against its manifest checksums. Entries are read
+/// and hashed in memory; nothing is extracted.
+#[tauri::command]
+#[instrument]
+pub async fn validate_backup(path: String) -> Result<BackupValidationDto> {
+    info!("Validating library backup {}", path);
+
+    let validation =
+        tokio::task::spawn_blocking(move || validate_library_archive(&PathBuf::from(path)))
+            .await
+            .map_err(|e| AppError::generic(format!("Backup validation task failed: {}", e)))??;
+
+    Ok(BackupValidationDto {
+        valid: validation.is_valid(),
+        app_version: validation.manifest.app_version,
+        schema_version: validation.manifest.schema_version,
+        created_at: validation.manifest.created_at,
+        file_count: validation.manifest.entries.len(),
+        mismatched: validation.mismatched,
+        missing: validation.missing,
+    })
+}
+
+/// Restore the library from an archive written by `export_library_backup`.
+///
+/// The archive is checked against its manifest first. `replace` takes a
+/// snapshot of the current database into the backup directory, then swaps
+/// in the archived config, attachments and database; the database change
+/// takes effect on the next start. `merge` adds the categories, labels and
+/// papers the library does not have yet, with their missing attachment
+/// files, and reports every entity it skipped or failed to copy.
+///
+/// Progress is emitted as `library-restore-progress`. The frontend should
+/// call `restart_app` afterwards.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn restore_library_backup(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    archive_path: String,
+    mode: RestoreMode,
+) -> Result<LibraryRestoreResultDto> {
+    info!("Restoring library from {} ({:?})", archive_path, mode);
+
+    let emit_progress = |app: AppHandle| {
+        move |status: &MigrationStatus| {
+            let _ = app.emit("library-restore-progress", status);
+        }
+    };
+    let mut on_progress = emit_progress(app.clone());
+    let phase = |phase: MigrationPhase| MigrationStatus {
+        phase,
+        current_file: None,
+        total_files: 0,
+        processed_files: 0,
+        error: None,
+    };
+
+    on_progress(&phase(MigrationPhase::Verifying));
+    let path = PathBuf::from(&archive_path);
+    let validate_path = path.clone();
+    let validation = tokio::task::spawn_blocking(move || validate_library_archive(&validate_path))
+        .await
+        .map_err(|e| AppError::generic(format!("Backup validation task failed: {}", e)))??;
+    if !validation.is_valid() {
+        return Err(AppError::validation(
+            "archive_path",
+            format!(
+                "The archive is damaged: {} files differ from the manifest and {} are missing",
+                validation.mismatched.len(),
+                validation.missing.len()
+            ),
+        ));
+    }
+    let manifest = validation.manifest;
+    ensure_compatible_schema(&manifest, &schema_version())?;
+
+    match mode {
+        RestoreMode::Replace => {
+            on_progress(&phase(MigrationPhase::Preparing));
+            let safety_backup = create_backup(&db, &default_backup_dir(&app_dirs)).await?;
+
+            let dirs = app_dirs.inner().clone();
+            let on_progress = emit_progress(app);
+            tokio::task::spawn_blocking(move || {
+                replace_library(&path, &manifest, &dirs, on_progress)
+            })
+            .await
+            .map_err(|e| AppError::generic(format!("Library restore task failed: {}", e)))??;
+
+            Ok(LibraryRestoreResultDto {
+                mode,
+                safety_backup_path: Some(safety_backup.to_string_lossy().to_string()),
+                merge: None,
+            })
+        }
+        RestoreMode::Merge => {
+            let staging_dir = PathBuf::from(&app_dirs.cache).join("library-restore");
+            let database = staging_dir.join("archived.sqlite");
+            let _ = std::fs::remove_dir_all(&staging_dir);
+
+            let result = async {
+                let (archive, extract_manifest, target) =
+                    (path.clone(), manifest.clone(), database.clone());
+                tokio::task::spawn_blocking(move || {
+                    extract_database(&archive, &extract_manifest, &target)
+                })
+                .await
+                .map_err(|e| AppError::generic(format!("Library restore task failed: {}", e)))??;
+
+                let source = open_database_file(&database).await?;
+                let report =
+                    merge_library(&db, &source, &path, &manifest, &app_dirs, on_progress).await;
+                if let Err(e) = source.close().await {
+                    warn!("Failed to close archived database: {}", e);
+                }
+                report
+            }
+            .await;
+
+            if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+                warn!("Failed to remove restore staging {:?}: {}", staging_dir, e);
+            }
+
+            Ok(LibraryRestoreResultDto {
+                mode,
+                safety_backup_path: None,
+                merge: Some(result?),
+            })
+        }
+    }
+}
@@ -6,8 +6,9 @@ use tracing::{info, instrument};
 
 use crate::axum::state::SelectedCategoryState;
 use crate::database::DatabaseConnection;
-use crate::models::{CreateCategory, UpdateCategory};
+use crate::models::{CategoryNodeWithCount, CreateCategory, UpdateCategory};
 use crate::repository::{CategoryRepository, TreeNodeData};
+use crate::service::category_suggestion_service::{self, CategorySuggestionDto};
 use crate::sys::error::Result;
 
 #[tauri::command]
@@ -30,6 +31,16 @@ pub async fn load_categories(db: State<'_, Arc<DatabaseConnection>>) -> Result<V
     Ok(result)
 }
 
+/// Category tree with the number of papers directly in each category and in
+/// its whole subtree, loaded in one pass for the sidebar
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_category_tree_with_counts(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<CategoryNodeWithCount>> {
+    CategoryRepository::load_tree_with_counts(&db).await
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn create_category(
@@ -240,3 +251,18 @@ pub async fn get_selected_category(
     info!("Getting selected category: {:?}", result);
     Ok(result)
 }
+
+/// Suggest categories for a paper from the categories of papers sharing its
+/// keywords, most confident first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn suggest_categories_for_paper(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Vec<CategorySuggestionDto>> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| crate::sys::error::AppError::validation("paper_id", "Invalid id format"))?;
+
+    category_suggestion_service::suggest_categories(&db, paper_id_num).await
+}
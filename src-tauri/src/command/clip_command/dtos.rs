@@ -2,6 +2,11 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::{ClipSortField, SortDirection};
+
+/// Page size used when `list_clips` is called without one
+pub const DEFAULT_CLIP_PAGE_SIZE: u32 = 50;
+
 /// Comment DTO for clip comments
 #[derive(Serialize, Clone)]
 pub struct CommentDto {
@@ -12,6 +17,14 @@ pub struct CommentDto {
     pub updated_at: String,
 }
 
+/// Label attached to a clip
+#[derive(Serialize, Clone)]
+pub struct LabelDto {
+    pub id: String,
+    pub name: String,
+    pub color: String,
+}
+
 /// Response DTO for clip list and detail views
 #[derive(Serialize, Clone)]
 pub struct ClipDto {
@@ -29,10 +42,71 @@ pub struct ClipDto {
     pub tags: Vec<String>,
     pub image_paths: Vec<String>,
     pub comments: Vec<CommentDto>,
+    pub labels: Vec<LabelDto>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// Sorting, filtering and paging for `list_clips`; every field may be
+/// omitted. `page` starts at 1.
+#[derive(Deserialize, Debug)]
+#[serde(default)]
+pub struct ListClipsParams {
+    pub sort_by: ClipSortField,
+    pub sort_dir: SortDirection,
+    /// 0 = unread, 1 = read
+    pub read_status: Option<i32>,
+    pub source_domain: Option<String>,
+    /// Only clips carrying all of these labels
+    pub label_ids: Vec<String>,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl Default for ListClipsParams {
+    fn default() -> Self {
+        Self {
+            sort_by: ClipSortField::default(),
+            sort_dir: SortDirection::default(),
+            read_status: None,
+            source_domain: None,
+            label_ids: Vec::new(),
+            page: 1,
+            page_size: DEFAULT_CLIP_PAGE_SIZE,
+        }
+    }
+}
+
+/// One page of clips
+#[derive(Serialize)]
+pub struct PaginatedClipsDto {
+    pub clips: Vec<ClipDto>,
+    pub total: u64,
+    pub page: u32,
+    pub page_size: u32,
+    pub has_more: bool,
+    /// Relevance score of each clip (0-100, higher is better); only set by
+    /// `search_clippings`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scores: Vec<f64>,
+}
+
+/// Number of clips from one source domain
+#[derive(Serialize)]
+pub struct DomainCountDto {
+    pub domain: String,
+    pub count: u64,
+}
+
+/// Clip totals for the clips sidebar
+#[derive(Serialize)]
+pub struct ClipStatsDto {
+    pub total: u64,
+    pub unread: u64,
+    /// Most clipped domains first
+    pub by_domain: Vec<DomainCountDto>,
+}
+
 /// Request DTO for creating a new clip
 #[derive(Deserialize, Debug)]
 pub struct CreateClipRequest {
@@ -48,6 +122,27 @@ pub struct CreateClipRequest {
     pub tags: Vec<String>,
 }
 
+/// Request DTO for updating a clip; omitted fields are left unchanged
+#[derive(Deserialize, Debug)]
+pub struct UpdateClipRequest {
+    pub title: Option<String>,
+    pub notes: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// 0 = unread, 1 = read
+    pub read_status: Option<i32>,
+    pub excerpt: Option<String>,
+}
+
+/// Result of `create_clip`. Saving a URL that is already clipped returns the
+/// existing clip with `already_exists` set instead of failing.
+#[derive(Serialize)]
+pub struct CreateClipResultDto {
+    pub already_exists: bool,
+    /// Message suitable for a notification
+    pub message: String,
+    pub clip: ClipDto,
+}
+
 /// Response DTO for create operation
 #[derive(Serialize)]
 pub struct CreateClipResponse {
@@ -3,8 +3,9 @@
 //! This module contains all clip-related Tauri commands:
 //! - `dtos`: Data Transfer Objects
 //! - `utils`: Helper functions for image processing
-//! - `query`: Read operations (list_clips, get_clip)
-//! - `mutation`: Write operations (create_clip, add_clip_comment, update_clip_comment, delete_clip_comment)
+//! - `query`: Read operations (list_clips, get_clip, get_clip_stats, search_clippings)
+//! - `mutation`: Write operations (create_clip, update_clip, delete_clip, add_clip_label,
+//!   remove_clip_label, add_clip_comment, update_clip_comment, delete_clip_comment)
 
 mod dtos;
 mod mutation;
@@ -12,5 +13,9 @@ mod query;
 mod utils;
 
 // Re-export all commands
-pub use mutation::{add_clip_comment, create_clip, delete_clip_comment, update_clip_comment};
-pub use query::{get_clip, list_clips};
+pub use dtos::{CreateClipRequest, CreateClipResponse};
+pub use mutation::{
+    add_clip_comment, add_clip_label, create_clip, create_clip_from_request, delete_clip,
+    delete_clip_comment, remove_clip_label, update_clip, update_clip_comment,
+};
+pub use query::{get_clip, get_clip_stats, get_clips_by_label, list_clips, search_clippings};
@@ -7,15 +7,27 @@ use tauri::{AppHandle, State};
 use tracing::{info, instrument, warn};
 
 use crate::database::DatabaseConnection;
-use crate::models::{CreateClipping, UpdateClipping};
-use crate::repository::ClippingRepository;
+use crate::models::{Clipping, CreateClipping, Label, UpdateClipping};
+use crate::repository::{ClippingRepository, LabelRepository};
+use crate::service::activity_service::{self, ACTION_CREATED, ENTITY_CLIP};
 use crate::sys::dirs::AppDirs;
 use crate::sys::error::{AppError, Result};
 
-use super::dtos::{CommentDto, CreateClipRequest, CreateClipResponse};
-use super::utils::process_markdown_images;
+use super::dtos::{
+    ClipDto, CommentDto, CreateClipRequest, CreateClipResponse, CreateClipResultDto,
+    UpdateClipRequest,
+};
+use super::query::clip_to_dto;
+use super::utils::{process_markdown_images, remove_clip_images};
 
-/// Create a new clip with image downloading
+fn parse_id(field: &str, id: &str) -> Result<i64> {
+    id.parse::<i64>().map_err(|_| {
+        AppError::validation(field, format!("Invalid {} format", field.replace('_', " ")))
+    })
+}
+
+/// Create a new clip with image downloading. A URL that is already clipped
+/// returns the existing clip with `already_exists` set.
 #[tauri::command]
 #[instrument(skip(db, app_dirs, _app))]
 pub async fn create_clip(
@@ -23,9 +35,72 @@ pub async fn create_clip(
     db: State<'_, Arc<DatabaseConnection>>,
     app_dirs: State<'_, AppDirs>,
     payload: CreateClipRequest,
+) -> Result<CreateClipResultDto> {
+    if let Some(existing) = ClippingRepository::find_by_url(&db, &payload.url).await? {
+        return already_saved(&db, existing).await;
+    }
+
+    let url = payload.url.clone();
+    let created = match create_clip_from_request(&db, &app_dirs.files, payload).await {
+        Ok(created) => created,
+        Err(e) => {
+            // Another caller may have stored the same URL in the meantime
+            if let Ok(Some(existing)) = ClippingRepository::find_by_url(&db, &url).await {
+                return already_saved(&db, existing).await;
+            }
+            return Err(e);
+        }
+    };
+
+    let clip_id = parse_id("id", &created.id)?;
+    let clipping = ClippingRepository::find_by_id(&db, clip_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Clipping", created.id.clone()))?;
+    activity_service::record(&db, ENTITY_CLIP, clip_id, ACTION_CREATED).await;
+    let labels = clip_labels(&db, clip_id).await?;
+
+    Ok(CreateClipResultDto {
+        already_exists: false,
+        message: format!("Clip '{}' saved", clipping.title),
+        clip: clip_to_dto(clipping, labels),
+    })
+}
+
+async fn already_saved(db: &DatabaseConnection, existing: Clipping) -> Result<CreateClipResultDto> {
+    info!("Clip for {} already exists: {}", existing.url, existing.id);
+    let labels = clip_labels(db, existing.id).await?;
+
+    Ok(CreateClipResultDto {
+        already_exists: true,
+        message: "Clip already saved".to_string(),
+        clip: clip_to_dto(existing, labels),
+    })
+}
+
+/// Labels currently on a clip
+async fn clip_labels(db: &DatabaseConnection, clip_id: i64) -> Result<Vec<Label>> {
+    Ok(LabelRepository::get_clip_labels_batch(db, &[clip_id])
+        .await?
+        .remove(&clip_id)
+        .unwrap_or_default())
+}
+
+/// Sanitize a clip's content, store it and download its images into
+/// `files_dir`. Shared by the Tauri command and the API server.
+pub async fn create_clip_from_request(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    payload: CreateClipRequest,
 ) -> Result<CreateClipResponse> {
     info!("Creating clip: {}", payload.title);
 
+    if let Some(existing) = ClippingRepository::find_by_url(db, &payload.url).await? {
+        return Err(AppError::validation(
+            "url",
+            format!("A clip for this URL already exists (id {})", existing.id),
+        ));
+    }
+
     // Sanitize HTML content
     let sanitized_content = clean(&payload.content);
 
@@ -43,14 +118,14 @@ pub async fn create_clip(
         image_paths: Vec::new(),
     };
 
-    let clipping = ClippingRepository::create_clipping(&db, create_clipping).await?;
+    let clipping = ClippingRepository::create_clipping(db, create_clipping).await?;
 
     // Extract clip ID for image processing
     let clip_id = clipping.id.to_string();
 
     // Process and download images
     let (processed_content, image_paths) =
-        process_markdown_images(sanitized_content, &clip_id, &app_dirs.files)
+        process_markdown_images(sanitized_content, &clip_id, files_dir)
             .await
             .map_err(|e| {
                 AppError::file_system(&clip_id, format!("Failed to process images: {}", e))
@@ -72,7 +147,7 @@ pub async fn create_clip(
         image_paths: Some(image_paths.clone()),
     };
 
-    let updated = ClippingRepository::update_clipping(&db, clipping.id, update_clipping).await?;
+    let updated = ClippingRepository::update_clipping(db, clipping.id, update_clipping).await?;
 
     if updated.is_none() {
         warn!("Failed to update clipping with image paths, but clip was created");
@@ -94,6 +169,110 @@ pub async fn create_clip(
     })
 }
 
+/// Update a clip's title, notes, tags, read status and excerpt. Omitted
+/// fields are left unchanged.
+#[tauri::command]
+#[instrument(skip(db, payload))]
+pub async fn update_clip(
+    db: State<'_, Arc<DatabaseConnection>>,
+    id: String,
+    payload: UpdateClipRequest,
+) -> Result<ClipDto> {
+    info!("Updating clip: {}", id);
+
+    let clip_id = parse_id("id", &id)?;
+
+    let title = payload.title.map(|t| t.trim().to_string());
+    if title.as_deref().is_some_and(str::is_empty) {
+        return Err(AppError::validation("title", "Clip title cannot be empty"));
+    }
+    if payload.read_status.is_some_and(|s| !(0..=1).contains(&s)) {
+        return Err(AppError::validation(
+            "read_status",
+            "Read status must be 0 (unread) or 1 (read)",
+        ));
+    }
+    let tags = payload.tags.map(|tags| {
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !cleaned.iter().any(|c| c == tag) {
+                cleaned.push(tag.to_string());
+            }
+        }
+        cleaned
+    });
+
+    let update = UpdateClipping {
+        title,
+        notes: payload.notes,
+        tags,
+        read_status: payload.read_status,
+        excerpt: payload.excerpt,
+        ..Default::default()
+    };
+
+    let clipping = ClippingRepository::update_clipping(&db, clip_id, update)
+        .await?
+        .ok_or_else(|| AppError::not_found("Clipping", id.clone()))?;
+    let labels = clip_labels(&db, clip_id).await?;
+
+    Ok(clip_to_dto(clipping, labels))
+}
+
+/// Permanently delete a clip with its comments, label links and downloaded
+/// images
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn delete_clip(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    id: String,
+) -> Result<()> {
+    info!("Deleting clip: {}", id);
+
+    let clip_id = parse_id("id", &id)?;
+    let clipping = ClippingRepository::delete(&db, clip_id).await?;
+    remove_clip_images(&app_dirs.files, &clip_id.to_string(), &clipping.image_paths);
+
+    Ok(())
+}
+
+/// Attach a label to a clip
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn add_clip_label(
+    db: State<'_, Arc<DatabaseConnection>>,
+    clip_id: String,
+    label_id: String,
+) -> Result<()> {
+    info!("Adding label {} to clip {}", label_id, clip_id);
+
+    LabelRepository::add_to_clip(
+        &db,
+        parse_id("clip_id", &clip_id)?,
+        parse_id("label_id", &label_id)?,
+    )
+    .await
+}
+
+/// Detach a label from a clip
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn remove_clip_label(
+    db: State<'_, Arc<DatabaseConnection>>,
+    clip_id: String,
+    label_id: String,
+) -> Result<()> {
+    info!("Removing label {} from clip {}", label_id, clip_id);
+
+    LabelRepository::remove_from_clip(
+        &db,
+        parse_id("clip_id", &clip_id)?,
+        parse_id("label_id", &label_id)?,
+    )
+    .await
+}
+
 /// Add a comment to a clip
 #[tauri::command]
 #[instrument(skip(db))]
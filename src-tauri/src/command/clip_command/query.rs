@@ -6,10 +6,16 @@ use tauri::State;
 use tracing::{info, instrument};
 
 use crate::database::DatabaseConnection;
-use crate::repository::ClippingRepository;
+use crate::models::{ClipFilter, ClipSortField, Clipping, Label, SortDirection};
+use crate::repository::{ClippingRepository, LabelRepository, SearchRepository};
 use crate::sys::error::{AppError, Result};
 
-use super::dtos::{ClipDto, CommentDto};
+use super::dtos::{
+    ClipDto, ClipStatsDto, CommentDto, DomainCountDto, LabelDto, ListClipsParams, PaginatedClipsDto,
+};
+
+/// Largest page accepted by `list_clips`
+const MAX_PAGE_SIZE: u32 = 200;
 
 /// Convert Clipping comments to CommentDto
 fn comments_to_dto(
@@ -27,47 +33,230 @@ fn comments_to_dto(
         .collect()
 }
 
-/// List all clips with optional pagination
+/// Convert a clipping and its labels to a ClipDto
+pub(super) fn clip_to_dto(c: Clipping, labels: Vec<Label>) -> ClipDto {
+    ClipDto {
+        id: c.id.to_string(),
+        title: c.title,
+        url: c.url,
+        content: c.content,
+        source_domain: c.source_domain,
+        author: c.author,
+        published_date: c.published_date,
+        excerpt: c.excerpt,
+        thumbnail_url: c.thumbnail_url,
+        read_status: c.read_status,
+        notes: c.notes,
+        tags: c.tags,
+        image_paths: c.image_paths,
+        comments: comments_to_dto(c.comments),
+        labels: labels
+            .into_iter()
+            .map(|l| LabelDto {
+                id: l.id.to_string(),
+                name: l.name,
+                color: l.color,
+            })
+            .collect(),
+        created_at: c.created_at.to_rfc3339(),
+        updated_at: c.updated_at.to_rfc3339(),
+    }
+}
+
+/// List one page of clips, sorted and filtered by `params`
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn list_clips(
     db: State<'_, Arc<DatabaseConnection>>,
-    limit: Option<usize>,
-    offset: Option<usize>,
-) -> Result<Vec<ClipDto>> {
-    info!("Fetching clips (limit: {:?}, offset: {:?})", limit, offset);
+    params: Option<ListClipsParams>,
+) -> Result<PaginatedClipsDto> {
+    let params = params.unwrap_or_default();
+    info!(
+        "Fetching clips (page: {}, page_size: {})",
+        params.page, params.page_size
+    );
 
-    let clippings = ClippingRepository::get_all_clippings(&db).await?;
+    check_page(params.page, params.page_size)?;
 
-    let offset_val = offset.unwrap_or(0);
-    let limit_val = limit.unwrap_or(clippings.len());
+    let label_ids = params
+        .label_ids
+        .iter()
+        .map(|id| {
+            id.parse::<i64>()
+                .map_err(|_| AppError::validation("label_ids", "Invalid label id format"))
+        })
+        .collect::<Result<Vec<i64>>>()?;
+    let filter = ClipFilter {
+        read_status: params.read_status,
+        source_domain: params.source_domain.filter(|d| !d.trim().is_empty()),
+        label_ids,
+    };
 
-    let mut result = Vec::new();
-    for c in clippings.into_iter().skip(offset_val).take(limit_val) {
-        // Get comments for this clipping
-        let comments = ClippingRepository::get_comments(&db, c.id).await.unwrap_or_default();
-        result.push(ClipDto {
-            id: c.id.to_string(),
-            title: c.title,
-            url: c.url,
-            content: c.content,
-            source_domain: c.source_domain,
-            author: c.author,
-            published_date: c.published_date,
-            excerpt: c.excerpt,
-            thumbnail_url: c.thumbnail_url,
-            read_status: c.read_status,
-            notes: c.notes,
-            tags: c.tags,
-            image_paths: c.image_paths,
-            comments: comments_to_dto(comments),
-            created_at: c.created_at.to_rfc3339(),
-            updated_at: c.updated_at.to_rfc3339(),
-        });
+    clips_page(
+        &db,
+        &filter,
+        params.sort_by,
+        params.sort_dir,
+        params.page,
+        params.page_size,
+    )
+    .await
+}
+
+/// One page of the clips carrying a label, newest first
+///
+/// # Arguments
+/// * `label_id` - The label
+/// * `page` - Page number, starting at 1
+/// * `page_size` - Clips per page (at most 200)
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_clips_by_label(
+    db: State<'_, Arc<DatabaseConnection>>,
+    label_id: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedClipsDto> {
+    check_page(page, page_size)?;
+    let label_id_num = label_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("label_id", "Invalid label id format"))?;
+    if LabelRepository::find_by_id(&db, label_id_num)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::not_found("Label", label_id));
+    }
+
+    let filter = ClipFilter {
+        label_ids: vec![label_id_num],
+        ..Default::default()
+    };
+    clips_page(
+        &db,
+        &filter,
+        ClipSortField::CreatedAt,
+        SortDirection::Desc,
+        page,
+        page_size,
+    )
+    .await
+}
+
+fn check_page(page: u32, page_size: u32) -> Result<()> {
+    if page == 0 {
+        return Err(AppError::validation("page", "Page numbers start at 1"));
+    }
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(AppError::validation(
+            "page_size",
+            format!("Must be between 1 and {}", MAX_PAGE_SIZE),
+        ));
+    }
+    Ok(())
+}
+
+/// One page of the clips matching `filter`, with their labels
+async fn clips_page(
+    db: &DatabaseConnection,
+    filter: &ClipFilter,
+    sort_by: ClipSortField,
+    sort_dir: SortDirection,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedClipsDto> {
+    let offset = (page as u64 - 1) * page_size as u64;
+    let (clips, total) =
+        ClippingRepository::find_page(db, filter, sort_by, sort_dir, offset, page_size as u64)
+            .await?;
+
+    let clip_ids: Vec<i64> = clips.iter().map(|c| c.id).collect();
+    let mut labels_map = LabelRepository::get_clip_labels_batch(db, &clip_ids).await?;
+
+    let clips: Vec<ClipDto> = clips
+        .into_iter()
+        .map(|c| {
+            let labels = labels_map.remove(&c.id).unwrap_or_default();
+            clip_to_dto(c, labels)
+        })
+        .collect();
+
+    info!("Fetched {} of {} clips", clips.len(), total);
+    Ok(PaginatedClipsDto {
+        has_more: offset + (clips.len() as u64) < total,
+        clips,
+        total,
+        page,
+        page_size,
+        scores: Vec::new(),
+    })
+}
+
+/// Full-text search across clip title, excerpt and content, one page at a
+/// time. Title matches rank above matches in the body.
+///
+/// # Arguments
+/// * `query` - Search words; every word must match
+/// * `page` - Page number, starting at 1
+/// * `page_size` - Clips per page (at most 200)
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_clippings(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedClipsDto> {
+    check_page(page, page_size)?;
+
+    let offset = (page as u64 - 1) * page_size as u64;
+    let (hits, total) =
+        SearchRepository::clip_fts_search_page(&db, query.trim(), offset, page_size as u64).await?;
+
+    let clip_ids: Vec<i64> = hits.iter().map(|(c, _)| c.id).collect();
+    let mut labels_map = LabelRepository::get_clip_labels_batch(&db, &clip_ids).await?;
+
+    let mut clips = Vec::with_capacity(hits.len());
+    let mut scores = Vec::with_capacity(hits.len());
+    for (model, score) in hits {
+        let labels = labels_map.remove(&model.id).unwrap_or_default();
+        clips.push(clip_to_dto(Clipping::from(model), labels));
+        scores.push(score);
     }
 
-    info!("Fetched {} clips", result.len());
-    Ok(result)
+    info!(
+        "Clip search for '{}' found {} of {} clips",
+        query,
+        clips.len(),
+        total
+    );
+    Ok(PaginatedClipsDto {
+        has_more: offset + (clips.len() as u64) < total,
+        clips,
+        total,
+        page,
+        page_size,
+        scores,
+    })
+}
+
+/// Count all clips, unread clips and clips per source domain
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_clip_stats(db: State<'_, Arc<DatabaseConnection>>) -> Result<ClipStatsDto> {
+    let (total, unread, by_domain) = ClippingRepository::count_stats(&db).await?;
+
+    let mut by_domain: Vec<DomainCountDto> = by_domain
+        .into_iter()
+        .map(|(domain, count)| DomainCountDto { domain, count })
+        .collect();
+    by_domain.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.domain.cmp(&b.domain)));
+
+    Ok(ClipStatsDto {
+        total,
+        unread,
+        by_domain,
+    })
 }
 
 /// Get a single clip by ID
@@ -84,26 +273,11 @@ pub async fn get_clip(id: String, db: State<'_, Arc<DatabaseConnection>>) -> Res
     match clipping {
         Some(c) => {
             info!("Found clip: {}", id);
-            // Get comments for this clipping
-            let comments = ClippingRepository::get_comments(&db, c.id).await.unwrap_or_default();
-            Ok(Some(ClipDto {
-                id: c.id.to_string(),
-                title: c.title,
-                url: c.url,
-                content: c.content,
-                source_domain: c.source_domain,
-                author: c.author,
-                published_date: c.published_date,
-                excerpt: c.excerpt,
-                thumbnail_url: c.thumbnail_url,
-                read_status: c.read_status,
-                notes: c.notes,
-                tags: c.tags,
-                image_paths: c.image_paths,
-                comments: comments_to_dto(comments),
-                created_at: c.created_at.to_rfc3339(),
-                updated_at: c.updated_at.to_rfc3339(),
-            }))
+            let labels = LabelRepository::get_clip_labels_batch(&db, &[c.id])
+                .await?
+                .remove(&c.id)
+                .unwrap_or_default();
+            Ok(Some(clip_to_dto(c, labels)))
         }
         None => {
             info!("Clip not found: {}", id);
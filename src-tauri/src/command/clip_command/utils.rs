@@ -1,7 +1,7 @@
 //! Utility functions for clip commands
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 use regex::Regex;
 use sha1::{Digest, Sha1};
@@ -9,6 +9,9 @@ use tracing::{info, warn};
 
 use crate::sys::error::{AppError, Result};
 
+/// URL prefix downloaded clip images are served under; maps to `{files}/clips`
+const CLIP_IMAGE_URL_PREFIX: &str = "/clips/images/";
+
 /// Extract filename from URL
 fn extract_filename_from_url(url: &str) -> String {
     if let Some(parsed) = url.split('?').next() {
@@ -70,7 +73,10 @@ async fn download_image(url: &str, clip_id: &str, files_dir: &str) -> Result<Str
     })?;
 
     info!("Downloaded image from {} to {:?}", url, local_path);
-    Ok(format!("/clips/images/{}/images/{}", clip_id, filename))
+    Ok(format!(
+        "{}{}/images/{}",
+        CLIP_IMAGE_URL_PREFIX, clip_id, filename
+    ))
 }
 
 /// Process markdown content to download and replace image URLs
@@ -109,3 +115,62 @@ pub async fn process_markdown_images(
 
     Ok((updated_content, image_paths))
 }
+
+/// Delete a clip's downloaded images, then its directory if nothing else is
+/// left in it. `image_paths` are the URLs recorded by
+/// `process_markdown_images`; anything resolving outside the clips
+/// directory is skipped.
+pub fn remove_clip_images(files_dir: &str, clip_id: &str, image_paths: &[String]) {
+    let clips_dir = PathBuf::from(files_dir).join("clips");
+
+    for image_path in image_paths {
+        let relative = image_path
+            .strip_prefix(CLIP_IMAGE_URL_PREFIX)
+            .map(Path::new)
+            .filter(|p| p.components().all(|c| matches!(c, Component::Normal(_))));
+        let Some(relative) = relative else {
+            warn!("Skipping unexpected clip image path: {}", image_path);
+            continue;
+        };
+
+        let path = clips_dir.join(relative);
+        if let Err(e) = fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to delete clip image {:?}: {}", path, e);
+            }
+        }
+    }
+
+    // remove_dir only succeeds on empty directories
+    let clip_dir = clips_dir.join(clip_id);
+    let _ = fs::remove_dir(clip_dir.join("images"));
+    let _ = fs::remove_dir(&clip_dir);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_clip_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let files_dir = dir.path().to_string_lossy().to_string();
+        let images_dir = dir.path().join("clips").join("7").join("images");
+        fs::create_dir_all(&images_dir).unwrap();
+        fs::write(images_dir.join("a.png"), b"png").unwrap();
+        let outside = dir.path().join("keep.txt");
+        fs::write(&outside, b"keep").unwrap();
+
+        remove_clip_images(
+            &files_dir,
+            "7",
+            &[
+                "/clips/images/7/images/a.png".to_string(),
+                "/clips/images/../keep.txt".to_string(),
+            ],
+        );
+
+        assert!(!dir.path().join("clips").join("7").exists());
+        assert!(outside.exists());
+    }
+}
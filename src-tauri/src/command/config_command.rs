@@ -1,14 +1,300 @@
-use crate::sys::config::AppConfig;
+use std::time::Instant;
+
+use crate::llm::client::LlmClient;
+use crate::papers::importer::grobid::check_alive;
+use crate::sys::config::{AppConfig, GrobidServer, LlmProvider, LlmProviderKind};
 use crate::sys::dirs::AppDirs;
-use crate::sys::error::Result;
+use crate::sys::error::{AppError, Result};
+use crate::sys::secrets;
+use serde::{Deserialize, Serialize};
 use tauri::State;
+use tracing::{info, instrument, warn};
+
+/// Tokens the connection probe may answer with
+const PROBE_MAX_TOKENS: u32 = 16;
+
+#[derive(Serialize)]
+pub struct GrobidHealthDto {
+    pub url: String,
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub response_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct GrobidServerDto {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub is_active: bool,
+}
 
+impl From<GrobidServer> for GrobidServerDto {
+    fn from(server: GrobidServer) -> Self {
+        Self {
+            id: server.id,
+            name: server.name,
+            url: server.url,
+            is_active: server.is_active,
+        }
+    }
+}
+
+/// Settings of the default LLM provider. The API key is write-only: it is
+/// never returned, `has_api_key` says whether one is stored.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LlmConfigDto {
+    pub provider: LlmProviderKind,
+    /// New key to store in the keychain; `None` keeps the stored key and an
+    /// empty string removes it
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub has_api_key: bool,
+    pub api_url: String,
+    pub model: String,
+    pub max_tokens: u32,
+    pub temperature: f32,
+}
+
+#[derive(Serialize)]
+pub struct LlmConnectionTestDto {
+    pub success: bool,
+    pub model: String,
+    pub response_ms: u64,
+    /// The model's reply on success, the error otherwise
+    pub message: String,
+}
+
+/// Get the settings with every API key blanked; keys never leave the backend
 #[tauri::command]
 pub async fn get_app_config(app_dirs: State<'_, AppDirs>) -> Result<AppConfig> {
-    AppConfig::load(&app_dirs.config)
+    Ok(AppConfig::load(&app_dirs.config)?.without_secrets())
+}
+
+/// Save the settings. API keys go to the OS keychain; a key left unset keeps
+/// the one already stored and an empty key removes it.
+#[tauri::command]
+pub async fn save_app_config(app_dirs: State<'_, AppDirs>, mut config: AppConfig) -> Result<()> {
+    let previous = AppConfig::load(&app_dirs.config)?;
+    config.store_secrets(&previous)?;
+    config.save(&app_dirs.config)
+}
+
+/// Check whether a GROBID server answers `/api/isalive` within 5 seconds.
+/// An unreachable server is a normal result, not an error.
+#[tauri::command]
+#[instrument]
+pub async fn check_grobid_server(url: String) -> Result<GrobidHealthDto> {
+    let url = url.trim().trim_end_matches('/').to_string();
+    if url.is_empty() {
+        return Err(AppError::validation("url", "GROBID server URL is required"));
+    }
+
+    let health = check_alive(&url).await;
+    info!(
+        "GROBID server {} reachable: {} ({} ms)",
+        url, health.reachable, health.response_ms
+    );
+
+    Ok(GrobidHealthDto {
+        url,
+        reachable: health.reachable,
+        version: health.version,
+        response_ms: health.response_ms,
+    })
 }
 
+/// Get the configured GROBID servers
 #[tauri::command]
-pub async fn save_app_config(app_dirs: State<'_, AppDirs>, config: AppConfig) -> Result<()> {
+#[instrument(skip(app_dirs))]
+pub async fn get_grobid_servers(app_dirs: State<'_, AppDirs>) -> Result<Vec<GrobidServerDto>> {
+    let config = AppConfig::load(&app_dirs.config)?;
+    Ok(config
+        .paper
+        .grobid
+        .servers
+        .into_iter()
+        .map(GrobidServerDto::from)
+        .collect())
+}
+
+/// Make the configured server with this URL the one used for PDF imports
+#[tauri::command]
+#[instrument(skip(app_dirs))]
+pub async fn set_active_grobid_server(app_dirs: State<'_, AppDirs>, url: String) -> Result<()> {
+    let url = url.trim().trim_end_matches('/');
+    let mut config = AppConfig::load(&app_dirs.config)?;
+    let servers = &mut config.paper.grobid.servers;
+
+    if !servers.iter().any(|s| s.url.trim_end_matches('/') == url) {
+        return Err(AppError::not_found("GrobidServer", url));
+    }
+    for server in servers.iter_mut() {
+        server.is_active = server.url.trim_end_matches('/') == url;
+    }
+
+    info!("Active GROBID server set to {}", url);
     config.save(&app_dirs.config)
 }
+
+/// Get the default LLM provider's settings; defaults when none is configured
+#[tauri::command]
+#[instrument(skip(app_dirs))]
+pub async fn get_llm_config(app_dirs: State<'_, AppDirs>) -> Result<LlmConfigDto> {
+    let config = AppConfig::load(&app_dirs.config)?;
+    let provider = config
+        .system
+        .default_llm_provider()
+        .cloned()
+        .unwrap_or_else(|| LlmProvider {
+            kind: LlmProviderKind::OpenAi,
+            base_url: LlmProviderKind::OpenAi.default_base_url().to_string(),
+            ..Default::default()
+        });
+    let has_api_key = !provider.id.is_empty()
+        && provider
+            .clone()
+            .with_stored_api_key()
+            .api_key
+            .is_some_and(|k| !k.trim().is_empty());
+
+    Ok(LlmConfigDto {
+        provider: provider.kind,
+        api_key: None,
+        has_api_key,
+        api_url: provider.base_url,
+        model: provider.model_name,
+        max_tokens: provider.max_tokens,
+        temperature: provider.temperature,
+    })
+}
+
+/// Save the default LLM provider's settings, creating the provider if none
+/// exists. The API key goes to the OS keychain, never into settings.json.
+#[tauri::command]
+#[instrument(skip(app_dirs, config), fields(provider = ?config.provider, model = %config.model))]
+pub async fn save_llm_config(app_dirs: State<'_, AppDirs>, config: LlmConfigDto) -> Result<()> {
+    let model = config.model.trim();
+    if model.is_empty() {
+        return Err(AppError::validation("model", "Model is required"));
+    }
+    let api_url = match config.api_url.trim().trim_end_matches('/') {
+        "" => config.provider.default_base_url(),
+        url => url,
+    };
+    if api_url.is_empty() {
+        return Err(AppError::validation(
+            "api_url",
+            "API URL is required for OpenAI-compatible providers",
+        ));
+    }
+    if config.max_tokens == 0 {
+        return Err(AppError::validation("max_tokens", "Must be at least 1"));
+    }
+    if !(0.0..=2.0).contains(&config.temperature) {
+        return Err(AppError::validation(
+            "temperature",
+            "Must be between 0 and 2",
+        ));
+    }
+
+    let mut app_config = AppConfig::load(&app_dirs.config)?;
+    let providers = &mut app_config.system.llm_providers;
+    let index = match providers
+        .iter()
+        .position(|p| p.is_default)
+        .or_else(|| (!providers.is_empty()).then_some(0))
+    {
+        Some(index) => index,
+        None => {
+            providers.push(LlmProvider {
+                id: format!("llm-{}", chrono::Utc::now().timestamp_millis()),
+                name: match config.provider {
+                    LlmProviderKind::OpenAi => "OpenAI",
+                    LlmProviderKind::Anthropic => "Anthropic",
+                    LlmProviderKind::Ollama => "Ollama",
+                    LlmProviderKind::OpenAiCompatible => "OpenAI-compatible",
+                }
+                .to_string(),
+                ..Default::default()
+            });
+            providers.len() - 1
+        }
+    };
+    for (i, provider) in providers.iter_mut().enumerate() {
+        provider.is_default = i == index;
+    }
+
+    let provider = &mut providers[index];
+    match config.api_key.as_deref().map(str::trim) {
+        Some("") => secrets::delete_secret(&provider.keychain_entry())?,
+        Some(key) => secrets::set_secret(&provider.keychain_entry(), key)?,
+        None => {
+            // Move a plain-text key from an older version into the keychain
+            if let Some(key) = provider.api_key.as_deref().map(str::trim) {
+                if !key.is_empty() {
+                    secrets::set_secret(&provider.keychain_entry(), key)?;
+                }
+            }
+        }
+    }
+    provider.api_key = None;
+    provider.kind = config.provider;
+    provider.base_url = api_url.to_string();
+    provider.model_name = model.to_string();
+    provider.max_tokens = config.max_tokens;
+    provider.temperature = config.temperature;
+
+    info!("Saved LLM config for provider {}", provider.id);
+    app_config.save(&app_dirs.config)
+}
+
+/// Send a short probe to the LLM provider with `provider_id`, or to the
+/// default one. A failing provider is a normal result, not an error.
+#[tauri::command]
+#[instrument(skip(app_dirs))]
+pub async fn test_llm_connection(
+    app_dirs: State<'_, AppDirs>,
+    provider_id: Option<String>,
+) -> Result<LlmConnectionTestDto> {
+    let config = AppConfig::load(&app_dirs.config)?;
+    let provider = match provider_id.as_deref() {
+        Some(id) => config
+            .system
+            .llm_providers
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AppError::not_found("LlmProvider", id))?,
+        None => config.system.default_llm_provider().ok_or_else(|| {
+            AppError::config_error("system.llm_providers", "No LLM provider is configured")
+        })?,
+    };
+    let mut provider = provider.clone().with_stored_api_key();
+    provider.max_tokens = PROBE_MAX_TOKENS;
+
+    let started = Instant::now();
+    let result = LlmClient::new()
+        .chat(&provider, "You are a connection test.", "Reply with OK.")
+        .await;
+    let response_ms = started.elapsed().as_millis() as u64;
+
+    let (success, message) = match result {
+        Ok(reply) => (true, reply.trim().to_string()),
+        Err(e) => {
+            warn!("LLM connection test failed: {}", e);
+            (false, e.to_string())
+        }
+    };
+    info!(
+        "LLM connection test of {} succeeded: {} ({} ms)",
+        provider.model_name, success, response_ms
+    );
+
+    Ok(LlmConnectionTestDto {
+        success,
+        model: provider.model_name,
+        response_ms,
+        message,
+    })
+}
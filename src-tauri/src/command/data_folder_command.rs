@@ -4,25 +4,33 @@
 //! - Getting current data folder information
 //! - Validating new data folder paths
 //! - Migrating data to a new location
+//! - Rolling back a finished migration before restarting
 //! - Restarting the application
 //! - Clearing all data (dev mode only)
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use sea_orm::{DatabaseConnection, EntityTrait};
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, State};
 use tracing::{error, info};
 
 use crate::database::entities::{
-    attachment, label, paper, paper_author, paper_category, paper_keyword, paper_label,
+    attachment, label, paper, paper_author, paper_category, paper_citation, paper_keyword,
+    paper_label,
+};
+use crate::service::attachment_service::{largest_paper_dirs, PaperStorageDto};
+use crate::service::backup_service::DATABASE_FILE;
+use crate::service::data_migration_service::{
+    ChecksumSample, DataMigrationService, DataMigrationState, MigrationState,
+    DEFAULT_CHECKSUM_SAMPLE,
 };
-use crate::service::data_migration_service::DataMigrationService;
 use crate::sys::{
     dirs::{
-        calculate_data_size, get_data_folder_info, get_default_data_path, save_data_path_config,
-        validate_data_folder, DataFolderInfo, DataPathConfig, ValidationResult, AppDirs,
+        calculate_data_size, calculate_dir_size, get_data_folder_info, get_default_data_path,
+        load_data_path_config, save_data_path_config, validate_data_folder, AppDirs,
+        DataFolderInfo, DataPathConfig, ValidationResult,
     },
     error::{AppError, Result},
 };
@@ -36,6 +44,20 @@ pub struct ClearDataResult {
     pub errors: Vec<String>,
 }
 
+/// Size of each data subdirectory, plus the papers using the most space
+#[derive(Debug, Serialize, Clone)]
+pub struct DiskUsageBreakdownDto {
+    pub data_db_bytes: u64,
+    pub files_bytes: u64,
+    pub cache_bytes: u64,
+    pub config_bytes: u64,
+    pub logs_bytes: u64,
+    pub largest_papers: Vec<PaperStorageDto>,
+}
+
+/// Number of papers listed in `DiskUsageBreakdownDto::largest_papers`
+const LARGEST_PAPERS_LIMIT: usize = 20;
+
 /// Get current data folder information
 #[tauri::command]
 pub async fn get_data_folder_info_command(app_dirs: State<'_, AppDirs>) -> Result<DataFolderInfo> {
@@ -43,6 +65,26 @@ pub async fn get_data_folder_info_command(app_dirs: State<'_, AppDirs>) -> Resul
     get_data_folder_info(&app_dirs)
 }
 
+/// Break the data folder size down by subdirectory and list the papers
+/// whose attachments take the most space
+#[tauri::command]
+pub async fn get_disk_usage_breakdown(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<DiskUsageBreakdownDto> {
+    info!("Calculating disk usage breakdown");
+
+    let size_of = |dir: &str| calculate_dir_size(&PathBuf::from(dir));
+    Ok(DiskUsageBreakdownDto {
+        data_db_bytes: size_of(&app_dirs.data)?,
+        files_bytes: size_of(&app_dirs.files)?,
+        cache_bytes: size_of(&app_dirs.cache)?,
+        config_bytes: size_of(&app_dirs.config)?,
+        logs_bytes: size_of(&app_dirs.logs)?,
+        largest_papers: largest_paper_dirs(&db, &app_dirs.files, LARGEST_PAPERS_LIMIT).await?,
+    })
+}
+
 /// Get the default system data folder path
 #[tauri::command]
 pub async fn get_default_data_folder() -> Result<String> {
@@ -50,6 +92,26 @@ pub async fn get_default_data_folder() -> Result<String> {
     get_default_data_path()
 }
 
+/// Space a migration to `new_base` needs: what is not already there from an
+/// earlier, interrupted migration, plus a 10% buffer
+fn required_space(app_dirs: &AppDirs, new_base: &Path) -> Result<u64> {
+    let to_copy = DataMigrationService::new(current_base(app_dirs)?, new_base.to_path_buf())
+        .bytes_to_copy()
+        .or_else(|_| calculate_data_size(app_dirs))
+        .unwrap_or(0);
+    Ok(to_copy + to_copy / 10)
+}
+
+/// Base directory of the current data folder (parent of the XuanBrain folder)
+fn current_base(app_dirs: &AppDirs) -> Result<PathBuf> {
+    // app_dirs.data is {base}/XuanBrain/data, so we need parent twice to get {base}
+    Ok(PathBuf::from(&app_dirs.data)
+        .parent()
+        .and_then(|p| p.parent())
+        .ok_or_else(|| AppError::migration_error("migrate", "Invalid current data path"))?
+        .to_path_buf())
+}
+
 /// Validate a potential new data folder path
 #[tauri::command]
 pub async fn validate_data_folder_command(
@@ -58,35 +120,37 @@ pub async fn validate_data_folder_command(
 ) -> Result<ValidationResult> {
     info!("Validating data folder path: {}", path);
 
-    // Calculate required space (current data size + 10% buffer)
-    let current_size = calculate_data_size(&app_dirs).unwrap_or(0);
-    let required_space = current_size + (current_size / 10);
-
+    let required_space = required_space(&app_dirs, &PathBuf::from(&path))?;
     validate_data_folder(&path, required_space)
 }
 
 /// Migrate data to a new folder
+///
+/// The database is checkpointed and closed before it is copied, so the app
+/// has to be restarted afterwards even when the migration fails. A copied
+/// database that fails its integrity check, or copied files that still
+/// differ from their source after being copied again, roll the migration
+/// back; the reason is in the `error` of the `Failed` status. Database
+/// files and a sample of the other files are compared by SHA-256, or every
+/// file with `verify_all_files`.
+///
+/// When copying fails or `cancel_migration` stops it, the files copied so
+/// far are kept and migrating to the same folder again skips them.
 #[tauri::command]
 pub async fn migrate_data_folder_command(
     app: AppHandle,
     new_path: String,
     app_dirs: State<'_, AppDirs>,
+    state: State<'_, DataMigrationState>,
+    verify_all_files: Option<bool>,
 ) -> Result<()> {
     info!("Starting data migration to: {}", new_path);
 
-    // Get current base directory (parent of XuanBrain folder)
-    // app_dirs.data is {base}/XuanBrain/data, so we need parent twice to get {base}
-    let current_base = PathBuf::from(&app_dirs.data)
-        .parent()
-        .and_then(|p| p.parent())
-        .ok_or_else(|| AppError::migration_error("migrate", "Invalid current data path"))?
-        .to_path_buf();
-
+    let current_base = current_base(&app_dirs)?;
     let new_base = PathBuf::from(&new_path);
 
     // Validate the new path
-    let current_size = calculate_data_size(&app_dirs).unwrap_or(0);
-    let required_space = current_size + (current_size / 10);
+    let required_space = required_space(&app_dirs, &new_base)?;
     let validation = validate_data_folder(&new_path, required_space)?;
 
     if !validation.valid {
@@ -96,28 +160,139 @@ pub async fn migrate_data_folder_command(
         ));
     }
 
+    // Snapshot the current layout so a finished migration can be rolled back
+    MigrationState::new(&current_base, &new_base, &app_dirs).save(&app_dirs.config)?;
+
     // Create migration service
-    let migration_service = DataMigrationService::new(current_base, new_base);
+    let checksum_sample = if verify_all_files.unwrap_or(false) {
+        ChecksumSample::All
+    } else {
+        ChecksumSample::Count(DEFAULT_CHECKSUM_SAMPLE)
+    };
+    let migration_service =
+        DataMigrationService::new(current_base, new_base).with_checksum_sample(checksum_sample);
 
     // Execute migration
-    match migration_service.migrate(&app).await {
+    let cancel = state.start()?;
+    let result = migration_service.migrate(&app, &cancel).await;
+    state.finish();
+
+    match result {
         Ok(_) => {
             info!("Data migration completed successfully");
             Ok(())
         }
         Err(e) => {
+            // The data path config still points at the source; the partial
+            // copy is kept for the next attempt
             error!("Data migration failed: {}", e);
-
-            // Attempt rollback
-            if let Err(rollback_err) = migration_service.rollback(&app) {
-                error!("Rollback also failed: {}", rollback_err);
-            }
-
+            MigrationState::clear(&app_dirs.config);
             Err(e)
         }
     }
 }
 
+/// Stop a running data folder migration before its next file. The files
+/// copied so far are kept, so migrating to the same folder again resumes.
+#[tauri::command]
+pub async fn cancel_migration(state: State<'_, DataMigrationState>) -> Result<()> {
+    if state.cancel() {
+        info!("Cancelling data migration");
+        Ok(())
+    } else {
+        Err(AppError::not_found("Data migration", "running"))
+    }
+}
+
+/// Undo the last data folder migration, returning to `source_path`.
+///
+/// Only possible until the app restarts: the snapshot taken by
+/// `migrate_data_folder_command` must still be there, the app must still be
+/// running from the source folder, and the source database must not have
+/// been cleaned up yet. The migrated copy is removed and the data path
+/// config points back at the source.
+#[tauri::command]
+pub async fn rollback_data_migration(
+    app: AppHandle,
+    source_path: String,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<()> {
+    info!("Rolling back data migration to: {}", source_path);
+
+    let state = MigrationState::load(&app_dirs.config)?.ok_or_else(|| {
+        AppError::migration_error("rollback", "No data folder migration to roll back")
+    })?;
+
+    let source_base = PathBuf::from(&state.source_base);
+    let source_dir = DataMigrationService::get_xuanbrain_dir(&source_base);
+    if DataMigrationService::get_xuanbrain_dir(&PathBuf::from(&source_path)) != source_dir {
+        return Err(AppError::migration_error(
+            "rollback",
+            format!("The last migration started from {}", state.source_base),
+        ));
+    }
+    if app_dirs.data != state.app_dirs.data {
+        return Err(AppError::migration_error(
+            "rollback",
+            "The app has already restarted into the new data folder",
+        ));
+    }
+    if !source_dir.join("data").join(DATABASE_FILE).exists() {
+        return Err(AppError::migration_error(
+            "rollback",
+            format!(
+                "Source database no longer exists in {}",
+                source_dir.display()
+            ),
+        ));
+    }
+
+    // The migrated copy must be the one the config points at, and must have
+    // been written by this migration
+    let dest_base = load_data_path_config()?
+        .custom_data_path
+        .map(PathBuf::from)
+        .ok_or_else(|| {
+            AppError::migration_error("rollback", "No custom data folder is configured")
+        })?;
+    let dest_dir = DataMigrationService::get_xuanbrain_dir(&dest_base);
+    if dest_dir == source_dir
+        || dest_dir != DataMigrationService::get_xuanbrain_dir(&PathBuf::from(&state.dest_base))
+    {
+        return Err(AppError::migration_error(
+            "rollback",
+            "The configured data folder is not the migration destination",
+        ));
+    }
+    let dest_db = dest_dir.join("data").join(DATABASE_FILE);
+    let copied_at = std::fs::metadata(&dest_db)
+        .and_then(|m| m.modified())
+        .map(chrono::DateTime::<chrono::Utc>::from)
+        .map_err(|e| {
+            AppError::migration_error(
+                "rollback",
+                format!(
+                    "Migrated database not found at {}: {}",
+                    dest_db.display(),
+                    e
+                ),
+            )
+        })?;
+    if copied_at < state.started_at {
+        return Err(AppError::migration_error(
+            "rollback",
+            "Migrated database is older than the migration",
+        ));
+    }
+
+    DataMigrationService::new(source_base, dest_base).rollback(&app)?;
+    MigrationState::clear(&app_dirs.config);
+
+    let _ = app.emit("migration-rollback-complete", &source_path);
+    info!("Data migration rolled back");
+    Ok(())
+}
+
 /// Revert to default data folder
 #[tauri::command]
 pub async fn revert_to_default_data_folder_command(
@@ -186,7 +361,8 @@ pub async fn restart_app(app: AppHandle) -> Result<()> {
 /// - All labels
 /// - All attachments
 /// - All files in the files directory
-/// - All relations (paper-author, paper-label, paper-keyword, paper-category)
+/// - All relations (paper-author, paper-label, paper-keyword, paper-category,
+///   paper-citation)
 ///
 /// Category data is preserved.
 #[tauri::command]
@@ -228,7 +404,13 @@ pub async fn clear_all_data_command(
         Err(e) => result.errors.push(format!("Failed to delete paper_categories: {}", e)),
     }
 
-    // 5. Delete all attachments
+    // 5. Delete all paper_citation relations
+    match paper_citation::Entity::delete_many().exec(db.as_ref()).await {
+        Ok(_) => info!("Deleted all paper_citation relations"),
+        Err(e) => result.errors.push(format!("Failed to delete paper_citations: {}", e)),
+    }
+
+    // 6. Delete all attachments
     match attachment::Entity::delete_many().exec(db.as_ref()).await {
         Ok(r) => {
             let deleted = r.rows_affected;
@@ -237,7 +419,7 @@ pub async fn clear_all_data_command(
         Err(e) => result.errors.push(format!("Failed to delete attachments: {}", e)),
     }
 
-    // 6. Delete all papers (including soft-deleted)
+    // 7. Delete all papers (including soft-deleted)
     match paper::Entity::delete_many().exec(db.as_ref()).await {
         Ok(r) => {
             result.papers_deleted = r.rows_affected;
@@ -246,7 +428,7 @@ pub async fn clear_all_data_command(
         Err(e) => result.errors.push(format!("Failed to delete papers: {}", e)),
     }
 
-    // 7. Delete all labels
+    // 8. Delete all labels
     match label::Entity::delete_many().exec(db.as_ref()).await {
         Ok(r) => {
             result.labels_deleted = r.rows_affected;
@@ -255,7 +437,7 @@ pub async fn clear_all_data_command(
         Err(e) => result.errors.push(format!("Failed to delete labels: {}", e)),
     }
 
-    // 8. Clear files directory
+    // 9. Clear files directory
     let files_path = PathBuf::from(&app_dirs.files);
     if files_path.exists() {
         match clear_directory_contents(&files_path) {
@@ -0,0 +1,29 @@
+//! Tauri commands for in-progress attachment downloads
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::service::download_service::{DownloadProgress, DownloadRegistry};
+use crate::sys::error::{AppError, Result};
+
+/// List running downloads with their progress, oldest first
+#[tauri::command]
+#[instrument(skip(registry))]
+pub async fn list_active_downloads(
+    registry: State<'_, DownloadRegistry>,
+) -> Result<Vec<DownloadProgress>> {
+    Ok(registry.list())
+}
+
+/// Cancel a running download and discard what it has received so far
+#[tauri::command]
+#[instrument(skip(registry))]
+pub async fn cancel_download(registry: State<'_, DownloadRegistry>, id: String) -> Result<()> {
+    info!("Cancelling download {}", id);
+
+    if registry.cancel(&id) {
+        Ok(())
+    } else {
+        Err(AppError::not_found("Download", id))
+    }
+}
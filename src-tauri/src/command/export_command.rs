@@ -0,0 +1,409 @@
+//! Bibliography export commands
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::papers::exporter::csl::{citation_key, paper_to_csl};
+use crate::papers::exporter::markdown::{Template, DEFAULT_TEMPLATE};
+use crate::repository::{AuthorRepository, PaperRepository};
+use crate::service::citation_service::{self, CitationFormat};
+use crate::service::export_service;
+use crate::service::library_archive_service::ensure_outside_library;
+use crate::service::library_export_service::{collect_library, write_library_zip};
+use crate::service::markdown_export_service::{self, CollisionStrategy, MarkdownWrite};
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Which papers to export
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportScope {
+    /// Every paper in the library (excluding the trash)
+    All,
+    /// Papers in one category
+    Category(String),
+    /// An explicit selection of paper IDs
+    Papers(Vec<String>),
+}
+
+#[derive(Serialize)]
+pub struct ExportWarningDto {
+    pub paper_id: String,
+    pub title: String,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct CslExportResultDto {
+    pub path: String,
+    pub exported: usize,
+    /// Items written with at least one warning
+    pub with_warnings: usize,
+    pub warnings: Vec<ExportWarningDto>,
+}
+
+#[derive(Serialize)]
+pub struct ExportResultDto {
+    pub path: String,
+    pub exported: usize,
+}
+
+/// Result of `export_library_as_zip`. Named apart from `ExportResultDto`,
+/// which the CSV export already returns.
+#[derive(Serialize)]
+pub struct LibraryExportResultDto {
+    pub path: String,
+    pub size_bytes: u64,
+    pub paper_count: usize,
+    /// Attachment files included in the zip
+    pub attachment_count: usize,
+}
+
+#[derive(Serialize)]
+pub struct MarkdownExportDto {
+    pub path: String,
+    /// False when the file already existed and was skipped
+    pub written: bool,
+}
+
+#[derive(Serialize)]
+pub struct MarkdownExportResultDto {
+    pub total: usize,
+    pub written: usize,
+    /// Papers whose file already existed (with the `skip` strategy)
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<String>,
+}
+
+/// Progress event DTO for Markdown export
+#[derive(Clone, Serialize)]
+pub struct MarkdownExportProgress {
+    pub current: usize,
+    pub total: usize,
+    pub title: String,
+}
+
+fn parse_id(field: &str, id: &str) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| AppError::validation(field, "Invalid id format"))
+}
+
+fn parse_template(template: Option<&str>) -> Result<Template> {
+    Template::parse(template.unwrap_or(DEFAULT_TEMPLATE))
+        .map_err(|e| AppError::validation("template", e))
+}
+
+/// Create the export folder if needed
+fn prepare_target_dir(target_dir: &str) -> Result<PathBuf> {
+    let dir = PathBuf::from(target_dir);
+    if dir.exists() && !dir.is_dir() {
+        return Err(AppError::validation(
+            "target_dir",
+            "Export path is not a directory",
+        ));
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| {
+        AppError::file_system(
+            target_dir.to_string(),
+            format!("Failed to create folder: {}", e),
+        )
+    })?;
+    Ok(dir)
+}
+
+async fn papers_in_scope(db: &DatabaseConnection, scope: &ExportScope) -> Result<Vec<Paper>> {
+    match scope {
+        ExportScope::All => PaperRepository::find_all(db).await,
+        ExportScope::Category(category_id) => {
+            PaperRepository::find_by_category(db, parse_id("category_id", category_id)?).await
+        }
+        ExportScope::Papers(ids) => {
+            let mut papers = Vec::with_capacity(ids.len());
+            for id in ids {
+                let paper = PaperRepository::find_by_id(db, parse_id("paper_id", id)?)
+                    .await?
+                    .ok_or_else(|| AppError::not_found("Paper", id.clone()))?;
+                papers.push(paper);
+            }
+            Ok(papers)
+        }
+    }
+}
+
+/// Export papers as a CSL-JSON bibliography that Pandoc and Quarto can use
+/// directly (`--bibliography refs.json`)
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn export_csl_json(
+    db: State<'_, Arc<DatabaseConnection>>,
+    scope: ExportScope,
+    path: String,
+) -> Result<CslExportResultDto> {
+    info!("Exporting CSL-JSON ({:?}) to {}", scope, path);
+
+    let target = PathBuf::from(&path);
+    if target.is_dir() {
+        return Err(AppError::validation("path", "Export path is a directory"));
+    }
+
+    let papers = papers_in_scope(&db, &scope).await?;
+    let paper_ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let authors_map = AuthorRepository::get_paper_authors_batch(&db, &paper_ids).await?;
+
+    let mut used_keys = HashSet::new();
+    let mut items = Vec::with_capacity(papers.len());
+    let mut warnings = Vec::new();
+    let mut with_warnings = 0;
+
+    for paper in &papers {
+        let authors = authors_map.get(&paper.id).cloned().unwrap_or_default();
+        let key = citation_key(paper, &authors, &mut used_keys);
+        let (item, item_warnings) = paper_to_csl(paper, &authors, key);
+
+        if !item_warnings.is_empty() {
+            with_warnings += 1;
+        }
+        warnings.extend(item_warnings.into_iter().map(|message| ExportWarningDto {
+            paper_id: paper.id.to_string(),
+            title: paper.title.clone(),
+            message,
+        }));
+        items.push(item);
+    }
+
+    let json = serde_json::to_string_pretty(&items)
+        .map_err(|e| AppError::generic(format!("Failed to serialize CSL-JSON: {}", e)))?;
+    std::fs::write(&target, json).map_err(|e| {
+        AppError::file_system(path.clone(), format!("Failed to write CSL-JSON: {}", e))
+    })?;
+
+    info!(
+        "Exported {} items to {} ({} with warnings)",
+        items.len(),
+        path,
+        with_warnings
+    );
+
+    Ok(CslExportResultDto {
+        path,
+        exported: items.len(),
+        with_warnings,
+        warnings,
+    })
+}
+
+/// Export papers as CSV for analysis in a spreadsheet or R. Exports the whole
+/// library (excluding the trash) when `paper_ids` is omitted.
+#[tauri::command]
+#[instrument(skip(db, paper_ids))]
+pub async fn export_papers_csv(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_ids: Option<Vec<String>>,
+    output_path: String,
+) -> Result<ExportResultDto> {
+    info!("Exporting papers as CSV to {}", output_path);
+
+    let target = PathBuf::from(&output_path);
+    if tokio::fs::metadata(&target)
+        .await
+        .is_ok_and(|meta| meta.is_dir())
+    {
+        return Err(AppError::validation(
+            "output_path",
+            "Export path is a directory",
+        ));
+    }
+
+    let scope = match paper_ids {
+        Some(ids) => ExportScope::Papers(ids),
+        None => ExportScope::All,
+    };
+    let papers = papers_in_scope(&db, &scope).await?;
+    let exported = export_service::export_papers_csv(&db, &papers, &target).await?;
+
+    Ok(ExportResultDto {
+        path: output_path,
+        exported,
+    })
+}
+
+/// Format one paper as a reference in the given citation style, from the
+/// metadata already in the library
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn generate_citation_string(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    format: CitationFormat,
+) -> Result<String> {
+    let id_num = parse_id("paper_id", &paper_id)?;
+    let paper = PaperRepository::find_by_id(&db, id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let authors = AuthorRepository::get_paper_authors(&db, id_num).await?;
+
+    Ok(citation_service::format_citation(&paper, &authors, format))
+}
+
+/// Export one paper as a Markdown file with YAML front matter, its notes and
+/// PDF highlights. `template` replaces the built-in layout; see
+/// `papers::exporter::markdown` for the placeholders it can use.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, template))]
+pub async fn export_paper_to_markdown(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    target_dir: String,
+    template: Option<String>,
+    on_conflict: Option<CollisionStrategy>,
+) -> Result<MarkdownExportDto> {
+    let template = parse_template(template.as_deref())?;
+    let paper = PaperRepository::find_by_id(&db, parse_id("paper_id", &paper_id)?)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let dir = prepare_target_dir(&target_dir)?;
+
+    let written = markdown_export_service::export_paper(
+        &db,
+        &app_dirs.files,
+        &paper,
+        &dir,
+        &template,
+        on_conflict.unwrap_or_default(),
+        &mut HashSet::new(),
+    )
+    .await?;
+
+    Ok(match written {
+        MarkdownWrite::Written(path) => MarkdownExportDto {
+            path: path.to_string_lossy().to_string(),
+            written: true,
+        },
+        MarkdownWrite::Skipped(path) => MarkdownExportDto {
+            path: path.to_string_lossy().to_string(),
+            written: false,
+        },
+    })
+}
+
+/// Export every paper in the library (excluding the trash) as Markdown, one
+/// file per paper. A paper that fails is reported and does not stop the
+/// export.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs, template))]
+pub async fn export_all_to_markdown(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    target_dir: String,
+    template: Option<String>,
+    on_conflict: Option<CollisionStrategy>,
+) -> Result<MarkdownExportResultDto> {
+    info!("Exporting library as Markdown to {}", target_dir);
+
+    let template = parse_template(template.as_deref())?;
+    let dir = prepare_target_dir(&target_dir)?;
+    let strategy = on_conflict.unwrap_or_default();
+    let papers = papers_in_scope(&db, &ExportScope::All).await?;
+
+    let mut result = MarkdownExportResultDto {
+        total: papers.len(),
+        written: 0,
+        skipped: 0,
+        failed: 0,
+        errors: Vec::new(),
+    };
+    let mut claimed = HashSet::new();
+
+    for (index, paper) in papers.iter().enumerate() {
+        let _ = app.emit(
+            "markdown-export:progress",
+            MarkdownExportProgress {
+                current: index + 1,
+                total: papers.len(),
+                title: paper.title.clone(),
+            },
+        );
+
+        match markdown_export_service::export_paper(
+            &db,
+            &app_dirs.files,
+            paper,
+            &dir,
+            &template,
+            strategy,
+            &mut claimed,
+        )
+        .await
+        {
+            Ok(MarkdownWrite::Written(_)) => result.written += 1,
+            Ok(MarkdownWrite::Skipped(_)) => result.skipped += 1,
+            Err(e) => {
+                result.failed += 1;
+                result
+                    .errors
+                    .push(format!("Failed to export '{}': {}", paper.title, e));
+            }
+        }
+    }
+
+    info!(
+        "Markdown export finished: {} written, {} skipped, {} failed",
+        result.written, result.skipped, result.failed
+    );
+    Ok(result)
+}
+
+/// Export the library as a portable zip with `library.json`, `bibtex.bib`
+/// and, with `include_pdfs`, the attachment files under `files/`. Progress is
+/// emitted as `export-progress`.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn export_library_as_zip(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    output_path: String,
+    include_pdfs: bool,
+) -> Result<LibraryExportResultDto> {
+    info!("Exporting library as zip to {}", output_path);
+
+    let target = PathBuf::from(&output_path);
+    if target.is_dir() {
+        return Err(AppError::validation(
+            "output_path",
+            "Export path is a directory",
+        ));
+    }
+    ensure_outside_library(&target, &app_dirs)?;
+
+    let files_dir = PathBuf::from(&app_dirs.files);
+    let content = collect_library(&db, include_pdfs.then_some(files_dir.as_path())).await?;
+    let paper_count = content.library.papers.len();
+    let attachment_count = content.attachments.len();
+
+    let zip_target = target.clone();
+    let size_bytes = tokio::task::spawn_blocking(move || {
+        write_library_zip(&content, &zip_target, |progress| {
+            let _ = app.emit("export-progress", progress);
+        })
+    })
+    .await
+    .map_err(|e| AppError::generic(format!("Library export task failed: {}", e)))??;
+
+    Ok(LibraryExportResultDto {
+        path: output_path,
+        size_bytes,
+        paper_count,
+        attachment_count,
+    })
+}
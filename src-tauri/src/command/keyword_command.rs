@@ -0,0 +1,280 @@
+//! Tauri commands for paper keywords
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::command::paper::{papers_to_list_dtos, PaginatedPapersDto};
+use crate::database::DatabaseConnection;
+use crate::models::Keyword;
+use crate::repository::{KeywordRepository, PaperRepository};
+use crate::service::keyword_service::extract_and_store_keywords;
+use crate::sys::error::{AppError, Result};
+
+/// Upper bound for `max_keywords`
+const MAX_EXTRACTED_KEYWORDS: u32 = 50;
+
+/// Maximum number of keywords returned by `search_keywords`
+const KEYWORD_SEARCH_LIMIT: usize = 20;
+
+/// Largest page accepted by `get_papers_by_keyword`
+const MAX_PAGE_SIZE: u32 = 200;
+
+#[derive(Serialize)]
+pub struct KeywordDto {
+    pub id: String,
+    pub word: String,
+    /// Number of papers (not in the trash) using the keyword
+    pub paper_count: u64,
+}
+
+fn parse_keyword_id(id: &str) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| AppError::validation("keyword_id", "Invalid keyword id format"))
+}
+
+fn parse_paper_id(id: &str) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))
+}
+
+/// Attach usage counts and sort by usage, most used first
+fn with_usage(keywords: Vec<Keyword>, counts: &HashMap<i64, u64>) -> Vec<KeywordDto> {
+    let mut dtos: Vec<KeywordDto> = keywords
+        .into_iter()
+        .map(|k| KeywordDto {
+            id: k.id.to_string(),
+            paper_count: counts.get(&k.id).copied().unwrap_or(0),
+            word: k.word,
+        })
+        .collect();
+    dtos.sort_by(|a, b| {
+        b.paper_count
+            .cmp(&a.paper_count)
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    dtos
+}
+
+/// Extract the top keywords from a paper's abstract by TF-IDF against the
+/// rest of the library and link them to the paper
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn extract_keywords_from_abstract(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    max_keywords: u32,
+) -> Result<Vec<String>> {
+    info!("Extracting keywords for paper {}", paper_id);
+
+    if max_keywords == 0 || max_keywords > MAX_EXTRACTED_KEYWORDS {
+        return Err(AppError::validation(
+            "max_keywords",
+            format!("Must be between 1 and {}", MAX_EXTRACTED_KEYWORDS),
+        ));
+    }
+
+    let paper_id_num = parse_paper_id(&paper_id)?;
+
+    extract_and_store_keywords(&db, paper_id_num, max_keywords as usize).await
+}
+
+/// Get all keywords, most used first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_all_keywords(db: State<'_, Arc<DatabaseConnection>>) -> Result<Vec<KeywordDto>> {
+    let keywords = KeywordRepository::find_all(&db).await?;
+    let counts = KeywordRepository::count_papers(&db).await?;
+    Ok(with_usage(keywords, &counts))
+}
+
+/// Get the keywords of a paper, most used first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_keywords(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Vec<KeywordDto>> {
+    let keywords = KeywordRepository::get_paper_keywords(&db, parse_paper_id(&paper_id)?).await?;
+    let counts = KeywordRepository::count_papers(&db).await?;
+    Ok(with_usage(keywords, &counts))
+}
+
+/// Add a keyword to a paper. An existing keyword with the same word in
+/// any case is reused.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn add_paper_keyword(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    word: String,
+) -> Result<KeywordDto> {
+    let word = word.trim();
+    if word.is_empty() {
+        return Err(AppError::validation("word", "Keyword cannot be empty"));
+    }
+
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    if PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::not_found("Paper", paper_id));
+    }
+
+    info!("Adding keyword '{}' to paper {}", word, paper_id);
+    let keyword = KeywordRepository::create_or_find(&db, word).await?;
+    KeywordRepository::add_to_paper(&db, paper_id_num, keyword.id).await?;
+    let counts = KeywordRepository::count_papers(&db).await?;
+
+    Ok(KeywordDto {
+        id: keyword.id.to_string(),
+        paper_count: counts.get(&keyword.id).copied().unwrap_or(0),
+        word: keyword.word,
+    })
+}
+
+/// Remove a keyword from a paper. The keyword itself is kept.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn remove_paper_keyword(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    keyword_id: String,
+) -> Result<()> {
+    info!("Removing keyword {} from paper {}", keyword_id, paper_id);
+    let removed = KeywordRepository::remove_from_paper(
+        &db,
+        parse_paper_id(&paper_id)?,
+        parse_keyword_id(&keyword_id)?,
+    )
+    .await?;
+    if !removed {
+        return Err(AppError::not_found(
+            "Paper keyword",
+            format!("{}/{}", paper_id, keyword_id),
+        ));
+    }
+    Ok(())
+}
+
+/// Keywords containing `query`, for autocomplete. Keywords starting with
+/// the query come first, then the most used.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_keywords(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+) -> Result<Vec<KeywordDto>> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keywords = KeywordRepository::search(&db, &query).await?;
+    let counts = KeywordRepository::count_papers(&db).await?;
+
+    let prefix = query.trim().to_lowercase();
+    let mut dtos = with_usage(keywords, &counts);
+    dtos.sort_by_key(|k| !k.word.to_lowercase().starts_with(&prefix));
+    dtos.truncate(KEYWORD_SEARCH_LIMIT);
+
+    info!("Found {} keywords for '{}'", dtos.len(), query);
+    Ok(dtos)
+}
+
+/// Get one page of the papers using a keyword, newest first. `page` starts
+/// at 1.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_by_keyword(
+    db: State<'_, Arc<DatabaseConnection>>,
+    keyword_id: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedPapersDto> {
+    if page == 0 {
+        return Err(AppError::validation("page", "Page numbers start at 1"));
+    }
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(AppError::validation(
+            "page_size",
+            format!("Must be between 1 and {}", MAX_PAGE_SIZE),
+        ));
+    }
+
+    let keyword_id_num = parse_keyword_id(&keyword_id)?;
+    if KeywordRepository::find_by_id(&db, keyword_id_num)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::not_found("Keyword", keyword_id));
+    }
+
+    let offset = (page as u64 - 1) * page_size as u64;
+    let limit = page_size as u64;
+    let (papers, total) =
+        PaperRepository::find_by_keyword_paginated(&db, keyword_id_num, offset, limit).await?;
+    let papers = papers_to_list_dtos(&db, papers).await?;
+
+    Ok(PaginatedPapersDto {
+        has_more: offset + (papers.len() as u64) < total as u64,
+        papers,
+        total,
+        offset,
+        limit,
+    })
+}
+
+/// Delete a keyword and remove it from all papers
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn delete_keyword(
+    db: State<'_, Arc<DatabaseConnection>>,
+    keyword_id: String,
+) -> Result<()> {
+    info!("Deleting keyword {}", keyword_id);
+    KeywordRepository::delete(&db, parse_keyword_id(&keyword_id)?).await
+}
+
+/// Merge synonyms into one keyword: papers of the secondary keywords are
+/// moved to the primary keyword and the secondary keywords are deleted
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn merge_keywords(
+    db: State<'_, Arc<DatabaseConnection>>,
+    primary_id: String,
+    secondary_ids: Vec<String>,
+) -> Result<KeywordDto> {
+    info!(
+        "Merging {} keywords into {}",
+        secondary_ids.len(),
+        primary_id
+    );
+
+    let primary_id_num = parse_keyword_id(&primary_id)?;
+    let mut secondary_id_nums = Vec::with_capacity(secondary_ids.len());
+    for id in &secondary_ids {
+        let id = parse_keyword_id(id)?;
+        if id == primary_id_num {
+            return Err(AppError::validation(
+                "secondary_ids",
+                "A keyword cannot be merged into itself",
+            ));
+        }
+        if !secondary_id_nums.contains(&id) {
+            secondary_id_nums.push(id);
+        }
+    }
+
+    let keyword = KeywordRepository::merge(&db, primary_id_num, &secondary_id_nums).await?;
+    let counts = KeywordRepository::count_papers(&db).await?;
+
+    Ok(KeywordDto {
+        id: keyword.id.to_string(),
+        paper_count: counts.get(&keyword.id).copied().unwrap_or(0),
+        word: keyword.word,
+    })
+}
@@ -1,20 +1,82 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::Serialize;
 use tauri::{AppHandle, State};
 use tracing::{info, instrument};
 
+use crate::command::paper::{papers_to_list_dtos, PaperListDto};
 use crate::database::DatabaseConnection;
-use crate::models::{CreateLabel, UpdateLabel};
-use crate::repository::LabelRepository;
-use crate::sys::error::Result;
+use crate::models::{CreateLabel, Label, LabelNode, UpdateLabel};
+use crate::repository::{LabelRepository, PaperRepository};
+use crate::sys::error::{AppError, Result};
 
 #[derive(Serialize)]
 pub struct LabelResponse {
     pub id: String,
     pub name: String,
     pub color: String,
+    /// Papers and clips carrying exactly this label
     pub document_count: i32,
+    pub sort_order: i32,
+    pub parent_id: Option<String>,
+    /// Papers and clips carrying this label or any label below it; only
+    /// filled in when listing labels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recursive_count: Option<u64>,
+}
+
+impl From<Label> for LabelResponse {
+    fn from(label: Label) -> Self {
+        Self {
+            id: label.id.to_string(),
+            name: label.name,
+            color: label.color,
+            document_count: label.document_count,
+            sort_order: label.sort_order,
+            parent_id: label.parent_id.map(|id| id.to_string()),
+            recursive_count: None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LabelMergeResultDto {
+    pub label: LabelResponse,
+    /// Paper and clip links moved to the primary label
+    pub relations_moved: usize,
+    /// Links dropped because the document already carried the primary label
+    pub duplicates_dropped: usize,
+}
+
+#[derive(Serialize)]
+pub struct LabelUsageDto {
+    pub label: LabelResponse,
+    /// Papers outside the trash
+    pub paper_count: u64,
+    pub clip_count: u64,
+    /// Last update of a paper or clip carrying the label
+    pub recently_used_at: Option<String>,
+}
+
+fn parse_label_id(field: &str, id: &str) -> Result<i64> {
+    id.parse::<i64>()
+        .map_err(|_| AppError::validation(field, "Invalid id format"))
+}
+
+/// Parse a parent label id, where an empty string means the top level
+fn parse_parent_id(parent_id: &str) -> Result<Option<i64>> {
+    match parent_id.trim() {
+        "" => Ok(None),
+        id => parse_label_id("parent_id", id).map(Some),
+    }
+}
+
+fn collect_recursive_counts(nodes: &[LabelNode], counts: &mut HashMap<i64, u64>) {
+    for node in nodes {
+        counts.insert(node.id, node.recursive_count);
+        collect_recursive_counts(&node.children, counts);
+    }
 }
 
 #[tauri::command]
@@ -22,14 +84,20 @@ pub struct LabelResponse {
 pub async fn get_all_labels(db: State<'_, Arc<DatabaseConnection>>) -> Result<Vec<LabelResponse>> {
     info!("Fetching all labels");
     let labels = LabelRepository::find_all(&db).await?;
+    let mut recursive_counts = HashMap::new();
+    collect_recursive_counts(
+        &LabelRepository::load_tree(&db).await?,
+        &mut recursive_counts,
+    );
 
     let result: Vec<LabelResponse> = labels
         .into_iter()
-        .map(|l| LabelResponse {
-            id: l.id.to_string(),
-            name: l.name,
-            color: l.color,
-            document_count: l.document_count,
+        .map(|label| {
+            let recursive_count = recursive_counts.get(&label.id).copied();
+            LabelResponse {
+                recursive_count,
+                ..LabelResponse::from(label)
+            }
         })
         .collect();
 
@@ -37,6 +105,36 @@ pub async fn get_all_labels(db: State<'_, Arc<DatabaseConnection>>) -> Result<Ve
     Ok(result)
 }
 
+/// Labels as a tree, with the labels grouped under each one as children
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_label_tree(db: State<'_, Arc<DatabaseConnection>>) -> Result<Vec<LabelNode>> {
+    LabelRepository::load_tree(&db).await
+}
+
+/// Papers carrying the label or any label below it
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_by_label(
+    db: State<'_, Arc<DatabaseConnection>>,
+    label_id: String,
+) -> Result<Vec<PaperListDto>> {
+    let label_id = parse_label_id("label_id", &label_id)?;
+    if LabelRepository::find_by_id(&db, label_id).await?.is_none() {
+        return Err(AppError::not_found("Label", label_id.to_string()));
+    }
+
+    let label_ids = LabelRepository::descendant_ids(&db, label_id).await?;
+    let papers = PaperRepository::find_by_labels(&db, &label_ids).await?;
+    info!(
+        "Found {} papers under label {} and {} sub-labels",
+        papers.len(),
+        label_id,
+        label_ids.len() - 1
+    );
+    papers_to_list_dtos(&db, papers).await
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn create_label(
@@ -44,17 +142,26 @@ pub async fn create_label(
     db: State<'_, Arc<DatabaseConnection>>,
     name: String,
     color: String,
+    parent_id: Option<String>,
 ) -> Result<LabelResponse> {
     info!("Creating label '{}' with color '{}'", name, color);
-    let label = LabelRepository::create(&db, CreateLabel { name: name.clone(), color }).await?;
+    let parent_id = parent_id
+        .as_deref()
+        .map(parse_parent_id)
+        .transpose()?
+        .flatten();
+    let label = LabelRepository::create(
+        &db,
+        CreateLabel {
+            name: name.clone(),
+            color,
+            parent_id,
+        },
+    )
+    .await?;
 
     info!("Label created successfully");
-    Ok(LabelResponse {
-        id: label.id.to_string(),
-        name: label.name,
-        color: label.color,
-        document_count: label.document_count,
-    })
+    Ok(LabelResponse::from(label))
 }
 
 #[tauri::command]
@@ -65,6 +172,7 @@ pub async fn update_label(
     id: String,
     name: Option<String>,
     color: Option<String>,
+    parent_id: Option<String>,
 ) -> Result<LabelResponse> {
     info!("Updating label id {}", id);
 
@@ -72,16 +180,21 @@ pub async fn update_label(
         .parse::<i64>()
         .map_err(|_| crate::sys::error::AppError::validation("id", "Invalid id format"))?;
 
-    let updated_label =
-        LabelRepository::update(&db, id_num, UpdateLabel { name, color }).await?;
+    let parent_id = parent_id.as_deref().map(parse_parent_id).transpose()?;
+
+    let updated_label = LabelRepository::update(
+        &db,
+        id_num,
+        UpdateLabel {
+            name,
+            color,
+            parent_id,
+        },
+    )
+    .await?;
 
     info!("Label updated successfully");
-    Ok(LabelResponse {
-        id: updated_label.id.to_string(),
-        name: updated_label.name,
-        color: updated_label.color,
-        document_count: updated_label.document_count,
-    })
+    Ok(LabelResponse::from(updated_label))
 }
 
 #[tauri::command]
@@ -101,3 +214,105 @@ pub async fn delete_label(
 
     Ok(())
 }
+
+/// Move every paper and clip of the secondary labels to the primary label
+/// and delete the secondaries
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn merge_labels(
+    db: State<'_, Arc<DatabaseConnection>>,
+    primary_id: String,
+    secondary_ids: Vec<String>,
+) -> Result<LabelMergeResultDto> {
+    info!(
+        "Merging {} labels into label {}",
+        secondary_ids.len(),
+        primary_id
+    );
+
+    let primary_id = parse_label_id("primary_id", &primary_id)?;
+    let secondary_ids = secondary_ids
+        .iter()
+        .map(|id| parse_label_id("secondary_ids", id))
+        .collect::<Result<Vec<_>>>()?;
+    if secondary_ids.is_empty() {
+        return Err(AppError::validation("secondary_ids", "No labels to merge"));
+    }
+
+    let merged = LabelRepository::merge(&db, primary_id, &secondary_ids).await?;
+    Ok(LabelMergeResultDto {
+        label: LabelResponse::from(merged.label),
+        relations_moved: merged.relations_moved,
+        duplicates_dropped: merged.duplicates_dropped,
+    })
+}
+
+/// Labels no paper or clip carries, in the user's label order
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn find_unused_labels(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<LabelResponse>> {
+    let labels = LabelRepository::find_unused(&db).await?;
+    info!("Found {} unused labels", labels.len());
+    Ok(labels.into_iter().map(LabelResponse::from).collect())
+}
+
+/// Delete several labels at once and return how many were deleted
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn delete_labels(
+    db: State<'_, Arc<DatabaseConnection>>,
+    ids: Vec<String>,
+) -> Result<u64> {
+    let ids = ids
+        .iter()
+        .map(|id| parse_label_id("ids", id))
+        .collect::<Result<Vec<_>>>()?;
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    LabelRepository::delete_many(&db, &ids).await
+}
+
+/// Store the user's label order. Labels missing from the list keep their
+/// relative order after the listed ones.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn reorder_labels(
+    db: State<'_, Arc<DatabaseConnection>>,
+    label_ids_in_order: Vec<String>,
+) -> Result<()> {
+    let ids = label_ids_in_order
+        .iter()
+        .map(|id| parse_label_id("label_ids_in_order", id))
+        .collect::<Result<Vec<_>>>()?;
+
+    LabelRepository::reorder(&db, &ids).await
+}
+
+/// Paper and clip counts of every label and when it was last used, in the
+/// user's label order
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_label_usage_stats(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<LabelUsageDto>> {
+    let labels = LabelRepository::find_all(&db).await?;
+    let mut stats = LabelRepository::usage_stats(&db).await?;
+
+    Ok(labels
+        .into_iter()
+        .map(|label| {
+            let (paper_count, clip_count, recently_used_at) =
+                stats.remove(&label.id).unwrap_or((0, 0, None));
+            LabelUsageDto {
+                label: LabelResponse::from(label),
+                paper_count,
+                clip_count,
+                recently_used_at: recently_used_at.map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,79 @@
+//! LLM commands that work on a paper's stored metadata
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::entities::paper_ai_summary;
+use crate::database::DatabaseConnection;
+use crate::repository::{AiSummaryRepository, PaperRepository};
+use crate::service::summary_service::{self, ABSTRACT_STYLE_PREFIX};
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct SummaryDto {
+    pub paper_id: String,
+    pub summary: String,
+    pub model_used: String,
+    pub generated_at: String,
+}
+
+impl From<paper_ai_summary::Model> for SummaryDto {
+    fn from(summary: paper_ai_summary::Model) -> Self {
+        Self {
+            paper_id: summary.paper_id.to_string(),
+            summary: summary.summary,
+            model_used: summary.model,
+            generated_at: summary.created_at.to_rfc3339(),
+        }
+    }
+}
+
+fn parse_paper_id(paper_id: &str) -> Result<i64> {
+    paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))
+}
+
+/// Summarize a paper's abstract in at most `max_sentences` sentences with
+/// the configured LLM provider. Repeated calls return the cached summary
+/// until the paper is edited.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn summarize_paper_abstract(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    max_sentences: u32,
+) -> Result<SummaryDto> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    let summary =
+        summary_service::summarize_abstract(&db, &app_dirs.config, paper_id_num, max_sentences)
+            .await?;
+
+    info!("Abstract summary of paper {} ready", paper_id);
+    Ok(summary.into())
+}
+
+/// The newest cached abstract summary of a paper, whatever its sentence
+/// limit, or `None` when there is none or the paper was edited since
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_cached_summary(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Option<SummaryDto>> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+
+    let cached =
+        AiSummaryRepository::find_latest_with_prefix(&db, paper_id_num, ABSTRACT_STYLE_PREFIX)
+            .await?
+            .filter(|s| s.created_at >= paper.updated_at);
+    Ok(cached.map(SummaryDto::from))
+}
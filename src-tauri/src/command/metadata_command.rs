@@ -0,0 +1,282 @@
+//! Tauri commands for refreshing and enriching paper metadata from
+//! Crossref and citation counts from OpenAlex
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, instrument};
+
+use crate::database::entities::open_access_cache;
+use crate::database::DatabaseConnection;
+use crate::papers::importer::crossref::fetch_crossref_metadata;
+use crate::papers::importer::doi::fetch_doi_metadata;
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::repository::PaperRepository;
+use crate::service::citation_count_service::{self, CitationRefreshState};
+use crate::service::metadata_refresh_service::{
+    self, MetadataRefreshReport, MetadataRefreshResult, MetadataRefreshState,
+};
+use crate::service::open_access_service;
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Re-fetch one paper's metadata by DOI. Citation count is always updated;
+/// pages, volume, issue and abstract only when they are blank.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn refresh_paper_metadata(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<MetadataRefreshResult> {
+    let id = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+    let paper = PaperRepository::find_by_id(&db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let doi = paper
+        .doi
+        .clone()
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| AppError::validation("doi", "Paper has no DOI"))?;
+
+    info!("Refreshing metadata for paper {} ({})", id, doi);
+    let metadata = fetch_doi_metadata(&doi)
+        .await
+        .map_err(|e| AppError::network_error(&doi, e.to_string()))?;
+    let updated_fields = metadata_refresh_service::apply_metadata(&db, &paper, &metadata).await?;
+
+    Ok(MetadataRefreshResult {
+        paper_id,
+        updated_fields,
+    })
+}
+
+/// Back-fill a paper's blank journal or conference, publisher and ISSN from
+/// the Crossref works API and link its Crossref subjects as keywords
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn enrich_paper_from_crossref(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<()> {
+    let id = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+    let paper = PaperRepository::find_by_id(&db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let doi = paper
+        .doi
+        .clone()
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| AppError::validation("doi", "Paper has no DOI"))?;
+
+    info!("Enriching paper {} from Crossref ({})", id, doi);
+    let work = fetch_crossref_metadata(&doi)
+        .await
+        .map_err(|e| AppError::network_error(&doi, e.to_string()))?;
+    let updated_fields = metadata_refresh_service::apply_crossref_work(&db, &paper, &work).await?;
+    info!(
+        "Crossref enrichment of paper {} updated: {:?}",
+        id, updated_fields
+    );
+
+    Ok(())
+}
+
+/// Refresh every paper that has a DOI, about one request per second.
+///
+/// Emits `metadata-refresh:progress` before each paper. Failures are listed
+/// in the report instead of stopping the run.
+#[tauri::command]
+#[instrument(skip(app, db, state))]
+pub async fn refresh_all_metadata(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    state: State<'_, MetadataRefreshState>,
+) -> Result<MetadataRefreshReport> {
+    let cancel = state.start()?;
+
+    let report = metadata_refresh_service::refresh_all(
+        &db,
+        &RateLimiter::default(),
+        &cancel,
+        |doi| async move { fetch_doi_metadata(&doi).await },
+        |progress| {
+            let _ = app.emit("metadata-refresh:progress", progress);
+        },
+    )
+    .await;
+
+    state.finish();
+    report
+}
+
+/// Stop a running `refresh_all_metadata` before its next paper
+#[tauri::command]
+#[instrument(skip(state))]
+pub async fn cancel_metadata_refresh(state: State<'_, MetadataRefreshState>) -> Result<()> {
+    if state.cancel() {
+        info!("Cancelling metadata refresh");
+        Ok(())
+    } else {
+        Err(AppError::not_found("Metadata refresh", "running"))
+    }
+}
+
+#[derive(Serialize)]
+pub struct RefreshJobDto {
+    pub job_id: String,
+    pub paper_count: u32,
+}
+
+/// Refresh citation counts from OpenAlex in the background. With
+/// `paper_ids`, those papers are refreshed regardless of when they were
+/// last checked; without, every paper whose count is older than
+/// `citation_refresh.interval_hours`.
+///
+/// Emits `citation-refresh-progress` after each batch of 50 and
+/// `citation-refresh-complete` at the end, both carrying the job id.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs, state))]
+pub async fn trigger_citation_count_refresh(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    state: State<'_, CitationRefreshState>,
+    paper_ids: Option<Vec<String>>,
+) -> Result<RefreshJobDto> {
+    let paper_ids = paper_ids
+        .map(|ids| {
+            ids.iter()
+                .map(|id| id.parse::<i64>())
+                .collect::<std::result::Result<Vec<i64>, _>>()
+        })
+        .transpose()
+        .map_err(|_| AppError::validation("paper_ids", "Invalid paper id format"))?;
+    let config = AppConfig::load(&app_dirs.config)?;
+
+    state.start()?;
+    let papers =
+        match citation_count_service::find_due_papers(&db, &config, paper_ids.as_deref()).await {
+            Ok(papers) => papers,
+            Err(e) => {
+                state.finish();
+                return Err(e);
+            }
+        };
+
+    let job = RefreshJobDto {
+        job_id: citation_count_service::new_job_id(),
+        paper_count: papers.len() as u32,
+    };
+    info!(
+        "Starting citation refresh {} for {} papers",
+        job.job_id, job.paper_count
+    );
+
+    let db = db.inner().clone();
+    let state = state.inner().clone();
+    let job_id = job.job_id.clone();
+    tauri::async_runtime::spawn(async move {
+        citation_count_service::run_refresh_job(&app, &db, &state, &job_id, papers).await;
+    });
+
+    Ok(job)
+}
+
+#[derive(Serialize)]
+pub struct OpenAccessInfoDto {
+    pub paper_id: String,
+    pub doi: String,
+    pub is_oa: bool,
+    /// `gold`, `green`, `hybrid`, `bronze` or `closed`
+    pub oa_status: String,
+    pub oa_url: Option<String>,
+    pub license: Option<String>,
+    /// When Unpaywall was last asked, RFC 3339
+    pub updated_at: String,
+}
+
+impl From<open_access_cache::Model> for OpenAccessInfoDto {
+    fn from(entry: open_access_cache::Model) -> Self {
+        Self {
+            paper_id: entry.paper_id.to_string(),
+            doi: entry.doi,
+            is_oa: entry.is_oa,
+            oa_status: entry.oa_status,
+            oa_url: entry.oa_url,
+            license: entry.license,
+            updated_at: entry.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Open access status of a paper from Unpaywall. Lookups are cached for
+/// seven days.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn get_paper_open_access_info(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+) -> Result<OpenAccessInfoDto> {
+    let id = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+    let paper = PaperRepository::find_by_id(&db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let doi = paper
+        .doi
+        .filter(|d| !d.trim().is_empty())
+        .ok_or_else(|| AppError::validation("doi", "Paper has no DOI"))?;
+
+    let email = open_access_service::unpaywall_email(&AppConfig::load(&app_dirs.config)?.paper)?;
+    let client = open_access_service::unpaywall_client(&email)?;
+    let entry = open_access_service::open_access_info(&db, id, &doi, |doi| {
+        let client = client.clone();
+        let email = email.clone();
+        async move { open_access_service::fetch_unpaywall(&client, &email, &doi).await }
+    })
+    .await?;
+
+    Ok(entry.into())
+}
+
+/// Open access status of every paper that has a DOI. Cached lookups younger
+/// than seven days are reused; only the others are sent to Unpaywall, about
+/// one per second.
+///
+/// Emits `open-access:progress` before each lookup.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn get_oa_status_for_all_papers(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<Vec<OpenAccessInfoDto>> {
+    let email = open_access_service::unpaywall_email(&AppConfig::load(&app_dirs.config)?.paper)?;
+    let papers = open_access_service::find_papers_with_doi(&db).await?;
+    let client = open_access_service::unpaywall_client(&email)?;
+
+    let entries = open_access_service::open_access_for_papers(
+        &db,
+        &papers,
+        &RateLimiter::default(),
+        |doi| {
+            let client = client.clone();
+            let email = email.clone();
+            async move { open_access_service::fetch_unpaywall(&client, &email, &doi).await }
+        },
+        |progress| {
+            let _ = app.emit("open-access:progress", progress);
+        },
+    )
+    .await?;
+
+    Ok(entries.into_iter().map(OpenAccessInfoDto::from).collect())
+}
@@ -1,7 +1,23 @@
+pub mod activity_command;
+pub mod admin_command;
+pub mod api_key_command;
+pub mod api_server_command;
+pub mod author_command;
+pub mod backup_command;
 pub mod category_command;
 pub mod clip_command;
 pub mod config_command;
 pub mod data_folder_command;
+pub mod download_command;
+pub mod export_command;
+pub mod keyword_command;
 pub mod label_command;
+pub mod llm_command;
+pub mod metadata_command;
 pub mod paper;
+pub mod quiet_hours_command;
+pub mod reading_progress_command;
 pub mod search_command;
+pub mod share_command;
+pub mod shortcut_command;
+pub mod stats_command;
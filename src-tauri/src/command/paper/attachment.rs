@@ -1,21 +1,39 @@
 //! Attachment operations for papers
 
-use std::path::PathBuf;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 use tauri_plugin_opener::OpenerExt;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
+use crate::axum::state::ApiServerState;
 use crate::database::DatabaseConnection;
 use crate::models::Attachment;
-use crate::repository::PaperRepository;
+use crate::repository::{PaperRepository, TextContentRepository};
+use crate::service::attachment_service::{
+    self, backfill_attachment_size, file_size_on_disk, find_pdf_path, storage_stats,
+    validate_attachments, AttachmentPathReport, AttachmentValidationReport, MAX_BLOB_SIZE_BYTES,
+};
+use crate::service::download_service::{download_client, download_resumable, DownloadRegistry};
+use crate::service::ocr_service::{
+    embed_text_layer, extract_text, DEFAULT_OCRMYPDF_BINARY, TEXT_SOURCE_LAYER, TEXT_SOURCE_OCR,
+};
+use crate::service::thumbnail_service::{
+    is_fresh, png_dimensions, render_thumbnail, thumbnail_path, DEFAULT_PDFTOPPM_BINARY,
+    MAX_THUMBNAIL_SIZE,
+};
+use crate::sys::config::AppConfig;
 use crate::sys::dirs::AppDirs;
 use crate::sys::error::{AppError, Result};
 
 use super::dtos::*;
-use super::utils::{base64_decode, base64_encode, calculate_attachment_hash};
+use super::utils::{base64_decode, base64_encode_reader, calculate_attachment_hash};
 use chrono::Utc;
 
+/// Number of attachments listed in `StorageStatsDto::largest_attachments`
+const LARGEST_ATTACHMENTS_LIMIT: usize = 20;
+
 #[tauri::command]
 #[instrument(skip(db, app_dirs))]
 pub async fn add_attachment(
@@ -62,7 +80,7 @@ pub async fn add_attachment(
     let file_type = source_path
         .extension()
         .map(|s| s.to_string_lossy().to_string());
-    let file_size = std::fs::metadata(&target_path).ok().map(|m| m.len() as i64);
+    let file_size = file_size_on_disk(&target_path);
 
     let attachment = Attachment {
         id: 0, // Will be auto-generated
@@ -80,10 +98,135 @@ pub async fn add_attachment(
         paper_id: paper_id.clone(),
         file_name: Some(file_name),
         file_type,
+        file_size,
         created_at: Some(Utc::now().to_rfc3339()),
     })
 }
 
+/// Download a file from `url` and attach it to a paper. `file_name`
+/// defaults to the decoded last segment of the URL path.
+///
+/// An interrupted download resumes where it stopped the next time the same
+/// URL is downloaded to the same file. A file already on disk is never
+/// overwritten: the download gets a numbered name instead. An attachment
+/// row whose file is missing is reused rather than duplicated.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, downloads))]
+pub async fn download_attachment_from_url(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    downloads: State<'_, DownloadRegistry>,
+    paper_id: String,
+    url: String,
+    file_name: Option<String>,
+) -> Result<AttachmentDto> {
+    info!("Downloading attachment for paper {} from {}", paper_id, url);
+
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+
+    let parsed_url = reqwest::Url::parse(&url)
+        .ok()
+        .filter(|u| matches!(u.scheme(), "http" | "https"))
+        .ok_or_else(|| AppError::validation("url", "URL must be an http(s) URL"))?;
+
+    let file_name = file_name
+        .or_else(|| {
+            parsed_url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .map(|s| {
+                    urlencoding::decode(s)
+                        .map(|decoded| decoded.into_owned())
+                        .unwrap_or_else(|_| s.to_string())
+                })
+        })
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| AppError::validation("file_name", "Could not determine a file name"))?;
+    if Path::new(&file_name).file_name() != Some(std::ffi::OsStr::new(&file_name)) {
+        return Err(AppError::validation("file_name", "Invalid file name"));
+    }
+
+    let hash_string = paper
+        .attachment_path
+        .clone()
+        .unwrap_or_else(|| calculate_attachment_hash(&paper.title));
+
+    let target_dir = PathBuf::from(&app_dirs.files).join(&hash_string);
+    if !target_dir.exists() {
+        std::fs::create_dir_all(&target_dir).map_err(|e| {
+            AppError::file_system(target_dir.to_string_lossy().to_string(), e.to_string())
+        })?;
+    }
+    let attachments = PaperRepository::get_attachments(&db, paper_id_num).await?;
+    let file_name = free_file_name(&target_dir, &file_name);
+    let target_path = target_dir.join(&file_name);
+
+    let client = download_client()?;
+    let size = download_resumable(&client, &downloads, &url, &target_path).await?;
+
+    let file_type = Path::new(&file_name)
+        .extension()
+        .map(|s| s.to_string_lossy().to_lowercase());
+
+    let attachment = match attachments
+        .into_iter()
+        .find(|a| a.file_name.as_deref() == Some(file_name.as_str()))
+    {
+        Some(existing) => {
+            PaperRepository::update_attachment_size(&db, existing.id, size as i64).await?;
+            existing
+        }
+        None => {
+            PaperRepository::add_attachment(
+                &db,
+                paper_id_num,
+                Some(file_name.clone()),
+                file_type.clone(),
+                Some(size as i64),
+            )
+            .await?
+        }
+    };
+
+    Ok(AttachmentDto {
+        id: attachment.id.to_string(),
+        paper_id,
+        file_name: Some(file_name),
+        file_type,
+        file_size: Some(size as i64),
+        created_at: Some(attachment.created_at.to_rfc3339()),
+    })
+}
+
+/// `file_name`, or `name (2).ext`, `name (3).ext`, ... when a file with that
+/// name is already in `dir`
+fn free_file_name(dir: &Path, file_name: &str) -> String {
+    if !dir.join(file_name).exists() {
+        return file_name.to_string();
+    }
+
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (2..)
+        .map(|n| format!("{} ({}){}", stem, n, extension))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap_or_else(|| file_name.to_string())
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn get_attachments(
@@ -105,6 +248,7 @@ pub async fn get_attachments(
             paper_id: a.paper_id.to_string(),
             file_name: a.file_name.clone(),
             file_type: a.file_type.clone(),
+            file_size: a.file_size,
             created_at: Some(a.created_at.to_rfc3339()),
         })
         .collect())
@@ -228,11 +372,12 @@ pub async fn read_pdf_file(app_dirs: State<'_, AppDirs>, file_path: String) -> R
 }
 
 #[tauri::command]
-#[instrument(skip(db, app_dirs))]
+#[instrument(skip(db, app_dirs, server_state))]
 pub async fn read_pdf_as_blob(
     paper_id: String,
     db: State<'_, Arc<DatabaseConnection>>,
     app_dirs: State<'_, AppDirs>,
+    server_state: State<'_, ApiServerState>,
 ) -> Result<PdfBlobResponse> {
     info!("Reading PDF as blob for paper {}", paper_id);
 
@@ -272,15 +417,34 @@ pub async fn read_pdf_as_blob(
         ));
     }
 
-    let pdf_bytes = std::fs::read(&pdf_path).map_err(|e| {
+    let read_error = |e: std::io::Error| {
         AppError::file_system(
             pdf_path.to_string_lossy().to_string(),
             format!("Failed to read PDF file: {}", e),
         )
-    })?;
+    };
 
-    let size_bytes = pdf_bytes.len();
-    let base64_data = base64_encode(&pdf_bytes);
+    let mut file = std::fs::File::open(&pdf_path).map_err(read_error)?;
+    let size_bytes = file.metadata().map_err(read_error)?.len() as usize;
+
+    if size_bytes as u64 > MAX_BLOB_SIZE_BYTES {
+        let hint = match server_state.base_url() {
+            Some(base_url) => format!(
+                "load it from {}/api/papers/{}/pdf instead",
+                base_url, paper_id
+            ),
+            None => "enable the local API server to stream it instead".to_string(),
+        };
+        return Err(AppError::validation(
+            "paper_id",
+            format!(
+                "PDF is {} bytes, larger than the {} byte blob limit; {}",
+                size_bytes, MAX_BLOB_SIZE_BYTES, hint
+            ),
+        ));
+    }
+    let base64_data = base64_encode_reader(&mut BufReader::new(&mut file), Some(size_bytes as u64))
+        .map_err(read_error)?;
 
     info!(
         "Successfully read PDF as blob for paper {}: {} bytes",
@@ -306,70 +470,11 @@ pub async fn save_pdf_blob(
     app_dirs: State<'_, AppDirs>,
 ) -> Result<PdfSaveResponse> {
     info!("Saving PDF blob for paper {}", paper_id);
-
-    let paper_id_num = paper_id
-        .parse::<i64>()
-        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
-
-    let paper = PaperRepository::find_by_id(&db, paper_id_num)
-        .await?
-        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
-
-    let hash_string = paper
-        .attachment_path
-        .clone()
-        .unwrap_or_else(|| calculate_attachment_hash(&paper.title));
-
-    let attachment = PaperRepository::find_pdf_attachment(&db, paper_id_num)
-        .await?
-        .ok_or_else(|| AppError::not_found("PDF attachment", format!("paper_id={}", paper_id)))?;
-
-    let file_name = attachment.file_name.clone().unwrap_or_else(|| {
-        format!(
-            "{}.pdf",
-            paper
-                .title
-                .replace(|c: char| !c.is_alphanumeric() && c != ' ', "_")
-        )
-    });
-
-    let pdf_bytes = base64_decode(&base64_data).map_err(|e| {
-        AppError::validation("base64_data", format!("Failed to decode base64: {}", e))
-    })?;
-
-    let size_bytes = pdf_bytes.len();
-
-    let files_dir = PathBuf::from(&app_dirs.files);
-    let pdf_path = files_dir.join(&hash_string).join(&file_name);
-
-    if let Some(parent) = pdf_path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| {
-            AppError::file_system(parent.to_string_lossy().to_string(), e.to_string())
-        })?;
-    }
-
-    std::fs::write(&pdf_path, &pdf_bytes).map_err(|e| {
-        AppError::file_system(pdf_path.to_string_lossy().to_string(), e.to_string())
-    })?;
-
-    info!(
-        "Successfully saved PDF blob for paper {}: {} bytes",
-        paper_id, size_bytes
-    );
-
-    Ok(PdfSaveResponse {
-        success: true,
-        file_path: pdf_path.to_string_lossy().to_string(),
-        size_bytes,
-        message: format!(
-            "PDF saved successfully: {} ({} bytes)",
-            file_name, size_bytes
-        ),
-    })
+    save_pdf(&db, &app_dirs, &paper_id, &base64_data, None).await
 }
 
 #[tauri::command]
-#[instrument(skip(db, app_dirs, base64_data))]
+#[instrument(skip(db, app_dirs, base64_data, annotations_json))]
 pub async fn save_pdf_with_annotations(
     _app: AppHandle,
     paper_id: String,
@@ -379,21 +484,38 @@ pub async fn save_pdf_with_annotations(
     app_dirs: State<'_, AppDirs>,
 ) -> Result<PdfSaveResponse> {
     info!("Saving PDF blob with annotations for paper {}", paper_id);
+    save_pdf(
+        &db,
+        &app_dirs,
+        &paper_id,
+        &base64_data,
+        annotations_json.as_deref(),
+    )
+    .await
+}
 
+/// Shared implementation behind `save_pdf_blob` and `save_pdf_with_annotations`
+async fn save_pdf(
+    db: &DatabaseConnection,
+    app_dirs: &AppDirs,
+    paper_id: &str,
+    base64_data: &str,
+    annotations_json: Option<&str>,
+) -> Result<PdfSaveResponse> {
     let paper_id_num = paper_id
         .parse::<i64>()
         .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
 
-    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+    let paper = PaperRepository::find_by_id(db, paper_id_num)
         .await?
-        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
 
     let hash_string = paper
         .attachment_path
         .clone()
         .unwrap_or_else(|| calculate_attachment_hash(&paper.title));
 
-    let attachment = PaperRepository::find_pdf_attachment(&db, paper_id_num)
+    let attachment = PaperRepository::find_pdf_attachment(db, paper_id_num)
         .await?
         .ok_or_else(|| AppError::not_found("PDF attachment", format!("paper_id={}", paper_id)))?;
 
@@ -406,53 +528,71 @@ pub async fn save_pdf_with_annotations(
         )
     });
 
-    let pdf_bytes = base64_decode(&base64_data).map_err(|e| {
+    let pdf_bytes = base64_decode(base64_data).map_err(|e| {
         AppError::validation("base64_data", format!("Failed to decode base64: {}", e))
     })?;
 
-    let size_bytes = pdf_bytes.len();
+    let pdf_path = PathBuf::from(&app_dirs.files)
+        .join(&hash_string)
+        .join(&file_name);
 
-    let files_dir = PathBuf::from(&app_dirs.files);
-    let pdf_path = files_dir.join(&hash_string).join(&file_name);
+    let response = write_pdf_with_annotations(&pdf_path, &file_name, &pdf_bytes, annotations_json)?;
+
+    info!(
+        "Successfully saved PDF for paper {}: {} bytes",
+        paper_id, response.size_bytes
+    );
 
+    Ok(response)
+}
+
+/// Write the PDF bytes and, when provided, the annotations sidecar next to it.
+///
+/// Succeeds whether or not annotations are supplied; a missing annotations
+/// payload only means no `.json` sidecar is written.
+fn write_pdf_with_annotations(
+    pdf_path: &Path,
+    file_name: &str,
+    pdf_bytes: &[u8],
+    annotations_json: Option<&str>,
+) -> Result<PdfSaveResponse> {
     if let Some(parent) = pdf_path.parent() {
         std::fs::create_dir_all(parent).map_err(|e| {
             AppError::file_system(parent.to_string_lossy().to_string(), e.to_string())
         })?;
     }
 
-    std::fs::write(&pdf_path, &pdf_bytes).map_err(|e| {
+    std::fs::write(pdf_path, pdf_bytes).map_err(|e| {
         AppError::file_system(pdf_path.to_string_lossy().to_string(), e.to_string())
     })?;
 
-    if let Some(annotations) = annotations_json {
-        let annotations_path = pdf_path.with_extension("json");
-        std::fs::write(&annotations_path, &annotations).map_err(|e| {
-            AppError::file_system(
-                annotations_path.to_string_lossy().to_string(),
-                e.to_string(),
-            )
-        })?;
+    let size_bytes = pdf_bytes.len();
 
-        return Ok(PdfSaveResponse {
-            success: true,
-            file_path: pdf_path.to_string_lossy().to_string(),
-            size_bytes,
-            message: format!(
+    let message = match annotations_json {
+        Some(annotations) => {
+            let annotations_path = pdf_path.with_extension("json");
+            std::fs::write(&annotations_path, annotations).map_err(|e| {
+                AppError::file_system(
+                    annotations_path.to_string_lossy().to_string(),
+                    e.to_string(),
+                )
+            })?;
+            format!(
                 "PDF and annotations saved successfully ({} bytes)",
                 size_bytes
-            ),
-        });
-    }
+            )
+        }
+        None => format!(
+            "PDF saved successfully: {} ({} bytes)",
+            file_name, size_bytes
+        ),
+    };
 
     Ok(PdfSaveResponse {
         success: true,
         file_path: pdf_path.to_string_lossy().to_string(),
         size_bytes,
-        message: format!(
-            "PDF saved successfully: {} ({} bytes)",
-            file_name, size_bytes
-        ),
+        message,
     })
 }
 
@@ -477,3 +617,340 @@ pub async fn delete_attachment(
     );
     Ok(())
 }
+
+/// Check that every attachment file still exists on disk.
+///
+/// With `attempt_repair`, a missing file found elsewhere under the files
+/// directory is moved back to where the database expects it.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn validate_all_attachments(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    attempt_repair: bool,
+) -> Result<AttachmentValidationReport> {
+    info!("Validating attachments (repair: {})", attempt_repair);
+    validate_attachments(&db, &app_dirs.files, attempt_repair).await
+}
+
+/// Rename the attachment directories still named after the hash of their
+/// paper's title to the names new papers get, so title edits and papers
+/// with the same title no longer affect them. Directories shared by papers
+/// with the same title are left alone and reported as conflicts.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn normalize_attachment_paths(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<AttachmentPathReport> {
+    info!("Normalizing attachment directory names");
+    attachment_service::normalize_attachment_paths(&db, &app_dirs.files).await
+}
+
+/// Read an attachment's size from its file and store it, for attachments
+/// recorded before sizes were tracked
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn update_attachment_file_size(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    attachment_id: String,
+) -> Result<()> {
+    let attachment_id_num = attachment_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("attachment_id", "Invalid attachment id format"))?;
+
+    let size = backfill_attachment_size(&db, &app_dirs.files, attachment_id_num).await?;
+    info!("Attachment {} is {} bytes", attachment_id, size);
+    Ok(())
+}
+
+/// Count and size all attachments and list the largest ones. Missing
+/// sizes are filled in from disk first.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn get_library_storage_stats(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+) -> Result<StorageStatsDto> {
+    let stats = storage_stats(&db, &app_dirs.files, LARGEST_ATTACHMENTS_LIMIT).await?;
+
+    Ok(StorageStatsDto {
+        total_attachment_count: stats.total_count,
+        total_attachment_size_bytes: stats.total_size_bytes,
+        largest_attachments: stats
+            .largest
+            .iter()
+            .map(|a| AttachmentDto {
+                id: a.id.to_string(),
+                paper_id: a.paper_id.to_string(),
+                file_name: a.file_name.clone(),
+                file_type: a.file_type.clone(),
+                file_size: a.file_size,
+                created_at: Some(a.created_at.to_rfc3339()),
+            })
+            .collect(),
+        pdf_count: stats.pdf_count,
+        other_count: stats.other_count,
+    })
+}
+
+/// Embed an invisible OCR text layer into the PDFs of scanned papers so
+/// external PDF viewers can search them.
+///
+/// Each PDF is processed with `ocrmypdf` and only replaced after the result
+/// passes verification. The original is kept as `*_original.pdf` unless
+/// `keep_original` is `false`. Failures are reported per paper and leave
+/// that paper's PDF untouched.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn embed_pdf_text_layer(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_ids: Vec<String>,
+    keep_original: Option<bool>,
+) -> Result<BulkOperationResultDto> {
+    info!("Embedding OCR text layer for {} papers", paper_ids.len());
+
+    let binary = AppConfig::load(&app_dirs.config)?
+        .paper
+        .ocrmypdf_path
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_OCRMYPDF_BINARY.to_string());
+    let keep_original = keep_original.unwrap_or(true);
+
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+
+    for paper_id in paper_ids {
+        match embed_text_layer_for_paper(&db, &app_dirs.files, &paper_id, &binary, keep_original)
+            .await
+        {
+            Ok(()) => succeeded.push(paper_id),
+            Err(e) => {
+                warn!("Failed to embed text layer for paper {}: {}", paper_id, e);
+                failed.push((paper_id, e.to_string()));
+            }
+        }
+    }
+
+    info!(
+        "Text layer embedding finished: {} succeeded, {} failed",
+        succeeded.len(),
+        failed.len()
+    );
+
+    Ok(BulkOperationResultDto { succeeded, failed })
+}
+
+async fn embed_text_layer_for_paper(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper_id: &str,
+    binary: &str,
+    keep_original: bool,
+) -> Result<()> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let paper = PaperRepository::find_by_id(db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let attachment = PaperRepository::find_pdf_attachment(db, paper.id)
+        .await?
+        .ok_or_else(|| AppError::not_found("PDF attachment", paper_id.to_string()))?;
+    let pdf_path = find_pdf_path(db, files_dir, &paper)
+        .await?
+        .ok_or_else(|| AppError::not_found("PDF file", paper_id.to_string()))?;
+
+    let size = embed_text_layer(&pdf_path, binary, keep_original).await?;
+    PaperRepository::update_attachment_size(db, attachment.id, size as i64).await?;
+
+    Ok(())
+}
+
+/// Extract the full text of a paper's PDF and store it for full-text
+/// indexing.
+///
+/// PDFs with a text layer are read directly. Image-only scans are run
+/// through OCR: the API in `paper.ocr_api_url` when configured, `ocrmypdf`
+/// otherwise. The PDF file is never modified.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn extract_text_from_scanned_pdf(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+) -> Result<OcrResultDto> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let pdf_path = find_pdf_path(&db, &app_dirs.files, &paper)
+        .await?
+        .ok_or_else(|| AppError::not_found("PDF file", paper_id.clone()))?;
+
+    let config = AppConfig::load(&app_dirs.config)?.paper;
+    let extracted = extract_text(&pdf_path, &config).await?;
+
+    let source = if extracted.has_text_layer {
+        TEXT_SOURCE_LAYER
+    } else {
+        TEXT_SOURCE_OCR
+    };
+    TextContentRepository::save(
+        &db,
+        paper_id_num,
+        &extracted.text,
+        source,
+        extracted.page_count as i32,
+    )
+    .await?;
+    info!(
+        "Stored {} characters of text for paper {} ({})",
+        extracted.text.chars().count(),
+        paper_id_num,
+        source
+    );
+
+    Ok(OcrResultDto {
+        paper_id,
+        page_count: extracted.page_count,
+        extracted_text: extracted.text,
+        has_text_layer: extracted.has_text_layer,
+    })
+}
+
+/// Render the first page of a paper's PDF to a PNG that fits within
+/// `width`×`height` pixels and cache it as `<cache>/<paper_id>_thumb.png`.
+///
+/// Pages are rendered by `pdftoppm` from `paper.pdftoppm_path`, looked up
+/// on PATH when unset. An existing thumbnail is replaced.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn generate_pdf_thumbnail(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    width: u32,
+    height: u32,
+) -> Result<ThumbnailDto> {
+    for (field, size) in [("width", width), ("height", height)] {
+        if size == 0 || size > MAX_THUMBNAIL_SIZE {
+            return Err(AppError::validation(
+                field,
+                format!("Must be between 1 and {}", MAX_THUMBNAIL_SIZE),
+            ));
+        }
+    }
+
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let pdf_path = find_pdf_path(&db, &app_dirs.files, &paper)
+        .await?
+        .ok_or_else(|| AppError::not_found("PDF file", paper_id.clone()))?;
+
+    let binary = AppConfig::load(&app_dirs.config)?
+        .paper
+        .pdftoppm_path
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PDFTOPPM_BINARY.to_string());
+
+    let target = thumbnail_path(&app_dirs.cache, paper_id_num);
+    let (width, height) = render_thumbnail(&binary, &pdf_path, &target, width, height).await?;
+
+    Ok(ThumbnailDto {
+        paper_id,
+        path: target.to_string_lossy().to_string(),
+        width,
+        height,
+    })
+}
+
+/// The cached thumbnail of a paper, or `None` when there is none or the PDF
+/// has changed or been removed since it was rendered. Stale thumbnails are
+/// deleted.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn get_cached_thumbnail(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+) -> Result<Option<ThumbnailDto>> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let target = thumbnail_path(&app_dirs.cache, paper_id_num);
+    if !target.exists() {
+        return Ok(None);
+    }
+
+    let pdf_path = match PaperRepository::find_by_id(&db, paper_id_num).await? {
+        Some(paper) => find_pdf_path(&db, &app_dirs.files, &paper).await?,
+        None => None,
+    };
+    let fresh = pdf_path.is_some_and(|pdf| is_fresh(&target, &pdf));
+    let dimensions = if fresh {
+        png_dimensions(&target).ok()
+    } else {
+        None
+    };
+
+    let Some((width, height)) = dimensions else {
+        info!("Removing stale thumbnail of paper {}", paper_id);
+        if let Err(e) = std::fs::remove_file(&target) {
+            warn!("Failed to remove thumbnail {:?}: {}", target, e);
+        }
+        return Ok(None);
+    };
+
+    Ok(Some(ThumbnailDto {
+        paper_id,
+        path: target.to_string_lossy().to_string(),
+        width,
+        height,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_pdf_without_annotations_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf_path = dir.path().join("abc123").join("paper.pdf");
+
+        let response =
+            write_pdf_with_annotations(&pdf_path, "paper.pdf", b"%PDF-1.7", None).unwrap();
+
+        assert!(response.success);
+        assert_eq!(response.size_bytes, 8);
+        assert_eq!(std::fs::read(&pdf_path).unwrap(), b"%PDF-1.7");
+        assert!(!pdf_path.with_extension("json").exists());
+    }
+
+    #[test]
+    fn test_write_pdf_with_annotations_writes_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf_path = dir.path().join("paper.pdf");
+
+        let response =
+            write_pdf_with_annotations(&pdf_path, "paper.pdf", b"%PDF-1.7", Some("[]")).unwrap();
+
+        assert!(response.success);
+        assert_eq!(
+            std::fs::read_to_string(pdf_path.with_extension("json")).unwrap(),
+            "[]"
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Citation links between papers in the library
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::repository::citation_repository::MAX_CITATION_DEPTH;
+use crate::repository::{CitationRepository, PaperRepository, ReferenceRepository};
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+use super::utils::parse_id;
+
+/// Parse an id and check that the paper exists and is not in the trash
+async fn existing_paper_id(db: &DatabaseConnection, field: &str, id: &str) -> Result<i64> {
+    let id_num = parse_id(id).map_err(|_| AppError::validation(field, "Invalid id format"))?;
+    match PaperRepository::find_by_id(db, id_num).await? {
+        Some(paper) if paper.deleted_at.is_none() => Ok(id_num),
+        _ => Err(AppError::not_found("Paper", id)),
+    }
+}
+
+/// Record that one paper cites another. Linking an existing pair again is a
+/// no-op; a paper citing itself is rejected.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn link_citation(
+    db: State<'_, Arc<DatabaseConnection>>,
+    citing_paper_id: String,
+    cited_paper_id: String,
+) -> Result<()> {
+    let citing = existing_paper_id(&db, "citing_paper_id", &citing_paper_id).await?;
+    let cited = existing_paper_id(&db, "cited_paper_id", &cited_paper_id).await?;
+
+    CitationRepository::link(&db, citing, cited).await?;
+    info!("Paper {} now cites paper {}", citing, cited);
+
+    Ok(())
+}
+
+/// Remove a citation link; removing a link that does not exist is a no-op
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn unlink_citation(
+    db: State<'_, Arc<DatabaseConnection>>,
+    citing_paper_id: String,
+    cited_paper_id: String,
+) -> Result<()> {
+    let citing = parse_id(&citing_paper_id)
+        .map_err(|_| AppError::validation("citing_paper_id", "Invalid id format"))?;
+    let cited = parse_id(&cited_paper_id)
+        .map_err(|_| AppError::validation("cited_paper_id", "Invalid id format"))?;
+
+    if CitationRepository::unlink(&db, citing, cited).await? {
+        info!("Removed citation from paper {} to paper {}", citing, cited);
+    }
+
+    Ok(())
+}
+
+/// Papers up to `depth` citation hops away from a paper, in either
+/// direction, and the links between them. Depth is capped at 3.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_citation_graph(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    depth: Option<u32>,
+) -> Result<CitationGraphDto> {
+    let root = existing_paper_id(&db, "paper_id", &paper_id).await?;
+    let (papers, citations) =
+        CitationRepository::citation_graph(&db, root, depth.unwrap_or(1)).await?;
+
+    Ok(CitationGraphDto {
+        root_id: root.to_string(),
+        nodes: papers
+            .into_iter()
+            .map(|p| CitationNodeDto {
+                id: p.id.to_string(),
+                title: p.title,
+                publication_year: p.publication_year,
+                read_status: p.read_status,
+            })
+            .collect(),
+        edges: citations
+            .into_iter()
+            .map(|c| CitationEdgeDto {
+                source: c.citing_paper_id.to_string(),
+                target: c.cited_paper_id.to_string(),
+            })
+            .collect(),
+    })
+}
+
+/// Relation name of citation edges in a `CitationNetworkDto`
+const CITES: &str = "cites";
+
+/// Hops from `root` to every node reachable over `edges` in either direction
+fn hop_distances(root: i64, edges: &[(i64, i64)]) -> HashMap<i64, u32> {
+    let mut neighbours: HashMap<i64, Vec<i64>> = HashMap::new();
+    for &(from, to) in edges {
+        neighbours.entry(from).or_default().push(to);
+        neighbours.entry(to).or_default().push(from);
+    }
+
+    let mut distances = HashMap::from([(root, 0)]);
+    let mut queue = VecDeque::from([root]);
+    while let Some(id) = queue.pop_front() {
+        let next = distances[&id] + 1;
+        for &other in neighbours.get(&id).map(Vec::as_slice).unwrap_or(&[]) {
+            if !distances.contains_key(&other) {
+                distances.insert(other, next);
+                queue.push_back(other);
+            }
+        }
+    }
+    distances
+}
+
+/// Citation network around a paper for visualization: papers citing it and
+/// cited by it, expanded up to `depth` hops (capped at 3).
+///
+/// Extracted references that match a library paper by DOI are linked first,
+/// so papers imported after their citers' references were extracted show
+/// up. Bibliography entries that are not in the library are included as
+/// leaf nodes of the papers that cite them.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_citation_network(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    depth: u32,
+) -> Result<CitationNetworkDto> {
+    let root = existing_paper_id(&db, "paper_id", &paper_id).await?;
+    let depth = depth.clamp(1, MAX_CITATION_DEPTH);
+
+    let added = CitationRepository::link_extracted_references(&db).await?;
+    if added > 0 {
+        info!("Linked {} extracted references to library papers", added);
+    }
+
+    let (papers, citations) = CitationRepository::citation_graph(&db, root, depth).await?;
+    let pairs: Vec<(i64, i64)> = citations
+        .iter()
+        .map(|c| (c.citing_paper_id, c.cited_paper_id))
+        .collect();
+
+    let mut nodes: Vec<NetworkNodeDto> = papers
+        .iter()
+        .map(|p| NetworkNodeDto {
+            paper_id: p.id.to_string(),
+            title: p.title.clone(),
+            year: p.publication_year,
+            doi: p.doi.clone(),
+            in_library: true,
+        })
+        .collect();
+    let mut edges: Vec<NetworkEdgeDto> = pairs
+        .iter()
+        .map(|(from, to)| NetworkEdgeDto {
+            from: from.to_string(),
+            to: to.to_string(),
+            relation: CITES.to_string(),
+        })
+        .collect();
+
+    // Only papers short of the last hop contribute references outside the library
+    let distances = hop_distances(root, &pairs);
+    let expandable: Vec<i64> = papers
+        .iter()
+        .map(|p| p.id)
+        .filter(|id| distances.get(id).is_some_and(|d| *d < depth))
+        .collect();
+    let references = ReferenceRepository::find_by_papers(&db, &expandable).await?;
+    let dois: Vec<String> = references.iter().filter_map(|r| r.doi.clone()).collect();
+    let library = PaperRepository::find_ids_by_dois(&db, &dois).await?;
+
+    let mut external: HashSet<String> = HashSet::new();
+    let mut seen_edges: HashSet<(i64, String)> = HashSet::new();
+    for reference in references {
+        let doi = reference.doi.as_deref().map(|d| d.trim().to_lowercase());
+        if doi.as_ref().is_some_and(|d| library.contains_key(d)) {
+            continue;
+        }
+        let Some(title) = reference
+            .title
+            .clone()
+            .or_else(|| reference.raw_text.clone())
+            .or_else(|| reference.doi.clone())
+        else {
+            continue;
+        };
+
+        let node_id = match &doi {
+            Some(doi) => format!("doi:{}", doi),
+            None => format!("reference:{}", reference.id),
+        };
+        if external.insert(node_id.clone()) {
+            nodes.push(NetworkNodeDto {
+                paper_id: node_id.clone(),
+                title,
+                year: reference.year,
+                doi: reference.doi.clone(),
+                in_library: false,
+            });
+        }
+        if seen_edges.insert((reference.paper_id, node_id.clone())) {
+            edges.push(NetworkEdgeDto {
+                from: reference.paper_id.to_string(),
+                to: node_id,
+                relation: CITES.to_string(),
+            });
+        }
+    }
+
+    info!(
+        "Citation network of paper {} at depth {}: {} nodes, {} edges",
+        root,
+        depth,
+        nodes.len(),
+        edges.len()
+    );
+    Ok(CitationNetworkDto { nodes, edges })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hop_distances() {
+        // 1 -> 2, 3 -> 2, 3 -> 4, 5 -> 6
+        let distances = hop_distances(1, &[(1, 2), (3, 2), (3, 4), (5, 6)]);
+        assert_eq!(distances[&1], 0);
+        assert_eq!(distances[&2], 1);
+        assert_eq!(distances[&3], 2);
+        assert_eq!(distances[&4], 3);
+        assert!(!distances.contains_key(&5));
+    }
+}
@@ -0,0 +1,83 @@
+//! Custom fields on papers
+
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::models::{CustomField, CustomFieldType};
+use crate::repository::CustomFieldRepository;
+use crate::service::activity_service::{self, ACTION_UPDATED, ENTITY_PAPER};
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+use super::utils::parse_id;
+
+fn to_dto(field: CustomField) -> CustomFieldDto {
+    CustomFieldDto {
+        paper_id: field.paper_id.to_string(),
+        key: field.key,
+        value: field.value,
+        value_type: field.value_type,
+        updated_at: field.updated_at.to_rfc3339(),
+    }
+}
+
+/// Set a custom field on a paper, replacing its earlier value. The value is
+/// checked against `value_type`: numbers must parse, dates are
+/// `YYYY-MM-DD` and booleans are true/false (or yes/no, 1/0). `value_type`
+/// must be the key's type while other papers use the key.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn set_paper_custom_field(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    key: String,
+    value: String,
+    value_type: CustomFieldType,
+) -> Result<CustomFieldDto> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    let field = CustomFieldRepository::set(&db, paper_id_num, &key, &value, value_type).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!("Set custom field '{}' on paper {}", field.key, paper_id);
+    Ok(to_dto(field))
+}
+
+/// Remove a custom field from a paper
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn delete_paper_custom_field(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    key: String,
+) -> Result<()> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    CustomFieldRepository::delete(&db, paper_id_num, &key).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!("Deleted custom field '{}' from paper {}", key, paper_id);
+    Ok(())
+}
+
+/// Custom field keys in use with their type, most used first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn list_custom_field_keys(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<CustomFieldKeyDto>> {
+    let keys = CustomFieldRepository::list_keys(&db).await?;
+    Ok(keys
+        .into_iter()
+        .map(|k| CustomFieldKeyDto {
+            key: k.key,
+            value_type: k.value_type,
+            paper_count: k.paper_count,
+        })
+        .collect())
+}
@@ -1,7 +1,13 @@
 //! Data Transfer Objects for paper commands
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
+use crate::models::{CustomFieldType, NoteFormat};
+use crate::papers::importer::csv_file::CsvRowError;
+use crate::service::category_suggestion_service::CategorySuggestionDto;
+
 /// Batch DTO for streaming papers via Channel - uses lightweight PaperListDto
 #[derive(Clone, Serialize)]
 pub struct PaperBatchDto {
@@ -37,12 +43,20 @@ pub struct LabelDto {
     pub color: String,
 }
 
+#[derive(Clone, Serialize)]
+pub struct PaperCategoryDto {
+    pub id: String,
+    pub name: String,
+}
+
 #[derive(Clone, Serialize)]
 pub struct AttachmentDto {
     pub id: String,
     pub paper_id: String,
     pub file_name: Option<String>,
     pub file_type: Option<String>,
+    /// Size in bytes; absent for attachments recorded before sizes were tracked
+    pub file_size: Option<i64>,
     pub created_at: Option<String>,
 }
 
@@ -55,6 +69,9 @@ pub struct ImportResultDto {
     pub message: String,
     /// The paper data (None if already exists)
     pub paper: Option<PaperDto>,
+    /// Suggested categories for a new paper imported without a category
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub category_suggestions: Vec<CategorySuggestionDto>,
 }
 
 #[derive(Serialize)]
@@ -98,6 +115,8 @@ pub struct PaperDto {
     pub publisher: Option<String>,
     pub issn: Option<String>,
     pub language: Option<String>,
+    pub is_starred: bool,
+    pub starred_at: Option<String>,
 }
 
 /// Lightweight DTO for paper list view - optimized for fast serialization
@@ -114,9 +133,30 @@ pub struct PaperListDto {
     pub author_count: usize,
     pub attachment_count: usize,
     pub attachments: Vec<AttachmentDto>,
+    pub is_starred: bool,
+    pub starred_at: Option<String>,
     // NOTE: labels excluded - not displayed in table view
 }
 
+/// Author of a paper with their position in the author list
+#[derive(Clone, Serialize)]
+pub struct PaperAuthorDto {
+    pub id: String,
+    pub name: String,
+    /// Zero-based position in the author list
+    pub order: i32,
+    pub is_corresponding: bool,
+}
+
+/// One entry of the author list sent by `update_paper_authors`
+#[derive(Deserialize, Debug)]
+pub struct PaperAuthorInputDto {
+    pub author_id: String,
+    pub order: i32,
+    #[serde(default)]
+    pub is_corresponding: bool,
+}
+
 #[derive(Serialize)]
 pub struct PaperDetailDto {
     pub id: String,
@@ -134,10 +174,13 @@ pub struct PaperDetailDto {
     pub citation_count: Option<i32>,
     pub read_status: Option<String>,
     pub notes: Option<String>,
-    pub authors: Vec<String>,
+    pub authors: Vec<PaperAuthorDto>,
     pub labels: Vec<LabelDto>,
+    /// The category the paper was filed under first
     pub category_id: Option<String>,
     pub category_name: Option<String>,
+    /// Every category of the paper, `category_id` first
+    pub categories: Vec<PaperCategoryDto>,
     pub attachments: Vec<AttachmentDto>,
     pub attachment_count: usize,
     pub created_at: Option<String>,
@@ -146,6 +189,10 @@ pub struct PaperDetailDto {
     pub publisher: Option<String>,
     pub issn: Option<String>,
     pub language: Option<String>,
+    pub is_starred: bool,
+    pub starred_at: Option<String>,
+    /// Custom field values by key
+    pub custom_fields: HashMap<String, String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -169,6 +216,25 @@ pub struct UpdatePaperDto {
     pub language: Option<String>,
 }
 
+/// Text extracted from a paper's PDF by `extract_text_from_scanned_pdf`
+#[derive(Serialize)]
+pub struct OcrResultDto {
+    pub paper_id: String,
+    pub page_count: u32,
+    pub extracted_text: String,
+    /// `true` when the text was read from the PDF rather than recognized
+    pub has_text_layer: bool,
+}
+
+/// Result DTO for bulk operations applied to several papers at once
+#[derive(Serialize)]
+pub struct BulkOperationResultDto {
+    /// IDs of papers that were updated
+    pub succeeded: Vec<String>,
+    /// `(paper_id, error_message)` for papers that could not be updated
+    pub failed: Vec<(String, String)>,
+}
+
 /// Result DTO for batch import operations (e.g., Zotero RDF import)
 #[derive(Serialize)]
 pub struct BatchImportResultDto {
@@ -185,3 +251,232 @@ pub struct BatchImportResultDto {
     /// List of error messages
     pub errors: Vec<String>,
 }
+
+/// Result DTO for importing a DOI list file
+#[derive(Serialize)]
+pub struct DoiFileImportResultDto {
+    pub batch_id: String,
+    pub total: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// Lines not processed yet (non-zero only when the run was interrupted)
+    pub pending: usize,
+    /// Line the run resumed from, when an earlier batch was continued
+    pub resumed_from_line: Option<usize>,
+    pub cancelled: bool,
+    /// Path of the errors-only file written next to the input, if any DOI failed
+    pub errors_file: Option<String>,
+}
+
+/// Result DTO for importing papers from a CSV file
+#[derive(Serialize)]
+pub struct CsvImportResultDto {
+    /// Rows read, excluding the header
+    pub total: usize,
+    /// Papers created, or that would be created when `dry_run` is set
+    pub imported: usize,
+    /// Rows whose DOI is already in the library or earlier in the file
+    pub skipped: usize,
+    pub failed: usize,
+    pub dry_run: bool,
+    /// IDs of the created papers
+    pub paper_ids: Vec<String>,
+    /// Rows that failed, with their line number in the file
+    pub errors: Vec<CsvRowError>,
+}
+
+/// Result DTO for importing a portable library zip
+#[derive(Serialize)]
+pub struct ImportZipResultDto {
+    /// Papers created, including duplicates made with `create_duplicate`
+    pub papers_imported: usize,
+    /// Papers already in the library, left alone with `skip_existing`
+    pub papers_skipped: usize,
+    /// Library papers replaced with `overwrite_existing`
+    pub papers_updated: usize,
+    pub errors: Vec<String>,
+}
+
+/// Kind of identifier checked by `check_duplicate_paper` or found by
+/// `detect_identifier_from_clipboard`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentifierType {
+    Doi,
+    ArxivId,
+    Pmid,
+    Isbn,
+    Title,
+}
+
+/// An identifier recognized in free text, such as the clipboard
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DetectedIdentifierDto {
+    pub identifier_type: IdentifierType,
+    /// Identifier without prefixes or URL, e.g. `10.1000/xyz` or `2101.00001v2`
+    pub value: String,
+    /// How likely the text really is this identifier, in `0.0..=1.0`
+    pub confidence: f32,
+}
+
+/// Result DTO for a local duplicate check before import
+#[derive(Serialize)]
+pub struct DuplicateCheckResult {
+    pub is_duplicate: bool,
+    pub existing_paper: Option<PaperDto>,
+    /// Title similarity in `0.0..=1.0`; only set for title checks
+    pub similarity_score: Option<f32>,
+}
+
+/// Bibliography entry of a paper
+#[derive(Clone, Serialize)]
+pub struct PaperReferenceDto {
+    pub id: String,
+    /// Zero-based position in the bibliography
+    pub position: i32,
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    pub journal: Option<String>,
+    /// Citation as printed in the PDF
+    pub raw_text: Option<String>,
+    /// Whether a paper with the same DOI is in the library
+    pub is_in_library: bool,
+    /// Id of the paper in the library with the same DOI, if any
+    pub library_paper_id: Option<String>,
+}
+
+/// Paper in a citation graph, with what a network view needs to draw it
+#[derive(Clone, Serialize)]
+pub struct CitationNodeDto {
+    pub id: String,
+    pub title: String,
+    pub publication_year: Option<i32>,
+    pub read_status: String,
+}
+
+/// `source` cites `target`
+#[derive(Clone, Serialize)]
+pub struct CitationEdgeDto {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct CitationGraphDto {
+    pub root_id: String,
+    pub nodes: Vec<CitationNodeDto>,
+    pub edges: Vec<CitationEdgeDto>,
+}
+
+/// Paper in a citation network. Bibliography entries that are not in the
+/// library are nodes too, with an id of the form `doi:<doi>` or
+/// `reference:<id>`.
+#[derive(Clone, Serialize)]
+pub struct NetworkNodeDto {
+    pub paper_id: String,
+    pub title: String,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    pub in_library: bool,
+}
+
+/// `from` is related to `to` by `relation`; currently always `cites`
+#[derive(Clone, Serialize)]
+pub struct NetworkEdgeDto {
+    pub from: String,
+    pub to: String,
+    pub relation: String,
+}
+
+#[derive(Clone, Serialize)]
+pub struct CitationNetworkDto {
+    pub nodes: Vec<NetworkNodeDto>,
+    pub edges: Vec<NetworkEdgeDto>,
+}
+
+/// Stored LLM summary of a paper
+#[derive(Clone, Serialize)]
+pub struct PaperSummaryDto {
+    pub paper_id: String,
+    /// `tldr` or `detailed`
+    pub style: String,
+    pub summary: String,
+    /// Model that wrote the summary
+    pub model: String,
+    pub created_at: String,
+}
+
+/// A paper similar to the one being viewed
+#[derive(Clone, Serialize)]
+pub struct RelatedPaperDto {
+    #[serde(flatten)]
+    pub paper: PaperDto,
+    /// Relevance, higher is better; text similarity is 0-1 and shared
+    /// authors or labels add to it
+    pub score: f32,
+    /// Why the paper is related, e.g. "Shared authors: …; Similar abstract"
+    pub reason: String,
+}
+
+/// Cached first-page thumbnail of a paper's PDF
+#[derive(Clone, Serialize)]
+pub struct ThumbnailDto {
+    pub paper_id: String,
+    pub path: String,
+    /// Actual size of the PNG, which fits within the requested box
+    pub width: u32,
+    pub height: u32,
+}
+
+/// One saved version of a paper's notes
+#[derive(Clone, Serialize)]
+pub struct NoteVersionDto {
+    pub paper_id: String,
+    pub version: u32,
+    pub content: String,
+    pub content_format: NoteFormat,
+    pub created_at: String,
+}
+
+/// Entry of the reading list (the reading queue with due dates)
+#[derive(Clone, Serialize)]
+pub struct ReadingListItemDto {
+    pub paper_id: String,
+    pub title: String,
+    /// 0 is read next, unless another paper is overdue
+    pub position: u32,
+    pub added_at: String,
+    pub due_date: Option<String>,
+}
+
+/// Custom field value of a paper
+#[derive(Clone, Serialize)]
+pub struct CustomFieldDto {
+    pub paper_id: String,
+    pub key: String,
+    pub value: String,
+    pub value_type: CustomFieldType,
+    pub updated_at: String,
+}
+
+/// Custom field key in use, for autocomplete
+#[derive(Clone, Serialize)]
+pub struct CustomFieldKeyDto {
+    pub key: String,
+    pub value_type: CustomFieldType,
+    pub paper_count: u64,
+}
+
+/// Attachment counts and sizes for the storage management view
+#[derive(Clone, Serialize)]
+pub struct StorageStatsDto {
+    pub total_attachment_count: u64,
+    pub total_attachment_size_bytes: u64,
+    /// Largest first
+    pub largest_attachments: Vec<AttachmentDto>,
+    pub pdf_count: u64,
+    pub other_count: u64,
+}
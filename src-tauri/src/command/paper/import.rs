@@ -1,4 +1,4 @@
-//! Import operations for papers (DOI, arXiv, PMID, PDF, Zotero RDF)
+//! Import operations for papers (DOI, arXiv, PMID, PDF, URL, Zotero RDF, CSV)
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -6,23 +6,73 @@ use std::sync::Arc;
 
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, State};
-use tracing::{info, instrument};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, instrument, warn};
 
 use crate::database::DatabaseConnection;
 use crate::models::CreateLabel;
-use crate::models::{CreateCategory, CreatePaper};
+use crate::models::{AuthorDetails, CreateCategory, CreateClipping, CreatePaper};
 use crate::papers::importer::arxiv::{fetch_arxiv_metadata, ArxivError};
+use crate::papers::importer::csv_file::CsvColumnMapping;
 use crate::papers::importer::doi::{fetch_doi_metadata, DoiError};
-use crate::papers::importer::grobid::process_header_document;
+use crate::papers::importer::grobid::{process_header_document, GrobidMetadata};
+use crate::papers::importer::isbn::{fetch_isbn_metadata, IsbnError};
 use crate::papers::importer::pubmed::{fetch_pubmed_metadata, PubmedError};
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::papers::importer::url::{
+    extract_arxiv_id_from_url, extract_doi_from_url, fetch_page, parse_page_metadata, url_host,
+    PageMetadata, UrlImportError,
+};
 use crate::papers::importer::zotero_rdf::{parse_rdf_file, ZoteroRdfError};
-use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository, PaperRepository};
+use crate::repository::{
+    AuthorRepository, CategoryRepository, ClippingRepository, LabelRepository, PaperRepository,
+};
+use crate::service::activity_service::{self, ACTION_IMPORTED, ENTITY_PAPER};
+use crate::service::attachment_service::{file_size_on_disk, new_attachment_dir};
+use crate::service::category_suggestion_service;
+use crate::service::csv_import_service;
+use crate::service::doi_import_service::{self, ItemOutcome};
+use crate::service::download_service::{download_client, download_resumable, DownloadRegistry};
+use crate::service::keyword_service;
+use crate::service::library_import_service::{extract_library_zip, import_library, MergeStrategy};
+use crate::service::pdf_import_queue_service::{
+    PdfBatchImportReport, PdfImportQueue, PdfImportQueueStatus, PdfSaveOutcome,
+};
 use crate::sys::config::AppConfig;
 use crate::sys::dirs::AppDirs;
 use crate::sys::error::{AppError, Result};
 
 use super::dtos::*;
-use super::utils::calculate_attachment_hash;
+use super::utils::detect_identifier;
+
+/// Progress event DTO for DOI list file import
+#[derive(Clone, Serialize)]
+pub struct DoiFileImportProgress {
+    pub batch_id: String,
+    pub line_number: usize,
+    pub doi: String,
+    pub total: usize,
+}
+
+/// Progress event DTO for folder import
+#[derive(Clone, Serialize)]
+pub struct FolderImportProgress {
+    pub total: usize,
+    pub processed: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// Path relative to the imported folder
+    pub current_file: String,
+}
+
+/// Progress event DTO for CSV import
+#[derive(Clone, Serialize)]
+pub struct CsvImportProgress {
+    /// Rows handled so far
+    pub processed: usize,
+    pub dry_run: bool,
+}
 
 /// Progress event DTO for Zotero import
 #[derive(Clone, Serialize)]
@@ -34,29 +84,89 @@ pub struct ZoteroImportProgress {
 }
 
 #[tauri::command]
-#[instrument(skip(db))]
+#[instrument(skip(db, app_dirs))]
 pub async fn import_paper_by_doi(
-    _app: AppHandle,
+    app: AppHandle,
     doi: String,
     category_id: Option<String>,
     db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
 ) -> Result<ImportResultDto> {
     info!("Importing paper with DOI: {}", doi);
 
+    let category_id = parse_category_id(category_id.as_deref())?;
+    let mut result = import_doi(&db, &app_dirs.config, &doi, category_id).await?;
+    suggest_categories_for_import(&db, &mut result, category_id).await;
+
+    emit_paper_imported(&app, &result);
+    Ok(result)
+}
+
+/// Offer categories for papers imported without one. Suggestions are a
+/// convenience, so failing to compute them does not fail the import.
+async fn suggest_categories_for_import(
+    db: &DatabaseConnection,
+    result: &mut ImportResultDto,
+    category_id: Option<i64>,
+) {
+    let new_paper_id = result.paper.as_ref().and_then(|p| p.id.parse::<i64>().ok());
+    if let (None, Some(paper_id)) = (category_id, new_paper_id) {
+        match category_suggestion_service::suggest_categories(db, paper_id).await {
+            Ok(mut suggestions) => {
+                suggestions.truncate(category_suggestion_service::IMPORT_SUGGESTION_COUNT);
+                result.category_suggestions = suggestions;
+            }
+            Err(e) => warn!("Category suggestion failed for paper {}: {}", paper_id, e),
+        }
+    }
+}
+
+fn parse_category_id(category_id: Option<&str>) -> Result<Option<i64>> {
+    category_id
+        .map(|id| {
+            id.parse::<i64>()
+                .map_err(|_| AppError::validation("category_id", "Invalid id format"))
+        })
+        .transpose()
+}
+
+/// Announce a newly imported paper so the paper list and the tray's recent
+/// papers refresh
+fn emit_paper_imported(app: &AppHandle, result: &ImportResultDto) {
+    if let Some(paper) = &result.paper {
+        let _ = app.emit(
+            "paper:imported",
+            serde_json::json!({
+                "id": paper.id,
+                "title": paper.title,
+            }),
+        );
+    }
+}
+
+/// Fetch a DOI record and store it as a new paper unless it already exists.
+/// Keywords are extracted from the abstract when `paper.auto_extract_keywords`
+/// is set in the config under `config_dir`.
+pub async fn import_doi(
+    db: &DatabaseConnection,
+    config_dir: &str,
+    doi: &str,
+    category_id: Option<i64>,
+) -> Result<ImportResultDto> {
     // Fetch metadata from DOI
-    let metadata = fetch_doi_metadata(&doi).await.map_err(|e| match e {
+    let metadata = fetch_doi_metadata(doi).await.map_err(|e| match e {
         DoiError::InvalidDoi(doi) => AppError::validation("doi", format!("Invalid DOI: {}", doi)),
         DoiError::NotFound => AppError::not_found("DOI", doi),
         DoiError::ParseError(msg) => {
             AppError::validation("metadata", format!("Failed to parse DOI metadata: {}", msg))
         }
         DoiError::RequestError(e) => {
-            AppError::network_error(&doi, format!("Failed to fetch DOI: {}", e))
+            AppError::network_error(doi, format!("Failed to fetch DOI: {}", e))
         }
     })?;
 
     // Check if paper already exists
-    if let Some(existing_paper) = PaperRepository::find_by_doi(&db, &metadata.doi).await? {
+    if let Some(existing_paper) = PaperRepository::find_by_doi(db, &metadata.doi).await? {
         info!(
             "Paper with DOI {} already exists: {}",
             metadata.doi, existing_paper.title
@@ -69,11 +179,12 @@ pub async fn import_paper_by_doi(
                 existing_paper.title
             ),
             paper: None,
+            category_suggestions: Vec::new(),
         });
     }
 
-    // Calculate attachment path hash
-    let hash_string = calculate_attachment_hash(&metadata.title);
+    // Name the attachment directory
+    let hash_string = new_attachment_dir();
 
     // Create paper
     let publication_year = metadata
@@ -81,7 +192,7 @@ pub async fn import_paper_by_doi(
         .and_then(|y| y.parse::<i32>().ok());
 
     let paper = PaperRepository::create(
-        &db,
+        db,
         CreatePaper {
             title: metadata.title.clone(),
             doi: Some(metadata.doi.clone()),
@@ -98,6 +209,7 @@ pub async fn import_paper_by_doi(
             publisher: metadata.publisher.clone(),
             issn: None,
             language: None,
+            isbn: None,
         },
     )
     .await?;
@@ -108,24 +220,27 @@ pub async fn import_paper_by_doi(
     // DOI provides given/family names separately, so use create_or_find_from_parts
     for (order, author_parts) in metadata.authors.iter().enumerate() {
         let author = AuthorRepository::create_or_find_from_parts(
-            &db,
+            db,
             author_parts.given.as_deref(),
             author_parts.family.as_deref(),
-            None,
+            &AuthorDetails {
+                affiliation: author_parts.affiliation.clone(),
+                orcid: author_parts.orcid.clone(),
+                ..Default::default()
+            },
         )
         .await?;
         // Create paper-author relation
-        PaperRepository::add_author(&db, paper_id, author.id, order as i32).await?;
+        PaperRepository::add_author(db, paper_id, author.id, order as i32).await?;
     }
 
     // Link category if provided
-    if let Some(cat_id) = category_id {
-        let cat_id_num = cat_id
-            .parse::<i64>()
-            .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
-        PaperRepository::set_category(&db, paper_id, Some(cat_id_num)).await?;
+    if category_id.is_some() {
+        PaperRepository::set_category(db, paper_id, category_id).await?;
     }
 
+    keyword_service::auto_extract_keywords(db, config_dir, paper_id).await;
+
     info!(
         "Successfully imported paper: {} (doi: {})",
         metadata.title, metadata.doi
@@ -138,6 +253,8 @@ pub async fn import_paper_by_doi(
         .filter_map(|a| a.full_name.clone())
         .collect();
 
+    activity_service::record(db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
     Ok(ImportResultDto {
         already_exists: false,
         message: format!("Paper '{}' imported successfully", paper.title),
@@ -154,22 +271,41 @@ pub async fn import_paper_by_doi(
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
         }),
+        category_suggestions: Vec::new(),
     })
 }
 
 #[tauri::command]
-#[instrument(skip(db, app_dirs))]
+#[instrument(skip(db, app_dirs, downloads))]
 pub async fn import_paper_by_arxiv_id(
-    _app: AppHandle,
+    app: AppHandle,
     db: State<'_, Arc<DatabaseConnection>>,
     app_dirs: State<'_, AppDirs>,
+    downloads: State<'_, DownloadRegistry>,
     arxiv_id: String,
     category_id: Option<String>,
 ) -> Result<ImportResultDto> {
     info!("Importing paper with arXiv ID: {}", arxiv_id);
 
-    let metadata = fetch_arxiv_metadata(&arxiv_id).await.map_err(|e| match e {
+    let category_id = parse_category_id(category_id.as_deref())?;
+    let result = import_arxiv(&db, &app_dirs.files, &downloads, &arxiv_id, category_id).await?;
+    emit_paper_imported(&app, &result);
+    Ok(result)
+}
+
+/// Fetch an arXiv record, store it as a new paper and download its PDF
+/// unless a paper with the same DOI already exists
+pub async fn import_arxiv(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    downloads: &DownloadRegistry,
+    arxiv_id: &str,
+    category_id: Option<i64>,
+) -> Result<ImportResultDto> {
+    let metadata = fetch_arxiv_metadata(arxiv_id).await.map_err(|e| match e {
         ArxivError::InvalidArxivId(id) => {
             AppError::validation("arxiv_id", format!("Invalid arXiv ID: {}", id))
         }
@@ -179,13 +315,13 @@ pub async fn import_paper_by_arxiv_id(
             format!("Failed to parse arXiv metadata: {}", msg),
         ),
         ArxivError::RequestError(e) => {
-            AppError::network_error(&arxiv_id, format!("Failed to fetch arXiv: {}", e))
+            AppError::network_error(arxiv_id, format!("Failed to fetch arXiv: {}", e))
         }
     })?;
 
     // Check if paper already exists by DOI
     if let Some(doi) = &metadata.doi {
-        if let Some(existing_paper) = PaperRepository::find_by_doi(&db, doi).await? {
+        if let Some(existing_paper) = PaperRepository::find_by_doi(db, doi).await? {
             info!(
                 "Paper with DOI {} already exists: {}",
                 doi, existing_paper.title
@@ -198,11 +334,12 @@ pub async fn import_paper_by_arxiv_id(
                     existing_paper.title
                 ),
                 paper: None,
+                category_suggestions: Vec::new(),
             });
         }
     }
 
-    let hash_string = calculate_attachment_hash(&metadata.title);
+    let hash_string = new_attachment_dir();
     let publication_year = metadata
         .published
         .split('-')
@@ -210,7 +347,7 @@ pub async fn import_paper_by_arxiv_id(
         .and_then(|y| y.parse::<i32>().ok());
 
     let paper = PaperRepository::create(
-        &db,
+        db,
         CreatePaper {
             title: metadata.title.clone(),
             doi: metadata.doi.clone(),
@@ -227,6 +364,7 @@ pub async fn import_paper_by_arxiv_id(
             publisher: None,
             issn: None,
             language: None,
+            isbn: None,
         },
     )
     .await?;
@@ -235,21 +373,19 @@ pub async fn import_paper_by_arxiv_id(
 
     // Add authors and create paper-author relations
     for (order, author_name) in metadata.authors.iter().enumerate() {
-        let author = AuthorRepository::create_or_find(&db, author_name, None).await?;
+        let author =
+            AuthorRepository::create_or_find(db, author_name, &AuthorDetails::default()).await?;
         // Create paper-author relation
-        PaperRepository::add_author(&db, paper_id, author.id, order as i32).await?;
+        PaperRepository::add_author(db, paper_id, author.id, order as i32).await?;
     }
 
     if let Some(cat_id) = category_id {
-        let cat_id_num = cat_id
-            .parse::<i64>()
-            .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
-        PaperRepository::set_category(&db, paper_id, Some(cat_id_num)).await?;
+        PaperRepository::set_category(db, paper_id, Some(cat_id)).await?;
     }
 
     // Download PDF from arXiv
     let pdf_filename = format!("{}.pdf", metadata.arxiv_id.replace('/', "_"));
-    let target_dir = PathBuf::from(&app_dirs.files).join(&hash_string);
+    let target_dir = PathBuf::from(files_dir).join(&hash_string);
     if !target_dir.exists() {
         std::fs::create_dir_all(&target_dir).map_err(|e| {
             AppError::file_system(target_dir.to_string_lossy().to_string(), e.to_string())
@@ -260,44 +396,15 @@ pub async fn import_paper_by_arxiv_id(
     info!("Downloading arXiv PDF from: {}", metadata.pdf_url);
     info!("Saving to: {:?}", target_path);
 
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(120)) // 2 minutes timeout for large PDFs
-        .build()
-        .map_err(|e| {
-            AppError::network_error(
-                &metadata.pdf_url,
-                format!("Failed to create HTTP client: {}", e),
-            )
-        })?;
-
-    let response = client.get(&metadata.pdf_url).send().await.map_err(|e| {
-        AppError::network_error(&metadata.pdf_url, format!("Failed to download PDF: {}", e))
-    })?;
-
-    if !response.status().is_success() {
-        return Err(AppError::network_error(
-            &metadata.pdf_url,
-            format!("Failed to download PDF: HTTP {}", response.status()),
-        ));
-    }
-
-    let pdf_bytes = response.bytes().await.map_err(|e| {
-        AppError::network_error(
-            &metadata.pdf_url,
-            format!("Failed to read PDF content: {}", e),
-        )
-    })?;
-
-    std::fs::write(&target_path, &pdf_bytes).map_err(|e| {
-        AppError::file_system(target_path.to_string_lossy().to_string(), e.to_string())
-    })?;
+    let client = download_client()?;
+    let pdf_size = download_resumable(&client, downloads, &metadata.pdf_url, &target_path).await?;
 
-    info!("PDF downloaded successfully: {} bytes", pdf_bytes.len());
+    info!("PDF downloaded successfully: {} bytes", pdf_size);
 
     // Create attachment record
-    let file_size = Some(pdf_bytes.len() as i64);
+    let file_size = Some(pdf_size as i64);
     PaperRepository::add_attachment(
-        &db,
+        db,
         paper_id,
         Some(pdf_filename.clone()),
         Some("pdf".to_string()),
@@ -305,6 +412,8 @@ pub async fn import_paper_by_arxiv_id(
     )
     .await?;
 
+    activity_service::record(db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
     Ok(ImportResultDto {
         already_exists: false,
         message: format!("Paper '{}' imported successfully", paper.title),
@@ -322,19 +431,216 @@ pub async fn import_paper_by_arxiv_id(
                 paper_id: paper_id.to_string(),
                 file_name: Some(pdf_filename),
                 file_type: Some("pdf".to_string()),
+                file_size,
                 created_at: None,
             }],
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
+        }),
+        category_suggestions: Vec::new(),
+    })
+}
+
+/// Import a paper from the URL of its page. A DOI in the URL is imported
+/// like `import_paper_by_doi`, an arxiv.org URL like
+/// `import_paper_by_arxiv_id`. Otherwise the page is fetched: a DOI in its
+/// `<meta>` tags is imported, else a paper is created from the title,
+/// authors and description found there. With `save_as_clipping`, a URL
+/// that cannot be imported is kept as a clipping instead.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, downloads))]
+pub async fn import_paper_by_url(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    downloads: State<'_, DownloadRegistry>,
+    url: String,
+    category_id: Option<String>,
+    save_as_clipping: Option<bool>,
+) -> Result<ImportResultDto> {
+    info!("Importing paper from URL: {}", url);
+
+    let url = url.trim();
+    if url_host(url).is_none() {
+        return Err(AppError::validation("url", "Must be an http or https URL"));
+    }
+    let category_id = parse_category_id(category_id.as_deref())?;
+
+    let mut page = None;
+    let mut result = match import_url(&db, &app_dirs, &downloads, url, category_id, &mut page).await
+    {
+        Ok(result) => result,
+        Err(e) if save_as_clipping.unwrap_or(false) => {
+            warn!("Import from {} failed, saving it as a clipping: {}", url, e);
+            save_url_as_clipping(&db, url, page.as_ref()).await?;
+            return Err(AppError::generic(format!(
+                "{}. The page was saved as a clipping instead",
+                e
+            )));
+        }
+        Err(e) => return Err(e),
+    };
+    suggest_categories_for_import(&db, &mut result, category_id).await;
+
+    emit_paper_imported(&app, &result);
+    Ok(result)
+}
+
+/// Import the paper at `url`; see `import_paper_by_url`. The metadata of a
+/// fetched page is left in `page`.
+async fn import_url(
+    db: &DatabaseConnection,
+    app_dirs: &AppDirs,
+    downloads: &DownloadRegistry,
+    url: &str,
+    category_id: Option<i64>,
+    page: &mut Option<PageMetadata>,
+) -> Result<ImportResultDto> {
+    if let Some(doi) = extract_doi_from_url(url) {
+        info!("Found DOI {} in URL", doi);
+        return import_doi(db, &app_dirs.config, &doi, category_id).await;
+    }
+    if let Some(arxiv_id) = extract_arxiv_id_from_url(url) {
+        info!("Found arXiv ID {} in URL", arxiv_id);
+        return import_arxiv(db, &app_dirs.files, downloads, &arxiv_id, category_id).await;
+    }
+
+    if let Some(existing_paper) = PaperRepository::find_by_url(db, url).await? {
+        return Ok(ImportResultDto {
+            already_exists: true,
+            message: format!(
+                "Paper '{}' is already in your library",
+                existing_paper.title
+            ),
+            paper: None,
+            category_suggestions: Vec::new(),
+        });
+    }
+
+    let html = fetch_page(url).await.map_err(|e| match e {
+        UrlImportError::InvalidUrl(url) => {
+            AppError::validation("url", format!("Invalid URL: {}", url))
+        }
+        UrlImportError::RequestError(e) => {
+            AppError::network_error(url, format!("Failed to fetch page: {}", e))
+        }
+        UrlImportError::NotHtml(content_type) => AppError::validation(
+            "url",
+            format!("The URL is not a web page ({})", content_type),
+        ),
+    })?;
+    let metadata = page.insert(parse_page_metadata(&html));
+
+    if let Some(doi) = metadata.doi.clone() {
+        info!("Found DOI {} in page metadata", doi);
+        return import_doi(db, &app_dirs.config, &doi, category_id).await;
+    }
+    let Some(title) = metadata.title.clone() else {
+        return Err(AppError::not_found("Paper metadata", url));
+    };
+
+    let paper = PaperRepository::create(
+        db,
+        CreatePaper {
+            title: title.clone(),
+            doi: None,
+            publication_year: metadata.publication_year,
+            publication_date: None,
+            journal_name: metadata.journal_name.clone(),
+            conference_name: None,
+            volume: None,
+            issue: None,
+            pages: None,
+            url: Some(url.to_string()),
+            abstract_text: metadata.description.clone(),
+            attachment_path: Some(new_attachment_dir()),
+            publisher: metadata.site_name.clone(),
+            issn: None,
+            language: None,
+            isbn: None,
+        },
+    )
+    .await?;
+
+    let paper_id = paper.id;
+
+    for (order, author_name) in metadata.authors.iter().enumerate() {
+        let author =
+            AuthorRepository::create_or_find(db, author_name, &AuthorDetails::default()).await?;
+        PaperRepository::add_author(db, paper_id, author.id, order as i32).await?;
+    }
+
+    if let Some(cat_id) = category_id {
+        PaperRepository::set_category(db, paper_id, Some(cat_id)).await?;
+    }
+
+    keyword_service::auto_extract_keywords(db, &app_dirs.config, paper_id).await;
+    activity_service::record(db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
+    info!("Imported paper '{}' from page metadata", paper.title);
+    Ok(ImportResultDto {
+        already_exists: false,
+        message: format!("Paper '{}' imported successfully", paper.title),
+        paper: Some(PaperDto {
+            id: paper_id.to_string(),
+            title: paper.title,
+            publication_year: paper.publication_year,
+            journal_name: paper.journal_name,
+            conference_name: paper.conference_name,
+            authors: metadata.authors.clone(),
+            labels: vec![],
+            attachment_count: 0,
+            attachments: vec![],
+            publisher: paper.publisher,
+            issn: paper.issn,
+            language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
         }),
+        category_suggestions: Vec::new(),
     })
 }
 
+/// Keep a URL that could not be imported as a clipping, titled from its
+/// page when it was fetched. Nothing is saved when a clipping for the URL
+/// already exists.
+async fn save_url_as_clipping(
+    db: &DatabaseConnection,
+    url: &str,
+    page: Option<&PageMetadata>,
+) -> Result<()> {
+    if ClippingRepository::find_by_url(db, url).await?.is_some() {
+        return Ok(());
+    }
+
+    ClippingRepository::create(
+        db,
+        CreateClipping {
+            title: page
+                .and_then(|p| p.title.clone())
+                .unwrap_or_else(|| url.to_string()),
+            url: url.to_string(),
+            content: None,
+            source_domain: url_host(url),
+            author: page.map(|p| p.authors.join(", ")).filter(|a| !a.is_empty()),
+            published_date: None,
+            excerpt: page.and_then(|p| p.description.clone()),
+            thumbnail_url: None,
+            tags: Vec::new(),
+            image_paths: Vec::new(),
+        },
+    )
+    .await?;
+    Ok(())
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn import_paper_by_pmid(
-    _app: AppHandle,
+    app: AppHandle,
     pmid: String,
     category_id: Option<String>,
     db: State<'_, Arc<DatabaseConnection>>,
@@ -372,12 +678,13 @@ pub async fn import_paper_by_pmid(
                     existing_paper.title
                 ),
                 paper: None,
+                category_suggestions: Vec::new(),
             });
         }
     }
 
     let pubmed_url = format!("https://pubmed.ncbi.nlm.nih.gov/{}/", metadata.pmid);
-    let hash_string = calculate_attachment_hash(&metadata.title);
+    let hash_string = new_attachment_dir();
     let publication_year = metadata
         .publication_year
         .and_then(|y| y.parse::<i32>().ok());
@@ -400,6 +707,7 @@ pub async fn import_paper_by_pmid(
             publisher: None,
             issn: None,
             language: None,
+            isbn: None,
         },
     )
     .await?;
@@ -413,13 +721,27 @@ pub async fn import_paper_by_pmid(
             &db,
             author_parts.fore_name.as_deref(),
             author_parts.last_name.as_deref(),
-            None,
+            &AuthorDetails::default(),
         )
         .await?;
         // Create paper-author relation
         PaperRepository::add_author(&db, paper_id, author.id, order as i32).await?;
     }
 
+    // Author keywords and MeSH terms become the paper's keywords
+    let keywords: Vec<String> = metadata
+        .keywords
+        .iter()
+        .chain(&metadata.mesh_terms)
+        .cloned()
+        .collect();
+    if let Err(e) = keyword_service::store_keywords(&db, paper_id, &keywords).await {
+        warn!(
+            "Failed to store PubMed keywords for paper {}: {}",
+            paper_id, e
+        );
+    }
+
     if let Some(cat_id) = category_id {
         let cat_id_num = cat_id
             .parse::<i64>()
@@ -434,7 +756,9 @@ pub async fn import_paper_by_pmid(
         .filter_map(|a| a.full_name.clone())
         .collect();
 
-    Ok(ImportResultDto {
+    activity_service::record(&db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
+    let result = ImportResultDto {
         already_exists: false,
         message: format!("Paper '{}' imported successfully", paper.title),
         paper: Some(PaperDto {
@@ -450,14 +774,123 @@ pub async fn import_paper_by_pmid(
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
         }),
-    })
+        category_suggestions: Vec::new(),
+    };
+    emit_paper_imported(&app, &result);
+    Ok(result)
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn import_paper_by_isbn(
+    app: AppHandle,
+    isbn: String,
+    category_id: Option<String>,
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<ImportResultDto> {
+    info!("Importing book with ISBN: {}", isbn);
+
+    let category_id = parse_category_id(category_id.as_deref())?;
+
+    // Checksums are validated before any request is made
+    let metadata = fetch_isbn_metadata(&isbn).await.map_err(|e| match e {
+        IsbnError::InvalidIsbn(isbn) => {
+            AppError::validation("isbn", format!("Invalid ISBN: {}", isbn))
+        }
+        IsbnError::NotFound => AppError::not_found("ISBN", isbn.clone()),
+        IsbnError::ParseError(msg) => AppError::validation(
+            "metadata",
+            format!("Failed to parse book metadata: {}", msg),
+        ),
+        IsbnError::RequestError(e) => {
+            AppError::network_error(&isbn, format!("Failed to fetch book metadata: {}", e))
+        }
+    })?;
+
+    if let Some(existing_paper) = PaperRepository::find_by_isbn(&db, &metadata.isbn).await? {
+        info!(
+            "Book with ISBN {} already exists: {}",
+            metadata.isbn, existing_paper.title
+        );
+
+        return Ok(ImportResultDto {
+            already_exists: true,
+            message: format!("Book '{}' is already in your library", existing_paper.title),
+            paper: None,
+            category_suggestions: Vec::new(),
+        });
+    }
+    info!("Found ISBN {} in {}", metadata.isbn, metadata.source);
+
+    let paper = PaperRepository::create(
+        &db,
+        CreatePaper {
+            title: metadata.title.clone(),
+            doi: None,
+            publication_year: metadata.publication_year,
+            publication_date: metadata.publish_date.clone(),
+            journal_name: None,
+            conference_name: None,
+            volume: None,
+            issue: None,
+            pages: None,
+            url: Some(metadata.url.clone()),
+            abstract_text: None,
+            attachment_path: Some(new_attachment_dir()),
+            publisher: metadata.publisher.clone(),
+            issn: None,
+            language: None,
+            isbn: Some(metadata.isbn.clone()),
+        },
+    )
+    .await?;
+
+    let paper_id = paper.id;
+
+    for (order, author_name) in metadata.authors.iter().enumerate() {
+        let author =
+            AuthorRepository::create_or_find(&db, author_name, &AuthorDetails::default()).await?;
+        PaperRepository::add_author(&db, paper_id, author.id, order as i32).await?;
+    }
+
+    if let Some(cat_id) = category_id {
+        PaperRepository::set_category(&db, paper_id, Some(cat_id)).await?;
+    }
+
+    activity_service::record(&db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
+    let result = ImportResultDto {
+        already_exists: false,
+        message: format!("Book '{}' imported successfully", paper.title),
+        paper: Some(PaperDto {
+            id: paper_id.to_string(),
+            title: paper.title,
+            publication_year: paper.publication_year,
+            journal_name: paper.journal_name,
+            conference_name: paper.conference_name,
+            authors: metadata.authors,
+            labels: vec![],
+            attachment_count: 0,
+            attachments: vec![],
+            publisher: paper.publisher,
+            issn: paper.issn,
+            language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
+        }),
+        category_suggestions: Vec::new(),
+    };
+    emit_paper_imported(&app, &result);
+    Ok(result)
 }
 
 #[tauri::command]
 #[instrument(skip(db, app_dirs))]
 pub async fn import_paper_by_pdf(
-    _app: AppHandle,
+    app: AppHandle,
     db: State<'_, Arc<DatabaseConnection>>,
     app_dirs: State<'_, AppDirs>,
     file_path: String,
@@ -468,22 +901,38 @@ pub async fn import_paper_by_pdf(
     if !path.exists() {
         return Err(AppError::file_system(file_path, "File not found"));
     }
+    let category_id = parse_category_id(category_id.as_deref())?;
 
     // Get GROBID URL from config
     let config = AppConfig::load(&app_dirs.config)?;
-    let grobid_url = config
-        .paper
-        .grobid
-        .servers
-        .iter()
-        .find(|s| s.is_active)
-        .map(|s| s.url.clone())
-        .unwrap_or_else(|| "https://kermitt2-grobid.hf.space".to_string());
+    let grobid_url = config.paper.grobid.active_url();
+
+    let result = import_pdf(&db, &app_dirs, &grobid_url, &path, category_id).await?;
+    emit_paper_imported(&app, &result);
+    Ok(result)
+}
 
+/// Store a PDF as a new paper with the file attached, unless a paper with
+/// the same DOI already exists. Metadata comes from the GROBID server at
+/// `grobid_url`; the file name is used as the title when that fails.
+pub async fn import_pdf(
+    db: &DatabaseConnection,
+    app_dirs: &AppDirs,
+    grobid_url: &str,
+    path: &Path,
+    category_id: Option<i64>,
+) -> Result<ImportResultDto> {
+    let metadata = extract_pdf_metadata(grobid_url, path).await;
+    save_pdf_import(db, app_dirs, path, metadata, category_id).await
+}
+
+/// Header metadata of a PDF from the GROBID server at `grobid_url`. The file
+/// name is used as the title when GROBID fails or finds no title.
+pub async fn extract_pdf_metadata(grobid_url: &str, path: &Path) -> GrobidMetadata {
     info!("Using GROBID server: {}", grobid_url);
 
     // Try to get metadata from GROBID, but don't fail the whole import if it fails
-    let metadata_result = process_header_document(&path, &grobid_url).await;
+    let metadata_result = process_header_document(path, grobid_url).await;
 
     let (title, metadata) = match metadata_result {
         Ok(m) if !m.title.is_empty() => {
@@ -519,10 +968,23 @@ pub async fn import_paper_by_pdf(
     };
 
     info!("Using title: {}", title);
+    metadata
+}
+
+/// Store a PDF with its extracted metadata as a new paper, unless a paper
+/// with the same DOI already exists
+pub async fn save_pdf_import(
+    db: &DatabaseConnection,
+    app_dirs: &AppDirs,
+    path: &Path,
+    metadata: GrobidMetadata,
+    category_id: Option<i64>,
+) -> Result<ImportResultDto> {
+    let title = metadata.title.clone();
 
     // Check if paper already exists by DOI (if available)
     if let Some(ref doi) = metadata.doi {
-        if let Some(existing_paper) = PaperRepository::find_by_doi(&db, doi).await? {
+        if let Some(existing_paper) = PaperRepository::find_by_doi(db, doi).await? {
             info!(
                 "Paper with DOI {} already exists: {}",
                 doi, existing_paper.title
@@ -535,17 +997,21 @@ pub async fn import_paper_by_pdf(
                     existing_paper.title
                 ),
                 paper: None,
+                category_suggestions: Vec::new(),
             });
         }
     }
 
     let target_filename = path.file_name().unwrap().to_string_lossy().to_string();
-    let hash_string = calculate_attachment_hash(&title);
+    let hash_string = new_attachment_dir();
 
-    info!("Creating paper record with hash: {}", hash_string);
+    info!(
+        "Creating paper record with attachment directory: {}",
+        hash_string
+    );
 
     let paper = PaperRepository::create(
-        &db,
+        db,
         CreatePaper {
             title: title.clone(),
             doi: metadata.doi.clone(),
@@ -564,6 +1030,7 @@ pub async fn import_paper_by_pdf(
             publisher: None,
             issn: None,
             language: None,
+            isbn: None,
         },
     )
     .await?;
@@ -572,17 +1039,23 @@ pub async fn import_paper_by_pdf(
     info!("Created paper with ID: {}", paper_id);
 
     // Add authors and create paper-author relations
-    for (order, author_name) in metadata.authors.iter().enumerate() {
-        let author = AuthorRepository::create_or_find(&db, author_name, None).await?;
+    for (order, grobid_author) in metadata.authors.iter().enumerate() {
+        let author = AuthorRepository::create_or_find(
+            db,
+            &grobid_author.name,
+            &AuthorDetails {
+                affiliation: grobid_author.affiliation.clone(),
+                orcid: grobid_author.orcid.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
         // Create paper-author relation
-        PaperRepository::add_author(&db, paper_id, author.id, order as i32).await?;
+        PaperRepository::add_author(db, paper_id, author.id, order as i32).await?;
     }
 
     if let Some(cat_id) = category_id {
-        let cat_id_num = cat_id
-            .parse::<i64>()
-            .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
-        PaperRepository::set_category(&db, paper_id, Some(cat_id_num)).await?;
+        PaperRepository::set_category(db, paper_id, Some(cat_id)).await?;
     }
 
     // Copy file to attachment path
@@ -596,17 +1069,17 @@ pub async fn import_paper_by_pdf(
 
     info!("Copying PDF to: {:?}", target_path);
 
-    std::fs::copy(&path, &target_path).map_err(|e| {
+    std::fs::copy(path, &target_path).map_err(|e| {
         AppError::file_system(target_path.to_string_lossy().to_string(), e.to_string())
     })?;
 
     // Create attachment record
-    let file_size = std::fs::metadata(&target_path).ok().map(|m| m.len() as i64);
+    let file_size = file_size_on_disk(&target_path);
 
     info!("Creating attachment record");
 
     PaperRepository::add_attachment(
-        &db,
+        db,
         paper_id,
         Some(target_filename.clone()),
         Some("pdf".to_string()),
@@ -616,6 +1089,8 @@ pub async fn import_paper_by_pdf(
 
     info!("PDF import completed successfully");
 
+    activity_service::record(db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
+
     Ok(ImportResultDto {
         already_exists: false,
         message: format!("Paper '{}' imported successfully", paper.title),
@@ -625,7 +1100,7 @@ pub async fn import_paper_by_pdf(
             publication_year: paper.publication_year,
             journal_name: paper.journal_name,
             conference_name: paper.conference_name,
-            authors: metadata.authors,
+            authors: metadata.authors.into_iter().map(|a| a.name).collect(),
             labels: vec![],
             attachment_count: 1,
             attachments: vec![AttachmentDto {
@@ -633,15 +1108,212 @@ pub async fn import_paper_by_pdf(
                 paper_id: paper_id.to_string(),
                 file_name: Some(target_filename),
                 file_type: Some("pdf".to_string()),
+                file_size,
                 created_at: None,
             }],
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
         }),
+        category_suggestions: Vec::new(),
     })
 }
 
+/// PDF files in `dir`, sorted by path. Subdirectories are searched when
+/// `recursive` is set; hidden files and directories are skipped.
+pub fn collect_pdf_files(dir: &Path, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("pdf"))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Import every PDF in a folder, each through the same GROBID extraction as
+/// `import_paper_by_pdf`. A file that fails is recorded in `errors` and the
+/// rest of the folder is still imported.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn import_papers_from_folder(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    folder_path: String,
+    category_id: Option<String>,
+    recursive: bool,
+) -> Result<BatchImportResultDto> {
+    info!("Importing PDFs from folder: {}", folder_path);
+
+    let folder = PathBuf::from(&folder_path);
+    if !folder.is_dir() {
+        return Err(AppError::file_system(folder_path, "Folder not found"));
+    }
+    let category_id = parse_category_id(category_id.as_deref())?;
+
+    let files = collect_pdf_files(&folder, recursive).map_err(|e| {
+        AppError::file_system(folder_path.clone(), format!("Failed to read folder: {}", e))
+    })?;
+
+    let config = AppConfig::load(&app_dirs.config)?;
+    let grobid_url = config.paper.grobid.active_url();
+
+    let mut result = BatchImportResultDto {
+        total: files.len(),
+        imported: 0,
+        skipped: 0,
+        failed: 0,
+        papers: vec![],
+        errors: vec![],
+    };
+    info!("Found {} PDF files in {}", result.total, folder_path);
+
+    for (index, file) in files.iter().enumerate() {
+        let current_file = file
+            .strip_prefix(&folder)
+            .unwrap_or(file)
+            .to_string_lossy()
+            .to_string();
+        let _ = app.emit(
+            "folder-import-progress",
+            FolderImportProgress {
+                total: result.total,
+                processed: index,
+                succeeded: result.imported,
+                failed: result.failed,
+                current_file: current_file.clone(),
+            },
+        );
+
+        match import_pdf(&db, &app_dirs, &grobid_url, file, category_id).await {
+            Ok(ImportResultDto {
+                already_exists: true,
+                ..
+            }) => result.skipped += 1,
+            Ok(imported) => {
+                result.imported += 1;
+                result.papers.extend(imported.paper);
+            }
+            Err(e) => {
+                warn!("Failed to import {}: {}", current_file, e);
+                result.failed += 1;
+                result.errors.push(format!("{}: {}", current_file, e));
+            }
+        }
+    }
+
+    let _ = app.emit(
+        "folder-import-progress",
+        FolderImportProgress {
+            total: result.total,
+            processed: result.total,
+            succeeded: result.imported,
+            failed: result.failed,
+            current_file: String::new(),
+        },
+    );
+
+    info!(
+        "Folder import completed: {} imported, {} skipped, {} failed",
+        result.imported, result.skipped, result.failed
+    );
+
+    let _ = app.emit(
+        "paper:imported",
+        serde_json::json!({
+            "imported": result.imported,
+            "skipped": result.skipped,
+            "failed": result.failed
+        }),
+    );
+
+    Ok(result)
+}
+
+/// Import dropped PDFs through the shared import queue, at most two GROBID
+/// extractions at a time. Emits `pdf-import:progress` with the file's queue
+/// item whenever it changes stage; a file that fails is reported and the
+/// rest are still imported.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs, queue))]
+pub async fn import_pdfs_batch(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    queue: State<'_, PdfImportQueue>,
+    file_paths: Vec<String>,
+    category_id: Option<String>,
+) -> Result<PdfBatchImportReport> {
+    if file_paths.is_empty() {
+        return Err(AppError::validation("file_paths", "No files to import"));
+    }
+    let category_id = parse_category_id(category_id.as_deref())?;
+    let config = AppConfig::load(&app_dirs.config)?;
+    let grobid_url = config.paper.grobid.active_url();
+    info!("Queueing {} PDFs for import", file_paths.len());
+
+    let items = queue.enqueue(&file_paths);
+    let (db, app_dirs, grobid_url) = (db.inner().as_ref(), app_dirs.inner(), &grobid_url);
+    let report = queue
+        .run(
+            items,
+            |path| async move { Ok(extract_pdf_metadata(grobid_url, &path).await) },
+            |path, metadata| async move {
+                let result = save_pdf_import(db, app_dirs, &path, metadata, category_id).await?;
+                Ok(PdfSaveOutcome {
+                    paper_id: result.paper.map(|p| p.id),
+                    already_exists: result.already_exists,
+                    message: result.message,
+                })
+            },
+            |item| {
+                let _ = app.emit("pdf-import:progress", item);
+            },
+        )
+        .await;
+
+    let _ = app.emit(
+        "paper:imported",
+        serde_json::json!({
+            "imported": report.imported,
+            "skipped": report.skipped,
+            "failed": report.failed
+        }),
+    );
+
+    Ok(report)
+}
+
+/// Files queued by `import_pdfs_batch` and where each one is, so a reloaded
+/// window can show the import again
+#[tauri::command]
+#[instrument(skip(queue))]
+pub async fn get_import_queue_status(
+    queue: State<'_, PdfImportQueue>,
+) -> Result<PdfImportQueueStatus> {
+    Ok(queue.status())
+}
+
 /// Import papers from a Zotero RDF export file
 ///
 /// This function parses a Zotero RDF file and imports all papers found in it.
@@ -802,8 +1474,8 @@ pub async fn import_papers_from_zotero_rdf(
             .and_then(|d| d.split('/').next())
             .and_then(|y| y.parse::<i32>().ok());
 
-        // Calculate attachment hash
-        let hash_string = calculate_attachment_hash(&title);
+        // Name the attachment directory
+        let hash_string = new_attachment_dir();
 
         // Create paper record
         let paper = match PaperRepository::create(
@@ -824,6 +1496,7 @@ pub async fn import_papers_from_zotero_rdf(
                 publisher: None,
                 issn: None,
                 language: None,
+                isbn: None,
             },
         )
         .await
@@ -839,6 +1512,7 @@ pub async fn import_papers_from_zotero_rdf(
         };
 
         let paper_id = paper.id;
+        activity_service::record(&db, ENTITY_PAPER, paper_id, ACTION_IMPORTED).await;
 
         // Add authors (with deduplication to avoid UNIQUE constraint errors)
         let mut added_author_ids: HashSet<i64> = HashSet::new();
@@ -847,7 +1521,7 @@ pub async fn import_papers_from_zotero_rdf(
                 &db,
                 author.given_name.as_deref(),
                 author.surname.as_deref(),
-                None,
+                &AuthorDetails::default(),
             )
             .await?;
 
@@ -882,6 +1556,7 @@ pub async fn import_papers_from_zotero_rdf(
                     CreateLabel {
                         name: tag_name.to_string(),
                         color: "#607D8B".to_string(), // Default gray color
+                        parent_id: None,
                     },
                 )
                 .await?
@@ -962,7 +1637,7 @@ pub async fn import_papers_from_zotero_rdf(
             }
 
             // Create attachment record
-            let file_size = std::fs::metadata(&target_path).ok().map(|m| m.len() as i64);
+            let file_size = file_size_on_disk(&target_path);
 
             if let Err(e) = PaperRepository::add_attachment(
                 &db,
@@ -985,6 +1660,7 @@ pub async fn import_papers_from_zotero_rdf(
                 paper_id: paper_id.to_string(),
                 file_name: Some(filename),
                 file_type: Some("pdf".to_string()),
+                file_size,
                 created_at: None,
             });
         }
@@ -1006,6 +1682,8 @@ pub async fn import_papers_from_zotero_rdf(
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
         });
     }
 
@@ -1040,3 +1718,273 @@ pub async fn import_papers_from_zotero_rdf(
 
     Ok(result)
 }
+
+/// Import every DOI listed in a text file (one per line, `#` comments allowed).
+///
+/// The batch is recorded before processing starts. Calling this again with
+/// the same, unchanged file resumes from the first line that was not yet
+/// processed instead of starting over.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn import_doi_file(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    path: String,
+    category_id: Option<String>,
+) -> Result<DoiFileImportResultDto> {
+    info!("Importing DOI list from {}", path);
+
+    let input_path = PathBuf::from(&path);
+    if !input_path.exists() {
+        return Err(AppError::file_system(path, "DOI list file not found"));
+    }
+
+    let category_id = parse_category_id(category_id.as_deref())?;
+    let (batch, resumed) = doi_import_service::prepare_batch(&db, &input_path, category_id).await?;
+    if resumed {
+        info!("Found unfinished import batch {}, resuming", batch.id);
+    }
+
+    let db_arc = db.inner().clone();
+    let config_dir = app_dirs.config.clone();
+    let batch_category = batch.category_id;
+    let total = batch.total as usize;
+    let batch_id = batch.id.to_string();
+
+    let summary = doi_import_service::run_batch(
+        &db,
+        &batch,
+        &input_path,
+        &RateLimiter::default(),
+        &CancellationToken::new(),
+        |item| {
+            let db = db_arc.clone();
+            let config_dir = config_dir.clone();
+            let _ = app.emit(
+                "doi-import:progress",
+                DoiFileImportProgress {
+                    batch_id: batch_id.clone(),
+                    line_number: item.line_number as usize,
+                    doi: item.identifier.clone(),
+                    total,
+                },
+            );
+            async move {
+                let result = import_doi(&db, &config_dir, &item.identifier, batch_category).await?;
+                Ok(match result.paper {
+                    Some(paper) if !result.already_exists => ItemOutcome::Imported {
+                        paper_id: paper.id.parse().unwrap_or_default(),
+                    },
+                    _ => ItemOutcome::AlreadyExists,
+                })
+            }
+        },
+    )
+    .await?;
+
+    if summary.imported > 0 {
+        let _ = app.emit(
+            "paper:imported",
+            serde_json::json!({
+                "imported": summary.imported,
+                "skipped": summary.skipped,
+                "failed": summary.failed
+            }),
+        );
+    }
+
+    Ok(DoiFileImportResultDto {
+        batch_id: summary.batch_id.to_string(),
+        total: summary.total,
+        imported: summary.imported,
+        skipped: summary.skipped,
+        failed: summary.failed,
+        pending: summary.pending,
+        resumed_from_line: summary.resumed_from_line,
+        cancelled: summary.cancelled,
+        errors_file: summary.errors_file.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// Import papers from a CSV file, e.g. a Mendeley export or a spreadsheet.
+///
+/// `mapping` names the column holding each paper field; authors and labels
+/// are separated by `;` within a cell. Rows that fail validation are reported
+/// with their line number and do not stop the import. With `dry_run` the
+/// file is only parsed and validated.
+#[tauri::command]
+#[instrument(skip(app, db, mapping))]
+pub async fn import_papers_from_csv(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    file_path: String,
+    mapping: CsvColumnMapping,
+    dry_run: Option<bool>,
+) -> Result<CsvImportResultDto> {
+    let dry_run = dry_run.unwrap_or(false);
+    info!(
+        "Importing papers from CSV {} (dry run: {})",
+        file_path, dry_run
+    );
+
+    let path = PathBuf::from(&file_path);
+    if !path.is_file() {
+        return Err(AppError::file_system(file_path, "CSV file not found"));
+    }
+
+    let summary = csv_import_service::import_csv(&db, &path, &mapping, dry_run, |processed| {
+        let _ = app.emit(
+            "csv-import:progress",
+            CsvImportProgress { processed, dry_run },
+        );
+    })
+    .await?;
+
+    if !dry_run && summary.imported > 0 {
+        let _ = app.emit(
+            "paper:imported",
+            serde_json::json!({
+                "imported": summary.imported,
+                "skipped": summary.skipped,
+                "failed": summary.failed
+            }),
+        );
+    }
+
+    Ok(CsvImportResultDto {
+        total: summary.total,
+        imported: summary.imported,
+        skipped: summary.skipped,
+        failed: summary.failed,
+        dry_run,
+        paper_ids: summary.paper_ids.iter().map(|id| id.to_string()).collect(),
+        errors: summary.errors,
+    })
+}
+
+/// Import a zip written by `export_library_as_zip`.
+///
+/// Papers already in the library, matched by DOI or title, are handled as
+/// `merge_strategy` says. Missing authors, labels and categories are created
+/// and attachment files are copied into the files directory. Progress is
+/// emitted as `import-progress`.
+#[tauri::command]
+#[instrument(skip(app, db, app_dirs))]
+pub async fn import_library_from_zip(
+    app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    zip_path: String,
+    merge_strategy: MergeStrategy,
+) -> Result<ImportZipResultDto> {
+    info!("Importing library from {} ({:?})", zip_path, merge_strategy);
+
+    let path = PathBuf::from(&zip_path);
+    if !path.is_file() {
+        return Err(AppError::file_system(zip_path, "Zip file not found"));
+    }
+
+    let staging_dir = PathBuf::from(&app_dirs.cache).join("library-import");
+    let _ = std::fs::remove_dir_all(&staging_dir);
+
+    let result = async {
+        let (archive, target) = (path.clone(), staging_dir.clone());
+        tokio::task::spawn_blocking(move || extract_library_zip(&archive, &target))
+            .await
+            .map_err(|e| AppError::generic(format!("Library import task failed: {}", e)))??;
+
+        import_library(
+            &db,
+            &staging_dir,
+            Path::new(&app_dirs.files),
+            merge_strategy,
+            |progress| {
+                let _ = app.emit("import-progress", progress);
+            },
+        )
+        .await
+    }
+    .await;
+
+    if let Err(e) = std::fs::remove_dir_all(&staging_dir) {
+        warn!("Failed to remove import staging {:?}: {}", staging_dir, e);
+    }
+
+    let report = result?;
+    if report.papers_imported > 0 {
+        let _ = app.emit(
+            "paper:imported",
+            serde_json::json!({
+                "imported": report.papers_imported,
+                "skipped": report.papers_skipped,
+                "failed": report.errors.len()
+            }),
+        );
+    }
+
+    Ok(ImportZipResultDto {
+        papers_imported: report.papers_imported,
+        papers_skipped: report.papers_skipped,
+        papers_updated: report.papers_updated,
+        errors: report.errors,
+    })
+}
+
+/// Look for a DOI, arXiv ID, ISBN or PMID in the clipboard so the import
+/// dialog can be pre-filled. Detection runs locally; nothing is fetched.
+/// Returns `None` when the clipboard holds no text or no identifier.
+#[tauri::command]
+#[instrument(skip(app))]
+pub async fn detect_identifier_from_clipboard(
+    app: AppHandle,
+) -> Result<Option<DetectedIdentifierDto>> {
+    let text = match app.clipboard().read_text() {
+        Ok(text) => text,
+        Err(e) => {
+            info!("Clipboard has no text: {}", e);
+            return Ok(None);
+        }
+    };
+
+    let detected = detect_identifier(&text);
+    if let Some(identifier) = &detected {
+        info!(
+            "Detected {:?} in clipboard (confidence {:.2})",
+            identifier.identifier_type, identifier.confidence
+        );
+    }
+    Ok(detected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_pdf_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(dir.path().join(".hidden")).unwrap();
+        for path in [
+            dir.path().join("b.pdf"),
+            dir.path().join("a.PDF"),
+            dir.path().join("notes.txt"),
+            nested.join("c.pdf"),
+            dir.path().join(".hidden").join("d.pdf"),
+        ] {
+            std::fs::write(path, b"%PDF-1.4").unwrap();
+        }
+
+        let flat = collect_pdf_files(dir.path(), false).unwrap();
+        assert_eq!(
+            flat,
+            vec![dir.path().join("a.PDF"), dir.path().join("b.pdf")]
+        );
+
+        let recursive = collect_pdf_files(dir.path(), true).unwrap();
+        assert_eq!(recursive.len(), 3);
+        assert!(recursive.contains(&nested.join("c.pdf")));
+    }
+}
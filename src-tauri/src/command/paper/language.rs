@@ -0,0 +1,141 @@
+//! Language detection of papers and listing papers by language
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::repository::PaperRepository;
+use crate::service::language_detection_service::{
+    detect_and_store, detect_missing_languages, language_name,
+};
+use crate::sys::error::{AppError, Result};
+
+use super::query::{papers_to_list_dtos, PaginatedPapersDto};
+use super::utils::parse_id;
+
+/// Largest page accepted by `get_papers_by_language`
+const MAX_PAGE_SIZE: u32 = 200;
+
+#[derive(Serialize)]
+pub struct LanguageDetectionDto {
+    pub paper_id: String,
+    /// ISO 639-1 code, e.g. `en`
+    pub language_code: String,
+    pub language_name: String,
+    /// Between 0 and 1
+    pub confidence: f32,
+}
+
+#[derive(Serialize)]
+pub struct LanguageCountDto {
+    pub language_code: String,
+    pub language_name: String,
+    pub paper_count: usize,
+}
+
+#[derive(Serialize)]
+pub struct LanguageDetectionSummaryDto {
+    /// Papers that had no language
+    pub processed: usize,
+    pub detected: usize,
+    pub undetected: usize,
+    /// Most papers first
+    pub languages: Vec<LanguageCountDto>,
+}
+
+/// Detect the language of a paper from its abstract, or its title when it
+/// has no abstract, and store it on the paper
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn detect_paper_language(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<LanguageDetectionDto> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    let detected = detect_and_store(&db, paper_id_num).await?.ok_or_else(|| {
+        AppError::validation(
+            "paper_id",
+            "The language of the paper could not be detected",
+        )
+    })?;
+
+    info!("Paper {} is in {}", paper_id, detected.name);
+    Ok(LanguageDetectionDto {
+        paper_id,
+        language_code: detected.code.to_string(),
+        language_name: detected.name.to_string(),
+        confidence: detected.confidence,
+    })
+}
+
+/// Detect the language of every paper that does not have one yet.
+/// Languages that were set before are kept.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn detect_language_for_all_papers(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<LanguageDetectionSummaryDto> {
+    let summary = detect_missing_languages(&db).await?;
+
+    Ok(LanguageDetectionSummaryDto {
+        processed: summary.processed,
+        detected: summary.detected,
+        undetected: summary.undetected,
+        languages: summary
+            .languages
+            .into_iter()
+            .map(|(code, paper_count)| LanguageCountDto {
+                language_name: language_name(&code).unwrap_or_default().to_string(),
+                language_code: code,
+                paper_count,
+            })
+            .collect(),
+    })
+}
+
+/// Get one page of the papers in a language, newest first. `page` starts
+/// at 1.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_by_language(
+    db: State<'_, Arc<DatabaseConnection>>,
+    language_code: String,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedPapersDto> {
+    if page == 0 {
+        return Err(AppError::validation("page", "Page numbers start at 1"));
+    }
+    if page_size == 0 || page_size > MAX_PAGE_SIZE {
+        return Err(AppError::validation(
+            "page_size",
+            format!("Must be between 1 and {}", MAX_PAGE_SIZE),
+        ));
+    }
+    let language_code = language_code.trim().to_lowercase();
+    if language_code.is_empty() {
+        return Err(AppError::validation(
+            "language_code",
+            "Language code cannot be empty",
+        ));
+    }
+
+    let offset = (page as u64 - 1) * page_size as u64;
+    let limit = page_size as u64;
+    let (papers, total) =
+        PaperRepository::find_by_language_paginated(&db, &language_code, offset, limit).await?;
+    let papers = papers_to_list_dtos(&db, papers).await?;
+
+    Ok(PaginatedPapersDto {
+        has_more: offset + (papers.len() as u64) < total as u64,
+        papers,
+        total,
+        offset,
+        limit,
+    })
+}
@@ -7,6 +7,15 @@
 //! - `mutation`: Write operations (create, update, delete)
 //! - `import`: Import operations (DOI, arXiv, PMID, PDF)
 //! - `attachment`: Attachment operations
+//! - `reference`: Bibliography extraction
+//! - `citation`: Citation links between papers
+//! - `summary`: LLM summaries
+//! - `related`: Related paper recommendations
+//! - `notes`: Versioned notes
+//! - `custom_field`: Custom fields
+//! - `reading_queue`: Ordered reading queue
+//! - `language`: Language detection
+//! - `timeline`: Papers grouped by date added
 
 mod dtos;
 mod utils;
@@ -14,9 +23,29 @@ mod query;
 mod mutation;
 mod import;
 mod attachment;
+mod reference;
+mod citation;
+mod summary;
+mod related;
+mod notes;
+mod custom_field;
+mod reading_queue;
+mod language;
+mod timeline;
 
 // Re-export all commands
+pub use dtos::{DetectedIdentifierDto, IdentifierType, PaperDto, PaperListDto, RelatedPaperDto};
+pub use utils::detect_identifier;
 pub use query::*;
 pub use mutation::*;
 pub use import::*;
 pub use attachment::*;
+pub use reference::*;
+pub use citation::*;
+pub use summary::*;
+pub use related::*;
+pub use notes::*;
+pub use custom_field::*;
+pub use reading_queue::*;
+pub use language::*;
+pub use timeline::*;
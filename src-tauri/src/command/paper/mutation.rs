@@ -7,7 +7,13 @@ use tracing::{info, instrument};
 
 use crate::database::DatabaseConnection;
 use crate::models::UpdatePaper;
-use crate::repository::{LabelRepository, PaperRepository};
+use crate::repository::{
+    AuthorRepository, CategoryRepository, LabelRepository, PaperAuthorEntry, PaperRepository,
+    ReadingQueueRepository,
+};
+use crate::service::activity_service::{self, ACTION_DELETED, ACTION_UPDATED, ENTITY_PAPER};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
 use crate::sys::error::{AppError, Result};
 
 use super::dtos::*;
@@ -25,10 +31,11 @@ pub async fn migrate_abstract_field(
 }
 
 #[tauri::command]
-#[instrument(skip(db))]
+#[instrument(skip(db, app_dirs))]
 pub async fn update_paper_details(
     _app: AppHandle,
     db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
     payload: UpdatePaperDto,
 ) -> Result<()> {
     info!("Updating paper details for id {}", payload.id);
@@ -51,7 +58,7 @@ pub async fn update_paper_details(
             issue: payload.issue,
             pages: payload.pages,
             url: payload.url,
-            read_status: payload.read_status,
+            read_status: payload.read_status.clone(),
             notes: payload.notes,
             attachment_path: None,
             publisher: payload.publisher,
@@ -60,10 +67,70 @@ pub async fn update_paper_details(
         },
     )
     .await?;
+    activity_service::record(&db, ENTITY_PAPER, id_num, ACTION_UPDATED).await;
+
+    if payload.read_status.as_deref() == Some("read")
+        && AppConfig::load(&app_dirs.config)?.paper.dequeue_when_read
+        && ReadingQueueRepository::dequeue(&db, id_num).await?
+    {
+        info!("Removed read paper {} from the reading queue", id_num);
+    }
 
     Ok(())
 }
 
+/// Star or unstar a paper and return whether it is now starred. Papers in
+/// the trash cannot be starred.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn toggle_paper_star(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<bool> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    let starred = PaperRepository::toggle_star(&db, paper_id_num).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!(
+        "Paper {} is now {}",
+        paper_id,
+        if starred { "starred" } else { "unstarred" }
+    );
+    Ok(starred)
+}
+
+/// Star a paper. Starring a starred paper keeps the time it was starred.
+/// Papers in the trash cannot be starred.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn star_paper(db: State<'_, Arc<DatabaseConnection>>, paper_id: String) -> Result<()> {
+    set_paper_star(&db, &paper_id, true).await
+}
+
+/// Unstar a paper
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn unstar_paper(db: State<'_, Arc<DatabaseConnection>>, paper_id: String) -> Result<()> {
+    set_paper_star(&db, &paper_id, false).await
+}
+
+async fn set_paper_star(db: &DatabaseConnection, paper_id: &str, starred: bool) -> Result<()> {
+    let paper_id_num =
+        parse_id(paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    PaperRepository::set_star(db, paper_id_num, starred).await?;
+    activity_service::record(db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!(
+        "Paper {} is now {}",
+        paper_id,
+        if starred { "starred" } else { "unstarred" }
+    );
+    Ok(())
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn delete_paper(
@@ -77,6 +144,7 @@ pub async fn delete_paper(
         .map_err(|_| AppError::validation("id", "Invalid id format"))?;
 
     PaperRepository::soft_delete(&db, id_num).await?;
+    activity_service::record(&db, ENTITY_PAPER, id_num, ACTION_DELETED).await;
 
     Ok(())
 }
@@ -111,15 +179,20 @@ pub async fn permanently_delete_paper(
         .map_err(|_| AppError::validation("id", "Invalid id format"))?;
 
     PaperRepository::delete(&db, id_num).await?;
+    activity_service::record(&db, ENTITY_PAPER, id_num, ACTION_DELETED).await;
 
     Ok(())
 }
 
+/// Move a paper to a category, or take it out of all categories when
+/// `category_id` is empty. With `paper.multi_category` enabled the category
+/// is added next to the paper's other categories instead.
 #[tauri::command]
-#[instrument(skip(db))]
+#[instrument(skip(db, app_dirs))]
 pub async fn update_paper_category(
     _app: AppHandle,
     db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
     paper_id: String,
     category_id: Option<String>,
 ) -> Result<()> {
@@ -136,11 +209,131 @@ pub async fn update_paper_category(
         None
     };
 
-    PaperRepository::set_category(&db, paper_id_num, category_id_num).await?;
+    match category_id_num {
+        Some(cat_id) if AppConfig::load(&app_dirs.config)?.paper.multi_category => {
+            PaperRepository::add_category(&db, paper_id_num, cat_id).await?
+        }
+        _ => PaperRepository::set_category(&db, paper_id_num, category_id_num).await?,
+    }
+
+    Ok(())
+}
+
+/// File a paper under one more category, keeping its other categories.
+/// Requires `paper.multi_category`.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn add_paper_to_category(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    category_id: String,
+) -> Result<()> {
+    info!("Adding paper {} to category {}", paper_id, category_id);
+
+    if !AppConfig::load(&app_dirs.config)?.paper.multi_category {
+        return Err(AppError::validation(
+            "category_id",
+            "Multiple categories per paper are disabled",
+        ));
+    }
+
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+    let category_id_num = parse_id(&category_id)
+        .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
+
+    PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    CategoryRepository::find_by_id(&db, category_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Category", category_id.clone()))?;
+
+    PaperRepository::add_category(&db, paper_id_num, category_id_num).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    Ok(())
+}
+
+/// Take a paper out of one category, keeping its other categories
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn remove_paper_from_category(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    category_id: String,
+) -> Result<()> {
+    info!("Removing paper {} from category {}", paper_id, category_id);
+
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+    let category_id_num = parse_id(&category_id)
+        .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
+
+    PaperRepository::remove_category(&db, paper_id_num, category_id_num).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
 
     Ok(())
 }
 
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn bulk_update_paper_category(
+    _app: AppHandle,
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_ids: Vec<String>,
+    category_id: Option<String>,
+) -> Result<BulkOperationResultDto> {
+    info!(
+        "Bulk updating category for {} papers: {:?}",
+        paper_ids.len(),
+        category_id
+    );
+
+    let category_id_num = if let Some(cat_id) = category_id {
+        let cat_id_num = parse_id(&cat_id)
+            .map_err(|_| AppError::validation("category_id", "Invalid id format"))?;
+        CategoryRepository::find_by_id(&db, cat_id_num)
+            .await?
+            .ok_or_else(|| AppError::not_found("Category", cat_id.clone()))?;
+        Some(cat_id_num)
+    } else {
+        None
+    };
+
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    let mut valid_ids = Vec::with_capacity(paper_ids.len());
+
+    for paper_id in paper_ids {
+        match parse_id(&paper_id) {
+            Ok(id) => valid_ids.push(id),
+            Err(e) => failed.push((paper_id, e)),
+        }
+    }
+
+    let multi_category = AppConfig::load(&app_dirs.config)?.paper.multi_category;
+    let results =
+        PaperRepository::bulk_set_category(&db, &valid_ids, category_id_num, multi_category)
+            .await?;
+    for (paper_id, result) in results {
+        match result {
+            Ok(()) => succeeded.push(paper_id.to_string()),
+            Err(e) => failed.push((paper_id.to_string(), e.to_string())),
+        }
+    }
+
+    info!(
+        "Bulk category update finished: {} succeeded, {} failed",
+        succeeded.len(),
+        failed.len()
+    );
+
+    Ok(BulkOperationResultDto { succeeded, failed })
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn add_paper_label(
@@ -181,6 +374,42 @@ pub async fn remove_paper_label(
     Ok(())
 }
 
+/// Replace a paper's author list, e.g. after drag-reordering it or marking
+/// the corresponding author. Authors left out are only unlinked from the
+/// paper; their author records are kept.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn update_paper_authors(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    authors: Vec<PaperAuthorInputDto>,
+) -> Result<()> {
+    info!("Updating {} authors of paper {}", authors.len(), paper_id);
+
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+    PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+
+    let entries = authors
+        .into_iter()
+        .map(|a| {
+            Ok(PaperAuthorEntry {
+                author_id: parse_id(&a.author_id)
+                    .map_err(|_| AppError::validation("author_id", "Invalid id format"))?,
+                order: a.order,
+                is_corresponding: a.is_corresponding,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    AuthorRepository::set_paper_authors(&db, paper_id_num, &entries).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    Ok(())
+}
+
 /// Repair attachment_count for all papers (development utility)
 #[tauri::command]
 #[instrument(skip(db))]
@@ -0,0 +1,94 @@
+//! Versioned paper notes
+
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::models::{NoteFormat, NoteVersion};
+use crate::repository::NoteRepository;
+use crate::service::activity_service::{self, ACTION_UPDATED, ENTITY_PAPER};
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+use super::utils::parse_id;
+
+fn to_dto(note: NoteVersion) -> NoteVersionDto {
+    NoteVersionDto {
+        paper_id: note.paper_id.to_string(),
+        version: note.version,
+        content: note.content,
+        content_format: note.content_format,
+        created_at: note.created_at.to_rfc3339(),
+    }
+}
+
+fn parse_paper_id(paper_id: &str) -> Result<i64> {
+    parse_id(paper_id).map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))
+}
+
+/// Save new notes for a paper as its next version
+#[tauri::command]
+#[instrument(skip(db, content))]
+pub async fn update_paper_notes(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    content: String,
+    format: NoteFormat,
+) -> Result<NoteVersionDto> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+
+    let note = NoteRepository::save(&db, paper_id_num, &content, format).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!(
+        "Saved notes version {} for paper {}",
+        note.version, paper_id
+    );
+    Ok(to_dto(note))
+}
+
+/// Current notes of a paper, if any were saved
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_notes(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Option<NoteVersionDto>> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    Ok(NoteRepository::latest(&db, paper_id_num).await?.map(to_dto))
+}
+
+/// Every saved version of a paper's notes, newest first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_notes_history(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Vec<NoteVersionDto>> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    let history = NoteRepository::history(&db, paper_id_num).await?;
+    Ok(history.into_iter().map(to_dto).collect())
+}
+
+/// Make an earlier version the current notes. The old content is saved again
+/// as a new version, so later versions stay in the history.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn restore_note_version(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    version: u32,
+) -> Result<NoteVersionDto> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+
+    let note = NoteRepository::restore(&db, paper_id_num, version).await?;
+    activity_service::record(&db, ENTITY_PAPER, paper_id_num, ACTION_UPDATED).await;
+
+    info!(
+        "Restored notes version {} of paper {} as version {}",
+        version, paper_id, note.version
+    );
+    Ok(to_dto(note))
+}
@@ -9,11 +9,22 @@ use tauri::ipc::Channel;
 use tracing::{info, instrument};
 
 use crate::database::DatabaseConnection;
-use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository, PaperRepository};
+use crate::models::Paper;
+use crate::papers::importer::isbn::normalize_isbn;
+use crate::repository::search_repository::fts_phrase;
+use crate::repository::stats_repository::MissingField;
+use crate::repository::{
+    AuthorRepository, CategoryRepository, CustomFieldRepository, LabelRepository, PaperRepository,
+    SearchRepository, StatsRepository,
+};
+use crate::service::activity_service::{self, ACTION_VIEWED, ENTITY_PAPER};
 use crate::sys::error::{AppError, Result};
 
 use super::dtos::*;
-use super::utils::parse_id;
+use super::utils::{parse_id, title_similarity, title_words};
+
+/// Largest page accepted by `get_starred_papers`
+const MAX_STARRED_PAGE_SIZE: u32 = 200;
 
 /// DTO for paper count
 #[derive(Serialize)]
@@ -107,6 +118,7 @@ pub async fn get_all_papers(db: State<'_, Arc<DatabaseConnection>>) -> Result<Ve
                     paper_id: paper.id.to_string(),
                     file_name: a.file_name.clone(),
                     file_type: a.file_type.clone(),
+                    file_size: a.file_size,
                     created_at: Some(a.created_at.to_rfc3339()),
                 })
                 .collect();
@@ -135,6 +147,8 @@ pub async fn get_all_papers(db: State<'_, Arc<DatabaseConnection>>) -> Result<Ve
                 publisher: paper.publisher,
                 issn: paper.issn,
                 language: paper.language,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
             }
         })
         .collect();
@@ -210,6 +224,7 @@ pub async fn get_deleted_papers(db: State<'_, Arc<DatabaseConnection>>) -> Resul
                     paper_id: paper.id.to_string(),
                     file_name: a.file_name.clone(),
                     file_type: a.file_type.clone(),
+                    file_size: a.file_size,
                     created_at: Some(a.created_at.to_rfc3339()),
                 })
                 .collect();
@@ -238,6 +253,8 @@ pub async fn get_deleted_papers(db: State<'_, Arc<DatabaseConnection>>) -> Resul
                 publisher: paper.publisher,
                 issn: paper.issn,
                 language: paper.language,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
             }
         })
         .collect();
@@ -252,6 +269,63 @@ pub async fn get_deleted_papers(db: State<'_, Arc<DatabaseConnection>>) -> Resul
     Ok(result)
 }
 
+/// One page of the starred papers that are not in the trash, most recently
+/// starred first. `page` starts at 1.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_starred_papers(
+    db: State<'_, Arc<DatabaseConnection>>,
+    page: u32,
+    page_size: u32,
+) -> Result<PaginatedPapersDto> {
+    if page == 0 {
+        return Err(AppError::validation("page", "Page numbers start at 1"));
+    }
+    if page_size == 0 || page_size > MAX_STARRED_PAGE_SIZE {
+        return Err(AppError::validation(
+            "page_size",
+            format!("Must be between 1 and {}", MAX_STARRED_PAGE_SIZE),
+        ));
+    }
+
+    let offset = (page as u64 - 1) * page_size as u64;
+    let limit = page_size as u64;
+    let (papers, total) = PaperRepository::find_starred_paginated(&db, offset, limit).await?;
+    let papers = papers_to_list_dtos(&db, papers).await?;
+
+    info!("Found {} starred papers", total);
+    Ok(PaginatedPapersDto {
+        has_more: offset + (papers.len() as u64) < total as u64,
+        papers,
+        total,
+        offset,
+        limit,
+    })
+}
+
+/// Papers missing any of `fields`, most recently added first, so the gaps
+/// can be filled with the enrichment commands
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_with_missing_metadata(
+    db: State<'_, Arc<DatabaseConnection>>,
+    fields: Vec<MissingField>,
+) -> Result<Vec<PaperDto>> {
+    if fields.is_empty() {
+        return Err(AppError::validation(
+            "fields",
+            "At least one field is required",
+        ));
+    }
+
+    let papers = StatsRepository::find_missing_metadata(&db, &fields).await?;
+
+    let result = papers_to_dtos(&db, papers).await?;
+
+    info!("Found {} papers missing {:?}", result.len(), fields);
+    Ok(result)
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn get_paper(
@@ -266,9 +340,19 @@ pub async fn get_paper(
     let paper = PaperRepository::find_by_id(&db, id_num).await?;
 
     if let Some(paper) = paper {
+        activity_service::record(&db, ENTITY_PAPER, paper.id, ACTION_VIEWED).await;
+
         // Get authors
-        let authors = AuthorRepository::get_paper_authors(&db, paper.id).await?;
-        let author_names: Vec<String> = authors.iter().map(|a| a.full_name()).collect();
+        let authors = AuthorRepository::get_paper_authors_with_order(&db, paper.id).await?;
+        let author_dtos: Vec<PaperAuthorDto> = authors
+            .into_iter()
+            .map(|a| PaperAuthorDto {
+                id: a.id.to_string(),
+                name: a.name,
+                order: a.author_order,
+                is_corresponding: a.is_corresponding,
+            })
+            .collect();
 
         // Get labels
         let labels = LabelRepository::get_paper_labels(&db, paper.id).await?;
@@ -281,15 +365,18 @@ pub async fn get_paper(
             })
             .collect();
 
-        // Get category
-        let category_id = PaperRepository::get_category_id(&db, paper.id).await?;
-        let category_name = if let Some(cat_id) = category_id {
-            CategoryRepository::find_by_id(&db, cat_id)
-                .await?
-                .map(|c| c.name)
-        } else {
-            None
-        };
+        // Get categories
+        let mut categories = Vec::new();
+        for cat_id in PaperRepository::get_category_ids(&db, paper.id).await? {
+            if let Some(category) = CategoryRepository::find_by_id(&db, cat_id).await? {
+                categories.push(PaperCategoryDto {
+                    id: category.id.to_string(),
+                    name: category.name,
+                });
+            }
+        }
+        let category_id = categories.first().map(|c| c.id.clone());
+        let category_name = categories.first().map(|c| c.name.clone());
 
         // Get attachments
         let attachments = PaperRepository::get_attachments(&db, paper.id).await?;
@@ -300,11 +387,18 @@ pub async fn get_paper(
                 paper_id: paper.id.to_string(),
                 file_name: a.file_name.clone(),
                 file_type: a.file_type.clone(),
+                file_size: a.file_size,
                 created_at: Some(a.created_at.to_rfc3339()),
             })
             .collect();
         let attachment_count = attachment_dtos.len();
 
+        let custom_fields = CustomFieldRepository::find_by_paper(&db, paper.id)
+            .await?
+            .into_iter()
+            .map(|f| (f.key, f.value))
+            .collect();
+
         Ok(Some(PaperDetailDto {
             id: paper.id.to_string(),
             title: paper.title,
@@ -321,10 +415,11 @@ pub async fn get_paper(
             citation_count: Some(paper.citation_count),
             read_status: Some(paper.read_status),
             notes: paper.notes,
-            authors: author_names,
+            authors: author_dtos,
             labels: label_dtos,
-            category_id: category_id.map(|id| id.to_string()),
+            category_id,
             category_name,
+            categories,
             attachments: attachment_dtos,
             attachment_count,
             created_at: Some(paper.created_at.to_rfc3339()),
@@ -332,6 +427,9 @@ pub async fn get_paper(
             publisher: paper.publisher,
             issn: paper.issn,
             language: paper.language,
+            is_starred: paper.is_starred,
+            starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
+            custom_fields,
         }))
     } else {
         info!("Paper id {} not found", id);
@@ -339,6 +437,214 @@ pub async fn get_paper(
     }
 }
 
+/// Minimum title similarity for a title check to count as a duplicate
+const DUPLICATE_TITLE_THRESHOLD: f32 = 0.9;
+
+/// Number of full-text matches compared when checking a title
+const DUPLICATE_TITLE_CANDIDATES: u64 = 10;
+
+/// Check whether a paper is already in the library before importing it.
+/// Only local lookups are done, so this is cheap enough to call while typing.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn check_duplicate_paper(
+    db: State<'_, Arc<DatabaseConnection>>,
+    identifier: String,
+    identifier_type: IdentifierType,
+) -> Result<DuplicateCheckResult> {
+    let identifier = identifier.trim();
+    if identifier.is_empty() {
+        return Ok(DuplicateCheckResult {
+            is_duplicate: false,
+            existing_paper: None,
+            similarity_score: None,
+        });
+    }
+
+    let (existing, similarity_score) = match identifier_type {
+        IdentifierType::Doi => {
+            let doi = normalize_doi(identifier);
+            (PaperRepository::find_by_doi(&db, doi).await?, None)
+        }
+        IdentifierType::ArxivId => {
+            let arxiv_id = normalize_arxiv_id(identifier);
+            (
+                PaperRepository::find_by_arxiv_id(&db, arxiv_id).await?,
+                None,
+            )
+        }
+        IdentifierType::Pmid => {
+            let url = format!("https://pubmed.ncbi.nlm.nih.gov/{}/", identifier);
+            (PaperRepository::find_by_url(&db, &url).await?, None)
+        }
+        IdentifierType::Isbn => {
+            let isbn = normalize_isbn(identifier).unwrap_or_else(|| identifier.to_string());
+            (PaperRepository::find_by_isbn(&db, &isbn).await?, None)
+        }
+        IdentifierType::Title => match find_similar_title(&db, identifier).await? {
+            Some((paper, score)) => (Some(paper), Some(score)),
+            None => (None, None),
+        },
+    };
+
+    let existing_paper = match existing {
+        Some(paper) if paper.deleted_at.is_none() => Some(paper_to_dto(&db, paper).await?),
+        _ => None,
+    };
+
+    Ok(DuplicateCheckResult {
+        is_duplicate: existing_paper.is_some(),
+        existing_paper,
+        similarity_score,
+    })
+}
+
+fn normalize_doi(doi: &str) -> &str {
+    ["https://doi.org/", "http://doi.org/", "doi:"]
+        .iter()
+        .find_map(|prefix| doi.strip_prefix(prefix))
+        .unwrap_or(doi)
+}
+
+/// Strip an `arXiv:` prefix and version suffix (`2101.00001v2` -> `2101.00001`)
+fn normalize_arxiv_id(arxiv_id: &str) -> &str {
+    let id = arxiv_id.strip_prefix("arXiv:").unwrap_or(arxiv_id);
+    match id.rfind('v') {
+        Some(pos) if pos > 0 && id[pos + 1..].chars().all(|c| c.is_ascii_digit()) => &id[..pos],
+        _ => id,
+    }
+}
+
+/// Best full-text match for a title whose similarity passes the threshold
+async fn find_similar_title(db: &DatabaseConnection, title: &str) -> Result<Option<(Paper, f32)>> {
+    let words = title_words(title);
+    if words.is_empty() {
+        return Ok(None);
+    }
+
+    // Quote every word so punctuation in titles cannot break the FTS5 syntax
+    let query = words
+        .iter()
+        .map(|w| fts_phrase(w))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+
+    let best = SearchRepository::fts_search(db, &query, Some(DUPLICATE_TITLE_CANDIDATES))
+        .await?
+        .into_iter()
+        .map(|(model, _)| {
+            let score = title_similarity(title, &model.title);
+            (Paper::from(model), score)
+        })
+        .filter(|(_, score)| *score > DUPLICATE_TITLE_THRESHOLD)
+        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+    Ok(best)
+}
+
+/// Build the list DTO for a paper, including authors, labels and attachments
+pub async fn paper_to_dto(db: &DatabaseConnection, paper: Paper) -> Result<PaperDto> {
+    let mut dtos = papers_to_dtos(db, vec![paper]).await?;
+    Ok(dtos.remove(0))
+}
+
+/// Build list DTOs for several papers, batching the author, label and
+/// attachment queries
+pub async fn papers_to_dtos(db: &DatabaseConnection, papers: Vec<Paper>) -> Result<Vec<PaperDto>> {
+    let paper_ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let mut authors_map = AuthorRepository::get_paper_authors_batch(db, &paper_ids).await?;
+    let mut labels_map = LabelRepository::get_paper_labels_batch(db, &paper_ids).await?;
+    let mut attachments_map = PaperRepository::get_attachments_batch(db, &paper_ids).await?;
+
+    Ok(papers
+        .into_iter()
+        .map(|paper| {
+            let authors = authors_map.remove(&paper.id).unwrap_or_default();
+            let labels = labels_map.remove(&paper.id).unwrap_or_default();
+            let attachments = attachments_map.remove(&paper.id).unwrap_or_default();
+
+            let attachment_dtos: Vec<AttachmentDto> = attachments
+                .iter()
+                .map(|a| AttachmentDto {
+                    id: a.id.to_string(),
+                    paper_id: paper.id.to_string(),
+                    file_name: a.file_name.clone(),
+                    file_type: a.file_type.clone(),
+                    file_size: a.file_size,
+                    created_at: Some(a.created_at.to_rfc3339()),
+                })
+                .collect();
+
+            PaperDto {
+                id: paper.id.to_string(),
+                title: paper.title,
+                publication_year: paper.publication_year,
+                journal_name: paper.journal_name,
+                conference_name: paper.conference_name,
+                authors: authors.iter().map(|a| a.full_name()).collect(),
+                labels: labels
+                    .into_iter()
+                    .map(|l| LabelDto {
+                        id: l.id.to_string(),
+                        name: l.name,
+                        color: l.color,
+                    })
+                    .collect(),
+                attachment_count: attachment_dtos.len(),
+                attachments: attachment_dtos,
+                publisher: paper.publisher,
+                issn: paper.issn,
+                language: paper.language,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect())
+}
+
+/// Build lightweight list DTOs for a page of papers, batching the author and
+/// attachment queries
+pub async fn papers_to_list_dtos(
+    db: &DatabaseConnection,
+    papers: Vec<Paper>,
+) -> Result<Vec<PaperListDto>> {
+    let paper_ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let attachments_map = PaperRepository::get_attachments_batch(db, &paper_ids).await?;
+    let authors_map = AuthorRepository::get_paper_authors_batch(db, &paper_ids).await?;
+
+    Ok(papers
+        .into_iter()
+        .map(|paper| {
+            let authors = authors_map.get(&paper.id).cloned().unwrap_or_default();
+            let attachments = attachments_map.get(&paper.id).cloned().unwrap_or_default();
+
+            PaperListDto {
+                id: paper.id.to_string(),
+                first_author: authors.first().map(|a| a.full_name()),
+                author_count: authors.len(),
+                attachment_count: attachments.len(),
+                attachments: attachments
+                    .iter()
+                    .map(|a| AttachmentDto {
+                        id: a.id.to_string(),
+                        paper_id: paper.id.to_string(),
+                        file_name: a.file_name.clone(),
+                        file_type: a.file_type.clone(),
+                        file_size: a.file_size,
+                        created_at: Some(a.created_at.to_rfc3339()),
+                    })
+                    .collect(),
+                title: paper.title,
+                publication_year: paper.publication_year,
+                journal_name: paper.journal_name,
+                conference_name: paper.conference_name,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
+            }
+        })
+        .collect())
+}
+
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn get_papers_by_category(
@@ -400,6 +706,7 @@ pub async fn get_papers_by_category(
                     paper_id: paper.id.to_string(),
                     file_name: a.file_name.clone(),
                     file_type: a.file_type.clone(),
+                    file_size: a.file_size,
                     created_at: Some(a.created_at.to_rfc3339()),
                 })
                 .collect();
@@ -428,6 +735,8 @@ pub async fn get_papers_by_category(
                 publisher: paper.publisher,
                 issn: paper.issn,
                 language: paper.language,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
             }
         })
         .collect();
@@ -442,25 +751,32 @@ pub async fn get_papers_by_category(
     Ok(result)
 }
 
+/// One page of papers, newest first. `starred_only` limits the listing to
+/// starred papers.
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn get_papers_paginated(
     db: State<'_, Arc<DatabaseConnection>>,
     offset: u64,
     limit: u64,
+    starred_only: Option<bool>,
 ) -> Result<PaginatedPapersDto> {
     let total_start = Instant::now();
     info!(
-        "[PERF] Starting get_papers_paginated (offset={}, limit={})",
-        offset, limit
+        "[PERF] Starting get_papers_paginated (offset={}, limit={}, starred_only={:?})",
+        offset, limit, starred_only
     );
 
-    // Step 1: Get total count
-    let total = PaperRepository::count(&db).await?;
-
-    // Step 2: Fetch paginated papers
+    // Steps 1-2: Get total count and fetch paginated papers
     let step2_start = Instant::now();
-    let papers = PaperRepository::find_all_paginated(&db, offset, limit).await?;
+    let (papers, total) = if starred_only.unwrap_or(false) {
+        PaperRepository::find_starred_paginated(&db, offset, limit).await?
+    } else {
+        (
+            PaperRepository::find_all_paginated(&db, offset, limit).await?,
+            PaperRepository::count(&db).await?,
+        )
+    };
     let paper_count = papers.len();
     info!(
         "[PERF] Step 2 - find_paginated: {:?}ms, found {} papers",
@@ -518,6 +834,7 @@ pub async fn get_papers_paginated(
                     paper_id: paper.id.to_string(),
                     file_name: a.file_name.clone(),
                     file_type: a.file_type.clone(),
+                    file_size: a.file_size,
                     created_at: Some(a.created_at.to_rfc3339()),
                 })
                 .collect();
@@ -528,6 +845,8 @@ pub async fn get_papers_paginated(
                 publication_year: paper.publication_year,
                 journal_name: paper.journal_name,
                 conference_name: paper.conference_name,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
                 first_author,
                 author_count,
                 attachment_count,
@@ -646,6 +965,8 @@ pub async fn stream_all_papers(
                 publication_year: paper.publication_year,
                 journal_name: paper.journal_name,
                 conference_name: paper.conference_name,
+                is_starred: paper.is_starred,
+                starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
                 first_author,
                 author_count,
                 attachment_count: paper.attachment_count as usize,
@@ -707,6 +1028,8 @@ pub async fn stream_all_papers(
                         publication_year: paper.publication_year,
                         journal_name: paper.journal_name,
                         conference_name: paper.conference_name,
+                        is_starred: paper.is_starred,
+                        starred_at: paper.starred_at.map(|t| t.to_rfc3339()),
                         first_author,
                         author_count,
                         attachment_count: paper.attachment_count as usize,
@@ -763,3 +1086,41 @@ pub async fn stream_all_papers(
         has_more,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_find_similar_title() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Deep Residual Learning for Image Recognition")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Image Recognition with Deep Networks")
+            .insert(&db)
+            .await;
+
+        let (found, score) =
+            find_similar_title(&db, "Deep residual learning for image recognition.")
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(found.id, paper.id);
+        assert!(score > DUPLICATE_TITLE_THRESHOLD);
+
+        // Quotes and other punctuation do not break the full-text query
+        let (found, _) =
+            find_similar_title(&db, "\"Deep\" residual learning: for image (recognition)")
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(found.id, paper.id);
+
+        assert!(find_similar_title(&db, "Attention is all you need")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
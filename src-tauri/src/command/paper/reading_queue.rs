@@ -0,0 +1,171 @@
+//! Ordered "to read next" queue
+//!
+//! The reading list commands work on the same queue and add due dates.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::entities::reading_queue;
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::{PaperRepository, ReadingQueueRepository};
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+use super::query::{paper_to_dto, papers_to_dtos};
+use super::utils::parse_id;
+
+/// Put a paper at the end of the reading queue. A paper that is already
+/// queued is moved to the end.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn enqueue_paper(db: State<'_, Arc<DatabaseConnection>>, paper_id: String) -> Result<()> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    ReadingQueueRepository::enqueue(&db, paper_id_num).await?;
+
+    info!("Queued paper {} for reading", paper_id);
+    Ok(())
+}
+
+/// Take a paper out of the reading queue
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn dequeue_paper(db: State<'_, Arc<DatabaseConnection>>, paper_id: String) -> Result<()> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+
+    if !ReadingQueueRepository::dequeue(&db, paper_id_num).await? {
+        return Err(AppError::not_found("Queued paper", paper_id));
+    }
+
+    info!("Removed paper {} from the reading queue", paper_id);
+    Ok(())
+}
+
+/// Reorder the reading queue. `paper_ids_in_order` must list every queued
+/// paper exactly once, next to read first.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn reorder_reading_queue(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_ids_in_order: Vec<String>,
+) -> Result<()> {
+    let paper_ids = paper_ids_in_order
+        .iter()
+        .map(|id| parse_id(id))
+        .collect::<std::result::Result<Vec<i64>, _>>()
+        .map_err(|_| AppError::validation("paper_ids_in_order", "Invalid id format"))?;
+
+    ReadingQueueRepository::reorder(&db, &paper_ids).await?;
+
+    info!("Reordered reading queue of {} papers", paper_ids.len());
+    Ok(())
+}
+
+/// Queued papers in queue order, next to read first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_reading_queue(db: State<'_, Arc<DatabaseConnection>>) -> Result<Vec<PaperDto>> {
+    let papers = ReadingQueueRepository::find_papers(&db).await?;
+
+    papers_to_dtos(&db, papers).await
+}
+
+fn parse_due_date(due_date: &str) -> Result<DateTime<Utc>> {
+    let due_date = due_date.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(due_date, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    DateTime::parse_from_rfc3339(due_date)
+        .map(|d| d.with_timezone(&Utc))
+        .map_err(|_| {
+            AppError::validation("due_date", "Expected YYYY-MM-DD or an RFC 3339 timestamp")
+        })
+}
+
+fn reading_list_item(entry: reading_queue::Model, paper: &Paper) -> ReadingListItemDto {
+    ReadingListItemDto {
+        paper_id: entry.paper_id.to_string(),
+        title: paper.title.clone(),
+        position: entry.position.max(0) as u32,
+        added_at: entry.added_at.to_rfc3339(),
+        due_date: entry.due_date.map(|d| d.to_rfc3339()),
+    }
+}
+
+/// Add a paper to the reading list, optionally with a due date
+/// (`YYYY-MM-DD` or RFC 3339). A paper already on the list keeps its
+/// position and gets the new due date.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn add_to_reading_list(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    due_date: Option<String>,
+) -> Result<ReadingListItemDto> {
+    let paper_id_num =
+        parse_id(&paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+    let due_date = due_date
+        .filter(|d| !d.trim().is_empty())
+        .map(|d| parse_due_date(&d))
+        .transpose()?;
+
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let entry = ReadingQueueRepository::add(&db, paper_id_num, due_date).await?;
+
+    info!("Added paper {} to the reading list", paper_id);
+    Ok(reading_list_item(entry, &paper))
+}
+
+/// Take a paper off the reading list, the same as `dequeue_paper`
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn remove_from_reading_list(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<()> {
+    dequeue_paper(db, paper_id).await
+}
+
+/// Reorder the reading list, the same as `reorder_reading_queue`
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn reorder_reading_list(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_ids_in_order: Vec<String>,
+) -> Result<()> {
+    reorder_reading_queue(db, paper_ids_in_order).await
+}
+
+/// Reading list entries in queue order
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_reading_list(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Vec<ReadingListItemDto>> {
+    let entries = ReadingQueueRepository::find_entries(&db).await?;
+    Ok(entries
+        .into_iter()
+        .map(|(entry, paper)| reading_list_item(entry, &paper))
+        .collect())
+}
+
+/// Paper to read next: the overdue paper with the earliest due date, or
+/// the head of the reading list when nothing is overdue
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_next_paper_to_read(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<Option<PaperDto>> {
+    match ReadingQueueRepository::find_next(&db, Utc::now()).await? {
+        Some(paper) => Ok(Some(paper_to_dto(&db, paper).await?)),
+        None => Ok(None),
+    }
+}
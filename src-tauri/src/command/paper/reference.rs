@@ -0,0 +1,110 @@
+//! Reference (bibliography) operations for papers
+
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::entities::paper_reference;
+use crate::database::DatabaseConnection;
+use crate::papers::importer::grobid::process_references;
+use crate::repository::{CitationRepository, PaperRepository, ReferenceRepository};
+use crate::service::attachment_service::find_pdf_path;
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+
+fn parse_paper_id(paper_id: &str) -> Result<i64> {
+    paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))
+}
+
+/// Convert stored references to DTOs, linking DOIs found in the library
+async fn to_dtos(
+    db: &DatabaseConnection,
+    references: Vec<paper_reference::Model>,
+) -> Result<Vec<PaperReferenceDto>> {
+    let dois: Vec<String> = references.iter().filter_map(|r| r.doi.clone()).collect();
+    let library = PaperRepository::find_ids_by_dois(db, &dois).await?;
+
+    Ok(references
+        .into_iter()
+        .map(|r| {
+            let library_paper_id = r
+                .doi
+                .as_deref()
+                .and_then(|doi| library.get(&doi.trim().to_lowercase()))
+                .map(|id| id.to_string());
+            PaperReferenceDto {
+                id: r.id.to_string(),
+                position: r.position,
+                is_in_library: library_paper_id.is_some(),
+                library_paper_id,
+                title: r.title,
+                authors: r
+                    .authors
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                year: r.year,
+                doi: r.doi,
+                journal: r.journal,
+                raw_text: r.raw_text,
+            }
+        })
+        .collect())
+}
+
+/// Extract the bibliography of a paper's PDF with GROBID reference
+/// processing, replacing any references extracted before.
+///
+/// References whose DOI matches a paper in the library are also recorded
+/// as citation links. Uses the active GROBID server. A timeout on a large PDF is returned as a
+/// `Timeout` error that can be retried.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn extract_references(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+) -> Result<Vec<PaperReferenceDto>> {
+    let id = parse_paper_id(&paper_id)?;
+    let paper = PaperRepository::find_by_id(&db, id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+    let pdf_path = find_pdf_path(&db, &app_dirs.files, &paper)
+        .await?
+        .ok_or_else(|| AppError::not_found("PDF attachment", paper_id.clone()))?;
+
+    let grobid_url = AppConfig::load(&app_dirs.config)?.paper.grobid.active_url();
+    info!("Extracting references of paper {} with {}", id, grobid_url);
+
+    let references = process_references(&pdf_path, &grobid_url).await?;
+    let saved = ReferenceRepository::replace_for_paper(&db, id, &references).await?;
+    let dtos = to_dtos(&db, saved).await?;
+
+    // References that are themselves in the library become citation links
+    for cited in dtos
+        .iter()
+        .filter_map(|r| r.library_paper_id.as_deref()?.parse::<i64>().ok())
+        .filter(|cited| *cited != id)
+    {
+        CitationRepository::link(&db, id, cited).await?;
+    }
+
+    Ok(dtos)
+}
+
+/// References previously extracted from a paper, in bibliography order
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_references(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Vec<PaperReferenceDto>> {
+    let id = parse_paper_id(&paper_id)?;
+    let references = ReferenceRepository::find_by_paper(&db, id).await?;
+    to_dtos(&db, references).await
+}
@@ -0,0 +1,73 @@
+//! Related papers for the paper detail view
+
+use std::sync::Arc;
+
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::service::related_papers_service::{self, DEFAULT_RELATED_LIMIT};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+use super::query::papers_to_dtos;
+use super::utils::parse_id;
+
+/// Papers related to `paper_id`, best first. Shared by the Tauri command
+/// and the HTTP API.
+pub async fn find_related_papers(
+    db: &DatabaseConnection,
+    config_dir: &str,
+    paper_id: &str,
+    limit: Option<usize>,
+) -> Result<Vec<RelatedPaperDto>> {
+    let paper_id_num =
+        parse_id(paper_id).map_err(|_| AppError::validation("paper_id", "Invalid id format"))?;
+    let config = AppConfig::load(config_dir)?;
+
+    let related = related_papers_service::related_papers(
+        db,
+        &config.embedding.model_name,
+        paper_id_num,
+        limit.unwrap_or(DEFAULT_RELATED_LIMIT),
+    )
+    .await?;
+
+    let (papers, scored): (Vec<_>, Vec<_>) = related
+        .into_iter()
+        .map(|item| (item.paper, (item.score, item.reason)))
+        .unzip();
+    let dtos = papers_to_dtos(db, papers).await?;
+
+    Ok(dtos
+        .into_iter()
+        .zip(scored)
+        .map(|(paper, (score, reason))| RelatedPaperDto {
+            paper,
+            score,
+            reason,
+        })
+        .collect())
+}
+
+/// Papers similar to the given one, with a score and the reason they were
+/// picked. Uses embeddings when the paper has one and the title keywords
+/// otherwise; shared authors and labels raise the score.
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn get_related_papers(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<RelatedPaperDto>> {
+    let related = find_related_papers(&db, &app_dirs.config, &paper_id, limit).await?;
+    info!(
+        "Returning {} related papers for {}",
+        related.len(),
+        paper_id
+    );
+    Ok(related)
+}
@@ -0,0 +1,73 @@
+//! LLM summaries of papers
+
+use std::sync::Arc;
+
+use tauri::ipc::Channel;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::entities::paper_ai_summary;
+use crate::database::DatabaseConnection;
+use crate::repository::AiSummaryRepository;
+use crate::service::summary_service::{self, SummaryStyle};
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::*;
+
+fn to_dto(summary: paper_ai_summary::Model) -> PaperSummaryDto {
+    PaperSummaryDto {
+        paper_id: summary.paper_id.to_string(),
+        style: summary.style,
+        summary: summary.summary,
+        model: summary.model,
+        created_at: summary.created_at.to_rfc3339(),
+    }
+}
+
+/// Summarize a paper with the configured LLM provider. Pieces of the answer
+/// are sent on `on_token` as they arrive; the stored summary is returned
+/// once the model has finished.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, on_token))]
+pub async fn summarize_paper(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    paper_id: String,
+    style: SummaryStyle,
+    on_token: Channel<String>,
+) -> Result<PaperSummaryDto> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let summary = summary_service::summarize_paper(
+        &db,
+        &app_dirs.config,
+        &app_dirs.files,
+        paper_id_num,
+        style,
+        |token| {
+            let _ = on_token.send(token.to_string());
+        },
+    )
+    .await?;
+
+    info!("Stored {} summary for paper {}", summary.style, paper_id);
+    Ok(to_dto(summary))
+}
+
+/// Stored summaries of a paper, newest first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_paper_summaries(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Vec<PaperSummaryDto>> {
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let summaries = AiSummaryRepository::find_by_paper(&db, paper_id_num).await?;
+    Ok(summaries.into_iter().map(to_dto).collect())
+}
@@ -0,0 +1,100 @@
+//! Library timeline: papers grouped by when they were added
+
+use std::sync::Arc;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::service::paper_timeline_service::{papers_in_bucket, time_zone, AddedBucket};
+use crate::sys::error::{AppError, Result};
+
+use super::dtos::PaperListDto;
+use super::query::{papers_to_list_dtos, PaginatedPapersDto};
+
+/// Largest page accepted by the timeline commands
+const MAX_PAGE_SIZE: u64 = 200;
+
+#[derive(Serialize)]
+pub struct AddedBucketDto {
+    pub bucket: AddedBucket,
+    /// Papers in the bucket
+    pub total: i64,
+    /// The newest papers of the bucket
+    pub papers: Vec<PaperListDto>,
+    pub has_more: bool,
+}
+
+fn check_page_size(field: &str, size: u64) -> Result<()> {
+    if size == 0 || size > MAX_PAGE_SIZE {
+        return Err(AppError::validation(
+            field,
+            format!("Must be between 1 and {}", MAX_PAGE_SIZE),
+        ));
+    }
+    Ok(())
+}
+
+/// Group papers by when they were added: today, this week, this month and
+/// earlier, newest first. Each group has its paper count and its first
+/// `papers_per_bucket` papers; `get_papers_in_bucket` pages through the
+/// rest. Days start at midnight `utc_offset_minutes` east of UTC, which is
+/// `-new Date().getTimezoneOffset()` in the frontend.
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_grouped_by_added(
+    db: State<'_, Arc<DatabaseConnection>>,
+    utc_offset_minutes: i32,
+    papers_per_bucket: u64,
+) -> Result<Vec<AddedBucketDto>> {
+    check_page_size("papers_per_bucket", papers_per_bucket)?;
+    let zone = time_zone(utc_offset_minutes)?;
+    let now = Utc::now();
+
+    let mut buckets = Vec::new();
+    for bucket in AddedBucket::ALL {
+        let (papers, total) =
+            papers_in_bucket(&db, bucket, now, zone, 0, papers_per_bucket).await?;
+        let papers = papers_to_list_dtos(&db, papers).await?;
+        buckets.push(AddedBucketDto {
+            bucket,
+            has_more: (papers.len() as i64) < total,
+            total,
+            papers,
+        });
+    }
+
+    info!(
+        "Grouped papers by date added: {:?}",
+        buckets.iter().map(|b| b.total).collect::<Vec<_>>()
+    );
+    Ok(buckets)
+}
+
+/// One page of the papers in a group of `get_papers_grouped_by_added`,
+/// newest first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_papers_in_bucket(
+    db: State<'_, Arc<DatabaseConnection>>,
+    bucket: AddedBucket,
+    offset: u64,
+    limit: u64,
+    utc_offset_minutes: i32,
+) -> Result<PaginatedPapersDto> {
+    check_page_size("limit", limit)?;
+    let zone = time_zone(utc_offset_minutes)?;
+
+    let (papers, total) = papers_in_bucket(&db, bucket, Utc::now(), zone, offset, limit).await?;
+    let papers = papers_to_list_dtos(&db, papers).await?;
+
+    Ok(PaginatedPapersDto {
+        has_more: offset + (papers.len() as u64) < total as u64,
+        papers,
+        total,
+        offset,
+        limit,
+    })
+}
@@ -1,28 +1,269 @@
 //! Utility functions for paper commands
 
-use sha1::{Digest, Sha1};
+use std::collections::HashSet;
+use std::io::{self, Read};
 
-/// Calculate SHA1 hash of title for attachment path
-pub fn calculate_attachment_hash(title: &str) -> String {
-    let mut hasher = Sha1::new();
-    hasher.update(title.as_bytes());
-    let result = hasher.finalize();
-    format!("{:x}", result)
-}
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::write::EncoderStringWriter;
+use base64::{DecodeError, Engine as _};
+use regex::Regex;
+
+use super::dtos::{DetectedIdentifierDto, IdentifierType};
+use crate::papers::importer::isbn::normalize_isbn;
+
+pub use crate::models::calculate_attachment_hash;
+
+/// Standard alphabet; decoding accepts input with or without `=` padding
+const BASE64_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
 
 /// Base64 encoding
 pub fn base64_encode(data: &[u8]) -> String {
-    use base64::{Engine as _, engine::general_purpose};
-    general_purpose::STANDARD.encode(data)
+    BASE64_ENGINE.encode(data)
+}
+
+/// Base64 encode everything read from `reader` into a single string.
+///
+/// The input is streamed through the encoder, so a large PDF is never held
+/// in memory as both raw bytes and encoded text. `size_hint` (the input
+/// length, if known) pre-sizes the output to avoid reallocations.
+pub fn base64_encode_reader<R: Read>(reader: &mut R, size_hint: Option<u64>) -> io::Result<String> {
+    let capacity = size_hint
+        .and_then(|len| base64::encoded_len(len as usize, true))
+        .unwrap_or(0);
+
+    let mut encoder =
+        EncoderStringWriter::from_consumer(String::with_capacity(capacity), &BASE64_ENGINE);
+    io::copy(reader, &mut encoder)?;
+    Ok(encoder.into_inner())
 }
 
 /// Base64 decoding
+///
+/// Accepts padded and unpadded input. Whitespace, characters outside the
+/// standard alphabet and truncated input are rejected with a descriptive error.
 pub fn base64_decode(data: &str) -> std::result::Result<Vec<u8>, String> {
-    use base64::{Engine as _, engine::general_purpose};
-    general_purpose::STANDARD.decode(data).map_err(|e| e.to_string())
+    BASE64_ENGINE.decode(data).map_err(|e| match e {
+        DecodeError::InvalidByte(offset, byte) => format!(
+            "Invalid character {:?} at position {}",
+            byte as char, offset
+        ),
+        DecodeError::InvalidLength(len) => format!(
+            "Invalid length: {} symbols cannot be valid base64 (input may be truncated)",
+            len
+        ),
+        DecodeError::InvalidLastSymbol(offset, byte) => format!(
+            "Invalid last symbol {:?} at position {}",
+            byte as char, offset
+        ),
+        DecodeError::InvalidPadding => "Invalid padding".to_string(),
+    })
 }
 
 /// Parse string ID to i64
 pub fn parse_id(id: &str) -> Result<i64, String> {
-    id.parse::<i64>().map_err(|_| format!("Invalid id format: {}", id))
+    id.parse::<i64>()
+        .map_err(|_| format!("Invalid id format: {}", id))
+}
+
+/// Lowercased alphanumeric words of a title, used for duplicate detection
+pub fn title_words(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Dice similarity of the word sets of two titles, in `0.0..=1.0`
+pub fn title_similarity(a: &str, b: &str) -> f32 {
+    let a: HashSet<String> = title_words(a).into_iter().collect();
+    let b: HashSet<String> = title_words(b).into_iter().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let shared = a.intersection(&b).count();
+    (2 * shared) as f32 / (a.len() + b.len()) as f32
+}
+
+/// Longest text searched for an identifier; longer clipboard contents are
+/// more likely a document than something copied to import
+const MAX_IDENTIFIER_TEXT_CHARS: usize = 2_000;
+
+/// Find a DOI, arXiv ID, ISBN or PMID in free text. DOIs win over arXiv IDs,
+/// which win over ISBNs; a PMID is only recognized when it is the whole text.
+/// Confidence is higher when the identifier is the whole text or carries a
+/// prefix such as `doi:`, `arXiv:` or `PMID:`.
+pub fn detect_identifier(text: &str) -> Option<DetectedIdentifierDto> {
+    let text = text.trim();
+    if text.is_empty() || text.chars().count() > MAX_IDENTIFIER_TEXT_CHARS {
+        return None;
+    }
+    let detected = |identifier_type, value: &str, confidence| {
+        Some(DetectedIdentifierDto {
+            identifier_type,
+            value: value.to_string(),
+            confidence,
+        })
+    };
+
+    let doi_pattern = Regex::new(r"10\.\d{4,}/\S+").unwrap();
+    if let Some(m) = doi_pattern.find(text) {
+        let doi = m
+            .as_str()
+            .trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '>', '"', '\'']);
+        let prefix = text[..m.start()].to_lowercase();
+        let whole = m.end() == text.len()
+            && (prefix.is_empty()
+                || prefix == "doi:"
+                || prefix == "doi: "
+                || prefix.ends_with("doi.org/"));
+        return detected(IdentifierType::Doi, doi, if whole { 0.95 } else { 0.8 });
+    }
+
+    let arxiv_pattern =
+        Regex::new(r"(?i)(arxiv[:/]\s*|abs/|pdf/)?\b(\d{4}\.\d{4,5}(?:v\d+)?)\b").unwrap();
+    if let Some(caps) = arxiv_pattern.captures(text) {
+        let id = caps.get(2).unwrap();
+        let confidence = if caps.get(1).is_some() || id.as_str() == text {
+            0.9
+        } else {
+            0.6
+        };
+        return detected(IdentifierType::ArxivId, id.as_str(), confidence);
+    }
+    let old_arxiv_pattern =
+        Regex::new(r"^(?i:arxiv:\s*)?([a-z-]+(?:\.[A-Z]{2})?/\d{7}(?:v\d+)?)$").unwrap();
+    if let Some(caps) = old_arxiv_pattern.captures(text) {
+        return detected(IdentifierType::ArxivId, &caps[1], 0.7);
+    }
+
+    let isbn_pattern = Regex::new(r"\b(?:\d[- ]?){9,12}[\dXx]\b").unwrap();
+    if let Some(isbn) = isbn_pattern
+        .find_iter(text)
+        .find_map(|m| normalize_isbn(m.as_str()))
+    {
+        return detected(IdentifierType::Isbn, &isbn, 0.9);
+    }
+
+    let pmid_pattern = Regex::new(r"^(?i:pmid:?\s*)?(\d{5,8})$").unwrap();
+    if let Some(caps) = pmid_pattern.captures(text) {
+        let confidence = if caps[0].len() > caps[1].len() {
+            0.9
+        } else {
+            0.5
+        };
+        return detected(IdentifierType::Pmid, &caps[1], confidence);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_empty_input() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_decode("").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_base64_with_padding() {
+        assert_eq!(base64_encode(b"PDF"), "UERG");
+        assert_eq!(base64_encode(b"PD"), "UEQ=");
+        assert_eq!(base64_decode("UEQ=").unwrap(), b"PD");
+        assert_eq!(base64_decode("UA==").unwrap(), b"P");
+    }
+
+    #[test]
+    fn test_base64_without_padding() {
+        assert_eq!(base64_decode("UEQ").unwrap(), b"PD");
+        assert_eq!(base64_decode("UA").unwrap(), b"P");
+    }
+
+    #[test]
+    fn test_base64_corrupted_data() {
+        let err = base64_decode("UE*G").unwrap_err();
+        assert!(
+            err.contains("Invalid character '*' at position 2"),
+            "{}",
+            err
+        );
+
+        let err = base64_decode("UEQ=\nUEQ=").unwrap_err();
+        assert!(err.contains("Invalid character"), "{}", err);
+
+        let err = base64_decode("UERGU").unwrap_err();
+        assert!(err.contains("Invalid length"), "{}", err);
+
+        assert!(base64_decode("UE Q=").is_err());
+    }
+
+    #[test]
+    fn test_base64_encode_reader_matches_encode() {
+        let data: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+        let encoded = base64_encode_reader(&mut data.as_slice(), Some(data.len() as u64)).unwrap();
+        assert_eq!(encoded, base64_encode(&data));
+        assert_eq!(base64_decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_title_similarity_ignores_case_and_punctuation() {
+        let score = title_similarity("Attention Is All You Need", "attention is all you need.");
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_title_similarity_of_different_titles() {
+        let score = title_similarity(
+            "Attention Is All You Need",
+            "Deep Residual Learning for Image Recognition",
+        );
+        assert!(score < 0.2);
+        assert_eq!(title_similarity("", "Anything"), 0.0);
+    }
+
+    #[test]
+    fn test_detect_identifier() {
+        let detect = |text: &str| {
+            detect_identifier(text).map(|d| (d.identifier_type, d.value, d.confidence))
+        };
+
+        assert_eq!(
+            detect("https://doi.org/10.1038/nature14539."),
+            Some((IdentifierType::Doi, "10.1038/nature14539".to_string(), 0.95))
+        );
+        assert_eq!(
+            detect("see (10.1038/nature14539) for details"),
+            Some((IdentifierType::Doi, "10.1038/nature14539".to_string(), 0.8))
+        );
+        assert_eq!(
+            detect("arXiv:2101.00001v2"),
+            Some((IdentifierType::ArxivId, "2101.00001v2".to_string(), 0.9))
+        );
+        assert_eq!(
+            detect("hep-th/9901001"),
+            Some((IdentifierType::ArxivId, "hep-th/9901001".to_string(), 0.7))
+        );
+        assert_eq!(
+            detect("ISBN 0-306-40615-2"),
+            Some((IdentifierType::Isbn, "9780306406157".to_string(), 0.9))
+        );
+        assert_eq!(
+            detect("PMID: 12345678"),
+            Some((IdentifierType::Pmid, "12345678".to_string(), 0.9))
+        );
+        assert_eq!(
+            detect(" 1234567 "),
+            Some((IdentifierType::Pmid, "1234567".to_string(), 0.5))
+        );
+        assert_eq!(detect("just some words"), None);
+        assert_eq!(detect("123"), None);
+    }
 }
@@ -0,0 +1,45 @@
+//! Tauri commands for quiet hours and focus mode
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::service::quiet_hours_service::{current_pause_reason, FocusModeState};
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::Result;
+
+#[derive(Serialize)]
+pub struct BackgroundStatusDto {
+    pub focus_mode: bool,
+    pub paused: bool,
+    /// `"running"`, or e.g. `"paused (quiet hours until 14:00)"`
+    pub status: String,
+}
+
+/// Turn focus mode on or off. While on, scheduled background work is
+/// deferred just like during quiet hours.
+#[tauri::command]
+#[instrument(skip(focus_mode))]
+pub async fn set_focus_mode(focus_mode: State<'_, FocusModeState>, enabled: bool) -> Result<()> {
+    info!("Setting focus mode: {}", enabled);
+    focus_mode.set(enabled);
+    Ok(())
+}
+
+/// Get whether background work is running or paused, and why
+#[tauri::command]
+#[instrument(skip(app_dirs, focus_mode))]
+pub async fn get_background_status(
+    app_dirs: State<'_, AppDirs>,
+    focus_mode: State<'_, FocusModeState>,
+) -> Result<BackgroundStatusDto> {
+    let reason = current_pause_reason(&app_dirs, &focus_mode);
+
+    Ok(BackgroundStatusDto {
+        focus_mode: focus_mode.get(),
+        paused: reason.is_some(),
+        status: reason
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| "running".to_string()),
+    })
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::entities::{reading_progress, reading_session};
+use crate::database::DatabaseConnection;
+use crate::repository::{PaperRepository, ReadingProgressRepository};
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct ReadingSessionDto {
+    pub started_at: String,
+    pub ended_at: String,
+    pub pages_read: u32,
+}
+
+#[derive(Serialize)]
+pub struct ReadingProgressDto {
+    pub paper_id: String,
+    pub current_page: u32,
+    pub total_pages: u32,
+    /// `current_page / total_pages` as a percentage
+    pub reading_percentage: f32,
+    pub last_read_at: String,
+    pub reading_sessions: Vec<ReadingSessionDto>,
+}
+
+impl ReadingProgressDto {
+    fn new(progress: reading_progress::Model, sessions: Vec<reading_session::Model>) -> Self {
+        let current_page = progress.current_page.max(0) as u32;
+        let total_pages = progress.total_pages.max(0) as u32;
+        let reading_percentage = if total_pages == 0 {
+            0.0
+        } else {
+            current_page as f32 / total_pages as f32 * 100.0
+        };
+
+        Self {
+            paper_id: progress.paper_id.to_string(),
+            current_page,
+            total_pages,
+            reading_percentage,
+            last_read_at: progress.last_read_at.to_rfc3339(),
+            reading_sessions: sessions
+                .into_iter()
+                .map(|s| ReadingSessionDto {
+                    started_at: s.started_at.to_rfc3339(),
+                    ended_at: s.ended_at.to_rfc3339(),
+                    pages_read: s.pages_read.max(0) as u32,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn parse_paper_id(paper_id: &str) -> Result<i64> {
+    paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))
+}
+
+/// Record the page currently shown in the PDF viewer
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn update_reading_progress(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+    current_page: u32,
+    total_pages: u32,
+) -> Result<ReadingProgressDto> {
+    let paper_id_num = parse_paper_id(&paper_id)?;
+
+    if total_pages == 0 {
+        return Err(AppError::validation(
+            "total_pages",
+            "Total pages must be greater than zero",
+        ));
+    }
+    if current_page == 0 || current_page > total_pages {
+        return Err(AppError::validation(
+            "current_page",
+            format!("Page must be between 1 and {}", total_pages),
+        ));
+    }
+
+    PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+
+    let (progress, sessions) = ReadingProgressRepository::record(
+        &db,
+        paper_id_num,
+        current_page as i32,
+        total_pages as i32,
+        chrono::Utc::now(),
+    )
+    .await?;
+
+    Ok(ReadingProgressDto::new(progress, sessions))
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_reading_progress(
+    db: State<'_, Arc<DatabaseConnection>>,
+    paper_id: String,
+) -> Result<Option<ReadingProgressDto>> {
+    info!("Fetching reading progress for paper {}", paper_id);
+
+    let paper_id_num = parse_paper_id(&paper_id)?;
+    let progress = ReadingProgressRepository::find(&db, paper_id_num).await?;
+
+    Ok(progress.map(|(progress, sessions)| ReadingProgressDto::new(progress, sessions)))
+}
@@ -6,11 +6,15 @@
 use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
 use tauri::State;
 use tracing::{info, instrument};
 
 use crate::database::DatabaseConnection;
-use crate::repository::{PaperRepository, SearchRepository};
+use crate::repository::{PaperRepository, PaperSearchFilter, SearchRepository};
+use crate::service::embedding_service::{self, ReindexProgress, DEFAULT_SEMANTIC_LIMIT};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
 use crate::sys::error::Result;
 
 /// Search result with relevance score
@@ -26,6 +30,7 @@ pub struct SearchResultDto {
     pub publication_year: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub journal_name: Option<String>,
+    pub is_starred: bool,
     /// Relevance score (0-100, higher is better)
     pub score: f64,
     /// Labels that matched the search query
@@ -34,16 +39,83 @@ pub struct SearchResultDto {
     pub matched_attachments: Vec<String>,
 }
 
+/// Clipping search result with relevance score
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ClipSearchResultDto {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_domain: Option<String>,
+    /// Relevance score (0-100, higher is better)
+    pub score: f64,
+}
+
+/// Paper whose current note matches a note search
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NoteSearchResultDto {
+    pub paper_id: String,
+    pub paper_title: String,
+    /// About 200 characters of the note around the first match
+    pub note_excerpt: String,
+    /// Byte spans `(start, end)` of the matches within `note_excerpt`
+    pub match_positions: Vec<(usize, usize)>,
+}
+
+/// Semantic search result with vector and (in hybrid mode) BM25 scores
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SemanticSearchResultDto {
+    pub id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abstract_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publication_year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub journal_name: Option<String>,
+    /// Combined score (0-1, higher is better)
+    pub score: f32,
+    /// Cosine similarity to the query (0-1)
+    pub vector_score: f32,
+    /// Full-text relevance (0-1); only present in hybrid mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bm25_score: Option<f32>,
+}
+
+/// One hit of `search_all`, tagged with `kind: "paper"` or `kind: "clip"`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchHitDto {
+    Paper(SearchResultDto),
+    Clip(ClipSearchResultDto),
+}
+
+impl SearchHitDto {
+    fn score(&self) -> f64 {
+        match self {
+            Self::Paper(hit) => hit.score,
+            Self::Clip(hit) => hit.score,
+        }
+    }
+}
+
 /// Search papers using SQLite LIKE query (legacy, kept for compatibility)
+///
+/// `starred_only` limits the results to starred papers
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn search_papers(
     db: State<'_, Arc<DatabaseConnection>>,
     query: String,
+    starred_only: Option<bool>,
 ) -> Result<Vec<SearchResultDto>> {
     info!("Searching papers with query: {}", query);
 
-    let papers = PaperRepository::search(&db, &query).await?;
+    let papers = PaperRepository::search(&db, &query, starred_only.unwrap_or(false)).await?;
 
     let results: Vec<SearchResultDto> = papers
         .into_iter()
@@ -54,6 +126,7 @@ pub async fn search_papers(
             doi: p.doi,
             publication_year: p.publication_year,
             journal_name: p.journal_name,
+            is_starred: p.is_starred,
             score: 0.0, // No score for simple search
             matched_labels: vec![],
             matched_attachments: vec![],
@@ -74,22 +147,45 @@ pub async fn search_papers(
 /// # Arguments
 /// * `query` - Search query string (supports FTS5 query syntax like AND, OR, NOT)
 /// * `limit` - Maximum number of results (default: 50)
+/// * `starred_only` - Only return starred papers
+///
+/// Terms of the form `custom:key=value` are not searched for but keep only
+/// papers whose custom field `key` equals `value`; quote values that contain
+/// spaces. A query of only such terms lists every matching paper.
 #[tauri::command]
 #[instrument(skip(db))]
 pub async fn search_papers_fts(
     db: State<'_, Arc<DatabaseConnection>>,
     query: String,
     limit: Option<i32>,
+    starred_only: Option<bool>,
 ) -> Result<Vec<SearchResultDto>> {
     info!("FTS search with query: '{}'", query);
 
+    let mut filter = PaperSearchFilter {
+        starred_only: starred_only.unwrap_or(false),
+        ..Default::default()
+    };
+    let text = filter.extract_custom_terms(query.trim());
+
     // Validate query
-    let query = query.trim();
-    if query.is_empty() {
+    if text.is_empty() && filter.custom_fields.is_empty() {
         return Ok(vec![]);
     }
 
-    let results = SearchRepository::fts_search(&db, query, limit.map(|l| l as u64)).await?;
+    let dtos = fts_search_papers(&db, &text, limit.map(|l| l as u64), &filter).await?;
+
+    info!("FTS search found {} results", dtos.len());
+    Ok(dtos)
+}
+
+async fn fts_search_papers(
+    db: &DatabaseConnection,
+    query: &str,
+    limit: Option<u64>,
+    filter: &PaperSearchFilter,
+) -> Result<Vec<SearchResultDto>> {
+    let results = SearchRepository::fts_search_filtered(db, query, limit, filter).await?;
 
     // Convert to DTO
     let dtos: Vec<SearchResultDto> = results
@@ -105,6 +201,7 @@ pub async fn search_papers_fts(
                 doi: paper.doi,
                 publication_year: paper.publication_year,
                 journal_name: paper.journal_name,
+                is_starred: paper.is_starred,
                 score,
                 matched_labels: vec![], // TODO: Extract from FTS snippet
                 matched_attachments: vec![], // TODO: Extract from FTS snippet
@@ -112,10 +209,196 @@ pub async fn search_papers_fts(
         })
         .collect();
 
-    info!("FTS search found {} results", dtos.len());
     Ok(dtos)
 }
 
+async fn fts_search_clips(
+    db: &DatabaseConnection,
+    query: &str,
+    limit: Option<u64>,
+) -> Result<Vec<ClipSearchResultDto>> {
+    let results = SearchRepository::clip_fts_search(db, query, limit).await?;
+
+    Ok(results
+        .into_iter()
+        .map(|(clip, score)| ClipSearchResultDto {
+            id: clip.id.to_string(),
+            title: clip.title,
+            url: clip.url,
+            excerpt: clip.excerpt,
+            source_domain: clip.source_domain,
+            score,
+        })
+        .collect())
+}
+
+/// Full-text search across clipping title, excerpt and content
+///
+/// # Arguments
+/// * `query` - Search words; every word must match
+/// * `limit` - Maximum number of results (default: 50)
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_clips(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+    limit: Option<i32>,
+) -> Result<Vec<ClipSearchResultDto>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let dtos = fts_search_clips(&db, query, limit.map(|l| l as u64)).await?;
+    info!("Clip search for '{}' found {} results", query, dtos.len());
+    Ok(dtos)
+}
+
+/// Full-text search across the current notes of all papers
+///
+/// # Arguments
+/// * `query` - Search words; every word must match
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_paper_notes(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+) -> Result<Vec<NoteSearchResultDto>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let dtos: Vec<NoteSearchResultDto> = SearchRepository::note_fts_search(&db, query, None)
+        .await?
+        .into_iter()
+        .map(|hit| {
+            let (note_excerpt, match_positions) =
+                SearchRepository::note_excerpt(&hit.content, query);
+            NoteSearchResultDto {
+                paper_id: hit.paper_id.to_string(),
+                paper_title: hit.paper_title,
+                note_excerpt,
+                match_positions,
+            }
+        })
+        .collect();
+
+    info!("Note search for '{}' found {} results", query, dtos.len());
+    Ok(dtos)
+}
+
+/// Search papers and clippings together
+///
+/// Returns one list of paper and clip hits sorted by score, best first
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn search_all(
+    db: State<'_, Arc<DatabaseConnection>>,
+    query: String,
+) -> Result<Vec<SearchHitDto>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut hits: Vec<SearchHitDto> =
+        fts_search_papers(&db, query, None, &PaperSearchFilter::default())
+            .await?
+            .into_iter()
+            .map(SearchHitDto::Paper)
+            .collect();
+    hits.extend(
+        fts_search_clips(&db, query, None)
+            .await?
+            .into_iter()
+            .map(SearchHitDto::Clip),
+    );
+    hits.sort_by(|a, b| b.score().total_cmp(&a.score()));
+
+    info!(
+        "Combined search for '{}' found {} results",
+        query,
+        hits.len()
+    );
+    Ok(hits)
+}
+
+/// Search papers by meaning using their embeddings
+///
+/// # Arguments
+/// * `query` - Free text describing what to find
+/// * `k` - Maximum number of results (default: 20)
+/// * `bm25_weight` - Share of the score taken from FTS5 BM25 relevance,
+///   from 0 (vectors only, the default) to 1 (BM25 only)
+#[tauri::command]
+#[instrument(skip(db, app_dirs))]
+pub async fn search_papers_semantic(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    query: String,
+    k: Option<usize>,
+    bm25_weight: Option<f32>,
+) -> Result<Vec<SemanticSearchResultDto>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let embedding = AppConfig::load(&app_dirs.config)?
+        .embedding
+        .with_stored_api_key();
+    let hits = embedding_service::search_semantic(
+        &db,
+        &embedding,
+        query,
+        k.unwrap_or(DEFAULT_SEMANTIC_LIMIT),
+        bm25_weight.unwrap_or(0.0),
+    )
+    .await?;
+
+    info!(
+        "Semantic search for '{}' found {} results",
+        query,
+        hits.len()
+    );
+    Ok(hits
+        .into_iter()
+        .map(|hit| SemanticSearchResultDto {
+            id: hit.paper.id.to_string(),
+            title: hit.paper.title,
+            abstract_text: hit.paper.abstract_text,
+            doi: hit.paper.doi,
+            publication_year: hit.paper.publication_year,
+            journal_name: hit.paper.journal_name,
+            score: hit.score,
+            vector_score: hit.vector_score,
+            bm25_score: hit.bm25_score,
+        })
+        .collect())
+}
+
+/// Embed papers whose embedding is missing or stale
+///
+/// Progress is sent on `on_progress` after every batch. Finished batches
+/// are kept if the run fails, so calling this again picks up where it
+/// stopped.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, on_progress))]
+pub async fn reindex_embeddings(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    on_progress: Channel<ReindexProgress>,
+) -> Result<ReindexProgress> {
+    let embedding = AppConfig::load(&app_dirs.config)?
+        .embedding
+        .with_stored_api_key();
+    embedding_service::reindex_embeddings(&db, &embedding, |progress| {
+        let _ = on_progress.send(progress.clone());
+    })
+    .await
+}
+
 /// Get search suggestions for autocomplete
 ///
 /// Returns paper titles that start with the given prefix
@@ -148,6 +431,8 @@ pub async fn rebuild_search_index(db: State<'_, Arc<DatabaseConnection>>) -> Res
     info!("Rebuilding search index");
 
     SearchRepository::rebuild_fts_index(&db).await?;
+    SearchRepository::rebuild_clip_fts_index(&db).await?;
+    SearchRepository::rebuild_note_fts_index(db.inner().as_ref()).await?;
 
     info!("Search index rebuilt successfully");
     Ok(())
@@ -0,0 +1,83 @@
+//! Tauri commands for temporary sharing over the local API server
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::axum::state::ApiServerState;
+use crate::database::DatabaseConnection;
+use crate::repository::{AuthorRepository, PaperRepository};
+use crate::service::share_service::{
+    load_highlights, render_notes_page, ShareRegistry, MAX_SHARE_TTL_SECS,
+};
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct ShareLinkDto {
+    pub token: String,
+    pub url: String,
+    pub expires_at: String,
+}
+
+/// Share a paper's notes and highlights as a one-time HTML page.
+///
+/// `ttl` is the link lifetime in seconds. With `include_notes = false` only
+/// the PDF highlights are rendered and the private notes stay out of the page.
+#[tauri::command]
+#[instrument(skip(db, app_dirs, registry, server_state))]
+pub async fn share_paper_notes(
+    db: State<'_, Arc<DatabaseConnection>>,
+    app_dirs: State<'_, AppDirs>,
+    registry: State<'_, ShareRegistry>,
+    server_state: State<'_, ApiServerState>,
+    paper_id: String,
+    ttl: u64,
+    include_notes: bool,
+) -> Result<ShareLinkDto> {
+    info!("Sharing notes for paper {} (ttl {}s)", paper_id, ttl);
+
+    if ttl == 0 || ttl > MAX_SHARE_TTL_SECS {
+        return Err(AppError::validation(
+            "ttl",
+            format!("TTL must be between 1 and {} seconds", MAX_SHARE_TTL_SECS),
+        ));
+    }
+
+    let base_url = server_state
+        .base_url()
+        .ok_or_else(|| AppError::validation("api", "The local API server is not running"))?;
+
+    let paper_id_num = paper_id
+        .parse::<i64>()
+        .map_err(|_| AppError::validation("paper_id", "Invalid paper id format"))?;
+
+    let paper = PaperRepository::find_by_id(&db, paper_id_num)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.clone()))?;
+
+    let authors: Vec<String> = AuthorRepository::get_paper_authors(&db, paper.id)
+        .await?
+        .iter()
+        .map(|a| a.full_name())
+        .collect();
+
+    let highlights = load_highlights(&db, &app_dirs.files, &paper).await?;
+
+    let notes = if include_notes {
+        paper.notes.as_deref()
+    } else {
+        None
+    };
+    let html = render_notes_page(&paper, &authors, notes, &highlights);
+
+    let (token, expires_at) = registry.register(html, chrono::Duration::seconds(ttl as i64));
+
+    Ok(ShareLinkDto {
+        url: format!("{}/share/{}", base_url, token),
+        token,
+        expires_at: expires_at.to_rfc3339(),
+    })
+}
@@ -0,0 +1,15 @@
+//! Tauri commands for global shortcuts
+
+use tauri::State;
+use tracing::instrument;
+
+use crate::service::clipboard_capture_service::{ShortcutStatus, ShortcutStatusState};
+use crate::sys::error::Result;
+
+/// Get whether the clipboard capture hotkey is registered, and why not if
+/// it is not
+#[tauri::command]
+#[instrument(skip(status))]
+pub async fn get_shortcut_status(status: State<'_, ShortcutStatusState>) -> Result<ShortcutStatus> {
+    Ok(status.get())
+}
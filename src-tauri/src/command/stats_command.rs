@@ -0,0 +1,257 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::State;
+use tracing::{info, instrument};
+
+use crate::database::DatabaseConnection;
+use crate::repository::stats_repository::{
+    MetadataCompleteness, MonthCount, NamedCount, YearCount,
+};
+use crate::repository::StatsRepository;
+use crate::service::reading_stats_service::{self, ReadingStatistics};
+use crate::sys::error::{AppError, Result};
+
+#[derive(Serialize)]
+pub struct NamedCountDto {
+    /// Category, label or author id; absent for read statuses and journals
+    pub id: Option<String>,
+    pub name: String,
+    pub count: i64,
+}
+
+impl From<NamedCount> for NamedCountDto {
+    fn from(c: NamedCount) -> Self {
+        Self {
+            id: c.id.map(|id| id.to_string()),
+            name: c.name,
+            count: c.count,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct YearCountDto {
+    pub year: i32,
+    pub count: i64,
+}
+
+impl From<YearCount> for YearCountDto {
+    fn from(c: YearCount) -> Self {
+        Self {
+            year: c.year,
+            count: c.count,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MonthCountDto {
+    /// `YYYY-MM`
+    pub month: String,
+    pub count: i64,
+}
+
+impl From<MonthCount> for MonthCountDto {
+    fn from(c: MonthCount) -> Self {
+        Self {
+            month: c.month,
+            count: c.count,
+        }
+    }
+}
+
+/// Library overview for the statistics dashboard. Papers in the trash are
+/// not counted anywhere.
+#[derive(Serialize)]
+pub struct LibraryStatsDto {
+    pub total_papers: i64,
+    pub papers_per_year: Vec<YearCountDto>,
+    pub papers_without_year: i64,
+    pub by_category: Vec<NamedCountDto>,
+    pub by_label: Vec<NamedCountDto>,
+    pub by_read_status: Vec<NamedCountDto>,
+    /// Ten authors with the most papers
+    pub top_authors: Vec<NamedCountDto>,
+    /// Ten journals with the most papers
+    pub top_journals: Vec<NamedCountDto>,
+    pub attachment_bytes: i64,
+    /// Papers added in each of the last 24 months, oldest first
+    pub added_per_month: Vec<MonthCountDto>,
+}
+
+fn dtos<T, D: From<T>>(items: Vec<T>) -> Vec<D> {
+    items.into_iter().map(D::from).collect()
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_library_stats(db: State<'_, Arc<DatabaseConnection>>) -> Result<LibraryStatsDto> {
+    let started = Instant::now();
+    let stats = StatsRepository::library_stats(&db).await?;
+    info!(
+        "Computed library statistics for {} papers in {:?}",
+        stats.total_papers,
+        started.elapsed()
+    );
+
+    Ok(LibraryStatsDto {
+        total_papers: stats.total_papers,
+        papers_per_year: dtos(stats.papers_per_year),
+        papers_without_year: stats.papers_without_year,
+        by_category: dtos(stats.by_category),
+        by_label: dtos(stats.by_label),
+        by_read_status: dtos(stats.by_read_status),
+        top_authors: dtos(stats.top_authors),
+        top_journals: dtos(stats.top_journals),
+        attachment_bytes: stats.attachment_bytes,
+        added_per_month: dtos(stats.added_per_month),
+    })
+}
+
+/// Papers that have each kind of metadata. Papers in the trash are not
+/// counted.
+#[derive(Serialize)]
+pub struct MetadataCompletenessReport {
+    pub total_papers: u64,
+    pub with_abstract: u64,
+    pub with_doi: u64,
+    pub with_year: u64,
+    /// Journal or conference name
+    pub with_journal: u64,
+    pub with_authors: u64,
+    pub with_keywords: u64,
+    pub with_pdf: u64,
+    /// Share of all these fields that are filled in, from 0 to 1
+    pub completeness_score: f32,
+}
+
+impl From<MetadataCompleteness> for MetadataCompletenessReport {
+    fn from(c: MetadataCompleteness) -> Self {
+        Self {
+            completeness_score: c.score(),
+            total_papers: c.total_papers as u64,
+            with_abstract: c.with_abstract as u64,
+            with_doi: c.with_doi as u64,
+            with_year: c.with_year as u64,
+            with_journal: c.with_journal as u64,
+            with_authors: c.with_authors as u64,
+            with_keywords: c.with_keywords as u64,
+            with_pdf: c.with_pdf as u64,
+        }
+    }
+}
+
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_metadata_completeness_report(
+    db: State<'_, Arc<DatabaseConnection>>,
+) -> Result<MetadataCompletenessReport> {
+    let completeness = StatsRepository::metadata_completeness(&db).await?;
+    Ok(completeness.into())
+}
+
+/// Longest period accepted by the reading statistics commands
+const MAX_PERIOD_DAYS: u32 = 3650;
+
+/// Papers opened or read in an ISO week
+#[derive(Serialize)]
+pub struct WeekCountDto {
+    /// `YYYY-Www`
+    pub week: String,
+    pub count: u64,
+}
+
+/// Reading activity over the last `period_days` days
+#[derive(Serialize)]
+pub struct ReadingStatisticsDto {
+    pub period_days: u32,
+    /// Papers opened in the viewer or read
+    pub total_papers_opened: u64,
+    /// Papers read to their last page
+    pub total_papers_finished: u64,
+    pub total_pages_read: u64,
+    pub average_session_length_minutes: f32,
+    /// `YYYY-MM-DD` (UTC) of the day with the most reading time
+    pub most_active_day: Option<String>,
+    /// Every ISO week of the period, oldest first, including empty weeks
+    pub papers_read_per_week: Vec<WeekCountDto>,
+    /// Categories of the papers opened or read, most papers first
+    pub top_categories_by_reading: Vec<NamedCountDto>,
+}
+
+impl ReadingStatisticsDto {
+    fn new(period_days: u32, stats: ReadingStatistics) -> Self {
+        Self {
+            period_days,
+            total_papers_opened: stats.papers_opened,
+            total_papers_finished: stats.papers_finished,
+            total_pages_read: stats.pages_read,
+            average_session_length_minutes: stats.average_session_minutes,
+            most_active_day: stats.most_active_day,
+            papers_read_per_week: stats
+                .papers_per_week
+                .into_iter()
+                .map(|(week, count)| WeekCountDto { week, count })
+                .collect(),
+            top_categories_by_reading: dtos(stats.top_categories),
+        }
+    }
+}
+
+async fn reading_statistics(
+    db: &DatabaseConnection,
+    period_days: u32,
+) -> Result<ReadingStatisticsDto> {
+    if period_days == 0 || period_days > MAX_PERIOD_DAYS {
+        return Err(AppError::validation(
+            "period_days",
+            format!("Must be between 1 and {}", MAX_PERIOD_DAYS),
+        ));
+    }
+    let stats = reading_stats_service::reading_statistics(db, period_days, Utc::now()).await?;
+    Ok(ReadingStatisticsDto::new(period_days, stats))
+}
+
+/// Reading activity over the last `period_days` days, from reading sessions
+/// and papers opened in the viewer
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn get_reading_statistics(
+    db: State<'_, Arc<DatabaseConnection>>,
+    period_days: u32,
+) -> Result<ReadingStatisticsDto> {
+    reading_statistics(&db, period_days).await
+}
+
+/// Write the result of `get_reading_statistics` to `output_path` as JSON
+#[tauri::command]
+#[instrument(skip(db))]
+pub async fn export_reading_statistics(
+    db: State<'_, Arc<DatabaseConnection>>,
+    period_days: u32,
+    output_path: String,
+) -> Result<()> {
+    let target = PathBuf::from(&output_path);
+    if target.is_dir() {
+        return Err(AppError::validation(
+            "output_path",
+            "Export path is a directory",
+        ));
+    }
+
+    let stats = reading_statistics(&db, period_days).await?;
+    let json = serde_json::to_string_pretty(&stats)
+        .map_err(|e| AppError::generic(format!("Failed to serialize reading statistics: {}", e)))?;
+    std::fs::write(&target, json)
+        .map_err(|e| AppError::file_system(output_path.clone(), e.to_string()))?;
+
+    info!(
+        "Exported reading statistics of {} days to {}",
+        period_days, output_path
+    );
+    Ok(())
+}
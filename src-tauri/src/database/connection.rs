@@ -2,13 +2,16 @@
 //!
 //! Provides initialization and connection handling for SQLite database.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
-use sea_orm::{Database, DatabaseConnection};
-use tracing::info;
+use sea_orm::sqlx::{self, Row};
+use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbErr, RuntimeErr};
+use tracing::{error, info, warn};
 
 use crate::database::migration::run_migrations;
+use crate::service::backup_service::{apply_pending_restore, DATABASE_FILE};
 use crate::sys::error::{AppError, Result};
 
 /// Initialize SQLite connection
@@ -16,23 +19,224 @@ use crate::sys::error::{AppError, Result};
 /// Creates or connects to the SQLite database file at `{data_dir}/xuan-brain.sqlite`.
 /// Runs any pending migrations automatically.
 pub async fn init_sqlite_connection(data_dir: PathBuf) -> Result<Arc<DatabaseConnection>> {
-    let db_path = data_dir.join("xuan-brain.sqlite");
+    apply_staged_restore(&data_dir);
+    open_and_migrate(&data_dir).await.map_err(open_error)
+}
+
+/// A restore staged by `restore_database` replaces the file before it is opened
+fn apply_staged_restore(data_dir: &Path) {
+    if let Err(e) = apply_pending_restore(data_dir) {
+        error!("Failed to apply staged database restore: {}", e);
+    }
+}
+
+/// Connect, check that the file is a readable database and run pending
+/// migrations, keeping the SQLite error for `is_transient`
+async fn open_and_migrate(data_dir: &Path) -> std::result::Result<Arc<DatabaseConnection>, DbErr> {
+    let db_path = data_dir.join(DATABASE_FILE);
     let db_url = format!("sqlite://{}?mode=rwc", db_path.display());
 
     info!("Connecting to SQLite database at: {:?}", db_path);
 
-    let db = Database::connect(&db_url)
-        .await
-        .map_err(|e| AppError::generic(format!("Failed to connect to SQLite: {}", e)))?;
+    let db = Database::connect(&db_url).await?;
 
     info!("SQLite connection established");
 
-    // Run migrations
+    // Read the schema to make sure the file is a readable SQLite database;
+    // a corrupt or foreign file fails here rather than in a later query
+    db.execute_unprepared("SELECT count(*) FROM sqlite_master")
+        .await?;
+    run_migrations(&db).await?;
+
+    info!("Database migrations completed");
+
+    Ok(Arc::new(db))
+}
+
+fn open_error(e: DbErr) -> AppError {
+    AppError::generic(format!("Failed to open the SQLite database: {}", e))
+}
+
+/// Attempts made by `init_sqlite_connection_with_retry`
+const CONNECT_ATTEMPTS: u32 = 4;
+
+/// Wait before the second attempt; doubled before each further one
+const CONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// SQLite primary result codes of a database held by another connection
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+/// Whether opening the database may succeed if tried again, i.e. SQLite
+/// reported `SQLITE_BUSY` or `SQLITE_LOCKED` (or one of their extended
+/// codes) while another process briefly held a lock on the file
+fn is_transient(error: &DbErr) -> bool {
+    let (DbErr::Conn(RuntimeErr::SqlxError(e))
+    | DbErr::Exec(RuntimeErr::SqlxError(e))
+    | DbErr::Query(RuntimeErr::SqlxError(e))) = error
+    else {
+        return false;
+    };
+    e.as_database_error()
+        .and_then(|db_error| db_error.code())
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED))
+}
+
+/// `init_sqlite_connection`, retried with backoff while the database is
+/// locked or busy. Other failures are returned at once.
+pub async fn init_sqlite_connection_with_retry(
+    data_dir: PathBuf,
+) -> Result<Arc<DatabaseConnection>> {
+    apply_staged_restore(&data_dir);
+
+    let mut backoff = CONNECT_INITIAL_BACKOFF;
+    let mut attempt = 1;
+    loop {
+        match open_and_migrate(&data_dir).await {
+            Ok(db) => return Ok(db),
+            Err(e) if attempt < CONNECT_ATTEMPTS && is_transient(&e) => {
+                warn!(
+                    "Opening the database failed (attempt {} of {}), retrying in {:?}: {}",
+                    attempt, CONNECT_ATTEMPTS, backoff, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(open_error(e)),
+        }
+    }
+}
+
+/// Open a database file other than the library's own, e.g. one extracted
+/// from a backup, and bring it up to the current schema
+pub async fn open_database_file(path: &Path) -> Result<DatabaseConnection> {
+    let db_url = format!("sqlite://{}?mode=rw", path.display());
+    let db = Database::connect(&db_url).await.map_err(|e| {
+        AppError::generic(format!("Failed to open database {}: {}", path.display(), e))
+    })?;
+
     run_migrations(&db)
         .await
         .map_err(|e| AppError::generic(format!("Failed to run migrations: {}", e)))?;
 
-    info!("Database migrations completed");
+    Ok(db)
+}
 
-    Ok(Arc::new(db))
+/// Write the WAL back into the database file and close every pooled
+/// connection, so the file can be copied with nothing left in `-wal` and no
+/// later writes. Queries on `db` fail afterwards until the app restarts.
+pub async fn checkpoint_and_close(db: &DatabaseConnection) -> Result<()> {
+    db.execute_unprepared("PRAGMA wal_checkpoint(TRUNCATE)")
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to checkpoint database: {}", e)))?;
+    db.get_sqlite_connection_pool().close().await;
+    info!("Database checkpointed and closed");
+    Ok(())
+}
+
+/// Open the database file at `path` and run `PRAGMA integrity_check`,
+/// failing with the reported problems unless it says `ok`
+pub async fn check_integrity(path: &Path) -> Result<()> {
+    let db_url = format!("sqlite://{}?mode=rw", path.display());
+    let db = Database::connect(&db_url).await.map_err(|e| {
+        AppError::generic(format!("Failed to open database {}: {}", path.display(), e))
+    })?;
+
+    let result = sqlx::query("PRAGMA integrity_check")
+        .fetch_all(db.get_sqlite_connection_pool())
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to check database integrity: {}", e)))
+        .map(|rows| {
+            rows.iter()
+                .filter_map(|row| row.try_get::<String, _>(0).ok())
+                .collect::<Vec<_>>()
+        });
+    if let Err(e) = db.close().await {
+        warn!("Failed to close {}: {}", path.display(), e);
+    }
+
+    let problems = result?;
+    if problems.len() == 1 && problems[0] == "ok" {
+        return Ok(());
+    }
+    Err(AppError::generic(format!(
+        "Database {} failed the integrity check: {}",
+        path.display(),
+        problems.join("; ")
+    )))
+}
+
+/// Open an in-memory SQLite database with all migrations applied (tests only)
+#[cfg(test)]
+pub async fn init_memory_connection() -> Arc<DatabaseConnection> {
+    use sea_orm::ConnectOptions;
+
+    // A single connection keeps every query on the same in-memory database
+    let mut options = ConnectOptions::new("sqlite::memory:");
+    options.max_connections(1);
+
+    let db = Database::connect(options)
+        .await
+        .expect("Failed to open in-memory SQLite");
+    run_migrations(&db)
+        .await
+        .expect("Failed to run migrations on in-memory SQLite");
+
+    Arc::new(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checkpoint_and_close_leaves_a_complete_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = init_sqlite_connection(dir.path().to_path_buf())
+            .await
+            .unwrap();
+        db.execute_unprepared(
+            "INSERT INTO label (name, color, created_at) \
+             VALUES ('recent', '#ffffff', '2025-01-01T00:00:00Z')",
+        )
+        .await
+        .unwrap();
+        checkpoint_and_close(&db).await.unwrap();
+        assert!(db.execute_unprepared("SELECT 1").await.is_err());
+
+        // Only the main file is copied, so the edit must not be left in the WAL
+        let copy = dir.path().join("copy.sqlite");
+        std::fs::copy(dir.path().join(DATABASE_FILE), &copy).unwrap();
+        check_integrity(&copy).await.unwrap();
+
+        let copied = open_database_file(&copy).await.unwrap();
+        let row = sqlx::query("SELECT COUNT(*) FROM label WHERE name = 'recent'")
+            .fetch_one(copied.get_sqlite_connection_pool())
+            .await
+            .unwrap();
+        assert_eq!(row.try_get::<i64, _>(0).unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_a_damaged_file_is_not_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(DATABASE_FILE),
+            b"SQLite format 3\0 but nothing else",
+        )
+        .unwrap();
+
+        let err = open_and_migrate(dir.path()).await.unwrap_err();
+        assert!(!is_transient(&err), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check_rejects_a_damaged_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("damaged.sqlite");
+        std::fs::write(&path, b"SQLite format 3\0 but nothing else").unwrap();
+        assert!(check_integrity(&path).await.is_err());
+    }
 }
@@ -15,6 +15,8 @@ pub struct Model {
     pub last_name: Option<String>,
     pub affiliation: Option<String>,
     pub email: Option<String>,
+    /// ORCID iD in its bare form, e.g. "0000-0002-1825-0097"
+    pub orcid: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
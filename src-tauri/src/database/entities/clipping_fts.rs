@@ -0,0 +1,27 @@
+//! Clipping full-text index entity definition
+//!
+//! Maps the `clipping_fts` FTS5 virtual table. The row id is the id of the
+//! indexed clipping and the text columns hold plain text with HTML stripped.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "clipping_fts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub rowid: i64,
+    pub title: String,
+    pub excerpt: Option<String>,
+    pub content: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match *self {}
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,35 @@
+//! Import batch entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "import_batch")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Kind of import, e.g. "doi_file"
+    pub source: String,
+    pub source_path: String,
+    /// SHA-1 of the source file contents, used to detect an unchanged file
+    pub source_hash: String,
+    pub category_id: Option<i64>,
+    pub total: i32,
+    /// "running" or "completed"
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match *self {}
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
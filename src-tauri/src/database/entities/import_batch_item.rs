@@ -0,0 +1,45 @@
+//! Import batch item entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "import_batch_item")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub batch_id: i64,
+    /// 1-based line number in the source file
+    pub line_number: i32,
+    pub identifier: String,
+    /// "pending", "imported", "skipped" or "failed"
+    pub status: String,
+    pub paper_id: Option<i64>,
+    pub error: Option<String>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Batch,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Batch => Entity::belongs_to(super::import_batch::Entity)
+                .from(Column::BatchId)
+                .to(super::import_batch::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::import_batch::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Batch.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
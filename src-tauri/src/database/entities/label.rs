@@ -12,6 +12,10 @@ pub struct Model {
     pub name: String,
     pub color: String,
     pub document_count: i32,
+    /// Position in the label list; labels with equal values sort by name
+    pub sort_order: i32,
+    /// Label this one is grouped under; `None` for top-level labels
+    pub parent_id: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -2,21 +2,43 @@
 //!
 //! Each entity corresponds to a database table.
 
+pub mod activity_log;
+pub mod api_key;
 pub mod attachment;
 pub mod author;
 pub mod category;
 pub mod clip_label;
 pub mod clipping;
+pub mod clipping_fts;
 pub mod comment;
+pub mod custom_field_definition;
+pub mod import_batch;
+pub mod import_batch_item;
 pub mod keyword;
 pub mod label;
+pub mod open_access_cache;
 pub mod paper;
+pub mod paper_ai_summary;
 pub mod paper_author;
 pub mod paper_category;
+pub mod paper_citation;
+pub mod paper_custom_field;
+pub mod paper_embedding;
 pub mod paper_keyword;
 pub mod paper_label;
+pub mod paper_note_fts;
+pub mod paper_note_version;
+pub mod paper_reference;
+pub mod paper_text_content;
+pub mod reading_progress;
+pub mod reading_queue;
+pub mod reading_session;
 pub mod search_history;
 #[allow(unused_imports)]
+pub use activity_log::Entity as ActivityLog;
+#[allow(unused_imports)]
+pub use api_key::Entity as ApiKey;
+#[allow(unused_imports)]
 pub use attachment::Entity as Attachment;
 #[allow(unused_imports)]
 pub use author::Entity as Author;
@@ -27,19 +49,51 @@ pub use clip_label::Entity as ClipLabel;
 #[allow(unused_imports)]
 pub use clipping::Entity as Clipping;
 #[allow(unused_imports)]
+pub use clipping_fts::Entity as ClippingFts;
+#[allow(unused_imports)]
 pub use comment::Entity as Comment;
 #[allow(unused_imports)]
+pub use custom_field_definition::Entity as CustomFieldDefinition;
+#[allow(unused_imports)]
+pub use import_batch::Entity as ImportBatch;
+#[allow(unused_imports)]
+pub use import_batch_item::Entity as ImportBatchItem;
+#[allow(unused_imports)]
 pub use keyword::Entity as Keyword;
 #[allow(unused_imports)]
 pub use label::Entity as Label;
 #[allow(unused_imports)]
+pub use open_access_cache::Entity as OpenAccessCache;
+#[allow(unused_imports)]
 pub use paper::Entity as Paper;
 #[allow(unused_imports)]
+pub use paper_ai_summary::Entity as PaperAiSummary;
+#[allow(unused_imports)]
 pub use paper_author::Entity as PaperAuthor;
 #[allow(unused_imports)]
 pub use paper_category::Entity as PaperCategory;
 #[allow(unused_imports)]
+pub use paper_citation::Entity as PaperCitation;
+#[allow(unused_imports)]
+pub use paper_custom_field::Entity as PaperCustomField;
+#[allow(unused_imports)]
+pub use paper_embedding::Entity as PaperEmbedding;
+#[allow(unused_imports)]
 pub use paper_keyword::Entity as PaperKeyword;
 #[allow(unused_imports)]
 pub use paper_label::Entity as PaperLabel;
+#[allow(unused_imports)]
+pub use paper_note_fts::Entity as PaperNoteFts;
+#[allow(unused_imports)]
+pub use paper_note_version::Entity as PaperNoteVersion;
+#[allow(unused_imports)]
+pub use paper_reference::Entity as PaperReference;
+#[allow(unused_imports)]
+pub use paper_text_content::Entity as PaperTextContent;
+#[allow(unused_imports)]
+pub use reading_progress::Entity as ReadingProgress;
+#[allow(unused_imports)]
+pub use reading_queue::Entity as ReadingQueue;
+#[allow(unused_imports)]
+pub use reading_session::Entity as ReadingSession;
 
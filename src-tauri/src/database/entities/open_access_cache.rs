@@ -0,0 +1,47 @@
+//! Open access cache entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "open_access_cache")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    #[sea_orm(unique)]
+    pub paper_id: i64,
+    /// DOI that was looked up; a row for another DOI is stale
+    pub doi: String,
+    pub is_oa: bool,
+    /// Unpaywall status: `gold`, `green`, `hybrid`, `bronze` or `closed`
+    pub oa_status: String,
+    /// Best open access location
+    pub oa_url: Option<String>,
+    pub license: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Paper,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Paper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::PaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::paper::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Paper.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -27,10 +27,14 @@ pub struct Model {
     pub publisher: Option<String>,
     pub issn: Option<String>,
     pub language: Option<String>,
+    pub isbn: Option<String>,
     pub attachment_count: i32,
+    pub is_starred: bool,
+    pub starred_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    pub last_citation_refresh_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -0,0 +1,43 @@
+//! Paper AI summary entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "paper_ai_summary")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub paper_id: i64,
+    /// Summary style, e.g. `tldr` or `detailed`
+    pub style: String,
+    pub summary: String,
+    /// Model name reported by the configured LLM provider
+    pub model: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Paper,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Paper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::PaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::paper::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Paper.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,38 @@
+//! Paper citation entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "paper_citation")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    pub citing_paper_id: i64,
+    pub cited_paper_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    CitingPaper,
+    CitedPaper,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::CitingPaper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::CitingPaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+            Self::CitedPaper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::CitedPaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
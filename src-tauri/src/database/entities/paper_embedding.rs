@@ -0,0 +1,44 @@
+//! Paper embedding entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "paper_embedding")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub paper_id: i64,
+    /// Embedding model name from the embedding config
+    pub model: String,
+    pub dimensions: i32,
+    /// Little-endian f32 components
+    pub vector: Vec<u8>,
+    /// SHA-1 of the model name and the embedded text
+    pub content_hash: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Paper,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Paper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::PaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::paper::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Paper.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,25 @@
+//! Paper note full-text index entity definition
+//!
+//! Maps the `paper_note_fts` FTS5 virtual table. The row id is the id of the
+//! paper and `content` holds the plain text of its current note.
+
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "paper_note_fts")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub rowid: i64,
+    pub content: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match *self {}
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,50 @@
+//! Paper reference entity definition
+
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "paper_reference")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// Paper whose bibliography contains this entry
+    pub paper_id: i64,
+    /// Zero-based position in the bibliography
+    pub position: i32,
+    pub title: Option<String>,
+    /// JSON array of author names
+    pub authors: Option<String>,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    /// Journal, proceedings or book the entry was published in
+    pub journal: Option<String>,
+    /// Citation string as printed in the bibliography
+    pub raw_text: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Paper,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Paper => Entity::belongs_to(super::paper::Entity)
+                .from(Column::PaperId)
+                .to(super::paper::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::paper::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Paper.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,141 @@
+//! Add import history tables for resumable batch imports
+//!
+//! `import_batch` records one import run over a source file and
+//! `import_batch_item` tracks the state of every identifier in it, so an
+//! interrupted batch can resume from the first unprocessed line.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportBatch::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportBatch::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ImportBatch::Source).text().not_null())
+                    .col(ColumnDef::new(ImportBatch::SourcePath).text().not_null())
+                    .col(ColumnDef::new(ImportBatch::SourceHash).text().not_null())
+                    .col(ColumnDef::new(ImportBatch::CategoryId).integer())
+                    .col(ColumnDef::new(ImportBatch::Total).integer().not_null())
+                    .col(ColumnDef::new(ImportBatch::Status).text().not_null())
+                    .col(
+                        ColumnDef::new(ImportBatch::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(ImportBatch::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(ColumnDef::new(ImportBatch::CompletedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportBatchItem::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportBatchItem::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportBatchItem::BatchId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportBatchItem::LineNumber)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ImportBatchItem::Identifier)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ImportBatchItem::Status).text().not_null())
+                    .col(ColumnDef::new(ImportBatchItem::PaperId).integer())
+                    .col(ColumnDef::new(ImportBatchItem::Error).text())
+                    .col(ColumnDef::new(ImportBatchItem::ProcessedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_batch_item_batch")
+                            .from(ImportBatchItem::Table, ImportBatchItem::BatchId)
+                            .to(ImportBatch::Table, ImportBatch::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_import_batch_item_batch_line")
+                    .table(ImportBatchItem::Table)
+                    .col(ImportBatchItem::BatchId)
+                    .col(ImportBatchItem::LineNumber)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ImportBatchItem::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ImportBatch::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ImportBatch {
+    Table,
+    Id,
+    Source,
+    SourcePath,
+    SourceHash,
+    CategoryId,
+    Total,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+    CompletedAt,
+}
+
+#[derive(Iden)]
+enum ImportBatchItem {
+    Table,
+    Id,
+    BatchId,
+    LineNumber,
+    Identifier,
+    Status,
+    PaperId,
+    Error,
+    ProcessedAt,
+}
@@ -0,0 +1,136 @@
+//! Add reading progress tracking
+//!
+//! `reading_progress` keeps the last page read per paper and
+//! `reading_session` records continuous reading sessions.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReadingProgress::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReadingProgress::PaperId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingProgress::CurrentPage)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingProgress::TotalPages)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingProgress::LastReadAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reading_progress_paper")
+                            .from(ReadingProgress::Table, ReadingProgress::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReadingSession::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReadingSession::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ReadingSession::PaperId).integer().not_null())
+                    .col(
+                        ColumnDef::new(ReadingSession::StartedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingSession::EndedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingSession::PagesRead)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reading_session_paper")
+                            .from(ReadingSession::Table, ReadingSession::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reading_session_paper_started")
+                    .table(ReadingSession::Table)
+                    .col(ReadingSession::PaperId)
+                    .col(ReadingSession::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReadingSession::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(ReadingProgress::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum ReadingProgress {
+    Table,
+    PaperId,
+    CurrentPage,
+    TotalPages,
+    LastReadAt,
+}
+
+#[derive(Iden)]
+enum ReadingSession {
+    Table,
+    Id,
+    PaperId,
+    StartedAt,
+    EndedAt,
+    PagesRead,
+}
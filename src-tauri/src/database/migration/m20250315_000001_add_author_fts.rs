@@ -0,0 +1,88 @@
+//! Add SQLite FTS5 full-text search for author names
+//!
+//! `author_fts` indexes the `author` table directly as external content, so
+//! only triggers are needed to keep it in sync.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS author_fts USING fts5(
+                first_name,
+                last_name,
+                content='author',
+                content_rowid='id',
+                tokenize='unicode61 remove_diacritics 2'
+            )
+            "#,
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS author_fts_insert
+            AFTER INSERT ON author
+            BEGIN
+                INSERT INTO author_fts (rowid, first_name, last_name)
+                VALUES (new.id, new.first_name, new.last_name);
+            END
+            "#,
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS author_fts_update
+            AFTER UPDATE OF first_name, last_name ON author
+            BEGIN
+                INSERT INTO author_fts (author_fts, rowid, first_name, last_name)
+                VALUES ('delete', old.id, old.first_name, old.last_name);
+                INSERT INTO author_fts (rowid, first_name, last_name)
+                VALUES (new.id, new.first_name, new.last_name);
+            END
+            "#,
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS author_fts_delete
+            AFTER DELETE ON author
+            BEGIN
+                INSERT INTO author_fts (author_fts, rowid, first_name, last_name)
+                VALUES ('delete', old.id, old.first_name, old.last_name);
+            END
+            "#,
+        )
+        .await?;
+
+        // Index authors that already exist
+        conn.execute_unprepared("INSERT INTO author_fts (author_fts) VALUES ('rebuild')")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS author_fts_insert")
+            .await?;
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS author_fts_update")
+            .await?;
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS author_fts_delete")
+            .await?;
+        conn.execute_unprepared("DROP TABLE IF EXISTS author_fts")
+            .await?;
+
+        Ok(())
+    }
+}
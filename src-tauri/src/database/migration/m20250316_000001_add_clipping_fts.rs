@@ -0,0 +1,71 @@
+//! Add SQLite FTS5 full-text search for clippings
+//!
+//! `clipping_fts` keeps its own copy of the clipping title, excerpt and
+//! content with HTML stripped, so it is filled from Rust rather than by
+//! triggers. Only deletes are synced by a trigger.
+
+use sea_orm::EntityTrait;
+use sea_orm_migration::prelude::*;
+
+use crate::database::entities::clipping;
+use crate::repository::SearchRepository;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Same tokenizer as paper_fts so scores are comparable
+        conn.execute_unprepared(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS clipping_fts USING fts5(
+                title,
+                excerpt,
+                content,
+                tokenize='trigram'
+            )
+            "#,
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS clipping_fts_delete
+            AFTER DELETE ON clipping
+            BEGIN
+                DELETE FROM clipping_fts WHERE rowid = old.id;
+            END
+            "#,
+        )
+        .await?;
+
+        // Index clippings that already exist
+        for clip in clipping::Entity::find().all(conn).await? {
+            SearchRepository::index_clip_on(
+                conn,
+                clip.id,
+                &clip.title,
+                clip.excerpt.as_deref(),
+                clip.content.as_deref(),
+            )
+            .await
+            .map_err(|e| DbErr::Custom(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS clipping_fts_delete")
+            .await?;
+        conn.execute_unprepared("DROP TABLE IF EXISTS clipping_fts")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! Add an isbn column to paper
+//!
+//! Books are deduplicated by their normalized ISBN-13. Books imported before
+//! this column existed are recognized by their Open Library ISBN URL.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .add_column(ColumnDef::new(Paper::Isbn).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_isbn")
+                    .table(Paper::Table)
+                    .col(Paper::Isbn)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        // "https://openlibrary.org/isbn/" is 29 characters long
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE paper
+                SET isbn = substr(url, 30)
+                WHERE url LIKE 'https://openlibrary.org/isbn/%'
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_paper_isbn")
+                    .table(Paper::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .drop_column(Paper::Isbn)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Isbn,
+}
@@ -0,0 +1,88 @@
+//! Add the paper_reference table
+//!
+//! Holds the bibliography GROBID extracted from a paper's PDF, in the order
+//! the entries appear in the paper.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperReference::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperReference::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaperReference::PaperId).integer().not_null())
+                    .col(
+                        ColumnDef::new(PaperReference::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PaperReference::Title).text())
+                    .col(ColumnDef::new(PaperReference::Authors).text())
+                    .col(ColumnDef::new(PaperReference::Year).integer())
+                    .col(ColumnDef::new(PaperReference::Doi).text())
+                    .col(
+                        ColumnDef::new(PaperReference::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_reference_paper")
+                            .from(PaperReference::Table, PaperReference::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_reference_paper")
+                    .table(PaperReference::Table)
+                    .col(PaperReference::PaperId)
+                    .col(PaperReference::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperReference::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperReference {
+    Table,
+    Id,
+    PaperId,
+    Position,
+    Title,
+    Authors,
+    Year,
+    Doi,
+    CreatedAt,
+}
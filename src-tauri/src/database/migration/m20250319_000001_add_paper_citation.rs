@@ -0,0 +1,102 @@
+//! Add the paper_citation table
+//!
+//! Directed edges between papers in the library: `citing_paper_id` cites
+//! `cited_paper_id`. Each pair is stored at most once.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperCitation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperCitation::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperCitation::CitingPaperId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperCitation::CitedPaperId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperCitation::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_citation_citing")
+                            .from(PaperCitation::Table, PaperCitation::CitingPaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_citation_cited")
+                            .from(PaperCitation::Table, PaperCitation::CitedPaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_citation_pair")
+                    .table(PaperCitation::Table)
+                    .col(PaperCitation::CitingPaperId)
+                    .col(PaperCitation::CitedPaperId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_citation_cited")
+                    .table(PaperCitation::Table)
+                    .col(PaperCitation::CitedPaperId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperCitation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperCitation {
+    Table,
+    Id,
+    CitingPaperId,
+    CitedPaperId,
+    CreatedAt,
+}
@@ -0,0 +1,81 @@
+//! Add the paper_ai_summary table
+//!
+//! LLM-generated summaries of papers. A paper keeps the latest summary per
+//! style, together with the model that wrote it.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperAiSummary::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperAiSummary::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaperAiSummary::PaperId).integer().not_null())
+                    .col(ColumnDef::new(PaperAiSummary::Style).string().not_null())
+                    .col(ColumnDef::new(PaperAiSummary::Summary).text().not_null())
+                    .col(ColumnDef::new(PaperAiSummary::Model).string().not_null())
+                    .col(
+                        ColumnDef::new(PaperAiSummary::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_ai_summary_paper")
+                            .from(PaperAiSummary::Table, PaperAiSummary::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_ai_summary_paper_style")
+                    .table(PaperAiSummary::Table)
+                    .col(PaperAiSummary::PaperId)
+                    .col(PaperAiSummary::Style)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperAiSummary::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperAiSummary {
+    Table,
+    Id,
+    PaperId,
+    Style,
+    Summary,
+    Model,
+    CreatedAt,
+}
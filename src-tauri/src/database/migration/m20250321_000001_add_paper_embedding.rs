@@ -0,0 +1,87 @@
+//! Add the paper_embedding table
+//!
+//! One embedding vector per paper for semantic search, stored as
+//! little-endian f32 bytes. `content_hash` covers the model and the embedded
+//! text, so reindexing skips papers whose vector is still current.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperEmbedding::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperEmbedding::PaperId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaperEmbedding::Model).string().not_null())
+                    .col(
+                        ColumnDef::new(PaperEmbedding::Dimensions)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PaperEmbedding::Vector).blob().not_null())
+                    .col(
+                        ColumnDef::new(PaperEmbedding::ContentHash)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperEmbedding::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_embedding_paper")
+                            .from(PaperEmbedding::Table, PaperEmbedding::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_embedding_model")
+                    .table(PaperEmbedding::Table)
+                    .col(PaperEmbedding::Model)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperEmbedding::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperEmbedding {
+    Table,
+    PaperId,
+    Model,
+    Dimensions,
+    Vector,
+    ContentHash,
+    UpdatedAt,
+}
@@ -0,0 +1,57 @@
+//! Add journal and raw_text columns to paper_reference
+//!
+//! `raw_text` keeps the citation string as it appears in the PDF, so entries
+//! GROBID could not structure are still readable.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PaperReference::Table)
+                    .add_column(ColumnDef::new(PaperReference::Journal).text())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PaperReference::Table)
+                    .add_column(ColumnDef::new(PaperReference::RawText).text())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PaperReference::Table)
+                    .drop_column(PaperReference::Journal)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PaperReference::Table)
+                    .drop_column(PaperReference::RawText)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PaperReference {
+    Table,
+    Journal,
+    RawText,
+}
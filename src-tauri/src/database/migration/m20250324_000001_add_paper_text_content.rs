@@ -0,0 +1,71 @@
+//! Add the paper_text_content table
+//!
+//! Full text of a paper's PDF, either read from its text layer or recognized
+//! by OCR for image-only scans. One row per paper, kept for later full-text
+//! indexing.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperTextContent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperTextContent::PaperId)
+                            .integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PaperTextContent::Content).text().not_null())
+                    .col(ColumnDef::new(PaperTextContent::Source).string().not_null())
+                    .col(
+                        ColumnDef::new(PaperTextContent::PageCount)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperTextContent::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_text_content_paper")
+                            .from(PaperTextContent::Table, PaperTextContent::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperTextContent::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperTextContent {
+    Table,
+    PaperId,
+    Content,
+    Source,
+    PageCount,
+    UpdatedAt,
+}
@@ -0,0 +1,77 @@
+//! Add the activity_log table
+//!
+//! A lightweight history of what happened to papers and clips: views,
+//! imports, edits and deletions. Entries are trimmed at startup according to
+//! `activity.retention_days`.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ActivityLog::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ActivityLog::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ActivityLog::EntityType).string().not_null())
+                    .col(ColumnDef::new(ActivityLog::EntityId).integer().not_null())
+                    .col(ColumnDef::new(ActivityLog::Action).string().not_null())
+                    .col(
+                        ColumnDef::new(ActivityLog::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activity_log_created_at")
+                    .table(ActivityLog::Table)
+                    .col(ActivityLog::CreatedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_activity_log_entity")
+                    .table(ActivityLog::Table)
+                    .col(ActivityLog::EntityType)
+                    .col(ActivityLog::Action)
+                    .col(ActivityLog::EntityId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ActivityLog::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ActivityLog {
+    Table,
+    Id,
+    EntityType,
+    EntityId,
+    Action,
+    CreatedAt,
+}
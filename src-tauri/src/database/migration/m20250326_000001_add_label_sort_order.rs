@@ -0,0 +1,45 @@
+//! Add a sort_order column to label
+//!
+//! Labels are listed by sort_order, then by name. Existing labels all start
+//! at 0, so they stay in alphabetical order until the user reorders them.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Label::Table)
+                    .add_column(
+                        ColumnDef::new(Label::SortOrder)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Label::Table)
+                    .drop_column(Label::SortOrder)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Label {
+    Table,
+    SortOrder,
+}
@@ -0,0 +1,105 @@
+//! Add the paper_note_version table
+//!
+//! Every save of a paper's notes is kept as a numbered version with its
+//! format. The highest version is the current note; `paper.notes` keeps a
+//! plain-text copy of it. Existing notes become version 1.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperNoteVersion::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperNoteVersion::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperNoteVersion::PaperId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperNoteVersion::Version)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PaperNoteVersion::Content).text().not_null())
+                    .col(
+                        ColumnDef::new(PaperNoteVersion::ContentFormat)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperNoteVersion::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_note_version_paper")
+                            .from(PaperNoteVersion::Table, PaperNoteVersion::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_note_version_paper_version")
+                    .table(PaperNoteVersion::Table)
+                    .col(PaperNoteVersion::PaperId)
+                    .col(PaperNoteVersion::Version)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO paper_note_version (paper_id, version, content, content_format, created_at) \
+                 SELECT id, 1, notes, 'plain_text', updated_at FROM paper \
+                 WHERE notes IS NOT NULL AND TRIM(notes) != ''",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperNoteVersion::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum PaperNoteVersion {
+    Table,
+    Id,
+    PaperId,
+    Version,
+    Content,
+    ContentFormat,
+    CreatedAt,
+}
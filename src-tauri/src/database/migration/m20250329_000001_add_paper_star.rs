@@ -0,0 +1,64 @@
+//! Add an is_starred column to paper
+//!
+//! Starred papers are a short list of must-reads with their own listing.
+//! Existing papers start unstarred.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .add_column(
+                        ColumnDef::new(Paper::IsStarred)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_is_starred")
+                    .table(Paper::Table)
+                    .col(Paper::IsStarred)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_paper_is_starred")
+                    .table(Paper::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .drop_column(Paper::IsStarred)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    IsStarred,
+}
@@ -0,0 +1,139 @@
+//! Add the custom_field_definition and paper_custom_field tables
+//!
+//! Free-form key/value metadata on papers, such as a funding source or an
+//! internal project code. `custom_field_definition` holds the type of each
+//! key; values are stored as text in `paper_custom_field`, normalized for
+//! that type. A paper has at most one value per key.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CustomFieldDefinition::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(CustomFieldDefinition::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFieldDefinition::Key)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFieldDefinition::ValueType)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(CustomFieldDefinition::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaperCustomField::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaperCustomField::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PaperCustomField::PaperId)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PaperCustomField::Key).string().not_null())
+                    .col(ColumnDef::new(PaperCustomField::Value).text().not_null())
+                    .col(
+                        ColumnDef::new(PaperCustomField::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_paper_custom_field_paper")
+                            .from(PaperCustomField::Table, PaperCustomField::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_custom_field_paper_key")
+                    .table(PaperCustomField::Table)
+                    .col(PaperCustomField::PaperId)
+                    .col(PaperCustomField::Key)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_custom_field_key")
+                    .table(PaperCustomField::Table)
+                    .col(PaperCustomField::Key)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PaperCustomField::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(CustomFieldDefinition::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum CustomFieldDefinition {
+    Table,
+    Id,
+    Key,
+    ValueType,
+    CreatedAt,
+}
+
+#[derive(Iden)]
+enum PaperCustomField {
+    Table,
+    Id,
+    PaperId,
+    Key,
+    Value,
+    UpdatedAt,
+}
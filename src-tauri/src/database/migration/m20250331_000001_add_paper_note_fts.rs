@@ -0,0 +1,64 @@
+//! Add SQLite FTS5 full-text search for paper notes
+//!
+//! `paper_note_fts` holds the plain text of the current note of each paper,
+//! keyed by paper id. Earlier versions are not indexed. Like `clipping_fts`
+//! it is filled from Rust after the initial backfill; only deletes are
+//! synced by a trigger.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        // Same tokenizer as paper_fts and clipping_fts
+        conn.execute_unprepared(
+            r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS paper_note_fts USING fts5(
+                content,
+                tokenize='trigram'
+            )
+            "#,
+        )
+        .await?;
+
+        conn.execute_unprepared(
+            r#"
+            CREATE TRIGGER IF NOT EXISTS paper_note_fts_delete
+            AFTER DELETE ON paper
+            BEGIN
+                DELETE FROM paper_note_fts WHERE rowid = old.id;
+            END
+            "#,
+        )
+        .await?;
+
+        // Index notes that already exist. `paper.notes` holds the plain text
+        // of the current note of every paper.
+        conn.execute_unprepared(
+            r#"
+            INSERT INTO paper_note_fts (rowid, content)
+            SELECT id, notes FROM paper
+            WHERE notes IS NOT NULL AND TRIM(notes) != ''
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let conn = manager.get_connection();
+
+        conn.execute_unprepared("DROP TRIGGER IF EXISTS paper_note_fts_delete")
+            .await?;
+        conn.execute_unprepared("DROP TABLE IF EXISTS paper_note_fts")
+            .await?;
+
+        Ok(())
+    }
+}
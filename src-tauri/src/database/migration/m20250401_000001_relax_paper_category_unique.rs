@@ -0,0 +1,73 @@
+//! Allow a paper to be filed under several categories
+//!
+//! The initial schema made `paper_id` unique in `paper_category`. SQLite
+//! cannot drop a table constraint, so the table is rebuilt with
+//! `(paper_id, category_id)` unique instead. Existing rows keep their ids.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild(
+            manager,
+            &["paper_id", "category_id"],
+            "SELECT id, paper_id, category_id FROM paper_category",
+        )
+        .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Keep the first category of each paper
+        rebuild(
+            manager,
+            &["paper_id"],
+            r#"
+            SELECT id, paper_id, category_id FROM paper_category
+            WHERE id IN (SELECT MIN(id) FROM paper_category GROUP BY paper_id)
+            "#,
+        )
+        .await
+    }
+}
+
+/// Recreate `paper_category` with `unique_columns` unique, copying the rows
+/// returned by `select`
+async fn rebuild(
+    manager: &SchemaManager<'_>,
+    unique_columns: &[&str],
+    select: &str,
+) -> Result<(), DbErr> {
+    let conn = manager.get_connection();
+
+    conn.execute_unprepared(&format!(
+        r#"
+        CREATE TABLE paper_category_new (
+            id INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT,
+            paper_id INTEGER NOT NULL,
+            category_id INTEGER NOT NULL,
+            CONSTRAINT fk_paper_category_paper FOREIGN KEY (paper_id)
+                REFERENCES paper (id) ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT fk_paper_category_category FOREIGN KEY (category_id)
+                REFERENCES category (id) ON DELETE CASCADE ON UPDATE CASCADE,
+            CONSTRAINT idx_paper_category_unique UNIQUE ({})
+        )
+        "#,
+        unique_columns.join(", ")
+    ))
+    .await?;
+
+    conn.execute_unprepared(&format!(
+        "INSERT INTO paper_category_new (id, paper_id, category_id) {}",
+        select
+    ))
+    .await?;
+    conn.execute_unprepared("DROP TABLE paper_category").await?;
+    conn.execute_unprepared("ALTER TABLE paper_category_new RENAME TO paper_category")
+        .await?;
+
+    Ok(())
+}
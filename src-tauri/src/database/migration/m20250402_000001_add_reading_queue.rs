@@ -0,0 +1,80 @@
+//! Add the reading_queue table
+//!
+//! An ordered "to read next" list, separate from labels. A paper is queued
+//! at most once; `position` orders the queue, lowest first.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ReadingQueue::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ReadingQueue::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ReadingQueue::PaperId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ReadingQueue::Position).integer().not_null())
+                    .col(
+                        ColumnDef::new(ReadingQueue::AddedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_reading_queue_paper")
+                            .from(ReadingQueue::Table, ReadingQueue::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_reading_queue_position")
+                    .table(ReadingQueue::Table)
+                    .col(ReadingQueue::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ReadingQueue::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum ReadingQueue {
+    Table,
+    Id,
+    PaperId,
+    Position,
+    AddedAt,
+}
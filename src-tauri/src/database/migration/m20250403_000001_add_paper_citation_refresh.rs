@@ -0,0 +1,45 @@
+//! Add a last_citation_refresh_at column to paper
+//!
+//! Records when the citation count was last fetched so the daily refresh
+//! can skip papers that are still fresh. Existing papers start unset and
+//! are refreshed on the first run.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .add_column(
+                        ColumnDef::new(Paper::LastCitationRefreshAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .drop_column(Paper::LastCitationRefreshAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    LastCitationRefreshAt,
+}
@@ -0,0 +1,82 @@
+//! Add the open_access_cache table
+//!
+//! Open access status of papers as reported by Unpaywall. A paper keeps one
+//! row, replaced when it is looked up again after the cache has expired.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OpenAccessCache::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OpenAccessCache::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OpenAccessCache::PaperId)
+                            .integer()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(OpenAccessCache::Doi).string().not_null())
+                    .col(ColumnDef::new(OpenAccessCache::IsOa).boolean().not_null())
+                    .col(
+                        ColumnDef::new(OpenAccessCache::OaStatus)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(OpenAccessCache::OaUrl).string().null())
+                    .col(ColumnDef::new(OpenAccessCache::License).string().null())
+                    .col(
+                        ColumnDef::new(OpenAccessCache::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_open_access_cache_paper")
+                            .from(OpenAccessCache::Table, OpenAccessCache::PaperId)
+                            .to(Paper::Table, Paper::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OpenAccessCache::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Id,
+}
+
+#[derive(Iden)]
+enum OpenAccessCache {
+    Table,
+    Id,
+    PaperId,
+    Doi,
+    IsOa,
+    OaStatus,
+    OaUrl,
+    License,
+    UpdatedAt,
+}
@@ -0,0 +1,44 @@
+//! Add a due_date column to reading_queue
+//!
+//! A queued paper can be given a date by which it should be read. Papers
+//! whose due date has passed are suggested before the head of the queue.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReadingQueue::Table)
+                    .add_column(
+                        ColumnDef::new(ReadingQueue::DueDate)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ReadingQueue::Table)
+                    .drop_column(ReadingQueue::DueDate)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ReadingQueue {
+    Table,
+    DueDate,
+}
@@ -0,0 +1,41 @@
+//! Index paper.language
+//!
+//! Papers are listed by detected language, so the column that has been on
+//! paper since `m20250307_000002_add_paper_fields` gets an index.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_language")
+                    .table(Paper::Table)
+                    .col(Paper::Language)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_paper_language")
+                    .table(Paper::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    Language,
+}
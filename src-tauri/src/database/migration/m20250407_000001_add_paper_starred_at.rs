@@ -0,0 +1,68 @@
+//! Add a starred_at column to paper
+//!
+//! Starred papers are listed by when they were starred. Papers starred
+//! before this migration take their last update as an approximation.
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .add_column(
+                        ColumnDef::new(Paper::StarredAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .get_connection()
+            .execute_unprepared("UPDATE paper SET starred_at = updated_at WHERE is_starred = 1")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_paper_starred_at")
+                    .table(Paper::Table)
+                    .col(Paper::StarredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_paper_starred_at")
+                    .table(Paper::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Paper::Table)
+                    .drop_column(Paper::StarredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Paper {
+    Table,
+    StarredAt,
+}
@@ -10,6 +10,33 @@ mod m20250308_000001_add_attachment_count;
 mod m20250309_000001_add_fts5_search;
 mod m20250310_000001_update_fts5_tokenizer;
 mod m20250311_000001_add_search_history;
+mod m20250312_000001_add_api_keys;
+mod m20250313_000001_add_import_history;
+mod m20250314_000001_add_reading_progress;
+mod m20250315_000001_add_author_fts;
+mod m20250316_000001_add_clipping_fts;
+mod m20250317_000001_add_paper_isbn;
+mod m20250318_000001_add_paper_reference;
+mod m20250319_000001_add_paper_citation;
+mod m20250320_000001_add_paper_ai_summary;
+mod m20250321_000001_add_paper_embedding;
+mod m20250322_000001_add_reference_details;
+mod m20250323_000001_add_author_orcid;
+mod m20250324_000001_add_paper_text_content;
+mod m20250325_000001_add_activity_log;
+mod m20250326_000001_add_label_sort_order;
+mod m20250327_000001_add_label_parent;
+mod m20250328_000001_add_paper_note_version;
+mod m20250329_000001_add_paper_star;
+mod m20250330_000001_add_paper_custom_field;
+mod m20250331_000001_add_paper_note_fts;
+mod m20250401_000001_relax_paper_category_unique;
+mod m20250402_000001_add_reading_queue;
+mod m20250403_000001_add_paper_citation_refresh;
+mod m20250404_000001_add_open_access_cache;
+mod m20250405_000001_add_reading_queue_due_date;
+mod m20250406_000001_add_paper_language_index;
+mod m20250407_000001_add_paper_starred_at;
 
 #[allow(unused_imports)]
 pub use m20240101_000001_initial::Migration as InitialMigration;
@@ -22,6 +49,15 @@ pub async fn run_migrations(db: &DatabaseConnection) -> Result<(), DbErr> {
     Ok(())
 }
 
+/// Name of the newest migration, recorded as the schema version in library
+/// archives
+pub fn schema_version() -> String {
+    Migrator::migrations()
+        .last()
+        .map(|m| m.name().to_string())
+        .unwrap_or_default()
+}
+
 pub struct Migrator;
 
 impl MigratorTrait for Migrator {
@@ -34,6 +70,33 @@ impl MigratorTrait for Migrator {
             Box::new(m20250309_000001_add_fts5_search::Migration),
             Box::new(m20250310_000001_update_fts5_tokenizer::Migration),
             Box::new(m20250311_000001_add_search_history::Migration),
+            Box::new(m20250312_000001_add_api_keys::Migration),
+            Box::new(m20250313_000001_add_import_history::Migration),
+            Box::new(m20250314_000001_add_reading_progress::Migration),
+            Box::new(m20250315_000001_add_author_fts::Migration),
+            Box::new(m20250316_000001_add_clipping_fts::Migration),
+            Box::new(m20250317_000001_add_paper_isbn::Migration),
+            Box::new(m20250318_000001_add_paper_reference::Migration),
+            Box::new(m20250319_000001_add_paper_citation::Migration),
+            Box::new(m20250320_000001_add_paper_ai_summary::Migration),
+            Box::new(m20250321_000001_add_paper_embedding::Migration),
+            Box::new(m20250322_000001_add_reference_details::Migration),
+            Box::new(m20250323_000001_add_author_orcid::Migration),
+            Box::new(m20250324_000001_add_paper_text_content::Migration),
+            Box::new(m20250325_000001_add_activity_log::Migration),
+            Box::new(m20250326_000001_add_label_sort_order::Migration),
+            Box::new(m20250327_000001_add_label_parent::Migration),
+            Box::new(m20250328_000001_add_paper_note_version::Migration),
+            Box::new(m20250329_000001_add_paper_star::Migration),
+            Box::new(m20250330_000001_add_paper_custom_field::Migration),
+            Box::new(m20250331_000001_add_paper_note_fts::Migration),
+            Box::new(m20250401_000001_relax_paper_category_unique::Migration),
+            Box::new(m20250402_000001_add_reading_queue::Migration),
+            Box::new(m20250403_000001_add_paper_citation_refresh::Migration),
+            Box::new(m20250404_000001_add_open_access_cache::Migration),
+            Box::new(m20250405_000001_add_reading_queue_due_date::Migration),
+            Box::new(m20250406_000001_add_paper_language_index::Migration),
+            Box::new(m20250407_000001_add_paper_starred_at::Migration),
         ]
     }
 }
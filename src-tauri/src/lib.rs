@@ -8,47 +8,123 @@ mod papers;
 mod repository;
 mod service;
 mod sys;
+#[cfg(test)]
+mod testing;
 
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::command::activity_command::{get_recent_activity, get_recently_viewed_papers};
+use crate::command::admin_command::{
+    check_database_integrity, get_database_status, repair_database,
+};
+use crate::command::api_key_command::{
+    create_api_key, list_api_keys, regenerate_api_token, revoke_api_key,
+};
+use crate::command::api_server_command::get_api_server_status;
+use crate::command::author_command::{
+    get_author_papers, list_authors, merge_authors, rename_author, search_authors,
+    suggest_author_duplicates, update_author,
+};
+use crate::command::backup_command::{
+    backup_database, export_library_backup, restore_database, restore_library_backup,
+    validate_backup,
+};
 use crate::command::category_command::{
-    create_category, delete_category, get_selected_category, load_categories, move_category,
-    reorder_tree, set_selected_category, update_category,
+    create_category, delete_category, get_category_tree_with_counts, get_selected_category,
+    load_categories, move_category, reorder_tree, set_selected_category,
+    suggest_categories_for_paper, update_category,
 };
 use crate::command::clip_command::{
-    add_clip_comment, create_clip, delete_clip_comment, get_clip, list_clips, update_clip_comment,
+    add_clip_comment, add_clip_label, create_clip, delete_clip, delete_clip_comment, get_clip,
+    get_clip_stats, get_clips_by_label, list_clips, remove_clip_label, search_clippings,
+    update_clip, update_clip_comment,
+};
+use crate::command::config_command::{
+    check_grobid_server, get_app_config, get_grobid_servers, get_llm_config, save_app_config,
+    save_llm_config, set_active_grobid_server, test_llm_connection,
 };
-use crate::command::config_command::{get_app_config, save_app_config};
 use crate::command::data_folder_command::{
-    clear_all_data_command, get_data_folder_info_command, get_default_data_folder,
-    migrate_data_folder_command, restart_app, revert_to_default_data_folder_command,
-    validate_data_folder_command,
+    cancel_migration, clear_all_data_command, get_data_folder_info_command,
+    get_default_data_folder, get_disk_usage_breakdown, migrate_data_folder_command, restart_app,
+    revert_to_default_data_folder_command, rollback_data_migration, validate_data_folder_command,
+};
+use crate::command::download_command::{cancel_download, list_active_downloads};
+use crate::command::export_command::{
+    export_all_to_markdown, export_csl_json, export_library_as_zip, export_paper_to_markdown,
+    export_papers_csv, generate_citation_string,
+};
+use crate::command::keyword_command::{
+    add_paper_keyword, delete_keyword, extract_keywords_from_abstract, get_all_keywords,
+    get_paper_keywords, get_papers_by_keyword, merge_keywords, remove_paper_keyword,
+    search_keywords,
+};
+use crate::command::label_command::{
+    create_label, delete_label, delete_labels, find_unused_labels, get_all_labels, get_label_tree,
+    get_label_usage_stats, get_papers_by_label, merge_labels, reorder_labels, update_label,
+};
+use crate::command::llm_command::{get_cached_summary, summarize_paper_abstract};
+use crate::command::metadata_command::{
+    cancel_metadata_refresh, enrich_paper_from_crossref, get_oa_status_for_all_papers,
+    get_paper_open_access_info, refresh_all_metadata, refresh_paper_metadata,
+    trigger_citation_count_refresh,
 };
-use crate::command::label_command::{create_label, delete_label, get_all_labels, update_label};
 use crate::command::paper::{
-    add_attachment, add_paper_label, delete_paper, get_all_papers, get_attachments,
-    get_deleted_papers, get_paper, get_paper_count, get_papers_by_category, get_papers_paginated,
-    get_pdf_attachment_path, import_paper_by_arxiv_id, import_paper_by_doi, import_paper_by_pdf,
-    import_paper_by_pmid, import_papers_from_zotero_rdf, migrate_abstract_field, open_paper_folder,
-    permanently_delete_paper, read_pdf_as_blob, read_pdf_file, remove_paper_label,
-    repair_attachment_counts, restore_paper, save_pdf_blob, save_pdf_with_annotations,
-    stream_all_papers, update_paper_category, update_paper_details,
+    add_attachment, add_paper_label, add_paper_to_category, add_to_reading_list,
+    bulk_update_paper_category, check_duplicate_paper, delete_paper, delete_paper_custom_field,
+    dequeue_paper, detect_identifier_from_clipboard, detect_language_for_all_papers,
+    detect_paper_language, download_attachment_from_url, embed_pdf_text_layer, enqueue_paper,
+    extract_references, extract_text_from_scanned_pdf, generate_pdf_thumbnail, get_all_papers,
+    get_attachments, get_cached_thumbnail, get_citation_graph, get_deleted_papers,
+    get_import_queue_status, get_library_storage_stats, get_next_paper_to_read, get_paper,
+    get_paper_citation_network, get_paper_count, get_paper_notes, get_paper_notes_history,
+    get_paper_references, get_paper_summaries, get_papers_by_category, get_papers_by_language,
+    get_papers_grouped_by_added, get_papers_in_bucket, get_papers_paginated,
+    get_papers_with_missing_metadata, get_pdf_attachment_path, get_reading_list, get_reading_queue,
+    get_related_papers, get_starred_papers, import_doi_file, import_library_from_zip,
+    import_paper_by_arxiv_id, import_paper_by_doi, import_paper_by_isbn, import_paper_by_pdf,
+    import_paper_by_pmid, import_paper_by_url, import_papers_from_csv, import_papers_from_folder,
+    import_papers_from_zotero_rdf, import_pdfs_batch, link_citation, list_custom_field_keys,
+    migrate_abstract_field, normalize_attachment_paths, open_paper_folder,
+    permanently_delete_paper, read_pdf_as_blob, read_pdf_file, remove_from_reading_list,
+    remove_paper_from_category, remove_paper_label, reorder_reading_list, reorder_reading_queue,
+    repair_attachment_counts, restore_note_version, restore_paper, save_pdf_blob,
+    save_pdf_with_annotations, set_paper_custom_field, star_paper, stream_all_papers,
+    summarize_paper, toggle_paper_star, unlink_citation, unstar_paper, update_attachment_file_size,
+    update_paper_authors, update_paper_category, update_paper_details, update_paper_notes,
+    validate_all_attachments,
 };
+use crate::command::quiet_hours_command::{get_background_status, set_focus_mode};
+use crate::command::reading_progress_command::{get_reading_progress, update_reading_progress};
 use crate::command::search_command::{
-    add_search_history, check_fts_index_status, clear_search_history, debug_fts_query, delete_search_history,
-    get_fts_sample, get_search_history, get_search_suggestions, rebuild_search_index, search_papers, search_papers_fts,
+    add_search_history, check_fts_index_status, clear_search_history, debug_fts_query,
+    delete_search_history, get_fts_sample, get_search_history, get_search_suggestions,
+    rebuild_search_index, reindex_embeddings, search_all, search_clips, search_paper_notes,
+    search_papers, search_papers_fts, search_papers_semantic,
 };
-use crate::axum::state::SelectedCategoryState;
-use crate::database::connection::init_sqlite_connection;
+use crate::command::share_command::share_paper_notes;
+use crate::command::shortcut_command::get_shortcut_status;
+use crate::command::stats_command::{
+    export_reading_statistics, get_library_stats, get_metadata_completeness_report,
+    get_reading_statistics,
+};
+use crate::axum::auth::AppTokenState;
+use crate::axum::state::{ApiServerState, SelectedCategoryState};
+use crate::service::citation_count_service::CitationRefreshState;
+use crate::service::backup_service::DATABASE_FILE;
+use crate::service::clipboard_capture_service::{register_capture_shortcut, ShortcutStatusState};
+use crate::service::data_migration_service::DataMigrationState;
+use crate::service::database_recovery_service::DatabaseStatusState;
+use crate::service::download_service::DownloadRegistry;
+use crate::service::pdf_import_queue_service::PdfImportQueue;
+use crate::service::metadata_refresh_service::MetadataRefreshState;
+use crate::service::quiet_hours_service::FocusModeState;
+use crate::service::share_service::ShareRegistry;
+use crate::database::connection::init_sqlite_connection_with_retry;
 use crate::database::DatabaseConnection;
 use crate::sys::error::Result;
 use futures::executor::block_on;
 use tauri::Manager;
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-};
 use tracing::info;
 
 use crate::sys::dirs::init_app_dirs;
@@ -77,7 +153,9 @@ pub fn run() -> Result<()> {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init());
 
@@ -90,72 +168,118 @@ pub fn run() -> Result<()> {
             let app_handle = app.handle().clone();
             app_handle.manage(log_guard);
             app_handle.manage(app_dirs.clone());
+            // Cancellation handle for migrate_data_folder_command
+            app_handle.manage(DataMigrationState::default());
 
             // Initialize SQLite database
             let app_handle_for_axum = app.handle().clone();
             let app_dirs_for_db = app_dirs.clone();
             let data_dir = app_dirs_for_db.data.clone();
 
+            let db_path = PathBuf::from(&data_dir).join(DATABASE_FILE);
             let db_result = tauri::async_runtime::block_on(async move {
-                init_sqlite_connection(PathBuf::from(&data_dir)).await
+                init_sqlite_connection_with_retry(PathBuf::from(&data_dir)).await
             });
 
             match db_result {
                 Ok(db) => {
                     info!("SQLite connection initialized");
+                    app_handle.manage(DatabaseStatusState::available(&db_path));
                     let db_arc: Arc<DatabaseConnection> = db;
                     app_handle.manage(db_arc.clone());
 
+                    // Quiet hours and focus mode defer scheduled background work
+                    let focus_mode_state = FocusModeState::new();
+                    app_handle.manage(focus_mode_state.clone());
+
+                    crate::service::backup_service::spawn_auto_backup(
+                        db_arc.clone(),
+                        app_dirs_for_db.clone(),
+                        focus_mode_state.clone(),
+                    );
+
+                    // Daily citation counts; shared with the manual trigger
+                    let citation_refresh_state = CitationRefreshState::new();
+                    app_handle.manage(citation_refresh_state.clone());
+                    crate::service::citation_count_service::spawn_citation_refresh(
+                        app_handle.clone(),
+                        db_arc.clone(),
+                        app_dirs_for_db.clone(),
+                        focus_mode_state,
+                        citation_refresh_state,
+                    );
+
                     // Create and register shared selected category state
                     let selected_category_state = SelectedCategoryState::new();
                     app_handle.manage(selected_category_state.clone());
 
+                    // Share links are created by commands and served by Axum
+                    let share_registry = ShareRegistry::new();
+                    app_handle.manage(share_registry.clone());
+
+                    // Downloads are started by commands and by Axum handlers
+                    let download_registry = DownloadRegistry::new();
+                    app_handle.manage(download_registry.clone());
+                    crate::service::download_service::spawn_stale_part_cleanup(
+                        app_dirs_for_db.clone(),
+                    );
+                    crate::service::activity_service::spawn_retention_cleanup(
+                        db_arc.clone(),
+                        app_dirs_for_db.clone(),
+                    );
+
+                    // Files dropped for import, kept across window reloads
+                    app_handle.manage(PdfImportQueue::new());
+
+                    // Cancellation handle for refresh_all_metadata
+                    app_handle.manage(MetadataRefreshState::default());
+
+                    // Desktop app's API token. Issued at startup so a fresh
+                    // install has an admin key before the first request.
+                    let app_token = AppTokenState::default();
+                    app_handle.manage(app_token.clone());
+                    let token_db = db_arc.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = app_token.get(&token_db).await {
+                            tracing::error!("Failed to issue the app API token: {}", e);
+                        }
+                    });
+
+                    // Filled in with the bound port once the server is listening
+                    let api_server_state = ApiServerState::new();
+                    app_handle.manage(api_server_state.clone());
+
+                    // Clipboard capture hotkey; a taken hotkey is reported,
+                    // not fatal
+                    let shortcut_status = ShortcutStatusState::new();
+                    app_handle.manage(shortcut_status.clone());
+                    register_capture_shortcut(
+                        &app_handle,
+                        &app_dirs_for_db.config,
+                        &shortcut_status,
+                    );
+
                     // Start Axum API server with SQLite
                     crate::axum::start_axum_server_with_handle(
                         db_arc,
                         app_dirs_for_db,
                         app_handle_for_axum,
                         selected_category_state,
+                        share_registry,
+                        download_registry,
+                        api_server_state,
                     );
                 }
                 Err(e) => {
+                    // Keep starting so the window can report the failure and
+                    // offer `repair_database`
                     tracing::error!("Failed to initialize SQLite connection: {}", e);
-                    return Err(Box::new(e));
+                    app_handle.manage(DatabaseStatusState::unavailable(&db_path, &e));
                 }
             }
 
             // Setup system tray
-            let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&quit_i])?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| {
-                    if event.id.as_ref() == "quit" {
-                        app.exit(0);
-                    }
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            if window.is_visible().unwrap_or(false) {
-                                let _ = window.hide();
-                            } else {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                    }
-                })
-                .build(app)?;
+            crate::service::tray_menu_service::setup_tray(app.handle())?;
 
             Ok(())
         })
@@ -170,8 +294,16 @@ pub fn run() -> Result<()> {
             get_all_labels,
             create_label,
             delete_label,
+            merge_labels,
+            find_unused_labels,
+            delete_labels,
+            get_label_tree,
+            get_papers_by_label,
+            reorder_labels,
+            get_label_usage_stats,
             update_label,
             load_categories,
+            get_category_tree_with_counts,
             create_category,
             delete_category,
             update_category,
@@ -179,38 +311,114 @@ pub fn run() -> Result<()> {
             reorder_tree,
             set_selected_category,
             get_selected_category,
+            suggest_categories_for_paper,
             get_all_papers,
             get_deleted_papers,
             get_paper_count,
+            get_library_stats,
+            get_metadata_completeness_report,
+            get_reading_statistics,
+            export_reading_statistics,
+            get_papers_with_missing_metadata,
+            get_recent_activity,
+            get_recently_viewed_papers,
+            check_database_integrity,
+            get_database_status,
+            repair_database,
             get_papers_paginated,
+            get_starred_papers,
             get_papers_by_category,
             stream_all_papers,
             get_paper,
             import_paper_by_doi,
+            import_doi_file,
+            import_papers_from_csv,
+            import_library_from_zip,
             import_paper_by_arxiv_id,
             import_paper_by_pdf,
             import_paper_by_pmid,
+            import_paper_by_url,
+            import_paper_by_isbn,
+            detect_identifier_from_clipboard,
             import_papers_from_zotero_rdf,
+            import_papers_from_folder,
+            import_pdfs_batch,
+            get_import_queue_status,
+            extract_references,
+            get_paper_references,
+            link_citation,
+            unlink_citation,
+            get_citation_graph,
+            get_paper_citation_network,
+            summarize_paper,
+            get_paper_summaries,
+            update_paper_notes,
+            get_paper_notes,
+            get_paper_notes_history,
+            restore_note_version,
+            set_paper_custom_field,
+            delete_paper_custom_field,
+            list_custom_field_keys,
+            get_related_papers,
             add_paper_label,
             remove_paper_label,
             update_paper_details,
+            update_paper_authors,
             update_paper_category,
+            bulk_update_paper_category,
+            add_paper_to_category,
+            remove_paper_from_category,
+            check_duplicate_paper,
             delete_paper,
+            star_paper,
+            toggle_paper_star,
+            unstar_paper,
+            enqueue_paper,
+            dequeue_paper,
+            reorder_reading_queue,
+            get_reading_queue,
+            add_to_reading_list,
+            remove_from_reading_list,
+            reorder_reading_list,
+            get_reading_list,
+            get_next_paper_to_read,
+            detect_paper_language,
+            detect_language_for_all_papers,
+            get_papers_by_language,
+            get_papers_grouped_by_added,
+            get_papers_in_bucket,
             restore_paper,
             permanently_delete_paper,
             add_attachment,
             get_attachments,
+            update_attachment_file_size,
+            get_library_storage_stats,
             open_paper_folder,
             get_pdf_attachment_path,
+            generate_pdf_thumbnail,
+            get_cached_thumbnail,
             read_pdf_file,
             read_pdf_as_blob,
+            embed_pdf_text_layer,
+            extract_text_from_scanned_pdf,
             save_pdf_blob,
             save_pdf_with_annotations,
             get_app_config,
             save_app_config,
+            get_llm_config,
+            save_llm_config,
+            test_llm_connection,
+            check_grobid_server,
+            get_grobid_servers,
+            set_active_grobid_server,
             // Search commands
             search_papers,
             search_papers_fts,
+            search_papers_semantic,
+            reindex_embeddings,
+            search_clips,
+            search_paper_notes,
+            search_all,
             get_search_suggestions,
             rebuild_search_index,
             check_fts_index_status,
@@ -223,22 +431,95 @@ pub fn run() -> Result<()> {
             delete_search_history,
             // Data folder commands
             get_data_folder_info_command,
+            get_disk_usage_breakdown,
             get_default_data_folder,
             validate_data_folder_command,
             migrate_data_folder_command,
+            cancel_migration,
+            rollback_data_migration,
             revert_to_default_data_folder_command,
             restart_app,
             clear_all_data_command,
             // Database migration commands
             migrate_abstract_field,
             repair_attachment_counts,
+            normalize_attachment_paths,
+            validate_all_attachments,
             // Clip commands
             list_clips,
+            search_clippings,
             get_clip,
+            get_clip_stats,
+            get_clips_by_label,
             create_clip,
+            update_clip,
+            delete_clip,
+            add_clip_label,
+            remove_clip_label,
             add_clip_comment,
             update_clip_comment,
-            delete_clip_comment
+            delete_clip_comment,
+            // API key commands
+            create_api_key,
+            list_api_keys,
+            revoke_api_key,
+            regenerate_api_token,
+            get_api_server_status,
+            get_shortcut_status,
+            // Backup commands
+            backup_database,
+            export_library_backup,
+            validate_backup,
+            restore_library_backup,
+            restore_database,
+            // Share commands
+            share_paper_notes,
+            // Reading progress commands
+            update_reading_progress,
+            get_reading_progress,
+            // Export commands
+            export_csl_json,
+            generate_citation_string,
+            export_paper_to_markdown,
+            export_all_to_markdown,
+            export_library_as_zip,
+            export_papers_csv,
+            // Author commands
+            get_author_papers,
+            search_authors,
+            update_author,
+            list_authors,
+            rename_author,
+            merge_authors,
+            suggest_author_duplicates,
+            // Keyword commands
+            extract_keywords_from_abstract,
+            get_all_keywords,
+            get_paper_keywords,
+            add_paper_keyword,
+            remove_paper_keyword,
+            search_keywords,
+            get_papers_by_keyword,
+            delete_keyword,
+            merge_keywords,
+            // Quiet hours commands
+            set_focus_mode,
+            get_background_status,
+            // Download commands
+            download_attachment_from_url,
+            list_active_downloads,
+            cancel_download,
+            // Metadata refresh commands
+            refresh_paper_metadata,
+            enrich_paper_from_crossref,
+            refresh_all_metadata,
+            cancel_metadata_refresh,
+            trigger_citation_count_refresh,
+            get_paper_open_access_info,
+            get_oa_status_for_all_papers,
+            // LLM commands
+            summarize_paper_abstract,
+            get_cached_summary
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -2,7 +2,10 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::sys::config::LlmProvider;
+use crate::sys::config::{LlmProvider, LlmProviderKind};
+
+/// `anthropic-version` header sent with Anthropic requests
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
 
 #[derive(Error, Debug)]
 pub enum LlmError {
@@ -33,6 +36,46 @@ struct ChatRequest {
     model: String,
     messages: Vec<ChatMessage>,
     temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+/// Anthropic Messages API request; the system prompt is a top-level field
+#[derive(Serialize)]
+struct AnthropicRequest {
+    model: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    system: String,
+    messages: Vec<ChatMessage>,
+    temperature: f32,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContent {
+    #[serde(default)]
+    text: String,
+}
+
+/// One streamed Anthropic event; only `content_block_delta` carries text
+#[derive(Deserialize)]
+struct AnthropicEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    delta: Option<AnthropicDelta>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicDelta {
+    text: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -50,6 +93,21 @@ struct MessageContent {
     content: String,
 }
 
+#[derive(Deserialize)]
+struct ChatChunk {
+    choices: Vec<ChunkChoice>,
+}
+
+#[derive(Deserialize)]
+struct ChunkChoice {
+    delta: ChunkDelta,
+}
+
+#[derive(Deserialize)]
+struct ChunkDelta {
+    content: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct ErrorResponse {
     error: Option<ApiErrorDetail>,
@@ -81,62 +139,192 @@ impl LlmClient {
         system_prompt: &str,
         user_content: &str,
     ) -> Result<String, LlmError> {
-        let url = format!("{}/chat/completions", provider.base_url.trim_end_matches('/'));
-
-        let request = ChatRequest {
-            model: provider.model_name.clone(),
-            messages: vec![
-                ChatMessage {
-                    role: "system".to_string(),
-                    content: system_prompt.to_string(),
-                },
-                ChatMessage {
-                    role: "user".to_string(),
-                    content: user_content.to_string(),
-                },
-            ],
-            temperature: 0.3,
-        };
-
         let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", format!("Bearer {}", provider.api_key))
-            .json(&request)
-            .send()
+            .send(provider, system_prompt, user_content, false)
             .await?;
-
-        let status = response.status();
         let body = response.text().await?;
+        parse_chat_response(provider.kind, &body)
+    }
 
-        if !status.is_success() {
-            // Try to parse error message
-            if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&body) {
-                if let Some(error) = error_response.error {
-                    if let Some(message) = error.message {
-                        return Err(LlmError::ApiError(message));
-                    }
+    /// Send a chat request with streaming enabled, calling `on_token` with
+    /// each piece of the answer as it arrives. Returns the full answer.
+    pub async fn chat_stream<F>(
+        &self,
+        provider: &LlmProvider,
+        system_prompt: &str,
+        user_content: &str,
+        mut on_token: F,
+    ) -> Result<String, LlmError>
+    where
+        F: FnMut(&str),
+    {
+        let mut response = self
+            .send(provider, system_prompt, user_content, true)
+            .await?;
+
+        // Server-sent events: `data: {json}` lines. OpenAI-style streams end
+        // with `data: [DONE]`, Anthropic streams simply close.
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut content = String::new();
+        'read: while let Some(chunk) = response.chunk().await? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let line = String::from_utf8_lossy(&line);
+                let Some(data) = line.trim().strip_prefix("data:") else {
+                    continue;
+                };
+                let data = data.trim();
+                if data == "[DONE]" {
+                    break 'read;
+                }
+                if let Some(token) = parse_stream_chunk(provider.kind, data)? {
+                    on_token(&token);
+                    content.push_str(&token);
                 }
             }
-            return Err(LlmError::ApiError(format!(
-                "API returned status {}: {}",
-                status, body
-            )));
         }
 
-        // Parse successful response
-        let chat_response: ChatResponse =
-            serde_json::from_str(&body).map_err(|e| LlmError::ParseError(e.to_string()))?;
+        if content.is_empty() {
+            return Err(LlmError::NoResponse);
+        }
+        Ok(content)
+    }
+
+    /// POST a chat completion request, failing on error statuses
+    async fn send(
+        &self,
+        provider: &LlmProvider,
+        system_prompt: &str,
+        user_content: &str,
+        stream: bool,
+    ) -> Result<reqwest::Response, LlmError> {
+        let base_url = match provider.base_url.trim() {
+            "" => provider.kind.default_base_url(),
+            url => url,
+        }
+        .trim_end_matches('/');
+        let system_message = ChatMessage {
+            role: "system".to_string(),
+            content: system_prompt.to_string(),
+        };
+        let user_message = ChatMessage {
+            role: "user".to_string(),
+            content: user_content.to_string(),
+        };
+
+        let request = match provider.kind {
+            LlmProviderKind::Anthropic => self
+                .client
+                .post(format!("{}/messages", base_url))
+                .header("x-api-key", provider.api_key.as_deref().unwrap_or_default())
+                .header("anthropic-version", ANTHROPIC_API_VERSION)
+                .json(&AnthropicRequest {
+                    model: provider.model_name.clone(),
+                    system: system_message.content,
+                    messages: vec![user_message],
+                    temperature: provider.temperature,
+                    max_tokens: provider.max_tokens,
+                    stream,
+                }),
+            _ => {
+                let request = self
+                    .client
+                    .post(format!("{}/chat/completions", base_url))
+                    .json(&ChatRequest {
+                        model: provider.model_name.clone(),
+                        messages: vec![system_message, user_message],
+                        temperature: provider.temperature,
+                        max_tokens: provider.max_tokens,
+                        stream,
+                    });
+                match provider.api_key.as_deref().filter(|k| !k.trim().is_empty()) {
+                    Some(key) => request.header("Authorization", format!("Bearer {}", key)),
+                    None => request,
+                }
+            }
+        };
 
-        let content = chat_response
-            .choices
-            .first()
-            .map(|c| c.message.content.clone())
-            .ok_or(LlmError::NoResponse)?;
+        let response = request.send().await?;
 
-        Ok(content)
+        check_status(response).await
+    }
+}
+
+/// Pass successful responses through and turn error statuses into
+/// `LlmError::InvalidApiKey` or `LlmError::ApiError`
+pub(crate) async fn check_status(
+    response: reqwest::Response,
+) -> Result<reqwest::Response, LlmError> {
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(LlmError::InvalidApiKey);
+    }
+    if !status.is_success() {
+        let body = response.text().await?;
+        // Try to parse error message
+        if let Ok(error_response) = serde_json::from_str::<ErrorResponse>(&body) {
+            if let Some(error) = error_response.error {
+                if let Some(message) = error.message {
+                    return Err(LlmError::ApiError(message));
+                }
+            }
+        }
+        return Err(LlmError::ApiError(format!(
+            "API returned status {}: {}",
+            status, body
+        )));
     }
+
+    Ok(response)
+}
+
+/// Answer text of a non-streamed response
+fn parse_chat_response(kind: LlmProviderKind, body: &str) -> Result<String, LlmError> {
+    let parse_error = |e: serde_json::Error| LlmError::ParseError(e.to_string());
+    match kind {
+        LlmProviderKind::Anthropic => {
+            let response: AnthropicResponse = serde_json::from_str(body).map_err(parse_error)?;
+            let text: String = response.content.into_iter().map(|c| c.text).collect();
+            if text.is_empty() {
+                return Err(LlmError::NoResponse);
+            }
+            Ok(text)
+        }
+        _ => {
+            let response: ChatResponse = serde_json::from_str(body).map_err(parse_error)?;
+            response
+                .choices
+                .into_iter()
+                .next()
+                .map(|c| c.message.content)
+                .ok_or(LlmError::NoResponse)
+        }
+    }
+}
+
+/// Text carried by one streamed chunk, if any
+fn parse_stream_chunk(kind: LlmProviderKind, data: &str) -> Result<Option<String>, LlmError> {
+    let text = match kind {
+        LlmProviderKind::Anthropic => {
+            let event: AnthropicEvent =
+                serde_json::from_str(data).map_err(|e| LlmError::ParseError(e.to_string()))?;
+            if event.kind != "content_block_delta" {
+                return Ok(None);
+            }
+            event.delta.and_then(|d| d.text)
+        }
+        _ => {
+            let chunk: ChatChunk =
+                serde_json::from_str(data).map_err(|e| LlmError::ParseError(e.to_string()))?;
+            chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.delta.content)
+        }
+    };
+    Ok(text.filter(|t| !t.is_empty()))
 }
 
 impl Default for LlmClient {
@@ -144,3 +332,45 @@ impl Default for LlmClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_chunk() {
+        let openai = LlmProviderKind::OpenAiCompatible;
+        let data = r#"{"choices":[{"index":0,"delta":{"content":"Hello"}}]}"#;
+        assert_eq!(
+            parse_stream_chunk(openai, data).unwrap(),
+            Some("Hello".to_string())
+        );
+
+        // The first chunk usually only carries the role
+        let data = r#"{"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(parse_stream_chunk(openai, data).unwrap(), None);
+
+        assert!(parse_stream_chunk(openai, "not json").is_err());
+    }
+
+    #[test]
+    fn test_parse_anthropic_responses() {
+        let anthropic = LlmProviderKind::Anthropic;
+        let data =
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#;
+        assert_eq!(
+            parse_stream_chunk(anthropic, data).unwrap(),
+            Some("Hi".to_string())
+        );
+        let data = r#"{"type":"message_start","message":{"id":"msg_1"}}"#;
+        assert_eq!(parse_stream_chunk(anthropic, data).unwrap(), None);
+
+        let body =
+            r#"{"id":"msg_1","content":[{"type":"text","text":"OK"}],"stop_reason":"end_turn"}"#;
+        assert_eq!(parse_chat_response(anthropic, body).unwrap(), "OK");
+        assert!(matches!(
+            parse_chat_response(anthropic, r#"{"content":[]}"#),
+            Err(LlmError::NoResponse)
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::llm::client::{check_status, LlmError};
+use crate::sys::config::EmbeddingConfig;
+
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+pub struct EmbeddingClient {
+    client: Client,
+}
+
+impl EmbeddingClient {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(std::time::Duration::from_secs(60))
+                .build()
+                .unwrap_or_else(|_| Client::new()),
+        }
+    }
+
+    /// Embed each of `inputs` with one request. The vectors come back in
+    /// the same order as the inputs.
+    pub async fn embed(
+        &self,
+        config: &EmbeddingConfig,
+        inputs: &[String],
+    ) -> Result<Vec<Vec<f32>>, LlmError> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/embeddings", config.base_url.trim_end_matches('/'));
+        let mut request = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&EmbeddingRequest {
+                model: &config.model_name,
+                input: inputs,
+            });
+        if let Some(key) = config.api_key.as_deref().filter(|k| !k.trim().is_empty()) {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let response = check_status(request.send().await?).await?;
+        let body = response.text().await?;
+        parse_embeddings(&body, inputs.len())
+    }
+}
+
+/// Vectors of an `/embeddings` response, ordered by input index
+fn parse_embeddings(body: &str, expected: usize) -> Result<Vec<Vec<f32>>, LlmError> {
+    let mut response: EmbeddingResponse =
+        serde_json::from_str(body).map_err(|e| LlmError::ParseError(e.to_string()))?;
+    if response.data.len() != expected {
+        return Err(LlmError::ParseError(format!(
+            "Expected {} embeddings, got {}",
+            expected,
+            response.data.len()
+        )));
+    }
+
+    response.data.sort_by_key(|d| d.index);
+    Ok(response.data.into_iter().map(|d| d.embedding).collect())
+}
+
+impl Default for EmbeddingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embeddings_orders_by_index() {
+        let body = r#"{"data":[
+            {"object":"embedding","index":1,"embedding":[0.0,1.0]},
+            {"object":"embedding","index":0,"embedding":[1.0,0.0]}
+        ]}"#;
+        let vectors = parse_embeddings(body, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+
+        assert!(parse_embeddings(body, 3).is_err());
+        assert!(parse_embeddings("not json", 1).is_err());
+    }
+}
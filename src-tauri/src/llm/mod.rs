@@ -1,3 +1,3 @@
 pub mod client;
+pub mod embedding;
 pub mod prompts;
-
@@ -70,3 +70,24 @@ JSON Schema reference:
 
 # Input HTML
 "#;
+
+/// System prompt for a three-sentence summary of a paper
+pub const SUMMARY_TLDR_PROMPT: &str = r#"You are a research assistant summarizing academic papers.
+Write a TL;DR of the paper below in exactly three sentences: the problem it addresses, what the authors did, and the main finding.
+Use plain language, do not use Markdown, and do not invent details that are not in the text."#;
+
+/// System prompt for a structured summary of a paper
+pub const SUMMARY_DETAILED_PROMPT: &str = r#"You are a research assistant summarizing academic papers.
+Summarize the paper below as Markdown bullet points under three headings: "Method", "Results" and "Limitations".
+Give two to four concise bullets per heading. If the text does not mention limitations, say so in a single bullet. Do not invent details that are not in the text."#;
+
+/// System prompt for summarizing an abstract in at most `max_sentences` sentences
+pub fn abstract_summary_prompt(max_sentences: u32) -> String {
+    format!(
+        "You are a research assistant summarizing academic papers.\n\
+Summarize the abstract below in at most {} sentence{}, keeping the main contribution and finding.\n\
+Use plain language, do not use Markdown, and do not invent details that are not in the abstract.",
+        max_sentences,
+        if max_sentences == 1 { "" } else { "s" }
+    )
+}
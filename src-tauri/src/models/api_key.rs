@@ -0,0 +1,110 @@
+//! API key domain model
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::entities::api_key;
+
+/// Permission scope granted to an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    Read,
+    Write,
+    Import,
+    Admin,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Write => "write",
+            ApiScope::Import => "import",
+            ApiScope::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ApiScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "read" => Ok(ApiScope::Read),
+            "write" => Ok(ApiScope::Write),
+            "import" => Ok(ApiScope::Import),
+            "admin" => Ok(ApiScope::Admin),
+            other => Err(format!("Unknown API scope: {}", other)),
+        }
+    }
+}
+
+/// Named API key record (the plaintext key is never stored)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<ApiScope>,
+    pub request_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey {
+    /// Whether this key may access a route requiring `required`.
+    /// Admin keys are allowed everywhere.
+    pub fn allows(&self, required: ApiScope) -> bool {
+        self.scopes
+            .iter()
+            .any(|s| *s == required || *s == ApiScope::Admin)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+}
+
+/// Parse the comma-separated scope column, skipping unknown entries
+pub fn parse_scopes(value: &str) -> Vec<ApiScope> {
+    value
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| s.parse().ok())
+        .collect()
+}
+
+/// Serialize scopes into the comma-separated column format
+pub fn join_scopes(scopes: &[ApiScope]) -> String {
+    scopes
+        .iter()
+        .map(|s| s.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl From<api_key::Model> for ApiKey {
+    fn from(model: api_key::Model) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            key_prefix: model.key_prefix,
+            scopes: parse_scopes(&model.scopes),
+            request_count: model.request_count,
+            last_used_at: model.last_used_at,
+            created_at: model.created_at,
+            revoked_at: model.revoked_at,
+        }
+    }
+}
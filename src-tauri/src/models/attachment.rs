@@ -2,9 +2,19 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
 
 use crate::database::entities::attachment;
 
+/// Calculate SHA1 hash of title for attachment path. This is the legacy
+/// naming; new papers use `attachment_service::new_attachment_dir`.
+pub fn calculate_attachment_hash(title: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(title.as_bytes());
+    let result = hasher.finalize();
+    format!("{:x}", result)
+}
+
 /// Attachment for a paper
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Attachment {
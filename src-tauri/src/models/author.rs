@@ -13,6 +13,8 @@ pub struct Author {
     pub last_name: Option<String>,
     pub affiliation: Option<String>,
     pub email: Option<String>,
+    /// ORCID iD in its bare form, e.g. "0000-0002-1825-0097"
+    pub orcid: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -23,6 +25,16 @@ pub struct CreateAuthor {
     pub last_name: Option<String>,
     pub affiliation: Option<String>,
     pub email: Option<String>,
+    pub orcid: Option<String>,
+}
+
+/// Details an importer knows about an author besides the name
+#[derive(Debug, Clone, Default)]
+pub struct AuthorDetails {
+    pub email: Option<String>,
+    pub affiliation: Option<String>,
+    /// ORCID iD in any common form; see `normalize_orcid`
+    pub orcid: Option<String>,
 }
 
 /// Structured author name parts for importers
@@ -46,6 +58,50 @@ impl Author {
     }
 }
 
+/// Bare ORCID iD of `value`, which may also be an orcid.org URL. Returns
+/// `None` when the format or the check digit is wrong.
+pub fn normalize_orcid(value: &str) -> Option<String> {
+    let value = value.trim();
+    let id = value
+        .rsplit_once("orcid.org/")
+        .map(|(_, id)| id)
+        .unwrap_or(value)
+        .trim_end_matches('/');
+
+    // Four groups of four separated by hyphens
+    let well_formed = id.chars().count() == 19
+        && id
+            .chars()
+            .enumerate()
+            .all(|(i, c)| (i % 5 == 4) == (c == '-'));
+    if !well_formed {
+        return None;
+    }
+    let chars: Vec<char> = id.chars().filter(|c| *c != '-').collect();
+    let mut total = 0u32;
+    for c in &chars[..15] {
+        total = (total + c.to_digit(10)?) * 2;
+    }
+    let check = (12 - total % 11) % 11;
+    let expected = if check == 10 {
+        'X'
+    } else {
+        char::from_digit(check, 10)?
+    };
+    if chars[15].to_ascii_uppercase() != expected {
+        return None;
+    }
+
+    let digits: String = chars[..15].iter().chain([&expected]).collect();
+    Some(format!(
+        "{}-{}-{}-{}",
+        &digits[..4],
+        &digits[4..8],
+        &digits[8..12],
+        &digits[12..]
+    ))
+}
+
 impl AuthorNameParser {
     /// Parse a full name string into first_name and last_name
     ///
@@ -139,6 +195,7 @@ impl From<CreateAuthor> for Author {
             last_name: create.last_name,
             affiliation: create.affiliation,
             email: create.email,
+            orcid: create.orcid,
             created_at: Utc::now(),
         }
     }
@@ -152,6 +209,7 @@ impl From<author::Model> for Author {
             last_name: model.last_name,
             affiliation: model.affiliation,
             email: model.email,
+            orcid: model.orcid,
             created_at: model.created_at,
         }
     }
@@ -239,6 +297,21 @@ mod tests {
         assert_eq!(name.last_name, None);
     }
 
+    #[test]
+    fn test_normalize_orcid() {
+        assert_eq!(
+            normalize_orcid("0000-0002-1825-0097").as_deref(),
+            Some("0000-0002-1825-0097")
+        );
+        assert_eq!(
+            normalize_orcid("https://orcid.org/0000-0002-1694-233x").as_deref(),
+            Some("0000-0002-1694-233X")
+        );
+        assert_eq!(normalize_orcid("0000-0002-1825-0098"), None);
+        assert_eq!(normalize_orcid("0000000218250097"), None);
+        assert_eq!(normalize_orcid(""), None);
+    }
+
     #[test]
     fn test_author_full_name() {
         let author = Author {
@@ -247,6 +320,7 @@ mod tests {
             last_name: Some("Smith".to_string()),
             affiliation: None,
             email: None,
+            orcid: None,
             created_at: Utc::now(),
         };
         assert_eq!(author.full_name(), "John Smith");
@@ -260,6 +334,7 @@ mod tests {
             last_name: None,
             affiliation: None,
             email: None,
+            orcid: None,
             created_at: Utc::now(),
         };
         assert_eq!(author.full_name(), "张三");
@@ -40,6 +40,21 @@ pub struct CategoryNode {
     pub children: Vec<CategoryNode>,
 }
 
+/// Category node with paper counts, for the sidebar tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CategoryNodeWithCount {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub sort_order: i32,
+    /// Papers filed directly in this category
+    pub direct_count: u64,
+    /// Papers in this category or any of its descendants, each counted once
+    pub recursive_count: u64,
+    #[serde(default)]
+    pub children: Vec<CategoryNodeWithCount>,
+}
+
 impl Category {
     pub fn new(name: String) -> Self {
         Self {
@@ -63,6 +63,35 @@ pub struct UpdateClipping {
     pub image_paths: Option<Vec<String>>,
 }
 
+/// Field a clip list is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    SourceDomain,
+}
+
+/// Order of a sorted list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filters for listing clips; unset filters match every clip
+#[derive(Debug, Clone, Default)]
+pub struct ClipFilter {
+    pub read_status: Option<i32>,
+    pub source_domain: Option<String>,
+    /// Clips must carry every one of these labels
+    pub label_ids: Vec<i64>,
+}
+
 impl Clipping {
     /// Create a new clipping with default values
     pub fn new(title: String, url: String, content: Option<String>, source_domain: Option<String>) -> Self {
@@ -0,0 +1,163 @@
+//! Paper custom field domain model
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::entities::paper_custom_field;
+
+/// Longest accepted custom field key
+pub const MAX_CUSTOM_FIELD_KEY_LEN: usize = 64;
+
+/// Type a custom field value is validated against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Date,
+    Bool,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Bool => "bool",
+        }
+    }
+
+    /// Check `value` against this type and return it in its stored form:
+    /// trimmed, numbers in their shortest decimal form (`1.0` becomes `1`),
+    /// dates as `YYYY-MM-DD` and booleans as `true` or `false`
+    pub fn normalize(&self, value: &str) -> Result<String, String> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err("Value is empty".to_string());
+        }
+        match self {
+            CustomFieldType::Text => Ok(value.to_string()),
+            CustomFieldType::Number => match value.parse::<f64>() {
+                // Adding 0.0 turns -0 into 0
+                Ok(n) if n.is_finite() => Ok((n + 0.0).to_string()),
+                _ => Err(format!("'{}' is not a number", value)),
+            },
+            CustomFieldType::Date => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .map_err(|_| format!("'{}' is not a date in YYYY-MM-DD form", value)),
+            CustomFieldType::Bool => match value.to_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok("true".to_string()),
+                "false" | "no" | "0" => Ok("false".to_string()),
+                _ => Err(format!("'{}' is not true or false", value)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(CustomFieldType::Text),
+            "number" => Ok(CustomFieldType::Number),
+            "date" => Ok(CustomFieldType::Date),
+            "bool" => Ok(CustomFieldType::Bool),
+            other => Err(format!("Unknown custom field type: {}", other)),
+        }
+    }
+}
+
+/// Trim a custom field key and check it can be written in a
+/// `custom:key=value` search: letters, digits, `_`, `-` and `.` only
+pub fn normalize_custom_field_key(key: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Key is empty".to_string());
+    }
+    if key.chars().count() > MAX_CUSTOM_FIELD_KEY_LEN {
+        return Err(format!(
+            "Key is longer than {} characters",
+            MAX_CUSTOM_FIELD_KEY_LEN
+        ));
+    }
+    if !key
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err("Key may only contain letters, digits, '_', '-' and '.'".to_string());
+    }
+    Ok(key.to_string())
+}
+
+/// A custom field value of a paper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomField {
+    pub paper_id: i64,
+    pub key: String,
+    pub value: String,
+    pub value_type: CustomFieldType,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CustomField {
+    /// A stored value with the type of its key
+    pub fn from_model(model: paper_custom_field::Model, value_type: CustomFieldType) -> Self {
+        Self {
+            paper_id: model.paper_id,
+            key: model.key,
+            value: model.value,
+            value_type,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+/// A custom field key in use, with its type and how many papers set it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomFieldKey {
+    pub key: String,
+    pub value_type: CustomFieldType,
+    pub paper_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_values() {
+        assert_eq!(CustomFieldType::Bool.normalize(" Yes ").unwrap(), "true");
+        assert_eq!(
+            CustomFieldType::Date.normalize("2024-3-5").unwrap(),
+            "2024-03-05"
+        );
+        assert_eq!(
+            CustomFieldType::Number.normalize("-1.5e3").unwrap(),
+            "-1500"
+        );
+        assert_eq!(CustomFieldType::Number.normalize("1.0").unwrap(), "1");
+        assert_eq!(CustomFieldType::Number.normalize("0.250").unwrap(), "0.25");
+        assert_eq!(CustomFieldType::Number.normalize("-0").unwrap(), "0");
+        assert!(CustomFieldType::Number.normalize("NaN").is_err());
+        assert!(CustomFieldType::Date.normalize("2024-02-30").is_err());
+        assert!(CustomFieldType::Text.normalize("  ").is_err());
+
+        assert_eq!(
+            normalize_custom_field_key(" grant.id ").unwrap(),
+            "grant.id"
+        );
+        assert!(normalize_custom_field_key("review decision").is_err());
+        assert!(normalize_custom_field_key("a=b").is_err());
+    }
+}
@@ -12,15 +12,37 @@ pub struct Label {
     pub name: String,
     pub color: String,
     pub document_count: i32,
+    pub sort_order: i32,
+    /// Label this one is grouped under; `None` for top-level labels
+    pub parent_id: Option<i64>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Label with the labels grouped under it, for the label tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelNode {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    pub parent_id: Option<i64>,
+    pub sort_order: i32,
+    /// Papers and clips carrying exactly this label
+    pub document_count: i32,
+    /// Papers and clips carrying this label or any label below it, each
+    /// counted once
+    pub recursive_count: u64,
+    #[serde(default)]
+    pub children: Vec<LabelNode>,
+}
+
 /// DTO for creating a new label
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateLabel {
     pub name: String,
     #[serde(default = "default_color")]
     pub color: String,
+    #[serde(default)]
+    pub parent_id: Option<i64>,
 }
 
 /// DTO for updating a label
@@ -28,6 +50,10 @@ pub struct CreateLabel {
 pub struct UpdateLabel {
     pub name: Option<String>,
     pub color: Option<String>,
+    /// New parent: `Some(None)` moves the label to the top level, `None`
+    /// leaves it where it is
+    #[serde(default)]
+    pub parent_id: Option<Option<i64>>,
 }
 
 fn default_color() -> String {
@@ -41,6 +67,8 @@ impl Label {
             name,
             color: color.unwrap_or_else(default_color),
             document_count: 0,
+            sort_order: 0,
+            parent_id: None,
             created_at: Utc::now(),
         }
     }
@@ -48,7 +76,10 @@ impl Label {
 
 impl From<CreateLabel> for Label {
     fn from(create: CreateLabel) -> Self {
-        Self::new(create.name, Some(create.color))
+        Self {
+            parent_id: create.parent_id,
+            ..Self::new(create.name, Some(create.color))
+        }
     }
 }
 
@@ -59,6 +90,8 @@ impl From<label::Model> for Label {
             name: model.name,
             color: model.color,
             document_count: model.document_count,
+            sort_order: model.sort_order,
+            parent_id: model.parent_id,
             created_at: model.created_at,
         }
     }
@@ -3,22 +3,36 @@
 //! These models are used for business logic and API responses.
 //! They are separate from database entities to allow flexibility.
 
+pub mod api_key;
 pub mod attachment;
 pub mod author;
 pub mod category;
 pub mod comment;
+pub mod custom_field;
 pub mod keyword;
 pub mod label;
+pub mod note;
 pub mod paper;
 pub mod clipping;  // clipping must come after comment
 
 // Explicit exports to avoid ambiguity between modules
-pub use attachment::Attachment;
-pub use author::{Author, AuthorNameParser, AuthorNameParts, CreateAuthor};
-pub use category::{Category, CategoryNode, CreateCategory, UpdateCategory};
+pub use api_key::{ApiKey, ApiScope};
+pub use attachment::{calculate_attachment_hash, Attachment};
+pub use author::{
+    normalize_orcid, Author, AuthorDetails, AuthorNameParser, AuthorNameParts, CreateAuthor,
+};
+pub use category::{
+    Category, CategoryNode, CategoryNodeWithCount, CreateCategory, UpdateCategory,
+};
 pub use comment::Comment;
+pub use custom_field::{
+    normalize_custom_field_key, CustomField, CustomFieldKey, CustomFieldType,
+};
 pub use keyword::{CreateKeyword, Keyword};
-pub use label::{CreateLabel, Label, UpdateLabel};
+pub use label::{CreateLabel, Label, LabelNode, UpdateLabel};
+pub use note::{NoteFormat, NoteVersion};
 #[allow(unused_imports)]
 pub use paper::{AuthorWithOrder, CreatePaper, Paper, UpdatePaper};
-pub use clipping::{Clipping, CreateClipping, UpdateClipping};
+pub use clipping::{
+    ClipFilter, ClipSortField, Clipping, CreateClipping, SortDirection, UpdateClipping,
+};
@@ -0,0 +1,73 @@
+//! Paper note domain model
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::entities::paper_note_version;
+
+/// Format of a stored note
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteFormat {
+    PlainText,
+    Markdown,
+    Html,
+}
+
+impl NoteFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NoteFormat::PlainText => "plain_text",
+            NoteFormat::Markdown => "markdown",
+            NoteFormat::Html => "html",
+        }
+    }
+}
+
+impl fmt::Display for NoteFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NoteFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "plain_text" => Ok(NoteFormat::PlainText),
+            "markdown" => Ok(NoteFormat::Markdown),
+            "html" => Ok(NoteFormat::Html),
+            other => Err(format!("Unknown note format: {}", other)),
+        }
+    }
+}
+
+/// One saved version of a paper's notes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoteVersion {
+    pub paper_id: i64,
+    pub version: u32,
+    pub content: String,
+    pub content_format: NoteFormat,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<paper_note_version::Model> for NoteVersion {
+    fn from(model: paper_note_version::Model) -> Self {
+        Self {
+            paper_id: model.paper_id,
+            version: model.version.max(0) as u32,
+            content: model.content,
+            // Rows are only written through `NoteFormat::as_str`
+            content_format: model
+                .content_format
+                .parse()
+                .unwrap_or(NoteFormat::PlainText),
+            created_at: model.created_at,
+        }
+    }
+}
@@ -31,8 +31,16 @@ pub struct Paper {
     pub publisher: Option<String>,
     pub issn: Option<String>,
     pub language: Option<String>,
+    /// Normalized ISBN-13, for books
+    pub isbn: Option<String>,
     /// Denormalized field for performance optimization
     pub attachment_count: i32,
+    /// Marked as a must-read
+    #[serde(default)]
+    pub is_starred: bool,
+    /// When the paper was starred
+    #[serde(default)]
+    pub starred_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub attachments: Vec<Attachment>,
     #[serde(default)]
@@ -92,6 +100,7 @@ pub struct CreatePaper {
     pub publisher: Option<String>,
     pub issn: Option<String>,
     pub language: Option<String>,
+    pub isbn: Option<String>,
 }
 
 /// DTO for updating paper details
@@ -144,7 +153,10 @@ impl Paper {
             publisher: None,
             issn: None,
             language: None,
+            isbn: None,
             attachment_count: 0,
+            is_starred: false,
+            starred_at: None,
             attachments: Vec::new(),
             labels: Vec::new(),
             authors: Vec::new(),
@@ -183,7 +195,10 @@ impl From<CreatePaper> for Paper {
             publisher: create.publisher,
             issn: create.issn,
             language: create.language,
+            isbn: create.isbn,
             attachment_count: 0,
+            is_starred: false,
+            starred_at: None,
             attachments: Vec::new(),
             labels: Vec::new(),
             authors: Vec::new(),
@@ -217,7 +232,10 @@ impl From<paper::Model> for Paper {
             publisher: model.publisher,
             issn: model.issn,
             language: model.language,
+            isbn: model.isbn,
             attachment_count: model.attachment_count,
+            is_starred: model.is_starred,
+            starred_at: model.starred_at,
             attachments: Vec::new(),
             labels: Vec::new(),
             authors: Vec::new(),
@@ -0,0 +1,209 @@
+//! BibTeX mapping for papers
+//!
+//! Writes one entry per paper, reusing the author name splitting and
+//! citation keys of the CSL-JSON export so both files cite a paper the same
+//! way. Values are wrapped in braces and LaTeX special characters escaped.
+
+use crate::models::{Author, Paper};
+use crate::papers::exporter::csl::{author_name, issued_date, item_type};
+
+/// Escape the characters LaTeX treats specially
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '{' | '}' | '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\r' => {}
+            '\n' => escaped.push(' '),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Entry type matching the CSL item type
+fn entry_type(paper: &Paper) -> &'static str {
+    match item_type(paper) {
+        "paper-conference" => "inproceedings",
+        "article-journal" => "article",
+        _ => "misc",
+    }
+}
+
+/// Authors in `Family, Given and Family, Given` form, with each name part
+/// escaped
+fn author_list(authors: &[Author]) -> String {
+    authors
+        .iter()
+        .map(|a| {
+            let (name, _) = author_name(a);
+            let family = escape(&name.family);
+            match name.given {
+                Some(given) => format!("{}, {}", family, escape(&given)),
+                None => format!("{{{}}}", family),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// Format a paper and its ordered authors as a BibTeX entry with the given
+/// citation key
+pub fn paper_to_bibtex(paper: &Paper, authors: &[Author], key: &str) -> String {
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let container = match entry_type(paper) {
+        "inproceedings" => non_empty(&paper.conference_name).map(|v| ("booktitle", v)),
+        "article" => non_empty(&paper.journal_name).map(|v| ("journal", v)),
+        _ => None,
+    };
+    let month = issued_date(paper)
+        .and_then(|date| {
+            date.date_parts
+                .first()
+                .and_then(|parts| parts.get(1).copied())
+        })
+        .filter(|m| (1..=12).contains(m));
+
+    let mut fields: Vec<(&str, String)> = vec![("title", paper.title.trim().to_string())];
+    if !authors.is_empty() {
+        fields.push(("author", author_list(authors)));
+    }
+    fields.extend(container);
+    fields.extend(
+        [
+            ("year", paper.publication_year.map(|y| y.to_string())),
+            ("month", month.map(|m| m.to_string())),
+            ("volume", non_empty(&paper.volume)),
+            ("number", non_empty(&paper.issue)),
+            (
+                "pages",
+                non_empty(&paper.pages).map(|p| p.replace('-', "--")),
+            ),
+            ("publisher", non_empty(&paper.publisher)),
+            ("issn", non_empty(&paper.issn)),
+            ("isbn", non_empty(&paper.isbn)),
+            ("doi", non_empty(&paper.doi)),
+            ("url", non_empty(&paper.url)),
+            ("language", non_empty(&paper.language)),
+            ("abstract", non_empty(&paper.abstract_text)),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|v| (name, v))),
+    );
+
+    let mut entry = format!("@{}{{{},\n", entry_type(paper), key);
+    for (name, value) in fields {
+        // Names are already escaped by `author_list`; DOIs and URLs are read
+        // verbatim
+        let value = match name {
+            "author" | "doi" | "url" => value,
+            _ => escape(&value),
+        };
+        entry.push_str(&format!("  {} = {{{}}},\n", name, value));
+    }
+    entry.push_str("}\n");
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper() -> Paper {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "Deep Learning & 100% Attention_Maps",
+            "abstract_text": null,
+            "doi": "10.1000/a_b",
+            "publication_year": 2017,
+            "publication_date": "2017-12-04",
+            "journal_name": null,
+            "conference_name": "NeurIPS",
+            "volume": null,
+            "issue": null,
+            "pages": "5998-6008",
+            "url": null,
+            "citation_count": 0,
+            "read_status": "unread",
+            "notes": null,
+            "attachment_path": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "deleted_at": null,
+            "publisher": null,
+            "issn": null,
+            "language": null,
+            "isbn": null,
+            "attachment_count": 0
+        }))
+        .unwrap()
+    }
+
+    fn author(first: &str, last: Option<&str>) -> Author {
+        Author {
+            id: 1,
+            first_name: first.to_string(),
+            last_name: last.map(str::to_string),
+            affiliation: None,
+            email: None,
+            orcid: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("R&D: 50% of $x$ #1 {a_b}"),
+            "R\\&D: 50\\% of \\$x\\$ \\#1 \\{a\\_b\\}"
+        );
+        assert_eq!(
+            escape("a\\b ~ ^\r\nc"),
+            "a\\textbackslash{}b \\textasciitilde{} \\textasciicircum{} c"
+        );
+    }
+
+    #[test]
+    fn test_paper_to_bibtex_escapes_abstract_and_authors() {
+        let mut paper = paper();
+        paper.title = "Costs in $ & {Braces}".to_string();
+        paper.abstract_text = Some("We save 20% on #hashtags_and more".to_string());
+        let authors = [author("Ben_Jr", Some("Smith & Co"))];
+
+        let entry = paper_to_bibtex(&paper, &authors, "smith2017");
+        assert!(entry.contains("  title = {Costs in \\$ \\& \\{Braces\\}},\n"));
+        assert!(entry.contains("  author = {Smith \\& Co, Ben\\_Jr},\n"));
+        assert!(entry.contains("  abstract = {We save 20\\% on \\#hashtags\\_and more},\n"));
+    }
+
+    #[test]
+    fn test_paper_to_bibtex() {
+        let authors = [author("Ashish", Some("Vaswani")), author("Plato", None)];
+        assert_eq!(
+            paper_to_bibtex(&paper(), &authors, "vaswani2017"),
+            concat!(
+                "@inproceedings{vaswani2017,\n",
+                "  title = {Deep Learning \\& 100\\% Attention\\_Maps},\n",
+                "  author = {Vaswani, Ashish and {Plato}},\n",
+                "  booktitle = {NeurIPS},\n",
+                "  year = {2017},\n",
+                "  month = {12},\n",
+                "  pages = {5998--6008},\n",
+                "  doi = {10.1000/a_b},\n",
+                "}\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! CSL-JSON mapping for papers
+//!
+//! Converts papers to CSL-JSON items, the bibliography format Pandoc and
+//! Quarto read directly. Items are checked for the minimum fields a citation
+//! needs, and anything guessed or missing is reported as a warning.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::models::{Author, Paper};
+
+/// CSL name variable
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CslName {
+    pub family: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given: Option<String>,
+}
+
+/// CSL date variable with `date-parts`, e.g. `[[2020, 5, 1]]`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CslDate {
+    #[serde(rename = "date-parts")]
+    pub date_parts: Vec<Vec<i32>>,
+}
+
+/// One CSL-JSON bibliography item
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CslItem {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub author: Vec<CslName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued: Option<CslDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container_title: Option<String>,
+    #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
+    pub doi: Option<String>,
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(rename = "ISSN", skip_serializing_if = "Option::is_none")]
+    pub issn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    #[serde(rename = "abstract", skip_serializing_if = "Option::is_none")]
+    pub abstract_text: Option<String>,
+}
+
+fn is_cjk(c: char) -> bool {
+    let code = c as u32;
+    (0x4E00..=0x9FFF).contains(&code)
+        || (0x3400..=0x4DBF).contains(&code)
+        || (0x20000..=0x2A6DF).contains(&code)
+        || (0x3040..=0x30FF).contains(&code)
+        || (0xAC00..=0xD7AF).contains(&code)
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Split a full name into a CSL name.
+///
+/// `"Family, Given"` is split at the comma, CJK names are kept whole as the
+/// family name, and otherwise the last word is the family name. Returns
+/// `None` for names that cannot be split reliably (a single Latin word).
+pub fn split_name(full_name: &str) -> Option<CslName> {
+    let name = full_name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some((family, given)) = name.split_once(',') {
+        let family = family.trim();
+        if !family.is_empty() {
+            return Some(CslName {
+                family: family.to_string(),
+                given: non_empty(Some(given)),
+            });
+        }
+    }
+
+    if name.chars().any(is_cjk) {
+        return Some(CslName {
+            family: name.split_whitespace().collect::<Vec<_>>().join(""),
+            given: None,
+        });
+    }
+
+    let words: Vec<&str> = name.split_whitespace().collect();
+    match words.split_last() {
+        Some((family, given)) if !given.is_empty() => Some(CslName {
+            family: family.to_string(),
+            given: Some(given.join(" ")),
+        }),
+        _ => None,
+    }
+}
+
+/// CSL name for a stored author. Returns the name and whether it had to be
+/// guessed from an unsplittable full name.
+pub fn author_name(author: &Author) -> (CslName, bool) {
+    if let Some(family) = non_empty(author.last_name.as_deref()) {
+        return (
+            CslName {
+                family,
+                given: non_empty(Some(&author.first_name)),
+            },
+            false,
+        );
+    }
+
+    match split_name(&author.first_name) {
+        Some(name) => (name, false),
+        None => (
+            CslName {
+                family: author.first_name.trim().to_string(),
+                given: None,
+            },
+            true,
+        ),
+    }
+}
+
+/// `issued` from `publication_date` ("2020", "2020-05" or "2020-05-01"),
+/// falling back to `publication_year`
+pub fn issued_date(paper: &Paper) -> Option<CslDate> {
+    let from_date = paper.publication_date.as_deref().and_then(|date| {
+        let parts: Vec<i32> = date
+            .trim()
+            .split(['-', '/'])
+            .take(3)
+            .map_while(|p| p.trim().parse::<i32>().ok())
+            .collect();
+        (!parts.is_empty()).then_some(parts)
+    });
+
+    from_date
+        .or_else(|| paper.publication_year.map(|y| vec![y]))
+        .map(|parts| CslDate {
+            date_parts: vec![parts],
+        })
+}
+
+/// CSL item type: conference papers, journal articles, or a generic article
+pub fn item_type(paper: &Paper) -> &'static str {
+    if non_empty(paper.conference_name.as_deref()).is_some() {
+        "paper-conference"
+    } else if non_empty(paper.journal_name.as_deref()).is_some() {
+        "article-journal"
+    } else {
+        "article"
+    }
+}
+
+/// Map a paper and its ordered authors to a CSL item with the given
+/// citation key. Returns the item and any warnings about guessed or missing
+/// data.
+pub fn paper_to_csl(paper: &Paper, authors: &[Author], id: String) -> (CslItem, Vec<String>) {
+    let mut warnings = Vec::new();
+
+    let author: Vec<CslName> = authors
+        .iter()
+        .map(|a| {
+            let (name, guessed) = author_name(a);
+            if guessed {
+                warnings.push(format!("Could not parse author name '{}'", a.first_name));
+            }
+            name
+        })
+        .collect();
+
+    let item = CslItem {
+        id,
+        item_type: item_type(paper).to_string(),
+        title: paper.title.trim().to_string(),
+        author,
+        issued: issued_date(paper),
+        container_title: non_empty(paper.conference_name.as_deref())
+            .or_else(|| non_empty(paper.journal_name.as_deref())),
+        doi: non_empty(paper.doi.as_deref()),
+        url: non_empty(paper.url.as_deref()),
+        issn: non_empty(paper.issn.as_deref()),
+        volume: non_empty(paper.volume.as_deref()),
+        issue: non_empty(paper.issue.as_deref()),
+        page: non_empty(paper.pages.as_deref()),
+        publisher: non_empty(paper.publisher.as_deref()),
+        language: non_empty(paper.language.as_deref()),
+        abstract_text: non_empty(paper.abstract_text.as_deref()),
+    };
+
+    warnings.extend(validate(&item));
+    (item, warnings)
+}
+
+/// Check that an item has what a citation needs
+pub fn validate(item: &CslItem) -> Vec<String> {
+    let mut problems = Vec::new();
+    if item.title.is_empty() {
+        problems.push("Missing title".to_string());
+    }
+    if item.author.is_empty() {
+        problems.push("No authors".to_string());
+    }
+    if item.issued.is_none() {
+        problems.push("No publication date".to_string());
+    }
+    if item.item_type != "article" && item.container_title.is_none() {
+        problems.push("Missing container title".to_string());
+    }
+    problems
+}
+
+/// Citation key in the usual `family2020` form, made unique with a letter
+/// suffix (`family2020a`, `family2020b`, ...)
+pub fn citation_key(paper: &Paper, authors: &[Author], used: &mut HashSet<String>) -> String {
+    let family: String = authors
+        .first()
+        .map(|a| author_name(a).0.family)
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let base = match (family.is_empty(), paper.publication_year) {
+        (false, Some(year)) => format!("{}{}", family, year),
+        (false, None) => family,
+        (true, _) => format!("paper{}", paper.id),
+    };
+
+    let mut key = base.clone();
+    let mut suffix = b'a';
+    while used.contains(&key) {
+        key = format!("{}{}", base, suffix as char);
+        suffix = suffix.saturating_add(1);
+        if suffix > b'z' {
+            key = format!("{}-{}", base, paper.id);
+            break;
+        }
+    }
+
+    used.insert(key.clone());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(first: &str, last: Option<&str>) -> Author {
+        Author {
+            id: 1,
+            first_name: first.to_string(),
+            last_name: last.map(str::to_string),
+            affiliation: None,
+            email: None,
+            orcid: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_split_name_heuristics() {
+        let comma = split_name("Smith, John R.").unwrap();
+        assert_eq!(comma.family, "Smith");
+        assert_eq!(comma.given.as_deref(), Some("John R."));
+
+        let western = split_name("Ada Lovelace King").unwrap();
+        assert_eq!(western.family, "King");
+        assert_eq!(western.given.as_deref(), Some("Ada Lovelace"));
+
+        let cjk = split_name("张三").unwrap();
+        assert_eq!(cjk.family, "张三");
+        assert_eq!(cjk.given, None);
+
+        assert!(split_name("Plato").is_none());
+    }
+
+    #[test]
+    fn test_author_name_flags_unparseable_names() {
+        let (name, guessed) = author_name(&author("John", Some("Smith")));
+        assert_eq!(name.family, "Smith");
+        assert!(!guessed);
+
+        let (name, guessed) = author_name(&author("Plato", None));
+        assert_eq!(name.family, "Plato");
+        assert!(guessed);
+    }
+
+    #[test]
+    fn test_citation_keys_are_unique() {
+        let paper: Paper = serde_json::from_value(serde_json::json!({
+            "id": 7,
+            "title": "T",
+            "abstract_text": null,
+            "doi": null,
+            "publication_year": 2020,
+            "publication_date": null,
+            "journal_name": null,
+            "conference_name": null,
+            "volume": null,
+            "issue": null,
+            "pages": null,
+            "url": null,
+            "citation_count": 0,
+            "read_status": "unread",
+            "notes": null,
+            "attachment_path": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "deleted_at": null,
+            "publisher": null,
+            "issn": null,
+            "language": null,
+            "attachment_count": 0
+        }))
+        .unwrap();
+        let authors = vec![author("John", Some("O'Brien"))];
+
+        let mut used = HashSet::new();
+        assert_eq!(citation_key(&paper, &authors, &mut used), "obrien2020");
+        assert_eq!(citation_key(&paper, &authors, &mut used), "obrien2020a");
+        assert_eq!(citation_key(&paper, &[], &mut used), "paper7");
+    }
+}
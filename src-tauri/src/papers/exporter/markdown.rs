@@ -0,0 +1,363 @@
+//! Markdown rendering for papers
+//!
+//! Renders a paper with its notes and PDF highlights as a Markdown file with
+//! YAML front matter, ready for an Obsidian vault. The layout comes from a
+//! small template language:
+//!
+//! - `{{field}}` inserts a value; lists are joined with `, `
+//! - `{{yaml field}}` inserts a value as a quoted YAML string
+//! - `{{quote field}}` prefixes every line with `> `
+//! - `{{#if field}}…{{/if}}` keeps its body when the value is non-empty
+//! - `{{#each list}}…{{/each}}` repeats its body per item; `{{this}}` is the
+//!   item and the fields of an object item can be used directly
+//!
+//! A line holding nothing but a block tag is dropped entirely, so block tags
+//! can sit on their own lines without leaving blank lines behind.
+
+use serde_json::{json, Value};
+
+use crate::models::Paper;
+use crate::service::share_service::Highlight;
+
+/// Template used when none is given
+pub const DEFAULT_TEMPLATE: &str = r#"---
+title: {{yaml title}}
+{{#if authors}}
+authors:
+{{#each authors}}
+  - {{yaml this}}
+{{/each}}
+{{/if}}
+{{#if year}}
+year: {{year}}
+{{/if}}
+{{#if doi}}
+doi: {{yaml doi}}
+{{/if}}
+{{#if labels}}
+labels:
+{{#each labels}}
+  - {{yaml this}}
+{{/each}}
+{{/if}}
+{{#if categories}}
+categories:
+{{#each categories}}
+  - {{yaml this}}
+{{/each}}
+{{/if}}
+---
+
+# {{title}}
+
+{{#if abstract}}
+## Abstract
+
+{{abstract}}
+
+{{/if}}
+{{#if notes}}
+## Notes
+
+{{notes}}
+
+{{/if}}
+{{#if highlights}}
+## Highlights
+
+{{#each highlights}}
+{{#if text}}
+{{quote text}}
+{{/if}}
+{{#if comment}}
+{{comment}}
+{{/if}}
+{{#if page}}
+*p. {{page}}*
+{{/if}}
+
+{{/each}}
+{{/if}}
+"#;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Value {
+        helper: Option<String>,
+        path: String,
+    },
+    If {
+        path: String,
+        body: Vec<Node>,
+    },
+    Each {
+        path: String,
+        body: Vec<Node>,
+    },
+}
+
+/// A parsed Markdown template
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+/// Whether `line` is a lone `{{#…}}` or `{{/…}}` tag
+fn is_standalone_block_tag(line: &str) -> bool {
+    let line = line.trim();
+    (line.starts_with("{{#") || line.starts_with("{{/"))
+        && line.ends_with("}}")
+        && line.matches("{{").count() == 1
+}
+
+impl Template {
+    /// Parse `source`, failing on unclosed or mismatched blocks and unknown
+    /// helpers
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let source: String = source
+            .split_inclusive('\n')
+            .map(|line| {
+                if is_standalone_block_tag(line) {
+                    line.trim()
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        let mut rest = source.as_str();
+        let nodes = parse_nodes(&mut rest, None)?;
+        Ok(Self { nodes })
+    }
+
+    /// Render the template with the fields of `context`
+    pub fn render(&self, context: &Value) -> String {
+        let mut out = String::new();
+        render_nodes(&self.nodes, &mut vec![context], &mut out);
+        out
+    }
+}
+
+/// Parse nodes until `{{/closing}}` (or the end when `closing` is `None`),
+/// consuming the closing tag
+fn parse_nodes(rest: &mut &str, closing: Option<&str>) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    loop {
+        let Some(start) = rest.find("{{") else {
+            if !rest.is_empty() {
+                nodes.push(Node::Text(rest.to_string()));
+            }
+            *rest = "";
+            return match closing {
+                Some(block) => Err(format!("Missing {{{{/{}}}}}", block)),
+                None => Ok(nodes),
+            };
+        };
+        if start > 0 {
+            nodes.push(Node::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find("}}")
+            .ok_or_else(|| "Unclosed {{ tag".to_string())?
+            + start;
+        let tag = rest[start + 2..end].trim().to_string();
+        *rest = &rest[end + 2..];
+
+        if let Some(block) = tag.strip_prefix('/') {
+            return match closing {
+                Some(expected) if expected == block.trim() => Ok(nodes),
+                _ => Err(format!("Unexpected {{{{/{}}}}}", block.trim())),
+            };
+        }
+        if let Some(block) = tag.strip_prefix('#') {
+            let (kind, path) = block
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("Block {{{{#{}}}}} needs a field", block))?;
+            let path = path.trim().to_string();
+            let body = parse_nodes(rest, Some(kind))?;
+            nodes.push(match kind {
+                "if" => Node::If { path, body },
+                "each" => Node::Each { path, body },
+                _ => return Err(format!("Unknown block '{}'", kind)),
+            });
+            continue;
+        }
+
+        nodes.push(match tag.split_once(char::is_whitespace) {
+            Some((helper, path)) if matches!(helper, "yaml" | "quote") => Node::Value {
+                helper: Some(helper.to_string()),
+                path: path.trim().to_string(),
+            },
+            Some((helper, _)) => return Err(format!("Unknown helper '{}'", helper)),
+            None if tag.is_empty() => return Err("Empty {{}} tag".to_string()),
+            None => Node::Value {
+                helper: None,
+                path: tag,
+            },
+        });
+    }
+}
+
+/// Look `path` up in the innermost scope that has it
+fn lookup<'a>(scopes: &[&'a Value], path: &str) -> &'a Value {
+    if path == "this" {
+        return scopes.last().copied().unwrap_or(&Value::Null);
+    }
+    scopes
+        .iter()
+        .rev()
+        .find_map(|scope| scope.get(path))
+        .unwrap_or(&Value::Null)
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::String(s) => !s.trim().is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Number(_) | Value::Object(_) => true,
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(to_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// A double-quoted YAML scalar
+fn yaml_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn render_nodes<'a>(nodes: &'a [Node], scopes: &mut Vec<&'a Value>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value { helper, path } => {
+                let text = to_text(lookup(scopes, path));
+                match helper.as_deref() {
+                    Some("yaml") => out.push_str(&yaml_string(&text)),
+                    Some("quote") => {
+                        let quoted: Vec<String> =
+                            text.lines().map(|line| format!("> {}", line)).collect();
+                        out.push_str(&quoted.join("\n"));
+                    }
+                    _ => out.push_str(&text),
+                }
+            }
+            Node::If { path, body } => {
+                if is_truthy(lookup(scopes, path)) {
+                    render_nodes(body, scopes, out);
+                }
+            }
+            Node::Each { path, body } => {
+                if let Value::Array(items) = lookup(scopes, path) {
+                    for item in items {
+                        scopes.push(item);
+                        render_nodes(body, scopes, out);
+                        scopes.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fields a template can use for one paper. `category` is the first of
+/// `categories`, kept for templates written before papers could be in
+/// several categories.
+pub fn paper_context(
+    paper: &Paper,
+    authors: &[String],
+    labels: &[String],
+    categories: &[String],
+    highlights: &[Highlight],
+) -> Value {
+    let highlights: Vec<Value> = highlights
+        .iter()
+        .map(|h| json!({ "text": h.text, "comment": h.comment, "page": h.page }))
+        .collect();
+    json!({
+        "id": paper.id,
+        "title": paper.title,
+        "authors": authors,
+        "year": paper.publication_year,
+        "doi": paper.doi,
+        "journal": paper.journal_name.as_ref().or(paper.conference_name.as_ref()),
+        "url": paper.url,
+        "labels": labels,
+        "category": categories.first(),
+        "categories": categories,
+        "abstract": paper.abstract_text,
+        "notes": paper.notes,
+        "read_status": paper.read_status,
+        "highlights": highlights,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_blocks_and_helpers() {
+        let template = Template::parse(concat!(
+            "{{#if missing}}\n",
+            "never\n",
+            "{{/if}}\n",
+            "tags:\n",
+            "{{#each tags}}\n",
+            "  - {{yaml this}}\n",
+            "{{/each}}\n",
+            "{{#each items}}{{name}} of {{title}}; {{/each}}\n",
+            "{{quote body}}\n",
+        ))
+        .unwrap();
+        let context = json!({
+            "title": "Paper",
+            "tags": ["a \"b\"", "c:d"],
+            "items": [{ "name": "x" }, { "name": "y" }],
+            "body": "one\ntwo",
+        });
+
+        assert_eq!(
+            template.render(&context),
+            concat!(
+                "tags:\n",
+                "  - \"a \\\"b\\\"\"\n",
+                "  - \"c:d\"\n",
+                "x of Paper; y of Paper; \n",
+                "> one\n> two\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates_are_rejected() {
+        assert!(Template::parse("{{#each authors}}x").is_err());
+        assert!(Template::parse("{{#if a}}x{{/each}}").is_err());
+        assert!(Template::parse("{{shout title}}").is_err());
+        assert!(Template::parse("{{title").is_err());
+        assert!(Template::parse(DEFAULT_TEMPLATE).is_ok());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod bibtex;
+pub mod csl;
+pub mod markdown;
@@ -0,0 +1,238 @@
+//! Bibliographic metadata from the Crossref REST API
+//!
+//! `doi.rs` resolves DOIs through doi.org content negotiation, which only
+//! returns citation fields. The Crossref works API additionally lists the
+//! ISSNs, work type, subjects, licenses and funders of a work. Requests
+//! carry a `mailto:` in the User-Agent so they go to Crossref's polite pool.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::doi::is_valid_doi;
+
+const CROSSREF_WORKS_URL: &str = "https://api.crossref.org/works";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Crossref API error types
+#[derive(Error, Debug)]
+pub enum CrossrefError {
+    #[error("HTTP request failed: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Invalid DOI format: {0}")]
+    InvalidDoi(String),
+
+    #[error("DOI not found in Crossref")]
+    NotFound,
+}
+
+/// Bibliographic metadata of a work registered with Crossref
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CrossrefWork {
+    pub doi: String,
+    pub publisher: Option<String>,
+    /// Journal, book or proceedings title
+    pub container_title: Option<String>,
+    /// Print and electronic ISSNs
+    pub issn: Vec<String>,
+    /// Crossref work type, e.g. `journal-article`, `book-chapter` or
+    /// `proceedings-article`
+    pub type_: Option<String>,
+    /// Fields of study
+    pub subject: Vec<String>,
+    pub license: Vec<CrossrefLicense>,
+    pub funder: Vec<CrossrefFunder>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossrefLicense {
+    pub url: String,
+    /// Version the license applies to: `vor`, `am`, `tdm` or `unspecified`
+    pub content_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossrefFunder {
+    pub name: String,
+    /// Funder DOI in the Open Funder Registry
+    pub doi: Option<String>,
+    /// Grant numbers
+    pub award: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrossrefResponse {
+    message: RawWork,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawWork {
+    #[serde(rename = "DOI")]
+    doi: String,
+    publisher: Option<String>,
+    #[serde(rename = "container-title", default)]
+    container_title: Vec<String>,
+    #[serde(rename = "ISSN", default)]
+    issn: Vec<String>,
+    #[serde(rename = "type")]
+    work_type: Option<String>,
+    #[serde(default)]
+    subject: Vec<String>,
+    #[serde(default)]
+    license: Vec<RawLicense>,
+    #[serde(default)]
+    funder: Vec<RawFunder>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLicense {
+    #[serde(rename = "URL")]
+    url: String,
+    #[serde(rename = "content-version")]
+    content_version: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFunder {
+    name: Option<String>,
+    #[serde(rename = "DOI")]
+    doi: Option<String>,
+    #[serde(default)]
+    award: Vec<String>,
+}
+
+impl From<RawWork> for CrossrefWork {
+    fn from(raw: RawWork) -> Self {
+        Self {
+            doi: raw.doi,
+            publisher: raw.publisher.filter(|p| !p.trim().is_empty()),
+            container_title: raw
+                .container_title
+                .into_iter()
+                .find(|t| !t.trim().is_empty()),
+            issn: raw.issn,
+            type_: raw.work_type,
+            subject: raw.subject,
+            license: raw
+                .license
+                .into_iter()
+                .map(|l| CrossrefLicense {
+                    url: l.url,
+                    content_version: l.content_version,
+                })
+                .collect(),
+            funder: raw
+                .funder
+                .into_iter()
+                .filter_map(|f| {
+                    Some(CrossrefFunder {
+                        name: f.name?,
+                        doi: f.doi,
+                        award: f.award,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Works API URL of a DOI. The DOI is percent-encoded as one path segment,
+/// since DOIs may contain `#`, `?` or `;`.
+fn works_url(doi: &str) -> String {
+    format!("{}/{}", CROSSREF_WORKS_URL, urlencoding::encode(doi))
+}
+
+/// Fetch the Crossref record of a DOI
+pub async fn fetch_crossref_metadata(doi: &str) -> Result<CrossrefWork, CrossrefError> {
+    if !is_valid_doi(doi) {
+        return Err(CrossrefError::InvalidDoi(doi.to_string()));
+    }
+    let doi = doi.strip_prefix("doi:").unwrap_or(doi);
+    let doi = doi.strip_prefix("https://doi.org/").unwrap_or(doi);
+
+    let client = reqwest::Client::builder()
+        .user_agent("XuanBrain/0.1.0 (mailto:support@example.com)")
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+
+    let response = client
+        .get(works_url(doi))
+        .send()
+        .await?
+        .error_for_status()
+        .map_err(|e| {
+            if e.status() == Some(reqwest::StatusCode::NOT_FOUND) {
+                CrossrefError::NotFound
+            } else {
+                CrossrefError::RequestError(e)
+            }
+        })?;
+
+    let response: CrossrefResponse = response.json().await?;
+    Ok(response.message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_works_url_encodes_doi() {
+        assert_eq!(
+            works_url("10.1002/(SICI)1097-4571(199806)49:8<693::AID-ASI4>3.0.CO;2-0"),
+            "https://api.crossref.org/works/10.1002%2F%28SICI%291097-4571%28199806%2949%3A8%3C693%3A%3AAID-ASI4%3E3.0.CO%3B2-0"
+        );
+        assert_eq!(
+            works_url("10.1000/a#b?c"),
+            "https://api.crossref.org/works/10.1000%2Fa%23b%3Fc"
+        );
+    }
+
+    #[test]
+    fn test_parse_crossref_work() {
+        let response: CrossrefResponse = serde_json::from_str(
+            r#"{
+                "status": "ok",
+                "message": {
+                    "DOI": "10.1016/j.precisioneng.2019.10.013",
+                    "type": "journal-article",
+                    "publisher": "Elsevier BV",
+                    "container-title": ["Precision Engineering"],
+                    "ISSN": ["0141-6359"],
+                    "subject": ["General Engineering"],
+                    "license": [{
+                        "URL": "https://www.elsevier.com/tdm/userlicense/1.0/",
+                        "content-version": "tdm"
+                    }],
+                    "funder": [
+                        {"name": "National Natural Science Foundation of China",
+                         "DOI": "10.13039/501100001809", "award": ["51775378"]},
+                        {"DOI": "10.13039/000000000"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let work = CrossrefWork::from(response.message);
+        assert_eq!(
+            work.container_title.as_deref(),
+            Some("Precision Engineering")
+        );
+        assert_eq!(work.type_.as_deref(), Some("journal-article"));
+        assert_eq!(work.issn, vec!["0141-6359"]);
+        assert_eq!(work.subject, vec!["General Engineering"]);
+        assert_eq!(work.license[0].content_version.as_deref(), Some("tdm"));
+        assert_eq!(work.funder.len(), 1);
+        assert_eq!(work.funder[0].award, vec!["51775378"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_invalid_doi() {
+        let result = fetch_crossref_metadata("invalid-doi").await;
+        assert!(matches!(result, Err(CrossrefError::InvalidDoi(_))));
+    }
+}
@@ -0,0 +1,326 @@
+//! Generic CSV import module
+//!
+//! Reads paper records from CSV exports (Mendeley, spreadsheets, other
+//! reference managers). Which column holds which paper field is described by
+//! a [`CsvColumnMapping`]; rows are read one at a time so large files are
+//! never loaded into memory as a whole.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Separator between several authors or labels inside one cell
+pub const LIST_SEPARATOR: char = ';';
+
+/// CSV import error types
+#[derive(Error, Debug)]
+pub enum CsvImportError {
+    #[error("Failed to read CSV file: {0}")]
+    ReadError(String),
+
+    #[error("Invalid column mapping: {0}")]
+    InvalidMapping(String),
+
+    #[error("CSV header is missing mapped columns: {}", .0.join(", "))]
+    MissingColumns(Vec<String>),
+}
+
+/// Names of the CSV columns that hold each paper field. Only the title is
+/// required; unmapped fields are left empty.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CsvColumnMapping {
+    pub title: String,
+    /// Authors separated by `;`
+    pub authors: Option<String>,
+    pub year: Option<String>,
+    pub doi: Option<String>,
+    pub journal: Option<String>,
+    pub url: Option<String>,
+    #[serde(rename = "abstract")]
+    pub abstract_text: Option<String>,
+    /// Labels separated by `;`
+    pub labels: Option<String>,
+}
+
+impl CsvColumnMapping {
+    /// Mapped column names with the field each one feeds
+    fn columns(&self) -> Vec<(&'static str, &str)> {
+        let mut columns = vec![("title", self.title.as_str())];
+        let optional = [
+            ("authors", &self.authors),
+            ("year", &self.year),
+            ("doi", &self.doi),
+            ("journal", &self.journal),
+            ("url", &self.url),
+            ("abstract", &self.abstract_text),
+            ("labels", &self.labels),
+        ];
+        columns.extend(
+            optional
+                .into_iter()
+                .filter_map(|(field, column)| column.as_deref().map(|c| (field, c))),
+        );
+        columns
+    }
+}
+
+/// One paper read from the CSV file
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvPaperRow {
+    /// Line of the record in the file, counting the header as line 1
+    pub line: u64,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    pub journal: Option<String>,
+    pub url: Option<String>,
+    pub abstract_text: Option<String>,
+    pub labels: Vec<String>,
+}
+
+/// A row that could not be read or failed validation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CsvRowError {
+    pub line: u64,
+    pub message: String,
+}
+
+/// Reads [`CsvPaperRow`]s from a CSV file, one record at a time
+pub struct CsvPaperReader {
+    reader: csv::Reader<File>,
+    /// Field name to column index
+    indices: HashMap<&'static str, usize>,
+    record: csv::StringRecord,
+    done: bool,
+}
+
+impl CsvPaperReader {
+    /// Open `path` and check that its header has every column named in
+    /// `mapping`. Column names are compared ignoring surrounding whitespace.
+    pub fn open(path: &Path, mapping: &CsvColumnMapping) -> Result<Self, CsvImportError> {
+        if mapping.title.trim().is_empty() {
+            return Err(CsvImportError::InvalidMapping(
+                "A column must be mapped to the title".to_string(),
+            ));
+        }
+
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_path(path)
+            .map_err(|e| CsvImportError::ReadError(e.to_string()))?;
+        let header: Vec<String> = reader
+            .headers()
+            .map_err(|e| CsvImportError::ReadError(e.to_string()))?
+            .iter()
+            // Spreadsheet exports often start with a UTF-8 byte order mark
+            .map(|h| h.trim_start_matches('\u{feff}').trim().to_string())
+            .collect();
+
+        let mut indices = HashMap::new();
+        let mut missing = Vec::new();
+        for (field, column) in mapping.columns() {
+            match header.iter().position(|h| h == column.trim()) {
+                Some(index) => {
+                    indices.insert(field, index);
+                }
+                None => missing.push(column.to_string()),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(CsvImportError::MissingColumns(missing));
+        }
+
+        Ok(Self {
+            reader,
+            indices,
+            record: csv::StringRecord::new(),
+            done: false,
+        })
+    }
+
+    fn cell(&self, field: &str) -> Option<String> {
+        self.indices
+            .get(field)
+            .and_then(|&index| self.record.get(index))
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+
+    fn list(&self, field: &str) -> Vec<String> {
+        self.cell(field)
+            .map(|value| {
+                value
+                    .split(LIST_SEPARATOR)
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_record(&self, line: u64) -> Result<CsvPaperRow, CsvRowError> {
+        let invalid = |message: String| CsvRowError { line, message };
+
+        let title = self
+            .cell("title")
+            .ok_or_else(|| invalid("Title is empty".to_string()))?;
+        let year = self
+            .cell("year")
+            .map(|value| {
+                parse_year(&value).ok_or_else(|| invalid(format!("Invalid year '{}'", value)))
+            })
+            .transpose()?;
+        let doi = self
+            .cell("doi")
+            .map(|value| {
+                let doi = normalize_doi(&value);
+                if doi.starts_with("10.") && doi.contains('/') {
+                    Ok(doi.to_string())
+                } else {
+                    Err(invalid(format!("Invalid DOI '{}'", value)))
+                }
+            })
+            .transpose()?;
+
+        Ok(CsvPaperRow {
+            line,
+            title,
+            authors: self.list("authors"),
+            year,
+            doi,
+            journal: self.cell("journal"),
+            url: self.cell("url"),
+            abstract_text: self.cell("abstract"),
+            labels: self.list("labels"),
+        })
+    }
+}
+
+impl Iterator for CsvPaperReader {
+    type Item = Result<CsvPaperRow, CsvRowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            match self.reader.read_record(&mut self.record) {
+                Ok(false) => self.done = true,
+                Ok(true) => {
+                    // Rows of empty cells are padding, not papers
+                    if self.record.iter().all(|cell| cell.trim().is_empty()) {
+                        continue;
+                    }
+                    let line = self.record.position().map_or(0, |p| p.line());
+                    return Some(self.parse_record(line));
+                }
+                Err(e) => {
+                    let line = e.position().map_or(0, |p| p.line());
+                    // Nothing after an I/O error can be read either
+                    self.done = e.is_io_error();
+                    return Some(Err(CsvRowError {
+                        line,
+                        message: format!("Malformed row: {}", e),
+                    }));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Year from values like `2019`, `2019-05-01` or `May 2019`
+fn parse_year(value: &str) -> Option<i32> {
+    if let Ok(year) = value.parse::<i32>() {
+        return (1000..=9999).contains(&year).then_some(year);
+    }
+    value
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .and_then(|part| part.parse().ok())
+}
+
+/// Strip URL and `doi:` prefixes from a DOI
+fn normalize_doi(doi: &str) -> &str {
+    [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .unwrap_or(doi)
+    .trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            title: "Title".to_string(),
+            authors: Some("Authors".to_string()),
+            year: Some("Year".to_string()),
+            doi: Some("DOI".to_string()),
+            labels: Some("Tags".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn write_csv(content: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.csv");
+        std::fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_missing_columns_are_reported() {
+        let (_dir, path) = write_csv("Title,Authors\nA paper,Ada Lovelace\n");
+
+        match CsvPaperReader::open(&path, &mapping()) {
+            Err(CsvImportError::MissingColumns(missing)) => {
+                assert_eq!(missing, vec!["Year", "DOI", "Tags"])
+            }
+            other => panic!("expected missing columns, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_rows_are_parsed_with_line_numbers() {
+        let (_dir, path) = write_csv(concat!(
+            "\u{feff}Title, Authors ,Year,DOI,Tags\n",
+            "Attention,\"Vaswani, A.; Shazeer, N.\",2017,https://doi.org/10.5555/3295222,nlp; ml\n",
+            ",Nobody,2020,,\n",
+            "\"Multi\nline\",,May 2019,,\n",
+            "Bad year,,soon,,\n",
+            "Bad DOI,,,not-a-doi,\n",
+        ));
+
+        let rows: Vec<_> = CsvPaperReader::open(&path, &mapping()).unwrap().collect();
+        assert_eq!(rows.len(), 5);
+
+        let first = rows[0].as_ref().unwrap();
+        assert_eq!(first.line, 2);
+        assert_eq!(first.authors, vec!["Vaswani, A.", "Shazeer, N."]);
+        assert_eq!(first.year, Some(2017));
+        assert_eq!(first.doi.as_deref(), Some("10.5555/3295222"));
+        assert_eq!(first.labels, vec!["nlp", "ml"]);
+
+        assert_eq!(rows[1].as_ref().unwrap_err().line, 3);
+        let multiline = rows[2].as_ref().unwrap();
+        assert_eq!((multiline.line, multiline.year), (4, Some(2019)));
+        assert_eq!(rows[3].as_ref().unwrap_err().line, 6);
+        assert!(rows[4]
+            .as_ref()
+            .unwrap_err()
+            .message
+            .contains("Invalid DOI"));
+    }
+}
@@ -33,6 +33,8 @@ pub struct DoiMetadata {
     pub publisher: Option<String>,
     pub url: Option<String>,
     pub abstract_text: Option<String>,
+    /// Number of works citing this one according to Crossref
+    pub citation_count: Option<i32>,
 }
 
 /// Author name from DOI (Crossref) with separated given/family names
@@ -45,6 +47,12 @@ pub struct DoiAuthor {
     /// Full name (for display, computed from given + family)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub full_name: Option<String>,
+    /// First affiliation listed by Crossref
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affiliation: Option<String>,
+    /// ORCID iD as given by Crossref, usually an orcid.org URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub orcid: Option<String>,
 }
 
 impl DoiAuthor {
@@ -56,6 +64,8 @@ impl DoiAuthor {
                 given: Some(full.clone()),
                 family: None,
                 full_name: Some(full),
+                affiliation: None,
+                orcid: None,
             }
         } else {
             let full_name = match (&given, &family) {
@@ -68,6 +78,8 @@ impl DoiAuthor {
                 given,
                 family,
                 full_name,
+                affiliation: None,
+                orcid: None,
             }
         }
     }
@@ -129,7 +141,10 @@ struct CrossrefWork {
     publisher: Option<String>,
     #[serde(rename = "URL")]
     url: Option<String>,
+    #[serde(alias = "abstract")]
     abstract_text: Option<String>,
+    #[serde(rename = "is-referenced-by-count")]
+    citation_count: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -139,6 +154,15 @@ struct CrossrefAuthor {
     #[serde(rename = "family")]
     family_name: Option<String>,
     name: Option<String>,
+    #[serde(rename = "ORCID")]
+    orcid: Option<String>,
+    #[serde(default)]
+    affiliation: Vec<CrossrefAffiliation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrossrefAffiliation {
+    name: Option<String>,
 }
 
 impl CrossrefWork {
@@ -154,7 +178,16 @@ impl CrossrefWork {
         let authors = self
             .author
             .into_iter()
-            .map(|a| DoiAuthor::from_crossref(a.given_name, a.family_name, a.name))
+            .map(|a| DoiAuthor {
+                affiliation: a
+                    .affiliation
+                    .into_iter()
+                    .filter_map(|aff| aff.name)
+                    .map(|name| name.trim().to_string())
+                    .find(|name| !name.is_empty()),
+                orcid: a.orcid,
+                ..DoiAuthor::from_crossref(a.given_name, a.family_name, a.name)
+            })
             .collect();
 
         // Extract publication year from published date
@@ -184,7 +217,8 @@ impl CrossrefWork {
             pages: self.page,
             publisher: self.publisher,
             url: self.url,
-            abstract_text: self.abstract_text,
+            abstract_text: self.abstract_text.as_deref().and_then(clean_jats_abstract),
+            citation_count: self.citation_count,
         })
     }
 }
@@ -227,8 +261,17 @@ pub async fn fetch_doi_metadata(doi: &str) -> Result<DoiMetadata, DoiError> {
     crossref_work.to_metadata()
 }
 
+/// Crossref abstracts are JATS XML (`<jats:p>...</jats:p>`); keep the text
+/// only. Returns `None` when nothing but markup is left.
+fn clean_jats_abstract(abstract_text: &str) -> Option<String> {
+    let tag_pattern = regex::Regex::new(r"<[^>]+>").unwrap();
+    let text = tag_pattern.replace_all(abstract_text, " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
 /// Validate DOI format (basic check)
-fn is_valid_doi(doi: &str) -> bool {
+pub fn is_valid_doi(doi: &str) -> bool {
     // Basic DOI format validation: 10.xxx/xxx
     if doi.is_empty() {
         return false;
@@ -249,6 +292,37 @@ fn is_valid_doi(doi: &str) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_crossref_author_details() {
+        let work: CrossrefWork = serde_json::from_str(
+            r#"{
+                "DOI": "10.1234/example",
+                "type": "journal-article",
+                "title": ["Example"],
+                "author": [
+                    {
+                        "given": "Josiah",
+                        "family": "Carberry",
+                        "ORCID": "https://orcid.org/0000-0002-1825-0097",
+                        "affiliation": [{"name": " Brown University "}]
+                    },
+                    {"given": "Jane", "family": "Doe", "affiliation": []}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let metadata = work.to_metadata().unwrap();
+        let carberry = &metadata.authors[0];
+        assert_eq!(carberry.affiliation.as_deref(), Some("Brown University"));
+        assert_eq!(
+            carberry.orcid.as_deref(),
+            Some("https://orcid.org/0000-0002-1825-0097")
+        );
+        assert_eq!(carberry.full_name.as_deref(), Some("Josiah Carberry"));
+        assert_eq!(metadata.authors[1].affiliation, None);
+    }
+
     #[tokio::test]
     async fn test_fetch_doi_metadata() {
         let doi = "10.1016/j.precisioneng.2019.10.013";
@@ -328,6 +402,27 @@ mod tests {
         println!("========== End DOI Test ==========\n");
     }
 
+    #[test]
+    fn test_crossref_citation_count_and_abstract() {
+        let work: CrossrefWork = serde_json::from_str(
+            r#"{
+                "DOI": "10.1000/xyz",
+                "type": "journal-article",
+                "title": ["A title"],
+                "abstract": "<jats:title>Abstract</jats:title><jats:p>Some  text.</jats:p>",
+                "is-referenced-by-count": 42
+            }"#,
+        )
+        .unwrap();
+
+        let metadata = work.to_metadata().unwrap();
+        assert_eq!(metadata.citation_count, Some(42));
+        assert_eq!(
+            metadata.abstract_text.as_deref(),
+            Some("Abstract Some text.")
+        );
+    }
+
     #[test]
     fn test_is_valid_doi() {
         // Valid DOIs
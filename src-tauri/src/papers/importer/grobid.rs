@@ -1,23 +1,98 @@
 use crate::sys::error::{AppError, Result};
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::reader::Reader;
-use reqwest::multipart;
+use reqwest::{multipart, StatusCode};
 use std::path::Path;
 use std::time::Duration;
 use tokio::fs;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Debug, Default)]
 pub struct GrobidMetadata {
     pub title: String,
-    pub authors: Vec<String>,
+    pub authors: Vec<GrobidAuthor>,
     pub doi: Option<String>,
     pub abstract_text: Option<String>,
     pub publication_year: Option<i64>,
     pub journal_name: Option<String>,
 }
 
+/// An author from the header of a TEI document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrobidAuthor {
+    pub name: String,
+    /// Institution of the author's first affiliation
+    pub affiliation: Option<String>,
+    pub orcid: Option<String>,
+}
+
+/// A bibliography entry from the `<listBibl>` of a TEI document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GrobidReference {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    pub doi: Option<String>,
+    /// Journal, proceedings or book title when the entry is a part of one
+    pub journal: Option<String>,
+    /// The citation as printed in the PDF
+    pub raw_text: Option<String>,
+}
+
+/// Reference processing reads the whole PDF and takes much longer than the
+/// header endpoint on long documents
+const REFERENCES_TIMEOUT: Duration = Duration::from_secs(300);
+
 pub async fn process_header_document(file_path: &Path, server_url: &str) -> Result<GrobidMetadata> {
+    let xml_content = post_document(
+        file_path,
+        server_url,
+        "processHeaderDocument",
+        &[],
+        Duration::from_secs(60),
+    )
+    .await?;
+
+    info!("\n========== GROBID RAW XML RESPONSE ==========");
+    info!("{}", xml_content);
+    info!("========== END GROBID RESPONSE ==========\n");
+
+    // 3. Parse XML
+    parse_tei_xml(&xml_content)
+}
+
+/// Run GROBID reference processing and return the parsed bibliography,
+/// including the raw citation strings.
+///
+/// Timeouts and an overloaded server are reported as `AppError::Timeout`,
+/// so callers can offer to retry.
+pub async fn process_references(
+    file_path: &Path,
+    server_url: &str,
+) -> Result<Vec<GrobidReference>> {
+    let xml_content = post_document(
+        file_path,
+        server_url,
+        "processReferences",
+        &[("includeRawCitations", "1")],
+        REFERENCES_TIMEOUT,
+    )
+    .await?;
+
+    let references = parse_tei_references(&xml_content);
+    info!("GROBID found {} references", references.len());
+    Ok(references)
+}
+
+/// Send a PDF and the form `fields` to `{server_url}/api/{endpoint}` and
+/// return the TEI XML
+async fn post_document(
+    file_path: &Path,
+    server_url: &str,
+    endpoint: &str,
+    fields: &[(&'static str, &'static str)],
+    timeout: Duration,
+) -> Result<String> {
     // 1. Read file
     let file_bytes = fs::read(file_path).await?;
     let file_part = multipart::Part::bytes(file_bytes)
@@ -33,21 +108,21 @@ pub async fn process_header_document(file_path: &Path, server_url: &str) -> Resu
             AppError::network_error(server_url, format!("Failed to create multipart: {}", e))
         })?;
 
-    let form = multipart::Form::new().part("input", file_part);
+    let form = fields.iter().fold(
+        multipart::Form::new().part("input", file_part),
+        |form, (name, value)| form.text(*name, *value),
+    );
 
     // 2. Send request with timeout
     let client = reqwest::Client::builder()
         .no_proxy()
-        .timeout(Duration::from_secs(60)) // 60 seconds timeout
+        .timeout(timeout)
         .build()
         .map_err(|e| {
             AppError::network_error(server_url, format!("Failed to create client: {}", e))
         })?;
 
-    let url = format!(
-        "{}/api/processHeaderDocument",
-        server_url.trim_end_matches('/')
-    );
+    let url = format!("{}/api/{}", server_url.trim_end_matches('/'), endpoint);
 
     info!("Sending PDF to GROBID server: {}", url);
 
@@ -59,29 +134,180 @@ pub async fn process_header_document(file_path: &Path, server_url: &str) -> Resu
         .await
         .map_err(|e| {
             info!("GROBID request failed: {}", e);
-            AppError::network_error(&url, format!("GROBID request failed: {}", e))
+            if e.is_timeout() {
+                AppError::timeout(
+                    &url,
+                    format!("GROBID did not answer within {}s", timeout.as_secs()),
+                )
+            } else {
+                AppError::network_error(&url, format!("GROBID request failed: {}", e))
+            }
         })?;
 
-    if !response.status().is_success() {
-        let status = response.status();
+    let status = response.status();
+    if !status.is_success() {
         info!("GROBID returned non-success status: {}", status);
-        return Err(AppError::network_error(
-            &url,
-            format!("GROBID returned status: {}", status),
-        ));
+        // GROBID answers 503 when all of its workers are busy
+        return Err(match status {
+            StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+            | StatusCode::REQUEST_TIMEOUT => {
+                AppError::timeout(&url, format!("GROBID is busy (status {})", status))
+            }
+            _ => AppError::network_error(&url, format!("GROBID returned status: {}", status)),
+        });
     }
 
-    let xml_content = response.text().await.map_err(|e| {
+    response.text().await.map_err(|e| {
         info!("Failed to read GROBID response: {}", e);
-        AppError::network_error(&url, format!("Failed to read GROBID response: {}", e))
-    })?;
+        if e.is_timeout() {
+            AppError::timeout(&url, "Timed out reading the GROBID response")
+        } else {
+            AppError::network_error(&url, format!("Failed to read GROBID response: {}", e))
+        }
+    })
+}
 
-    info!("\n========== GROBID RAW XML RESPONSE ==========");
-    info!("{}", xml_content);
-    info!("========== END GROBID RESPONSE ==========\n");
+/// Year from a TEI `when` attribute such as "2017-06-12"
+fn parse_when_year(when: &str) -> Option<i32> {
+    when.get(..4).and_then(|year| year.parse().ok())
+}
 
-    // 3. Parse XML
-    parse_tei_xml(&xml_content)
+/// Parse every `<biblStruct>` inside `<listBibl>`. The title is the article
+/// title when there is one, otherwise the book or journal title; in the
+/// first case the book or journal title becomes `journal`. Entries with
+/// neither a title nor authors are dropped.
+fn parse_tei_references(xml: &str) -> Vec<GrobidReference> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut references = Vec::new();
+    let mut buf = Vec::new();
+
+    let mut in_list_bibl = false;
+    let mut in_analytic = false;
+    let mut in_author = false;
+    let mut in_name_part = false;
+    let mut current: Option<GrobidReference> = None;
+    let mut monogr_title: Option<String> = None;
+    let mut current_author = String::new();
+
+    loop {
+        let event = reader.read_event_into(&mut buf);
+        match event {
+            Ok(Event::Start(ref e)) => match e.name().as_ref() {
+                b"listBibl" => in_list_bibl = true,
+                b"biblStruct" if in_list_bibl => {
+                    current = Some(GrobidReference::default());
+                    monogr_title = None;
+                }
+                b"analytic" => in_analytic = true,
+                b"author" => {
+                    in_author = true;
+                    current_author.clear();
+                }
+                b"forename" | b"surname" if in_author => in_name_part = true,
+                b"title" => {
+                    if let Some(reference) = current.as_mut() {
+                        let title = reader
+                            .read_text(e.name())
+                            .map(|t| t.trim().to_string())
+                            .unwrap_or_default();
+                        if !title.is_empty() {
+                            if in_analytic {
+                                reference.title.get_or_insert(title);
+                            } else if monogr_title.is_none() {
+                                monogr_title = Some(title);
+                            }
+                        }
+                    }
+                }
+                b"idno" => {
+                    let is_doi = e
+                        .attributes()
+                        .flatten()
+                        .any(|a| a.key.as_ref() == b"type" && a.value.as_ref() == b"DOI");
+                    if let Some(reference) = current.as_mut().filter(|_| is_doi) {
+                        if let Ok(doi) = reader.read_text(e.name()) {
+                            let doi = doi.trim();
+                            if !doi.is_empty() {
+                                reference.doi = Some(doi.to_string());
+                            }
+                        }
+                    }
+                }
+                b"note" => {
+                    let is_raw = e
+                        .attributes()
+                        .flatten()
+                        .any(|a| a.key.as_ref() == b"type" && a.value.as_ref() == b"raw_reference");
+                    if let Some(reference) = current.as_mut().filter(|_| is_raw) {
+                        if let Ok(raw) = reader.read_text(e.name()) {
+                            let raw = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+                            if !raw.is_empty() {
+                                reference.raw_text = Some(raw);
+                            }
+                        }
+                    }
+                }
+                b"date" => set_reference_year(current.as_mut(), e),
+                _ => (),
+            },
+            Ok(Event::Empty(ref e)) if e.name().as_ref() == b"date" => {
+                set_reference_year(current.as_mut(), e)
+            }
+            Ok(Event::End(ref e)) => match e.name().as_ref() {
+                b"listBibl" => in_list_bibl = false,
+                b"analytic" => in_analytic = false,
+                b"forename" | b"surname" => in_name_part = false,
+                b"author" => {
+                    in_author = false;
+                    let name = current_author.trim();
+                    if let Some(reference) = current.as_mut().filter(|_| !name.is_empty()) {
+                        reference.authors.push(name.to_string());
+                    }
+                }
+                b"biblStruct" => {
+                    if let Some(mut reference) = current.take() {
+                        if reference.title.is_none() {
+                            reference.title = monogr_title.take();
+                        } else {
+                            reference.journal = monogr_title.take();
+                        }
+                        if reference.title.is_some() || !reference.authors.is_empty() {
+                            references.push(reference);
+                        }
+                    }
+                }
+                _ => (),
+            },
+            Ok(Event::Text(e)) if in_name_part => {
+                current_author.push_str(&String::from_utf8_lossy(&e));
+                current_author.push(' ');
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                warn!("Stopped parsing GROBID references: {}", e);
+                break;
+            }
+            _ => (),
+        }
+        buf.clear();
+    }
+
+    references
+}
+
+/// Take the year of the first dated `<date>` in a reference
+fn set_reference_year(reference: Option<&mut GrobidReference>, date: &BytesStart) {
+    let Some(reference) = reference.filter(|r| r.year.is_none()) else {
+        return;
+    };
+    reference.year = date
+        .attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == b"when")
+        .and_then(|a| parse_when_year(&String::from_utf8_lossy(a.value.as_ref())));
 }
 
 #[allow(unused_assignments, unused_variables)]
@@ -106,7 +332,9 @@ fn parse_tei_xml(xml: &str) -> Result<GrobidMetadata> {
     let mut in_surname = false;
     let mut in_forename = false;
     let mut in_abstract = false;
+    let mut in_affiliation = false;
     let mut current_author = String::new();
+    let mut current_details = GrobidAuthor::default();
 
     loop {
         match reader.read_event_into(&mut buf) {
@@ -117,6 +345,7 @@ fn parse_tei_xml(xml: &str) -> Result<GrobidMetadata> {
                 b"author" => {
                     in_author = true;
                     current_author.clear();
+                    current_details = GrobidAuthor::default();
                     info!("Starting to parse author");
                 }
                 b"surname" => in_surname = true,
@@ -145,6 +374,35 @@ fn parse_tei_xml(xml: &str) -> Result<GrobidMetadata> {
                         }
                     }
                 }
+                b"affiliation" if in_author => in_affiliation = true,
+                b"orgName" if in_affiliation && current_details.affiliation.is_none() => {
+                    let is_institution = e
+                        .attributes()
+                        .flatten()
+                        .any(|a| a.key.as_ref() == b"type" && a.value.as_ref() == b"institution");
+                    if is_institution {
+                        if let Ok(name) = reader.read_text(e.name()) {
+                            let name = name.trim();
+                            if !name.is_empty() {
+                                current_details.affiliation = Some(name.to_string());
+                            }
+                        }
+                    }
+                }
+                b"idno" if in_author => {
+                    let is_orcid = e
+                        .attributes()
+                        .flatten()
+                        .any(|a| a.key.as_ref() == b"type" && a.value.as_ref() == b"ORCID");
+                    if is_orcid {
+                        if let Ok(orcid) = reader.read_text(e.name()) {
+                            let orcid = orcid.trim();
+                            if !orcid.is_empty() {
+                                current_details.orcid = Some(orcid.to_string());
+                            }
+                        }
+                    }
+                }
                 b"idno" => {
                     let mut is_doi = false;
                     for a in e.attributes().flatten() {
@@ -187,10 +445,14 @@ fn parse_tei_xml(xml: &str) -> Result<GrobidMetadata> {
                     in_author = false;
                     let name = current_author.trim();
                     if !name.is_empty() {
-                        metadata.authors.push(name.to_string());
                         info!("Added author: {}", name);
+                        metadata.authors.push(GrobidAuthor {
+                            name: name.to_string(),
+                            ..std::mem::take(&mut current_details)
+                        });
                     }
                 }
+                b"affiliation" => in_affiliation = false,
                 b"surname" => in_surname = false,
                 b"forename" => in_forename = false,
                 b"abstract" => in_abstract = false,
@@ -230,3 +492,175 @@ fn parse_tei_xml(xml: &str) -> Result<GrobidMetadata> {
 
     Ok(metadata)
 }
+
+/// Result of pinging a GROBID server
+#[derive(Debug, Clone)]
+pub struct GrobidHealth {
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub response_ms: u64,
+}
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Call `/api/isalive` on a GROBID server. Connection failures, timeouts
+/// and error statuses are reported as unreachable rather than as an error.
+pub async fn check_alive(server_url: &str) -> GrobidHealth {
+    let url = format!("{}/api/isalive", server_url.trim_end_matches('/'));
+    let started = std::time::Instant::now();
+
+    let result = async {
+        let client = reqwest::Client::builder()
+            .no_proxy()
+            .timeout(HEALTH_CHECK_TIMEOUT)
+            .build()?;
+        client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await
+    }
+    .await;
+    let response_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(body) => GrobidHealth {
+            reachable: true,
+            version: parse_version(&body),
+            response_ms,
+        },
+        Err(e) => {
+            warn!("GROBID server {} is not reachable: {}", server_url, e);
+            GrobidHealth {
+                reachable: false,
+                version: None,
+                response_ms,
+            }
+        }
+    }
+}
+
+/// Version number in an `isalive` body. Older servers answer a bare `true`,
+/// which carries no version.
+fn parse_version(body: &str) -> Option<String> {
+    body.split(|c: char| c.is_whitespace() || matches!(c, '"' | ',' | ':' | '{' | '}'))
+        .map(|token| token.trim_start_matches(['v', 'V']))
+        .find(|token| {
+            token.contains('.')
+                && token.starts_with(|c: char| c.is_ascii_digit())
+                && token
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+        })
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("true"), None);
+        assert_eq!(parse_version("0.8.0\n").as_deref(), Some("0.8.0"));
+        assert_eq!(
+            parse_version(r#"{"version": "0.7.3-SNAPSHOT", "alive": true}"#).as_deref(),
+            Some("0.7.3-SNAPSHOT")
+        );
+    }
+
+    #[test]
+    fn test_parse_tei_xml_author_details() {
+        let xml = r#"<TEI><teiHeader><fileDesc><sourceDesc><biblStruct><analytic>
+            <title level="a" type="main">Example</title>
+            <author role="corresp">
+                <persName><forename type="first">Josiah</forename><surname>Carberry</surname></persName>
+                <email>josiah@brown.edu</email>
+                <idno type="ORCID">0000-0002-1825-0097</idno>
+                <affiliation key="aff0">
+                    <orgName type="department">Psychoceramics</orgName>
+                    <orgName type="institution">Brown University</orgName>
+                </affiliation>
+            </author>
+            <author><persName><forename>Jane</forename><surname>Doe</surname></persName></author>
+        </analytic><monogr><title level="j">Journal of Psychoceramics</title></monogr>
+        </biblStruct></sourceDesc></fileDesc>
+        <profileDesc><abstract><p>Cracked pots.</p></abstract></profileDesc></teiHeader></TEI>"#;
+
+        let metadata = parse_tei_xml(xml).unwrap();
+        assert_eq!(metadata.title, "Example");
+        assert_eq!(
+            metadata.journal_name.as_deref(),
+            Some("Journal of Psychoceramics")
+        );
+        assert_eq!(
+            metadata.abstract_text.as_deref().map(str::trim),
+            Some("Cracked pots.")
+        );
+        assert_eq!(
+            metadata.authors,
+            vec![
+                GrobidAuthor {
+                    name: "Josiah Carberry".to_string(),
+                    affiliation: Some("Brown University".to_string()),
+                    orcid: Some("0000-0002-1825-0097".to_string()),
+                },
+                GrobidAuthor {
+                    name: "Jane Doe".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_tei_references() {
+        let xml = r#"<TEI><text><back><div type="references"><listBibl>
+            <biblStruct xml:id="b0">
+                <analytic>
+                    <title level="a" type="main">Attention is all you need</title>
+                    <author><persName><forename type="first">Ashish</forename><surname>Vaswani</surname></persName></author>
+                    <author><persName><forename type="first">Noam</forename><surname>Shazeer</surname></persName></author>
+                    <idno type="DOI">10.5555/3295222.3295349</idno>
+                </analytic>
+                <monogr>
+                    <title level="m">Advances in Neural Information Processing Systems</title>
+                    <imprint><date type="published" when="2017" /></imprint>
+                </monogr>
+                <note type="raw_reference">A. Vaswani, N. Shazeer. Attention is all you need.
+                    NeurIPS 2017.</note>
+            </biblStruct>
+            <biblStruct xml:id="b1">
+                <monogr>
+                    <title level="m" type="main">Deep Learning</title>
+                    <author><persName><forename>Ian</forename><surname>Goodfellow</surname></persName></author>
+                    <imprint><date type="published" when="2016-11-18">2016</date></imprint>
+                </monogr>
+            </biblStruct>
+            <biblStruct xml:id="b2"><monogr><imprint /></monogr></biblStruct>
+        </listBibl></div></back></text></TEI>"#;
+
+        let references = parse_tei_references(xml);
+        assert_eq!(references.len(), 2);
+        assert_eq!(
+            references[0],
+            GrobidReference {
+                title: Some("Attention is all you need".to_string()),
+                authors: vec!["Ashish Vaswani".to_string(), "Noam Shazeer".to_string()],
+                year: Some(2017),
+                doi: Some("10.5555/3295222.3295349".to_string()),
+                journal: Some("Advances in Neural Information Processing Systems".to_string()),
+                raw_text: Some(
+                    "A. Vaswani, N. Shazeer. Attention is all you need. NeurIPS 2017.".to_string()
+                ),
+            }
+        );
+        assert_eq!(references[1].title.as_deref(), Some("Deep Learning"));
+        assert_eq!(references[1].journal, None);
+        assert_eq!(references[1].authors, vec!["Ian Goodfellow"]);
+        assert_eq!(references[1].year, Some(2016));
+        assert_eq!(references[1].doi, None);
+    }
+}
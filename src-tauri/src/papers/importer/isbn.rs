@@ -0,0 +1,286 @@
+//! Book metadata by ISBN
+//!
+//! Open Library is queried first. Google Books is only asked when Open
+//! Library does not know the ISBN or cannot be reached.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::papers::importer::openlibrary::{fetch_openlibrary_metadata, OpenLibraryError};
+
+/// ISBN lookup error types
+#[derive(Error, Debug)]
+pub enum IsbnError {
+    #[error("HTTP request failed: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Invalid ISBN: {0}")]
+    InvalidIsbn(String),
+
+    #[error("Failed to parse book metadata: {0}")]
+    ParseError(String),
+
+    #[error("ISBN not found in Open Library or Google Books")]
+    NotFound,
+}
+
+/// Metadata of a book, whichever service it came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookMetadata {
+    /// Normalized ISBN-13
+    pub isbn: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    /// Publication date as given by the source, e.g. "March 1988"
+    pub publish_date: Option<String>,
+    pub publication_year: Option<i32>,
+    pub number_of_pages: Option<u32>,
+    pub cover_url: Option<String>,
+    /// Page for the book at the source
+    pub url: String,
+    /// "openlibrary" or "googlebooks"
+    pub source: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleBooksResponse {
+    #[serde(default)]
+    items: Vec<GoogleBooksItem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleBooksItem {
+    #[serde(rename = "volumeInfo")]
+    volume_info: GoogleVolumeInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleVolumeInfo {
+    title: Option<String>,
+    subtitle: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    publisher: Option<String>,
+    #[serde(rename = "publishedDate")]
+    published_date: Option<String>,
+    #[serde(rename = "pageCount")]
+    page_count: Option<u32>,
+    #[serde(rename = "imageLinks")]
+    image_links: Option<GoogleImageLinks>,
+    #[serde(rename = "infoLink")]
+    info_link: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GoogleImageLinks {
+    thumbnail: Option<String>,
+    #[serde(rename = "smallThumbnail")]
+    small_thumbnail: Option<String>,
+}
+
+/// Normalize an ISBN-10 or ISBN-13 (hyphens and spaces allowed) to ISBN-13.
+/// Returns `None` if the length or check digit is wrong.
+pub fn normalize_isbn(isbn: &str) -> Option<String> {
+    let raw = isbn.trim();
+    let raw = raw
+        .strip_prefix("ISBN:")
+        .or_else(|| raw.strip_prefix("ISBN"))
+        .unwrap_or(raw);
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    match cleaned.len() {
+        10 => {
+            let (body, check) = cleaned.split_at(9);
+            if !body.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let sum: u32 = body
+                .chars()
+                .zip((2..=10).rev())
+                .map(|(c, w)| c.to_digit(10).unwrap() * w)
+                .sum();
+            let expected = match (11 - sum % 11) % 11 {
+                10 => 'X',
+                d => char::from_digit(d, 10).unwrap(),
+            };
+            if check.chars().next() != Some(expected) {
+                return None;
+            }
+
+            let body13 = format!("978{}", body);
+            Some(format!("{}{}", body13, isbn13_check_digit(&body13)))
+        }
+        13 => {
+            if !cleaned.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            let (body, check) = cleaned.split_at(12);
+            (check.chars().next() == Some(isbn13_check_digit(body))).then_some(cleaned)
+        }
+        _ => None,
+    }
+}
+
+fn isbn13_check_digit(body: &str) -> char {
+    let sum: u32 = body
+        .chars()
+        .enumerate()
+        .map(|(i, c)| c.to_digit(10).unwrap() * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    char::from_digit((10 - sum % 10) % 10, 10).unwrap()
+}
+
+/// First four-digit number in a free-form date such as "March 1988"
+pub(crate) fn parse_year(date: &str) -> Option<i32> {
+    date.split(|c: char| !c.is_ascii_digit())
+        .find(|part| part.len() == 4)
+        .and_then(|part| part.parse().ok())
+}
+
+fn parse_google_books(isbn13: &str, body: &str) -> Result<BookMetadata, IsbnError> {
+    let response: GoogleBooksResponse =
+        serde_json::from_str(body).map_err(|e| IsbnError::ParseError(e.to_string()))?;
+    let info = response
+        .items
+        .into_iter()
+        .next()
+        .map(|item| item.volume_info)
+        .ok_or(IsbnError::NotFound)?;
+
+    let title = info
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| IsbnError::ParseError("Book has no title".to_string()))?;
+    let title = match info.subtitle.filter(|s| !s.trim().is_empty()) {
+        Some(subtitle) => format!("{}: {}", title.trim(), subtitle.trim()),
+        None => title.trim().to_string(),
+    };
+    // Google serves covers over plain HTTP by default
+    let cover_url = info
+        .image_links
+        .and_then(|links| links.thumbnail.or(links.small_thumbnail))
+        .map(|url| url.replacen("http://", "https://", 1));
+
+    Ok(BookMetadata {
+        isbn: isbn13.to_string(),
+        title,
+        authors: info.authors,
+        publisher: info.publisher,
+        publication_year: info.published_date.as_deref().and_then(parse_year),
+        publish_date: info.published_date,
+        number_of_pages: info.page_count,
+        cover_url,
+        url: info
+            .info_link
+            .unwrap_or_else(|| format!("https://books.google.com/books?vid=ISBN{}", isbn13)),
+        source: "googlebooks".to_string(),
+    })
+}
+
+async fn fetch_google_books(isbn13: &str) -> Result<BookMetadata, IsbnError> {
+    let url = format!(
+        "https://www.googleapis.com/books/v1/volumes?q=isbn:{}",
+        isbn13
+    );
+
+    let client = reqwest::Client::builder()
+        .user_agent("XuanBrain/0.1.0 (mailto:support@example.com)")
+        .build()?;
+
+    let body = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    parse_google_books(isbn13, &body)
+}
+
+/// Fetch book metadata by ISBN-10 or ISBN-13, trying Open Library first and
+/// Google Books second. The ISBN is validated before any request is made.
+pub async fn fetch_isbn_metadata(isbn: &str) -> Result<BookMetadata, IsbnError> {
+    let isbn13 = normalize_isbn(isbn).ok_or_else(|| IsbnError::InvalidIsbn(isbn.to_string()))?;
+
+    match fetch_openlibrary_metadata(&isbn13).await {
+        Ok(book) => {
+            return Ok(BookMetadata {
+                isbn: book.isbn,
+                title: book.title,
+                authors: book.authors,
+                publisher: book.publisher,
+                publish_date: book.publish_date,
+                publication_year: book.publication_year,
+                number_of_pages: book.number_of_pages,
+                cover_url: book.cover_url,
+                url: book.url,
+                source: "openlibrary".to_string(),
+            })
+        }
+        Err(OpenLibraryError::NotFound) => {
+            info!("ISBN {} not in Open Library, trying Google Books", isbn13);
+        }
+        Err(e) => {
+            warn!("Open Library lookup for {} failed: {}", isbn13, e);
+        }
+    }
+
+    fetch_google_books(&isbn13).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(
+            normalize_isbn("0-14-032872-1").as_deref(),
+            Some("9780140328721")
+        );
+        assert_eq!(
+            normalize_isbn("978-0-14-032872-1").as_deref(),
+            Some("9780140328721")
+        );
+        assert_eq!(
+            normalize_isbn("ISBN:080442957X").as_deref(),
+            Some("9780804429573")
+        );
+        assert_eq!(normalize_isbn("0140328722"), None);
+        assert_eq!(normalize_isbn("12345"), None);
+    }
+
+    #[test]
+    fn test_parse_google_books() {
+        let body = r#"{"totalItems": 1, "items": [{"volumeInfo": {
+            "title": "Fantastic Mr. Fox",
+            "authors": ["Roald Dahl"],
+            "publisher": "Puffin",
+            "publishedDate": "1988-10-01",
+            "pageCount": 96,
+            "imageLinks": {"thumbnail": "http://books.google.com/books/content?id=x"},
+            "infoLink": "https://books.google.com/books?id=x"
+        }}]}"#;
+
+        let metadata = parse_google_books("9780140328721", body).unwrap();
+        assert_eq!(metadata.title, "Fantastic Mr. Fox");
+        assert_eq!(metadata.authors, vec!["Roald Dahl"]);
+        assert_eq!(metadata.publication_year, Some(1988));
+        assert_eq!(
+            metadata.cover_url.as_deref(),
+            Some("https://books.google.com/books/content?id=x")
+        );
+
+        assert!(matches!(
+            parse_google_books("9780140328721", r#"{"totalItems": 0}"#),
+            Err(IsbnError::NotFound)
+        ));
+    }
+}
@@ -1,6 +1,12 @@
 pub mod arxiv;
+pub mod crossref;
+pub mod csv_file;
 pub mod doi;
 pub mod grobid;
 pub mod html;
+pub mod isbn;
+pub mod openlibrary;
 pub mod pubmed;
+pub mod rate_limiter;
+pub mod url;
 pub mod zotero_rdf;
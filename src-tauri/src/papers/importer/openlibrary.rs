@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+use crate::papers::importer::isbn::{normalize_isbn, parse_year};
+
+/// Open Library metadata fetcher error types
+#[derive(Error, Debug)]
+pub enum OpenLibraryError {
+    #[error("HTTP request failed: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Invalid ISBN: {0}")]
+    InvalidIsbn(String),
+
+    #[error("Failed to parse Open Library metadata: {0}")]
+    ParseError(String),
+
+    #[error("ISBN not found in Open Library")]
+    NotFound,
+}
+
+/// Metadata of a book from Open Library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenLibraryMetadata {
+    /// Normalized ISBN-13
+    pub isbn: String,
+    pub title: String,
+    pub authors: Vec<String>,
+    pub publisher: Option<String>,
+    /// Publication date as given by Open Library, e.g. "March 1988"
+    pub publish_date: Option<String>,
+    pub publication_year: Option<i32>,
+    pub number_of_pages: Option<u32>,
+    pub cover_url: Option<String>,
+    /// Stable Open Library URL for the ISBN
+    pub url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryBook {
+    title: Option<String>,
+    subtitle: Option<String>,
+    #[serde(default)]
+    authors: Vec<NamedEntry>,
+    #[serde(default)]
+    publishers: Vec<NamedEntry>,
+    publish_date: Option<String>,
+    number_of_pages: Option<u32>,
+    cover: Option<OpenLibraryCover>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenLibraryCover {
+    large: Option<String>,
+    medium: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedEntry {
+    name: String,
+}
+
+fn parse_response(isbn13: &str, body: &str) -> Result<OpenLibraryMetadata, OpenLibraryError> {
+    let mut books: HashMap<String, OpenLibraryBook> =
+        serde_json::from_str(body).map_err(|e| OpenLibraryError::ParseError(e.to_string()))?;
+    let book = books
+        .drain()
+        .next()
+        .map(|(_, book)| book)
+        .ok_or(OpenLibraryError::NotFound)?;
+
+    let title = book
+        .title
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| OpenLibraryError::ParseError("Book has no title".to_string()))?;
+    let title = match book.subtitle.filter(|s| !s.trim().is_empty()) {
+        Some(subtitle) => format!("{}: {}", title.trim(), subtitle.trim()),
+        None => title.trim().to_string(),
+    };
+
+    Ok(OpenLibraryMetadata {
+        isbn: isbn13.to_string(),
+        title,
+        authors: book.authors.into_iter().map(|a| a.name).collect(),
+        publisher: book.publishers.into_iter().next().map(|p| p.name),
+        publication_year: book.publish_date.as_deref().and_then(parse_year),
+        publish_date: book.publish_date,
+        number_of_pages: book.number_of_pages,
+        cover_url: book.cover.and_then(|c| c.large.or(c.medium)),
+        url: format!("https://openlibrary.org/isbn/{}", isbn13),
+    })
+}
+
+/// Fetch book metadata from Open Library by ISBN-10 or ISBN-13
+pub async fn fetch_openlibrary_metadata(
+    isbn: &str,
+) -> Result<OpenLibraryMetadata, OpenLibraryError> {
+    let isbn13 =
+        normalize_isbn(isbn).ok_or_else(|| OpenLibraryError::InvalidIsbn(isbn.to_string()))?;
+
+    let url = format!(
+        "https://openlibrary.org/api/books?bibkeys=ISBN:{}&jscmd=data&format=json",
+        isbn13
+    );
+
+    let client = reqwest::Client::builder()
+        .user_agent("XuanBrain/0.1.0 (mailto:support@example.com)")
+        .build()?;
+
+    let body = client
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    parse_response(&isbn13, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let body = r#"{"ISBN:9780140328721": {
+            "title": "Fantastic Mr. Fox",
+            "authors": [{"url": "https://openlibrary.org/authors/OL34184A", "name": "Roald Dahl"}],
+            "publishers": [{"name": "Puffin"}],
+            "publish_date": "October 1, 1988",
+            "number_of_pages": 96,
+            "cover": {"large": "https://covers.openlibrary.org/b/id/1-L.jpg"}
+        }}"#;
+
+        let metadata = parse_response("9780140328721", body).unwrap();
+        assert_eq!(metadata.title, "Fantastic Mr. Fox");
+        assert_eq!(metadata.authors, vec!["Roald Dahl"]);
+        assert_eq!(metadata.publisher.as_deref(), Some("Puffin"));
+        assert_eq!(metadata.publication_year, Some(1988));
+        assert_eq!(
+            metadata.cover_url.as_deref(),
+            Some("https://covers.openlibrary.org/b/id/1-L.jpg")
+        );
+
+        assert!(matches!(
+            parse_response("9780140328721", "{}"),
+            Err(OpenLibraryError::NotFound)
+        ));
+    }
+}
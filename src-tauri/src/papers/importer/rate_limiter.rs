@@ -0,0 +1,45 @@
+//! Simple interval-based rate limiter for metadata APIs
+//!
+//! Crossref, arXiv and PubMed all ask clients to keep request rates low.
+//! `RateLimiter` hands out one slot per interval; callers await `acquire`
+//! before each request.
+
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Default spacing between metadata requests in batch imports
+pub const DEFAULT_IMPORT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    /// Wait until the next request slot is available
+    pub async fn acquire(&self) {
+        let mut next_slot = self.next_slot.lock().await;
+        let now = Instant::now();
+        if let Some(slot) = *next_slot {
+            if slot > now {
+                tokio::time::sleep_until(slot).await;
+            }
+        }
+        *next_slot = Some(Instant::now() + self.interval);
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_IMPORT_INTERVAL)
+    }
+}
@@ -0,0 +1,363 @@
+//! Paper identifiers and metadata from the URL of a landing page
+//!
+//! Most publishers put the DOI in their article URLs, and arXiv URLs carry
+//! the arXiv ID. Pages whose URL has neither (IEEE Xplore, ScienceDirect)
+//! are fetched and their `<meta>` tags read: Highwire `citation_*` tags,
+//! which scholarly sites add for Google Scholar, then Open Graph and
+//! Dublin Core.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+use thiserror::Error;
+
+use super::arxiv::extract_arxiv_id;
+
+/// Only the `<head>` is read, and at most this much of a page without one
+const MAX_HEAD_BYTES: usize = 512 * 1024;
+
+/// Path segments publishers append after the DOI in article URLs
+const DOI_URL_SUFFIXES: &[&str] = &["/full", "/abstract", "/pdf", "/epdf", "/html", ".pdf"];
+
+static URL_DOI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"10\.\d{4,9}/\S+").unwrap());
+static NATURE_ARTICLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"/articles/([a-z0-9][a-z0-9.-]+)").unwrap());
+static CHARACTER_REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+static META_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<meta\b[^>]*>").unwrap());
+static ATTRIBUTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?is)([a-z:_.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+static HTML_TITLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+static META_DOI: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"10\.\d{4,9}/[^\s<>]+").unwrap());
+// IEEE Xplore only has the DOI in the metadata object of its page script
+static SCRIPT_DOI: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""doi"\s*:\s*"(10\.\d{4,9}/[^"]+)""#).unwrap());
+static YEAR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(1[5-9]\d{2}|2\d{3})\b").unwrap());
+
+/// URL import error types
+#[derive(Error, Debug)]
+pub enum UrlImportError {
+    #[error("HTTP request failed: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Not an HTML page: {0}")]
+    NotHtml(String),
+}
+
+/// Bibliographic metadata found in the `<meta>` tags of a page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageMetadata {
+    pub title: Option<String>,
+    pub authors: Vec<String>,
+    pub description: Option<String>,
+    pub doi: Option<String>,
+    pub publication_year: Option<i32>,
+    pub journal_name: Option<String>,
+    /// Name of the site, e.g. "IEEE Xplore"
+    pub site_name: Option<String>,
+}
+
+/// Host of an http or https URL, lowercased
+pub fn url_host(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return None;
+    }
+    Some(parsed.host_str()?.to_lowercase())
+}
+
+/// DOI in an article URL, e.g. `https://doi.org/10.1145/3292500.3330701`,
+/// `https://dl.acm.org/doi/pdf/10.1145/3292500.3330701` or
+/// `https://link.springer.com/article/10.1007/s00170-019-03953-1`. Nature
+/// article URLs only carry the suffix of their `10.1038` DOI.
+pub fn extract_doi_from_url(url: &str) -> Option<String> {
+    let host = url_host(url)?;
+    let decoded = urlencoding::decode(url).ok()?;
+    let url = decoded.split(['?', '#']).next().unwrap_or_default();
+
+    if let Some(m) = URL_DOI.find(url) {
+        let mut doi = m.as_str().trim_end_matches('/');
+        while let Some(suffix) = DOI_URL_SUFFIXES.iter().find(|s| doi.ends_with(*s)) {
+            doi = &doi[..doi.len() - suffix.len()];
+        }
+        return Some(doi.to_string());
+    }
+
+    if host == "nature.com" || host.ends_with(".nature.com") {
+        return NATURE_ARTICLE
+            .captures(url)
+            .map(|caps| format!("10.1038/{}", caps[1].trim_end_matches(".pdf")));
+    }
+
+    None
+}
+
+/// arXiv ID in an arxiv.org abstract or PDF URL
+pub fn extract_arxiv_id_from_url(url: &str) -> Option<String> {
+    let host = url_host(url)?;
+    if host != "arxiv.org" && !host.ends_with(".arxiv.org") {
+        return None;
+    }
+    extract_arxiv_id(url)
+}
+
+/// Decode the character references that show up in `<meta>` contents
+fn decode_entities(text: &str) -> String {
+    CHARACTER_REFERENCE
+        .replace_all(text, |caps: &regex::Captures| {
+            let name = &caps[1];
+            let decoded = match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if name.starts_with("#x") || name.starts_with("#X") => {
+                    u32::from_str_radix(&name[2..], 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                }
+                _ if name.starts_with('#') => name[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            decoded.map_or_else(|| caps[0].to_string(), String::from)
+        })
+        .trim()
+        .to_string()
+}
+
+/// `(name, content)` of every `<meta>` tag in `html`, names lowercased.
+/// The name is the `name`, `property` or `itemprop` attribute.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    META_TAG
+        .find_iter(html)
+        .filter_map(|m| {
+            let mut name = None;
+            let mut content = None;
+            for caps in ATTRIBUTE.captures_iter(m.as_str()) {
+                let value = caps.get(2).or(caps.get(3)).map_or("", |v| v.as_str());
+                match caps[1].to_lowercase().as_str() {
+                    "name" | "property" | "itemprop" => name = Some(value.to_lowercase()),
+                    "content" => content = Some(decode_entities(value)),
+                    _ => {}
+                }
+            }
+            Some((name?, content.filter(|c| !c.is_empty())?))
+        })
+        .collect()
+}
+
+/// Bibliographic metadata from the `<meta>` tags and `<title>` of a page
+pub fn parse_page_metadata(html: &str) -> PageMetadata {
+    let head_end = html
+        .find("</head>")
+        .or_else(|| html.find("</HEAD>"))
+        .unwrap_or(html.len())
+        .min(MAX_HEAD_BYTES);
+    let head_end = (0..=head_end)
+        .rev()
+        .find(|i| html.is_char_boundary(*i))
+        .unwrap_or(0);
+    let head = &html[..head_end];
+
+    let tags = meta_tags(head);
+    let first = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            tags.iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, content)| content.clone())
+        })
+    };
+    let all = |name: &str| -> Vec<String> {
+        tags.iter()
+            .filter(|(n, _)| n == name)
+            .map(|(_, content)| content.clone())
+            .collect()
+    };
+
+    let html_title = HTML_TITLE
+        .captures(head)
+        .map(|caps| decode_entities(&caps[1]))
+        .filter(|t| !t.is_empty());
+
+    let mut authors = all("citation_author");
+    if authors.is_empty() {
+        authors = all("dc.creator");
+    }
+    if authors.is_empty() {
+        // Open Graph authors are often profile URLs
+        authors = ["author", "article:author"]
+            .into_iter()
+            .flat_map(all)
+            .filter(|a| !a.starts_with("http"))
+            .collect();
+    }
+
+    let doi = first(&[
+        "citation_doi",
+        "prism.doi",
+        "dc.identifier",
+        "bepress_citation_doi",
+    ])
+    .and_then(|d| META_DOI.find(&d).map(|m| m.as_str().to_string()))
+    .or_else(|| SCRIPT_DOI.captures(html).map(|caps| caps[1].to_string()));
+
+    let publication_year = first(&[
+        "citation_publication_date",
+        "citation_date",
+        "citation_online_date",
+        "dc.date",
+        "article:published_time",
+    ])
+    .and_then(|date| YEAR.find(&date)?.as_str().parse().ok());
+
+    PageMetadata {
+        title: first(&["citation_title", "og:title", "dc.title"]).or(html_title),
+        authors,
+        description: first(&[
+            "citation_abstract",
+            "og:description",
+            "dc.description",
+            "description",
+        ]),
+        doi,
+        publication_year,
+        journal_name: first(&["citation_journal_title", "prism.publicationname"]),
+        site_name: first(&["og:site_name"]),
+    }
+}
+
+/// Fetch the start of the HTML of a page, at most `MAX_HEAD_BYTES`. Other
+/// content types are rejected.
+pub async fn fetch_page(url: &str) -> Result<String, UrlImportError> {
+    if url_host(url).is_none() {
+        return Err(UrlImportError::InvalidUrl(url.to_string()));
+    }
+
+    let client = reqwest::Client::builder()
+        .user_agent("XuanBrain/0.1.0 (mailto:support@example.com)")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+    let mime = content_type.split(';').next().unwrap_or_default().trim();
+    if !matches!(mime, "text/html" | "application/xhtml+xml") {
+        return Err(UrlImportError::NotHtml(if mime.is_empty() {
+            "no content type".to_string()
+        } else {
+            mime.to_string()
+        }));
+    }
+
+    let mut body: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_HEAD_BYTES {
+            body.truncate(MAX_HEAD_BYTES);
+            break;
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_doi_from_url() {
+        let cases = [
+            (
+                "https://doi.org/10.1016/j.precisioneng.2019.10.013",
+                Some("10.1016/j.precisioneng.2019.10.013"),
+            ),
+            (
+                "https://dl.acm.org/doi/pdf/10.1145/3292500.3330701?download=true",
+                Some("10.1145/3292500.3330701"),
+            ),
+            (
+                "https://onlinelibrary.wiley.com/doi/full/10.1002/adma.201904789/",
+                Some("10.1002/adma.201904789"),
+            ),
+            (
+                "https://www.tandfonline.com/doi/abs/10.1080%2F00207543.2020.1740342",
+                Some("10.1080/00207543.2020.1740342"),
+            ),
+            (
+                "https://link.springer.com/content/pdf/10.1007/s00170-019-03953-1.pdf",
+                Some("10.1007/s00170-019-03953-1"),
+            ),
+            (
+                "https://www.nature.com/articles/s41586-020-2649-2",
+                Some("10.1038/s41586-020-2649-2"),
+            ),
+            ("https://ieeexplore.ieee.org/document/8953658", None),
+            ("ftp://example.org/10.1000/abc", None),
+        ];
+        for (url, doi) in cases {
+            assert_eq!(extract_doi_from_url(url).as_deref(), doi, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_extract_arxiv_id_from_url() {
+        assert_eq!(
+            extract_arxiv_id_from_url("https://arxiv.org/abs/2301.01234v2").as_deref(),
+            Some("2301.01234")
+        );
+        assert_eq!(
+            extract_arxiv_id_from_url("https://export.arxiv.org/pdf/2301.01234").as_deref(),
+            Some("2301.01234")
+        );
+        assert_eq!(
+            extract_arxiv_id_from_url("https://example.org/abs/2301.01234"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_page_metadata() {
+        let html = r#"<!DOCTYPE html><html><head>
+            <title>Ignored &amp; replaced</title>
+            <meta property="og:site_name" content="IEEE Xplore">
+            <meta property="og:title" content="Thermal Error Compensation of Machine Tools" />
+            <meta name="citation_author" content="Zhang, Wei">
+            <meta name="citation_author" content='Li, Na'>
+            <meta name="Description" content="We model thermal errors &#8211; and compensate them.">
+            <meta name="citation_publication_date" content="2019/12/03">
+            <script>xplGlobal.document.metadata={"doi":"10.1109/TIE.2019.2956360","title":"x"};</script>
+            </head><body><meta name="citation_title" content="Body"></body></html>"#;
+
+        let metadata = parse_page_metadata(html);
+        assert_eq!(
+            metadata.title.as_deref(),
+            Some("Thermal Error Compensation of Machine Tools")
+        );
+        assert_eq!(metadata.authors, vec!["Zhang, Wei", "Li, Na"]);
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("We model thermal errors \u{2013} and compensate them.")
+        );
+        assert_eq!(metadata.doi.as_deref(), Some("10.1109/TIE.2019.2956360"));
+        assert_eq!(metadata.publication_year, Some(2019));
+        assert_eq!(metadata.site_name.as_deref(), Some("IEEE Xplore"));
+
+        let bare = parse_page_metadata("<html><head><title>Only a title</title></head></html>");
+        assert_eq!(bare.title.as_deref(), Some("Only a title"));
+        assert!(bare.authors.is_empty());
+        assert_eq!(bare.doi, None);
+    }
+}
@@ -1 +1,2 @@
+pub mod exporter;
 pub mod importer;
@@ -0,0 +1,147 @@
+//! Activity log repository for SQLite using SeaORM
+
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+
+use crate::database::entities::activity_log;
+use crate::sys::error::{AppError, Result};
+
+#[derive(FromQueryResult)]
+struct ViewedPaper {
+    paper_id: i64,
+}
+
+/// Repository for the activity log
+pub struct ActivityRepository;
+
+impl ActivityRepository {
+    /// Append an entry stamped with the current time
+    pub async fn record(
+        db: &DatabaseConnection,
+        entity_type: &str,
+        entity_id: i64,
+        action: &str,
+    ) -> Result<()> {
+        activity_log::ActiveModel {
+            entity_type: Set(entity_type.to_string()),
+            entity_id: Set(entity_id),
+            action: Set(action.to_string()),
+            created_at: Set(Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to record activity: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// The `limit` most recent entries, newest first
+    pub async fn find_recent(
+        db: &DatabaseConnection,
+        limit: u64,
+    ) -> Result<Vec<activity_log::Model>> {
+        activity_log::Entity::find()
+            .order_by_desc(activity_log::Column::CreatedAt)
+            .order_by_desc(activity_log::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get activity: {}", e)))
+    }
+
+    /// Ids of the `limit` most recently viewed papers, each listed once and
+    /// ordered by their last view. Papers in the trash are skipped.
+    pub async fn recently_viewed_paper_ids(
+        db: &DatabaseConnection,
+        view_action: &str,
+        limit: u64,
+    ) -> Result<Vec<i64>> {
+        let rows = ViewedPaper::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+            SELECT a.entity_id AS paper_id
+            FROM activity_log a
+            INNER JOIN paper p ON p.id = a.entity_id AND p.deleted_at IS NULL
+            WHERE a.entity_type = 'paper' AND a.action = ?
+            GROUP BY a.entity_id
+            ORDER BY MAX(a.created_at) DESC, MAX(a.id) DESC
+            LIMIT ?
+            "#,
+            [view_action.into(), (limit as i64).into()],
+        ))
+        .all(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to get recently viewed papers: {}", e)))?;
+
+        Ok(rows.into_iter().map(|r| r.paper_id).collect())
+    }
+
+    /// Entries with `entity_type` and `action` recorded at or after `since`,
+    /// oldest first
+    pub async fn find_since(
+        db: &DatabaseConnection,
+        entity_type: &str,
+        action: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<activity_log::Model>> {
+        activity_log::Entity::find()
+            .filter(activity_log::Column::EntityType.eq(entity_type))
+            .filter(activity_log::Column::Action.eq(action))
+            .filter(activity_log::Column::CreatedAt.gte(since))
+            .order_by_asc(activity_log::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get activity: {}", e)))
+    }
+
+    /// Delete entries recorded before `cutoff`; returns how many were deleted
+    pub async fn delete_older_than(db: &DatabaseConnection, cutoff: DateTime<Utc>) -> Result<u64> {
+        let result = activity_log::Entity::delete_many()
+            .filter(activity_log::Column::CreatedAt.lt(cutoff))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to trim activity log: {}", e)))?;
+
+        Ok(result.rows_affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PaperRepository;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_recently_viewed_dedupes_and_skips_trash() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+        let c = PaperFixture::new("C").insert(&db).await;
+
+        for id in [a.id, b.id, a.id, c.id] {
+            ActivityRepository::record(&db, "paper", id, "viewed")
+                .await
+                .unwrap();
+        }
+        ActivityRepository::record(&db, "paper", b.id, "updated")
+            .await
+            .unwrap();
+        PaperRepository::soft_delete(&db, c.id).await.unwrap();
+
+        let ids = ActivityRepository::recently_viewed_paper_ids(&db, "viewed", 10)
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![a.id, b.id]);
+        assert_eq!(
+            ActivityRepository::find_recent(&db, 2).await.unwrap()[0].action,
+            "updated"
+        );
+
+        let trimmed = ActivityRepository::delete_older_than(&db, Utc::now())
+            .await
+            .unwrap();
+        assert_eq!(trimmed, 5);
+    }
+}
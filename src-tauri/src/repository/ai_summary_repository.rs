@@ -0,0 +1,133 @@
+//! Paper AI summary repository for SQLite using SeaORM
+
+use sea_orm::*;
+
+use crate::database::entities::paper_ai_summary;
+use crate::sys::error::{AppError, Result};
+
+/// Repository for LLM-generated paper summaries
+pub struct AiSummaryRepository;
+
+impl AiSummaryRepository {
+    /// Summaries of a paper, newest first
+    pub async fn find_by_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Vec<paper_ai_summary::Model>> {
+        paper_ai_summary::Entity::find()
+            .filter(paper_ai_summary::Column::PaperId.eq(paper_id))
+            .order_by_desc(paper_ai_summary::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper summaries: {}", e)))
+    }
+
+    /// Newest summary of a paper whose style starts with `style_prefix`
+    pub async fn find_latest_with_prefix(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        style_prefix: &str,
+    ) -> Result<Option<paper_ai_summary::Model>> {
+        paper_ai_summary::Entity::find()
+            .filter(paper_ai_summary::Column::PaperId.eq(paper_id))
+            .filter(paper_ai_summary::Column::Style.starts_with(style_prefix))
+            .order_by_desc(paper_ai_summary::Column::CreatedAt)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper summary: {}", e)))
+    }
+
+    /// Store a summary, replacing the paper's previous summary in that style
+    pub async fn save(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        style: &str,
+        summary: &str,
+        model: &str,
+    ) -> Result<paper_ai_summary::Model> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper_ai_summary::Entity::delete_many()
+            .filter(paper_ai_summary::Column::PaperId.eq(paper_id))
+            .filter(paper_ai_summary::Column::Style.eq(style))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to replace paper summary: {}", e)))?;
+
+        let saved = paper_ai_summary::ActiveModel {
+            paper_id: Set(paper_id),
+            style: Set(style.to_string()),
+            summary: Set(summary.to_string()),
+            model: Set(model.to_string()),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to save paper summary: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(saved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_save_replaces_same_style() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("A paper").insert(&db).await;
+
+        AiSummaryRepository::save(&db, paper.id, "tldr", "First.", "gpt-4o-mini")
+            .await
+            .unwrap();
+        AiSummaryRepository::save(&db, paper.id, "detailed", "- Method", "gpt-4o-mini")
+            .await
+            .unwrap();
+        AiSummaryRepository::save(&db, paper.id, "tldr", "Second.", "gpt-4o")
+            .await
+            .unwrap();
+
+        let summaries = AiSummaryRepository::find_by_paper(&db, paper.id)
+            .await
+            .unwrap();
+        assert_eq!(summaries.len(), 2);
+        let tldr = summaries.iter().find(|s| s.style == "tldr").unwrap();
+        assert_eq!(tldr.summary, "Second.");
+        assert_eq!(tldr.model, "gpt-4o");
+    }
+
+    #[tokio::test]
+    async fn test_find_latest_with_prefix() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("A paper").insert(&db).await;
+
+        AiSummaryRepository::save(&db, paper.id, "tldr", "TL;DR.", "m")
+            .await
+            .unwrap();
+        assert!(
+            AiSummaryRepository::find_latest_with_prefix(&db, paper.id, "abstract_")
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        AiSummaryRepository::save(&db, paper.id, "abstract_2", "Short.", "m")
+            .await
+            .unwrap();
+        let found = AiSummaryRepository::find_latest_with_prefix(&db, paper.id, "abstract_")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.summary, "Short.");
+    }
+}
@@ -0,0 +1,230 @@
+//! API key repository for SQLite using SeaORM
+//!
+//! Keys are stored as SHA-256 hashes; the plaintext is only returned once
+//! from `create` so the caller can show it to the user.
+
+use rand::RngCore;
+use sea_orm::{sea_query::Expr, *};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::database::entities::api_key;
+use crate::models::api_key::join_scopes;
+use crate::models::{ApiKey, ApiScope};
+use crate::sys::error::{AppError, Result};
+
+/// Prefix for generated keys so they are recognisable in scripts and logs
+const KEY_PREFIX: &str = "xb_";
+
+/// Number of leading characters kept in clear text for display
+const DISPLAY_PREFIX_LEN: usize = 10;
+
+/// Repository for API key operations
+pub struct ApiKeyRepository;
+
+impl ApiKeyRepository {
+    /// Generate a new random plaintext key
+    pub fn generate_key() -> String {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}{}", KEY_PREFIX, hex)
+    }
+
+    /// Hash a plaintext key for storage and lookup
+    pub fn hash_key(key: &str) -> String {
+        format!("{:x}", Sha256::digest(key.as_bytes()))
+    }
+
+    /// Create a new key with the given scopes.
+    /// Returns the stored record and the plaintext key (shown once).
+    pub async fn create(
+        db: &DatabaseConnection,
+        name: &str,
+        scopes: &[ApiScope],
+    ) -> Result<(ApiKey, String)> {
+        let key = Self::generate_key();
+        let record = Self::create_with_key(db, name, &key, scopes).await?;
+        Ok((record, key))
+    }
+
+    /// Store an existing plaintext key
+    pub async fn create_with_key(
+        db: &DatabaseConnection,
+        name: &str,
+        key: &str,
+        scopes: &[ApiScope],
+    ) -> Result<ApiKey> {
+        if scopes.is_empty() {
+            return Err(AppError::validation(
+                "scopes",
+                "At least one scope is required",
+            ));
+        }
+
+        let new_key = api_key::ActiveModel {
+            name: Set(name.to_string()),
+            key_prefix: Set(key.chars().take(DISPLAY_PREFIX_LEN).collect()),
+            key_hash: Set(Self::hash_key(key)),
+            scopes: Set(join_scopes(scopes)),
+            request_count: Set(0),
+            last_used_at: Set(None),
+            created_at: Set(chrono::Utc::now()),
+            revoked_at: Set(None),
+            ..Default::default()
+        };
+
+        let result = new_key
+            .insert(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to create API key: {}", e)))?;
+
+        info!("Created API key '{}' with scopes {:?}", name, scopes);
+        Ok(ApiKey::from(result))
+    }
+
+    /// List all keys, including revoked ones
+    pub async fn find_all(db: &DatabaseConnection) -> Result<Vec<ApiKey>> {
+        let keys = api_key::Entity::find()
+            .order_by_asc(api_key::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to list API keys: {}", e)))?;
+
+        Ok(keys.into_iter().map(ApiKey::from).collect())
+    }
+
+    /// Look up a non-revoked key by its plaintext value. Candidates are
+    /// found by their clear-text prefix and their hash is compared in
+    /// constant time.
+    pub async fn find_active_by_key(db: &DatabaseConnection, key: &str) -> Result<Option<ApiKey>> {
+        let prefix: String = key.chars().take(DISPLAY_PREFIX_LEN).collect();
+        let candidates = api_key::Entity::find()
+            .filter(api_key::Column::KeyPrefix.eq(prefix))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find API key: {}", e)))?;
+
+        let hash = Self::hash_key(key);
+        Ok(candidates
+            .into_iter()
+            .find(|candidate| hashes_match(&candidate.key_hash, &hash))
+            .map(ApiKey::from))
+    }
+
+    /// Revoke a key; revoked keys are kept for their usage history
+    pub async fn revoke(db: &DatabaseConnection, id: i64) -> Result<()> {
+        let key = api_key::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find API key: {}", e)))?
+            .ok_or_else(|| AppError::not_found("ApiKey", id.to_string()))?;
+
+        let mut key: api_key::ActiveModel = key.into();
+        key.revoked_at = Set(Some(chrono::Utc::now()));
+        key.update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to revoke API key: {}", e)))?;
+
+        info!("Revoked API key {}", id);
+        Ok(())
+    }
+
+    /// Add `requests` to the request counter and touch last_used_at
+    pub async fn record_usage(db: &DatabaseConnection, id: i64, requests: i64) -> Result<()> {
+        api_key::Entity::update_many()
+            .filter(api_key::Column::Id.eq(id))
+            .col_expr(
+                api_key::Column::RequestCount,
+                Expr::cust(format!("request_count + {}", requests)),
+            )
+            .col_expr(
+                api_key::Column::LastUsedAt,
+                Expr::value(Some(chrono::Utc::now())),
+            )
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to record API key usage: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Give the active key called `name` a fresh secret, creating the key if
+    /// there is none. The previous secret stops working; the new plaintext
+    /// is returned and only its hash is stored.
+    pub async fn rotate_key(
+        db: &DatabaseConnection,
+        name: &str,
+        scopes: &[ApiScope],
+    ) -> Result<String> {
+        let existing = api_key::Entity::find()
+            .filter(api_key::Column::Name.eq(name))
+            .filter(api_key::Column::RevokedAt.is_null())
+            .order_by_desc(api_key::Column::Id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find API key: {}", e)))?;
+
+        let Some(existing) = existing else {
+            let (_, key) = Self::create(db, name, scopes).await?;
+            return Ok(key);
+        };
+
+        let key = Self::generate_key();
+        let id = existing.id;
+        let mut existing: api_key::ActiveModel = existing.into();
+        existing.key_prefix = Set(key.chars().take(DISPLAY_PREFIX_LEN).collect());
+        existing.key_hash = Set(Self::hash_key(&key));
+        existing
+            .update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to rotate API key: {}", e)))?;
+
+        info!("Rotated API key {} ('{}')", id, name);
+        Ok(key)
+    }
+}
+
+/// Compare two hex digests without exiting at the first difference
+fn hashes_match(stored: &str, computed: &str) -> bool {
+    stored.len() == computed.len()
+        && stored
+            .bytes()
+            .zip(computed.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_db;
+
+    #[tokio::test]
+    async fn test_find_active_by_key() {
+        let db = test_db().await;
+        let (record, key) = ApiKeyRepository::create(&db, "cli", &[ApiScope::Read])
+            .await
+            .unwrap();
+
+        let found = ApiKeyRepository::find_active_by_key(&db, &key)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, record.id);
+
+        let mut wrong = key.clone();
+        wrong.push('0');
+        assert!(ApiKeyRepository::find_active_by_key(&db, &wrong)
+            .await
+            .unwrap()
+            .is_none());
+
+        ApiKeyRepository::revoke(&db, record.id).await.unwrap();
+        assert!(ApiKeyRepository::find_active_by_key(&db, &key)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,13 +1,24 @@
 //! Author repository for SQLite using SeaORM
 
 use sea_orm::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::info;
 
-use crate::database::entities::{author, paper_author};
-use crate::models::{Author, AuthorNameParser, AuthorNameParts, CreateAuthor};
+use crate::database::entities::{author, paper, paper_author};
+use crate::models::{
+    normalize_orcid, Author, AuthorDetails, AuthorNameParser, AuthorNameParts, AuthorWithOrder,
+    CreateAuthor,
+};
 use crate::sys::error::{AppError, Result};
 
+/// Position of an author on a paper, as edited in the paper detail view
+#[derive(Debug, Clone)]
+pub struct PaperAuthorEntry {
+    pub author_id: i64,
+    pub order: i32,
+    pub is_corresponding: bool,
+}
+
 /// Repository for Author operations
 pub struct AuthorRepository;
 
@@ -34,6 +45,239 @@ impl AuthorRepository {
         Ok(author.map(Author::from))
     }
 
+    /// Find the author with an ORCID iD in bare form
+    pub async fn find_by_orcid(db: &DatabaseConnection, orcid: &str) -> Result<Option<Author>> {
+        let author = author::Entity::find()
+            .filter(author::Column::Orcid.eq(orcid))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query author by ORCID: {}", e)))?;
+
+        Ok(author.map(Author::from))
+    }
+
+    /// Search authors by name using FTS5 with BM25 ranking.
+    /// Every word of `query` must match the start of a name token.
+    pub async fn search(db: &DatabaseConnection, query: &str, limit: u64) -> Result<Vec<Author>> {
+        let match_expr = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"*", word.replace('"', "")))
+            .filter(|term| term != "\"\"*")
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if match_expr.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let authors = author::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"
+                SELECT a.*
+                FROM author a
+                INNER JOIN author_fts ON author_fts.rowid = a.id
+                WHERE author_fts MATCH ?
+                ORDER BY bm25(author_fts)
+                LIMIT ?
+                "#,
+                [match_expr.into(), (limit as i64).into()],
+            ))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to search authors: {}", e)))?;
+
+        Ok(authors.into_iter().map(Author::from).collect())
+    }
+
+    /// Update an author's name, contact details and ORCID iD
+    pub async fn update(
+        db: &DatabaseConnection,
+        id: i64,
+        name: AuthorNameParts,
+        affiliation: Option<String>,
+        email: Option<String>,
+        orcid: Option<String>,
+    ) -> Result<Author> {
+        let existing = author::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get author: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Author", id.to_string()))?;
+
+        let mut active: author::ActiveModel = existing.into();
+        active.first_name = Set(name.first_name);
+        active.last_name = Set(name.last_name);
+        active.affiliation = Set(affiliation);
+        active.email = Set(email);
+        active.orcid = Set(orcid);
+
+        let result = active
+            .update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update author: {}", e)))?;
+
+        Ok(Author::from(result))
+    }
+
+    /// Change an author's name, keeping their other details
+    pub async fn rename(db: &DatabaseConnection, id: i64, name: AuthorNameParts) -> Result<Author> {
+        let existing = author::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get author: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Author", id.to_string()))?;
+
+        let mut active: author::ActiveModel = existing.into();
+        active.first_name = Set(name.first_name);
+        active.last_name = Set(name.last_name);
+
+        let result = active
+            .update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to rename author: {}", e)))?;
+
+        Ok(Author::from(result))
+    }
+
+    /// Number of non-deleted papers per author. Authors without papers are
+    /// absent from the map.
+    pub async fn count_papers(db: &DatabaseConnection) -> Result<HashMap<i64, u64>> {
+        let counts: Vec<(i64, i64)> = paper_author::Entity::find()
+            .select_only()
+            .column(paper_author::Column::AuthorId)
+            .column_as(paper_author::Column::PaperId.count(), "paper_count")
+            .join(JoinType::InnerJoin, paper_author::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .group_by(paper_author::Column::AuthorId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count author papers: {}", e)))?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(author_id, count)| (author_id, count as u64))
+            .collect())
+    }
+
+    /// Move all papers of `duplicate_ids` to `primary_id` and delete the
+    /// duplicates. On papers listing both authors the primary keeps its
+    /// position and the duplicate's entry is dropped. Affiliation, email and
+    /// ORCID iD missing on the primary are taken from the first duplicate
+    /// that has one.
+    pub async fn merge(
+        db: &DatabaseConnection,
+        primary_id: i64,
+        duplicate_ids: &[i64],
+    ) -> Result<Author> {
+        let primary = author::Entity::find_by_id(primary_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get author: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Author", primary_id.to_string()))?;
+        let mut duplicates = Vec::with_capacity(duplicate_ids.len());
+        for &id in duplicate_ids {
+            match Self::find_by_id(db, id).await? {
+                Some(author) => duplicates.push(author),
+                None => return Err(AppError::not_found("Author", id.to_string())),
+            }
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        // The primary's link on each paper, which duplicates on the same
+        // paper fold into
+        let mut linked: HashMap<i64, paper_author::Model> = paper_author::Entity::find()
+            .filter(paper_author::Column::AuthorId.eq(primary_id))
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query paper-author relations: {}", e))
+            })?
+            .into_iter()
+            .map(|relation| (relation.paper_id, relation))
+            .collect();
+
+        let relations = paper_author::Entity::find()
+            .filter(paper_author::Column::AuthorId.is_in(duplicate_ids.to_vec()))
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query paper-author relations: {}", e))
+            })?;
+
+        for relation in relations {
+            if let Some(kept) = linked.get_mut(&relation.paper_id) {
+                paper_author::Entity::delete_by_id(relation.id)
+                    .exec(&txn)
+                    .await
+                    .map_err(|e| {
+                        AppError::generic(format!("Failed to remove duplicate author link: {}", e))
+                    })?;
+                // Keep the corresponding-author flag set on either link
+                if relation.is_corresponding != 0 && kept.is_corresponding == 0 {
+                    let mut active: paper_author::ActiveModel = kept.clone().into();
+                    active.is_corresponding = Set(relation.is_corresponding);
+                    *kept = active.update(&txn).await.map_err(|e| {
+                        AppError::generic(format!("Failed to update author link: {}", e))
+                    })?;
+                }
+            } else {
+                let paper_id = relation.paper_id;
+                let mut relation: paper_author::ActiveModel = relation.into();
+                relation.author_id = Set(primary_id);
+                let moved = relation
+                    .update(&txn)
+                    .await
+                    .map_err(|e| AppError::generic(format!("Failed to move author link: {}", e)))?;
+                linked.insert(paper_id, moved);
+            }
+        }
+
+        author::Entity::delete_many()
+            .filter(author::Column::Id.is_in(duplicate_ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete merged authors: {}", e)))?;
+
+        let affiliation = primary
+            .affiliation
+            .clone()
+            .or_else(|| duplicates.iter().find_map(|a| a.affiliation.clone()));
+        let email = primary
+            .email
+            .clone()
+            .or_else(|| duplicates.iter().find_map(|a| a.email.clone()));
+        let orcid = primary
+            .orcid
+            .clone()
+            .or_else(|| duplicates.iter().find_map(|a| a.orcid.clone()));
+        let mut active: author::ActiveModel = primary.into();
+        active.affiliation = Set(affiliation);
+        active.email = Set(email);
+        active.orcid = Set(orcid);
+        let primary = active
+            .update(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update author: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        let primary = Author::from(primary);
+        info!(
+            "Merged {} authors into '{}'",
+            duplicate_ids.len(),
+            primary.full_name()
+        );
+        Ok(primary)
+    }
+
     /// Create a new author
     pub async fn create(db: &DatabaseConnection, create: CreateAuthor) -> Result<Author> {
         let now = chrono::Utc::now();
@@ -42,6 +286,7 @@ impl AuthorRepository {
             last_name: Set(create.last_name),
             affiliation: Set(create.affiliation),
             email: Set(create.email),
+            orcid: Set(create.orcid),
             created_at: Set(now),
             ..Default::default()
         };
@@ -54,15 +299,15 @@ impl AuthorRepository {
         Ok(Author::from(result))
     }
 
-    /// Create or find existing author by full name and email
+    /// Create or find existing author by full name and details
     /// This method parses the full name and is used for sources that only provide full names (e.g., arXiv)
     pub async fn create_or_find(
         db: &DatabaseConnection,
         full_name: &str,
-        email: Option<&str>,
+        details: &AuthorDetails,
     ) -> Result<Author> {
         let name_parts = AuthorNameParser::parse(full_name);
-        Self::create_or_find_by_parts(db, &name_parts, email).await
+        Self::create_or_find_by_parts(db, &name_parts, details).await
     }
 
     /// Create or find existing author by structured name parts
@@ -71,23 +316,45 @@ impl AuthorRepository {
         db: &DatabaseConnection,
         given_name: Option<&str>,
         family_name: Option<&str>,
-        email: Option<&str>,
+        details: &AuthorDetails,
     ) -> Result<Author> {
         let name_parts = AuthorNameParser::from_parts(given_name, family_name);
-        Self::create_or_find_by_parts(db, &name_parts, email).await
+        Self::create_or_find_by_parts(db, &name_parts, details).await
     }
 
-    /// Internal method to create or find by name parts
+    /// Internal method to create or find by name parts.
+    ///
+    /// A valid ORCID iD identifies the author regardless of the name.
+    /// Otherwise the name and email must match, and an author with a
+    /// different ORCID iD is never matched.
     async fn create_or_find_by_parts(
         db: &DatabaseConnection,
         name_parts: &AuthorNameParts,
-        email: Option<&str>,
+        details: &AuthorDetails,
     ) -> Result<Author> {
         // Skip if first_name is empty
         if name_parts.first_name.is_empty() {
             return Err(AppError::generic("Author first_name cannot be empty"));
         }
 
+        let orcid = details.orcid.as_deref().and_then(normalize_orcid);
+        let affiliation = details
+            .affiliation
+            .as_deref()
+            .map(str::trim)
+            .filter(|a| !a.is_empty());
+
+        if let Some(orcid) = &orcid {
+            let existing = author::Entity::find()
+                .filter(author::Column::Orcid.eq(orcid))
+                .one(db)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to query author: {}", e)))?;
+            if let Some(existing) = existing {
+                return Self::apply_details(db, existing, affiliation, None).await;
+            }
+        }
+
         // Build query based on whether last_name and email exist
         let mut query =
             author::Entity::find().filter(author::Column::FirstName.eq(&name_parts.first_name));
@@ -103,7 +370,7 @@ impl AuthorRepository {
         }
 
         // Handle email
-        match email {
+        match details.email.as_deref() {
             Some(email_val) => {
                 query = query.filter(author::Column::Email.eq(email_val));
             }
@@ -112,13 +379,18 @@ impl AuthorRepository {
             }
         }
 
+        // Same name with another ORCID iD is another person
+        if orcid.is_some() {
+            query = query.filter(author::Column::Orcid.is_null());
+        }
+
         let existing = query
             .one(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to query author: {}", e)))?;
 
-        if let Some(author) = existing {
-            return Ok(Author::from(author));
+        if let Some(existing) = existing {
+            return Self::apply_details(db, existing, affiliation, orcid).await;
         }
 
         // Create new author
@@ -127,13 +399,52 @@ impl AuthorRepository {
             CreateAuthor {
                 first_name: name_parts.first_name.clone(),
                 last_name: name_parts.last_name.clone(),
-                affiliation: None,
-                email: email.map(|s| s.to_string()),
+                affiliation: affiliation.map(str::to_string),
+                email: details.email.clone(),
+                orcid,
             },
         )
         .await
     }
 
+    /// Store details an import knows about a matched author. A different
+    /// affiliation replaces the stored one, as the latest import is the most
+    /// likely to be current; the change is logged.
+    async fn apply_details(
+        db: &DatabaseConnection,
+        existing: author::Model,
+        affiliation: Option<&str>,
+        orcid: Option<String>,
+    ) -> Result<Author> {
+        let new_affiliation = affiliation.filter(|a| existing.affiliation.as_deref() != Some(*a));
+        let new_orcid = orcid.filter(|_| existing.orcid.is_none());
+        if new_affiliation.is_none() && new_orcid.is_none() {
+            return Ok(Author::from(existing));
+        }
+
+        if let (Some(old), Some(new)) = (existing.affiliation.as_deref(), new_affiliation) {
+            info!(
+                "Affiliation of author {} changed from '{}' to '{}'",
+                existing.id, old, new
+            );
+        }
+
+        let mut active: author::ActiveModel = existing.into();
+        if let Some(affiliation) = new_affiliation {
+            active.affiliation = Set(Some(affiliation.to_string()));
+        }
+        if new_orcid.is_some() {
+            active.orcid = Set(new_orcid);
+        }
+
+        let result = active
+            .update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update author: {}", e)))?;
+
+        Ok(Author::from(result))
+    }
+
     /// Get authors for a paper, ordered by author_order
     pub async fn get_paper_authors(db: &DatabaseConnection, paper_id: i64) -> Result<Vec<Author>> {
         // First get paper_author relations
@@ -173,6 +484,118 @@ impl AuthorRepository {
         Ok(result)
     }
 
+    /// Get authors for a paper with their position and corresponding flag,
+    /// ordered by author_order
+    pub async fn get_paper_authors_with_order(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Vec<AuthorWithOrder>> {
+        let relations = paper_author::Entity::find()
+            .filter(paper_author::Column::PaperId.eq(paper_id))
+            .order_by_asc(paper_author::Column::AuthorOrder)
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to get paper-author relations: {}", e))
+            })?;
+
+        let author_ids: Vec<i64> = relations.iter().map(|r| r.author_id).collect();
+        let mut authors: HashMap<i64, Author> = author::Entity::find()
+            .filter(author::Column::Id.is_in(author_ids))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper authors: {}", e)))?
+            .into_iter()
+            .map(|a| (a.id, Author::from(a)))
+            .collect();
+
+        Ok(relations
+            .into_iter()
+            .filter_map(|relation| {
+                let author = authors.remove(&relation.author_id)?;
+                Some(AuthorWithOrder {
+                    id: author.id,
+                    name: author.full_name(),
+                    affiliation: author.affiliation,
+                    email: author.email,
+                    author_order: relation.author_order,
+                    is_corresponding: relation.is_corresponding != 0,
+                })
+            })
+            .collect())
+    }
+
+    /// Replace the author list of a paper in one transaction. Entries are
+    /// stored in `order` order and renumbered from 0. Authors dropped from
+    /// the list keep their author rows.
+    pub async fn set_paper_authors(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        entries: &[PaperAuthorEntry],
+    ) -> Result<()> {
+        let mut seen = HashSet::new();
+        if let Some(entry) = entries.iter().find(|e| !seen.insert(e.author_id)) {
+            return Err(AppError::validation(
+                "authors",
+                format!("Author {} is listed more than once", entry.author_id),
+            ));
+        }
+
+        let ids: Vec<i64> = entries.iter().map(|e| e.author_id).collect();
+        let found: HashSet<i64> = author::Entity::find()
+            .select_only()
+            .column(author::Column::Id)
+            .filter(author::Column::Id.is_in(ids.clone()))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query authors: {}", e)))?
+            .into_iter()
+            .collect();
+        if let Some(missing) = ids.iter().find(|id| !found.contains(id)) {
+            return Err(AppError::not_found("Author", missing.to_string()));
+        }
+
+        let mut sorted: Vec<&PaperAuthorEntry> = entries.iter().collect();
+        sorted.sort_by_key(|e| e.order);
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper_author::Entity::delete_many()
+            .filter(paper_author::Column::PaperId.eq(paper_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove paper authors: {}", e)))?;
+
+        if !sorted.is_empty() {
+            let relations =
+                sorted
+                    .into_iter()
+                    .enumerate()
+                    .map(|(order, entry)| paper_author::ActiveModel {
+                        paper_id: Set(paper_id),
+                        author_id: Set(entry.author_id),
+                        author_order: Set(order as i32),
+                        is_corresponding: Set(entry.is_corresponding as i32),
+                        ..Default::default()
+                    });
+            paper_author::Entity::insert_many(relations)
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to add paper authors: {}", e)))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!("Set {} authors on paper {}", entries.len(), paper_id);
+        Ok(())
+    }
+
     /// Get authors for multiple papers (batch query for N+1 optimization)
     /// Returns a HashMap mapping paper_id to its authors (ordered by author_order)
     pub async fn get_paper_authors_batch(
@@ -223,3 +646,210 @@ impl AuthorRepository {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connection::init_memory_connection;
+    use crate::testing::PaperFixture;
+
+    #[tokio::test]
+    async fn test_search_matches_prefixes_and_follows_updates() {
+        let db = init_memory_connection().await;
+        let john = AuthorRepository::create_or_find(&db, "John Smith", &AuthorDetails::default())
+            .await
+            .unwrap();
+        AuthorRepository::create_or_find(&db, "Jane Doe", &AuthorDetails::default())
+            .await
+            .unwrap();
+
+        let found = AuthorRepository::search(&db, "joh smi", 10).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, john.id);
+
+        let renamed = AuthorNameParser::parse("Johann Schmidt");
+        AuthorRepository::update(&db, john.id, renamed, None, None, None)
+            .await
+            .unwrap();
+
+        let old_name = AuthorRepository::search(&db, "smith", 10).await.unwrap();
+        let new_name = AuthorRepository::search(&db, "schmidt", 10).await.unwrap();
+        let quote_only = AuthorRepository::search(&db, "\"", 10).await.unwrap();
+        assert!(old_name.is_empty());
+        assert_eq!(new_name.len(), 1);
+        assert!(quote_only.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_papers_without_duplicates() {
+        let db = init_memory_connection().await;
+        let both = PaperFixture::new("Both")
+            .with_authors(&["John Smith", "J. Smith"])
+            .insert(&db)
+            .await;
+        PaperFixture::new("Initials only")
+            .with_authors(&["J. Smith"])
+            .insert(&db)
+            .await;
+
+        let john = AuthorRepository::create_or_find(&db, "John Smith", &AuthorDetails::default())
+            .await
+            .unwrap();
+        let initials = AuthorRepository::create_or_find(&db, "J. Smith", &AuthorDetails::default())
+            .await
+            .unwrap();
+        AuthorRepository::update(
+            &db,
+            initials.id,
+            AuthorNameParser::parse("J. Smith"),
+            Some("MIT".to_string()),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Only the duplicate is marked corresponding on the shared paper
+        paper_author::Entity::update_many()
+            .col_expr(
+                paper_author::Column::IsCorresponding,
+                sea_orm::sea_query::Expr::value(1),
+            )
+            .filter(paper_author::Column::PaperId.eq(both.id))
+            .filter(paper_author::Column::AuthorId.eq(initials.id))
+            .exec(&db)
+            .await
+            .unwrap();
+
+        let merged = AuthorRepository::merge(&db, john.id, &[initials.id])
+            .await
+            .unwrap();
+        assert_eq!(merged.affiliation.as_deref(), Some("MIT"));
+        assert!(AuthorRepository::find_by_id(&db, initials.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        let counts = AuthorRepository::count_papers(&db).await.unwrap();
+        assert_eq!(counts.get(&john.id), Some(&2));
+        let authors = AuthorRepository::get_paper_authors(&db, both.id)
+            .await
+            .unwrap();
+        assert_eq!(authors.len(), 1);
+        assert_eq!(authors[0].id, john.id);
+        let link = paper_author::Entity::find()
+            .filter(paper_author::Column::PaperId.eq(both.id))
+            .one(&db)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.is_corresponding, 1);
+
+        let renamed = AuthorRepository::rename(&db, john.id, AuthorNameParser::parse("Jon Smith"))
+            .await
+            .unwrap();
+        assert_eq!(renamed.full_name(), "Jon Smith");
+        assert_eq!(renamed.affiliation.as_deref(), Some("MIT"));
+    }
+
+    #[tokio::test]
+    async fn test_set_paper_authors_reorders_and_keeps_removed_authors() {
+        let db = init_memory_connection().await;
+        let paper = PaperFixture::new("Ordered")
+            .with_authors(&["Ada Lovelace", "Charles Babbage", "Mary Somerville"])
+            .insert(&db)
+            .await;
+        let authors = AuthorRepository::get_paper_authors(&db, paper.id)
+            .await
+            .unwrap();
+        let (ada, charles, mary) = (authors[0].id, authors[1].id, authors[2].id);
+
+        let entry = |author_id, order, is_corresponding| PaperAuthorEntry {
+            author_id,
+            order,
+            is_corresponding,
+        };
+        AuthorRepository::set_paper_authors(
+            &db,
+            paper.id,
+            &[entry(ada, 5, false), entry(mary, 1, true)],
+        )
+        .await
+        .unwrap();
+
+        let ordered = AuthorRepository::get_paper_authors_with_order(&db, paper.id)
+            .await
+            .unwrap();
+        let summary: Vec<(i64, i32, bool)> = ordered
+            .iter()
+            .map(|a| (a.id, a.author_order, a.is_corresponding))
+            .collect();
+        assert_eq!(summary, vec![(mary, 0, true), (ada, 1, false)]);
+        assert!(AuthorRepository::find_by_id(&db, charles)
+            .await
+            .unwrap()
+            .is_some());
+
+        let duplicate = AuthorRepository::set_paper_authors(
+            &db,
+            paper.id,
+            &[entry(ada, 0, false), entry(ada, 1, false)],
+        )
+        .await;
+        assert!(duplicate.is_err());
+        let unknown =
+            AuthorRepository::set_paper_authors(&db, paper.id, &[entry(-1, 0, false)]).await;
+        assert!(unknown.is_err());
+        assert_eq!(
+            AuthorRepository::get_paper_authors(&db, paper.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_or_find_matches_orcid_and_updates_affiliation() {
+        let db = init_memory_connection().await;
+        let details = |affiliation: &str, orcid: Option<&str>| AuthorDetails {
+            affiliation: Some(affiliation.to_string()),
+            orcid: orcid.map(str::to_string),
+            ..Default::default()
+        };
+
+        let first = AuthorRepository::create_or_find(
+            &db,
+            "Josiah Carberry",
+            &details(
+                "Brown University",
+                Some("https://orcid.org/0000-0002-1825-0097"),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(first.orcid.as_deref(), Some("0000-0002-1825-0097"));
+
+        // Another spelling with the same ORCID iD is the same author, and the
+        // newer affiliation wins
+        let second = AuthorRepository::create_or_find(
+            &db,
+            "J. Carberry",
+            &details("Wesleyan University", Some("0000-0002-1825-0097")),
+        )
+        .await
+        .unwrap();
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.affiliation.as_deref(), Some("Wesleyan University"));
+
+        // The same name with a different ORCID iD is someone else
+        let other = AuthorRepository::create_or_find(
+            &db,
+            "Josiah Carberry",
+            &details("Elsewhere", Some("0000-0002-1694-233X")),
+        )
+        .await
+        .unwrap();
+        assert_ne!(other.id, first.id);
+    }
+}
@@ -1,10 +1,15 @@
 //! Category repository for SQLite using SeaORM
 
+use std::collections::{HashMap, HashSet};
+
 use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QueryOrder, Set, DatabaseConnection, sea_query::Expr};
+use sea_orm::{JoinType, QuerySelect, RelationTrait};
 use tracing::info;
 
-use crate::database::entities::{category, paper_category};
-use crate::models::{Category, CategoryNode, CreateCategory, UpdateCategory};
+use crate::database::entities::{category, paper, paper_category};
+use crate::models::{
+    Category, CategoryNode, CategoryNodeWithCount, CreateCategory, UpdateCategory,
+};
 use crate::sys::error::{AppError, Result};
 
 /// Repository for Category operations
@@ -33,6 +38,28 @@ impl CategoryRepository {
         Ok(cat.map(Category::from))
     }
 
+    /// Find categories by ID, in the order of `ids`; unknown IDs are skipped
+    pub async fn find_by_ids(db: &DatabaseConnection, ids: &[i64]) -> Result<Vec<Category>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut categories: HashMap<i64, category::Model> = category::Entity::find()
+            .filter(category::Column::Id.is_in(ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get categories: {}", e)))?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| categories.remove(id))
+            .map(Category::from)
+            .collect())
+    }
+
     /// Create a new category
     pub async fn create(db: &DatabaseConnection, create: CreateCategory) -> Result<Category> {
         let now = chrono::Utc::now();
@@ -147,6 +174,35 @@ impl CategoryRepository {
         Ok(Self::build_tree(categories))
     }
 
+    /// Load categories as tree structure with paper counts. Papers in the
+    /// trash are not counted.
+    pub async fn load_tree_with_counts(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<CategoryNodeWithCount>> {
+        let tree = Self::load_tree(db).await?;
+
+        let edges: Vec<(i64, i64)> = paper_category::Entity::find()
+            .select_only()
+            .column(paper_category::Column::CategoryId)
+            .column(paper_category::Column::PaperId)
+            .join(JoinType::InnerJoin, paper_category::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to load paper categories: {}", e)))?;
+
+        let mut papers: HashMap<i64, HashSet<i64>> = HashMap::new();
+        for (category_id, paper_id) in edges {
+            papers.entry(category_id).or_default().insert(paper_id);
+        }
+
+        Ok(tree
+            .into_iter()
+            .map(|node| with_counts(node, &papers).0)
+            .collect())
+    }
+
     /// Reorder categories
     pub async fn reorder(db: &DatabaseConnection, orders: Vec<(i64, i32)>) -> Result<()> {
         for (id, sort_order) in orders {
@@ -227,6 +283,38 @@ fn build_tree_recursive(nodes: &[CategoryNode], parent_id: Option<i64>) -> Vec<C
     result
 }
 
+/// Add paper counts to `node` and its descendants. Also returns the papers
+/// in the subtree, so a paper filed under several of its categories is only
+/// counted once.
+fn with_counts(
+    node: CategoryNode,
+    papers: &HashMap<i64, HashSet<i64>>,
+) -> (CategoryNodeWithCount, HashSet<i64>) {
+    let mut subtree = papers.get(&node.id).cloned().unwrap_or_default();
+    let direct_count = subtree.len() as u64;
+
+    let children = node
+        .children
+        .into_iter()
+        .map(|child| {
+            let (child, child_papers) = with_counts(child, papers);
+            subtree.extend(child_papers);
+            child
+        })
+        .collect();
+
+    let counted = CategoryNodeWithCount {
+        id: node.id,
+        name: node.name,
+        parent_id: node.parent_id,
+        sort_order: node.sort_order,
+        direct_count,
+        recursive_count: subtree.len() as u64,
+        children,
+    };
+    (counted, subtree)
+}
+
 /// Tree node data for frontend
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct TreeNodeData {
@@ -235,3 +323,94 @@ pub struct TreeNodeData {
     #[serde(default)]
     pub children: Vec<TreeNodeData>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PaperRepository;
+    use crate::testing::{category, test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_delete_moves_children_to_root_and_unlinks_papers() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Filed")
+            .with_category("Physics")
+            .insert(&db)
+            .await;
+        let physics = category(&db, "Physics", None).await;
+        let optics = category(&db, "Optics", Some(physics.id)).await;
+
+        CategoryRepository::delete(&db, physics.id).await.unwrap();
+
+        let optics = CategoryRepository::find_by_id(&db, optics.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(optics.parent_id, None);
+        assert_eq!(
+            PaperRepository::get_category_ids(&db, paper.id)
+                .await
+                .unwrap(),
+            Vec::<i64>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_ids_keeps_order() {
+        let db = test_db().await;
+        let physics = category(&db, "Physics", None).await;
+        let optics = category(&db, "Optics", Some(physics.id)).await;
+
+        let found = CategoryRepository::find_by_ids(&db, &[optics.id, -1, physics.id])
+            .await
+            .unwrap();
+        assert_eq!(
+            found.iter().map(|c| c.id).collect::<Vec<_>>(),
+            vec![optics.id, physics.id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tree_counts_include_descendants() {
+        let db = test_db().await;
+        let physics = category(&db, "Physics", None).await;
+        let optics = category(&db, "Optics", Some(physics.id)).await;
+        category(&db, "Lasers", Some(optics.id)).await;
+        PaperFixture::new("Mechanics")
+            .with_category("Physics")
+            .insert(&db)
+            .await;
+        for title in ["Lenses", "Mirrors"] {
+            let paper = PaperFixture::new(title).insert(&db).await;
+            PaperRepository::set_category(&db, paper.id, Some(optics.id))
+                .await
+                .unwrap();
+        }
+        let trashed = PaperFixture::new("Trashed").deleted().insert(&db).await;
+        PaperRepository::set_category(&db, trashed.id, Some(optics.id))
+            .await
+            .unwrap();
+
+        let tree = CategoryRepository::load_tree_with_counts(&db)
+            .await
+            .unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].direct_count, tree[0].recursive_count), (1, 3));
+        let optics = &tree[0].children[0];
+        assert_eq!((optics.direct_count, optics.recursive_count), (2, 2));
+        let lasers = &optics.children[0];
+        assert_eq!((lasers.direct_count, lasers.recursive_count), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_move_to_parent_rejects_self() {
+        let db = test_db().await;
+        let physics = category(&db, "Physics", None).await;
+
+        assert!(
+            CategoryRepository::move_to_parent(&db, physics.id, Some(physics.id))
+                .await
+                .is_err()
+        );
+    }
+}
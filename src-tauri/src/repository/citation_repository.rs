@@ -0,0 +1,274 @@
+//! Paper citation repository for SQLite using SeaORM
+
+use std::collections::HashSet;
+
+use sea_orm::*;
+
+use crate::database::entities::{paper, paper_citation, paper_reference};
+use crate::models::Paper;
+use crate::repository::PaperRepository;
+use crate::sys::error::{AppError, Result};
+
+/// Deepest traversal `citation_graph` will run, in hops from the root
+pub const MAX_CITATION_DEPTH: u32 = 3;
+
+/// Repository for citation edges between papers in the library
+pub struct CitationRepository;
+
+impl CitationRepository {
+    /// Record that `citing_id` cites `cited_id`. Linking a pair that is
+    /// already linked returns the existing edge.
+    pub async fn link(
+        db: &DatabaseConnection,
+        citing_id: i64,
+        cited_id: i64,
+    ) -> Result<paper_citation::Model> {
+        if citing_id == cited_id {
+            return Err(AppError::validation(
+                "cited_paper_id",
+                "A paper cannot cite itself",
+            ));
+        }
+
+        if let Some(existing) = paper_citation::Entity::find()
+            .filter(paper_citation::Column::CitingPaperId.eq(citing_id))
+            .filter(paper_citation::Column::CitedPaperId.eq(cited_id))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get citation: {}", e)))?
+        {
+            return Ok(existing);
+        }
+
+        paper_citation::ActiveModel {
+            citing_paper_id: Set(citing_id),
+            cited_paper_id: Set(cited_id),
+            created_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to create citation: {}", e)))
+    }
+
+    /// Remove the edge from `citing_id` to `cited_id`; returns whether one existed
+    pub async fn unlink(db: &DatabaseConnection, citing_id: i64, cited_id: i64) -> Result<bool> {
+        let result = paper_citation::Entity::delete_many()
+            .filter(paper_citation::Column::CitingPaperId.eq(citing_id))
+            .filter(paper_citation::Column::CitedPaperId.eq(cited_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete citation: {}", e)))?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Add a citation link for every extracted reference whose DOI matches a
+    /// paper in the library, e.g. one imported after the references were
+    /// extracted. Returns the number of links added.
+    pub async fn link_extracted_references(db: &DatabaseConnection) -> Result<usize> {
+        let references: Vec<(i64, String)> = paper_reference::Entity::find()
+            .select_only()
+            .column(paper_reference::Column::PaperId)
+            .column(paper_reference::Column::Doi)
+            .filter(paper_reference::Column::Doi.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get reference DOIs: {}", e)))?;
+        if references.is_empty() {
+            return Ok(0);
+        }
+
+        let dois: Vec<String> = references.iter().map(|(_, doi)| doi.clone()).collect();
+        let library = PaperRepository::find_ids_by_dois(db, &dois).await?;
+
+        let mut linked: HashSet<(i64, i64)> = paper_citation::Entity::find()
+            .select_only()
+            .column(paper_citation::Column::CitingPaperId)
+            .column(paper_citation::Column::CitedPaperId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get citations: {}", e)))?
+            .into_iter()
+            .collect();
+
+        let now = chrono::Utc::now();
+        let mut missing = Vec::new();
+        for (citing_id, doi) in references {
+            let Some(&cited_id) = library.get(&doi.trim().to_lowercase()) else {
+                continue;
+            };
+            if citing_id != cited_id && linked.insert((citing_id, cited_id)) {
+                missing.push(paper_citation::ActiveModel {
+                    citing_paper_id: Set(citing_id),
+                    cited_paper_id: Set(cited_id),
+                    created_at: Set(now),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let added = missing.len();
+        if added > 0 {
+            paper_citation::Entity::insert_many(missing)
+                .exec(db)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to create citations: {}", e)))?;
+        }
+        Ok(added)
+    }
+
+    /// Papers within `depth` hops of `root_id`, following edges in either
+    /// direction, and the edges between them. Trashed papers are neither
+    /// returned nor traversed.
+    pub async fn citation_graph(
+        db: &DatabaseConnection,
+        root_id: i64,
+        depth: u32,
+    ) -> Result<(Vec<Paper>, Vec<paper_citation::Model>)> {
+        let depth = depth.min(MAX_CITATION_DEPTH);
+
+        let papers = paper::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"
+                WITH RECURSIVE reach(id, depth) AS (
+                    SELECT id, 0 FROM paper WHERE id = ? AND deleted_at IS NULL
+                    UNION
+                    SELECT p.id, r.depth + 1
+                    FROM reach r
+                    INNER JOIN paper_citation c
+                        ON r.id IN (c.citing_paper_id, c.cited_paper_id)
+                    INNER JOIN paper p
+                        ON p.id = CASE WHEN c.citing_paper_id = r.id
+                            THEN c.cited_paper_id ELSE c.citing_paper_id END
+                    WHERE r.depth < ? AND p.deleted_at IS NULL
+                )
+                SELECT p.*
+                FROM paper p
+                WHERE p.id IN (SELECT id FROM reach)
+                ORDER BY p.id
+                "#,
+                [root_id.into(), (depth as i64).into()],
+            ))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to traverse citations: {}", e)))?;
+
+        let ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+        let edges = paper_citation::Entity::find()
+            .filter(paper_citation::Column::CitingPaperId.is_in(ids.clone()))
+            .filter(paper_citation::Column::CitedPaperId.is_in(ids))
+            .order_by_asc(paper_citation::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get citations: {}", e)))?;
+
+        Ok((papers.into_iter().map(Paper::from).collect(), edges))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::papers::importer::grobid::GrobidReference;
+    use crate::repository::ReferenceRepository;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_link_guards() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+
+        assert!(CitationRepository::link(&db, a.id, a.id).await.is_err());
+
+        let first = CitationRepository::link(&db, a.id, b.id).await.unwrap();
+        let again = CitationRepository::link(&db, a.id, b.id).await.unwrap();
+        assert_eq!(first.id, again.id);
+
+        assert!(CitationRepository::unlink(&db, a.id, b.id).await.unwrap());
+        assert!(!CitationRepository::unlink(&db, a.id, b.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_citation_graph_depth() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+        let c = PaperFixture::new("C").insert(&db).await;
+        let d = PaperFixture::new("D").insert(&db).await;
+        // a -> b, c -> b, c -> d
+        CitationRepository::link(&db, a.id, b.id).await.unwrap();
+        CitationRepository::link(&db, c.id, b.id).await.unwrap();
+        CitationRepository::link(&db, c.id, d.id).await.unwrap();
+
+        let (nodes, edges) = CitationRepository::citation_graph(&db, a.id, 2)
+            .await
+            .unwrap();
+        let ids: Vec<i64> = nodes.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![a.id, b.id, c.id]);
+        assert_eq!(edges.len(), 2);
+
+        let (nodes, edges) = CitationRepository::citation_graph(&db, a.id, 3)
+            .await
+            .unwrap();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(edges.len(), 3);
+
+        PaperRepository::delete(&db, c.id).await.unwrap();
+        let (nodes, edges) = CitationRepository::citation_graph(&db, a.id, 3)
+            .await
+            .unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(edges.len(), 1);
+        let remaining = paper_citation::Entity::find()
+            .count(db.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_link_extracted_references() {
+        let db = test_db().await;
+        let citing = PaperFixture::new("Citing").insert(&db).await;
+        let cited = PaperFixture::new("Cited")
+            .with_doi("10.1000/cited")
+            .insert(&db)
+            .await;
+
+        let reference = |doi: &str| GrobidReference {
+            title: Some("Entry".to_string()),
+            doi: Some(doi.to_string()),
+            ..Default::default()
+        };
+        ReferenceRepository::replace_for_paper(
+            &db,
+            citing.id,
+            &[reference("10.1000/CITED "), reference("10.1000/elsewhere")],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            CitationRepository::link_extracted_references(&db)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            CitationRepository::link_extracted_references(&db)
+                .await
+                .unwrap(),
+            0
+        );
+        let (nodes, _) = CitationRepository::citation_graph(&db, citing.id, 1)
+            .await
+            .unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert!(nodes.iter().any(|p| p.id == cited.id));
+    }
+}
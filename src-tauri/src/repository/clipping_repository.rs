@@ -1,10 +1,15 @@
 //! Clipping repository for SQLite using SeaORM
 
+use std::collections::HashMap;
+
 use sea_orm::*;
 use tracing::info;
 
-use crate::database::entities::{clipping, comment};
-use crate::models::{Clipping, Comment, CreateClipping, UpdateClipping};
+use crate::database::entities::{clip_label, clipping, comment};
+use crate::models::{
+    ClipFilter, ClipSortField, Clipping, Comment, CreateClipping, SortDirection, UpdateClipping,
+};
+use crate::repository::{LabelRepository, SearchRepository};
 use crate::sys::error::{AppError, Result};
 
 /// Repository for Clipping operations
@@ -53,7 +58,9 @@ impl ClippingRepository {
             .await
             .map_err(|e| AppError::generic(format!("Failed to create clipping: {}", e)))?;
 
-        Ok(Clipping::from(result))
+        let clipping = Clipping::from(result);
+        SearchRepository::index_clip(db, &clipping).await?;
+        Ok(clipping)
     }
 
     /// Get all clippings (alias for find_all)
@@ -71,15 +78,128 @@ impl ClippingRepository {
 
         info!("Found {} clippings", clippings.len());
 
-        // Load comments for each clipping
-        let mut result = Vec::new();
-        for c in clippings {
-            let mut clipping = Clipping::from(c);
-            clipping.comments = Self::find_comments(db, clipping.id).await?;
-            result.push(clipping);
+        Self::with_comments(db, clippings).await
+    }
+
+    /// Get clippings, newest first, optionally only those with a label or
+    /// read status
+    pub async fn find_filtered(
+        db: &DatabaseConnection,
+        label_id: Option<i64>,
+        read_status: Option<i32>,
+    ) -> Result<Vec<Clipping>> {
+        let mut query = clipping::Entity::find().order_by_desc(clipping::Column::CreatedAt);
+
+        if let Some(label_id) = label_id {
+            let labelled = clip_label::Entity::find()
+                .select_only()
+                .column(clip_label::Column::ClippingId)
+                .filter(clip_label::Column::LabelId.eq(label_id))
+                .into_query();
+            query = query.filter(clipping::Column::Id.in_subquery(labelled));
+        }
+        if let Some(read_status) = read_status {
+            query = query.filter(clipping::Column::ReadStatus.eq(read_status));
         }
 
-        Ok(result)
+        let clippings = query
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clippings: {}", e)))?;
+
+        Self::with_comments(db, clippings).await
+    }
+
+    /// One page of clips matching `filter`, in the given order, and the
+    /// number of clips matching in total
+    pub async fn find_page(
+        db: &DatabaseConnection,
+        filter: &ClipFilter,
+        sort_by: ClipSortField,
+        sort_dir: SortDirection,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Clipping>, u64)> {
+        let mut query = clipping::Entity::find();
+
+        if let Some(read_status) = filter.read_status {
+            query = query.filter(clipping::Column::ReadStatus.eq(read_status));
+        }
+        if let Some(domain) = &filter.source_domain {
+            query = query.filter(clipping::Column::SourceDomain.eq(domain.as_str()));
+        }
+        for label_id in &filter.label_ids {
+            let labelled = clip_label::Entity::find()
+                .select_only()
+                .column(clip_label::Column::ClippingId)
+                .filter(clip_label::Column::LabelId.eq(*label_id))
+                .into_query();
+            query = query.filter(clipping::Column::Id.in_subquery(labelled));
+        }
+
+        let total = query
+            .clone()
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count clippings: {}", e)))?;
+
+        let column = match sort_by {
+            ClipSortField::CreatedAt => clipping::Column::CreatedAt,
+            ClipSortField::UpdatedAt => clipping::Column::UpdatedAt,
+            ClipSortField::Title => clipping::Column::Title,
+            ClipSortField::SourceDomain => clipping::Column::SourceDomain,
+        };
+        let order = match sort_dir {
+            SortDirection::Asc => Order::Asc,
+            SortDirection::Desc => Order::Desc,
+        };
+
+        let clippings = query
+            .order_by(column, order.clone())
+            .order_by(clipping::Column::Id, order)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clippings: {}", e)))?;
+
+        Ok((Self::with_comments(db, clippings).await?, total))
+    }
+
+    /// Number of clips, unread clips, and clips per source domain. Clips
+    /// without a domain are only counted in the totals.
+    pub async fn count_stats(db: &DatabaseConnection) -> Result<(u64, u64, Vec<(String, u64)>)> {
+        let total = clipping::Entity::find()
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count clippings: {}", e)))?;
+        let unread = clipping::Entity::find()
+            .filter(clipping::Column::ReadStatus.eq(0))
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count unread clippings: {}", e)))?;
+
+        let by_domain: Vec<(String, i64)> = clipping::Entity::find()
+            .select_only()
+            .column(clipping::Column::SourceDomain)
+            .column_as(clipping::Column::Id.count(), "clip_count")
+            .filter(clipping::Column::SourceDomain.is_not_null())
+            .group_by(clipping::Column::SourceDomain)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to count clippings by domain: {}", e))
+            })?;
+
+        Ok((
+            total,
+            unread,
+            by_domain
+                .into_iter()
+                .map(|(domain, count)| (domain, count as u64))
+                .collect(),
+        ))
     }
 
     /// Get clipping by ID (alias for find_by_id)
@@ -201,10 +321,44 @@ impl ClippingRepository {
             .map_err(|e| AppError::generic(format!("Failed to update clipping: {}", e)))?;
 
         let mut clipping = Clipping::from(result);
+        SearchRepository::index_clip(db, &clipping).await?;
         clipping.comments = Self::find_comments(db, clipping.id).await?;
         Ok(Some(clipping))
     }
 
+    /// Delete a clipping together with its comments and label relations.
+    /// Returns the deleted clipping so stored files can be cleaned up.
+    pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<Clipping> {
+        let clipping = Self::find_by_id(db, id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Clipping", id.to_string()))?;
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        comment::Entity::delete_many()
+            .filter(comment::Column::ClippingId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete clipping comments: {}", e)))?;
+
+        LabelRepository::remove_all_from_clip(&txn, id).await?;
+
+        clipping::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete clipping: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!("Deleted clipping {}", id);
+        Ok(clipping)
+    }
+
     // ==================== Comment operations ====================
 
     /// Get comments for a clipping (public method)
@@ -224,6 +378,51 @@ impl ClippingRepository {
         Ok(comments.into_iter().map(Comment::from).collect())
     }
 
+    /// Find comments for several clippings in one query, grouped by clipping
+    async fn find_comments_batch(
+        db: &DatabaseConnection,
+        clipping_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<Comment>>> {
+        if clipping_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let comments = comment::Entity::find()
+            .filter(comment::Column::ClippingId.is_in(clipping_ids.to_vec()))
+            .order_by_asc(comment::Column::CreatedAt)
+            .order_by_asc(comment::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get comments batch: {}", e)))?;
+
+        let mut result: HashMap<i64, Vec<Comment>> = HashMap::new();
+        for c in comments {
+            result
+                .entry(c.clipping_id)
+                .or_default()
+                .push(Comment::from(c));
+        }
+        Ok(result)
+    }
+
+    /// Convert clipping rows and attach their comments
+    async fn with_comments(
+        db: &DatabaseConnection,
+        clippings: Vec<clipping::Model>,
+    ) -> Result<Vec<Clipping>> {
+        let ids: Vec<i64> = clippings.iter().map(|c| c.id).collect();
+        let mut comments = Self::find_comments_batch(db, &ids).await?;
+
+        Ok(clippings
+            .into_iter()
+            .map(|c| {
+                let mut clipping = Clipping::from(c);
+                clipping.comments = comments.remove(&clipping.id).unwrap_or_default();
+                clipping
+            })
+            .collect())
+    }
+
     /// Add a comment to a clipping
     pub async fn add_comment(
         db: &DatabaseConnection,
@@ -329,3 +528,130 @@ impl ClippingRepository {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{label, test_db, ClipFixture};
+
+    #[tokio::test]
+    async fn test_find_page_filters_and_sorts() {
+        let db = test_db().await;
+        let b = ClipFixture::new("Beta")
+            .with_domain("b.org")
+            .with_label("rust")
+            .with_label("async")
+            .insert(&db)
+            .await;
+        let a = ClipFixture::new("Alpha")
+            .with_domain("a.org")
+            .with_label("rust")
+            .insert(&db)
+            .await;
+        ClipFixture::new("Gamma")
+            .with_domain("a.org")
+            .insert(&db)
+            .await;
+        ClippingRepository::add_comment(&db, a.id, "first")
+            .await
+            .unwrap();
+        ClippingRepository::add_comment(&db, a.id, "second")
+            .await
+            .unwrap();
+
+        let (page, total) = ClippingRepository::find_page(
+            &db,
+            &ClipFilter::default(),
+            ClipSortField::Title,
+            SortDirection::Asc,
+            0,
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(total, 3);
+        let titles: Vec<&str> = page.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Alpha", "Beta"]);
+        let comments: Vec<&str> = page[0]
+            .comments
+            .iter()
+            .map(|c| c.content.as_str())
+            .collect();
+        assert_eq!(comments, vec!["first", "second"]);
+        assert!(page[1].comments.is_empty());
+
+        let rust = label(&db, "rust").await;
+        let filter = ClipFilter {
+            label_ids: vec![rust.id],
+            ..Default::default()
+        };
+        let (page, total) = ClippingRepository::find_page(
+            &db,
+            &filter,
+            ClipSortField::SourceDomain,
+            SortDirection::Desc,
+            0,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(page[0].id, b.id);
+        assert_eq!(page[1].id, a.id);
+
+        // Every label must match
+        let filter = ClipFilter {
+            label_ids: vec![rust.id, label(&db, "async").await.id],
+            source_domain: Some("b.org".to_string()),
+            read_status: Some(0),
+        };
+        let (page, _) = ClippingRepository::find_page(
+            &db,
+            &filter,
+            ClipSortField::CreatedAt,
+            SortDirection::Desc,
+            0,
+            10,
+        )
+        .await
+        .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, b.id);
+    }
+
+    #[tokio::test]
+    async fn test_count_stats() {
+        let db = test_db().await;
+        let read = ClipFixture::new("Read")
+            .with_domain("a.org")
+            .insert(&db)
+            .await;
+        ClipFixture::new("Unread")
+            .with_domain("a.org")
+            .insert(&db)
+            .await;
+        ClipFixture::new("Other")
+            .with_domain("b.org")
+            .insert(&db)
+            .await;
+        ClippingRepository::update(
+            &db,
+            read.id,
+            UpdateClipping {
+                read_status: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let (total, unread, mut by_domain) = ClippingRepository::count_stats(&db).await.unwrap();
+        by_domain.sort();
+        assert_eq!(total, 3);
+        assert_eq!(unread, 2);
+        assert_eq!(
+            by_domain,
+            vec![("a.org".to_string(), 2), ("b.org".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Paper custom field repository for SQLite using SeaORM
+
+use std::collections::HashMap;
+
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::database::entities::{custom_field_definition, paper, paper_custom_field};
+use crate::models::{normalize_custom_field_key, CustomField, CustomFieldKey, CustomFieldType};
+use crate::sys::error::{AppError, Result};
+
+/// Repository for custom field values of papers
+pub struct CustomFieldRepository;
+
+impl CustomFieldRepository {
+    /// Custom fields of a paper, by key
+    pub async fn find_by_paper(db: &DatabaseConnection, paper_id: i64) -> Result<Vec<CustomField>> {
+        let fields = paper_custom_field::Entity::find()
+            .filter(paper_custom_field::Column::PaperId.eq(paper_id))
+            .order_by_asc(paper_custom_field::Column::Key)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get custom fields: {}", e)))?;
+
+        let keys: Vec<String> = fields.iter().map(|f| f.key.clone()).collect();
+        let types = Self::types_of(db, keys).await?;
+        Ok(fields
+            .into_iter()
+            .map(|field| {
+                let value_type = types
+                    .get(&field.key)
+                    .copied()
+                    .unwrap_or(CustomFieldType::Text);
+                CustomField::from_model(field, value_type)
+            })
+            .collect())
+    }
+
+    /// Custom fields of many papers as `key -> value` maps
+    pub async fn get_values_batch(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+    ) -> Result<HashMap<i64, HashMap<String, String>>> {
+        if paper_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let fields = paper_custom_field::Entity::find()
+            .filter(paper_custom_field::Column::PaperId.is_in(paper_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get custom fields: {}", e)))?;
+
+        let mut map: HashMap<i64, HashMap<String, String>> = HashMap::new();
+        for field in fields {
+            map.entry(field.paper_id)
+                .or_default()
+                .insert(field.key, field.value);
+        }
+        Ok(map)
+    }
+
+    /// Set a custom field of a paper, replacing any earlier value of the
+    /// key. A key has one type for all papers: it is defined by the first
+    /// value set, and can only change while no other paper uses the key.
+    /// The value is checked against `value_type` and stored in its
+    /// normalized form.
+    pub async fn set(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        key: &str,
+        value: &str,
+        value_type: CustomFieldType,
+    ) -> Result<CustomField> {
+        let key = normalize_custom_field_key(key).map_err(|e| AppError::validation("key", e))?;
+        let value = value_type
+            .normalize(value)
+            .map_err(|e| AppError::validation("value", e))?;
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper::Entity::find_by_id(paper_id)
+            .one(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find paper: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+        Self::define(&txn, paper_id, &key, value_type).await?;
+
+        let existing = paper_custom_field::Entity::find()
+            .filter(paper_custom_field::Column::PaperId.eq(paper_id))
+            .filter(paper_custom_field::Column::Key.eq(key.as_str()))
+            .one(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get custom field: {}", e)))?;
+
+        let now = chrono::Utc::now();
+        let saved = match existing {
+            Some(existing) => {
+                let mut field: paper_custom_field::ActiveModel = existing.into();
+                field.value = Set(value);
+                field.updated_at = Set(now);
+                field.update(&txn).await
+            }
+            None => {
+                paper_custom_field::ActiveModel {
+                    paper_id: Set(paper_id),
+                    key: Set(key),
+                    value: Set(value),
+                    updated_at: Set(now),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await
+            }
+        }
+        .map_err(|e| AppError::generic(format!("Failed to set custom field: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(CustomField::from_model(saved, value_type))
+    }
+
+    /// Make `value_type` the type of `key`, which `paper_id` is about to set
+    async fn define<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        key: &str,
+        value_type: CustomFieldType,
+    ) -> Result<()> {
+        let definition = custom_field_definition::Entity::find()
+            .filter(custom_field_definition::Column::Key.eq(key))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get custom field type: {}", e)))?;
+
+        match definition {
+            None => {
+                custom_field_definition::ActiveModel {
+                    key: Set(key.to_string()),
+                    value_type: Set(value_type.as_str().to_string()),
+                    created_at: Set(chrono::Utc::now()),
+                    ..Default::default()
+                }
+                .insert(db)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to define custom field: {}", e)))?;
+            }
+            Some(definition) if definition.value_type != value_type.as_str() => {
+                let used_elsewhere = paper_custom_field::Entity::find()
+                    .filter(paper_custom_field::Column::Key.eq(key))
+                    .filter(paper_custom_field::Column::PaperId.ne(paper_id))
+                    .count(db)
+                    .await
+                    .map_err(|e| {
+                        AppError::generic(format!("Failed to count custom fields: {}", e))
+                    })?;
+                if used_elsewhere > 0 {
+                    return Err(AppError::validation(
+                        "value_type",
+                        format!(
+                            "'{}' holds {} values on other papers",
+                            key, definition.value_type
+                        ),
+                    ));
+                }
+
+                let mut definition: custom_field_definition::ActiveModel = definition.into();
+                definition.value_type = Set(value_type.as_str().to_string());
+                definition.update(db).await.map_err(|e| {
+                    AppError::generic(format!("Failed to change custom field type: {}", e))
+                })?;
+            }
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Remove a custom field from a paper. A key no paper uses any more
+    /// loses its type.
+    pub async fn delete(db: &DatabaseConnection, paper_id: i64, key: &str) -> Result<()> {
+        let key = key.trim();
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let result = paper_custom_field::Entity::delete_many()
+            .filter(paper_custom_field::Column::PaperId.eq(paper_id))
+            .filter(paper_custom_field::Column::Key.eq(key))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete custom field: {}", e)))?;
+        if result.rows_affected == 0 {
+            return Err(AppError::not_found("Custom field", key.to_string()));
+        }
+
+        let remaining = paper_custom_field::Entity::find()
+            .filter(paper_custom_field::Column::Key.eq(key))
+            .count(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count custom fields: {}", e)))?;
+        if remaining == 0 {
+            custom_field_definition::Entity::delete_many()
+                .filter(custom_field_definition::Column::Key.eq(key))
+                .exec(&txn)
+                .await
+                .map_err(|e| {
+                    AppError::generic(format!("Failed to delete custom field type: {}", e))
+                })?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+        Ok(())
+    }
+
+    /// Keys in use with their type, most used first
+    pub async fn list_keys(db: &DatabaseConnection) -> Result<Vec<CustomFieldKey>> {
+        let rows: Vec<(String, i64)> = paper_custom_field::Entity::find()
+            .select_only()
+            .column(paper_custom_field::Column::Key)
+            .column_as(
+                Expr::col(paper_custom_field::Column::Id).count(),
+                "paper_count",
+            )
+            .group_by(paper_custom_field::Column::Key)
+            .order_by_desc(Expr::col(paper_custom_field::Column::Id).count())
+            .order_by_asc(paper_custom_field::Column::Key)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to list custom field keys: {}", e)))?;
+
+        let keys: Vec<String> = rows.iter().map(|(key, _)| key.clone()).collect();
+        let types = Self::types_of(db, keys).await?;
+        Ok(rows
+            .into_iter()
+            .map(|(key, paper_count)| CustomFieldKey {
+                value_type: types.get(&key).copied().unwrap_or(CustomFieldType::Text),
+                key,
+                paper_count: paper_count.max(0) as u64,
+            })
+            .collect())
+    }
+
+    /// Types of `keys`, by key
+    async fn types_of(
+        db: &DatabaseConnection,
+        keys: Vec<String>,
+    ) -> Result<HashMap<String, CustomFieldType>> {
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let definitions = custom_field_definition::Entity::find()
+            .filter(custom_field_definition::Column::Key.is_in(keys))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get custom field types: {}", e)))?;
+
+        // Rows are only written through `CustomFieldType::as_str`
+        Ok(definitions
+            .into_iter()
+            .map(|d| {
+                let value_type = d.value_type.parse().unwrap_or(CustomFieldType::Text);
+                (d.key, value_type)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_set_replace_and_delete() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+
+        CustomFieldRepository::set(&db, a.id, "funding", "ERC", CustomFieldType::Text)
+            .await
+            .unwrap();
+        CustomFieldRepository::set(&db, b.id, "funding", "NSF", CustomFieldType::Text)
+            .await
+            .unwrap();
+        let reviewed =
+            CustomFieldRepository::set(&db, a.id, "reviewed", "yes", CustomFieldType::Bool)
+                .await
+                .unwrap();
+        assert_eq!(reviewed.value, "true");
+        let replaced =
+            CustomFieldRepository::set(&db, a.id, "funding", "DFG", CustomFieldType::Text)
+                .await
+                .unwrap();
+        assert_eq!(replaced.value, "DFG");
+
+        assert!(
+            CustomFieldRepository::set(&db, a.id, "year", "soon", CustomFieldType::Number)
+                .await
+                .is_err()
+        );
+        assert!(
+            CustomFieldRepository::set(&db, a.id + 100, "funding", "x", CustomFieldType::Text)
+                .await
+                .is_err()
+        );
+
+        let fields = CustomFieldRepository::find_by_paper(&db, a.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            fields.iter().map(|f| f.key.as_str()).collect::<Vec<_>>(),
+            ["funding", "reviewed"]
+        );
+        let keys = CustomFieldRepository::list_keys(&db).await.unwrap();
+        assert_eq!((keys[0].key.as_str(), keys[0].paper_count), ("funding", 2));
+
+        // A key keeps its type while other papers use it
+        assert!(
+            CustomFieldRepository::set(&db, b.id, "reviewed", "1", CustomFieldType::Number)
+                .await
+                .is_err()
+        );
+        let retyped =
+            CustomFieldRepository::set(&db, a.id, "reviewed", "2.0", CustomFieldType::Number)
+                .await
+                .unwrap();
+        assert_eq!(retyped.value, "2");
+        assert_eq!(retyped.value_type, CustomFieldType::Number);
+
+        CustomFieldRepository::delete(&db, a.id, "reviewed")
+            .await
+            .unwrap();
+        assert!(CustomFieldRepository::delete(&db, a.id, "reviewed")
+            .await
+            .is_err());
+        let values = CustomFieldRepository::get_values_batch(&db, &[a.id, b.id])
+            .await
+            .unwrap();
+        assert_eq!(values[&a.id].len(), 1);
+        assert_eq!(values[&b.id]["funding"], "NSF");
+    }
+}
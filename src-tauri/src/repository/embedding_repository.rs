@@ -0,0 +1,169 @@
+//! Paper embedding repository for SQLite using SeaORM
+
+use std::collections::HashMap;
+
+use sea_orm::*;
+
+use crate::database::entities::paper_embedding;
+use crate::sys::error::{AppError, Result};
+
+/// A freshly computed embedding waiting to be stored
+pub struct NewEmbedding {
+    pub paper_id: i64,
+    pub content_hash: String,
+    pub vector: Vec<f32>,
+}
+
+/// Repository for paper embedding vectors
+pub struct EmbeddingRepository;
+
+impl EmbeddingRepository {
+    /// Content hashes of the stored embeddings made with `model`, by paper id
+    pub async fn find_hashes(db: &DatabaseConnection, model: &str) -> Result<HashMap<i64, String>> {
+        let rows: Vec<(i64, String)> = paper_embedding::Entity::find()
+            .select_only()
+            .column(paper_embedding::Column::PaperId)
+            .column(paper_embedding::Column::ContentHash)
+            .filter(paper_embedding::Column::Model.eq(model))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get embedding hashes: {}", e)))?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Vectors made with `model` for papers that are not in the trash
+    pub async fn find_vectors(
+        db: &DatabaseConnection,
+        model: &str,
+    ) -> Result<Vec<(i64, Vec<f32>)>> {
+        let rows = paper_embedding::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                r#"
+                SELECT e.*
+                FROM paper_embedding e
+                INNER JOIN paper p ON p.id = e.paper_id
+                WHERE e.model = ? AND p.deleted_at IS NULL
+                "#,
+                [model.into()],
+            ))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get embeddings: {}", e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.paper_id, decode_vector(&row.vector)))
+            .collect())
+    }
+
+    /// Store one batch of embeddings, replacing any earlier vectors of the
+    /// same papers. The batch is written in one transaction.
+    pub async fn save_batch(
+        db: &DatabaseConnection,
+        model: &str,
+        embeddings: Vec<NewEmbedding>,
+    ) -> Result<()> {
+        if embeddings.is_empty() {
+            return Ok(());
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let paper_ids: Vec<i64> = embeddings.iter().map(|e| e.paper_id).collect();
+        paper_embedding::Entity::delete_many()
+            .filter(paper_embedding::Column::PaperId.is_in(paper_ids))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to replace embeddings: {}", e)))?;
+
+        let now = chrono::Utc::now();
+        let models = embeddings
+            .into_iter()
+            .map(|e| paper_embedding::ActiveModel {
+                paper_id: Set(e.paper_id),
+                model: Set(model.to_string()),
+                dimensions: Set(e.vector.len() as i32),
+                vector: Set(encode_vector(&e.vector)),
+                content_hash: Set(e.content_hash),
+                updated_at: Set(now),
+            });
+        paper_embedding::Entity::insert_many(models)
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to save embeddings: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))
+    }
+}
+
+/// Little-endian bytes of a vector
+pub fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Inverse of `encode_vector`; trailing bytes that do not form an f32 are ignored
+pub fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PaperRepository;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vector = vec![0.25, -1.5, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+
+    #[tokio::test]
+    async fn test_save_batch_replaces_and_skips_trash() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+
+        let embedding = |paper_id, hash: &str, vector: Vec<f32>| NewEmbedding {
+            paper_id,
+            content_hash: hash.to_string(),
+            vector,
+        };
+        EmbeddingRepository::save_batch(
+            &db,
+            "m",
+            vec![
+                embedding(a.id, "a1", vec![1.0, 0.0]),
+                embedding(b.id, "b1", vec![0.0, 1.0]),
+            ],
+        )
+        .await
+        .unwrap();
+        EmbeddingRepository::save_batch(&db, "m", vec![embedding(a.id, "a2", vec![0.5, 0.5])])
+            .await
+            .unwrap();
+
+        let hashes = EmbeddingRepository::find_hashes(&db, "m").await.unwrap();
+        assert_eq!(hashes.get(&a.id).map(String::as_str), Some("a2"));
+        assert_eq!(hashes.len(), 2);
+        assert!(EmbeddingRepository::find_hashes(&db, "other")
+            .await
+            .unwrap()
+            .is_empty());
+
+        PaperRepository::soft_delete(&db, b.id).await.unwrap();
+        let vectors = EmbeddingRepository::find_vectors(&db, "m").await.unwrap();
+        assert_eq!(vectors, vec![(a.id, vec![0.5, 0.5])]);
+    }
+}
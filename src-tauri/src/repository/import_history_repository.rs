@@ -0,0 +1,174 @@
+//! Import history repository for SQLite using SeaORM
+//!
+//! Tracks batch imports and the per-line state of each batch so interrupted
+//! imports can be resumed.
+
+use sea_orm::{sea_query::Expr, *};
+use tracing::info;
+
+use crate::database::entities::{import_batch, import_batch_item};
+use crate::sys::error::{AppError, Result};
+
+pub const BATCH_RUNNING: &str = "running";
+pub const BATCH_COMPLETED: &str = "completed";
+
+pub const ITEM_PENDING: &str = "pending";
+pub const ITEM_IMPORTED: &str = "imported";
+pub const ITEM_SKIPPED: &str = "skipped";
+pub const ITEM_FAILED: &str = "failed";
+
+/// Repository for import batch operations
+pub struct ImportHistoryRepository;
+
+impl ImportHistoryRepository {
+    /// Find an unfinished batch for the same source file and contents
+    pub async fn find_resumable_batch(
+        db: &DatabaseConnection,
+        source: &str,
+        source_path: &str,
+        source_hash: &str,
+    ) -> Result<Option<import_batch::Model>> {
+        import_batch::Entity::find()
+            .filter(import_batch::Column::Source.eq(source))
+            .filter(import_batch::Column::SourcePath.eq(source_path))
+            .filter(import_batch::Column::SourceHash.eq(source_hash))
+            .filter(import_batch::Column::Status.eq(BATCH_RUNNING))
+            .order_by_desc(import_batch::Column::CreatedAt)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find import batch: {}", e)))
+    }
+
+    /// Record a new batch and all of its items as pending, in one transaction
+    pub async fn create_batch(
+        db: &DatabaseConnection,
+        source: &str,
+        source_path: &str,
+        source_hash: &str,
+        category_id: Option<i64>,
+        items: &[(usize, String)],
+    ) -> Result<import_batch::Model> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let now = chrono::Utc::now();
+        let batch = import_batch::ActiveModel {
+            source: Set(source.to_string()),
+            source_path: Set(source_path.to_string()),
+            source_hash: Set(source_hash.to_string()),
+            category_id: Set(category_id),
+            total: Set(items.len() as i32),
+            status: Set(BATCH_RUNNING.to_string()),
+            created_at: Set(now),
+            updated_at: Set(now),
+            completed_at: Set(None),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to create import batch: {}", e)))?;
+
+        if !items.is_empty() {
+            let models =
+                items
+                    .iter()
+                    .map(|(line_number, identifier)| import_batch_item::ActiveModel {
+                        batch_id: Set(batch.id),
+                        line_number: Set(*line_number as i32),
+                        identifier: Set(identifier.clone()),
+                        status: Set(ITEM_PENDING.to_string()),
+                        paper_id: Set(None),
+                        error: Set(None),
+                        processed_at: Set(None),
+                        ..Default::default()
+                    });
+
+            import_batch_item::Entity::insert_many(models)
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to create import items: {}", e)))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!(
+            "Created import batch {} for {} ({} items)",
+            batch.id,
+            source_path,
+            items.len()
+        );
+        Ok(batch)
+    }
+
+    /// Get all items of a batch in file order
+    pub async fn get_items(
+        db: &DatabaseConnection,
+        batch_id: i64,
+    ) -> Result<Vec<import_batch_item::Model>> {
+        import_batch_item::Entity::find()
+            .filter(import_batch_item::Column::BatchId.eq(batch_id))
+            .order_by_asc(import_batch_item::Column::LineNumber)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get import items: {}", e)))
+    }
+
+    /// Get the items of a batch that have not been processed yet, in file order
+    pub async fn get_pending_items(
+        db: &DatabaseConnection,
+        batch_id: i64,
+    ) -> Result<Vec<import_batch_item::Model>> {
+        import_batch_item::Entity::find()
+            .filter(import_batch_item::Column::BatchId.eq(batch_id))
+            .filter(import_batch_item::Column::Status.eq(ITEM_PENDING))
+            .order_by_asc(import_batch_item::Column::LineNumber)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get pending import items: {}", e)))
+    }
+
+    /// Record the outcome of a single item
+    pub async fn mark_item(
+        db: &DatabaseConnection,
+        item_id: i64,
+        status: &str,
+        paper_id: Option<i64>,
+        error: Option<String>,
+    ) -> Result<()> {
+        let now = chrono::Utc::now();
+        import_batch_item::Entity::update_many()
+            .filter(import_batch_item::Column::Id.eq(item_id))
+            .col_expr(import_batch_item::Column::Status, Expr::value(status))
+            .col_expr(import_batch_item::Column::PaperId, Expr::value(paper_id))
+            .col_expr(import_batch_item::Column::Error, Expr::value(error))
+            .col_expr(
+                import_batch_item::Column::ProcessedAt,
+                Expr::value(Some(now)),
+            )
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update import item: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Mark a batch as completed
+    pub async fn complete_batch(db: &DatabaseConnection, batch_id: i64) -> Result<()> {
+        let now = chrono::Utc::now();
+        import_batch::Entity::update_many()
+            .filter(import_batch::Column::Id.eq(batch_id))
+            .col_expr(import_batch::Column::Status, Expr::value(BATCH_COMPLETED))
+            .col_expr(import_batch::Column::UpdatedAt, Expr::value(now))
+            .col_expr(import_batch::Column::CompletedAt, Expr::value(Some(now)))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to complete import batch: {}", e)))?;
+
+        info!("Import batch {} completed", batch_id);
+        Ok(())
+    }
+}
@@ -1,9 +1,11 @@
 //! Keyword repository for SQLite using SeaORM
 
+use sea_orm::sea_query::{Expr, Func, LikeExpr};
 use sea_orm::*;
+use std::collections::HashMap;
 use tracing::info;
 
-use crate::database::entities::{keyword, paper_keyword};
+use crate::database::entities::{keyword, paper, paper_keyword};
 use crate::models::{CreateKeyword, Keyword};
 use crate::sys::error::{AppError, Result};
 
@@ -33,10 +35,13 @@ impl KeywordRepository {
         Ok(keyword.map(Keyword::from))
     }
 
-    /// Find keyword by word
+    /// Find keyword by word, ignoring case and surrounding whitespace
     pub async fn find_by_word(db: &DatabaseConnection, word: &str) -> Result<Option<Keyword>> {
         let keyword = keyword::Entity::find()
-            .filter(keyword::Column::Word.eq(word))
+            .filter(
+                Expr::expr(Func::lower(Expr::col(keyword::Column::Word)))
+                    .eq(word.trim().to_lowercase()),
+            )
             .one(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to query keyword by word: {}", e)))?;
@@ -55,7 +60,7 @@ impl KeywordRepository {
         }
 
         let new_keyword = keyword::ActiveModel {
-            word: Set(create.word),
+            word: Set(create.word.trim().to_string()),
             ..Default::default()
         };
 
@@ -84,6 +89,55 @@ impl KeywordRepository {
         .await
     }
 
+    /// Link a keyword to a paper, ignoring links that already exist
+    pub async fn add_to_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        keyword_id: i64,
+    ) -> Result<()> {
+        let existing = paper_keyword::Entity::find()
+            .filter(paper_keyword::Column::PaperId.eq(paper_id))
+            .filter(paper_keyword::Column::KeywordId.eq(keyword_id))
+            .one(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query paper-keyword relation: {}", e))
+            })?;
+
+        if existing.is_some() {
+            return Ok(());
+        }
+
+        paper_keyword::ActiveModel {
+            paper_id: Set(paper_id),
+            keyword_id: Set(keyword_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to add keyword to paper: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Unlink a keyword from a paper. Returns false when they were not linked.
+    pub async fn remove_from_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        keyword_id: i64,
+    ) -> Result<bool> {
+        let result = paper_keyword::Entity::delete_many()
+            .filter(paper_keyword::Column::PaperId.eq(paper_id))
+            .filter(paper_keyword::Column::KeywordId.eq(keyword_id))
+            .exec(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to remove keyword from paper: {}", e))
+            })?;
+
+        Ok(result.rows_affected > 0)
+    }
+
     /// Get keywords for a paper
     pub async fn get_paper_keywords(db: &DatabaseConnection, paper_id: i64) -> Result<Vec<Keyword>> {
         // First get paper_keyword relations
@@ -108,4 +162,335 @@ impl KeywordRepository {
 
         Ok(keywords.into_iter().map(Keyword::from).collect())
     }
+
+    /// Get keywords for multiple papers (batch query for N+1 optimization)
+    /// Returns a HashMap mapping paper_id to its keywords
+    pub async fn get_paper_keywords_batch(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<Keyword>>> {
+        if paper_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let relations = paper_keyword::Entity::find()
+            .filter(paper_keyword::Column::PaperId.is_in(paper_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!(
+                    "Failed to get paper-keyword relations batch: {}",
+                    e
+                ))
+            })?;
+
+        let keyword_ids: Vec<i64> = relations.iter().map(|r| r.keyword_id).collect();
+
+        if keyword_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let keywords = keyword::Entity::find()
+            .filter(keyword::Column::Id.is_in(keyword_ids))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper keywords batch: {}", e)))?;
+
+        let keyword_map: HashMap<i64, Keyword> = keywords
+            .into_iter()
+            .map(|k| (k.id, Keyword::from(k)))
+            .collect();
+
+        let mut result: HashMap<i64, Vec<Keyword>> = HashMap::new();
+        for relation in relations {
+            if let Some(keyword) = keyword_map.get(&relation.keyword_id).cloned() {
+                result.entry(relation.paper_id).or_default().push(keyword);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Number of non-deleted papers per keyword. Keywords without papers are
+    /// absent from the map.
+    pub async fn count_papers(db: &DatabaseConnection) -> Result<HashMap<i64, u64>> {
+        let counts: Vec<(i64, i64)> = paper_keyword::Entity::find()
+            .select_only()
+            .column(paper_keyword::Column::KeywordId)
+            .column_as(paper_keyword::Column::PaperId.count(), "paper_count")
+            .join(JoinType::InnerJoin, paper_keyword::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .group_by(paper_keyword::Column::KeywordId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count keyword usage: {}", e)))?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(keyword_id, count)| (keyword_id, count as u64))
+            .collect())
+    }
+
+    /// Keywords whose word is one of `words`
+    pub async fn find_by_words(db: &DatabaseConnection, words: &[String]) -> Result<Vec<Keyword>> {
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keywords = keyword::Entity::find()
+            .filter(keyword::Column::Word.is_in(words.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query keywords by word: {}", e)))?;
+
+        Ok(keywords.into_iter().map(Keyword::from).collect())
+    }
+
+    /// `(paper_id, keyword_id)` links to any of `keyword_ids`, for non-deleted
+    /// papers other than `exclude_paper_id`
+    pub async fn find_paper_links(
+        db: &DatabaseConnection,
+        keyword_ids: &[i64],
+        exclude_paper_id: i64,
+    ) -> Result<Vec<(i64, i64)>> {
+        if keyword_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        paper_keyword::Entity::find()
+            .select_only()
+            .column(paper_keyword::Column::PaperId)
+            .column(paper_keyword::Column::KeywordId)
+            .join(JoinType::InnerJoin, paper_keyword::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper_keyword::Column::KeywordId.is_in(keyword_ids.to_vec()))
+            .filter(paper_keyword::Column::PaperId.ne(exclude_paper_id))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query keyword links: {}", e)))
+    }
+
+    /// Keywords containing `query`, case-insensitively
+    pub async fn search(db: &DatabaseConnection, query: &str) -> Result<Vec<Keyword>> {
+        let escaped = query
+            .trim()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = LikeExpr::new(format!("%{}%", escaped)).escape('\\');
+
+        let keywords = keyword::Entity::find()
+            .filter(keyword::Column::Word.like(pattern))
+            .order_by_asc(keyword::Column::Word)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to search keywords: {}", e)))?;
+
+        Ok(keywords.into_iter().map(Keyword::from).collect())
+    }
+
+    /// Delete a keyword and unlink it from all papers
+    pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+        if Self::find_by_id(db, id).await?.is_none() {
+            return Err(AppError::not_found("Keyword", id.to_string()));
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper_keyword::Entity::delete_many()
+            .filter(paper_keyword::Column::KeywordId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to unlink keyword: {}", e)))?;
+        keyword::Entity::delete_by_id(id)
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete keyword: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Move all papers of `secondary_ids` to `primary_id` and delete the
+    /// secondary keywords. Papers already linked to the primary keyword keep
+    /// a single link.
+    pub async fn merge(
+        db: &DatabaseConnection,
+        primary_id: i64,
+        secondary_ids: &[i64],
+    ) -> Result<Keyword> {
+        let primary = Self::find_by_id(db, primary_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Keyword", primary_id.to_string()))?;
+        for &id in secondary_ids {
+            if Self::find_by_id(db, id).await?.is_none() {
+                return Err(AppError::not_found("Keyword", id.to_string()));
+            }
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let mut linked: Vec<i64> = paper_keyword::Entity::find()
+            .select_only()
+            .column(paper_keyword::Column::PaperId)
+            .filter(paper_keyword::Column::KeywordId.eq(primary_id))
+            .into_tuple()
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query paper-keyword relations: {}", e))
+            })?;
+
+        let relations = paper_keyword::Entity::find()
+            .filter(paper_keyword::Column::KeywordId.is_in(secondary_ids.to_vec()))
+            .all(&txn)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query paper-keyword relations: {}", e))
+            })?;
+
+        for relation in relations {
+            if linked.contains(&relation.paper_id) {
+                paper_keyword::Entity::delete_by_id(relation.id)
+                    .exec(&txn)
+                    .await
+                    .map_err(|e| {
+                        AppError::generic(format!("Failed to remove duplicate keyword link: {}", e))
+                    })?;
+            } else {
+                linked.push(relation.paper_id);
+                let mut relation: paper_keyword::ActiveModel = relation.into();
+                relation.keyword_id = Set(primary_id);
+                relation.update(&txn).await.map_err(|e| {
+                    AppError::generic(format!("Failed to move keyword link: {}", e))
+                })?;
+            }
+        }
+
+        keyword::Entity::delete_many()
+            .filter(keyword::Column::Id.is_in(secondary_ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete merged keywords: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!(
+            "Merged {} keywords into '{}'",
+            secondary_ids.len(),
+            primary.word
+        );
+        Ok(primary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_merge_moves_links_without_duplicates() {
+        let db = test_db().await;
+        let both = PaperFixture::new("Both")
+            .with_keyword("neural network")
+            .with_keyword("neural net")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Synonym only")
+            .with_keyword("neural net")
+            .insert(&db)
+            .await;
+        PaperFixture::new("In the trash")
+            .with_keyword("neural net")
+            .deleted()
+            .insert(&db)
+            .await;
+
+        let primary = KeywordRepository::find_by_word(&db, "neural network")
+            .await
+            .unwrap()
+            .unwrap();
+        let synonym = KeywordRepository::find_by_word(&db, "neural net")
+            .await
+            .unwrap()
+            .unwrap();
+
+        KeywordRepository::merge(&db, primary.id, &[synonym.id])
+            .await
+            .unwrap();
+
+        let counts = KeywordRepository::count_papers(&db).await.unwrap();
+        assert_eq!(counts.get(&primary.id), Some(&2));
+        assert!(KeywordRepository::find_by_id(&db, synonym.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            KeywordRepository::get_paper_keywords(&db, both.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        KeywordRepository::delete(&db, primary.id).await.unwrap();
+        assert!(KeywordRepository::count_papers(&db)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_keywords_are_case_insensitive() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Paper").insert(&db).await;
+
+        let keyword = KeywordRepository::create_or_find(&db, "Deep Learning")
+            .await
+            .unwrap();
+        let same = KeywordRepository::create_or_find(&db, " deep learning ")
+            .await
+            .unwrap();
+        assert_eq!(keyword.id, same.id);
+        assert!(KeywordRepository::create(
+            &db,
+            CreateKeyword {
+                word: "DEEP LEARNING".to_string()
+            }
+        )
+        .await
+        .is_err());
+
+        KeywordRepository::add_to_paper(&db, paper.id, keyword.id)
+            .await
+            .unwrap();
+        assert!(
+            KeywordRepository::remove_from_paper(&db, paper.id, keyword.id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !KeywordRepository::remove_from_paper(&db, paper.id, keyword.id)
+                .await
+                .unwrap()
+        );
+        assert!(KeywordRepository::get_paper_keywords(&db, paper.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
 }
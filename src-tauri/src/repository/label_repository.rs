@@ -1,20 +1,34 @@
 //! Label repository for SQLite using SeaORM
 
-use sea_orm::*;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, *};
+use std::collections::{HashMap, HashSet};
 use tracing::info;
 
-use crate::database::entities::{label, paper_label};
-use crate::models::{CreateLabel, Label, UpdateLabel};
+use crate::database::entities::{clip_label, clipping, label, paper, paper_label};
+use crate::models::{CreateLabel, Label, LabelNode, UpdateLabel};
 use crate::sys::error::{AppError, Result};
 
+/// Result of merging labels
+#[derive(Debug, Clone)]
+pub struct LabelMerge {
+    /// The primary label with its updated document count
+    pub label: Label,
+    /// Paper and clip links moved to the primary label
+    pub relations_moved: usize,
+    /// Links dropped because the document already carried the primary label
+    /// or another merged label
+    pub duplicates_dropped: usize,
+}
+
 /// Repository for Label operations
 pub struct LabelRepository;
 
 impl LabelRepository {
-    /// Find all labels
+    /// Find all labels, in the user's order
     pub async fn find_all(db: &DatabaseConnection) -> Result<Vec<Label>> {
         let labels = label::Entity::find()
+            .order_by_asc(label::Column::SortOrder)
             .order_by_asc(label::Column::Name)
             .all(db)
             .await
@@ -55,11 +69,34 @@ impl LabelRepository {
             ));
         }
 
+        if let Some(parent_id) = create.parent_id {
+            if Self::find_by_id(db, parent_id).await?.is_none() {
+                return Err(AppError::not_found("Label", parent_id.to_string()));
+            }
+        }
+
+        // Once labels have been reordered, new ones go to the end; before
+        // that they all stay at 0 and sort by name
+        let max_sort_order: Option<i32> = label::Entity::find()
+            .select_only()
+            .column_as(label::Column::SortOrder.max(), "max_sort_order")
+            .into_tuple()
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query label order: {}", e)))?
+            .flatten();
+        let sort_order = match max_sort_order {
+            Some(max) if max > 0 => max + 1,
+            _ => 0,
+        };
+
         let now = chrono::Utc::now();
         let new_label = label::ActiveModel {
             name: Set(create.name),
             color: Set(create.color),
             document_count: Set(0),
+            sort_order: Set(sort_order),
+            parent_id: Set(create.parent_id),
             created_at: Set(now),
             ..Default::default()
         };
@@ -86,11 +123,21 @@ impl LabelRepository {
             }
         }
 
+        // The cycle check reads every label's parent, so it runs in the same
+        // transaction as the move
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
         let label = label::Entity::find_by_id(id)
-            .one(db)
+            .one(&txn)
             .await
             .map_err(|e| AppError::generic(format!("Failed to find label: {}", e)))?
             .ok_or_else(|| AppError::not_found("Label", id.to_string()))?;
+        if let Some(Some(parent_id)) = update.parent_id {
+            Self::check_parent(&txn, id, parent_id).await?;
+        }
 
         let mut label: label::ActiveModel = label.into();
         if let Some(name) = update.name {
@@ -99,33 +146,489 @@ impl LabelRepository {
         if let Some(color) = update.color {
             label.color = Set(color);
         }
+        if let Some(parent_id) = update.parent_id {
+            label.parent_id = Set(parent_id);
+        }
 
         let result = label
-            .update(db)
+            .update(&txn)
             .await
             .map_err(|e| AppError::generic(format!("Failed to update label: {}", e)))?;
 
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
         Ok(Label::from(result))
     }
 
-    /// Delete label
+    /// Fail unless `parent_id` exists and is neither `id` nor a label below
+    /// it, either of which would make the labels a cycle
+    async fn check_parent<C: ConnectionTrait>(db: &C, id: i64, parent_id: i64) -> Result<()> {
+        if parent_id == id {
+            return Err(AppError::validation(
+                "parent_id",
+                "A label cannot be its own parent",
+            ));
+        }
+
+        let parents = Self::parent_map(db).await?;
+        if !parents.contains_key(&parent_id) {
+            return Err(AppError::not_found("Label", parent_id.to_string()));
+        }
+        if ancestors(&parents, parent_id).contains(&id) {
+            return Err(AppError::validation(
+                "parent_id",
+                "A label cannot be moved under one of its own sub-labels",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parent of every label
+    async fn parent_map<C: ConnectionTrait>(db: &C) -> Result<HashMap<i64, Option<i64>>> {
+        let parents: Vec<(i64, Option<i64>)> = label::Entity::find()
+            .select_only()
+            .column(label::Column::Id)
+            .column(label::Column::ParentId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query label parents: {}", e)))?;
+        Ok(parents.into_iter().collect())
+    }
+
+    /// `id` followed by every label below it
+    pub async fn descendant_ids(db: &DatabaseConnection, id: i64) -> Result<Vec<i64>> {
+        let mut children: HashMap<i64, Vec<i64>> = HashMap::new();
+        for (child, parent) in Self::parent_map(db).await? {
+            if let Some(parent) = parent {
+                children.entry(parent).or_default().push(child);
+            }
+        }
+
+        let mut ids = vec![id];
+        let mut seen = HashSet::from([id]);
+        let mut next = 0;
+        while let Some(&current) = ids.get(next) {
+            for &child in children.get(&current).into_iter().flatten() {
+                if seen.insert(child) {
+                    ids.push(child);
+                }
+            }
+            next += 1;
+        }
+        Ok(ids)
+    }
+
+    /// Load labels as a tree with document counts. Labels whose parent no
+    /// longer exists are shown at the top level.
+    pub async fn load_tree(db: &DatabaseConnection) -> Result<Vec<LabelNode>> {
+        let mut labels = Self::find_all(db).await?;
+        let ids: HashSet<i64> = labels.iter().map(|l| l.id).collect();
+        for label in &mut labels {
+            label.parent_id = label.parent_id.filter(|parent| ids.contains(parent));
+        }
+
+        let mut documents: HashMap<i64, HashSet<Document>> = HashMap::new();
+        let paper_edges: Vec<(i64, i64)> = paper_label::Entity::find()
+            .select_only()
+            .column(paper_label::Column::LabelId)
+            .column(paper_label::Column::PaperId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to load paper labels: {}", e)))?;
+        for (label_id, paper_id) in paper_edges {
+            documents
+                .entry(label_id)
+                .or_default()
+                .insert(Document::Paper(paper_id));
+        }
+        let clip_edges: Vec<(i64, i64)> = clip_label::Entity::find()
+            .select_only()
+            .column(clip_label::Column::LabelId)
+            .column(clip_label::Column::ClippingId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to load clip labels: {}", e)))?;
+        for (label_id, clip_id) in clip_edges {
+            documents
+                .entry(label_id)
+                .or_default()
+                .insert(Document::Clip(clip_id));
+        }
+
+        Ok(build_tree_recursive(&labels, None, &documents)
+            .into_iter()
+            .map(|(node, _)| node)
+            .collect())
+    }
+
+    /// Delete label. Labels grouped under it move to the top level.
     pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        label::Entity::update_many()
+            .filter(label::Column::ParentId.eq(id))
+            .col_expr(label::Column::ParentId, Expr::value(Option::<i64>::None))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update child labels: {}", e)))?;
+
         // First delete all paper-label relations (cascade will handle this, but we do it explicitly for safety)
         paper_label::Entity::delete_many()
             .filter(paper_label::Column::LabelId.eq(id))
-            .exec(db)
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete label relations: {}", e)))?;
+        clip_label::Entity::delete_many()
+            .filter(clip_label::Column::LabelId.eq(id))
+            .exec(&txn)
             .await
             .map_err(|e| AppError::generic(format!("Failed to delete label relations: {}", e)))?;
 
         // Then delete the label
         label::Entity::delete_by_id(id)
-            .exec(db)
+            .exec(&txn)
             .await
             .map_err(|e| AppError::generic(format!("Failed to delete label: {}", e)))?;
 
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Labels no paper or clip carries. Papers in the trash count as
+    /// carrying their labels, so restoring them keeps the labels.
+    pub async fn find_unused(db: &DatabaseConnection) -> Result<Vec<Label>> {
+        let mut used: HashSet<i64> = paper_label::Entity::find()
+            .select_only()
+            .column(paper_label::Column::LabelId)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query paper labels: {}", e)))?
+            .into_iter()
+            .collect();
+        let clip_labels: Vec<i64> = clip_label::Entity::find()
+            .select_only()
+            .column(clip_label::Column::LabelId)
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clip labels: {}", e)))?;
+        used.extend(clip_labels);
+
+        Ok(Self::find_all(db)
+            .await?
+            .into_iter()
+            .filter(|label| !used.contains(&label.id))
+            .collect())
+    }
+
+    /// Delete several labels in one transaction and return how many were
+    /// deleted. Labels grouped under a deleted label move to the top level.
+    pub async fn delete_many(db: &DatabaseConnection, ids: &[i64]) -> Result<u64> {
+        for &id in ids {
+            if Self::find_by_id(db, id).await?.is_none() {
+                return Err(AppError::not_found("Label", id.to_string()));
+            }
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+        label::Entity::update_many()
+            .filter(label::Column::ParentId.is_in(ids.to_vec()))
+            .col_expr(label::Column::ParentId, Expr::value(Option::<i64>::None))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update child labels: {}", e)))?;
+        paper_label::Entity::delete_many()
+            .filter(paper_label::Column::LabelId.is_in(ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete label relations: {}", e)))?;
+        clip_label::Entity::delete_many()
+            .filter(clip_label::Column::LabelId.is_in(ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete label relations: {}", e)))?;
+        let deleted = label::Entity::delete_many()
+            .filter(label::Column::Id.is_in(ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete labels: {}", e)))?
+            .rows_affected;
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!("Deleted {} labels", deleted);
+        Ok(deleted)
+    }
+
+    /// Move every paper and clip of `secondary_ids` to `primary_id` and
+    /// delete the secondary labels. Documents that already carry the primary
+    /// label keep a single link. Labels grouped under a secondary move under
+    /// the primary, unless the primary is below them; those move up to the
+    /// nearest remaining ancestor instead.
+    pub async fn merge(
+        db: &DatabaseConnection,
+        primary_id: i64,
+        secondary_ids: &[i64],
+    ) -> Result<LabelMerge> {
+        if secondary_ids.contains(&primary_id) {
+            return Err(AppError::validation(
+                "secondary_ids",
+                "A label cannot be merged into itself",
+            ));
+        }
+        for &id in std::iter::once(&primary_id).chain(secondary_ids) {
+            if Self::find_by_id(db, id).await?.is_none() {
+                return Err(AppError::not_found("Label", id.to_string()));
+            }
+        }
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let parents = Self::parent_map(&txn).await?;
+        let merged: HashSet<i64> = secondary_ids.iter().copied().collect();
+        let above_primary = ancestors(&parents, primary_id);
+        for (&id, &parent) in &parents {
+            if merged.contains(&id) || !parent.is_some_and(|p| merged.contains(&p)) {
+                continue;
+            }
+            let new_parent = if id == primary_id || above_primary.contains(&id) {
+                ancestors(&parents, id)
+                    .into_iter()
+                    .skip(1)
+                    .find(|ancestor| !merged.contains(ancestor))
+            } else {
+                Some(primary_id)
+            };
+            label::Entity::update_many()
+                .col_expr(label::Column::ParentId, Expr::value(new_parent))
+                .filter(label::Column::Id.eq(id))
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to move child labels: {}", e)))?;
+        }
+
+        // Links are deleted and re-inserted rather than updated, so the
+        // paper search index triggers see the change
+        let mut papers: HashSet<i64> = paper_label::Entity::find()
+            .select_only()
+            .column(paper_label::Column::PaperId)
+            .filter(paper_label::Column::LabelId.eq(primary_id))
+            .into_tuple()
+            .all(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query paper labels: {}", e)))?
+            .into_iter()
+            .collect();
+        let paper_relations = paper_label::Entity::find()
+            .filter(paper_label::Column::LabelId.is_in(secondary_ids.to_vec()))
+            .all(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query paper labels: {}", e)))?;
+        let relation_count = paper_relations.len();
+        let moved_papers: Vec<i64> = paper_relations
+            .into_iter()
+            .map(|relation| relation.paper_id)
+            .filter(|paper_id| papers.insert(*paper_id))
+            .collect();
+        paper_label::Entity::delete_many()
+            .filter(paper_label::Column::LabelId.is_in(secondary_ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove paper labels: {}", e)))?;
+        if !moved_papers.is_empty() {
+            paper_label::Entity::insert_many(moved_papers.iter().map(|paper_id| {
+                paper_label::ActiveModel {
+                    paper_id: Set(*paper_id),
+                    label_id: Set(primary_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to move paper labels: {}", e)))?;
+        }
+
+        let mut clips: HashSet<i64> = clip_label::Entity::find()
+            .select_only()
+            .column(clip_label::Column::ClippingId)
+            .filter(clip_label::Column::LabelId.eq(primary_id))
+            .into_tuple()
+            .all(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clip labels: {}", e)))?
+            .into_iter()
+            .collect();
+        let clip_relations = clip_label::Entity::find()
+            .filter(clip_label::Column::LabelId.is_in(secondary_ids.to_vec()))
+            .all(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clip labels: {}", e)))?;
+        let relation_count = relation_count + clip_relations.len();
+        let moved_clips: Vec<i64> = clip_relations
+            .into_iter()
+            .map(|relation| relation.clipping_id)
+            .filter(|clip_id| clips.insert(*clip_id))
+            .collect();
+        clip_label::Entity::delete_many()
+            .filter(clip_label::Column::LabelId.is_in(secondary_ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove clip labels: {}", e)))?;
+        if !moved_clips.is_empty() {
+            clip_label::Entity::insert_many(moved_clips.iter().map(|clip_id| {
+                clip_label::ActiveModel {
+                    clipping_id: Set(*clip_id),
+                    label_id: Set(primary_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to move clip labels: {}", e)))?;
+        }
+
+        label::Entity::delete_many()
+            .filter(label::Column::Id.is_in(secondary_ids.to_vec()))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete merged labels: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Self::update_document_count(db, primary_id).await?;
+        let primary = Self::find_by_id(db, primary_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Label", primary_id.to_string()))?;
+        let relations_moved = moved_papers.len() + moved_clips.len();
+        info!(
+            "Merged {} labels into '{}': {} links moved, {} duplicates dropped",
+            secondary_ids.len(),
+            primary.name,
+            relations_moved,
+            relation_count - relations_moved
+        );
+        Ok(LabelMerge {
+            label: primary,
+            relations_moved,
+            duplicates_dropped: relation_count - relations_moved,
+        })
+    }
+
+    /// Put the labels in `ordered_ids` first, in that order, followed by
+    /// the remaining labels in their current order
+    pub async fn reorder(db: &DatabaseConnection, ordered_ids: &[i64]) -> Result<()> {
+        let labels = Self::find_all(db).await?;
+        let known: HashSet<i64> = labels.iter().map(|l| l.id).collect();
+        let mut seen = HashSet::new();
+        for id in ordered_ids {
+            if !known.contains(id) {
+                return Err(AppError::not_found("Label", id.to_string()));
+            }
+            if !seen.insert(*id) {
+                return Err(AppError::validation(
+                    "label_ids",
+                    format!("Label {} is listed more than once", id),
+                ));
+            }
+        }
+
+        let current: HashMap<i64, i32> = labels.iter().map(|l| (l.id, l.sort_order)).collect();
+        let order = ordered_ids
+            .iter()
+            .copied()
+            .chain(labels.iter().map(|l| l.id).filter(|id| !seen.contains(id)));
+
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+        // Positions start at 1 so reordered labels can be told from new
+        // labels that were never placed
+        for (position, id) in order.enumerate() {
+            let sort_order = position as i32 + 1;
+            if current.get(&id) == Some(&sort_order) {
+                continue;
+            }
+            label::Entity::update_many()
+                .col_expr(label::Column::SortOrder, Expr::value(sort_order))
+                .filter(label::Column::Id.eq(id))
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to reorder labels: {}", e)))?;
+        }
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        info!("Reordered {} labels", labels.len());
         Ok(())
     }
 
+    /// Per label: number of non-deleted papers, number of clips, and when a
+    /// labelled paper or clip was last updated. Labels without documents
+    /// are absent from the map.
+    pub async fn usage_stats(
+        db: &DatabaseConnection,
+    ) -> Result<HashMap<i64, (u64, u64, Option<DateTime<Utc>>)>> {
+        let papers: Vec<(i64, i64, Option<DateTime<Utc>>)> = paper_label::Entity::find()
+            .select_only()
+            .column(paper_label::Column::LabelId)
+            .column_as(paper_label::Column::PaperId.count(), "paper_count")
+            .column_as(paper::Column::UpdatedAt.max(), "last_updated")
+            .join(JoinType::InnerJoin, paper_label::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .group_by(paper_label::Column::LabelId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count label papers: {}", e)))?;
+        let clips: Vec<(i64, i64, Option<DateTime<Utc>>)> = clip_label::Entity::find()
+            .select_only()
+            .column(clip_label::Column::LabelId)
+            .column_as(clip_label::Column::ClippingId.count(), "clip_count")
+            .column_as(clipping::Column::UpdatedAt.max(), "last_updated")
+            .join(JoinType::InnerJoin, clip_label::Relation::Clipping.def())
+            .group_by(clip_label::Column::LabelId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count label clips: {}", e)))?;
+
+        let mut stats: HashMap<i64, (u64, u64, Option<DateTime<Utc>>)> = HashMap::new();
+        for (label_id, count, last_updated) in papers {
+            stats.insert(label_id, (count as u64, 0, last_updated));
+        }
+        for (label_id, count, last_updated) in clips {
+            let entry = stats.entry(label_id).or_insert((0, 0, None));
+            entry.1 = count as u64;
+            entry.2 = entry.2.max(last_updated);
+        }
+        Ok(stats)
+    }
+
     /// Add label to paper
     pub async fn add_to_paper(db: &DatabaseConnection, paper_id: i64, label_id: i64) -> Result<()> {
         // Check if relation already exists
@@ -247,13 +750,149 @@ impl LabelRepository {
         Ok(result)
     }
 
-    /// Update document count for a label
-    async fn update_document_count(db: &DatabaseConnection, label_id: i64) -> Result<()> {
-        let count = paper_label::Entity::find()
+    /// Add label to clip
+    pub async fn add_to_clip(db: &DatabaseConnection, clip_id: i64, label_id: i64) -> Result<()> {
+        // Verify clipping and label exist
+        let clipping_exists = clipping::Entity::find_by_id(clip_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find clipping: {}", e)))?
+            .is_some();
+        if !clipping_exists {
+            return Err(AppError::not_found("Clipping", clip_id.to_string()));
+        }
+        if Self::find_by_id(db, label_id).await?.is_none() {
+            return Err(AppError::not_found("Label", label_id.to_string()));
+        }
+
+        let existing = clip_label::Entity::find()
+            .filter(clip_label::Column::ClippingId.eq(clip_id))
+            .filter(clip_label::Column::LabelId.eq(label_id))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to check existing relation: {}", e)))?;
+
+        if existing.is_none() {
+            let relation = clip_label::ActiveModel {
+                clipping_id: Set(clip_id),
+                label_id: Set(label_id),
+                ..Default::default()
+            };
+            relation
+                .insert(db)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to add label to clip: {}", e)))?;
+        }
+
+        Self::update_document_count(db, label_id).await?;
+
+        Ok(())
+    }
+
+    /// Remove label from clip
+    pub async fn remove_from_clip(
+        db: &DatabaseConnection,
+        clip_id: i64,
+        label_id: i64,
+    ) -> Result<()> {
+        clip_label::Entity::delete_many()
+            .filter(clip_label::Column::ClippingId.eq(clip_id))
+            .filter(clip_label::Column::LabelId.eq(label_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove label from clip: {}", e)))?;
+
+        Self::update_document_count(db, label_id).await?;
+
+        Ok(())
+    }
+
+    /// Remove all labels from a clip, e.g. before the clip is deleted
+    pub async fn remove_all_from_clip<C: ConnectionTrait>(db: &C, clip_id: i64) -> Result<()> {
+        let relations = clip_label::Entity::find()
+            .filter(clip_label::Column::ClippingId.eq(clip_id))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get clip-label relations: {}", e)))?;
+
+        clip_label::Entity::delete_many()
+            .filter(clip_label::Column::ClippingId.eq(clip_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove clip labels: {}", e)))?;
+
+        for relation in relations {
+            Self::update_document_count_on(db, relation.label_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Get labels for multiple clips
+    /// Returns a HashMap mapping clip id to its labels
+    pub async fn get_clip_labels_batch(
+        db: &DatabaseConnection,
+        clip_ids: &[i64],
+    ) -> Result<HashMap<i64, Vec<Label>>> {
+        if clip_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let relations = clip_label::Entity::find()
+            .filter(clip_label::Column::ClippingId.is_in(clip_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to get clip-label relations batch: {}", e))
+            })?;
+
+        let label_ids: Vec<i64> = relations.iter().map(|r| r.label_id).collect();
+
+        if label_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let labels = label::Entity::find()
+            .filter(label::Column::Id.is_in(label_ids))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get clip labels batch: {}", e)))?;
+
+        let label_map: HashMap<i64, Label> =
+            labels.into_iter().map(|l| (l.id, Label::from(l))).collect();
+
+        let mut result: HashMap<i64, Vec<Label>> = HashMap::new();
+        for relation in relations {
+            if let Some(label) = label_map.get(&relation.label_id).cloned() {
+                result.entry(relation.clipping_id).or_default().push(label);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Update document count for a label. Papers and clips both count as
+    /// documents.
+    pub async fn update_document_count(db: &DatabaseConnection, label_id: i64) -> Result<()> {
+        Self::update_document_count_on(db, label_id).await
+    }
+
+    /// Recount a label's papers and clips on any connection or transaction
+    pub(crate) async fn update_document_count_on<C: ConnectionTrait>(
+        db: &C,
+        label_id: i64,
+    ) -> Result<()> {
+        let paper_count = paper_label::Entity::find()
             .filter(paper_label::Column::LabelId.eq(label_id))
             .count(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to count label documents: {}", e)))?;
+        let clip_count = clip_label::Entity::find()
+            .filter(clip_label::Column::LabelId.eq(label_id))
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count label documents: {}", e)))?;
+        let count = paper_count + clip_count;
 
         let label = label::Entity::find_by_id(label_id)
             .one(db)
@@ -270,3 +909,307 @@ impl LabelRepository {
         Ok(())
     }
 }
+
+/// A labelled paper or clip
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Document {
+    Paper(i64),
+    Clip(i64),
+}
+
+/// `id` followed by its parent, grandparent and so on up to the top level
+fn ancestors(parents: &HashMap<i64, Option<i64>>, id: i64) -> Vec<i64> {
+    let mut chain = vec![id];
+    while let Some(&Some(parent)) = chain.last().and_then(|current| parents.get(current)) {
+        // Stop on a cycle left by inconsistent data
+        if chain.contains(&parent) {
+            break;
+        }
+        chain.push(parent);
+    }
+    chain
+}
+
+/// Build the nodes under `parent_id` with their counts. Also returns the
+/// documents of each subtree, so a document carrying several labels of it
+/// is only counted once.
+fn build_tree_recursive(
+    labels: &[Label],
+    parent_id: Option<i64>,
+    documents: &HashMap<i64, HashSet<Document>>,
+) -> Vec<(LabelNode, HashSet<Document>)> {
+    labels
+        .iter()
+        .filter(|label| label.parent_id == parent_id)
+        .map(|label| {
+            let mut subtree = documents.get(&label.id).cloned().unwrap_or_default();
+            let children = build_tree_recursive(labels, Some(label.id), documents)
+                .into_iter()
+                .map(|(child, child_documents)| {
+                    subtree.extend(child_documents);
+                    child
+                })
+                .collect();
+            let node = LabelNode {
+                id: label.id,
+                name: label.name.clone(),
+                color: label.color.clone(),
+                parent_id: label.parent_id,
+                sort_order: label.sort_order,
+                document_count: label.document_count,
+                recursive_count: subtree.len() as u64,
+                children,
+            };
+            (node, subtree)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::{ClippingRepository, PaperRepository};
+    use crate::testing::{label, test_db, ClipFixture, PaperFixture};
+
+    async fn document_count(db: &DatabaseConnection, label_id: i64) -> i32 {
+        LabelRepository::find_by_id(db, label_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .document_count
+    }
+
+    #[tokio::test]
+    async fn test_document_count_includes_papers_and_clips() {
+        let db = test_db().await;
+        PaperFixture::new("A paper")
+            .with_label("to-read")
+            .insert(&db)
+            .await;
+        let clip = ClipFixture::new("A clip")
+            .with_label("to-read")
+            .insert(&db)
+            .await;
+        let label = label(&db, "to-read").await;
+
+        // Linking twice is a no-op
+        LabelRepository::add_to_clip(&db, clip.id, label.id)
+            .await
+            .unwrap();
+        assert_eq!(document_count(&db, label.id).await, 2);
+
+        let labelled = ClippingRepository::find_filtered(&db, Some(label.id), None)
+            .await
+            .unwrap();
+        assert_eq!(labelled.len(), 1);
+
+        ClippingRepository::delete(&db, clip.id).await.unwrap();
+        assert_eq!(document_count(&db, label.id).await, 1);
+        assert!(ClippingRepository::find_by_id(&db, clip.id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_papers_and_clips() {
+        let db = test_db().await;
+        let both = PaperFixture::new("Both labels")
+            .with_label("ml")
+            .with_label("machine-learning")
+            .insert(&db)
+            .await;
+        let secondary_only = PaperFixture::new("Secondary only")
+            .with_label("machine-learning")
+            .insert(&db)
+            .await;
+        ClipFixture::new("A clip")
+            .with_label("AI")
+            .insert(&db)
+            .await;
+        let primary = label(&db, "ml").await;
+        let secondaries = [
+            label(&db, "machine-learning").await.id,
+            label(&db, "AI").await.id,
+        ];
+
+        let merged = LabelRepository::merge(&db, primary.id, &secondaries)
+            .await
+            .unwrap();
+
+        assert_eq!(merged.label.document_count, 3);
+        assert_eq!((merged.relations_moved, merged.duplicates_dropped), (2, 1));
+        for paper_id in [both.id, secondary_only.id] {
+            let labels = LabelRepository::get_paper_labels(&db, paper_id)
+                .await
+                .unwrap();
+            assert_eq!(labels.len(), 1);
+            assert_eq!(labels[0].id, primary.id);
+        }
+        assert_eq!(LabelRepository::find_all(&db).await.unwrap().len(), 1);
+        assert!(matches!(
+            LabelRepository::merge(&db, primary.id, &[primary.id]).await,
+            Err(AppError::ValidationError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reorder_and_new_labels_go_last() {
+        let db = test_db().await;
+        let a = label(&db, "a").await;
+        let b = label(&db, "b").await;
+        let c = label(&db, "c").await;
+
+        LabelRepository::reorder(&db, &[c.id, a.id]).await.unwrap();
+        label(&db, "0-new").await;
+
+        let names: Vec<String> = LabelRepository::find_all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.name)
+            .collect();
+        assert_eq!(names, vec!["c", "a", "b", "0-new"]);
+        assert!(LabelRepository::reorder(&db, &[b.id, b.id]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_unused_and_delete_many() {
+        let db = test_db().await;
+        PaperFixture::new("Trashed")
+            .with_label("in-trash")
+            .deleted()
+            .insert(&db)
+            .await;
+        ClipFixture::new("A clip")
+            .with_label("clipped")
+            .insert(&db)
+            .await;
+        let unused = [label(&db, "DL").await.id, label(&db, "old").await.id];
+
+        let found: Vec<i64> = LabelRepository::find_unused(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|l| l.id)
+            .collect();
+        assert_eq!(found, unused);
+
+        assert_eq!(LabelRepository::delete_many(&db, &unused).await.unwrap(), 2);
+        assert!(LabelRepository::find_unused(&db).await.unwrap().is_empty());
+        assert!(matches!(
+            LabelRepository::delete_many(&db, &unused).await,
+            Err(AppError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delete_unlinks_papers() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("A paper")
+            .with_label("to-read")
+            .with_label("important")
+            .insert(&db)
+            .await;
+        let label = label(&db, "to-read").await;
+
+        LabelRepository::delete(&db, label.id).await.unwrap();
+
+        let remaining = LabelRepository::get_paper_labels(&db, paper.id)
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "important");
+    }
+
+    async fn parent_of(db: &DatabaseConnection, label_id: i64) -> Option<i64> {
+        LabelRepository::find_by_id(db, label_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .parent_id
+    }
+
+    async fn move_label(db: &DatabaseConnection, id: i64, parent_id: Option<i64>) -> Result<Label> {
+        let update = UpdateLabel {
+            name: None,
+            color: None,
+            parent_id: Some(parent_id),
+        };
+        LabelRepository::update(db, id, update).await
+    }
+
+    #[tokio::test]
+    async fn test_label_tree_counts_and_filtering() {
+        let db = test_db().await;
+        let topics = label(&db, "topics").await;
+        let ml = label(&db, "ml").await;
+        let nlp = label(&db, "nlp").await;
+        move_label(&db, ml.id, Some(topics.id)).await.unwrap();
+        move_label(&db, nlp.id, Some(ml.id)).await.unwrap();
+        PaperFixture::new("Both")
+            .with_label("ml")
+            .with_label("nlp")
+            .insert(&db)
+            .await;
+        PaperFixture::new("NLP only")
+            .with_label("nlp")
+            .insert(&db)
+            .await;
+        ClipFixture::new("A clip")
+            .with_label("ml")
+            .insert(&db)
+            .await;
+
+        let tree = LabelRepository::load_tree(&db).await.unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!((tree[0].document_count, tree[0].recursive_count), (0, 3));
+        let ml_node = &tree[0].children[0];
+        assert_eq!((ml_node.document_count, ml_node.recursive_count), (2, 3));
+        let nlp_node = &ml_node.children[0];
+        assert_eq!((nlp_node.document_count, nlp_node.recursive_count), (2, 2));
+
+        let ids = LabelRepository::descendant_ids(&db, topics.id)
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![topics.id, ml.id, nlp.id]);
+        let papers = PaperRepository::find_by_labels(&db, &ids).await.unwrap();
+        assert_eq!(papers.len(), 2);
+
+        // Moving a label under itself or its own sub-label would be a cycle
+        for parent in [topics.id, nlp.id] {
+            assert!(matches!(
+                move_label(&db, topics.id, Some(parent)).await,
+                Err(AppError::ValidationError { .. })
+            ));
+        }
+
+        LabelRepository::delete(&db, ml.id).await.unwrap();
+        assert_eq!(parent_of(&db, nlp.id).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_merge_moves_child_labels() {
+        let db = test_db().await;
+        let root = label(&db, "root").await;
+        let secondary = label(&db, "secondary").await;
+        let child = label(&db, "child").await;
+        let middle = label(&db, "middle").await;
+        let primary = label(&db, "primary").await;
+        move_label(&db, secondary.id, Some(root.id)).await.unwrap();
+        move_label(&db, child.id, Some(secondary.id)).await.unwrap();
+        move_label(&db, middle.id, Some(secondary.id))
+            .await
+            .unwrap();
+        move_label(&db, primary.id, Some(middle.id)).await.unwrap();
+
+        LabelRepository::merge(&db, primary.id, &[secondary.id])
+            .await
+            .unwrap();
+
+        assert_eq!(parent_of(&db, child.id).await, Some(primary.id));
+        // The primary sits below `middle`, so `middle` moves up instead
+        assert_eq!(parent_of(&db, middle.id).await, Some(root.id));
+        assert_eq!(parent_of(&db, primary.id).await, Some(middle.id));
+    }
+}
@@ -10,11 +10,40 @@ pub mod keyword_repository;
 pub mod clipping_repository;
 pub mod search_repository;
 pub mod search_history_repository;
+pub mod api_key_repository;
+pub mod import_history_repository;
+pub mod reading_progress_repository;
+pub mod reference_repository;
+pub mod citation_repository;
+pub mod ai_summary_repository;
+pub mod embedding_repository;
+pub mod text_content_repository;
+pub mod stats_repository;
+pub mod activity_repository;
+pub mod note_repository;
+pub mod custom_field_repository;
+pub mod reading_queue_repository;
+pub mod open_access_repository;
 
 pub use paper_repository::PaperRepository;
 pub use category_repository::{CategoryRepository, TreeNodeData};
-pub use label_repository::LabelRepository;
-pub use author_repository::AuthorRepository;
+pub use label_repository::{LabelMerge, LabelRepository};
+pub use author_repository::{AuthorRepository, PaperAuthorEntry};
+pub use keyword_repository::KeywordRepository;
 pub use clipping_repository::ClippingRepository;
-pub use search_repository::SearchRepository;
+pub use search_repository::{PaperSearchFilter, SearchRepository};
 pub use search_history_repository::SearchHistoryRepository;
+pub use api_key_repository::ApiKeyRepository;
+pub use import_history_repository::ImportHistoryRepository;
+pub use reading_progress_repository::ReadingProgressRepository;
+pub use reference_repository::ReferenceRepository;
+pub use citation_repository::CitationRepository;
+pub use ai_summary_repository::AiSummaryRepository;
+pub use embedding_repository::{EmbeddingRepository, NewEmbedding};
+pub use text_content_repository::TextContentRepository;
+pub use stats_repository::StatsRepository;
+pub use activity_repository::ActivityRepository;
+pub use note_repository::NoteRepository;
+pub use custom_field_repository::CustomFieldRepository;
+pub use reading_queue_repository::ReadingQueueRepository;
+pub use open_access_repository::OpenAccessRepository;
@@ -0,0 +1,198 @@
+//! Paper note version repository for SQLite using SeaORM
+
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::database::entities::{paper, paper_note_version};
+use crate::models::{NoteFormat, NoteVersion};
+use crate::repository::SearchRepository;
+use crate::sys::error::{AppError, Result};
+
+/// Repository for versioned paper notes. Every save adds a version; the
+/// newest one is the current note and its plain text is kept in
+/// `paper.notes`.
+pub struct NoteRepository;
+
+impl NoteRepository {
+    /// Current note of a paper
+    pub async fn latest(db: &DatabaseConnection, paper_id: i64) -> Result<Option<NoteVersion>> {
+        paper_note_version::Entity::find()
+            .filter(paper_note_version::Column::PaperId.eq(paper_id))
+            .order_by_desc(paper_note_version::Column::Version)
+            .one(db)
+            .await
+            .map(|model| model.map(NoteVersion::from))
+            .map_err(|e| AppError::generic(format!("Failed to get paper notes: {}", e)))
+    }
+
+    /// Every version of a paper's notes, newest first
+    pub async fn history(db: &DatabaseConnection, paper_id: i64) -> Result<Vec<NoteVersion>> {
+        let versions = paper_note_version::Entity::find()
+            .filter(paper_note_version::Column::PaperId.eq(paper_id))
+            .order_by_desc(paper_note_version::Column::Version)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get note history: {}", e)))?;
+        Ok(versions.into_iter().map(NoteVersion::from).collect())
+    }
+
+    /// Store `content` as the new current note of a paper
+    pub async fn save(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        content: &str,
+        format: NoteFormat,
+    ) -> Result<NoteVersion> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let saved = Self::save_on(&txn, paper_id, content, format).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(saved)
+    }
+
+    /// Make an earlier version current again by saving a copy of it as a new
+    /// version, so the history is never rewritten
+    pub async fn restore(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        version: u32,
+    ) -> Result<NoteVersion> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let old: NoteVersion = paper_note_version::Entity::find()
+            .filter(paper_note_version::Column::PaperId.eq(paper_id))
+            .filter(paper_note_version::Column::Version.eq(version as i32))
+            .one(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get note version: {}", e)))?
+            .ok_or_else(|| {
+                AppError::not_found("Note version", format!("{}@{}", paper_id, version))
+            })?
+            .into();
+        let saved = Self::save_on(&txn, paper_id, &old.content, old.content_format).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(saved)
+    }
+
+    pub(crate) async fn save_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        content: &str,
+        format: NoteFormat,
+    ) -> Result<NoteVersion> {
+        paper::Entity::find_by_id(paper_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find paper: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+        let last: Option<i32> = paper_note_version::Entity::find()
+            .select_only()
+            .column_as(paper_note_version::Column::Version.max(), "version")
+            .filter(paper_note_version::Column::PaperId.eq(paper_id))
+            .into_tuple::<Option<i32>>()
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get note version: {}", e)))?
+            .flatten();
+
+        let now = chrono::Utc::now();
+        let model = paper_note_version::ActiveModel {
+            paper_id: Set(paper_id),
+            version: Set(last.unwrap_or(0) + 1),
+            content: Set(content.to_string()),
+            content_format: Set(format.as_str().to_string()),
+            created_at: Set(now),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to save paper notes: {}", e)))?;
+
+        let plain_text = Self::plain_text(content, format);
+        paper::Entity::update_many()
+            .col_expr(paper::Column::Notes, Expr::value(plain_text.as_str()))
+            .col_expr(paper::Column::UpdatedAt, Expr::value(now))
+            .filter(paper::Column::Id.eq(paper_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update paper notes: {}", e)))?;
+        SearchRepository::index_note_on(db, paper_id, &plain_text).await?;
+
+        Ok(model.into())
+    }
+
+    /// Searchable text of a note: HTML is stripped, other formats are kept
+    pub fn plain_text(content: &str, format: NoteFormat) -> String {
+        match format {
+            NoteFormat::Html => SearchRepository::strip_html(content),
+            NoteFormat::PlainText | NoteFormat::Markdown => content.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PaperRepository;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_versions_and_restore() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Noted").insert(&db).await;
+        assert!(NoteRepository::latest(&db, paper.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        NoteRepository::save(&db, paper.id, "first draft", NoteFormat::PlainText)
+            .await
+            .unwrap();
+        let html =
+            NoteRepository::save(&db, paper.id, "<p>Second &amp; final</p>", NoteFormat::Html)
+                .await
+                .unwrap();
+        assert_eq!(html.version, 2);
+        let stored = PaperRepository::find_by_id(&db, paper.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.notes.as_deref(), Some("Second & final"));
+
+        let restored = NoteRepository::restore(&db, paper.id, 1).await.unwrap();
+        assert_eq!(restored.version, 3);
+        assert_eq!(restored.content_format, NoteFormat::PlainText);
+        let stored = PaperRepository::find_by_id(&db, paper.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.notes.as_deref(), Some("first draft"));
+
+        let history = NoteRepository::history(&db, paper.id).await.unwrap();
+        assert_eq!(
+            history.iter().map(|v| v.version).collect::<Vec<_>>(),
+            [3, 2, 1]
+        );
+        assert!(NoteRepository::restore(&db, paper.id, 9).await.is_err());
+        assert!(
+            NoteRepository::save(&db, paper.id + 100, "x", NoteFormat::Markdown)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,80 @@
+//! Open access cache repository for SQLite using SeaORM
+
+use std::collections::HashMap;
+
+use sea_orm::*;
+
+use crate::database::entities::open_access_cache;
+use crate::sys::error::{AppError, Result};
+
+/// Open access status fetched for a paper, waiting to be cached
+pub struct NewOpenAccess {
+    pub paper_id: i64,
+    pub doi: String,
+    pub is_oa: bool,
+    pub oa_status: String,
+    pub oa_url: Option<String>,
+    pub license: Option<String>,
+}
+
+/// Repository for cached Unpaywall lookups
+pub struct OpenAccessRepository;
+
+impl OpenAccessRepository {
+    /// Cached entries of the given papers, by paper id
+    pub async fn find_by_paper_ids(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+    ) -> Result<HashMap<i64, open_access_cache::Model>> {
+        if paper_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows = open_access_cache::Entity::find()
+            .filter(open_access_cache::Column::PaperId.is_in(paper_ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get open access cache: {}", e)))?;
+
+        Ok(rows.into_iter().map(|row| (row.paper_id, row)).collect())
+    }
+
+    /// Cache a lookup, replacing the paper's previous entry
+    pub async fn save(
+        db: &DatabaseConnection,
+        entry: NewOpenAccess,
+    ) -> Result<open_access_cache::Model> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        open_access_cache::Entity::delete_many()
+            .filter(open_access_cache::Column::PaperId.eq(entry.paper_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to replace open access cache: {}", e))
+            })?;
+
+        let saved = open_access_cache::ActiveModel {
+            paper_id: Set(entry.paper_id),
+            doi: Set(entry.doi),
+            is_oa: Set(entry.is_oa),
+            oa_status: Set(entry.oa_status),
+            oa_url: Set(entry.oa_url),
+            license: Set(entry.license),
+            updated_at: Set(chrono::Utc::now()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to save open access cache: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(saved)
+    }
+}
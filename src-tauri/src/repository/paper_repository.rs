@@ -1,10 +1,16 @@
 //! Paper repository for SQLite using SeaORM
 
-use sea_orm::*;
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, *};
 use tracing::info;
 
-use crate::database::entities::{attachment, paper, paper_category};
-use crate::models::{Attachment, CreatePaper, Paper, UpdatePaper};
+use crate::database::entities::{
+    attachment, paper, paper_author, paper_category, paper_citation, paper_keyword, paper_label,
+};
+use crate::models::{
+    calculate_attachment_hash, Attachment, CreatePaper, NoteFormat, Paper, UpdatePaper,
+};
+use crate::repository::{NoteRepository, ReadingQueueRepository};
 use crate::sys::error::{AppError, Result};
 
 /// Repository for Paper operations
@@ -35,12 +41,33 @@ impl PaperRepository {
         Ok(papers.into_iter().map(Paper::from).collect())
     }
 
-    /// Find non-deleted papers with pagination
+    /// Abstracts of all non-deleted papers except `paper_id`
+    pub async fn find_other_abstracts(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Vec<String>> {
+        let abstracts: Vec<Option<String>> = paper::Entity::find()
+            .select_only()
+            .column(paper::Column::AbstractText)
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Id.ne(paper_id))
+            .filter(paper::Column::AbstractText.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query abstracts: {}", e)))?;
+
+        Ok(abstracts.into_iter().flatten().collect())
+    }
+
+    /// Find non-deleted papers with pagination; without a `limit` every
+    /// paper after `offset` is returned
     pub async fn find_all_paginated(
         db: &DatabaseConnection,
         offset: u64,
-        limit: u64,
+        limit: impl Into<Option<u64>>,
     ) -> Result<Vec<Paper>> {
+        let limit = limit.into();
         let papers = paper::Entity::find()
             .filter(paper::Column::DeletedAt.is_null())
             .order_by_desc(paper::Column::CreatedAt)
@@ -51,7 +78,7 @@ impl PaperRepository {
             .map_err(|e| AppError::generic(format!("Failed to query paginated papers: {}", e)))?;
 
         info!(
-            "Found {} papers (offset={}, limit={})",
+            "Found {} papers (offset={}, limit={:?})",
             papers.len(),
             offset,
             limit
@@ -59,6 +86,120 @@ impl PaperRepository {
         Ok(papers.into_iter().map(Paper::from).collect())
     }
 
+    /// Count non-deleted papers that have a DOI
+    pub async fn count_with_doi(db: &DatabaseConnection) -> Result<i64> {
+        let count = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Doi.is_not_null())
+            .filter(paper::Column::Doi.ne(""))
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count papers with DOI: {}", e)))?;
+
+        Ok(count as i64)
+    }
+
+    /// Next `limit` non-deleted papers with a DOI and an id above
+    /// `after_id`, in id order. Used to walk the library in batches.
+    pub async fn find_with_doi_after(
+        db: &DatabaseConnection,
+        after_id: i64,
+        limit: u64,
+    ) -> Result<Vec<Paper>> {
+        let papers = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Doi.is_not_null())
+            .filter(paper::Column::Doi.ne(""))
+            .filter(paper::Column::Id.gt(after_id))
+            .order_by_asc(paper::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers with DOI: {}", e)))?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Next `limit` non-deleted papers without a language and with an id
+    /// above `after_id`, in id order. Used to walk the library in batches.
+    pub async fn find_without_language_after(
+        db: &DatabaseConnection,
+        after_id: i64,
+        limit: u64,
+    ) -> Result<Vec<Paper>> {
+        let papers = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(paper::Column::Language.is_null())
+                    .add(paper::Column::Language.eq("")),
+            )
+            .filter(paper::Column::Id.gt(after_id))
+            .order_by_asc(paper::Column::Id)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query papers without language: {}", e))
+            })?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Non-deleted papers with a DOI whose citation count was never fetched
+    /// or last fetched before `refreshed_before`, in id order. With
+    /// `paper_ids`, only those papers are considered and the age is ignored.
+    pub async fn find_for_citation_refresh(
+        db: &DatabaseConnection,
+        paper_ids: Option<&[i64]>,
+        refreshed_before: DateTime<Utc>,
+    ) -> Result<Vec<Paper>> {
+        let mut query = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Doi.is_not_null())
+            .filter(paper::Column::Doi.ne(""));
+        query = match paper_ids {
+            Some(ids) => query.filter(paper::Column::Id.is_in(ids.to_vec())),
+            None => query.filter(
+                Condition::any()
+                    .add(paper::Column::LastCitationRefreshAt.is_null())
+                    .add(paper::Column::LastCitationRefreshAt.lt(refreshed_before)),
+            ),
+        };
+
+        let papers = query
+            .order_by_asc(paper::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!(
+                    "Failed to query papers for citation refresh: {}",
+                    e
+                ))
+            })?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Record that the citation counts of `ids` were fetched at `refreshed_at`
+    pub async fn mark_citations_refreshed(
+        db: &DatabaseConnection,
+        ids: &[i64],
+        refreshed_at: DateTime<Utc>,
+    ) -> Result<()> {
+        paper::Entity::update_many()
+            .col_expr(
+                paper::Column::LastCitationRefreshAt,
+                Expr::value(refreshed_at),
+            )
+            .filter(paper::Column::Id.is_in(ids.to_vec()))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to record citation refresh: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Find all deleted papers (trash)
     pub async fn find_deleted(db: &DatabaseConnection) -> Result<Vec<Paper>> {
         let papers = paper::Entity::find()
@@ -82,6 +223,141 @@ impl PaperRepository {
         Ok(count as i64)
     }
 
+    /// One page of starred non-deleted papers and the number of starred
+    /// papers, most recently starred first
+    pub async fn find_starred_paginated(
+        db: &DatabaseConnection,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Paper>, i64)> {
+        let query = paper::Entity::find()
+            .filter(paper::Column::IsStarred.eq(true))
+            .filter(paper::Column::DeletedAt.is_null());
+
+        let total = query
+            .clone()
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count starred papers: {}", e)))?;
+        let papers = query
+            .order_by_desc(paper::Column::StarredAt)
+            .order_by_desc(paper::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query starred papers: {}", e)))?;
+
+        Ok((papers.into_iter().map(Paper::from).collect(), total as i64))
+    }
+
+    /// One page of non-deleted papers in `language` and the number of such
+    /// papers, newest first
+    pub async fn find_by_language_paginated(
+        db: &DatabaseConnection,
+        language: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Paper>, i64)> {
+        let query = paper::Entity::find()
+            .filter(paper::Column::Language.eq(language))
+            .filter(paper::Column::DeletedAt.is_null());
+
+        let total =
+            query.clone().count(db).await.map_err(|e| {
+                AppError::generic(format!("Failed to count papers by language: {}", e))
+            })?;
+        let papers = query
+            .order_by_desc(paper::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers by language: {}", e)))?;
+
+        Ok((papers.into_iter().map(Paper::from).collect(), total as i64))
+    }
+
+    /// One page of non-deleted papers added in `[from, until)` and the
+    /// number of such papers, newest first. A missing bound is open.
+    pub async fn find_created_between_paginated(
+        db: &DatabaseConnection,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Paper>, i64)> {
+        let mut query = paper::Entity::find().filter(paper::Column::DeletedAt.is_null());
+        if let Some(from) = from {
+            query = query.filter(paper::Column::CreatedAt.gte(from));
+        }
+        if let Some(until) = until {
+            query = query.filter(paper::Column::CreatedAt.lt(until));
+        }
+
+        let total = query.clone().count(db).await.map_err(|e| {
+            AppError::generic(format!("Failed to count papers by date added: {}", e))
+        })?;
+        let papers = query
+            .order_by_desc(paper::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query papers by date added: {}", e))
+            })?;
+
+        Ok((papers.into_iter().map(Paper::from).collect(), total as i64))
+    }
+
+    /// Flip the star of a paper and return the new state. Papers in the
+    /// trash cannot be starred; the star is kept while a paper is in the
+    /// trash, so restoring it brings the star back.
+    pub async fn toggle_star(db: &DatabaseConnection, id: i64) -> Result<bool> {
+        let paper = Self::find_model(db, id).await?;
+        let starred = !paper.is_starred;
+        Self::write_star(db, paper, starred).await?;
+        Ok(starred)
+    }
+
+    /// Star or unstar a paper. Starring a starred paper keeps the time it
+    /// was first starred.
+    pub async fn set_star(db: &DatabaseConnection, id: i64, starred: bool) -> Result<()> {
+        let paper = Self::find_model(db, id).await?;
+        if paper.is_starred == starred {
+            return Ok(());
+        }
+        Self::write_star(db, paper, starred).await
+    }
+
+    async fn find_model(db: &DatabaseConnection, id: i64) -> Result<paper::Model> {
+        paper::Entity::find_by_id(id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find paper: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Paper", id.to_string()))
+    }
+
+    async fn write_star(db: &DatabaseConnection, paper: paper::Model, starred: bool) -> Result<()> {
+        if starred && paper.deleted_at.is_some() {
+            return Err(AppError::validation(
+                "paper_id",
+                "Papers in the trash cannot be starred",
+            ));
+        }
+
+        let mut paper: paper::ActiveModel = paper.into();
+        paper.is_starred = Set(starred);
+        paper.starred_at = Set(starred.then(chrono::Utc::now));
+        paper
+            .update(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update paper star: {}", e)))?;
+
+        Ok(())
+    }
+
     /// Find paper by ID
     pub async fn find_by_id(db: &DatabaseConnection, id: i64) -> Result<Option<Paper>> {
         let paper = paper::Entity::find_by_id(id)
@@ -92,6 +368,21 @@ impl PaperRepository {
         Ok(paper.map(Paper::from))
     }
 
+    /// Find papers by ID, in no particular order. Unknown IDs are skipped.
+    pub async fn find_by_ids(db: &DatabaseConnection, ids: &[i64]) -> Result<Vec<Paper>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let papers = paper::Entity::find()
+            .filter(paper::Column::Id.is_in(ids.to_vec()))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get papers: {}", e)))?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
     /// Find paper by DOI
     pub async fn find_by_doi(db: &DatabaseConnection, doi: &str) -> Result<Option<Paper>> {
         let paper = paper::Entity::find()
@@ -103,6 +394,38 @@ impl PaperRepository {
         Ok(paper.map(Paper::from))
     }
 
+    /// Map DOIs to the ids of non-deleted papers that have them. DOIs are
+    /// compared case-insensitively and the map is keyed by lowercase DOI.
+    pub async fn find_ids_by_dois(
+        db: &DatabaseConnection,
+        dois: &[String],
+    ) -> Result<std::collections::HashMap<String, i64>> {
+        if dois.is_empty() {
+            return Ok(std::collections::HashMap::new());
+        }
+        let wanted: std::collections::HashSet<String> =
+            dois.iter().map(|d| d.trim().to_lowercase()).collect();
+
+        let papers: Vec<(i64, Option<String>)> = paper::Entity::find()
+            .select_only()
+            .column(paper::Column::Id)
+            .column(paper::Column::Doi)
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Doi.is_not_null())
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers by DOI: {}", e)))?;
+
+        Ok(papers
+            .into_iter()
+            .filter_map(|(id, doi)| {
+                let doi = doi?.trim().to_lowercase();
+                wanted.contains(&doi).then_some((doi, id))
+            })
+            .collect())
+    }
+
     /// Find paper by URL
     pub async fn find_by_url(db: &DatabaseConnection, url: &str) -> Result<Option<Paper>> {
         let paper = paper::Entity::find()
@@ -114,6 +437,33 @@ impl PaperRepository {
         Ok(paper.map(Paper::from))
     }
 
+    /// Find paper by normalized ISBN-13
+    pub async fn find_by_isbn(db: &DatabaseConnection, isbn: &str) -> Result<Option<Paper>> {
+        let paper = paper::Entity::find()
+            .filter(paper::Column::Isbn.eq(isbn))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query paper by ISBN: {}", e)))?;
+
+        Ok(paper.map(Paper::from))
+    }
+
+    /// Find a non-deleted paper imported from arXiv by its arXiv ID
+    pub async fn find_by_arxiv_id(
+        db: &DatabaseConnection,
+        arxiv_id: &str,
+    ) -> Result<Option<Paper>> {
+        let paper = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .filter(paper::Column::Url.contains("arxiv.org/"))
+            .filter(paper::Column::Url.contains(arxiv_id))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query paper by arXiv ID: {}", e)))?;
+
+        Ok(paper.map(Paper::from))
+    }
+
     /// Create a new paper
     pub async fn create(db: &DatabaseConnection, create: CreatePaper) -> Result<Paper> {
         let now = chrono::Utc::now();
@@ -134,12 +484,14 @@ impl PaperRepository {
             notes: Set(None),
             attachment_path: Set(create.attachment_path),
             attachment_count: Set(0),
+            is_starred: Set(false),
             created_at: Set(now),
             updated_at: Set(now),
             deleted_at: Set(None),
             publisher: Set(create.publisher),
             issn: Set(create.issn),
             language: Set(create.language),
+            isbn: Set(create.isbn),
             ..Default::default()
         };
 
@@ -151,18 +503,47 @@ impl PaperRepository {
         Ok(Paper::from(result))
     }
 
-    /// Update paper
+    /// Update paper. Changed notes are saved as a new plain-text note
+    /// version in the same transaction.
     pub async fn update(db: &DatabaseConnection, id: i64, update: UpdatePaper) -> Result<Paper> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let updated = Self::update_on(&txn, id, update).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(updated)
+    }
+
+    async fn update_on<C: ConnectionTrait>(db: &C, id: i64, update: UpdatePaper) -> Result<Paper> {
         let paper = paper::Entity::find_by_id(id)
             .one(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to find paper: {}", e)))?
             .ok_or_else(|| AppError::not_found("Paper", id.to_string()))?;
 
+        // Papers without an attachment directory name use the hash of their
+        // title; pin it so a new title keeps the directory
+        let pinned_dir = (paper.attachment_path.is_none()
+            && update.attachment_path.is_none()
+            && update.title.as_ref().is_some_and(|t| *t != paper.title))
+        .then(|| calculate_attachment_hash(&paper.title));
+        let notes = update
+            .notes
+            .filter(|notes| paper.notes.as_ref() != Some(notes));
+
         let mut paper: paper::ActiveModel = paper.into();
         if let Some(title) = update.title {
             paper.title = Set(title);
         }
+        if let Some(dir) = pinned_dir {
+            paper.attachment_path = Set(Some(dir));
+        }
         if let Some(abstract_text) = update.abstract_text {
             paper.abstract_text = Set(Some(abstract_text));
         }
@@ -196,9 +577,6 @@ impl PaperRepository {
         if let Some(read_status) = update.read_status {
             paper.read_status = Set(read_status);
         }
-        if let Some(notes) = update.notes {
-            paper.notes = Set(Some(notes));
-        }
         if let Some(attachment_path) = update.attachment_path {
             paper.attachment_path = Set(Some(attachment_path));
         }
@@ -215,15 +593,52 @@ impl PaperRepository {
 
         paper.updated_at = Set(chrono::Utc::now());
 
-        let result = paper
-            .update(db)
+        let mut result = Paper::from(
+            paper
+                .update(db)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to update paper: {}", e)))?,
+        );
+
+        if let Some(notes) = notes {
+            NoteRepository::save_on(db, id, &notes, NoteFormat::PlainText).await?;
+            result.notes = Some(notes);
+        }
+
+        Ok(result)
+    }
+
+    /// Set the citation count of a paper
+    pub async fn update_citation_count(
+        db: &DatabaseConnection,
+        id: i64,
+        citation_count: i32,
+    ) -> Result<()> {
+        paper::Entity::update_many()
+            .col_expr(paper::Column::CitationCount, Expr::value(citation_count))
+            .col_expr(paper::Column::UpdatedAt, Expr::value(chrono::Utc::now()))
+            .filter(paper::Column::Id.eq(id))
+            .exec(db)
             .await
-            .map_err(|e| AppError::generic(format!("Failed to update paper: {}", e)))?;
+            .map_err(|e| AppError::generic(format!("Failed to update citation count: {}", e)))?;
 
-        Ok(Paper::from(result))
+        Ok(())
+    }
+
+    pub async fn update_language(db: &DatabaseConnection, id: i64, language: &str) -> Result<()> {
+        paper::Entity::update_many()
+            .col_expr(paper::Column::Language, Expr::value(language))
+            .col_expr(paper::Column::UpdatedAt, Expr::value(chrono::Utc::now()))
+            .filter(paper::Column::Id.eq(id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update language: {}", e)))?;
+
+        Ok(())
     }
 
-    /// Soft delete paper (move to trash)
+    /// Soft delete paper (move to trash). The paper also leaves the reading
+    /// queue.
     pub async fn soft_delete(db: &DatabaseConnection, id: i64) -> Result<()> {
         let paper = paper::Entity::find_by_id(id)
             .one(db)
@@ -237,6 +652,7 @@ impl PaperRepository {
             .update(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to soft delete paper: {}", e)))?;
+        ReadingQueueRepository::remove_on(db, id).await?;
 
         Ok(())
     }
@@ -261,19 +677,46 @@ impl PaperRepository {
 
     /// Permanently delete paper
     pub async fn delete(db: &DatabaseConnection, id: i64) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        // Citation edges point at the paper from both ends
+        paper_citation::Entity::delete_many()
+            .filter(
+                Condition::any()
+                    .add(paper_citation::Column::CitingPaperId.eq(id))
+                    .add(paper_citation::Column::CitedPaperId.eq(id)),
+            )
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete citations: {}", e)))?;
+
         paper::Entity::delete_by_id(id)
-            .exec(db)
+            .exec(&txn)
             .await
             .map_err(|e| AppError::generic(format!("Failed to delete paper: {}", e)))?;
 
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
         Ok(())
     }
 
     /// Search papers using LIKE query (basic search)
-    pub async fn search(db: &DatabaseConnection, query: &str) -> Result<Vec<Paper>> {
+    pub async fn search(
+        db: &DatabaseConnection,
+        query: &str,
+        starred_only: bool,
+    ) -> Result<Vec<Paper>> {
         let pattern = format!("%{}%", query);
-        let papers = paper::Entity::find()
-            .filter(paper::Column::DeletedAt.is_null())
+        let mut select = paper::Entity::find().filter(paper::Column::DeletedAt.is_null());
+        if starred_only {
+            select = select.filter(paper::Column::IsStarred.eq(true));
+        }
+        let papers = select
             .filter(
                 Condition::any()
                     .add(paper::Column::Title.like(&pattern))
@@ -318,11 +761,163 @@ impl PaperRepository {
         Ok(papers.into_iter().map(Paper::from).collect())
     }
 
+    /// Find non-deleted papers carrying any of `label_ids`, newest first
+    pub async fn find_by_labels(db: &DatabaseConnection, label_ids: &[i64]) -> Result<Vec<Paper>> {
+        let paper_ids: Vec<i64> = paper_label::Entity::find()
+            .select_only()
+            .column(paper_label::Column::PaperId)
+            .filter(paper_label::Column::LabelId.is_in(label_ids.to_vec()))
+            .distinct()
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to get paper-label relations: {}", e))
+            })?;
+
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let papers = paper::Entity::find()
+            .filter(paper::Column::Id.is_in(paper_ids))
+            .filter(paper::Column::DeletedAt.is_null())
+            .order_by_desc(paper::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers by label: {}", e)))?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Find non-deleted papers by an author, newest first
+    pub async fn find_by_author(db: &DatabaseConnection, author_id: i64) -> Result<Vec<Paper>> {
+        let relations = paper_author::Entity::find()
+            .filter(paper_author::Column::AuthorId.eq(author_id))
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to get paper-author relations: {}", e))
+            })?;
+
+        let paper_ids: Vec<i64> = relations.iter().map(|r| r.paper_id).collect();
+
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let papers = paper::Entity::find()
+            .filter(paper::Column::Id.is_in(paper_ids))
+            .filter(paper::Column::DeletedAt.is_null())
+            .order_by_desc(paper::Column::PublicationYear)
+            .order_by_desc(paper::Column::CreatedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers by author: {}", e)))?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Find non-deleted papers linked to a keyword, newest first. Returns one
+    /// page of papers and the total number of matching papers.
+    pub async fn find_by_keyword_paginated(
+        db: &DatabaseConnection,
+        keyword_id: i64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Paper>, i64)> {
+        let linked = paper_keyword::Entity::find()
+            .select_only()
+            .column(paper_keyword::Column::PaperId)
+            .filter(paper_keyword::Column::KeywordId.eq(keyword_id))
+            .into_query();
+        let query = paper::Entity::find()
+            .filter(paper::Column::Id.in_subquery(linked))
+            .filter(paper::Column::DeletedAt.is_null());
+
+        let total =
+            query.clone().count(db).await.map_err(|e| {
+                AppError::generic(format!("Failed to count papers by keyword: {}", e))
+            })?;
+        let papers = query
+            .order_by_desc(paper::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query papers by keyword: {}", e)))?;
+
+        Ok((papers.into_iter().map(Paper::from).collect(), total as i64))
+    }
+
     /// Set paper category (replaces existing category)
     pub async fn set_category(
         db: &DatabaseConnection,
         paper_id: i64,
         category_id: Option<i64>,
+    ) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        Self::set_category_on(&txn, paper_id, category_id).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Set the category for several papers inside a single transaction.
+    /// With `keep_existing` the category is added next to the papers' other
+    /// categories instead of replacing them; clearing always removes all.
+    ///
+    /// A paper that cannot be updated (e.g. it does not exist) is reported in
+    /// the returned list and skipped; the remaining papers are still committed.
+    pub async fn bulk_set_category(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+        category_id: Option<i64>,
+        keep_existing: bool,
+    ) -> Result<Vec<(i64, Result<()>)>> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let mut results = Vec::with_capacity(paper_ids.len());
+        for &paper_id in paper_ids {
+            let result = match paper::Entity::find_by_id(paper_id)
+                .filter(paper::Column::DeletedAt.is_null())
+                .one(&txn)
+                .await
+            {
+                Ok(Some(_)) => match category_id {
+                    Some(cat_id) if keep_existing => {
+                        Self::add_category_on(&txn, paper_id, cat_id).await
+                    }
+                    _ => Self::set_category_on(&txn, paper_id, category_id).await,
+                },
+                Ok(None) => Err(AppError::not_found("Paper", paper_id.to_string())),
+                Err(e) => Err(AppError::generic(format!("Failed to find paper: {}", e))),
+            };
+            results.push((paper_id, result));
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(results)
+    }
+
+    /// Replace a paper's category relation on any connection or transaction
+    async fn set_category_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        category_id: Option<i64>,
     ) -> Result<()> {
         // First delete existing category relation
         paper_category::Entity::delete_many()
@@ -347,15 +942,127 @@ impl PaperRepository {
         Ok(())
     }
 
-    /// Get paper's category ID
-    pub async fn get_category_id(db: &DatabaseConnection, paper_id: i64) -> Result<Option<i64>> {
-        let relation = paper_category::Entity::find()
+    /// File a paper under one more category, keeping its other categories.
+    /// Adding a category the paper already has does nothing.
+    pub async fn add_category(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        category_id: i64,
+    ) -> Result<()> {
+        Self::add_category_on(db, paper_id, category_id).await
+    }
+
+    async fn add_category_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        category_id: i64,
+    ) -> Result<()> {
+        let existing = paper_category::Entity::find()
             .filter(paper_category::Column::PaperId.eq(paper_id))
+            .filter(paper_category::Column::CategoryId.eq(category_id))
             .one(db)
             .await
             .map_err(|e| AppError::generic(format!("Failed to get paper category: {}", e)))?;
+        if existing.is_some() {
+            return Ok(());
+        }
+
+        paper_category::ActiveModel {
+            paper_id: Set(paper_id),
+            category_id: Set(category_id),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to add paper category: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Take a paper out of one category, keeping its other categories
+    pub async fn remove_category(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        category_id: i64,
+    ) -> Result<()> {
+        let result = paper_category::Entity::delete_many()
+            .filter(paper_category::Column::PaperId.eq(paper_id))
+            .filter(paper_category::Column::CategoryId.eq(category_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove paper category: {}", e)))?;
+
+        if result.rows_affected == 0 {
+            return Err(AppError::not_found(
+                "PaperCategory",
+                format!("{}/{}", paper_id, category_id),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get all category IDs of a paper in the order it was filed under them
+    pub async fn get_category_ids(db: &DatabaseConnection, paper_id: i64) -> Result<Vec<i64>> {
+        let relations = paper_category::Entity::find()
+            .filter(paper_category::Column::PaperId.eq(paper_id))
+            .order_by_asc(paper_category::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper categories: {}", e)))?;
+
+        Ok(relations.into_iter().map(|r| r.category_id).collect())
+    }
+
+    /// Number of non-deleted papers per category. Categories without papers
+    /// are absent from the map.
+    pub async fn count_by_category(
+        db: &DatabaseConnection,
+    ) -> Result<std::collections::HashMap<i64, u64>> {
+        let counts: Vec<(i64, i64)> = paper_category::Entity::find()
+            .select_only()
+            .column(paper_category::Column::CategoryId)
+            .column_as(paper_category::Column::PaperId.count(), "paper_count")
+            .join(JoinType::InnerJoin, paper_category::Relation::Paper.def())
+            .filter(paper::Column::DeletedAt.is_null())
+            .group_by(paper_category::Column::CategoryId)
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count papers by category: {}", e)))?;
+
+        Ok(counts
+            .into_iter()
+            .map(|(category_id, count)| (category_id, count as u64))
+            .collect())
+    }
+
+    /// Get category IDs for multiple papers, each list in the order the
+    /// paper was filed under them. Papers without a category are absent from
+    /// the map.
+    pub async fn get_category_ids_batch(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+    ) -> Result<std::collections::HashMap<i64, Vec<i64>>> {
+        let mut map: std::collections::HashMap<i64, Vec<i64>> = std::collections::HashMap::new();
+        if paper_ids.is_empty() {
+            return Ok(map);
+        }
+
+        let relations = paper_category::Entity::find()
+            .filter(paper_category::Column::PaperId.is_in(paper_ids.to_vec()))
+            .order_by_asc(paper_category::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to get paper categories batch: {}", e))
+            })?;
 
-        Ok(relation.map(|r| r.category_id))
+        for relation in relations {
+            map.entry(relation.paper_id)
+                .or_default()
+                .push(relation.category_id);
+        }
+        Ok(map)
     }
 
     /// Update attachment path
@@ -415,6 +1122,30 @@ impl PaperRepository {
         Ok(Attachment::from(result))
     }
 
+    /// Get every attachment of every paper, including papers in the trash
+    pub async fn find_all_attachments(db: &DatabaseConnection) -> Result<Vec<Attachment>> {
+        let attachments = attachment::Entity::find()
+            .order_by_asc(attachment::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get attachments: {}", e)))?;
+
+        Ok(attachments.into_iter().map(Attachment::from).collect())
+    }
+
+    /// Find an attachment by ID
+    pub async fn find_attachment(
+        db: &DatabaseConnection,
+        attachment_id: i64,
+    ) -> Result<Option<Attachment>> {
+        let attachment = attachment::Entity::find_by_id(attachment_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find attachment: {}", e)))?;
+
+        Ok(attachment.map(Attachment::from))
+    }
+
     /// Get all attachments for a paper
     pub async fn get_attachments(
         db: &DatabaseConnection,
@@ -472,6 +1203,21 @@ impl PaperRepository {
         }))
     }
 
+    /// Record a new size for an attachment whose file was replaced
+    pub async fn update_attachment_size(
+        db: &DatabaseConnection,
+        attachment_id: i64,
+        file_size: i64,
+    ) -> Result<()> {
+        attachment::Entity::update_many()
+            .col_expr(attachment::Column::FileSize, Expr::value(Some(file_size)))
+            .filter(attachment::Column::Id.eq(attachment_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to update attachment size: {}", e)))?;
+        Ok(())
+    }
+
     /// Remove attachment from paper by ID
     pub async fn remove_attachment(db: &DatabaseConnection, attachment_id: i64) -> Result<()> {
         // Get attachment to find paper_id
@@ -617,8 +1363,6 @@ impl PaperRepository {
         author_id: i64,
         author_order: i32,
     ) -> Result<()> {
-        use crate::database::entities::paper_author;
-
         let relation = paper_author::ActiveModel {
             paper_id: Set(paper_id),
             author_id: Set(author_id),
@@ -661,3 +1405,302 @@ impl PaperRepository {
         Ok(Attachment::from(result))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AuthorDetails;
+    use crate::repository::{AuthorRepository, KeywordRepository};
+    use crate::testing::{category, test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_trashed_papers_are_hidden_until_restored() {
+        let db = test_db().await;
+        PaperFixture::new("Kept")
+            .with_authors(&["Ada Lovelace"])
+            .with_category("Reading")
+            .insert(&db)
+            .await;
+        let trashed = PaperFixture::new("Trashed")
+            .with_authors(&["Ada Lovelace"])
+            .with_category("Reading")
+            .deleted()
+            .insert(&db)
+            .await;
+        let reading = category(&db, "Reading", None).await;
+        let author =
+            AuthorRepository::create_or_find(&db, "Ada Lovelace", &AuthorDetails::default())
+                .await
+                .unwrap();
+
+        assert_eq!(PaperRepository::count(&db).await.unwrap(), 1);
+        assert_eq!(PaperRepository::count_deleted(&db).await.unwrap(), 1);
+        assert_eq!(
+            PaperRepository::find_by_category(&db, reading.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            PaperRepository::find_by_author(&db, author.id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        PaperRepository::restore(&db, trashed.id).await.unwrap();
+        assert_eq!(PaperRepository::count(&db).await.unwrap(), 2);
+        assert_eq!(
+            PaperRepository::find_by_category(&db, reading.id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paper_in_several_categories() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Vision transformers")
+            .with_category("Vision")
+            .insert(&db)
+            .await;
+        let vision = category(&db, "Vision", None).await;
+        let nlp = category(&db, "NLP", None).await;
+
+        PaperRepository::add_category(&db, paper.id, nlp.id)
+            .await
+            .unwrap();
+        PaperRepository::add_category(&db, paper.id, nlp.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            PaperRepository::get_category_ids(&db, paper.id)
+                .await
+                .unwrap(),
+            vec![vision.id, nlp.id]
+        );
+        let counts = PaperRepository::count_by_category(&db).await.unwrap();
+        assert_eq!((counts[&vision.id], counts[&nlp.id]), (1, 1));
+
+        PaperRepository::remove_category(&db, paper.id, vision.id)
+            .await
+            .unwrap();
+        assert!(PaperRepository::remove_category(&db, paper.id, vision.id)
+            .await
+            .is_err());
+        assert_eq!(
+            PaperRepository::get_category_ids(&db, paper.id)
+                .await
+                .unwrap(),
+            vec![nlp.id]
+        );
+
+        // Setting a category still replaces all of them
+        PaperRepository::add_category(&db, paper.id, vision.id)
+            .await
+            .unwrap();
+        PaperRepository::set_category(&db, paper.id, Some(vision.id))
+            .await
+            .unwrap();
+        assert_eq!(
+            PaperRepository::get_category_ids(&db, paper.id)
+                .await
+                .unwrap(),
+            vec![vision.id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_all_paginated_without_limit() {
+        let db = test_db().await;
+        for title in ["First", "Second", "Third"] {
+            PaperFixture::new(title).insert(&db).await;
+        }
+
+        assert_eq!(
+            PaperRepository::find_all_paginated(&db, 1, None)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            PaperRepository::find_all_paginated(&db, 1, 1)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_by_keyword_paginated() {
+        let db = test_db().await;
+        for title in ["First", "Second", "Third"] {
+            PaperFixture::new(title)
+                .with_keyword("graphs")
+                .insert(&db)
+                .await;
+        }
+        PaperFixture::new("Unrelated")
+            .with_keyword("proteins")
+            .insert(&db)
+            .await;
+        let graphs = KeywordRepository::find_by_word(&db, "graphs")
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (page, total) = PaperRepository::find_by_keyword_paginated(&db, graphs.id, 2, 2)
+            .await
+            .unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_by_isbn() {
+        let db = test_db().await;
+        let book = PaperFixture::new("Fantastic Mr. Fox")
+            .with_isbn("9780140328721")
+            .insert(&db)
+            .await;
+        PaperFixture::new("No ISBN").insert(&db).await;
+
+        let found = PaperRepository::find_by_isbn(&db, "9780140328721")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, book.id);
+        assert_eq!(found.isbn.as_deref(), Some("9780140328721"));
+        assert!(PaperRepository::find_by_isbn(&db, "9780804429573")
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_star_survives_trash_and_restore() {
+        let db = test_db().await;
+        let starred = PaperFixture::new("Must read").insert(&db).await;
+        let trashed = PaperFixture::new("Trashed").deleted().insert(&db).await;
+        PaperFixture::new("Other").insert(&db).await;
+
+        assert!(PaperRepository::toggle_star(&db, starred.id).await.unwrap());
+        assert!(PaperRepository::toggle_star(&db, trashed.id).await.is_err());
+        let (page, total) = PaperRepository::find_starred_paginated(&db, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!((page.len(), total), (1, 1));
+
+        PaperRepository::soft_delete(&db, starred.id).await.unwrap();
+        let (_, total) = PaperRepository::find_starred_paginated(&db, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+        PaperRepository::restore(&db, starred.id).await.unwrap();
+        let (found, _) = PaperRepository::find_starred_paginated(&db, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert!(found[0].is_starred);
+
+        assert!(!PaperRepository::toggle_star(&db, starred.id).await.unwrap());
+        let (_, total) = PaperRepository::find_starred_paginated(&db, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_star_keeps_starred_at() {
+        let db = test_db().await;
+        let first = PaperFixture::new("First").insert(&db).await;
+        let second = PaperFixture::new("Second").insert(&db).await;
+
+        PaperRepository::set_star(&db, first.id, true)
+            .await
+            .unwrap();
+        let starred_at = PaperRepository::find_by_id(&db, first.id)
+            .await
+            .unwrap()
+            .unwrap()
+            .starred_at;
+        assert!(starred_at.is_some());
+
+        PaperRepository::set_star(&db, second.id, true)
+            .await
+            .unwrap();
+        PaperRepository::set_star(&db, first.id, true)
+            .await
+            .unwrap();
+        let (page, _) = PaperRepository::find_starred_paginated(&db, 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(page[0].id, second.id);
+        assert_eq!(page[1].starred_at, starred_at);
+
+        PaperRepository::set_star(&db, first.id, false)
+            .await
+            .unwrap();
+        let unstarred = PaperRepository::find_by_id(&db, first.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!unstarred.is_starred);
+        assert_eq!(unstarred.starred_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_title_edit_keeps_legacy_attachment_dir() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Titel with a typo").insert(&db).await;
+        assert_eq!(paper.attachment_path, None);
+
+        let rename = |title: &str| UpdatePaper {
+            title: Some(title.to_string()),
+            ..Default::default()
+        };
+        let updated = PaperRepository::update(&db, paper.id, rename("Title"))
+            .await
+            .unwrap();
+        assert_eq!(
+            updated.attachment_path,
+            Some(calculate_attachment_hash("Titel with a typo"))
+        );
+
+        let updated = PaperRepository::update(&db, paper.id, rename("Final title"))
+            .await
+            .unwrap();
+        assert_eq!(
+            updated.attachment_path,
+            Some(calculate_attachment_hash("Titel with a typo"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notes_edit_adds_note_version() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Noted").insert(&db).await;
+
+        let notes = |text: &str| UpdatePaper {
+            notes: Some(text.to_string()),
+            ..Default::default()
+        };
+        let updated = PaperRepository::update(&db, paper.id, notes("First thoughts"))
+            .await
+            .unwrap();
+        assert_eq!(updated.notes.as_deref(), Some("First thoughts"));
+        PaperRepository::update(&db, paper.id, notes("First thoughts"))
+            .await
+            .unwrap();
+
+        let history = NoteRepository::history(&db, paper.id).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "First thoughts");
+    }
+}
@@ -0,0 +1,187 @@
+//! Reading progress repository for SQLite using SeaORM
+//!
+//! Stores the current page per paper and groups updates into reading
+//! sessions: an update more than [`SESSION_GAP_MINUTES`] after the previous
+//! one starts a new session.
+
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::{sea_query::Expr, *};
+
+use crate::database::entities::{reading_progress, reading_session};
+use crate::sys::error::{AppError, Result};
+
+/// Idle time after which the next update opens a new session
+pub const SESSION_GAP_MINUTES: i64 = 30;
+
+/// Repository for reading progress operations
+pub struct ReadingProgressRepository;
+
+impl ReadingProgressRepository {
+    /// Progress of a paper with its sessions, oldest first
+    pub async fn find(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Option<(reading_progress::Model, Vec<reading_session::Model>)>> {
+        let Some(progress) = reading_progress::Entity::find_by_id(paper_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find reading progress: {}", e)))?
+        else {
+            return Ok(None);
+        };
+
+        let sessions = Self::sessions(db, paper_id).await?;
+        Ok(Some((progress, sessions)))
+    }
+
+    /// Record the page being read at `now`, extending the current session or
+    /// opening a new one after an idle gap
+    pub async fn record(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        current_page: i32,
+        total_pages: i32,
+        now: DateTime<Utc>,
+    ) -> Result<(reading_progress::Model, Vec<reading_session::Model>)> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let previous = reading_progress::Entity::find_by_id(paper_id)
+            .one(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find reading progress: {}", e)))?;
+
+        let last_session = reading_session::Entity::find()
+            .filter(reading_session::Column::PaperId.eq(paper_id))
+            .order_by_desc(reading_session::Column::StartedAt)
+            .one(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find reading session: {}", e)))?;
+
+        let page_changed = previous
+            .as_ref()
+            .is_some_and(|p| p.current_page != current_page);
+
+        match last_session {
+            Some(session) if now - session.ended_at <= Duration::minutes(SESSION_GAP_MINUTES) => {
+                let pages_read = session.pages_read + i32::from(page_changed);
+                let mut session: reading_session::ActiveModel = session.into();
+                session.ended_at = Set(now);
+                session.pages_read = Set(pages_read);
+                session.update(&txn).await.map_err(|e| {
+                    AppError::generic(format!("Failed to update reading session: {}", e))
+                })?;
+            }
+            _ => {
+                reading_session::ActiveModel {
+                    paper_id: Set(paper_id),
+                    started_at: Set(now),
+                    ended_at: Set(now),
+                    pages_read: Set(i32::from(page_changed)),
+                    ..Default::default()
+                }
+                .insert(&txn)
+                .await
+                .map_err(|e| {
+                    AppError::generic(format!("Failed to create reading session: {}", e))
+                })?;
+            }
+        }
+
+        let progress = reading_progress::ActiveModel {
+            paper_id: Set(paper_id),
+            current_page: Set(current_page),
+            total_pages: Set(total_pages),
+            last_read_at: Set(now),
+        };
+        let progress = if previous.is_some() {
+            progress.update(&txn).await
+        } else {
+            progress.insert(&txn).await
+        }
+        .map_err(|e| AppError::generic(format!("Failed to save reading progress: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        let sessions = Self::sessions(db, paper_id).await?;
+        Ok((progress, sessions))
+    }
+
+    /// Sessions of all papers started at or after `since`, oldest first
+    pub async fn sessions_since(
+        db: &DatabaseConnection,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<reading_session::Model>> {
+        reading_session::Entity::find()
+            .filter(reading_session::Column::StartedAt.gte(since))
+            .order_by_asc(reading_session::Column::StartedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query reading sessions: {}", e)))
+    }
+
+    /// Papers read to their last page, last read at or after `since`
+    pub async fn count_finished_since(
+        db: &DatabaseConnection,
+        since: DateTime<Utc>,
+    ) -> Result<u64> {
+        reading_progress::Entity::find()
+            .filter(reading_progress::Column::LastReadAt.gte(since))
+            .filter(reading_progress::Column::TotalPages.gt(0))
+            .filter(
+                Expr::col(reading_progress::Column::CurrentPage)
+                    .gte(Expr::col(reading_progress::Column::TotalPages)),
+            )
+            .count(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to count finished papers: {}", e)))
+    }
+
+    async fn sessions(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Vec<reading_session::Model>> {
+        reading_session::Entity::find()
+            .filter(reading_session::Column::PaperId.eq(paper_id))
+            .order_by_asc(reading_session::Column::StartedAt)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query reading sessions: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_idle_gap_starts_new_session() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Reading test").insert(&db).await;
+
+        // Whole seconds so timestamps compare equal after the SQLite round trip
+        let start = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+        ReadingProgressRepository::record(&db, paper.id, 1, 20, start)
+            .await
+            .unwrap();
+        ReadingProgressRepository::record(&db, paper.id, 2, 20, start + Duration::minutes(10))
+            .await
+            .unwrap();
+        let (progress, sessions) =
+            ReadingProgressRepository::record(&db, paper.id, 5, 20, start + Duration::minutes(45))
+                .await
+                .unwrap();
+
+        assert_eq!(progress.current_page, 5);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].pages_read, 1);
+        assert_eq!(sessions[0].ended_at, start + Duration::minutes(10));
+        assert_eq!(sessions[1].started_at, start + Duration::minutes(45));
+        assert_eq!(sessions[1].pages_read, 1);
+    }
+}
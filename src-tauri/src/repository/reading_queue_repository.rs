@@ -0,0 +1,330 @@
+//! Reading queue repository for SQLite using SeaORM
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+
+use crate::database::entities::{paper, reading_queue};
+use crate::models::Paper;
+use crate::sys::error::{AppError, Result};
+
+/// Repository for the ordered "to read next" queue. A paper is queued at
+/// most once.
+pub struct ReadingQueueRepository;
+
+impl ReadingQueueRepository {
+    /// Queued papers that are not in the trash, next to read first
+    pub async fn find_papers(db: &DatabaseConnection) -> Result<Vec<Paper>> {
+        let paper_ids = Self::paper_ids(db).await?;
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut papers: HashMap<i64, paper::Model> = paper::Entity::find()
+            .filter(paper::Column::Id.is_in(paper_ids.clone()))
+            .filter(paper::Column::DeletedAt.is_null())
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query queued papers: {}", e)))?
+            .into_iter()
+            .map(|p| (p.id, p))
+            .collect();
+
+        Ok(paper_ids
+            .into_iter()
+            .filter_map(|id| papers.remove(&id))
+            .map(Paper::from)
+            .collect())
+    }
+
+    /// Ids of the queued papers in queue order
+    pub async fn paper_ids(db: &DatabaseConnection) -> Result<Vec<i64>> {
+        let entries = reading_queue::Entity::find()
+            .order_by_asc(reading_queue::Column::Position)
+            .order_by_asc(reading_queue::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get reading queue: {}", e)))?;
+        Ok(entries.into_iter().map(|e| e.paper_id).collect())
+    }
+
+    /// Queued entries whose paper is not in the trash, with their papers,
+    /// in queue order
+    pub async fn find_entries(
+        db: &DatabaseConnection,
+    ) -> Result<Vec<(reading_queue::Model, Paper)>> {
+        let entries = reading_queue::Entity::find()
+            .find_also_related(paper::Entity)
+            .filter(paper::Column::DeletedAt.is_null())
+            .order_by_asc(reading_queue::Column::Position)
+            .order_by_asc(reading_queue::Column::Id)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get reading queue: {}", e)))?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(entry, paper)| Some((entry, Paper::from(paper?))))
+            .collect())
+    }
+
+    /// Paper to read next: the queued paper whose due date passed first,
+    /// or the head of the queue when none is due by `now`
+    pub async fn find_next(db: &DatabaseConnection, now: DateTime<Utc>) -> Result<Option<Paper>> {
+        let mut entries = Self::find_entries(db).await?;
+
+        let due = entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (entry, _))| Some((entry.due_date.filter(|d| *d <= now)?, i)))
+            .min()
+            .map(|(_, i)| i);
+        let next = due.or((!entries.is_empty()).then_some(0));
+
+        Ok(next.map(|i| entries.swap_remove(i).1))
+    }
+
+    /// Put a paper at the end of the queue. A paper that is already queued
+    /// is moved to the end and keeps its due date.
+    pub async fn enqueue(db: &DatabaseConnection, paper_id: i64) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        Self::ensure_paper_on(&txn, paper_id).await?;
+        let due_date = Self::find_entry_on(&txn, paper_id)
+            .await?
+            .and_then(|entry| entry.due_date);
+        Self::remove_on(&txn, paper_id).await?;
+        Self::append_on(&txn, paper_id, due_date).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Queue a paper with a due date. A paper that is already queued keeps
+    /// its position and gets the new due date.
+    pub async fn add(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<reading_queue::Model> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        Self::ensure_paper_on(&txn, paper_id).await?;
+        let entry = match Self::find_entry_on(&txn, paper_id).await? {
+            Some(entry) => {
+                let mut entry: reading_queue::ActiveModel = entry.into();
+                entry.due_date = Set(due_date);
+                entry
+                    .update(&txn)
+                    .await
+                    .map_err(|e| AppError::generic(format!("Failed to set due date: {}", e)))?
+            }
+            None => Self::append_on(&txn, paper_id, due_date).await?,
+        };
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(entry)
+    }
+
+    async fn ensure_paper_on<C: ConnectionTrait>(db: &C, paper_id: i64) -> Result<()> {
+        paper::Entity::find_by_id(paper_id)
+            .filter(paper::Column::DeletedAt.is_null())
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to find paper: {}", e)))?
+            .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+        Ok(())
+    }
+
+    async fn find_entry_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+    ) -> Result<Option<reading_queue::Model>> {
+        reading_queue::Entity::find()
+            .filter(reading_queue::Column::PaperId.eq(paper_id))
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get queued paper: {}", e)))
+    }
+
+    async fn append_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        due_date: Option<DateTime<Utc>>,
+    ) -> Result<reading_queue::Model> {
+        let last: Option<i32> = reading_queue::Entity::find()
+            .select_only()
+            .column_as(reading_queue::Column::Position.max(), "position")
+            .into_tuple::<Option<i32>>()
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get queue position: {}", e)))?
+            .flatten();
+
+        reading_queue::ActiveModel {
+            paper_id: Set(paper_id),
+            position: Set(last.map_or(0, |p| p + 1)),
+            added_at: Set(chrono::Utc::now()),
+            due_date: Set(due_date),
+            ..Default::default()
+        }
+        .insert(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to enqueue paper: {}", e)))
+    }
+
+    /// Take a paper out of the queue. Returns whether it was queued.
+    pub async fn dequeue(db: &DatabaseConnection, paper_id: i64) -> Result<bool> {
+        Self::remove_on(db, paper_id).await
+    }
+
+    pub(crate) async fn remove_on<C: ConnectionTrait>(db: &C, paper_id: i64) -> Result<bool> {
+        let result = reading_queue::Entity::delete_many()
+            .filter(reading_queue::Column::PaperId.eq(paper_id))
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to dequeue paper: {}", e)))?;
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Reorder the queue. `paper_ids` must hold every queued paper exactly
+    /// once.
+    pub async fn reorder(db: &DatabaseConnection, paper_ids: &[i64]) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        let queued: HashSet<i64> = reading_queue::Entity::find()
+            .all(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get reading queue: {}", e)))?
+            .into_iter()
+            .map(|e| e.paper_id)
+            .collect();
+        let requested: HashSet<i64> = paper_ids.iter().copied().collect();
+        if requested.len() != paper_ids.len() || requested != queued {
+            return Err(AppError::validation(
+                "paper_ids",
+                "Must list every queued paper exactly once",
+            ));
+        }
+
+        for (position, paper_id) in paper_ids.iter().enumerate() {
+            reading_queue::Entity::update_many()
+                .col_expr(
+                    reading_queue::Column::Position,
+                    Expr::value(position as i32),
+                )
+                .filter(reading_queue::Column::PaperId.eq(*paper_id))
+                .exec(&txn)
+                .await
+                .map_err(|e| AppError::generic(format!("Failed to reorder queue: {}", e)))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::PaperRepository;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_reading_queue_order() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+        let c = PaperFixture::new("C").insert(&db).await;
+
+        for paper in [&a, &b, &c, &a] {
+            ReadingQueueRepository::enqueue(&db, paper.id)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            ReadingQueueRepository::paper_ids(&db).await.unwrap(),
+            vec![b.id, c.id, a.id]
+        );
+
+        ReadingQueueRepository::reorder(&db, &[a.id, c.id, b.id])
+            .await
+            .unwrap();
+        assert!(ReadingQueueRepository::reorder(&db, &[a.id, c.id])
+            .await
+            .is_err());
+        assert!(ReadingQueueRepository::dequeue(&db, c.id).await.unwrap());
+        assert!(!ReadingQueueRepository::dequeue(&db, c.id).await.unwrap());
+
+        // Trashed papers leave the queue
+        PaperRepository::soft_delete(&db, a.id).await.unwrap();
+        let titles: Vec<String> = ReadingQueueRepository::find_papers(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.title)
+            .collect();
+        assert_eq!(titles, vec!["B"]);
+        assert_eq!(
+            ReadingQueueRepository::paper_ids(&db).await.unwrap(),
+            vec![b.id]
+        );
+        assert!(ReadingQueueRepository::enqueue(&db, a.id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_next_paper_prefers_passed_due_date() {
+        let db = test_db().await;
+        let a = PaperFixture::new("A").insert(&db).await;
+        let b = PaperFixture::new("B").insert(&db).await;
+        let c = PaperFixture::new("C").insert(&db).await;
+        let now = chrono::Utc::now();
+
+        assert!(ReadingQueueRepository::find_next(&db, now)
+            .await
+            .unwrap()
+            .is_none());
+
+        ReadingQueueRepository::add(&db, a.id, None).await.unwrap();
+        ReadingQueueRepository::add(&db, b.id, Some(now + chrono::Duration::days(3)))
+            .await
+            .unwrap();
+        ReadingQueueRepository::add(&db, c.id, None).await.unwrap();
+        let next = ReadingQueueRepository::find_next(&db, now).await.unwrap();
+        assert_eq!(next.map(|p| p.id), Some(a.id));
+
+        // Setting a due date keeps the position
+        let entry = ReadingQueueRepository::add(&db, c.id, Some(now - chrono::Duration::days(1)))
+            .await
+            .unwrap();
+        assert_eq!(entry.position, 2);
+        let next = ReadingQueueRepository::find_next(&db, now).await.unwrap();
+        assert_eq!(next.map(|p| p.id), Some(c.id));
+
+        // Moving to the end keeps the due date
+        ReadingQueueRepository::enqueue(&db, b.id).await.unwrap();
+        let entries = ReadingQueueRepository::find_entries(&db).await.unwrap();
+        assert_eq!(entries.last().unwrap().0.paper_id, b.id);
+        assert!(entries.last().unwrap().0.due_date.is_some());
+    }
+}
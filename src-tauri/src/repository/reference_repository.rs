@@ -0,0 +1,124 @@
+//! Paper reference repository for SQLite using SeaORM
+
+use sea_orm::*;
+
+use crate::database::entities::paper_reference;
+use crate::papers::importer::grobid::GrobidReference;
+use crate::sys::error::{AppError, Result};
+
+/// Repository for the bibliography entries of papers
+pub struct ReferenceRepository;
+
+impl ReferenceRepository {
+    /// References of a paper in bibliography order
+    pub async fn find_by_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Vec<paper_reference::Model>> {
+        paper_reference::Entity::find()
+            .filter(paper_reference::Column::PaperId.eq(paper_id))
+            .order_by_asc(paper_reference::Column::Position)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper references: {}", e)))
+    }
+
+    /// References of several papers, grouped by paper in bibliography order
+    pub async fn find_by_papers(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+    ) -> Result<Vec<paper_reference::Model>> {
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        paper_reference::Entity::find()
+            .filter(paper_reference::Column::PaperId.is_in(paper_ids.iter().copied()))
+            .order_by_asc(paper_reference::Column::PaperId)
+            .order_by_asc(paper_reference::Column::Position)
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper references: {}", e)))
+    }
+
+    /// Replace all references of a paper, e.g. after extracting them again
+    pub async fn replace_for_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        references: &[GrobidReference],
+    ) -> Result<Vec<paper_reference::Model>> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper_reference::Entity::delete_many()
+            .filter(paper_reference::Column::PaperId.eq(paper_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to delete paper references: {}", e)))?;
+
+        let now = chrono::Utc::now();
+        for (position, reference) in references.iter().enumerate() {
+            let authors = (!reference.authors.is_empty())
+                .then(|| serde_json::to_string(&reference.authors).unwrap_or_default());
+            paper_reference::ActiveModel {
+                paper_id: Set(paper_id),
+                position: Set(position as i32),
+                title: Set(reference.title.clone()),
+                authors: Set(authors),
+                year: Set(reference.year),
+                doi: Set(reference.doi.clone()),
+                journal: Set(reference.journal.clone()),
+                raw_text: Set(reference.raw_text.clone()),
+                created_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to insert paper reference: {}", e)))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Self::find_by_paper(db, paper_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    fn reference(title: &str) -> GrobidReference {
+        GrobidReference {
+            title: Some(title.to_string()),
+            authors: vec!["Ada Lovelace".to_string()],
+            year: Some(1843),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_for_paper() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Citing").insert(&db).await;
+
+        ReferenceRepository::replace_for_paper(&db, paper.id, &[reference("Old")])
+            .await
+            .unwrap();
+        let saved = ReferenceRepository::replace_for_paper(
+            &db,
+            paper.id,
+            &[reference("First"), reference("Second")],
+        )
+        .await
+        .unwrap();
+
+        let titles: Vec<_> = saved.iter().map(|r| r.title.as_deref().unwrap()).collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+        assert_eq!(saved[1].position, 1);
+        assert_eq!(saved[0].authors.as_deref(), Some(r#"["Ada Lovelace"]"#));
+    }
+}
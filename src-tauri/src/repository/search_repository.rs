@@ -1,18 +1,153 @@
 //! Search repository using SQLite FTS5 full-text search
 //!
 //! Provides efficient full-text search across paper title, abstract,
-//! labels, and attachments using SQLite's FTS5 extension with BM25 scoring.
+//! labels, and attachments, and across clipping title, excerpt and content,
+//! using SQLite's FTS5 extension with BM25 scoring.
+
+use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Utc};
 use sea_orm::{ConnectionTrait, DbBackend, *};
 use tracing::info;
 
-use crate::database::entities::paper;
+use crate::database::entities::{clipping, clipping_fts, paper, paper_note_fts};
+use crate::models::normalize_custom_field_key;
 use crate::sys::error::{AppError, Result};
 
 // Import sqlx types from SeaORM's re-export
 use sea_orm::sqlx::{Row, sqlite::SqliteRow};
 
+/// BM25 weight of the clipping title relative to the excerpt and content
+pub const CLIP_TITLE_WEIGHT: f64 = 2.0;
+
+/// Constraints applied on top of a paper search
+#[derive(Debug, Clone, Default)]
+pub struct PaperSearchFilter {
+    pub starred_only: bool,
+    /// `(key, value)` pairs the paper's custom fields must all match. Values
+    /// are compared without regard to case.
+    pub custom_fields: Vec<(String, String)>,
+}
+
+impl PaperSearchFilter {
+    /// Move `custom:key=value` terms from `query` into the filter and return
+    /// the rest of the query. Values containing spaces can be quoted, as in
+    /// `custom:decision="weak accept"`.
+    pub fn extract_custom_terms(&mut self, query: &str) -> String {
+        let mut rest = Vec::new();
+        for term in split_terms(query) {
+            let constraint = term
+                .get(..7)
+                .filter(|prefix| prefix.eq_ignore_ascii_case("custom:"))
+                .and_then(|_| term[7..].split_once('='))
+                .and_then(|(key, value)| {
+                    let key = normalize_custom_field_key(key).ok()?;
+                    let value = value.trim_matches('"').trim();
+                    (!value.is_empty()).then(|| (key, value.to_string()))
+                });
+            match constraint {
+                Some(constraint) => self.custom_fields.push(constraint),
+                None => rest.push(term),
+            }
+        }
+        rest.join(" ")
+    }
+
+    /// SQL conditions on the `paper p` row, each starting with `AND`
+    fn sql_conditions(&self) -> String {
+        let mut sql = String::new();
+        if self.starred_only {
+            sql.push_str(" AND p.is_starred = 1");
+        }
+        for (key, value) in &self.custom_fields {
+            sql.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM paper_custom_field cf \
+                 WHERE cf.paper_id = p.id AND cf.key = '{}' AND LOWER(cf.value) = LOWER('{}'))",
+                key.replace('\'', "''"),
+                value.replace('\'', "''")
+            ));
+        }
+        sql
+    }
+}
+
+/// Split a query on whitespace outside double quotes
+fn split_terms(query: &str) -> Vec<&str> {
+    let mut terms = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+    for (i, c) in query.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        if c.is_whitespace() && !quoted {
+            if let Some(s) = start.take() {
+                terms.push(&query[s..i]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        terms.push(&query[s..]);
+    }
+    terms
+}
+
+/// `LIKE` pattern matching `term` anywhere, with `\` as the escape character
+fn like_pattern(term: &str) -> String {
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// FTS5 phrase matching `term` literally. Double quotes are escaped by
+/// doubling them, so FTS5 operators and punctuation in `term` are not parsed.
+pub fn fts_phrase(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+/// Byte spans of every match of `needle` in `haystack`, ignoring case
+fn find_case_insensitive(haystack: &str, needle: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut from = 0;
+    while from < haystack.len() {
+        let mut rest = haystack[from..].chars();
+        let mut len = 0;
+        let matched = needle.chars().all(|n| match rest.next() {
+            Some(h) if h.to_lowercase().eq(n.to_lowercase()) => {
+                len += h.len_utf8();
+                true
+            }
+            _ => false,
+        });
+        let step = haystack[from..].chars().next().map_or(1, char::len_utf8);
+        if matched {
+            spans.push((from, from + len));
+            from += len;
+        } else {
+            from += step;
+        }
+    }
+    spans
+}
+
+/// A paper whose current note matches a note search
+#[derive(Debug, Clone)]
+pub struct NoteSearchHit {
+    pub paper_id: i64,
+    pub paper_title: String,
+    /// Plain text of the note
+    pub content: String,
+    /// Relevance score (0-100, higher is better)
+    pub score: f64,
+}
+
+/// Characters of note text shown around the first match
+pub const NOTE_EXCERPT_CHARS: usize = 200;
+
 /// Repository for full-text search operations
 pub struct SearchRepository;
 
@@ -30,42 +165,50 @@ impl SearchRepository {
         query: &str,
         limit: Option<u64>,
     ) -> Result<Vec<(paper::Model, f64)>> {
-        let limit = limit.unwrap_or(50);
-
-        info!("FTS search query: '{}'", query);
-
-        // Sanitize query by removing potential SQL injection
-        let sanitized_query = query.replace('\\', "\\\\").replace('"', "\\\"");
+        Self::fts_search_filtered(db, query, limit, &PaperSearchFilter::default()).await
+    }
 
-        // Check if query contains Chinese characters
-        let has_chinese = sanitized_query.chars().any(|c| {
-            let code = c as u32;
-            (0x4E00..=0x9FFF).contains(&code)
-                || (0x3400..=0x4DBF).contains(&code)
-                || (0x20000..=0x2A6DF).contains(&code)
-        });
+    /// Like `fts_search`, but every word of `query` must match and is
+    /// matched literally: FTS5 operators, quotes and punctuation such as
+    /// apostrophes are not parsed
+    pub async fn fts_search_words(
+        db: &DatabaseConnection,
+        query: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<(paper::Model, f64)>> {
+        // Queries answered with LIKE match the text as typed
+        let query = if Self::uses_like_search(query) {
+            query.to_string()
+        } else {
+            query
+                .split_whitespace()
+                .map(fts_phrase)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        Self::fts_search(db, &query, limit).await
+    }
 
-        // Count Chinese characters (trigram needs at least 3 chars to work effectively)
-        let chinese_char_count = sanitized_query
-            .chars()
-            .filter(|c| {
-                let code = *c as u32;
-                (0x4E00..=0x9FFF).contains(&code)
-                    || (0x3400..=0x4DBF).contains(&code)
-                    || (0x20000..=0x2A6DF).contains(&code)
-            })
-            .count();
+    /// Like `fts_search`, keeping only papers that pass `filter`. An empty
+    /// query lists every paper that passes it.
+    pub async fn fts_search_filtered(
+        db: &DatabaseConnection,
+        query: &str,
+        limit: Option<u64>,
+        filter: &PaperSearchFilter,
+    ) -> Result<Vec<(paper::Model, f64)>> {
+        let limit = limit.unwrap_or(50);
+        let filter_sql = filter.sql_conditions();
 
-        // For short Chinese queries (< 3 chars), use LIKE instead of FTS
-        // Trigram tokenizer needs at least 3 characters to generate tokens
-        let use_like_search = has_chinese && chinese_char_count < 3;
+        info!("FTS search query: '{}'", query);
 
-        info!("FTS search - has_chinese: {}, chinese_char_count: {}, use_like_search: {}",
-             has_chinese, chinese_char_count, use_like_search);
+        let use_like_search = Self::uses_like_search(query);
 
-        let sql = if use_like_search {
+        // The query and limit are bound as parameters; only the filter
+        // conditions, which escape their own values, are formatted in
+        let (sql, params) = if use_like_search {
             // Use LIKE for short Chinese queries
-            format!(
+            let sql = format!(
                 r#"
                 SELECT
                     p.id, p.title, p.abstract_text, p.doi, p.publication_year,
@@ -73,19 +216,23 @@ impl SearchRepository {
                     p.issue, p.pages, p.url, p.citation_count, p.read_status,
                     p.notes, p.attachment_path, p.created_at, p.updated_at,
                     p.deleted_at, p.publisher, p.issn, p.language, p.attachment_count,
-                    50.0 AS score
+                    0.0 AS score, p.isbn, p.is_starred,
+                    p.last_citation_refresh_at, p.starred_at
                 FROM paper p
                 WHERE p.deleted_at IS NULL
-                    AND (p.title LIKE '%{}%' OR p.abstract_text LIKE '%{}%')
+                    AND (p.title LIKE ? ESCAPE '\' OR p.abstract_text LIKE ? ESCAPE '\')
+                    {}
                 ORDER BY p.updated_at DESC
-                LIMIT {}
+                LIMIT ?
                 "#,
-                sanitized_query, sanitized_query, limit
-            )
+                filter_sql
+            );
+            let pattern = like_pattern(query.trim());
+            (sql, vec![pattern.clone(), pattern])
         } else {
             // Build FTS5 query with BM25 scoring
             // Use subquery approach for better FTS5 external content support
-            format!(
+            let sql = format!(
                 r#"
                 SELECT
                     p.id, p.title, p.abstract_text, p.doi, p.publication_year,
@@ -93,26 +240,34 @@ impl SearchRepository {
                     p.issue, p.pages, p.url, p.citation_count, p.read_status,
                     p.notes, p.attachment_path, p.created_at, p.updated_at,
                     p.deleted_at, p.publisher, p.issn, p.language, p.attachment_count,
-                    fts.score
+                    fts.score, p.isbn, p.is_starred,
+                    p.last_citation_refresh_at, p.starred_at
                 FROM paper p
                 INNER JOIN (
-                    SELECT paper_id, -bm25(paper_fts) AS score
+                    SELECT paper_id, bm25(paper_fts) AS score
                     FROM paper_fts
-                    WHERE paper_fts MATCH '{}'
+                    WHERE paper_fts MATCH ?
                 ) fts ON p.id = fts.paper_id
                 WHERE p.deleted_at IS NULL
-                ORDER BY fts.score DESC
-                LIMIT {}
+                    {}
+                ORDER BY fts.score ASC
+                LIMIT ?
                 "#,
-                sanitized_query, limit
-            )
+                filter_sql
+            );
+            (sql, vec![query.to_string()])
         };
 
         // Execute query using sqlx directly through SeaORM's connection
         let sqlx_rows: Vec<SqliteRow> = match db.get_database_backend() {
             DbBackend::Sqlite => {
                 let pool = db.get_sqlite_connection_pool();
-                sqlx::query(&sql)
+                let mut search_query = sqlx::query(&sql);
+                for param in &params {
+                    search_query = search_query.bind(param);
+                }
+                search_query
+                    .bind(limit as i64)
                     .fetch_all(pool)
                     .await
                     .map_err(|e| AppError::generic(format!("Failed to execute FTS search: {}", e)))?
@@ -133,7 +288,8 @@ impl SearchRepository {
             // 9=issue, 10=pages, 11=url, 12=citation_count, 13=read_status,
             // 14=notes, 15=attachment_path, 16=created_at, 17=updated_at,
             // 18=deleted_at, 19=publisher, 20=issn, 21=language, 22=attachment_count,
-            // 23=score
+            // 23=score, 24=isbn, 25=is_starred, 26=last_citation_refresh_at,
+            // 27=starred_at
 
             let paper_id: i64 = row
                 .try_get::<i64, _>(0)
@@ -193,11 +349,29 @@ impl SearchRepository {
             let language: Option<String> = row.try_get::<Option<String>, _>(21).ok().flatten();
             let attachment_count: i32 = row.try_get::<Option<i32>, _>(22).ok().flatten().unwrap_or(0);
 
-            // Get score (last column, index 23)
+            // Get BM25 score (index 23; lower is better)
             let raw_score: f64 = row.try_get::<Option<f64>, _>(23).ok().flatten().unwrap_or(0.0);
 
             // Normalize score to 0-100 range
             let normalized_score = Self::normalize_score(raw_score);
+            let isbn: Option<String> = row.try_get::<Option<String>, _>(24).ok().flatten();
+            let is_starred: bool = row
+                .try_get::<Option<bool>, _>(25)
+                .ok()
+                .flatten()
+                .unwrap_or(false);
+            let last_citation_refresh_at: Option<DateTime<Utc>> = row
+                .try_get::<Option<String>, _>(26)
+                .ok()
+                .flatten()
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc));
+            let starred_at: Option<DateTime<Utc>> = row
+                .try_get::<Option<String>, _>(27)
+                .ok()
+                .flatten()
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc));
 
             search_results.push((
                 paper::Model {
@@ -223,7 +397,11 @@ impl SearchRepository {
                     publisher,
                     issn,
                     language,
+                    isbn,
                     attachment_count,
+                    is_starred,
+                    starred_at,
+                    last_citation_refresh_at,
                 },
                 normalized_score,
             ));
@@ -237,6 +415,40 @@ impl SearchRepository {
         Ok(search_results)
     }
 
+    /// Whether `fts_search` answers `query` with a LIKE search instead of FTS5
+    fn uses_like_search(query: &str) -> bool {
+        // Check if query contains Chinese characters
+        let has_chinese = query.chars().any(|c| {
+            let code = c as u32;
+            (0x4E00..=0x9FFF).contains(&code)
+                || (0x3400..=0x4DBF).contains(&code)
+                || (0x20000..=0x2A6DF).contains(&code)
+        });
+
+        // Count Chinese characters (trigram needs at least 3 chars to work effectively)
+        let chinese_char_count = query
+            .chars()
+            .filter(|c| {
+                let code = *c as u32;
+                (0x4E00..=0x9FFF).contains(&code)
+                    || (0x3400..=0x4DBF).contains(&code)
+                    || (0x20000..=0x2A6DF).contains(&code)
+            })
+            .count();
+
+        // For short Chinese queries (< 3 chars), use LIKE instead of FTS
+        // Trigram tokenizer needs at least 3 characters to generate tokens
+        // An empty query cannot be matched by FTS5; LIKE '%%' matches all
+        let use_like_search = (has_chinese && chinese_char_count < 3) || query.trim().is_empty();
+
+        info!(
+            "FTS search - has_chinese: {}, chinese_char_count: {}, use_like_search: {}",
+            has_chinese, chinese_char_count, use_like_search
+        );
+
+        use_like_search
+    }
+
     /// Get search suggestions based on prefix matching
     ///
     /// Returns paper titles that start with the given prefix
@@ -376,6 +588,399 @@ impl SearchRepository {
         Ok(())
     }
 
+    // ==================== Clippings ====================
+
+    /// Full-text search across clipping title, excerpt and content with BM25
+    /// relevance scoring
+    ///
+    /// Returns clippings with their relevance scores (0-100, higher is better).
+    /// Every word of the query must match. The trigram tokenizer cannot match
+    /// words shorter than 3 characters, so such queries fall back to a LIKE
+    /// search for every word with a neutral score.
+    pub async fn clip_fts_search(
+        db: &DatabaseConnection,
+        query: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<(clipping::Model, f64)>> {
+        let (results, _) = Self::clip_fts_search_page(db, query, 0, limit.unwrap_or(50)).await?;
+        Ok(results)
+    }
+
+    /// One page of a clipping search, best matches first, with the total
+    /// number of matches. Scored and matched like [`Self::clip_fts_search`];
+    /// a title match counts [`CLIP_TITLE_WEIGHT`] times as much as a match
+    /// in the excerpt or content.
+    pub async fn clip_fts_search_page(
+        db: &DatabaseConnection,
+        query: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<(clipping::Model, f64)>, u64)> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
+
+        let use_like_search = terms.iter().any(|t| t.chars().count() < 3);
+        info!(
+            "Clip FTS search query: '{}', use_like_search: {}",
+            query, use_like_search
+        );
+
+        // Every word must match, like the FTS query below
+        let (condition, score, order, params) = if use_like_search {
+            let condition = terms
+                .iter()
+                .map(|_| {
+                    r"(title LIKE ? ESCAPE '\'
+                        OR excerpt LIKE ? ESCAPE '\'
+                        OR content LIKE ? ESCAPE '\')"
+                })
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            let params = terms
+                .iter()
+                .flat_map(|t| {
+                    let pattern = like_pattern(t);
+                    [pattern.clone(), pattern.clone(), pattern]
+                })
+                .collect();
+            (condition, "0.0".to_string(), "rowid DESC", params)
+        } else {
+            // Quote every word so FTS5 operators in the query are matched literally
+            let fts_query = terms
+                .iter()
+                .map(|t| fts_phrase(t))
+                .collect::<Vec<_>>()
+                .join(" ");
+            (
+                "clipping_fts MATCH ?".to_string(),
+                format!("bm25(clipping_fts, {:.1}, 1.0, 1.0)", CLIP_TITLE_WEIGHT),
+                "score ASC",
+                vec![fts_query],
+            )
+        };
+
+        let pool = db.get_sqlite_connection_pool();
+        let count_sql = format!("SELECT COUNT(*) FROM clipping_fts WHERE {}", condition);
+        let mut count_query = sqlx::query(&count_sql);
+        for param in &params {
+            count_query = count_query.bind(param);
+        }
+        let total: i64 = count_query
+            .fetch_one(pool)
+            .await
+            .and_then(|row: SqliteRow| row.try_get::<i64, _>(0))
+            .map_err(|e| AppError::generic(format!("Failed to count clip FTS matches: {}", e)))?;
+
+        let sql = format!(
+            r#"
+            SELECT rowid, {} AS score
+            FROM clipping_fts
+            WHERE {}
+            ORDER BY {}
+            LIMIT ? OFFSET ?
+            "#,
+            score, condition, order
+        );
+        let mut search_query = sqlx::query(&sql);
+        for param in &params {
+            search_query = search_query.bind(param);
+        }
+        let rows: Vec<SqliteRow> = search_query
+            .bind(limit as i64)
+            .bind(offset as i64)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to execute clip FTS search: {}", e)))?;
+
+        let hits: Vec<(i64, f64)> = rows
+            .iter()
+            .filter_map(|row| {
+                let id = row.try_get::<i64, _>(0).ok()?;
+                let score = row.try_get::<f64, _>(1).unwrap_or(0.0);
+                Some((id, score))
+            })
+            .collect();
+
+        let ids: Vec<i64> = hits.iter().map(|(id, _)| *id).collect();
+        let mut clips: HashMap<i64, clipping::Model> = clipping::Entity::find()
+            .filter(clipping::Column::Id.is_in(ids))
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to load clip search results: {}", e)))?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+
+        let results: Vec<(clipping::Model, f64)> = hits
+            .into_iter()
+            .filter_map(|(id, raw_score)| {
+                clips
+                    .remove(&id)
+                    .map(|c| (c, Self::normalize_score(raw_score)))
+            })
+            .collect();
+
+        info!(
+            "Clip FTS search for '{}' found {} of {} results",
+            query,
+            results.len(),
+            total
+        );
+        Ok((results, total as u64))
+    }
+
+    /// Add or replace the index entry of a clipping
+    pub async fn index_clip(db: &DatabaseConnection, clip: &crate::models::Clipping) -> Result<()> {
+        Self::index_clip_on(
+            db,
+            clip.id,
+            &clip.title,
+            clip.excerpt.as_deref(),
+            clip.content.as_deref(),
+        )
+        .await
+    }
+
+    /// Add or replace the index entry of a clipping on any connection (used
+    /// by the migration that creates the index). HTML is stripped first so
+    /// markup does not affect the scores.
+    pub async fn index_clip_on<C: ConnectionTrait>(
+        db: &C,
+        id: i64,
+        title: &str,
+        excerpt: Option<&str>,
+        content: Option<&str>,
+    ) -> Result<()> {
+        clipping_fts::Entity::delete_by_id(id)
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove clip from index: {}", e)))?;
+
+        let entry = clipping_fts::ActiveModel {
+            rowid: Set(id),
+            title: Set(Self::strip_html(title)),
+            excerpt: Set(excerpt.map(Self::strip_html)),
+            content: Set(content.map(Self::strip_html)),
+        };
+        clipping_fts::Entity::insert(entry)
+            .exec_without_returning(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to index clip: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Rebuild the clipping FTS index from the clipping table
+    pub async fn rebuild_clip_fts_index(db: &DatabaseConnection) -> Result<()> {
+        info!("Rebuilding clip FTS index");
+
+        clipping_fts::Entity::delete_many()
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to clear clip FTS index: {}", e)))?;
+
+        let clips = clipping::Entity::find()
+            .all(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to query clippings: {}", e)))?;
+        for clip in &clips {
+            Self::index_clip_on(
+                db,
+                clip.id,
+                &clip.title,
+                clip.excerpt.as_deref(),
+                clip.content.as_deref(),
+            )
+            .await?;
+        }
+
+        info!("Clip FTS index rebuilt with {} clippings", clips.len());
+        Ok(())
+    }
+
+    /// Index the plain text of a paper's current note, replacing what was
+    /// indexed before. An empty note removes the paper from the index.
+    pub async fn index_note_on<C: ConnectionTrait>(
+        db: &C,
+        paper_id: i64,
+        plain_text: &str,
+    ) -> Result<()> {
+        paper_note_fts::Entity::delete_by_id(paper_id)
+            .exec(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to remove note from index: {}", e)))?;
+
+        if plain_text.trim().is_empty() {
+            return Ok(());
+        }
+        let entry = paper_note_fts::ActiveModel {
+            rowid: Set(paper_id),
+            content: Set(plain_text.to_string()),
+        };
+        paper_note_fts::Entity::insert(entry)
+            .exec_without_returning(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to index note: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Full-text search over the current notes of papers not in the trash,
+    /// best match first. Every word must match.
+    pub async fn note_fts_search(
+        db: &DatabaseConnection,
+        query: &str,
+        limit: Option<u64>,
+    ) -> Result<Vec<NoteSearchHit>> {
+        let limit = limit.unwrap_or(50) as i64;
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // The trigram tokenizer cannot match words shorter than 3 characters
+        let use_like_search = terms.iter().any(|t| t.chars().count() < 3);
+        info!(
+            "Note FTS search query: '{}', use_like_search: {}",
+            query, use_like_search
+        );
+
+        let pool = db.get_sqlite_connection_pool();
+        let rows: Vec<SqliteRow> = if use_like_search {
+            // Every word must match, like the FTS query below
+            let condition = terms
+                .iter()
+                .map(|_| r"n.content LIKE ? ESCAPE '\'")
+                .collect::<Vec<_>>()
+                .join(" AND ");
+            let sql = format!(
+                r#"
+                SELECT n.rowid, n.content, p.title, 0.0 AS score
+                FROM paper_note_fts n
+                INNER JOIN paper p ON p.id = n.rowid
+                WHERE {}
+                    AND p.deleted_at IS NULL
+                ORDER BY p.updated_at DESC
+                LIMIT ?
+                "#,
+                condition
+            );
+            let mut like_query = sqlx::query(&sql);
+            for term in &terms {
+                like_query = like_query.bind(like_pattern(term));
+            }
+            like_query.bind(limit).fetch_all(pool).await
+        } else {
+            // Quote every word so FTS5 operators in the query are matched literally
+            let fts_query = terms
+                .iter()
+                .map(|t| fts_phrase(t))
+                .collect::<Vec<_>>()
+                .join(" ");
+            sqlx::query(
+                r#"
+                SELECT paper_note_fts.rowid, paper_note_fts.content, p.title,
+                    bm25(paper_note_fts) AS score
+                FROM paper_note_fts
+                INNER JOIN paper p ON p.id = paper_note_fts.rowid
+                WHERE paper_note_fts MATCH ?1
+                    AND p.deleted_at IS NULL
+                ORDER BY score ASC
+                LIMIT ?2
+                "#,
+            )
+            .bind(fts_query)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+        }
+        .map_err(|e| AppError::generic(format!("Failed to execute note FTS search: {}", e)))?;
+
+        let hits: Vec<NoteSearchHit> = rows
+            .iter()
+            .filter_map(|row| {
+                Some(NoteSearchHit {
+                    paper_id: row.try_get::<i64, _>(0).ok()?,
+                    content: row.try_get::<String, _>(1).ok()?,
+                    paper_title: row.try_get::<String, _>(2).ok()?,
+                    score: Self::normalize_score(row.try_get::<f64, _>(3).unwrap_or(0.0)),
+                })
+            })
+            .collect();
+
+        info!(
+            "Note FTS search for '{}' found {} results",
+            query,
+            hits.len()
+        );
+        Ok(hits)
+    }
+
+    /// Cut a window of about `NOTE_EXCERPT_CHARS` characters out of `text`,
+    /// centered on the first match of any of the query words. Returns the
+    /// excerpt and the byte spans `(start, end)` of every match inside it.
+    /// Without a match the excerpt is the start of the text.
+    pub fn note_excerpt(text: &str, query: &str) -> (String, Vec<(usize, usize)>) {
+        let mut matches: Vec<(usize, usize)> = query
+            .split_whitespace()
+            .map(|t| t.trim_matches('"'))
+            .filter(|t| !t.is_empty())
+            .flat_map(|t| find_case_insensitive(text, t))
+            .collect();
+        matches.sort_unstable();
+
+        // Char index of every char boundary, plus the end of the text
+        let boundaries: Vec<usize> = text
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        let char_count = boundaries.len() - 1;
+        let char_at = |byte: usize| boundaries.partition_point(|&b| b < byte);
+
+        let (start, end) = match matches.first() {
+            Some(&(match_start, match_end)) if char_count > NOTE_EXCERPT_CHARS => {
+                let center = (char_at(match_start) + char_at(match_end)) / 2;
+                let start = center
+                    .saturating_sub(NOTE_EXCERPT_CHARS / 2)
+                    .min(char_count - NOTE_EXCERPT_CHARS);
+                (start, start + NOTE_EXCERPT_CHARS)
+            }
+            _ => (0, char_count.min(NOTE_EXCERPT_CHARS)),
+        };
+        let (start_byte, end_byte) = (boundaries[start], boundaries[end]);
+
+        let spans = matches
+            .into_iter()
+            .filter(|&(s, e)| s >= start_byte && e <= end_byte)
+            .map(|(s, e)| (s - start_byte, e - start_byte))
+            .collect();
+        (text[start_byte..end_byte].to_string(), spans)
+    }
+
+    /// Plain text of an HTML fragment: tags are dropped together with the
+    /// bodies of `script` and `style` elements, entities are decoded and
+    /// whitespace is collapsed
+    pub fn strip_html(html: &str) -> String {
+        // A space before every tag keeps words in adjacent blocks apart
+        let spaced = html.replace('<', " <");
+        let text = ammonia::Builder::empty()
+            .clean_content_tags(HashSet::from(["script", "style"]))
+            .clean(&spaced)
+            .to_string();
+        let text = text
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&");
+
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
     /// Normalize BM25 score to 0-100 range
     ///
     /// BM25 returns unbounded scores (lower is better)
@@ -394,6 +999,9 @@ impl SearchRepository {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{CustomFieldType, NoteFormat, UpdateClipping};
+    use crate::repository::{ClippingRepository, CustomFieldRepository, NoteRepository};
+    use crate::testing::{test_db, ClipFixture, PaperFixture};
 
     #[test]
     fn test_normalize_score() {
@@ -412,4 +1020,213 @@ mod tests {
         let normalized = SearchRepository::normalize_score(neutral_score);
         assert!((45.0..=55.0).contains(&normalized));
     }
+
+    #[test]
+    fn test_note_excerpt() {
+        let text = format!("{}Transformer models{}", "a".repeat(300), "b".repeat(300));
+        let (excerpt, spans) = SearchRepository::note_excerpt(&text, "transformer");
+        assert_eq!(excerpt.chars().count(), NOTE_EXCERPT_CHARS);
+        assert_eq!(spans.len(), 1);
+        let (start, end) = spans[0];
+        assert_eq!(&excerpt[start..end], "Transformer");
+
+        // Spans are byte offsets, so multi-byte text stays sliceable
+        let (excerpt, spans) =
+            SearchRepository::note_excerpt("注意力机制 and attention", "注意力 ATTENTION");
+        assert_eq!(excerpt, "注意力机制 and attention");
+        assert_eq!(spans, vec![(0, 9), (20, 29)]);
+
+        let (excerpt, spans) = SearchRepository::note_excerpt("short note", "missing");
+        assert_eq!(excerpt, "short note");
+        assert!(spans.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_note_fts_search() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Attention is all you need")
+            .insert(&db)
+            .await;
+        let trashed = PaperFixture::new("Old draft").deleted().insert(&db).await;
+        NoteRepository::save(
+            &db,
+            paper.id,
+            "<p>Self-attention replaces recurrence</p>",
+            NoteFormat::Html,
+        )
+        .await
+        .unwrap();
+        NoteRepository::save(&db, trashed.id, "attention draft", NoteFormat::PlainText)
+            .await
+            .unwrap();
+
+        let hits = SearchRepository::note_fts_search(&db, "attention", None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].paper_id, paper.id);
+        assert_eq!(hits[0].content, "Self-attention replaces recurrence");
+
+        // A newer version replaces the indexed text
+        NoteRepository::save(&db, paper.id, "multi-head layers", NoteFormat::PlainText)
+            .await
+            .unwrap();
+        assert!(SearchRepository::note_fts_search(&db, "recurrence", None)
+            .await
+            .unwrap()
+            .is_empty());
+        let hits = SearchRepository::note_fts_search(&db, "head", None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        // Short words fall back to LIKE, still requiring every word
+        let hits = SearchRepository::note_fts_search(&db, "multi la", None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(SearchRepository::note_fts_search(&db, "multi xy", None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_custom_field_terms() {
+        let db = test_db().await;
+        let accepted = PaperFixture::new("Graph neural networks").insert(&db).await;
+        let rejected = PaperFixture::new("Graph kernels").insert(&db).await;
+        for (paper, decision) in [(&accepted, "Weak accept"), (&rejected, "reject")] {
+            CustomFieldRepository::set(&db, paper.id, "decision", decision, CustomFieldType::Text)
+                .await
+                .unwrap();
+        }
+
+        let mut filter = PaperSearchFilter::default();
+        let query = filter.extract_custom_terms("graph custom:decision=\"weak ACCEPT\"");
+        assert_eq!(query, "graph");
+        assert_eq!(
+            filter.custom_fields,
+            [("decision".to_string(), "weak ACCEPT".to_string())]
+        );
+
+        let hits = SearchRepository::fts_search_filtered(&db, &query, None, &filter)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, accepted.id);
+
+        let mut filter = PaperSearchFilter::default();
+        let query = filter.extract_custom_terms("custom:decision=reject");
+        let hits = SearchRepository::fts_search_filtered(&db, &query, None, &filter)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, rejected.id);
+    }
+
+    #[test]
+    fn test_fts_phrase() {
+        assert_eq!(fts_phrase("resnet"), "\"resnet\"");
+        assert_eq!(fts_phrase("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn test_strip_html() {
+        let html = "<h1>Title</h1><p>Fish &amp; chips<br>today</p><script>track()</script>";
+        assert_eq!(
+            SearchRepository::strip_html(html),
+            "Title Fish & chips today"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_clip_search_ignores_markup_and_follows_updates() {
+        let db = test_db().await;
+        let clip = ClipFixture::new("Rust async")
+            .with_content("<div class=\"article\"><p>Pinning futures explained</p></div>")
+            .insert(&db)
+            .await;
+
+        let hits = SearchRepository::clip_fts_search(&db, "futures", None)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.id, clip.id);
+        assert!(SearchRepository::clip_fts_search(&db, "article", None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        ClippingRepository::update(
+            &db,
+            clip.id,
+            UpdateClipping {
+                content: Some("Cancellation safety".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(SearchRepository::clip_fts_search(&db, "futures", None)
+            .await
+            .unwrap()
+            .is_empty());
+
+        ClippingRepository::delete(&db, clip.id).await.unwrap();
+        assert!(SearchRepository::clip_fts_search(&db, "cancellation", None)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_clip_search_ranks_title_matches_first_and_pages() {
+        let db = test_db().await;
+        let in_content = ClipFixture::new("Weekly notes")
+            .with_content("A short overview of borrow checking")
+            .insert(&db)
+            .await;
+        let in_title = ClipFixture::new("Borrow checking")
+            .with_content("A short overview")
+            .insert(&db)
+            .await;
+
+        let (first, total) = SearchRepository::clip_fts_search_page(&db, "borrow", 0, 1)
+            .await
+            .unwrap();
+        assert_eq!(total, 2);
+        assert_eq!(first[0].0.id, in_title.id);
+
+        let (second, _) = SearchRepository::clip_fts_search_page(&db, "borrow", 1, 1)
+            .await
+            .unwrap();
+        assert_eq!(second[0].0.id, in_content.id);
+        assert!(first[0].1 > second[0].1);
+    }
+
+    #[tokio::test]
+    async fn test_clip_search_with_short_words_matches_every_word() {
+        let db = test_db().await;
+        let both = ClipFixture::new("Go vs C")
+            .with_content("Comparing two languages")
+            .insert(&db)
+            .await;
+        ClipFixture::new("Go tips")
+            .with_content("Small idioms")
+            .insert(&db)
+            .await;
+
+        let (hits, total) = SearchRepository::clip_fts_search_page(&db, "c go", 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(hits[0].0.id, both.id);
+
+        let (hits, total) = SearchRepository::clip_fts_search_page(&db, "go 100%", 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 0);
+        assert!(hits.is_empty());
+    }
 }
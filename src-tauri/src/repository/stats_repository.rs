@@ -0,0 +1,536 @@
+//! Library statistics for SQLite using SeaORM
+//!
+//! Every figure is one grouped query, so the dashboard stays fast without
+//! loading papers into memory. Papers in the trash are never counted.
+
+use chrono::{DateTime, Datelike, Utc};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+
+use crate::database::entities::paper;
+use crate::models::Paper;
+use crate::sys::error::{AppError, Result};
+
+/// Authors and journals listed in `LibraryStats`
+pub const TOP_LIMIT: u64 = 10;
+
+/// Months covered by `LibraryStats::added_per_month`, including the current one
+pub const RECENT_MONTHS: usize = 24;
+
+/// Papers grouped under a category, label, status, author or journal. `id`
+/// is only set for groups that have one.
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct NamedCount {
+    pub id: Option<i64>,
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct YearCount {
+    pub year: i32,
+    pub count: i64,
+}
+
+/// Papers added in a `YYYY-MM` month
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct MonthCount {
+    pub month: String,
+    pub count: i64,
+}
+
+#[derive(FromQueryResult)]
+struct Total {
+    total: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LibraryStats {
+    pub total_papers: i64,
+    /// Papers with a publication year, oldest year first
+    pub papers_per_year: Vec<YearCount>,
+    pub papers_without_year: i64,
+    pub by_category: Vec<NamedCount>,
+    pub by_label: Vec<NamedCount>,
+    pub by_read_status: Vec<NamedCount>,
+    pub top_authors: Vec<NamedCount>,
+    pub top_journals: Vec<NamedCount>,
+    /// Recorded size of all attachments; attachments without a size count as 0
+    pub attachment_bytes: i64,
+    /// The last `RECENT_MONTHS` months, oldest first, including empty months
+    pub added_per_month: Vec<MonthCount>,
+}
+
+/// Metadata a paper can be missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingField {
+    Abstract,
+    Doi,
+    Year,
+    /// Neither a journal nor a conference name
+    Journal,
+    Authors,
+    Keywords,
+    Pdf,
+}
+
+impl MissingField {
+    pub const ALL: [MissingField; 7] = [
+        MissingField::Abstract,
+        MissingField::Doi,
+        MissingField::Year,
+        MissingField::Journal,
+        MissingField::Authors,
+        MissingField::Keywords,
+        MissingField::Pdf,
+    ];
+
+    /// SQL condition on paper `p` that holds when the field is missing
+    fn missing_sql(self) -> &'static str {
+        match self {
+            MissingField::Abstract => "TRIM(COALESCE(p.abstract_text, '')) = ''",
+            MissingField::Doi => "TRIM(COALESCE(p.doi, '')) = ''",
+            MissingField::Year => "p.publication_year IS NULL",
+            MissingField::Journal => {
+                "TRIM(COALESCE(p.journal_name, '')) = '' \
+                 AND TRIM(COALESCE(p.conference_name, '')) = ''"
+            }
+            MissingField::Authors => {
+                "NOT EXISTS (SELECT 1 FROM paper_author pa WHERE pa.paper_id = p.id)"
+            }
+            MissingField::Keywords => {
+                "NOT EXISTS (SELECT 1 FROM paper_keyword pk WHERE pk.paper_id = p.id)"
+            }
+            MissingField::Pdf => {
+                "NOT EXISTS (SELECT 1 FROM attachment a WHERE a.paper_id = p.id \
+                 AND (LOWER(COALESCE(a.file_type, '')) = 'pdf' \
+                 OR LOWER(COALESCE(a.file_name, '')) LIKE '%.pdf'))"
+            }
+        }
+    }
+}
+
+/// Papers that have each kind of metadata
+#[derive(Debug, Clone, PartialEq, FromQueryResult)]
+pub struct MetadataCompleteness {
+    pub total_papers: i64,
+    pub with_abstract: i64,
+    pub with_doi: i64,
+    pub with_year: i64,
+    pub with_journal: i64,
+    pub with_authors: i64,
+    pub with_keywords: i64,
+    pub with_pdf: i64,
+}
+
+impl MetadataCompleteness {
+    /// Share of all tracked fields that are filled in, from 0 to 1. An empty
+    /// library has nothing missing and scores 1.
+    pub fn score(&self) -> f32 {
+        if self.total_papers == 0 {
+            return 1.0;
+        }
+        let filled = self.with_abstract
+            + self.with_doi
+            + self.with_year
+            + self.with_journal
+            + self.with_authors
+            + self.with_keywords
+            + self.with_pdf;
+        filled as f32 / (self.total_papers as f32 * MissingField::ALL.len() as f32)
+    }
+}
+
+/// `YYYY-MM` of the `count` months up to and including the month of `now`,
+/// oldest first
+pub fn recent_months(now: DateTime<Utc>, count: usize) -> Vec<String> {
+    let current = now.year() * 12 + now.month0() as i32;
+    (0..count as i32)
+        .rev()
+        .map(|back| {
+            let month = current - back;
+            format!(
+                "{:04}-{:02}",
+                month.div_euclid(12),
+                month.rem_euclid(12) + 1
+            )
+        })
+        .collect()
+}
+
+/// Repository for library-wide statistics
+pub struct StatsRepository;
+
+impl StatsRepository {
+    async fn named_counts(
+        db: &DatabaseConnection,
+        sql: &str,
+        values: Vec<Value>,
+    ) -> Result<Vec<NamedCount>> {
+        NamedCount::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            sql,
+            values,
+        ))
+        .all(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to compute library statistics: {}", e)))
+    }
+
+    /// Compute all statistics of the library
+    pub async fn library_stats(db: &DatabaseConnection) -> Result<LibraryStats> {
+        let map_err =
+            |e: DbErr| AppError::generic(format!("Failed to compute library statistics: {}", e));
+
+        let total_papers = paper::Entity::find()
+            .filter(paper::Column::DeletedAt.is_null())
+            .count(db)
+            .await
+            .map_err(map_err)? as i64;
+
+        let papers_per_year = YearCount::find_by_statement(Statement::from_string(
+            DbBackend::Sqlite,
+            r#"
+            SELECT publication_year AS year, COUNT(*) AS count
+            FROM paper
+            WHERE deleted_at IS NULL AND publication_year IS NOT NULL
+            GROUP BY publication_year
+            ORDER BY publication_year
+            "#,
+        ))
+        .all(db)
+        .await
+        .map_err(map_err)?;
+        let with_year: i64 = papers_per_year.iter().map(|y| y.count).sum();
+
+        let by_category = Self::named_counts(
+            db,
+            r#"
+            SELECT c.id AS id, c.name AS name, COUNT(DISTINCT p.id) AS count
+            FROM category c
+            INNER JOIN paper_category pc ON pc.category_id = c.id
+            INNER JOIN paper p ON p.id = pc.paper_id AND p.deleted_at IS NULL
+            GROUP BY c.id
+            ORDER BY count DESC, c.name
+            "#,
+            vec![],
+        )
+        .await?;
+
+        let by_label = Self::named_counts(
+            db,
+            r#"
+            SELECT l.id AS id, l.name AS name, COUNT(DISTINCT p.id) AS count
+            FROM label l
+            INNER JOIN paper_label pl ON pl.label_id = l.id
+            INNER JOIN paper p ON p.id = pl.paper_id AND p.deleted_at IS NULL
+            GROUP BY l.id
+            ORDER BY count DESC, l.name
+            "#,
+            vec![],
+        )
+        .await?;
+
+        let by_read_status = Self::named_counts(
+            db,
+            r#"
+            SELECT NULL AS id, read_status AS name, COUNT(*) AS count
+            FROM paper
+            WHERE deleted_at IS NULL
+            GROUP BY read_status
+            ORDER BY count DESC, read_status
+            "#,
+            vec![],
+        )
+        .await?;
+
+        let top_authors = Self::named_counts(
+            db,
+            r#"
+            SELECT a.id AS id,
+                TRIM(a.first_name || ' ' || COALESCE(a.last_name, '')) AS name,
+                COUNT(DISTINCT p.id) AS count
+            FROM author a
+            INNER JOIN paper_author pa ON pa.author_id = a.id
+            INNER JOIN paper p ON p.id = pa.paper_id AND p.deleted_at IS NULL
+            GROUP BY a.id
+            ORDER BY count DESC, name
+            LIMIT ?
+            "#,
+            vec![(TOP_LIMIT as i64).into()],
+        )
+        .await?;
+
+        let top_journals = Self::named_counts(
+            db,
+            r#"
+            SELECT NULL AS id, TRIM(journal_name) AS name, COUNT(*) AS count
+            FROM paper
+            WHERE deleted_at IS NULL AND TRIM(COALESCE(journal_name, '')) != ''
+            GROUP BY TRIM(journal_name)
+            ORDER BY count DESC, name
+            LIMIT ?
+            "#,
+            vec![(TOP_LIMIT as i64).into()],
+        )
+        .await?;
+
+        let attachment_bytes = Total::find_by_statement(Statement::from_string(
+            DbBackend::Sqlite,
+            r#"
+            SELECT COALESCE(SUM(a.file_size), 0) AS total
+            FROM attachment a
+            INNER JOIN paper p ON p.id = a.paper_id AND p.deleted_at IS NULL
+            "#,
+        ))
+        .one(db)
+        .await
+        .map_err(map_err)?
+        .map(|t| t.total)
+        .unwrap_or(0);
+
+        // created_at is stored as text starting with `YYYY-MM`
+        let months = recent_months(Utc::now(), RECENT_MONTHS);
+        let added = MonthCount::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            r#"
+            SELECT substr(created_at, 1, 7) AS month, COUNT(*) AS count
+            FROM paper
+            WHERE deleted_at IS NULL AND substr(created_at, 1, 7) >= ?
+            GROUP BY month
+            "#,
+            [months[0].clone().into()],
+        ))
+        .all(db)
+        .await
+        .map_err(map_err)?;
+        let added_per_month = months
+            .into_iter()
+            .map(|month| {
+                let count = added
+                    .iter()
+                    .find(|m| m.month == month)
+                    .map_or(0, |m| m.count);
+                MonthCount { month, count }
+            })
+            .collect();
+
+        Ok(LibraryStats {
+            total_papers,
+            papers_per_year,
+            papers_without_year: total_papers - with_year,
+            by_category,
+            by_label,
+            by_read_status,
+            top_authors,
+            top_journals,
+            attachment_bytes,
+            added_per_month,
+        })
+    }
+
+    /// The `limit` categories holding the most of `paper_ids`. Papers in the
+    /// trash are not counted.
+    pub async fn top_categories_of(
+        db: &DatabaseConnection,
+        paper_ids: &[i64],
+        limit: u64,
+    ) -> Result<Vec<NamedCount>> {
+        if paper_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; paper_ids.len()].join(", ");
+        let mut values: Vec<Value> = paper_ids.iter().map(|&id| id.into()).collect();
+        values.push((limit as i64).into());
+        Self::named_counts(
+            db,
+            &format!(
+                r#"
+                SELECT c.id AS id, c.name AS name, COUNT(DISTINCT p.id) AS count
+                FROM category c
+                INNER JOIN paper_category pc ON pc.category_id = c.id
+                INNER JOIN paper p ON p.id = pc.paper_id AND p.deleted_at IS NULL
+                WHERE p.id IN ({})
+                GROUP BY c.id
+                ORDER BY count DESC, c.name
+                LIMIT ?
+                "#,
+                placeholders
+            ),
+            values,
+        )
+        .await
+    }
+
+    /// Non-deleted papers missing any of `fields`, most recently added first
+    pub async fn find_missing_metadata(
+        db: &DatabaseConnection,
+        fields: &[MissingField],
+    ) -> Result<Vec<Paper>> {
+        if fields.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let missing = fields
+            .iter()
+            .map(|f| format!("({})", f.missing_sql()))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let papers = paper::Entity::find()
+            .from_raw_sql(Statement::from_string(
+                DbBackend::Sqlite,
+                format!(
+                    "SELECT p.* FROM paper p WHERE p.deleted_at IS NULL AND ({}) \
+                     ORDER BY p.created_at DESC, p.id DESC",
+                    missing
+                ),
+            ))
+            .all(db)
+            .await
+            .map_err(|e| {
+                AppError::generic(format!("Failed to query papers missing metadata: {}", e))
+            })?;
+
+        Ok(papers.into_iter().map(Paper::from).collect())
+    }
+
+    /// Count the non-deleted papers that have each kind of metadata
+    pub async fn metadata_completeness(db: &DatabaseConnection) -> Result<MetadataCompleteness> {
+        let column = |field: MissingField| {
+            let name = match field {
+                MissingField::Abstract => "with_abstract",
+                MissingField::Doi => "with_doi",
+                MissingField::Year => "with_year",
+                MissingField::Journal => "with_journal",
+                MissingField::Authors => "with_authors",
+                MissingField::Keywords => "with_keywords",
+                MissingField::Pdf => "with_pdf",
+            };
+            format!(
+                "COALESCE(SUM(CASE WHEN {} THEN 0 ELSE 1 END), 0) AS {}",
+                field.missing_sql(),
+                name
+            )
+        };
+        let columns = MissingField::ALL
+            .iter()
+            .map(|f| column(*f))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        MetadataCompleteness::find_by_statement(Statement::from_string(
+            DbBackend::Sqlite,
+            format!(
+                "SELECT COUNT(*) AS total_papers, {} FROM paper p WHERE p.deleted_at IS NULL",
+                columns
+            ),
+        ))
+        .one(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to compute metadata completeness: {}", e)))?
+        .ok_or_else(|| AppError::generic("Failed to compute metadata completeness"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_recent_months_crosses_year() {
+        let now = Utc.with_ymd_and_hms(2025, 2, 15, 0, 0, 0).unwrap();
+        assert_eq!(
+            recent_months(now, 4),
+            vec!["2024-11", "2024-12", "2025-01", "2025-02"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_library_stats_skips_trash() {
+        let db = test_db().await;
+        PaperFixture::new("A")
+            .with_year(2020)
+            .with_authors(&["Ada Lovelace"])
+            .with_label("ml")
+            .with_category("Thesis")
+            .insert(&db)
+            .await;
+        PaperFixture::new("B")
+            .with_year(2020)
+            .with_authors(&["Ada Lovelace", "Alan Turing"])
+            .with_label("ml")
+            .insert(&db)
+            .await;
+        PaperFixture::new("C").insert(&db).await;
+        PaperFixture::new("Trashed")
+            .with_year(1999)
+            .with_authors(&["Alan Turing"])
+            .with_label("ml")
+            .deleted()
+            .insert(&db)
+            .await;
+
+        let stats = StatsRepository::library_stats(&db).await.unwrap();
+        assert_eq!(stats.total_papers, 3);
+        assert_eq!(
+            stats.papers_per_year,
+            vec![YearCount {
+                year: 2020,
+                count: 2
+            }]
+        );
+        assert_eq!(stats.papers_without_year, 1);
+        assert_eq!(stats.by_label.len(), 1);
+        assert_eq!(stats.by_label[0].count, 2);
+        assert_eq!(stats.by_category[0].name, "Thesis");
+        assert_eq!(stats.top_authors[0].name, "Ada Lovelace");
+        assert_eq!(stats.top_authors[0].count, 2);
+        assert_eq!(stats.top_authors[1].count, 1);
+        assert_eq!(stats.by_read_status[0].count, 3);
+
+        assert_eq!(stats.added_per_month.len(), RECENT_MONTHS);
+        assert_eq!(stats.added_per_month.last().unwrap().count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_missing_metadata() {
+        let db = test_db().await;
+        PaperFixture::new("Complete")
+            .with_doi("10.1000/a")
+            .with_year(2020)
+            .with_abstract("Abstract")
+            .with_authors(&["Ada Lovelace"])
+            .insert(&db)
+            .await;
+        let bare = PaperFixture::new("Bare").insert(&db).await;
+        PaperFixture::new("Trashed").deleted().insert(&db).await;
+
+        let ids = |papers: Vec<Paper>| papers.into_iter().map(|p| p.id).collect::<Vec<_>>();
+        let missing = StatsRepository::find_missing_metadata(&db, &[MissingField::Doi])
+            .await
+            .unwrap();
+        assert_eq!(ids(missing), vec![bare.id]);
+        let missing = StatsRepository::find_missing_metadata(
+            &db,
+            &[MissingField::Abstract, MissingField::Pdf],
+        )
+        .await
+        .unwrap();
+        assert_eq!(ids(missing).len(), 2);
+        assert!(StatsRepository::find_missing_metadata(&db, &[])
+            .await
+            .unwrap()
+            .is_empty());
+
+        let report = StatsRepository::metadata_completeness(&db).await.unwrap();
+        assert_eq!(report.total_papers, 2);
+        assert_eq!(report.with_doi, 1);
+        assert_eq!(report.with_authors, 1);
+        assert_eq!(report.with_pdf, 0);
+        assert!((report.score() - 4.0 / 14.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,85 @@
+//! Paper text content repository for SQLite using SeaORM
+
+use sea_orm::*;
+
+use crate::database::entities::paper_text_content;
+use crate::sys::error::{AppError, Result};
+
+/// Repository for the extracted full text of papers
+pub struct TextContentRepository;
+
+impl TextContentRepository {
+    /// Stored text of a paper, if it has been extracted
+    pub async fn find_by_paper(
+        db: &DatabaseConnection,
+        paper_id: i64,
+    ) -> Result<Option<paper_text_content::Model>> {
+        paper_text_content::Entity::find_by_id(paper_id)
+            .one(db)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to get paper text: {}", e)))
+    }
+
+    /// Store the text of a paper, replacing any earlier extraction
+    pub async fn save(
+        db: &DatabaseConnection,
+        paper_id: i64,
+        content: &str,
+        source: &str,
+        page_count: i32,
+    ) -> Result<()> {
+        let txn = db
+            .begin()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+        paper_text_content::Entity::delete_by_id(paper_id)
+            .exec(&txn)
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to replace paper text: {}", e)))?;
+
+        paper_text_content::ActiveModel {
+            paper_id: Set(paper_id),
+            content: Set(content.to_string()),
+            source: Set(source.to_string()),
+            page_count: Set(page_count),
+            updated_at: Set(chrono::Utc::now()),
+        }
+        .insert(&txn)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to save paper text: {}", e)))?;
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_save_replaces_earlier_text() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Scanned").insert(&db).await;
+
+        TextContentRepository::save(&db, paper.id, "first", "text_layer", 1)
+            .await
+            .unwrap();
+        TextContentRepository::save(&db, paper.id, "second", "ocr", 2)
+            .await
+            .unwrap();
+
+        let stored = TextContentRepository::find_by_paper(&db, paper.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.content, "second");
+        assert_eq!(stored.source, "ocr");
+        assert_eq!(stored.page_count, 2);
+    }
+}
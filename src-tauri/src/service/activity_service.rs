@@ -0,0 +1,53 @@
+//! Activity log of papers and clips
+//!
+//! Commands record what happened after their own work is done. Recording is
+//! best effort: a failure is logged and never fails the command itself.
+
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::repository::ActivityRepository;
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+
+pub const ENTITY_PAPER: &str = "paper";
+pub const ENTITY_CLIP: &str = "clip";
+
+pub const ACTION_VIEWED: &str = "viewed";
+pub const ACTION_IMPORTED: &str = "imported";
+pub const ACTION_CREATED: &str = "created";
+pub const ACTION_UPDATED: &str = "updated";
+pub const ACTION_DELETED: &str = "deleted";
+
+/// Record an activity, logging instead of failing when it cannot be stored
+pub async fn record(db: &DatabaseConnection, entity_type: &str, entity_id: i64, action: &str) {
+    if let Err(e) = ActivityRepository::record(db, entity_type, entity_id, action).await {
+        warn!(
+            "Failed to record {} of {} {}: {}",
+            action, entity_type, entity_id, e
+        );
+    }
+}
+
+/// Spawn a one-off task deleting activity older than
+/// `activity.retention_days`
+pub fn spawn_retention_cleanup(db: Arc<DatabaseConnection>, app_dirs: AppDirs) {
+    tauri::async_runtime::spawn(async move {
+        let retention_days = AppConfig::load(&app_dirs.config)
+            .unwrap_or_default()
+            .activity
+            .retention_days;
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+        match ActivityRepository::delete_older_than(&db, cutoff).await {
+            Ok(0) => {}
+            Ok(removed) => info!(
+                "Removed {} activity log entries older than {} days",
+                removed, retention_days
+            ),
+            Err(e) => warn!("Failed to trim activity log: {}", e),
+        }
+    });
+}
@@ -0,0 +1,717 @@
+//! Attachment file location helpers
+//!
+//! Attachments live under `{files}/{paper.attachment_path}/{file_name}`.
+//! New papers get a random `attachment_path` when they are created, so
+//! renaming a paper keeps its directory and papers with the same title do
+//! not share one. Older papers have the SHA-1 of their title there, or no
+//! `attachment_path` at all, in which case the SHA-1 of the current title
+//! is used; `normalize_attachment_paths` moves those to random names.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rand::RngCore;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::models::{calculate_attachment_hash, Attachment, Paper};
+use crate::repository::PaperRepository;
+use crate::sys::error::{AppError, Result};
+
+/// Largest PDF returned as a base64 blob over IPC; bigger files must be
+/// loaded from the API server's `/api/papers/{id}/pdf` route
+pub const MAX_BLOB_SIZE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Random attachment directory name for a new paper: 32 hex digits, which
+/// cannot be mistaken for a 40-digit title hash
+pub fn new_attachment_dir() -> String {
+    let mut bytes = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether an attachment directory name is a title hash
+pub fn is_legacy_attachment_dir(name: &str) -> bool {
+    name.len() == 40 && name.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Directory holding a paper's attachments
+pub fn paper_dir(files_dir: &str, paper: &Paper) -> PathBuf {
+    let hash_string = paper
+        .attachment_path
+        .clone()
+        .unwrap_or_else(|| calculate_attachment_hash(&paper.title));
+    PathBuf::from(files_dir).join(hash_string)
+}
+
+/// File name used for a PDF attachment without a recorded name
+pub fn default_pdf_file_name(title: &str) -> String {
+    format!(
+        "{}.pdf",
+        title.replace(|c: char| !c.is_alphanumeric() && c != ' ', "_")
+    )
+}
+
+/// Locate the PDF attachment of a paper on disk.
+/// Returns `None` when the paper has no PDF attachment or the file is missing.
+pub async fn find_pdf_path(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper: &Paper,
+) -> Result<Option<PathBuf>> {
+    let Some(attachment) = PaperRepository::find_pdf_attachment(db, paper.id).await? else {
+        return Ok(None);
+    };
+
+    let file_name = attachment
+        .file_name
+        .clone()
+        .unwrap_or_else(|| default_pdf_file_name(&paper.title));
+    let pdf_path = paper_dir(files_dir, paper).join(file_name);
+
+    Ok(pdf_path.exists().then_some(pdf_path))
+}
+
+/// Resolve `path` and make sure it stays under `base`, following symlinks.
+pub fn resolve_within(base: &Path, path: &Path) -> Result<PathBuf> {
+    let to_fs_error = |p: &Path, e: std::io::Error| {
+        AppError::file_system(
+            p.to_string_lossy().to_string(),
+            format!("Failed to resolve path: {}", e),
+        )
+    };
+
+    let base = base.canonicalize().map_err(|e| to_fs_error(base, e))?;
+    let resolved = path.canonicalize().map_err(|e| to_fs_error(path, e))?;
+
+    if !resolved.starts_with(&base) {
+        return Err(AppError::permission(resolved.to_string_lossy().to_string()));
+    }
+
+    Ok(resolved)
+}
+
+/// Attachment whose file is not where the database expects it
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingAttachmentDto {
+    pub paper_id: String,
+    pub attachment_id: String,
+    pub expected_path: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentValidationReport {
+    pub total: u64,
+    /// Attachments whose file was at the expected path
+    pub valid: u64,
+    /// Missing files that were found elsewhere and moved back
+    pub repaired: u64,
+    /// Attachments whose file could not be found
+    pub missing: Vec<MissingAttachmentDto>,
+}
+
+/// Check that the file of every attachment exists at
+/// `{files}/{paper.attachment_path}/{file_name}`.
+///
+/// With `attempt_repair`, a missing file is looked up by name anywhere under
+/// `files_dir` and moved back to its expected path. Files that are the
+/// expected file of another attachment are never moved, and neither are
+/// names found in more than one place.
+pub async fn validate_attachments(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    attempt_repair: bool,
+) -> Result<AttachmentValidationReport> {
+    let attachments = PaperRepository::find_all_attachments(db).await?;
+
+    let mut papers: HashMap<i64, Option<Paper>> = HashMap::new();
+    let mut expected = Vec::with_capacity(attachments.len());
+    for attachment in &attachments {
+        if !papers.contains_key(&attachment.paper_id) {
+            let paper = PaperRepository::find_by_id(db, attachment.paper_id).await?;
+            papers.insert(attachment.paper_id, paper);
+        }
+        let Some(paper) = &papers[&attachment.paper_id] else {
+            warn!(
+                "Attachment {} belongs to missing paper {}",
+                attachment.id, attachment.paper_id
+            );
+            continue;
+        };
+
+        let file_name = attachment
+            .file_name
+            .clone()
+            .unwrap_or_else(|| default_pdf_file_name(&paper.title));
+        let path = paper_dir(files_dir, paper).join(&file_name);
+        expected.push((attachment, file_name, path));
+    }
+
+    let mut report = AttachmentValidationReport {
+        total: expected.len() as u64,
+        valid: 0,
+        repaired: 0,
+        missing: Vec::new(),
+    };
+
+    let broken: Vec<_> = expected
+        .iter()
+        .filter(|(_, _, path)| !path.exists())
+        .collect();
+    report.valid = report.total - broken.len() as u64;
+
+    let mut candidates = HashMap::new();
+    if attempt_repair && !broken.is_empty() {
+        let claimed: HashSet<&PathBuf> = expected.iter().map(|(_, _, path)| path).collect();
+        collect_files(Path::new(files_dir), &mut candidates);
+        for paths in candidates.values_mut() {
+            paths.retain(|p| !claimed.contains(p));
+        }
+    }
+
+    for (attachment, file_name, path) in broken {
+        let found = match candidates.get_mut(file_name) {
+            Some(paths) if paths.len() == 1 => paths.pop(),
+            _ => None,
+        };
+        if let Some(found) = found {
+            if move_file(&found, path) {
+                info!(
+                    "Moved attachment {} back from {}",
+                    attachment.id,
+                    found.display()
+                );
+                report.repaired += 1;
+                continue;
+            }
+        }
+
+        report.missing.push(MissingAttachmentDto {
+            paper_id: attachment.paper_id.to_string(),
+            attachment_id: attachment.id.to_string(),
+            expected_path: path.to_string_lossy().to_string(),
+        });
+    }
+
+    info!(
+        "Validated {} attachments: {} valid, {} repaired, {} missing",
+        report.total,
+        report.valid,
+        report.repaired,
+        report.missing.len()
+    );
+    Ok(report)
+}
+
+/// Disk usage of one paper's attachment directory
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperStorageDto {
+    pub paper_id: String,
+    pub title: String,
+    pub total_size_bytes: u64,
+    pub file_count: u64,
+}
+
+/// The `limit` largest paper directories under `files_dir`, biggest first.
+///
+/// Papers in the trash are included because their files are still on disk.
+/// Directories that belong to no paper are skipped.
+pub async fn largest_paper_dirs(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    limit: usize,
+) -> Result<Vec<PaperStorageDto>> {
+    let mut papers = PaperRepository::find_all(db).await?;
+    papers.extend(PaperRepository::find_deleted(db).await?);
+    let papers_by_dir: HashMap<PathBuf, Paper> = papers
+        .into_iter()
+        .map(|paper| (paper_dir(files_dir, &paper), paper))
+        .collect();
+
+    let Ok(entries) = std::fs::read_dir(files_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut usage: Vec<PaperStorageDto> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let paper = papers_by_dir.get(&path)?;
+            let (total_size_bytes, file_count) = dir_usage(&path);
+            Some(PaperStorageDto {
+                paper_id: paper.id.to_string(),
+                title: paper.title.clone(),
+                total_size_bytes,
+                file_count,
+            })
+        })
+        .collect();
+
+    usage.sort_by(|a, b| b.total_size_bytes.cmp(&a.total_size_bytes));
+    usage.truncate(limit);
+    Ok(usage)
+}
+
+/// Total size and number of files under `dir`
+fn dir_usage(dir: &Path) -> (u64, u64) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return (0, 0);
+    };
+    let mut size = 0;
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let (sub_size, sub_count) = dir_usage(&path);
+            size += sub_size;
+            count += sub_count;
+        } else {
+            size += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            count += 1;
+        }
+    }
+    (size, count)
+}
+
+/// Size of a file in bytes, or `None` if it cannot be read
+pub fn file_size_on_disk(path: &Path) -> Option<i64> {
+    std::fs::metadata(path).ok().map(|m| m.len() as i64)
+}
+
+/// Whether an attachment is a PDF, by file type or extension
+pub fn is_pdf_attachment(attachment: &Attachment) -> bool {
+    attachment
+        .file_type
+        .as_deref()
+        .is_some_and(|t| t.eq_ignore_ascii_case("pdf"))
+        || attachment
+            .file_name
+            .as_deref()
+            .is_some_and(|n| n.to_lowercase().ends_with(".pdf"))
+}
+
+/// Read an attachment's size from its file and store it. Returns the size.
+pub async fn backfill_attachment_size(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    attachment_id: i64,
+) -> Result<i64> {
+    let attachment = PaperRepository::find_attachment(db, attachment_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Attachment", attachment_id.to_string()))?;
+    let paper = PaperRepository::find_by_id(db, attachment.paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", attachment.paper_id.to_string()))?;
+
+    let file_name = attachment
+        .file_name
+        .clone()
+        .unwrap_or_else(|| default_pdf_file_name(&paper.title));
+    let path = paper_dir(files_dir, &paper).join(file_name);
+    let size = file_size_on_disk(&path).ok_or_else(|| {
+        AppError::file_system(
+            path.to_string_lossy().to_string(),
+            "Attachment file not found",
+        )
+    })?;
+
+    if attachment.file_size != Some(size) {
+        PaperRepository::update_attachment_size(db, attachment_id, size).await?;
+    }
+    Ok(size)
+}
+
+/// Attachment counts and sizes across the library
+#[derive(Debug, Clone)]
+pub struct AttachmentStorageStats {
+    pub total_count: u64,
+    pub total_size_bytes: u64,
+    pub pdf_count: u64,
+    pub other_count: u64,
+    /// Largest first
+    pub largest: Vec<Attachment>,
+}
+
+/// Count and size every attachment, including those of papers in the trash
+/// since their files are still on disk.
+///
+/// Missing sizes are read from disk and stored first. Attachments whose
+/// file is gone count as 0 bytes.
+pub async fn storage_stats(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    largest_limit: usize,
+) -> Result<AttachmentStorageStats> {
+    let mut attachments = PaperRepository::find_all_attachments(db).await?;
+    for attachment in attachments.iter_mut().filter(|a| a.file_size.is_none()) {
+        match backfill_attachment_size(db, files_dir, attachment.id).await {
+            Ok(size) => attachment.file_size = Some(size),
+            Err(e) => warn!(
+                "Failed to backfill size of attachment {}: {}",
+                attachment.id, e
+            ),
+        }
+    }
+
+    let pdf_count = attachments.iter().filter(|a| is_pdf_attachment(a)).count() as u64;
+    let total_count = attachments.len() as u64;
+    let total_size_bytes = attachments
+        .iter()
+        .filter_map(|a| a.file_size)
+        .map(|size| size.max(0) as u64)
+        .sum();
+
+    attachments.sort_by_key(|a| std::cmp::Reverse(a.file_size.unwrap_or(0)));
+    attachments.truncate(largest_limit);
+
+    Ok(AttachmentStorageStats {
+        total_count,
+        total_size_bytes,
+        pdf_count,
+        other_count: total_count - pdf_count,
+        largest: attachments,
+    })
+}
+
+/// Paper whose attachment directory was left on its title hash
+#[derive(Debug, Clone, Serialize)]
+pub struct AttachmentPathConflictDto {
+    pub paper_id: String,
+    pub title: String,
+    /// Attachment directory name of the paper
+    pub attachment_path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttachmentPathReport {
+    /// Papers whose attachment directory was named by title hash
+    pub legacy: u64,
+    /// Papers whose directory was renamed
+    pub renamed: u64,
+    /// Papers without a directory on disk that were given a new name
+    pub reassigned: u64,
+    pub conflicts: Vec<AttachmentPathConflictDto>,
+}
+
+/// Move every paper whose attachment directory is named by title hash to a
+/// [`new_attachment_dir`] name, renaming its directory and updating
+/// `paper.attachment_path`. Papers in the trash are included.
+///
+/// A directory shared by papers with the same title is left in place and
+/// reported as a conflict for each of them, since its files cannot be told
+/// apart.
+pub async fn normalize_attachment_paths(
+    db: &DatabaseConnection,
+    files_dir: &str,
+) -> Result<AttachmentPathReport> {
+    let mut papers = PaperRepository::find_all(db).await?;
+    papers.extend(PaperRepository::find_deleted(db).await?);
+
+    let dir_name = |paper: &Paper| {
+        paper
+            .attachment_path
+            .clone()
+            .unwrap_or_else(|| calculate_attachment_hash(&paper.title))
+    };
+    let mut users: HashMap<String, Vec<i64>> = HashMap::new();
+    for paper in &papers {
+        users.entry(dir_name(paper)).or_default().push(paper.id);
+    }
+
+    let mut report = AttachmentPathReport::default();
+    for paper in &papers {
+        let old_name = dir_name(paper);
+        if !is_legacy_attachment_dir(&old_name) {
+            continue;
+        }
+        report.legacy += 1;
+
+        let conflict = |reason: String| AttachmentPathConflictDto {
+            paper_id: paper.id.to_string(),
+            title: paper.title.clone(),
+            attachment_path: old_name.clone(),
+            reason,
+        };
+        let others: Vec<String> = users[&old_name]
+            .iter()
+            .filter(|id| **id != paper.id)
+            .map(|id| id.to_string())
+            .collect();
+        if !others.is_empty() {
+            report.conflicts.push(conflict(format!(
+                "Directory shared with paper {}",
+                others.join(", ")
+            )));
+            continue;
+        }
+
+        let old_dir = Path::new(files_dir).join(&old_name);
+        let mut new_name = new_attachment_dir();
+        while Path::new(files_dir).join(&new_name).exists() {
+            new_name = new_attachment_dir();
+        }
+        let new_dir = Path::new(files_dir).join(&new_name);
+
+        let moved = old_dir.is_dir();
+        if moved {
+            if let Err(e) = std::fs::rename(&old_dir, &new_dir) {
+                report
+                    .conflicts
+                    .push(conflict(format!("Failed to rename directory: {}", e)));
+                continue;
+            }
+        }
+        if let Err(e) = PaperRepository::update_attachment_path(db, paper.id, &new_name).await {
+            if moved {
+                if let Err(e) = std::fs::rename(&new_dir, &old_dir) {
+                    warn!(
+                        "Failed to move {} back to {}: {}",
+                        new_dir.display(),
+                        old_dir.display(),
+                        e
+                    );
+                }
+            }
+            return Err(e);
+        }
+
+        if moved {
+            report.renamed += 1;
+        } else {
+            report.reassigned += 1;
+        }
+    }
+
+    info!(
+        "Normalized attachment paths: {} legacy, {} renamed, {} reassigned, {} conflicts",
+        report.legacy,
+        report.renamed,
+        report.reassigned,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+/// Index every file under `dir` by file name
+fn collect_files(dir: &Path, files: &mut HashMap<String, Vec<PathBuf>>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if let Some(name) = path.file_name() {
+            files
+                .entry(name.to_string_lossy().to_string())
+                .or_default()
+                .push(path);
+        }
+    }
+}
+
+fn move_file(from: &Path, to: &Path) -> bool {
+    let result = to
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::rename(from, to));
+    if let Err(e) = &result {
+        warn!(
+            "Failed to move {} to {}: {}",
+            from.display(),
+            to.display(),
+            e
+        );
+    }
+    result.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_within_accepts_files_under_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("paper").join("a.pdf");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, b"%PDF").unwrap();
+
+        let resolved = resolve_within(dir.path(), &file).unwrap();
+        assert!(resolved.ends_with("paper/a.pdf"));
+    }
+
+    #[test]
+    fn resolve_within_rejects_paths_escaping_base() {
+        let root = tempfile::tempdir().unwrap();
+        let files = root.path().join("files");
+        std::fs::create_dir_all(&files).unwrap();
+        std::fs::write(root.path().join("secret.pdf"), b"%PDF").unwrap();
+
+        let escaped = files.join("..").join("secret.pdf");
+        let err = resolve_within(&files, &escaped).unwrap_err();
+        assert!(matches!(err, AppError::PermissionError { .. }));
+    }
+
+    #[tokio::test]
+    async fn validate_attachments_reports_and_repairs_moved_files() {
+        use crate::testing::{test_db, PaperFixture};
+
+        let files = tempfile::tempdir().unwrap();
+        let files_dir = files.path().to_str().unwrap();
+        let db = test_db().await;
+
+        let present = PaperFixture::new("Present").insert(&db).await;
+        let moved = PaperFixture::new("Moved").insert(&db).await;
+        let lost = PaperFixture::new("Lost").insert(&db).await;
+        for (paper, name) in [(&present, "a.pdf"), (&moved, "b.pdf"), (&lost, "c.pdf")] {
+            PaperRepository::add_attachment(&db, paper.id, Some(name.to_string()), None, None)
+                .await
+                .unwrap();
+        }
+
+        let present_path = paper_dir(files_dir, &present).join("a.pdf");
+        std::fs::create_dir_all(present_path.parent().unwrap()).unwrap();
+        std::fs::write(&present_path, b"%PDF").unwrap();
+        let stray = files.path().join("elsewhere").join("b.pdf");
+        std::fs::create_dir_all(stray.parent().unwrap()).unwrap();
+        std::fs::write(&stray, b"%PDF").unwrap();
+
+        let report = validate_attachments(&db, files_dir, false).await.unwrap();
+        assert_eq!((report.total, report.valid, report.repaired), (3, 1, 0));
+        assert_eq!(report.missing.len(), 2);
+
+        let report = validate_attachments(&db, files_dir, true).await.unwrap();
+        assert_eq!((report.total, report.valid, report.repaired), (3, 1, 1));
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].paper_id, lost.id.to_string());
+        assert!(paper_dir(files_dir, &moved).join("b.pdf").exists());
+        assert!(!stray.exists());
+    }
+
+    #[tokio::test]
+    async fn largest_paper_dirs_sorts_by_size() {
+        use crate::testing::{test_db, PaperFixture};
+
+        let files = tempfile::tempdir().unwrap();
+        let files_dir = files.path().to_str().unwrap();
+        let db = test_db().await;
+
+        let small = PaperFixture::new("Small").insert(&db).await;
+        let large = PaperFixture::new("Large").deleted().insert(&db).await;
+        for (paper, sizes) in [(&small, vec![10]), (&large, vec![100, 50])] {
+            let dir = paper_dir(files_dir, paper);
+            std::fs::create_dir_all(&dir).unwrap();
+            for (i, size) in sizes.into_iter().enumerate() {
+                std::fs::write(dir.join(format!("{}.pdf", i)), vec![0u8; size]).unwrap();
+            }
+        }
+        std::fs::create_dir_all(files.path().join("orphan")).unwrap();
+        std::fs::write(files.path().join("orphan").join("x.pdf"), vec![0u8; 500]).unwrap();
+
+        let usage = largest_paper_dirs(&db, files_dir, 20).await.unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].paper_id, large.id.to_string());
+        assert_eq!((usage[0].total_size_bytes, usage[0].file_count), (150, 2));
+        assert_eq!(usage[1].title, "Small");
+
+        let usage = largest_paper_dirs(&db, files_dir, 1).await.unwrap();
+        assert_eq!(usage.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn storage_stats_backfills_missing_sizes() {
+        use crate::testing::{test_db, PaperFixture};
+
+        let files = tempfile::tempdir().unwrap();
+        let files_dir = files.path().to_str().unwrap();
+        let db = test_db().await;
+
+        let paper = PaperFixture::new("Sized").insert(&db).await;
+        let dir = paper_dir(files_dir, &paper);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("paper.pdf"), vec![0u8; 120]).unwrap();
+        std::fs::write(dir.join("data.csv"), vec![0u8; 30]).unwrap();
+        let pdf =
+            PaperRepository::add_attachment(&db, paper.id, Some("paper.pdf".into()), None, None)
+                .await
+                .unwrap();
+        PaperRepository::add_attachment(&db, paper.id, Some("data.csv".into()), None, Some(30))
+            .await
+            .unwrap();
+        PaperRepository::add_attachment(&db, paper.id, Some("gone.pdf".into()), None, None)
+            .await
+            .unwrap();
+
+        let stats = storage_stats(&db, files_dir, 2).await.unwrap();
+        assert_eq!((stats.total_count, stats.total_size_bytes), (3, 150));
+        assert_eq!((stats.pdf_count, stats.other_count), (2, 1));
+        assert_eq!(stats.largest.len(), 2);
+        assert_eq!(stats.largest[0].id, pdf.id);
+
+        let stored = PaperRepository::find_attachment(&db, pdf.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.file_size, Some(120));
+    }
+
+    #[tokio::test]
+    async fn normalize_attachment_paths_renames_title_hash_dirs() {
+        use crate::testing::{test_db, PaperFixture};
+
+        let files = tempfile::tempdir().unwrap();
+        let files_dir = files.path().to_str().unwrap();
+        let db = test_db().await;
+
+        let hashed = PaperFixture::new("Hashed")
+            .with_attachment_path(&calculate_attachment_hash("Hashed"))
+            .insert(&db)
+            .await;
+        let unset = PaperFixture::new("No directory").insert(&db).await;
+        let twin = PaperFixture::new("Twin").insert(&db).await;
+        PaperFixture::new("Twin").deleted().insert(&db).await;
+        let current = PaperFixture::new("Current")
+            .with_attachment_path(&new_attachment_dir())
+            .insert(&db)
+            .await;
+        for paper in [&hashed, &twin] {
+            let dir = paper_dir(files_dir, paper);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("paper.pdf"), b"%PDF").unwrap();
+        }
+
+        let report = normalize_attachment_paths(&db, files_dir).await.unwrap();
+        assert_eq!(
+            (report.legacy, report.renamed, report.reassigned),
+            (4, 1, 1)
+        );
+        assert_eq!(report.conflicts.len(), 2);
+        assert!(report.conflicts.iter().all(|c| c.title == "Twin"));
+
+        let hashed = PaperRepository::find_by_id(&db, hashed.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let dir = hashed.attachment_path.clone().unwrap();
+        assert!(!is_legacy_attachment_dir(&dir));
+        assert!(paper_dir(files_dir, &hashed).join("paper.pdf").exists());
+        assert!(!files
+            .path()
+            .join(calculate_attachment_hash("Hashed"))
+            .exists());
+
+        let unset = PaperRepository::find_by_id(&db, unset.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unset.attachment_path.unwrap().len(), 32);
+        let current_after = PaperRepository::find_by_id(&db, current.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(current_after.attachment_path, current.attachment_path);
+        assert!(paper_dir(files_dir, &twin).join("paper.pdf").exists());
+    }
+}
@@ -0,0 +1,105 @@
+//! Finding authors that are probably the same person
+//!
+//! Imports create authors by exact name, so "J. Smith", "John Smith" and
+//! "John R. Smíth" end up as separate rows. Authors are grouped by a key made
+//! of the first initial and the last name, lowercased and without
+//! diacritics, and groups with more than one author are offered for merging.
+
+use std::collections::HashMap;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::database::DatabaseConnection;
+use crate::models::Author;
+use crate::repository::AuthorRepository;
+use crate::sys::error::Result;
+
+/// Lowercase words of `text` with diacritics and punctuation removed
+fn name_words(text: &str) -> Vec<String> {
+    text.nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Key shared by authors that are probably the same person: the first
+/// initial and the last name, e.g. "j smith". Names without a last name,
+/// such as most Chinese names, must match as a whole.
+pub fn duplicate_key(author: &Author) -> String {
+    let first = name_words(&author.first_name);
+    let last = author
+        .last_name
+        .as_deref()
+        .map(name_words)
+        .unwrap_or_default();
+
+    match first.first().and_then(|w| w.chars().next()) {
+        Some(initial) if !last.is_empty() => format!("{} {}", initial, last.join(" ")),
+        _ => first.into_iter().chain(last).collect::<Vec<_>>().join(" "),
+    }
+}
+
+/// Groups of authors sharing a `duplicate_key`, each with its paper count.
+/// Within a group the author with the most papers comes first, which is
+/// the natural merge target; groups are ordered by their first author's name.
+pub async fn suggest_duplicates(db: &DatabaseConnection) -> Result<Vec<Vec<(Author, u64)>>> {
+    let counts = AuthorRepository::count_papers(db).await?;
+
+    let mut groups: HashMap<String, Vec<(Author, u64)>> = HashMap::new();
+    for author in AuthorRepository::find_all(db).await? {
+        let key = duplicate_key(&author);
+        if key.is_empty() {
+            continue;
+        }
+        let count = counts.get(&author.id).copied().unwrap_or(0);
+        groups.entry(key).or_default().push((author, count));
+    }
+
+    let mut groups: Vec<Vec<(Author, u64)>> = groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+            group
+        })
+        .collect();
+    groups.sort_by_key(|group| group[0].0.full_name().to_lowercase());
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AuthorNameParser;
+
+    fn author(name: &str) -> Author {
+        let parts = AuthorNameParser::parse(name);
+        Author {
+            id: 0,
+            first_name: parts.first_name,
+            last_name: parts.last_name,
+            affiliation: None,
+            email: None,
+            orcid: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_key() {
+        assert_eq!(duplicate_key(&author("J. Smith")), "j smith");
+        assert_eq!(duplicate_key(&author("John R. Smith")), "j smith");
+        assert_eq!(duplicate_key(&author("Smith, John")), "j smith");
+        assert_eq!(duplicate_key(&author("José Müller")), "j muller");
+        assert_eq!(duplicate_key(&author("张三")), "张三");
+        assert_ne!(
+            duplicate_key(&author("John Smith")),
+            duplicate_key(&author("John Smyth"))
+        );
+    }
+}
@@ -0,0 +1,383 @@
+//! Database backup and restore
+//!
+//! Backups are consistent SQLite snapshots written with `VACUUM INTO`, so they
+//! can be taken while the app is running. A restore cannot replace the open
+//! database file, so it is staged next to it and applied on the next start
+//! before the connection is opened.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use tracing::{debug, error, info, warn};
+
+use crate::service::quiet_hours_service::{current_pause_reason, FocusModeState};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Database file name inside the data directory
+pub const DATABASE_FILE: &str = "xuan-brain.sqlite";
+
+/// Staged restore file, applied by `apply_pending_restore` on startup
+pub const PENDING_RESTORE_FILE: &str = "xuan-brain.sqlite.restore";
+
+/// Copy of the database taken right before a restore is applied
+const PRE_RESTORE_FILE: &str = "xuan-brain.sqlite.pre-restore";
+
+const BACKUP_PREFIX: &str = "xuan-brain-backup-";
+/// Only backups with this prefix are pruned by retention
+const AUTO_BACKUP_PREFIX: &str = "xuan-brain-auto-backup-";
+const BACKUP_EXTENSION: &str = "sqlite";
+
+/// Every SQLite database file starts with this header
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// How often the auto-backup task checks whether a backup is due
+const AUTO_BACKUP_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Default backup directory: `backups` next to the data directory, so a
+/// library restore or data folder wipe does not take the backups with it
+pub fn default_backup_dir(app_dirs: &AppDirs) -> PathBuf {
+    let data = Path::new(&app_dirs.data);
+    data.parent().unwrap_or(data).join("backups")
+}
+
+/// Backup directory from config, falling back to the default
+pub fn configured_backup_dir(app_dirs: &AppDirs, config: &AppConfig) -> PathBuf {
+    config
+        .backup
+        .directory
+        .as_ref()
+        .filter(|d| !d.trim().is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_backup_dir(app_dirs))
+}
+
+/// `{prefix}{timestamp}.sqlite`
+pub fn backup_file_name(prefix: &str, now: DateTime<Utc>) -> String {
+    format!(
+        "{}{}.{}",
+        prefix,
+        now.format("%Y%m%d-%H%M%S"),
+        BACKUP_EXTENSION
+    )
+}
+
+/// Write a snapshot of the database into `target_dir`
+pub async fn create_backup(db: &DatabaseConnection, target_dir: &Path) -> Result<PathBuf> {
+    write_backup(db, target_dir, BACKUP_PREFIX).await
+}
+
+async fn write_backup(db: &DatabaseConnection, target_dir: &Path, prefix: &str) -> Result<PathBuf> {
+    std::fs::create_dir_all(target_dir).map_err(|e| {
+        AppError::file_system(target_dir.to_string_lossy().to_string(), e.to_string())
+    })?;
+
+    let backup_path = target_dir.join(backup_file_name(prefix, Utc::now()));
+    if backup_path.exists() {
+        return Err(AppError::file_system(
+            backup_path.to_string_lossy().to_string(),
+            "Backup file already exists",
+        ));
+    }
+
+    let escaped = backup_path.to_string_lossy().replace('\'', "''");
+    db.execute_unprepared(&format!("VACUUM INTO '{}'", escaped))
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to back up database: {}", e)))?;
+
+    info!("Database backed up to {:?}", backup_path);
+    Ok(backup_path)
+}
+
+/// Check that `path` exists and looks like a SQLite database
+fn validate_backup_file(path: &Path) -> Result<()> {
+    use std::io::Read;
+
+    let path_str = path.to_string_lossy().to_string();
+    if !path.is_file() {
+        return Err(AppError::not_found("Backup file", path_str));
+    }
+
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|_| AppError::validation("backup_path", "File is not a database backup"))?;
+
+    if &header != SQLITE_HEADER {
+        return Err(AppError::validation(
+            "backup_path",
+            "File is not a database backup",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Stage `backup_path` to replace the database on the next start.
+/// The backup must live inside one of `allowed_dirs`.
+pub fn stage_restore(data_dir: &Path, backup_path: &Path, allowed_dirs: &[PathBuf]) -> Result<()> {
+    validate_backup_file(backup_path)?;
+
+    let canonical = backup_path.canonicalize().map_err(|e| {
+        AppError::file_system(backup_path.to_string_lossy().to_string(), e.to_string())
+    })?;
+    let allowed = allowed_dirs
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .any(|dir| canonical.starts_with(dir));
+    if !allowed {
+        return Err(AppError::permission(format!(
+            "restore: {} is not within a backup directory",
+            backup_path.display()
+        )));
+    }
+
+    let staged = data_dir.join(PENDING_RESTORE_FILE);
+    std::fs::copy(&canonical, &staged)
+        .map_err(|e| AppError::file_system(staged.to_string_lossy().to_string(), e.to_string()))?;
+
+    info!("Staged database restore from {:?}", canonical);
+    Ok(())
+}
+
+/// Replace the database with a staged restore, if one exists.
+/// Must run before the database connection is opened.
+pub fn apply_pending_restore(data_dir: &Path) -> Result<bool> {
+    let staged = data_dir.join(PENDING_RESTORE_FILE);
+    if !staged.exists() {
+        return Ok(false);
+    }
+
+    let db_path = data_dir.join(DATABASE_FILE);
+    let fs_error = |path: &Path, e: std::io::Error| {
+        AppError::file_system(path.to_string_lossy().to_string(), e.to_string())
+    };
+
+    if db_path.exists() {
+        let previous = data_dir.join(PRE_RESTORE_FILE);
+        std::fs::rename(&db_path, &previous).map_err(|e| fs_error(&db_path, e))?;
+    }
+
+    // WAL/SHM files belong to the old database and must not be replayed
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = data_dir.join(format!("{}{}", DATABASE_FILE, suffix));
+        if sidecar.exists() {
+            std::fs::remove_file(&sidecar).map_err(|e| fs_error(&sidecar, e))?;
+        }
+    }
+
+    std::fs::rename(&staged, &db_path).map_err(|e| fs_error(&staged, e))?;
+    info!("Applied staged database restore");
+    Ok(true)
+}
+
+/// Timestamp part of a backup file name written with one of `prefixes`
+fn backup_timestamp(path: &Path, prefixes: &[&str]) -> Option<String> {
+    if path.extension().and_then(|e| e.to_str()) != Some(BACKUP_EXTENSION) {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?;
+    prefixes
+        .iter()
+        .find_map(|prefix| stem.strip_prefix(prefix))
+        .map(str::to_string)
+}
+
+/// List backups in `dir` named with one of `prefixes`, newest first
+fn list_backups(dir: &Path, prefixes: &[&str]) -> Vec<PathBuf> {
+    let mut backups: Vec<(String, PathBuf)> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter_map(|p| backup_timestamp(&p, prefixes).map(|t| (t, p)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Timestamps sort chronologically
+    backups.sort();
+    backups.reverse();
+    backups.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Newest manual or automatic backup in `dir`, if any
+pub fn latest_backup(dir: &Path) -> Option<PathBuf> {
+    list_backups(dir, &[BACKUP_PREFIX, AUTO_BACKUP_PREFIX])
+        .into_iter()
+        .next()
+}
+
+/// Delete all but the newest `keep` automatic backups in `dir`.
+/// Manual and pre-restore backups are never pruned.
+fn prune_auto_backups(dir: &Path, keep: usize) {
+    for old in list_backups(dir, &[AUTO_BACKUP_PREFIX])
+        .into_iter()
+        .skip(keep)
+    {
+        if let Err(e) = std::fs::remove_file(&old) {
+            warn!("Failed to remove old backup {:?}: {}", old, e);
+        }
+    }
+}
+
+/// Take a backup if the newest one is older than the configured frequency,
+/// then prune old backups. Returns the new backup path, if one was written.
+pub async fn run_auto_backup_if_due(
+    db: &DatabaseConnection,
+    app_dirs: &AppDirs,
+) -> Result<Option<PathBuf>> {
+    let config = AppConfig::load(&app_dirs.config)?;
+    let Some(interval) = config.backup.auto_backup.interval() else {
+        return Ok(None);
+    };
+
+    let dir = configured_backup_dir(app_dirs, &config);
+    let last_backup = latest_backup(&dir)
+        .and_then(|p| std::fs::metadata(p).ok())
+        .and_then(|m| m.modified().ok())
+        .map(DateTime::<Utc>::from);
+
+    if let Some(last) = last_backup {
+        if Utc::now() - last < interval {
+            return Ok(None);
+        }
+    }
+
+    let path = write_backup(db, &dir, AUTO_BACKUP_PREFIX).await?;
+
+    let keep = config.backup.keep_count as usize;
+    if keep > 0 {
+        prune_auto_backups(&dir, keep);
+    }
+
+    Ok(Some(path))
+}
+
+/// Spawn the background task that performs scheduled backups.
+/// Checks are skipped during quiet hours and focus mode.
+pub fn spawn_auto_backup(
+    db: Arc<DatabaseConnection>,
+    app_dirs: AppDirs,
+    focus_mode: FocusModeState,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(AUTO_BACKUP_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Some(reason) = current_pause_reason(&app_dirs, &focus_mode) {
+                debug!("Deferring automatic backup: {}", reason);
+                continue;
+            }
+            if let Err(e) = run_auto_backup_if_due(&db, &app_dirs).await {
+                error!("Automatic backup failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connection::init_memory_connection;
+
+    #[tokio::test]
+    async fn test_backup_and_restore_roundtrip() {
+        let db = init_memory_connection().await;
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("backups");
+        let data = dir.path().join("data");
+        std::fs::create_dir_all(&data).unwrap();
+        std::fs::write(data.join(DATABASE_FILE), b"old database").unwrap();
+
+        let backup = create_backup(&db, &backups).await.unwrap();
+        assert!(backup
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with(BACKUP_PREFIX));
+
+        stage_restore(&data, &backup, &[backups.clone()]).unwrap();
+        assert!(apply_pending_restore(&data).unwrap());
+        assert!(!apply_pending_restore(&data).unwrap());
+
+        let restored = std::fs::read(data.join(DATABASE_FILE)).unwrap();
+        assert_eq!(&restored[..16], SQLITE_HEADER);
+        assert_eq!(
+            std::fs::read(data.join(PRE_RESTORE_FILE)).unwrap(),
+            b"old database"
+        );
+    }
+
+    #[test]
+    fn test_prune_keeps_manual_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = [
+            "xuan-brain-auto-backup-20240101-000000.sqlite",
+            "xuan-brain-auto-backup-20240102-000000.sqlite",
+            "xuan-brain-auto-backup-20240103-000000.sqlite",
+            "xuan-brain-backup-20230101-000000.sqlite",
+            "xuan-brain-auto-backup-notes.txt",
+            "before-upgrade.sqlite",
+        ];
+        for name in names {
+            std::fs::write(dir.path().join(name), SQLITE_HEADER).unwrap();
+        }
+
+        prune_auto_backups(dir.path(), 2);
+
+        let mut left: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            vec![
+                "before-upgrade.sqlite",
+                "xuan-brain-auto-backup-20240102-000000.sqlite",
+                "xuan-brain-auto-backup-20240103-000000.sqlite",
+                "xuan-brain-auto-backup-notes.txt",
+                "xuan-brain-backup-20230101-000000.sqlite",
+            ]
+        );
+        assert_eq!(
+            latest_backup(dir.path()).unwrap(),
+            dir.path()
+                .join("xuan-brain-auto-backup-20240103-000000.sqlite")
+        );
+    }
+
+    #[test]
+    fn test_default_backup_dir_is_outside_data_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let app_dirs = crate::testing::test_app_dirs(dir.path());
+        let backups = default_backup_dir(&app_dirs);
+        assert!(!backups.starts_with(&app_dirs.data));
+        assert_eq!(backups, dir.path().join("backups"));
+    }
+
+    #[test]
+    fn test_restore_rejects_files_outside_backup_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("backups");
+        std::fs::create_dir_all(&backups).unwrap();
+        let outside = dir.path().join("outside.sqlite");
+        std::fs::write(&outside, SQLITE_HEADER).unwrap();
+        let not_sqlite = backups.join("notes.sqlite");
+        std::fs::write(&not_sqlite, b"definitely not a database").unwrap();
+
+        assert!(matches!(
+            stage_restore(dir.path(), &outside, &[backups.clone()]),
+            Err(AppError::PermissionError { .. })
+        ));
+        assert!(matches!(
+            stage_restore(dir.path(), &not_sqlite, &[backups]),
+            Err(AppError::ValidationError { .. })
+        ));
+    }
+}
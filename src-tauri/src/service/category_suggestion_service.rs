@@ -0,0 +1,192 @@
+//! Category suggestions from keyword co-occurrence
+//!
+//! A category is suggested when papers filed under it share keywords with
+//! the paper being categorized. The paper's terms are its stored keywords
+//! plus the abstract tokens that are already keywords elsewhere in the
+//! library. Each category scores
+//! `(overlapping_keywords / total_keywords) * ln(papers_in_category + 1)`,
+//! so a broad overlap counts most and larger categories are slightly
+//! preferred over tiny ones.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::database::DatabaseConnection;
+use crate::models::CategoryNode;
+use crate::repository::{CategoryRepository, KeywordRepository, PaperRepository};
+use crate::service::keyword_service::tokenize;
+use crate::sys::error::{AppError, Result};
+
+/// Number of suggestions attached to an import result
+pub const IMPORT_SUGGESTION_COUNT: usize = 3;
+
+/// Keywords named in a suggestion's reason before the rest are elided
+const REASON_KEYWORD_COUNT: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorySuggestionDto {
+    pub category: CategoryNode,
+    pub confidence: f32,
+    /// Human-readable explanation, e.g. which keywords were shared
+    pub reason: String,
+}
+
+/// Confidence of a category sharing `overlapping` of the paper's
+/// `total_keywords` terms and holding `papers_in_category` papers
+pub fn confidence(overlapping: usize, total_keywords: usize, papers_in_category: u64) -> f32 {
+    if total_keywords == 0 {
+        return 0.0;
+    }
+    let share = overlapping as f64 / total_keywords as f64;
+    (share * (papers_in_category as f64 + 1.0).ln()) as f32
+}
+
+/// Categories for a paper, most confident first. Papers without keywords
+/// matching the rest of the library get no suggestions.
+pub async fn suggest_categories(
+    db: &DatabaseConnection,
+    paper_id: i64,
+) -> Result<Vec<CategorySuggestionDto>> {
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let mut keywords: HashMap<i64, String> = KeywordRepository::get_paper_keywords(db, paper_id)
+        .await?
+        .into_iter()
+        .map(|k| (k.id, k.word))
+        .collect();
+    if let Some(abstract_text) = paper.abstract_text.as_deref() {
+        let mut terms = tokenize(abstract_text);
+        terms.sort();
+        terms.dedup();
+        for keyword in KeywordRepository::find_by_words(db, &terms).await? {
+            keywords.entry(keyword.id).or_insert(keyword.word);
+        }
+    }
+    if keywords.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keyword_ids: Vec<i64> = keywords.keys().copied().collect();
+    let links = KeywordRepository::find_paper_links(db, &keyword_ids, paper_id).await?;
+    let paper_ids: Vec<i64> = links
+        .iter()
+        .map(|(paper_id, _)| *paper_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let paper_categories = PaperRepository::get_category_ids_batch(db, &paper_ids).await?;
+
+    // Category -> keywords of this paper found on papers in that category
+    let mut overlaps: HashMap<i64, BTreeSet<&str>> = HashMap::new();
+    for (linked_paper_id, keyword_id) in &links {
+        if let (Some(category_ids), Some(word)) = (
+            paper_categories.get(linked_paper_id),
+            keywords.get(keyword_id),
+        ) {
+            for category_id in category_ids {
+                overlaps
+                    .entry(*category_id)
+                    .or_default()
+                    .insert(word.as_str());
+            }
+        }
+    }
+
+    let category_sizes = PaperRepository::count_by_category(db).await?;
+    let categories: HashMap<i64, CategoryNode> = CategoryRepository::find_all(db)
+        .await?
+        .into_iter()
+        .map(|c| (c.id, CategoryNode::from(c)))
+        .collect();
+
+    let mut suggestions: Vec<CategorySuggestionDto> = overlaps
+        .into_iter()
+        .filter_map(|(category_id, shared)| {
+            let category = categories.get(&category_id)?.clone();
+            let papers_in_category = category_sizes.get(&category_id).copied().unwrap_or(0);
+            let mut named: Vec<&str> = shared.iter().copied().take(REASON_KEYWORD_COUNT).collect();
+            if shared.len() > REASON_KEYWORD_COUNT {
+                named.push("…");
+            }
+            Some(CategorySuggestionDto {
+                confidence: confidence(shared.len(), keywords.len(), papers_in_category),
+                reason: format!(
+                    "Shares {} of {} keywords with papers in '{}': {}",
+                    shared.len(),
+                    keywords.len(),
+                    category.name,
+                    named.join(", ")
+                ),
+                category,
+            })
+        })
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.category.sort_order.cmp(&b.category.sort_order))
+            .then_with(|| a.category.id.cmp(&b.category.id))
+    });
+
+    info!(
+        "Found {} category suggestions for paper {}",
+        suggestions.len(),
+        paper_id
+    );
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_confidence() {
+        assert_eq!(confidence(1, 0, 10), 0.0);
+        assert_eq!(confidence(2, 4, 0), 0.0);
+        let full = confidence(4, 4, 3);
+        assert!((full - 4f32.ln()).abs() < 1e-6);
+        assert!(confidence(2, 4, 3) < full);
+    }
+
+    #[tokio::test]
+    async fn test_suggest_categories_by_shared_keywords() {
+        let db = test_db().await;
+        PaperFixture::new("Protein structure")
+            .with_keyword("protein")
+            .with_keyword("folding")
+            .with_category("Biology")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Graph networks")
+            .with_keyword("graph")
+            .with_category("Machine Learning")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Trashed")
+            .with_keyword("protein")
+            .with_category("Archive")
+            .deleted()
+            .insert(&db)
+            .await;
+        let paper = PaperFixture::new("New paper")
+            .with_keyword("protein")
+            .with_abstract("Folding dynamics studied with graph models")
+            .insert(&db)
+            .await;
+
+        let suggestions = suggest_categories(&db, paper.id).await.unwrap();
+        let names: Vec<&str> = suggestions
+            .iter()
+            .map(|s| s.category.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Biology", "Machine Learning"]);
+        assert!(suggestions[0].reason.contains("folding, protein"));
+    }
+}
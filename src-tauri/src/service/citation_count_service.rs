@@ -0,0 +1,398 @@
+//! Keep citation counts fresh from OpenAlex
+//!
+//! A background task checks hourly for papers whose count was fetched more
+//! than `citation_refresh.interval_hours` ago and looks their DOIs up in
+//! batches of [`CITATION_BATCH_SIZE`] per OpenAlex request.
+//! `trigger_citation_count_refresh` runs the same refresh on demand.
+//!
+//! Every paper of a successful batch is stamped with
+//! `last_citation_refresh_at`, including DOIs OpenAlex does not know, so
+//! they are not asked for again until the interval has passed. Papers of a
+//! failed batch stay due and are retried on the next check.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, error, info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::repository::PaperRepository;
+use crate::service::quiet_hours_service::{current_pause_reason, FocusModeState};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// DOIs looked up per OpenAlex request
+pub const CITATION_BATCH_SIZE: usize = 50;
+
+const OPENALEX_WORKS_URL: &str = "https://api.openalex.org/works";
+
+/// How often the background task looks for stale counts
+const CITATION_REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Emitted after each batch
+#[derive(Debug, Clone, Serialize)]
+pub struct CitationRefreshProgress {
+    pub job_id: String,
+    /// Papers looked up so far
+    pub current: usize,
+    pub total: usize,
+    /// Papers whose count changed so far
+    pub updated: usize,
+}
+
+/// Emitted once the run is over
+#[derive(Debug, Clone, Serialize)]
+pub struct CitationRefreshSummary {
+    pub job_id: String,
+    pub total: usize,
+    pub updated: usize,
+    /// Papers of batches whose request failed
+    pub failed: usize,
+}
+
+/// Makes sure only one refresh runs at a time, whether scheduled or manual
+#[derive(Clone, Default)]
+pub struct CitationRefreshState {
+    running: Arc<AtomicBool>,
+}
+
+impl CitationRefreshState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new run. Fails if one is already running.
+    pub fn start(&self) -> Result<()> {
+        self.running
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map(|_| ())
+            .map_err(|_| {
+                AppError::validation("refresh", "A citation count refresh is already running")
+            })
+    }
+
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAlexWorks {
+    results: Vec<OpenAlexWork>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAlexWork {
+    doi: Option<String>,
+    cited_by_count: i32,
+}
+
+/// Lowercase a DOI and strip `doi:` and resolver prefixes, as OpenAlex
+/// reports DOIs as `https://doi.org/...` URLs
+pub fn normalize_doi(doi: &str) -> String {
+    let doi = doi.trim().to_lowercase();
+    [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .map(str::to_string)
+    .unwrap_or(doi)
+}
+
+/// Look up the citation counts of up to [`CITATION_BATCH_SIZE`] DOIs in one
+/// request. Returns counts by normalized DOI; unknown DOIs are missing.
+pub async fn fetch_openalex_citation_counts(
+    client: &reqwest::Client,
+    dois: &[String],
+) -> Result<HashMap<String, i32>> {
+    let filter = format!(
+        "doi:{}",
+        dois.iter()
+            .map(|d| normalize_doi(d))
+            .collect::<Vec<_>>()
+            .join("|")
+    );
+    let per_page = dois.len().to_string();
+
+    let response = client
+        .get(OPENALEX_WORKS_URL)
+        .query(&[
+            ("filter", filter.as_str()),
+            ("select", "doi,cited_by_count"),
+            ("per-page", per_page.as_str()),
+        ])
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::network_error(OPENALEX_WORKS_URL, e.to_string()))?;
+
+    let works: OpenAlexWorks = response
+        .json()
+        .await
+        .map_err(|e| AppError::network_error(OPENALEX_WORKS_URL, e.to_string()))?;
+
+    Ok(works
+        .results
+        .into_iter()
+        .filter_map(|w| Some((normalize_doi(&w.doi?), w.cited_by_count)))
+        .collect())
+}
+
+/// Papers due for a refresh: `paper_ids` regardless of age, or every paper
+/// whose count is older than the configured interval
+pub async fn find_due_papers(
+    db: &DatabaseConnection,
+    config: &AppConfig,
+    paper_ids: Option<&[i64]>,
+) -> Result<Vec<Paper>> {
+    let interval = chrono::Duration::hours(config.citation_refresh.interval_hours as i64);
+    PaperRepository::find_for_citation_refresh(db, paper_ids, Utc::now() - interval).await
+}
+
+/// Look up `papers` in batches and store changed counts.
+///
+/// A failed batch is logged and counted in the summary; the run goes on
+/// with the next batch.
+pub async fn refresh_citation_counts<F, Fut, P>(
+    db: &DatabaseConnection,
+    job_id: &str,
+    papers: &[Paper],
+    rate_limiter: &RateLimiter,
+    mut fetch: F,
+    mut on_progress: P,
+) -> Result<CitationRefreshSummary>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = Result<HashMap<String, i32>>>,
+    P: FnMut(&CitationRefreshProgress),
+{
+    let mut summary = CitationRefreshSummary {
+        job_id: job_id.to_string(),
+        total: papers.len(),
+        updated: 0,
+        failed: 0,
+    };
+
+    let mut current = 0;
+    for batch in papers.chunks(CITATION_BATCH_SIZE) {
+        let dois = batch
+            .iter()
+            .map(|p| p.doi.clone().unwrap_or_default())
+            .collect();
+
+        rate_limiter.acquire().await;
+        match fetch(dois).await {
+            Ok(counts) => {
+                for paper in batch {
+                    let doi = normalize_doi(paper.doi.as_deref().unwrap_or_default());
+                    if let Some(&count) = counts.get(&doi) {
+                        if count != paper.citation_count {
+                            PaperRepository::update_citation_count(db, paper.id, count).await?;
+                            summary.updated += 1;
+                        }
+                    }
+                }
+                let ids: Vec<i64> = batch.iter().map(|p| p.id).collect();
+                PaperRepository::mark_citations_refreshed(db, &ids, Utc::now()).await?;
+            }
+            Err(e) => {
+                warn!("Failed to fetch citation counts: {}", e);
+                summary.failed += batch.len();
+            }
+        }
+
+        current += batch.len();
+        on_progress(&CitationRefreshProgress {
+            job_id: job_id.to_string(),
+            current,
+            total: summary.total,
+            updated: summary.updated,
+        });
+    }
+
+    info!(
+        "Citation refresh {}: {} of {} updated, {} failed",
+        job_id, summary.updated, summary.total, summary.failed
+    );
+    Ok(summary)
+}
+
+/// Random id for the events of one refresh run
+pub fn new_job_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Refresh `papers` against OpenAlex, emitting `citation-refresh-progress`
+/// after each batch and `citation-refresh-complete` at the end. Releases
+/// `state` when done.
+pub async fn run_refresh_job(
+    app: &AppHandle,
+    db: &DatabaseConnection,
+    state: &CitationRefreshState,
+    job_id: &str,
+    papers: Vec<Paper>,
+) {
+    let result = match reqwest::Client::builder()
+        .user_agent("XuanBrain/0.1.0 (mailto:support@example.com)")
+        .timeout(Duration::from_secs(30))
+        .build()
+    {
+        Ok(client) => {
+            refresh_citation_counts(
+                db,
+                job_id,
+                &papers,
+                &RateLimiter::default(),
+                |dois| {
+                    let client = client.clone();
+                    async move { fetch_openalex_citation_counts(&client, &dois).await }
+                },
+                |progress| {
+                    let _ = app.emit("citation-refresh-progress", progress);
+                },
+            )
+            .await
+        }
+        Err(e) => Err(AppError::generic(format!(
+            "Failed to create HTTP client: {}",
+            e
+        ))),
+    };
+    state.finish();
+
+    let summary = result.unwrap_or_else(|e| {
+        error!("Citation refresh {} failed: {}", job_id, e);
+        CitationRefreshSummary {
+            job_id: job_id.to_string(),
+            total: papers.len(),
+            updated: 0,
+            failed: papers.len(),
+        }
+    });
+    let _ = app.emit("citation-refresh-complete", &summary);
+}
+
+/// Spawn the background task that refreshes stale citation counts.
+/// Checks are skipped during quiet hours and focus mode, and while a
+/// manual refresh is running.
+pub fn spawn_citation_refresh(
+    app: AppHandle,
+    db: Arc<DatabaseConnection>,
+    app_dirs: AppDirs,
+    focus_mode: FocusModeState,
+    state: CitationRefreshState,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(CITATION_REFRESH_CHECK_INTERVAL);
+        loop {
+            ticker.tick().await;
+            if let Some(reason) = current_pause_reason(&app_dirs, &focus_mode) {
+                debug!("Deferring citation refresh: {}", reason);
+                continue;
+            }
+            let config = AppConfig::load(&app_dirs.config).unwrap_or_default();
+            if !config.citation_refresh.enabled || state.start().is_err() {
+                continue;
+            }
+
+            let papers = match find_due_papers(&db, &config, None).await {
+                Ok(papers) => papers,
+                Err(e) => {
+                    error!("Failed to find papers for citation refresh: {}", e);
+                    state.finish();
+                    continue;
+                }
+            };
+            if papers.is_empty() {
+                state.finish();
+                continue;
+            }
+
+            let job_id = new_job_id();
+            info!(
+                "Scheduled citation refresh {} for {} papers",
+                job_id,
+                papers.len()
+            );
+            run_refresh_job(&app, &db, &state, &job_id, papers).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_normalize_doi() {
+        assert_eq!(
+            normalize_doi("https://doi.org/10.1038/Nature14539"),
+            "10.1038/nature14539"
+        );
+        assert_eq!(normalize_doi(" doi:10.1000/A "), "10.1000/a");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_skips_fresh_papers() {
+        let db = test_db().await;
+        let known = PaperFixture::new("Known")
+            .with_doi("10.1000/Known")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Unknown")
+            .with_doi("10.1000/unknown")
+            .insert(&db)
+            .await;
+        PaperFixture::new("No DOI").insert(&db).await;
+
+        let config = AppConfig::default();
+        let papers = find_due_papers(&db, &config, None).await.unwrap();
+        assert_eq!(papers.len(), 2);
+
+        let summary = refresh_citation_counts(
+            &db,
+            "job",
+            &papers,
+            &RateLimiter::new(Duration::ZERO),
+            |_dois| async { Ok(HashMap::from([("10.1000/known".to_string(), 42)])) },
+            |_| {},
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.updated, 1);
+        assert_eq!(summary.failed, 0);
+
+        let paper = PaperRepository::find_by_id(&db, known.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paper.citation_count, 42);
+
+        // Both were looked up, so neither is due until the interval passes
+        assert!(find_due_papers(&db, &config, None)
+            .await
+            .unwrap()
+            .is_empty());
+        let forced = find_due_papers(&db, &config, Some(&[known.id]))
+            .await
+            .unwrap();
+        assert_eq!(forced.len(), 1);
+    }
+}
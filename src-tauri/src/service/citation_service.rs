@@ -0,0 +1,572 @@
+//! Formatted citation strings
+//!
+//! Renders one paper as a plain-text reference in a common citation style,
+//! from the metadata already in the library. Author names are split the
+//! same way as for CSL-JSON export, and fields the paper does not have are
+//! left out along with their punctuation.
+
+use serde::Deserialize;
+
+use crate::models::{Author, Paper};
+use crate::papers::exporter::csl::{author_name, issued_date, CslName};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CitationFormat {
+    Apa,
+    Mla,
+    Chicago,
+    Vancouver,
+    Harvard,
+    Ieee,
+}
+
+/// Authors listed by APA before the list is cut to the first 19 and the last
+const APA_MAX_AUTHORS: usize = 20;
+
+/// Authors listed by Vancouver before `et al.`
+const VANCOUVER_MAX_AUTHORS: usize = 6;
+
+/// Chicago lists up to ten authors, otherwise the first seven and `et al.`
+const CHICAGO_MAX_AUTHORS: usize = 10;
+const CHICAGO_TRUNCATED_AUTHORS: usize = 7;
+
+/// Harvard and IEEE list up to this many authors, otherwise the first and `et al.`
+const HARVARD_MAX_AUTHORS: usize = 3;
+const IEEE_MAX_AUTHORS: usize = 6;
+
+/// The metadata a citation is built from, with empty fields removed
+struct Fields {
+    names: Vec<CslName>,
+    title: String,
+    year: Option<i32>,
+    container: Option<String>,
+    volume: Option<String>,
+    issue: Option<String>,
+    pages: Option<String>,
+    doi: Option<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+impl Fields {
+    fn new(paper: &Paper, authors: &[Author]) -> Self {
+        Self {
+            names: authors.iter().map(|a| author_name(a).0).collect(),
+            title: paper.title.trim().to_string(),
+            year: issued_date(paper).and_then(|d| d.date_parts.first()?.first().copied()),
+            container: non_empty(paper.journal_name.as_deref())
+                .or_else(|| non_empty(paper.conference_name.as_deref())),
+            volume: non_empty(paper.volume.as_deref()),
+            issue: non_empty(paper.issue.as_deref()),
+            pages: non_empty(paper.pages.as_deref()),
+            doi: non_empty(paper.doi.as_deref()).map(|doi| bare_doi(&doi).to_string()),
+        }
+    }
+}
+
+fn bare_doi(doi: &str) -> &str {
+    [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "doi:",
+    ]
+    .iter()
+    .find_map(|prefix| doi.strip_prefix(prefix))
+    .unwrap_or(doi)
+}
+
+/// Initials of a given name, e.g. `"John Ronald"` -> `["J", "R"]` and
+/// `"Jean-Paul"` -> `["J-P"]`
+fn initials(given: &str) -> Vec<String> {
+    given
+        .split_whitespace()
+        .map(|word| {
+            word.split('-')
+                .filter_map(|part| part.trim_matches('.').chars().next())
+                .flat_map(char::to_uppercase)
+                .map(String::from)
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|i| !i.is_empty())
+        .collect()
+}
+
+/// `"J. R."`, or `"J.-P."` for hyphenated names
+fn dotted_initials(given: Option<&str>) -> Option<String> {
+    let initials: Vec<String> = initials(given?)
+        .into_iter()
+        .map(|i| format!("{}.", i.replace('-', ".-")))
+        .collect();
+    (!initials.is_empty()).then(|| initials.join(" "))
+}
+
+/// `Family, J. R.`
+fn family_initials(name: &CslName) -> String {
+    match dotted_initials(name.given.as_deref()) {
+        Some(initials) => format!("{}, {}", name.family, initials),
+        None => name.family.clone(),
+    }
+}
+
+/// `Family, Given`
+fn family_given(name: &CslName) -> String {
+    match &name.given {
+        Some(given) => format!("{}, {}", name.family, given),
+        None => name.family.clone(),
+    }
+}
+
+/// `Given Family`
+fn given_family(name: &CslName) -> String {
+    match &name.given {
+        Some(given) => format!("{} {}", given, name.family),
+        None => name.family.clone(),
+    }
+}
+
+/// Join names as `a, b, c` with `last_separator` before the last one
+fn join_names(names: &[String], separator: &str, last_separator: &str) -> String {
+    match names.split_last() {
+        None => String::new(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{}{}{}", rest.join(separator), last_separator, last),
+    }
+}
+
+/// Append `.` unless the text already ends with sentence punctuation
+fn sentence(text: &str) -> String {
+    if text.ends_with(['.', '?', '!']) {
+        text.to_string()
+    } else {
+        format!("{}.", text)
+    }
+}
+
+/// `12(3)`, `12`, or `(3)`
+fn volume_issue(fields: &Fields) -> Option<String> {
+    match (&fields.volume, &fields.issue) {
+        (Some(volume), Some(issue)) => Some(format!("{}({})", volume, issue)),
+        (Some(volume), None) => Some(volume.clone()),
+        (None, Some(issue)) => Some(format!("({})", issue)),
+        (None, None) => None,
+    }
+}
+
+/// Render a paper and its ordered authors in `format`
+pub fn format_citation(paper: &Paper, authors: &[Author], format: CitationFormat) -> String {
+    let fields = Fields::new(paper, authors);
+    match format {
+        CitationFormat::Apa => apa(&fields),
+        CitationFormat::Mla => mla(&fields),
+        CitationFormat::Chicago => chicago(&fields),
+        CitationFormat::Vancouver => vancouver(&fields),
+        CitationFormat::Harvard => harvard(&fields),
+        CitationFormat::Ieee => ieee(&fields),
+    }
+}
+
+/// `Family, J., & Other, A. (2020). Title. Journal, 12(3), 45-67. https://doi.org/DOI`
+fn apa(f: &Fields) -> String {
+    let names: Vec<String> = f.names.iter().map(family_initials).collect();
+    let authors = if names.len() > APA_MAX_AUTHORS {
+        format!(
+            "{}, ... {}",
+            names[..APA_MAX_AUTHORS - 1].join(", "),
+            names[names.len() - 1]
+        )
+    } else {
+        join_names(&names, ", ", ", & ")
+    };
+
+    let date = match f.year {
+        Some(year) => format!("({}).", year),
+        None => "(n.d.).".to_string(),
+    };
+    let mut parts = Vec::new();
+    if !authors.is_empty() {
+        parts.push(authors);
+    }
+    parts.push(date);
+    parts.push(sentence(&f.title));
+
+    let source: Vec<String> = [f.container.clone(), volume_issue(f), f.pages.clone()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if !source.is_empty() {
+        parts.push(sentence(&source.join(", ")));
+    }
+    if let Some(doi) = &f.doi {
+        parts.push(format!("https://doi.org/{}", doi));
+    }
+    parts.join(" ")
+}
+
+/// `Family, Given, and Given Other. "Title." Journal, vol. 12, no. 3, 2020, pp. 45-67. https://doi.org/DOI.`
+fn mla(f: &Fields) -> String {
+    let authors = match f.names.as_slice() {
+        [] => None,
+        [only] => Some(family_given(only)),
+        [first, second] => Some(format!(
+            "{}, and {}",
+            family_given(first),
+            given_family(second)
+        )),
+        [first, ..] => Some(format!("{}, et al", family_given(first))),
+    };
+
+    let mut parts = Vec::new();
+    if let Some(authors) = authors {
+        parts.push(sentence(&authors));
+    }
+    parts.push(format!("\"{}\"", sentence(&f.title)));
+
+    let source: Vec<String> = [
+        f.container.clone(),
+        f.volume.as_ref().map(|v| format!("vol. {}", v)),
+        f.issue.as_ref().map(|i| format!("no. {}", i)),
+        f.year.map(|y| y.to_string()),
+        f.pages.as_ref().map(|p| format!("pp. {}", p)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !source.is_empty() {
+        parts.push(sentence(&source.join(", ")));
+    }
+    if let Some(doi) = &f.doi {
+        parts.push(format!("https://doi.org/{}.", doi));
+    }
+    parts.join(" ")
+}
+
+/// `Family, Given, and Given Other. "Title." Journal 12, no. 3 (2020): 45-67. https://doi.org/DOI.`
+fn chicago(f: &Fields) -> String {
+    let (listed, et_al) = if f.names.len() > CHICAGO_MAX_AUTHORS {
+        (&f.names[..CHICAGO_TRUNCATED_AUTHORS], true)
+    } else {
+        (&f.names[..], false)
+    };
+    let names: Vec<String> = listed
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            if i == 0 {
+                family_given(name)
+            } else {
+                given_family(name)
+            }
+        })
+        .collect();
+    let authors = if et_al {
+        format!("{}, et al", names.join(", "))
+    } else {
+        join_names(&names, ", ", ", and ")
+    };
+
+    let mut parts = Vec::new();
+    if !authors.is_empty() {
+        parts.push(sentence(&authors));
+    }
+    parts.push(format!("\"{}\"", sentence(&f.title)));
+
+    let mut source = f.container.clone().unwrap_or_default();
+    if let Some(volume) = &f.volume {
+        source = format!("{} {}", source, volume).trim().to_string();
+    }
+    if let Some(issue) = &f.issue {
+        source = if source.is_empty() {
+            format!("no. {}", issue)
+        } else {
+            format!("{}, no. {}", source, issue)
+        };
+    }
+    if let Some(year) = f.year {
+        source = format!("{} ({})", source, year).trim().to_string();
+    }
+    if let Some(pages) = &f.pages {
+        source = if source.is_empty() {
+            pages.clone()
+        } else {
+            format!("{}: {}", source, pages)
+        };
+    }
+    if !source.is_empty() {
+        parts.push(sentence(&source));
+    }
+    if let Some(doi) = &f.doi {
+        parts.push(format!("https://doi.org/{}.", doi));
+    }
+    parts.join(" ")
+}
+
+/// `Family JR, Other A. Title. Journal. 2020;12(3):45-67. doi:DOI`
+fn vancouver(f: &Fields) -> String {
+    let mut names: Vec<String> = f
+        .names
+        .iter()
+        .take(VANCOUVER_MAX_AUTHORS)
+        .map(|name| {
+            let initials: String = initials(name.given.as_deref().unwrap_or(""))
+                .into_iter()
+                .map(|i| i.replace('-', ""))
+                .collect();
+            if initials.is_empty() {
+                name.family.clone()
+            } else {
+                format!("{} {}", name.family, initials)
+            }
+        })
+        .collect();
+    if f.names.len() > VANCOUVER_MAX_AUTHORS {
+        names.push("et al".to_string());
+    }
+
+    let mut parts = Vec::new();
+    if !names.is_empty() {
+        parts.push(sentence(&names.join(", ")));
+    }
+    parts.push(sentence(&f.title));
+    if let Some(container) = &f.container {
+        parts.push(sentence(container));
+    }
+
+    let mut source = f.year.map(|y| y.to_string()).unwrap_or_default();
+    if let Some(volume_issue) = volume_issue(f) {
+        source = format!("{};{}", source, volume_issue);
+    }
+    if let Some(pages) = &f.pages {
+        source = format!("{}:{}", source, pages);
+    }
+    let source = source.trim_start_matches([';', ':']);
+    if !source.is_empty() {
+        parts.push(sentence(source));
+    }
+    if let Some(doi) = &f.doi {
+        parts.push(format!("doi:{}", doi));
+    }
+    parts.join(" ")
+}
+
+/// `Family, J. and Other, A. (2020) 'Title', Journal, 12(3), pp. 45-67. Available at: https://doi.org/DOI.`
+fn harvard(f: &Fields) -> String {
+    let names: Vec<String> = f.names.iter().map(family_initials).collect();
+    let authors = if names.len() > HARVARD_MAX_AUTHORS {
+        format!("{} et al.", names[0])
+    } else {
+        join_names(&names, ", ", " and ")
+    };
+
+    let date = match f.year {
+        Some(year) => format!("({})", year),
+        None => "(no date)".to_string(),
+    };
+    let head = format!("{} {}", authors, date).trim().to_string();
+
+    let mut parts: Vec<String> = vec![format!("{} '{}'", head, f.title.trim_end_matches('.'))];
+    parts.extend(f.container.clone());
+    parts.extend(volume_issue(f));
+    parts.extend(f.pages.as_ref().map(|p| format!("pp. {}", p)));
+
+    let mut citation = sentence(&parts.join(", "));
+    if let Some(doi) = &f.doi {
+        citation = format!("{} Available at: https://doi.org/{}.", citation, doi);
+    }
+    citation
+}
+
+/// `[1] J. Family and A. Other, "Title," Journal, vol. 12, no. 3, pp. 45-67, 2020, doi: DOI.`
+fn ieee(f: &Fields) -> String {
+    let names: Vec<String> = f
+        .names
+        .iter()
+        .map(|name| match dotted_initials(name.given.as_deref()) {
+            Some(initials) => format!("{} {}", initials, name.family),
+            None => name.family.clone(),
+        })
+        .collect();
+    let authors = if names.len() > IEEE_MAX_AUTHORS {
+        format!("{} et al.", names[0])
+    } else if names.len() > 2 {
+        join_names(&names, ", ", ", and ")
+    } else {
+        join_names(&names, ", ", " and ")
+    };
+
+    let title = format!("\"{},\"", f.title.trim_end_matches('.'));
+    let mut head = if authors.is_empty() {
+        title
+    } else {
+        format!("{}, {}", authors, title)
+    };
+
+    let rest: Vec<String> = [
+        f.container.clone(),
+        f.volume.as_ref().map(|v| format!("vol. {}", v)),
+        f.issue.as_ref().map(|i| format!("no. {}", i)),
+        f.pages.as_ref().map(|p| {
+            if p.contains(['-', '–']) {
+                format!("pp. {}", p)
+            } else {
+                format!("p. {}", p)
+            }
+        }),
+        f.year.map(|y| y.to_string()),
+        f.doi.as_ref().map(|d| format!("doi: {}", d)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if rest.is_empty() {
+        head = head.trim_end_matches(",\"").to_string() + ".\"";
+    } else {
+        head = format!("{} {}", head, rest.join(", "));
+        head = sentence(&head);
+    }
+    format!("[1] {}", head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn author(first: &str, last: &str) -> Author {
+        Author {
+            id: 1,
+            first_name: first.to_string(),
+            last_name: Some(last.to_string()),
+            affiliation: None,
+            email: None,
+            orcid: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    fn paper(value: serde_json::Value) -> Paper {
+        let mut base = serde_json::json!({
+            "id": 1,
+            "title": "Deep learning",
+            "abstract_text": null,
+            "doi": "10.1038/nature14539",
+            "publication_year": 2015,
+            "publication_date": null,
+            "journal_name": "Nature",
+            "conference_name": null,
+            "volume": "521",
+            "issue": "7553",
+            "pages": "436-444",
+            "url": null,
+            "citation_count": 0,
+            "read_status": "unread",
+            "notes": null,
+            "attachment_path": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "deleted_at": null,
+            "publisher": null,
+            "issn": null,
+            "language": null,
+            "isbn": null,
+            "attachment_count": 0
+        });
+        for (key, v) in value.as_object().unwrap() {
+            base[key] = v.clone();
+        }
+        serde_json::from_value(base).unwrap()
+    }
+
+    fn authors() -> Vec<Author> {
+        vec![
+            author("Yann", "LeCun"),
+            author("Yoshua", "Bengio"),
+            author("Geoffrey E.", "Hinton"),
+        ]
+    }
+
+    #[test]
+    fn test_full_citations() {
+        let p = paper(serde_json::json!({}));
+        let a = authors();
+        let cite = |format| format_citation(&p, &a, format);
+
+        assert_eq!(
+            cite(CitationFormat::Apa),
+            "LeCun, Y., Bengio, Y., & Hinton, G. E. (2015). Deep learning. \
+             Nature, 521(7553), 436-444. https://doi.org/10.1038/nature14539"
+        );
+        assert_eq!(
+            cite(CitationFormat::Mla),
+            "LeCun, Yann, et al. \"Deep learning.\" Nature, vol. 521, no. 7553, 2015, \
+             pp. 436-444. https://doi.org/10.1038/nature14539."
+        );
+        assert_eq!(
+            cite(CitationFormat::Chicago),
+            "LeCun, Yann, Yoshua Bengio, and Geoffrey E. Hinton. \"Deep learning.\" \
+             Nature 521, no. 7553 (2015): 436-444. https://doi.org/10.1038/nature14539."
+        );
+        assert_eq!(
+            cite(CitationFormat::Vancouver),
+            "LeCun Y, Bengio Y, Hinton GE. Deep learning. Nature. 2015;521(7553):436-444. \
+             doi:10.1038/nature14539"
+        );
+        assert_eq!(
+            cite(CitationFormat::Harvard),
+            "LeCun, Y., Bengio, Y. and Hinton, G. E. (2015) 'Deep learning', Nature, \
+             521(7553), pp. 436-444. Available at: https://doi.org/10.1038/nature14539."
+        );
+        assert_eq!(
+            cite(CitationFormat::Ieee),
+            "[1] Y. LeCun, Y. Bengio, and G. E. Hinton, \"Deep learning,\" Nature, \
+             vol. 521, no. 7553, pp. 436-444, 2015, doi: 10.1038/nature14539."
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_are_omitted() {
+        let p = paper(serde_json::json!({
+            "doi": null,
+            "publication_year": null,
+            "journal_name": null,
+            "volume": null,
+            "issue": null,
+            "pages": null
+        }));
+        let a = vec![author("Ada", "Lovelace")];
+
+        assert_eq!(
+            format_citation(&p, &a, CitationFormat::Apa),
+            "Lovelace, A. (n.d.). Deep learning."
+        );
+        assert_eq!(
+            format_citation(&p, &a, CitationFormat::Ieee),
+            "[1] A. Lovelace, \"Deep learning.\""
+        );
+        assert_eq!(
+            format_citation(&p, &[], CitationFormat::Vancouver),
+            "Deep learning."
+        );
+        assert_eq!(
+            format_citation(&p, &[], CitationFormat::Chicago),
+            "\"Deep learning.\""
+        );
+    }
+
+    #[test]
+    fn test_initials() {
+        assert_eq!(
+            dotted_initials(Some("John Ronald")).as_deref(),
+            Some("J. R.")
+        );
+        assert_eq!(dotted_initials(Some("Jean-Paul")).as_deref(), Some("J.-P."));
+        assert_eq!(dotted_initials(Some("  ")), None);
+        assert_eq!(bare_doi("https://doi.org/10.1/x"), "10.1/x");
+    }
+}
@@ -0,0 +1,392 @@
+//! Save the clipboard as a clip from a global hotkey
+//!
+//! Pressing `shortcut.capture_clipboard` reads the clipboard text:
+//! - a DOI or arXiv ID is offered for import through the
+//!   `clipboard-capture:identifier` event, since importing needs a choice
+//!   of category and may hit the network for a while
+//! - a URL is saved as a clip titled after the page's `<title>`
+//! - any other text is saved as a plain-text clip
+//!
+//! The result is shown as a system notification, and new clips are
+//! announced with `clipboard-capture:clip` so an open window can highlight
+//! them. A hotkey that cannot be registered, e.g. because another app owns
+//! it, is recorded in [`ShortcutStatusState`] instead of failing startup.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+use tracing::{info, warn};
+
+use crate::command::clip_command::{create_clip_from_request, CreateClipRequest};
+use crate::command::paper::{detect_identifier, DetectedIdentifierDto, IdentifierType};
+use crate::database::DatabaseConnection;
+use crate::repository::ClippingRepository;
+use crate::service::activity_service::{self, ACTION_CREATED, ENTITY_CLIP};
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Lowest detection confidence at which the clipboard is treated as an
+/// identifier rather than text that happens to contain one
+const MIN_IDENTIFIER_CONFIDENCE: f32 = 0.8;
+
+/// Longest title taken from the first line of a plain-text clip
+const TEXT_TITLE_CHARS: usize = 80;
+
+/// Whether the capture hotkey is registered
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShortcutStatus {
+    /// Configured hotkey, e.g. `CommandOrControl+Shift+K`
+    pub shortcut: String,
+    pub enabled: bool,
+    pub registered: bool,
+    /// Why registration failed, e.g. the hotkey is taken by another app
+    pub error: Option<String>,
+}
+
+/// Registration result of the capture hotkey, read by `get_shortcut_status`
+#[derive(Clone, Default)]
+pub struct ShortcutStatusState {
+    status: Arc<Mutex<ShortcutStatus>>,
+}
+
+impl ShortcutStatusState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> ShortcutStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set(&self, status: ShortcutStatus) {
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = status;
+    }
+}
+
+/// What the clipboard holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardContent {
+    Identifier(DetectedIdentifierDto),
+    Url(String),
+    Text(String),
+}
+
+/// Event payload announcing a clip saved from the clipboard
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedClipEvent {
+    pub clip_id: String,
+    pub title: String,
+    /// The URL was clipped before; the existing clip is announced
+    pub already_exists: bool,
+}
+
+/// Register the capture hotkey from the settings. Failures are logged and
+/// recorded in `status`.
+pub fn register_capture_shortcut(app: &AppHandle, config_dir: &str, status: &ShortcutStatusState) {
+    let config = match AppConfig::load(config_dir) {
+        Ok(config) => config.shortcut,
+        Err(e) => {
+            warn!("Failed to load shortcut config, using defaults: {}", e);
+            Default::default()
+        }
+    };
+
+    let mut result = ShortcutStatus {
+        shortcut: config.capture_clipboard.clone(),
+        enabled: config.enabled,
+        ..Default::default()
+    };
+    if !config.enabled {
+        info!("Clipboard capture shortcut is disabled in settings");
+        status.set(result);
+        return;
+    }
+
+    let registered = app.global_shortcut().on_shortcut(
+        config.capture_clipboard.as_str(),
+        |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move { capture_and_notify(&app).await });
+            }
+        },
+    );
+    match registered {
+        Ok(()) => {
+            info!(
+                "Registered clipboard capture shortcut {}",
+                config.capture_clipboard
+            );
+            result.registered = true;
+        }
+        Err(e) => {
+            warn!(
+                "Failed to register clipboard capture shortcut {}: {}",
+                config.capture_clipboard, e
+            );
+            result.error = Some(e.to_string());
+        }
+    }
+    status.set(result);
+}
+
+/// Capture the clipboard and report the outcome as a system notification.
+/// Used by the hotkey and the tray menu.
+pub async fn capture_and_notify(app: &AppHandle) {
+    let (title, body) = match capture_clipboard(app).await {
+        Ok(message) => ("Clipboard captured", message),
+        Err(e) => {
+            warn!("Clipboard capture failed: {}", e);
+            ("Clipboard capture failed", e.to_string())
+        }
+    };
+    if let Err(e) = app.notification().builder().title(title).body(&body).show() {
+        warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Act on the clipboard and return a message describing what was done
+async fn capture_clipboard(app: &AppHandle) -> Result<String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| AppError::generic(format!("Clipboard has no text: {}", e)))?;
+    let content = classify_clipboard(&text)
+        .ok_or_else(|| AppError::validation("clipboard", "Clipboard is empty"))?;
+
+    if let ClipboardContent::Identifier(identifier) = content {
+        info!(
+            "Offering clipboard identifier {} for import",
+            identifier.value
+        );
+        let _ = app.emit("clipboard-capture:identifier", &identifier);
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return Ok(format!(
+            "Found {}; open xuan-brain to import it",
+            identifier.value
+        ));
+    }
+
+    // Not managed when the database failed to open at startup
+    let (Some(db), Some(app_dirs)) = (
+        app.try_state::<Arc<DatabaseConnection>>(),
+        app.try_state::<AppDirs>(),
+    ) else {
+        return Err(AppError::generic(
+            "Database unavailable; the clip could not be saved",
+        ));
+    };
+    let event = save_clip(&db, &app_dirs.files, content).await?;
+    let _ = app.emit("clipboard-capture:clip", &event);
+
+    Ok(if event.already_exists {
+        format!("Already saved: {}", event.title)
+    } else {
+        format!("Saved clip: {}", event.title)
+    })
+}
+
+/// Save a URL or text from the clipboard as a clip
+async fn save_clip(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    content: ClipboardContent,
+) -> Result<CapturedClipEvent> {
+    let request = match content {
+        ClipboardContent::Url(url) => {
+            let title = fetch_page_title(&url).await.unwrap_or_else(|| url.clone());
+            CreateClipRequest {
+                title,
+                source_domain: url_host(&url).unwrap_or_default().to_string(),
+                url,
+                content: String::new(),
+                author: None,
+                published_date: None,
+                excerpt: None,
+                thumbnail_url: None,
+                tags: Vec::new(),
+            }
+        }
+        ClipboardContent::Text(text) => {
+            // Equal texts share a URL, so copying the same text twice does
+            // not create a second clip
+            let hash = format!("{:x}", Sha256::digest(text.as_bytes()));
+            CreateClipRequest {
+                title: text_title(&text),
+                url: format!("text:{}", &hash[..16]),
+                content: text.clone(),
+                source_domain: "clipboard".to_string(),
+                author: None,
+                published_date: None,
+                excerpt: Some(text.chars().take(200).collect()),
+                thumbnail_url: None,
+                tags: Vec::new(),
+            }
+        }
+        ClipboardContent::Identifier(_) => {
+            return Err(AppError::validation(
+                "clipboard",
+                "Identifiers are imported, not clipped",
+            ))
+        }
+    };
+
+    if let Some(existing) = ClippingRepository::find_by_url(db, &request.url).await? {
+        return Ok(CapturedClipEvent {
+            clip_id: existing.id.to_string(),
+            title: existing.title,
+            already_exists: true,
+        });
+    }
+
+    let created = create_clip_from_request(db, files_dir, request).await?;
+    if let Ok(id) = created.id.parse::<i64>() {
+        activity_service::record(db, ENTITY_CLIP, id, ACTION_CREATED).await;
+    }
+    Ok(CapturedClipEvent {
+        clip_id: created.id,
+        title: created.title,
+        already_exists: false,
+    })
+}
+
+/// Decide what the clipboard holds. Returns `None` for blank text.
+pub fn classify_clipboard(text: &str) -> Option<ClipboardContent> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Some(identifier) = detect_identifier(text).filter(|id| {
+        matches!(
+            id.identifier_type,
+            IdentifierType::Doi | IdentifierType::ArxivId
+        ) && id.confidence >= MIN_IDENTIFIER_CONFIDENCE
+    }) {
+        return Some(ClipboardContent::Identifier(identifier));
+    }
+
+    let is_url = (text.starts_with("http://") || text.starts_with("https://"))
+        && !text.contains(char::is_whitespace)
+        && url_host(text).is_some();
+    if is_url {
+        return Some(ClipboardContent::Url(text.to_string()));
+    }
+
+    Some(ClipboardContent::Text(text.to_string()))
+}
+
+/// Host of an http(s) URL, without port or credentials
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://")?.1;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then_some(host)
+}
+
+/// First line of a text, shortened to a title
+fn text_title(text: &str) -> String {
+    let first_line = text.lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() <= TEXT_TITLE_CHARS {
+        return first_line.to_string();
+    }
+    let mut title: String = first_line.chars().take(TEXT_TITLE_CHARS - 1).collect();
+    title.push('…');
+    title
+}
+
+/// `<title>` of a web page, or `None` when it cannot be fetched
+async fn fetch_page_title(url: &str) -> Option<String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+        .ok()?;
+    let html = match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => response.text().await.ok()?,
+        Ok(response) => {
+            warn!(
+                "Fetching {} for its title returned {}",
+                url,
+                response.status()
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("Failed to fetch {} for its title: {}", url, e);
+            return None;
+        }
+    };
+    extract_title(&html)
+}
+
+/// Text of the first `<title>` element, with whitespace collapsed and the
+/// common entities decoded
+fn extract_title(html: &str) -> Option<String> {
+    let pattern = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap();
+    let raw = pattern.captures(html)?.get(1)?.as_str();
+    let title = raw
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_clipboard() {
+        assert!(matches!(
+            classify_clipboard("https://doi.org/10.1038/nature14539"),
+            Some(ClipboardContent::Identifier(id)) if id.value == "10.1038/nature14539"
+        ));
+        assert!(matches!(
+            classify_clipboard(" arXiv:1706.03762 "),
+            Some(ClipboardContent::Identifier(id)) if id.identifier_type == IdentifierType::ArxivId
+        ));
+        assert_eq!(
+            classify_clipboard("https://example.com/post?id=1"),
+            Some(ClipboardContent::Url(
+                "https://example.com/post?id=1".to_string()
+            ))
+        );
+        assert_eq!(
+            classify_clipboard("see https://example.com for details"),
+            Some(ClipboardContent::Text(
+                "see https://example.com for details".to_string()
+            ))
+        );
+        assert_eq!(classify_clipboard("  \n "), None);
+    }
+
+    #[test]
+    fn test_extract_title() {
+        let html = "<html><head><TITLE data-x=1>\n  Attention &amp; Memory\n</TITLE></head></html>";
+        assert_eq!(extract_title(html).as_deref(), Some("Attention & Memory"));
+        assert_eq!(extract_title("<title> </title>"), None);
+        assert_eq!(
+            url_host("https://user@example.com:8080/a"),
+            Some("example.com")
+        );
+    }
+}
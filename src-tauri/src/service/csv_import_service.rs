@@ -0,0 +1,436 @@
+//! Paper import from generic CSV files
+//!
+//! Rows come from [`CsvPaperReader`] and are handled in chunks, so memory use
+//! does not grow with the size of the file. Each chunk is written with one
+//! multi-row insert per table in a single transaction; when that fails,
+//! every row of the chunk is reported as failed. Papers whose DOI is already
+//! in the library, or earlier in the same file, are skipped. Authors and
+//! labels named in a row are created the first time they are seen.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use sea_orm::*;
+use tracing::info;
+
+use crate::database::entities::{paper, paper_author, paper_label};
+use crate::database::DatabaseConnection;
+use crate::models::{AuthorDetails, CreateLabel};
+use crate::papers::importer::csv_file::{
+    CsvColumnMapping, CsvImportError, CsvPaperReader, CsvPaperRow, CsvRowError,
+};
+use crate::repository::{AuthorRepository, LabelRepository, PaperRepository};
+use crate::service::activity_service::{self, ACTION_IMPORTED, ENTITY_PAPER};
+use crate::service::attachment_service::new_attachment_dir;
+use crate::sys::error::{AppError, Result};
+
+/// Rows looked up and written together
+const CHUNK_SIZE: usize = 500;
+
+/// Author or label links written by one insert, well below SQLite's limit
+/// of bound parameters
+const LINK_BATCH_SIZE: usize = 1000;
+
+/// Color given to labels created by the import
+const IMPORTED_LABEL_COLOR: &str = "#607D8B";
+
+/// Outcome of a CSV import
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportSummary {
+    /// Rows read, excluding the header
+    pub total: usize,
+    /// Papers created, or that would be created in a dry run
+    pub imported: usize,
+    /// Rows whose DOI is already in the library or earlier in the file
+    pub skipped: usize,
+    pub failed: usize,
+    /// Ids of the created papers
+    pub paper_ids: Vec<i64>,
+    /// Rows that failed, in file order
+    pub errors: Vec<CsvRowError>,
+}
+
+/// Import the papers in the CSV file at `path`. With `dry_run` the file is
+/// parsed, validated and checked for duplicates, but nothing is written.
+/// `on_progress` is called with the number of rows handled after each chunk.
+pub async fn import_csv(
+    db: &DatabaseConnection,
+    path: &Path,
+    mapping: &CsvColumnMapping,
+    dry_run: bool,
+    mut on_progress: impl FnMut(usize),
+) -> Result<CsvImportSummary> {
+    let reader = CsvPaperReader::open(path, mapping).map_err(|e| match e {
+        CsvImportError::ReadError(msg) => {
+            AppError::file_system(path.to_string_lossy().to_string(), msg)
+        }
+        e => AppError::validation("mapping", e.to_string()),
+    })?;
+
+    let mut summary = CsvImportSummary::default();
+    let mut seen_dois: HashSet<String> = HashSet::new();
+    let mut cache = LinkCache::default();
+    let mut chunk: Vec<CsvPaperRow> = Vec::with_capacity(CHUNK_SIZE);
+
+    let mut rows = reader.peekable();
+    while let Some(row) = rows.next() {
+        summary.total += 1;
+        match row {
+            Ok(row) => chunk.push(row),
+            Err(error) => {
+                summary.failed += 1;
+                summary.errors.push(error);
+            }
+        }
+
+        if chunk.len() == CHUNK_SIZE || rows.peek().is_none() {
+            import_chunk(
+                db,
+                std::mem::take(&mut chunk),
+                dry_run,
+                &mut seen_dois,
+                &mut cache,
+                &mut summary,
+            )
+            .await?;
+            on_progress(summary.total);
+        }
+    }
+
+    info!(
+        "CSV import of {:?}{}: {} imported, {} skipped, {} failed of {} rows",
+        path,
+        if dry_run { " (dry run)" } else { "" },
+        summary.imported,
+        summary.skipped,
+        summary.failed,
+        summary.total
+    );
+    Ok(summary)
+}
+
+async fn import_chunk(
+    db: &DatabaseConnection,
+    rows: Vec<CsvPaperRow>,
+    dry_run: bool,
+    seen_dois: &mut HashSet<String>,
+    cache: &mut LinkCache,
+    summary: &mut CsvImportSummary,
+) -> Result<()> {
+    let dois: Vec<String> = rows.iter().filter_map(|row| row.doi.clone()).collect();
+    let existing = PaperRepository::find_ids_by_dois(db, &dois).await?;
+
+    let mut new_rows = Vec::with_capacity(rows.len());
+    for row in rows {
+        if let Some(doi) = &row.doi {
+            let key = doi.to_lowercase();
+            if existing.contains_key(&key) || !seen_dois.insert(key) {
+                summary.skipped += 1;
+                continue;
+            }
+        }
+        new_rows.push(row);
+    }
+
+    if dry_run {
+        summary.imported += new_rows.len();
+        return Ok(());
+    }
+
+    let lines: Vec<u64> = new_rows.iter().map(|row| row.line).collect();
+    match create_papers(db, new_rows, cache).await {
+        Ok(paper_ids) => {
+            for paper_id in &paper_ids {
+                activity_service::record(db, ENTITY_PAPER, *paper_id, ACTION_IMPORTED).await;
+            }
+            summary.imported += paper_ids.len();
+            summary.paper_ids.extend(paper_ids);
+        }
+        Err(e) => {
+            summary.failed += lines.len();
+            summary
+                .errors
+                .extend(lines.into_iter().map(|line| CsvRowError {
+                    line,
+                    message: e.to_string(),
+                }));
+        }
+    }
+    Ok(())
+}
+
+/// Author and label ids by name, filled as rows name them
+#[derive(Default)]
+struct LinkCache {
+    authors: HashMap<String, i64>,
+    labels: HashMap<String, i64>,
+}
+
+impl LinkCache {
+    async fn author_id(&mut self, db: &DatabaseConnection, name: &str) -> Result<i64> {
+        if let Some(&id) = self.authors.get(name) {
+            return Ok(id);
+        }
+        let author = AuthorRepository::create_or_find(db, name, &AuthorDetails::default()).await?;
+        self.authors.insert(name.to_string(), author.id);
+        Ok(author.id)
+    }
+
+    async fn label_id(&mut self, db: &DatabaseConnection, name: &str) -> Result<i64> {
+        if let Some(&id) = self.labels.get(name) {
+            return Ok(id);
+        }
+        let label = match LabelRepository::find_by_name(db, name).await? {
+            Some(label) => label,
+            None => {
+                LabelRepository::create(
+                    db,
+                    CreateLabel {
+                        name: name.to_string(),
+                        color: IMPORTED_LABEL_COLOR.to_string(),
+                        parent_id: None,
+                    },
+                )
+                .await?
+            }
+        };
+        self.labels.insert(name.to_string(), label.id);
+        Ok(label.id)
+    }
+}
+
+/// Create the papers of a chunk with one multi-row insert per table, in a
+/// single transaction. Returns the new paper ids in row order.
+async fn create_papers(
+    db: &DatabaseConnection,
+    rows: Vec<CsvPaperRow>,
+    cache: &mut LinkCache,
+) -> Result<Vec<i64>> {
+    if rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Authors and labels are looked up (or created) before the transaction;
+    // each row keeps its first mention of a name
+    let mut links: Vec<(Vec<i64>, Vec<i64>)> = Vec::with_capacity(rows.len());
+    for row in &rows {
+        let mut author_ids = Vec::new();
+        for name in &row.authors {
+            let id = cache.author_id(db, name).await?;
+            if !author_ids.contains(&id) {
+                author_ids.push(id);
+            }
+        }
+        let mut label_ids = Vec::new();
+        for name in &row.labels {
+            let id = cache.label_id(db, name).await?;
+            if !label_ids.contains(&id) {
+                label_ids.push(id);
+            }
+        }
+        links.push((author_ids, label_ids));
+    }
+
+    // The random attachment directory of each paper finds its id again
+    // after the multi-row insert
+    let dirs: Vec<String> = rows.iter().map(|_| new_attachment_dir()).collect();
+    let now = chrono::Utc::now();
+    let insert_error = |e: DbErr| AppError::generic(format!("Failed to create papers: {}", e));
+
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+    paper::Entity::insert_many(
+        rows.into_iter()
+            .zip(&dirs)
+            .map(|(row, dir)| paper::ActiveModel {
+                title: Set(row.title),
+                abstract_text: Set(row.abstract_text),
+                doi: Set(row.doi),
+                publication_year: Set(row.year),
+                journal_name: Set(row.journal),
+                url: Set(row.url),
+                citation_count: Set(0),
+                read_status: Set("unread".to_string()),
+                attachment_path: Set(Some(dir.clone())),
+                attachment_count: Set(0),
+                is_starred: Set(false),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }),
+    )
+    .exec(&txn)
+    .await
+    .map_err(insert_error)?;
+
+    let ids_by_dir: HashMap<String, i64> = paper::Entity::find()
+        .select_only()
+        .column(paper::Column::AttachmentPath)
+        .column(paper::Column::Id)
+        .filter(paper::Column::AttachmentPath.is_in(dirs.clone()))
+        .into_tuple::<(Option<String>, i64)>()
+        .all(&txn)
+        .await
+        .map_err(insert_error)?
+        .into_iter()
+        .filter_map(|(dir, id)| Some((dir?, id)))
+        .collect();
+    let paper_ids = dirs
+        .iter()
+        .map(|dir| {
+            ids_by_dir
+                .get(dir)
+                .copied()
+                .ok_or_else(|| AppError::generic("Failed to find a created paper"))
+        })
+        .collect::<Result<Vec<i64>>>()?;
+
+    let authors: Vec<paper_author::ActiveModel> = paper_ids
+        .iter()
+        .zip(&links)
+        .flat_map(|(paper_id, (author_ids, _))| {
+            author_ids
+                .iter()
+                .enumerate()
+                .map(|(order, author_id)| paper_author::ActiveModel {
+                    paper_id: Set(*paper_id),
+                    author_id: Set(*author_id),
+                    author_order: Set(order as i32),
+                    is_corresponding: Set(0),
+                    ..Default::default()
+                })
+        })
+        .collect();
+    for batch in authors.chunks(LINK_BATCH_SIZE) {
+        paper_author::Entity::insert_many(batch.to_vec())
+            .exec(&txn)
+            .await
+            .map_err(insert_error)?;
+    }
+
+    let labels: Vec<paper_label::ActiveModel> = paper_ids
+        .iter()
+        .zip(&links)
+        .flat_map(|(paper_id, (_, label_ids))| {
+            label_ids.iter().map(|label_id| paper_label::ActiveModel {
+                paper_id: Set(*paper_id),
+                label_id: Set(*label_id),
+                ..Default::default()
+            })
+        })
+        .collect();
+    for batch in labels.chunks(LINK_BATCH_SIZE) {
+        paper_label::Entity::insert_many(batch.to_vec())
+            .exec(&txn)
+            .await
+            .map_err(insert_error)?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))?;
+
+    let touched: HashSet<i64> = links
+        .iter()
+        .flat_map(|(_, label_ids)| label_ids.iter().copied())
+        .collect();
+    for label_id in touched {
+        LabelRepository::update_document_count(db, label_id).await?;
+    }
+
+    Ok(paper_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    fn mapping() -> CsvColumnMapping {
+        CsvColumnMapping {
+            title: "Title".to_string(),
+            authors: Some("Authors".to_string()),
+            year: Some("Year".to_string()),
+            doi: Some("DOI".to_string()),
+            labels: Some("Tags".to_string()),
+            ..Default::default()
+        }
+    }
+
+    const CSV: &str = concat!(
+        "Title,Authors,Year,DOI,Tags\n",
+        "Existing,,2019,10.1000/EXISTING,\n",
+        "New paper,Ada Lovelace; Alan Turing,2021,10.1000/new,reading; ml\n",
+        "Same DOI again,,2021,10.1000/NEW,\n",
+        "No DOI,,not a year,,\n",
+        "Another,,,,ml\n",
+    );
+
+    #[tokio::test]
+    async fn test_import_skips_duplicates_and_reports_bad_rows() {
+        let db = test_db().await;
+        PaperFixture::new("Existing")
+            .with_doi("10.1000/existing")
+            .insert(&db)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.csv");
+        std::fs::write(&path, CSV).unwrap();
+
+        let summary = import_csv(&db, &path, &mapping(), false, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(
+            (
+                summary.total,
+                summary.imported,
+                summary.skipped,
+                summary.failed
+            ),
+            (5, 2, 2, 1)
+        );
+        assert_eq!(summary.errors[0].line, 5);
+
+        let paper = PaperRepository::find_by_doi(&db, "10.1000/new")
+            .await
+            .unwrap()
+            .unwrap();
+        let authors = AuthorRepository::get_paper_authors(&db, paper.id)
+            .await
+            .unwrap();
+        assert_eq!(authors.len(), 2);
+        let ml = LabelRepository::find_by_name(&db, "ml")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ml.document_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_writes_nothing() {
+        let db = test_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("library.csv");
+        std::fs::write(&path, CSV).unwrap();
+
+        let summary = import_csv(&db, &path, &mapping(), true, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(
+            (summary.imported, summary.skipped, summary.failed),
+            (3, 1, 1)
+        );
+        assert!(summary.paper_ids.is_empty());
+        assert!(LabelRepository::find_by_name(&db, "ml")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(PaperRepository::find_by_doi(&db, "10.1000/new")
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -1,25 +1,176 @@
 //! Data migration service for moving application data between folders
 //!
 //! This module provides functionality to migrate all application data
-//! (database, files, cache, config, logs) from one location to another.
+//! (database, files, backups, cache, config, logs) from one location to another.
 
+use std::collections::HashMap;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Emitter};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+use crate::database::connection::{check_integrity, checkpoint_and_close};
+use crate::database::DatabaseConnection;
+use crate::service::backup_service::DATABASE_FILE;
 use crate::sys::{
     consts::APP_FOLDER,
-    dirs::{save_data_path_config, DataPathConfig, MigrationPhase, MigrationStatus},
+    dirs::{
+        get_available_space, save_data_path_config, AppDirs, DataPathConfig, MigrationPhase,
+        MigrationStatus,
+    },
     error::{AppError, Result},
 };
 
+/// Snapshot written to the config directory before a data folder migration
+const MIGRATION_STATE_FILE: &str = "migration-state.json";
+
+/// Subdirectories of the XuanBrain folder that are migrated. The source
+/// folder is deleted on the next start, so everything the app keeps there
+/// has to be listed.
+const MIGRATED_DIRS: [&str; 6] = ["data", "files", "backups", "cache", "config", "logs"];
+
+/// Appended to the error of a cancelled or failed migration that kept its
+/// partial copy
+const RESUME_HINT: &str = "Copied files are kept; migrating to the same folder again resumes";
+
+/// Non-database files whose checksums are compared when verifying, unless
+/// the migration is asked to check every file
+pub const DEFAULT_CHECKSUM_SAMPLE: usize = 200;
+
+/// Mismatched files named in the error of a failed verification
+const MISMATCH_REPORT_LIMIT: usize = 10;
+
+/// Which files have their SHA-256 compared after copying. Database files
+/// are always compared.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChecksumSample {
+    All,
+    /// At most this many other files, spread evenly over the library
+    Count(usize),
+}
+
+/// Where a data folder migration started from, kept so that a finished
+/// migration can be rolled back until the app restarts into the new folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationState {
+    /// Source base directory as passed to `DataMigrationService::new`
+    pub source_base: String,
+    /// Destination base directory as passed to `DataMigrationService::new`
+    pub dest_base: String,
+    /// Directories the app was using when the migration started
+    pub app_dirs: AppDirs,
+    pub started_at: DateTime<Utc>,
+}
+
+impl MigrationState {
+    pub fn new(source_base: &Path, dest_base: &Path, app_dirs: &AppDirs) -> Self {
+        Self {
+            source_base: source_base.to_string_lossy().to_string(),
+            dest_base: dest_base.to_string_lossy().to_string(),
+            app_dirs: app_dirs.clone(),
+            started_at: Utc::now(),
+        }
+    }
+
+    fn path(config_dir: &str) -> PathBuf {
+        PathBuf::from(config_dir).join(MIGRATION_STATE_FILE)
+    }
+
+    /// Write the snapshot to `config_dir`, replacing an earlier one
+    pub fn save(&self, config_dir: &str) -> Result<()> {
+        let path = Self::path(config_dir);
+        let content = serde_json::to_string_pretty(self).map_err(|e| {
+            AppError::config_error(MIGRATION_STATE_FILE, format!("Failed to serialize: {}", e))
+        })?;
+        fs::write(&path, content).map_err(|e| {
+            AppError::file_system(
+                path.display().to_string(),
+                format!("Failed to write migration state: {}", e),
+            )
+        })
+    }
+
+    /// The snapshot in `config_dir`, if a migration recorded one
+    pub fn load(config_dir: &str) -> Result<Option<Self>> {
+        let path = Self::path(config_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path).map_err(|e| {
+            AppError::file_system(
+                path.display().to_string(),
+                format!("Failed to read migration state: {}", e),
+            )
+        })?;
+        serde_json::from_str(&content).map(Some).map_err(|e| {
+            AppError::config_error(MIGRATION_STATE_FILE, format!("Failed to parse: {}", e))
+        })
+    }
+
+    /// Remove the snapshot from `config_dir`. Failures are only logged.
+    pub fn clear(config_dir: &str) {
+        let path = Self::path(config_dir);
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("Failed to remove {:?}: {}", path, e);
+            }
+        }
+    }
+}
+
+/// Cancellation handle of the running data folder migration, if any
+#[derive(Clone, Default)]
+pub struct DataMigrationState {
+    running: Arc<Mutex<Option<CancellationToken>>>,
+}
+
+impl DataMigrationState {
+    /// Register a new migration. Fails if one is already running.
+    pub fn start(&self) -> Result<CancellationToken> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.is_some() {
+            return Err(AppError::migration_error(
+                "prepare",
+                "A data folder migration is already running",
+            ));
+        }
+        let token = CancellationToken::new();
+        *running = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Cancel the running migration. Returns false when none is running.
+    pub fn cancel(&self) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        match running.as_ref() {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self) {
+        *self.running.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
 /// Data migration service
 pub struct DataMigrationService {
     /// Source base directory (parent of XuanBrain folder)
     source_base: PathBuf,
     /// Destination base directory (parent of XuanBrain folder)
     dest_base: PathBuf,
+    checksum_sample: ChecksumSample,
 }
 
 impl DataMigrationService {
@@ -28,13 +179,20 @@ impl DataMigrationService {
         Self {
             source_base,
             dest_base,
+            checksum_sample: ChecksumSample::Count(DEFAULT_CHECKSUM_SAMPLE),
         }
     }
 
+    /// Set which copied files are checksummed during verification
+    pub fn with_checksum_sample(mut self, checksum_sample: ChecksumSample) -> Self {
+        self.checksum_sample = checksum_sample;
+        self
+    }
+
     /// Get the actual XuanBrain directory from a base path
     /// If the path already ends with APP_FOLDER, return it directly
     /// Otherwise, append APP_FOLDER
-    fn get_xuanbrain_dir(base: &Path) -> PathBuf {
+    pub fn get_xuanbrain_dir(base: &Path) -> PathBuf {
         if base.file_name()
             .map(|name| name.to_string_lossy() == APP_FOLDER)
             .unwrap_or(false)
@@ -62,7 +220,40 @@ impl DataMigrationService {
     }
 
     /// Execute the migration process
-    pub async fn migrate(&self, app_handle: &AppHandle) -> Result<()> {
+    ///
+    /// Files that already have a complete copy in the destination, left by
+    /// an earlier cancelled or failed run, are skipped. `cancel` stops the
+    /// migration before the next file. A cancelled or failed migration keeps
+    /// what was copied so far; only a copy that fails verification is
+    /// removed. Either way a `Cancelled` or `Failed` status is emitted.
+    pub async fn migrate<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let result = self.run(app_handle, cancel).await;
+        if let Err(e) = &result {
+            let kept_copy = Self::get_xuanbrain_dir(&self.dest_base).exists();
+            let (phase, error) = if cancel.is_cancelled() {
+                (
+                    MigrationPhase::Cancelled,
+                    format!("Migration cancelled. {}", RESUME_HINT),
+                )
+            } else if kept_copy {
+                (MigrationPhase::Failed, format!("{}. {}", e, RESUME_HINT))
+            } else {
+                (MigrationPhase::Failed, e.to_string())
+            };
+            let _ = self.emit_status(app_handle, phase, 0, 0, None, Some(error));
+        }
+        result
+    }
+
+    async fn run<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
         let source_dir = Self::get_xuanbrain_dir(&self.source_base);
         let dest_dir = Self::get_xuanbrain_dir(&self.dest_base);
 
@@ -71,12 +262,81 @@ impl DataMigrationService {
             source_dir, dest_dir
         );
 
+        let total_files = self.copy_and_verify(app_handle, cancel).await?;
+
+        // Update configuration with pending cleanup path
+        // Save the path without APP_FOLDER suffix (the actual parent directory)
+        // If the path already ends with APP_FOLDER, save its parent instead
+        let config_path = if self
+            .dest_base
+            .file_name()
+            .map(|name| name.to_string_lossy() == APP_FOLDER)
+            .unwrap_or(false)
+        {
+            self.dest_base
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.dest_base.to_string_lossy().to_string())
+        } else {
+            self.dest_base.to_string_lossy().to_string()
+        };
+
+        // Record source path for cleanup on next startup
+        let source_cleanup_path = Self::get_xuanbrain_dir(&self.source_base)
+            .to_string_lossy()
+            .to_string();
+
+        let config = DataPathConfig {
+            custom_data_path: Some(config_path),
+            version: 1,
+            pending_cleanup_path: Some(source_cleanup_path),
+        };
+        save_data_path_config(&config)?;
+
+        // Emit completion status
+        self.emit_status(
+            app_handle,
+            MigrationPhase::Completed,
+            total_files,
+            total_files,
+            None,
+            None,
+        )?;
+
+        info!("Data migration completed successfully");
+        Ok(())
+    }
+
+    /// Copy every migrated directory into the destination and verify the
+    /// copy, returning the number of files. A copy that fails verification
+    /// is rolled back.
+    async fn copy_and_verify<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
+    ) -> Result<u32> {
+        let dest_dir = Self::get_xuanbrain_dir(&self.dest_base);
+
         // Emit initial status
         self.emit_status(app_handle, MigrationPhase::Preparing, 0, 100, None, None)?;
 
         // Prepare for migration
         self.prepare()?;
 
+        // Only what an earlier run did not copy yet has to fit
+        let needed = self.bytes_to_copy()?;
+        if let Some(available) = get_available_space(&dest_dir) {
+            if available < needed {
+                return Err(AppError::migration_error(
+                    "prepare",
+                    format!(
+                        "Insufficient disk space: {} bytes to copy, {} bytes available",
+                        needed, available
+                    ),
+                ));
+            }
+        }
+
         // Count total files for progress tracking
         let total_files = self.count_files()?;
         let mut processed_files: u32 = 0;
@@ -90,7 +350,9 @@ impl DataMigrationService {
             None,
             None,
         )?;
-        processed_files += self.copy_database(app_handle, total_files, processed_files).await?;
+        processed_files += self
+            .copy_database(app_handle, cancel, total_files, processed_files)
+            .await?;
 
         // Copy config files
         self.emit_status(
@@ -101,7 +363,7 @@ impl DataMigrationService {
             None,
             None,
         )?;
-        processed_files += self.copy_config(app_handle, total_files, processed_files)?;
+        processed_files += self.copy_config(app_handle, cancel, total_files, processed_files)?;
 
         // Copy files (PDF attachments)
         self.emit_status(
@@ -112,79 +374,66 @@ impl DataMigrationService {
             None,
             None,
         )?;
-        processed_files += self.copy_files(app_handle, total_files, processed_files)?;
+        processed_files += self.copy_files(app_handle, cancel, total_files, processed_files)?;
 
-        // Copy cache
+        // Copy backups
         self.emit_status(
             app_handle,
-            MigrationPhase::CopyingCache,
+            MigrationPhase::CopyingBackups,
             processed_files,
             total_files,
             None,
             None,
         )?;
-        processed_files += self.copy_cache(app_handle, total_files, processed_files)?;
+        processed_files += self.copy_backups(app_handle, cancel, total_files, processed_files)?;
 
-        // Copy logs
+        // Copy cache
         self.emit_status(
             app_handle,
-            MigrationPhase::CopyingLogs,
+            MigrationPhase::CopyingCache,
             processed_files,
             total_files,
             None,
             None,
         )?;
-        let _ = self.copy_logs(app_handle, total_files, processed_files)?;
+        processed_files += self.copy_cache(app_handle, cancel, total_files, processed_files)?;
 
-        // Verify migration
+        // Copy logs
         self.emit_status(
             app_handle,
-            MigrationPhase::Verifying,
-            total_files,
+            MigrationPhase::CopyingLogs,
+            processed_files,
             total_files,
             None,
             None,
         )?;
-        self.verify()?;
-
-        // Update configuration with pending cleanup path
-        // Save the path without APP_FOLDER suffix (the actual parent directory)
-        // If the path already ends with APP_FOLDER, save its parent instead
-        let config_path = if self.dest_base.file_name()
-            .map(|name| name.to_string_lossy() == APP_FOLDER)
-            .unwrap_or(false)
-        {
-            self.dest_base.parent()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| self.dest_base.to_string_lossy().to_string())
-        } else {
-            self.dest_base.to_string_lossy().to_string()
-        };
+        let _ = self.copy_logs(app_handle, cancel, total_files, processed_files)?;
 
-        // Record source path for cleanup on next startup
-        let source_cleanup_path = Self::get_xuanbrain_dir(&self.source_base)
-            .to_string_lossy()
-            .to_string();
-
-        let config = DataPathConfig {
-            custom_data_path: Some(config_path),
-            version: 1,
-            pending_cleanup_path: Some(source_cleanup_path),
-        };
-        save_data_path_config(&config)?;
-
-        // Emit completion status
+        // Verify migration
         self.emit_status(
             app_handle,
-            MigrationPhase::Completed,
-            total_files,
+            MigrationPhase::Verifying,
+            0,
             total_files,
             None,
             None,
         )?;
+        // A copy that fails verification cannot be resumed from. The data
+        // path config is only switched below, after verification passed.
+        if let Err(e) = self.verify(app_handle, cancel).await {
+            if cancel.is_cancelled() {
+                return Err(e);
+            }
+            if let Err(rollback_err) = self.rollback(app_handle) {
+                warn!(
+                    "Rollback after failed verification failed: {}",
+                    rollback_err
+                );
+            }
+            return Err(e);
+        }
 
-        info!("Data migration completed successfully");
-        Ok(())
+        Ok(total_files)
     }
 
     /// Prepare for migration
@@ -214,8 +463,7 @@ impl DataMigrationService {
         let source_dir = Self::get_xuanbrain_dir(&self.source_base);
         let mut count: u32 = 0;
 
-        let subdirs = ["data", "files", "cache", "config", "logs"];
-        for subdir in subdirs {
+        for subdir in MIGRATED_DIRS {
             let dir_path = source_dir.join(subdir);
             if dir_path.exists() {
                 count += count_files_in_dir(&dir_path)?;
@@ -225,10 +473,24 @@ impl DataMigrationService {
         Ok(count.max(1)) // At least 1 to avoid division by zero
     }
 
+    /// Bytes still to be copied: the size of every source file without a
+    /// complete copy in the destination. Database files are always copied.
+    pub fn bytes_to_copy(&self) -> Result<u64> {
+        let source_dir = Self::get_xuanbrain_dir(&self.source_base);
+        let dest_dir = Self::get_xuanbrain_dir(&self.dest_base);
+
+        let mut bytes = 0;
+        for subdir in MIGRATED_DIRS {
+            bytes += pending_bytes(&source_dir.join(subdir), &dest_dir.join(subdir))?;
+        }
+        Ok(bytes)
+    }
+
     /// Copy database files
-    async fn copy_database(
+    async fn copy_database<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         total_files: u32,
         mut processed_files: u32,
     ) -> Result<u32> {
@@ -241,8 +503,13 @@ impl DataMigrationService {
 
         let mut copied: u32 = 0;
 
-        // Note: Database connections will be closed when the app restarts
-        // This is handled by the app lifecycle
+        // Flush the WAL and stop further writes, which would otherwise be
+        // left behind in the source folder. The app restarts afterwards.
+        if let Some(db) = app_handle.try_state::<Arc<DatabaseConnection>>() {
+            checkpoint_and_close(&db)
+                .await
+                .map_err(|e| AppError::migration_error("copy_database", e.to_string()))?;
+        }
 
         if source_dir.exists() {
             for entry in fs::read_dir(&source_dir).map_err(|e| {
@@ -251,15 +518,18 @@ impl DataMigrationService {
                 let entry = entry.map_err(|e| {
                     AppError::migration_error("copy_database", format!("Failed to read entry: {}", e))
                 })?;
+                if cancel.is_cancelled() {
+                    return Err(cancelled());
+                }
 
                 let file_name = entry.file_name();
                 let file_name_str = file_name.to_string_lossy().to_string();
                 let dest_path = dest_dir.join(&file_name);
 
-                // For SQLite database files, ensure proper handling
-                if file_name_str.ends_with(".sqlite") || file_name_str.ends_with(".db") {
+                // SQLite database files, with their WAL and shared memory files
+                if is_database_file(&file_name_str) {
                     // Copy with progress update
-                    fs::copy(entry.path(), &dest_path).map_err(|e| {
+                    copy_file(&entry.path(), &dest_path).map_err(|e| {
                         AppError::migration_error(
                             "copy_database",
                             format!("Failed to copy database file {}: {}", file_name_str, e),
@@ -279,13 +549,23 @@ impl DataMigrationService {
                     )?;
                 } else {
                     // Copy other files in data directory
-                    if entry.path().is_file() {
-                        fs::copy(entry.path(), &dest_path).map_err(|e| {
-                            AppError::migration_error(
-                                "copy_database",
-                                format!("Failed to copy file {}: {}", file_name_str, e),
-                            )
-                        })?;
+                    let metadata = entry.metadata().map_err(|e| {
+                        AppError::migration_error(
+                            "copy_database",
+                            format!("Failed to read entry: {}", e),
+                        )
+                    })?;
+                    // A complete copy from an earlier run is kept but still
+                    // counts as processed
+                    if metadata.is_file() {
+                        if !already_copied(&metadata, &dest_path) {
+                            copy_file(&entry.path(), &dest_path).map_err(|e| {
+                                AppError::migration_error(
+                                    "copy_database",
+                                    format!("Failed to copy file {}: {}", file_name_str, e),
+                                )
+                            })?;
+                        }
                         copied += 1;
                         processed_files += 1;
                     }
@@ -298,9 +578,10 @@ impl DataMigrationService {
     }
 
     /// Copy config files
-    fn copy_config(
+    fn copy_config<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         total_files: u32,
         processed_files: u32,
     ) -> Result<u32> {
@@ -315,6 +596,7 @@ impl DataMigrationService {
             &source_dir,
             &dest_dir,
             app_handle,
+            cancel,
             MigrationPhase::CopyingConfig,
             total_files,
             processed_files,
@@ -325,9 +607,10 @@ impl DataMigrationService {
     }
 
     /// Copy files (PDF attachments)
-    fn copy_files(
+    fn copy_files<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         total_files: u32,
         processed_files: u32,
     ) -> Result<u32> {
@@ -342,6 +625,7 @@ impl DataMigrationService {
             &source_dir,
             &dest_dir,
             app_handle,
+            cancel,
             MigrationPhase::CopyingFiles,
             total_files,
             processed_files,
@@ -351,10 +635,43 @@ impl DataMigrationService {
         Ok(copied)
     }
 
+    /// Copy database backups kept in the default backup directory
+    fn copy_backups<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
+        total_files: u32,
+        processed_files: u32,
+    ) -> Result<u32> {
+        let source_dir = Self::get_xuanbrain_dir(&self.source_base).join("backups");
+        let dest_dir = Self::get_xuanbrain_dir(&self.dest_base).join("backups");
+
+        fs::create_dir_all(&dest_dir).map_err(|e| {
+            AppError::migration_error(
+                "copy_backups",
+                format!("Failed to create backups directory: {}", e),
+            )
+        })?;
+
+        let copied = copy_directory_with_progress(
+            &source_dir,
+            &dest_dir,
+            app_handle,
+            cancel,
+            MigrationPhase::CopyingBackups,
+            total_files,
+            processed_files,
+        )?;
+
+        info!("Copied {} backup files", copied);
+        Ok(copied)
+    }
+
     /// Copy cache files
-    fn copy_cache(
+    fn copy_cache<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         total_files: u32,
         processed_files: u32,
     ) -> Result<u32> {
@@ -369,6 +686,7 @@ impl DataMigrationService {
             &source_dir,
             &dest_dir,
             app_handle,
+            cancel,
             MigrationPhase::CopyingCache,
             total_files,
             processed_files,
@@ -379,9 +697,10 @@ impl DataMigrationService {
     }
 
     /// Copy log files
-    fn copy_logs(
+    fn copy_logs<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         total_files: u32,
         processed_files: u32,
     ) -> Result<u32> {
@@ -396,6 +715,7 @@ impl DataMigrationService {
             &source_dir,
             &dest_dir,
             app_handle,
+            cancel,
             MigrationPhase::CopyingLogs,
             total_files,
             processed_files,
@@ -405,13 +725,25 @@ impl DataMigrationService {
         Ok(copied)
     }
 
-    /// Verify migration completed successfully
-    fn verify(&self) -> Result<()> {
+    /// Verify the copy. Every directory has to exist, and every copied file
+    /// has to have the size of its source. Database files and the
+    /// [`ChecksumSample`] of the other files also have to match their
+    /// source's SHA-256. Mismatched files are copied once more; files that
+    /// still differ are listed in the error, as are directories whose file
+    /// count or total size still differs from their source. Logs are only
+    /// checked for their directory, as the app keeps writing them while
+    /// migrating.
+    /// Finally the copied database has to pass an integrity check.
+    async fn verify<R: Runtime>(
+        &self,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let source_dir = Self::get_xuanbrain_dir(&self.source_base);
         let dest_dir = Self::get_xuanbrain_dir(&self.dest_base);
 
         // Verify destination directories exist
-        let subdirs = ["data", "files", "cache", "config", "logs"];
-        for subdir in subdirs {
+        for subdir in MIGRATED_DIRS {
             let dest_subdir = dest_dir.join(subdir);
             if !dest_subdir.exists() {
                 return Err(AppError::migration_error(
@@ -421,10 +753,145 @@ impl DataMigrationService {
             }
         }
 
+        // Compare sizes, and collect what gets checksummed
+        let mut mismatched: Vec<PathBuf> = Vec::new();
+        let mut database_files: Vec<PathBuf> = Vec::new();
+        let mut other_files: Vec<PathBuf> = Vec::new();
+        let mut uneven_dirs: Vec<&str> = Vec::new();
+        for subdir in MIGRATED_DIRS.iter().filter(|d| **d != "logs") {
+            let source_files = list_files(&source_dir.join(subdir))?;
+            let dest_files: HashMap<PathBuf, u64> =
+                list_files(&dest_dir.join(subdir))?.into_iter().collect();
+
+            let source_bytes = total_size(&source_files);
+            let dest_bytes: u64 = dest_files.values().sum();
+            if source_files.len() != dest_files.len() || source_bytes != dest_bytes {
+                warn!(
+                    "{}: {} files ({} bytes) in the source, {} files ({} bytes) copied",
+                    subdir,
+                    source_files.len(),
+                    source_bytes,
+                    dest_files.len(),
+                    dest_bytes
+                );
+                uneven_dirs.push(*subdir);
+            }
+
+            for (file, size) in source_files {
+                let relative = Path::new(subdir).join(&file);
+                if dest_files.get(&file) != Some(&size) {
+                    mismatched.push(relative);
+                } else if is_database_file(&file.to_string_lossy()) {
+                    database_files.push(relative);
+                } else {
+                    other_files.push(relative);
+                }
+            }
+        }
+
+        let to_checksum: Vec<PathBuf> = database_files
+            .into_iter()
+            .chain(sample(other_files, self.checksum_sample))
+            .collect();
+        let total = to_checksum.len() as u32;
+        for (checked, relative) in to_checksum.into_iter().enumerate() {
+            if cancel.is_cancelled() {
+                return Err(cancelled());
+            }
+            self.emit_status(
+                app_handle,
+                MigrationPhase::Verifying,
+                checked as u32,
+                total,
+                Some(relative.to_string_lossy().to_string()),
+                None,
+            )?;
+            if !checksum_in_background(source_dir.join(&relative), dest_dir.join(&relative)).await?
+            {
+                mismatched.push(relative);
+            }
+        }
+
+        // Copy mismatched files once more, then give up on them
+        let mut failed = Vec::new();
+        for relative in mismatched {
+            let (source, dest) = (source_dir.join(&relative), dest_dir.join(&relative));
+            warn!(
+                "Copy of {:?} does not match its source, copying again",
+                relative
+            );
+            let recopied = match dest
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| copy_file(&source, &dest))
+            {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Failed to copy {:?} again: {}", relative, e);
+                    false
+                }
+            };
+            if !recopied || !checksum_in_background(source, dest).await? {
+                failed.push(relative.to_string_lossy().to_string());
+            }
+        }
+
+        if !failed.is_empty() {
+            let mut listed = failed[..failed.len().min(MISMATCH_REPORT_LIMIT)].join(", ");
+            if failed.len() > MISMATCH_REPORT_LIMIT {
+                listed.push_str(&format!(
+                    " and {} more",
+                    failed.len() - MISMATCH_REPORT_LIMIT
+                ));
+            }
+            return Err(AppError::migration_error(
+                "verify",
+                format!(
+                    "{} copied files do not match their source: {}",
+                    failed.len(),
+                    listed
+                ),
+            ));
+        }
+
+        // Recopying fills in missing files; a directory that still differs
+        // has files the source does not
+        let mut still_uneven = Vec::new();
+        for subdir in uneven_dirs {
+            let (source_files, dest_files) = (
+                list_files(&source_dir.join(subdir))?,
+                list_files(&dest_dir.join(subdir))?,
+            );
+            let (source_bytes, dest_bytes) = (total_size(&source_files), total_size(&dest_files));
+            if source_files.len() != dest_files.len() || source_bytes != dest_bytes {
+                still_uneven.push(format!(
+                    "{} ({} files, {} bytes in the source; {} files, {} bytes copied)",
+                    subdir,
+                    source_files.len(),
+                    source_bytes,
+                    dest_files.len(),
+                    dest_bytes
+                ));
+            }
+        }
+        if !still_uneven.is_empty() {
+            return Err(AppError::migration_error(
+                "verify",
+                format!(
+                    "Copied directories do not match their source: {}",
+                    still_uneven.join(", ")
+                ),
+            ));
+        }
+
         // Verify database file exists
-        let db_path = dest_dir.join("data").join("xuan_brain.sqlite");
+        let db_path = dest_dir.join("data").join(DATABASE_FILE);
         if !db_path.exists() {
             warn!("Database file not found at {:?}, may be a new installation", db_path);
+        } else {
+            check_integrity(&db_path)
+                .await
+                .map_err(|e| AppError::migration_error("verify", e.to_string()))?;
         }
 
         info!("Migration verification completed successfully");
@@ -432,9 +899,9 @@ impl DataMigrationService {
     }
 
     /// Emit migration status to frontend
-    fn emit_status(
+    fn emit_status<R: Runtime>(
         &self,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
         phase: MigrationPhase,
         processed_files: u32,
         total_files: u32,
@@ -459,7 +926,7 @@ impl DataMigrationService {
     }
 
     /// Rollback migration in case of failure
-    pub fn rollback(&self, app_handle: &AppHandle) -> Result<()> {
+    pub fn rollback<R: Runtime>(&self, app_handle: &AppHandle<R>) -> Result<()> {
         info!("Starting migration rollback...");
 
         self.emit_status(
@@ -471,7 +938,7 @@ impl DataMigrationService {
             None,
         )?;
 
-        let dest_dir = self.dest_base.join(APP_FOLDER);
+        let dest_dir = Self::get_xuanbrain_dir(&self.dest_base);
 
         // Remove partially copied destination directory
         if dest_dir.exists() {
@@ -502,6 +969,162 @@ impl DataMigrationService {
     }
 }
 
+/// Error returned when a migration stops because it was cancelled
+fn cancelled() -> AppError {
+    AppError::migration_error("cancel", "Migration cancelled")
+}
+
+/// SQLite database files, with their WAL and shared memory files. These are
+/// always copied, never skipped as already copied.
+fn is_database_file(file_name: &str) -> bool {
+    [".sqlite", ".db", "-wal", "-shm"]
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+}
+
+/// Modification times closer than this are equal; FAT and exFAT drives
+/// store them in two-second steps
+const MTIME_TOLERANCE: Duration = Duration::from_secs(2);
+
+/// Whether `dest` is a complete copy of the file described by `source`
+/// from an earlier run: same size and modification time. A copy only gets
+/// the modification time of its source once it is complete, so a partly
+/// written file never matches.
+fn already_copied(source: &fs::Metadata, dest: &Path) -> bool {
+    let Ok(dest) = fs::metadata(dest) else {
+        return false;
+    };
+    let (Ok(source_time), Ok(dest_time)) = (source.modified(), dest.modified()) else {
+        return false;
+    };
+    let difference = source_time
+        .duration_since(dest_time)
+        .or_else(|_| dest_time.duration_since(source_time))
+        .unwrap_or(Duration::MAX);
+    dest.is_file() && dest.len() == source.len() && difference <= MTIME_TOLERANCE
+}
+
+/// Copy a file and give the copy the modification time of the source
+fn copy_file(source: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::copy(source, dest)?;
+    let modified = fs::metadata(source)?.modified()?;
+    fs::File::options()
+        .write(true)
+        .open(dest)?
+        .set_modified(modified)
+}
+
+/// Every file under `root` as its path relative to `root` and its size,
+/// sorted by path
+fn list_files(root: &Path) -> Result<Vec<(PathBuf, u64)>> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<(PathBuf, u64)>) -> Result<()> {
+        for entry in fs::read_dir(dir).map_err(|e| {
+            AppError::file_system(
+                dir.display().to_string(),
+                format!("Failed to read directory: {}", e),
+            )
+        })? {
+            let entry = entry.map_err(|e| {
+                AppError::file_system(
+                    dir.display().to_string(),
+                    format!("Failed to read entry: {}", e),
+                )
+            })?;
+            let metadata = entry.metadata().map_err(|e| {
+                AppError::file_system(entry.path().display().to_string(), e.to_string())
+            })?;
+            let path = entry.path();
+            if metadata.is_dir() {
+                walk(root, &path, files)?;
+            } else {
+                let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+                files.push((relative, metadata.len()));
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    if root.exists() {
+        walk(root, root, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The files of `files` picked by `sample`, spread evenly over the list
+fn sample(files: Vec<PathBuf>, sample: ChecksumSample) -> Vec<PathBuf> {
+    match sample {
+        ChecksumSample::Count(count) if count < files.len() => {
+            let step = files.len() as f64 / count as f64;
+            (0..count)
+                .map(|i| files[(i as f64 * step) as usize].clone())
+                .collect()
+        }
+        _ => files,
+    }
+}
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Whether `dest` has the same SHA-256 as `source`. A missing or
+/// unreadable copy does not match.
+fn same_checksum(source: &Path, dest: &Path) -> Result<bool> {
+    let source_hash = sha256_file(source)
+        .map_err(|e| AppError::file_system(source.display().to_string(), e.to_string()))?;
+    Ok(sha256_file(dest).is_ok_and(|dest_hash| dest_hash == source_hash))
+}
+
+/// Total size of files listed by [`list_files`]
+fn total_size(files: &[(PathBuf, u64)]) -> u64 {
+    files.iter().map(|(_, size)| size).sum()
+}
+
+/// [`same_checksum`] on the blocking thread pool
+async fn checksum_in_background(source: PathBuf, dest: PathBuf) -> Result<bool> {
+    tokio::task::spawn_blocking(move || same_checksum(&source, &dest))
+        .await
+        .map_err(|e| AppError::generic(format!("Checksum task failed: {}", e)))?
+}
+
+/// Size of the files under `source` that have no complete copy under `dest`
+fn pending_bytes(source: &Path, dest: &Path) -> Result<u64> {
+    if !source.exists() {
+        return Ok(0);
+    }
+
+    let mut bytes = 0;
+    for entry in fs::read_dir(source).map_err(|e| {
+        AppError::file_system(
+            source.display().to_string(),
+            format!("Failed to read directory: {}", e),
+        )
+    })? {
+        let entry = entry.map_err(|e| {
+            AppError::file_system(
+                source.display().to_string(),
+                format!("Failed to read entry: {}", e),
+            )
+        })?;
+        let metadata = entry.metadata().map_err(|e| {
+            AppError::file_system(entry.path().display().to_string(), e.to_string())
+        })?;
+        let dest_path = dest.join(entry.file_name());
+        if metadata.is_dir() {
+            bytes += pending_bytes(&entry.path(), &dest_path)?;
+        } else if is_database_file(&entry.file_name().to_string_lossy())
+            || !already_copied(&metadata, &dest_path)
+        {
+            bytes += metadata.len();
+        }
+    }
+    Ok(bytes)
+}
+
 /// Count files in a directory recursively
 fn count_files_in_dir(path: &PathBuf) -> Result<u32> {
     let mut count: u32 = 0;
@@ -528,10 +1151,11 @@ fn count_files_in_dir(path: &PathBuf) -> Result<u32> {
 }
 
 /// Copy a directory recursively with progress updates
-fn copy_directory_with_progress(
+fn copy_directory_with_progress<R: Runtime>(
     source: &PathBuf,
     dest: &PathBuf,
-    app_handle: &AppHandle,
+    app_handle: &AppHandle<R>,
+    cancel: &CancellationToken,
     phase: MigrationPhase,
     total_files: u32,
     mut processed_files: u32,
@@ -542,10 +1166,12 @@ fn copy_directory_with_progress(
 
     let mut copied: u32 = 0;
 
-    fn copy_dir_recursive(
+    #[allow(clippy::too_many_arguments)]
+    fn copy_dir_recursive<R: Runtime>(
         src: &PathBuf,
         dst: &PathBuf,
-        app_handle: &AppHandle,
+        app_handle: &AppHandle<R>,
+        cancel: &CancellationToken,
         phase: &MigrationPhase,
         total_files: u32,
         processed_files: &mut u32,
@@ -561,6 +1187,9 @@ fn copy_directory_with_progress(
             let entry = entry.map_err(|e| {
                 AppError::migration_error("copy_dir", format!("Failed to read entry: {}", e))
             })?;
+            if cancel.is_cancelled() {
+                return Err(cancelled());
+            }
             let entry_path = entry.path();
             let file_name = entry.file_name();
             let dest_path = dst.join(&file_name);
@@ -570,18 +1199,21 @@ fn copy_directory_with_progress(
                     &entry_path,
                     &dest_path,
                     app_handle,
+                    cancel,
                     phase,
                     total_files,
                     processed_files,
                     copied,
                 )?;
             } else {
-                fs::copy(&entry_path, &dest_path).map_err(|e| {
-                    AppError::migration_error(
-                        "copy_dir",
-                        format!("Failed to copy file: {}", e),
-                    )
+                let metadata = entry.metadata().map_err(|e| {
+                    AppError::migration_error("copy_dir", format!("Failed to read entry: {}", e))
                 })?;
+                if !already_copied(&metadata, &dest_path) {
+                    copy_file(&entry_path, &dest_path).map_err(|e| {
+                        AppError::migration_error("copy_dir", format!("Failed to copy file: {}", e))
+                    })?;
+                }
 
                 *copied += 1;
                 *processed_files += 1;
@@ -607,6 +1239,7 @@ fn copy_directory_with_progress(
         source,
         dest,
         app_handle,
+        cancel,
         &phase,
         total_files,
         &mut processed_files,
@@ -615,3 +1248,101 @@ fn copy_directory_with_progress(
 
     Ok(copied)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_complete_copies_are_not_counted_again() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let paper = source.path().join("paper.pdf");
+        fs::write(&paper, b"%PDF-1.7 content").unwrap();
+        fs::write(source.path().join(DATABASE_FILE), b"database").unwrap();
+
+        assert_eq!(pending_bytes(source.path(), dest.path()).unwrap(), 24);
+
+        // A partly written copy is not complete
+        fs::write(dest.path().join("paper.pdf"), b"%PDF").unwrap();
+        assert_eq!(pending_bytes(source.path(), dest.path()).unwrap(), 24);
+
+        // The database is always copied again
+        copy_file(&paper, &dest.path().join("paper.pdf")).unwrap();
+        copy_file(
+            &source.path().join(DATABASE_FILE),
+            &dest.path().join(DATABASE_FILE),
+        )
+        .unwrap();
+        assert_eq!(pending_bytes(source.path(), dest.path()).unwrap(), 8);
+
+        // Same size, but changed since it was copied
+        fs::write(&paper, b"%PDF-1.7 changed").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&paper)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(pending_bytes(source.path(), dest.path()).unwrap(), 24);
+    }
+
+    #[test]
+    fn test_truncated_copy_fails_checksum() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        fs::create_dir_all(source.path().join("ab")).unwrap();
+        fs::create_dir_all(dest.path().join("ab")).unwrap();
+        let paper = Path::new("ab").join("paper.pdf");
+        fs::write(source.path().join(&paper), b"%PDF-1.7 content").unwrap();
+        fs::write(dest.path().join(&paper), b"%PDF-1.7 conten").unwrap();
+
+        assert_eq!(
+            list_files(source.path()).unwrap(),
+            vec![(paper.clone(), 16)]
+        );
+        assert!(!same_checksum(&source.path().join(&paper), &dest.path().join(&paper)).unwrap());
+        assert!(!same_checksum(&source.path().join(&paper), &dest.path().join("missing")).unwrap());
+
+        copy_file(&source.path().join(&paper), &dest.path().join(&paper)).unwrap();
+        assert!(same_checksum(&source.path().join(&paper), &dest.path().join(&paper)).unwrap());
+    }
+
+    #[test]
+    fn test_checksum_sample_is_spread_over_files() {
+        let files: Vec<PathBuf> = (0..10).map(|i| PathBuf::from(i.to_string())).collect();
+        let picked = sample(files.clone(), ChecksumSample::Count(3));
+        assert_eq!(
+            picked,
+            vec![files[0].clone(), files[3].clone(), files[6].clone()]
+        );
+        assert_eq!(sample(files.clone(), ChecksumSample::Count(20)).len(), 10);
+        assert_eq!(sample(files, ChecksumSample::All).len(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_migration_keeps_backups() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let source_dir = source.path().join(APP_FOLDER);
+        for subdir in MIGRATED_DIRS {
+            fs::create_dir_all(source_dir.join(subdir)).unwrap();
+        }
+        fs::write(source_dir.join("data").join(DATABASE_FILE), b"database").unwrap();
+        let backup = "xuan-brain-backup-20260101-000000.sqlite";
+        fs::write(source_dir.join("backups").join(backup), b"backup").unwrap();
+
+        let app = tauri::test::mock_app();
+        DataMigrationService::new(source.path().to_path_buf(), dest.path().to_path_buf())
+            .copy_and_verify(app.handle(), &CancellationToken::new())
+            .await
+            .unwrap();
+
+        // The source folder is deleted on the next start
+        fs::remove_dir_all(&source_dir).unwrap();
+        let dest_dirs = crate::testing::test_app_dirs(&dest.path().join(APP_FOLDER));
+        let backups = crate::service::backup_service::default_backup_dir(&dest_dirs);
+        assert_eq!(fs::read(backups.join(backup)).unwrap(), b"backup");
+    }
+}
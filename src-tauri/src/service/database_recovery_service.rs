@@ -0,0 +1,120 @@
+//! Starting without a usable database
+//!
+//! When the database cannot be opened, setup records the failure in
+//! [`DatabaseStatusState`] instead of aborting, so the window and tray still
+//! come up and `get_database_status` can show what went wrong. Commands
+//! that need the database are unavailable until it is repaired.
+//!
+//! A repair moves the database file and its WAL/SHM files aside, optionally
+//! stages the newest backup for restore, and restarts the app, which then
+//! opens the restored backup or creates an empty library.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::info;
+
+use crate::service::backup_service::DATABASE_FILE;
+use crate::sys::error::{AppError, Result};
+
+/// Whether the database opened at startup, and why not if it did not
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DatabaseStatus {
+    pub available: bool,
+    pub database_path: String,
+    pub error: Option<String>,
+}
+
+/// Startup result of the database connection, read by `get_database_status`
+#[derive(Clone, Default)]
+pub struct DatabaseStatusState {
+    status: Arc<Mutex<DatabaseStatus>>,
+}
+
+impl DatabaseStatusState {
+    pub fn available(database_path: &Path) -> Self {
+        Self::with_status(DatabaseStatus {
+            available: true,
+            database_path: database_path.to_string_lossy().to_string(),
+            error: None,
+        })
+    }
+
+    pub fn unavailable(database_path: &Path, error: &AppError) -> Self {
+        Self::with_status(DatabaseStatus {
+            available: false,
+            database_path: database_path.to_string_lossy().to_string(),
+            error: Some(error.to_string()),
+        })
+    }
+
+    fn with_status(status: DatabaseStatus) -> Self {
+        Self {
+            status: Arc::new(Mutex::new(status)),
+        }
+    }
+
+    pub fn get(&self) -> DatabaseStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+/// Rename the database and its WAL/SHM files in `data_dir` to
+/// `xuan-brain.sqlite.corrupt-{timestamp}[-wal|-shm]`. Returns the new path
+/// of the database file.
+pub fn move_database_aside(data_dir: &Path, now: DateTime<Utc>) -> Result<PathBuf> {
+    let db_path = data_dir.join(DATABASE_FILE);
+    if !db_path.exists() {
+        return Err(AppError::not_found(
+            "Database file",
+            db_path.to_string_lossy().to_string(),
+        ));
+    }
+
+    let aside_name = format!("{}.corrupt-{}", DATABASE_FILE, now.format("%Y%m%d-%H%M%S"));
+    for suffix in ["", "-wal", "-shm"] {
+        let from = data_dir.join(format!("{}{}", DATABASE_FILE, suffix));
+        if !from.exists() {
+            continue;
+        }
+        let to = data_dir.join(format!("{}{}", aside_name, suffix));
+        std::fs::rename(&from, &to).map_err(|e| {
+            AppError::file_system(from.to_string_lossy().to_string(), e.to_string())
+        })?;
+    }
+
+    let aside = data_dir.join(aside_name);
+    info!("Moved unusable database aside to {:?}", aside);
+    Ok(aside)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::connection::init_sqlite_connection_with_retry;
+
+    #[tokio::test]
+    async fn test_corrupt_database_is_moved_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(DATABASE_FILE), b"not a database at all").unwrap();
+        std::fs::write(dir.path().join(format!("{}-wal", DATABASE_FILE)), b"").unwrap();
+
+        let result = init_sqlite_connection_with_retry(dir.path().to_path_buf()).await;
+        assert!(result.is_err());
+
+        let aside = move_database_aside(dir.path(), Utc::now()).unwrap();
+        assert!(aside.exists());
+        assert!(!dir.path().join(DATABASE_FILE).exists());
+        assert!(!dir.path().join(format!("{}-wal", DATABASE_FILE)).exists());
+
+        // The next start creates an empty library
+        init_sqlite_connection_with_retry(dir.path().to_path_buf())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,342 @@
+//! Resumable DOI list imports
+//!
+//! A DOI list file holds one DOI per line; blank lines and lines starting
+//! with `#` are ignored. Before any network request the whole file is
+//! recorded as an import batch, and each line is marked as it is processed.
+//! Re-running the import on an unchanged file resumes the unfinished batch
+//! from its first pending line.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::database::entities::{import_batch, import_batch_item};
+use crate::database::DatabaseConnection;
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::repository::import_history_repository::{
+    ITEM_FAILED, ITEM_IMPORTED, ITEM_PENDING, ITEM_SKIPPED,
+};
+use crate::repository::ImportHistoryRepository;
+use crate::sys::error::{AppError, Result};
+
+/// Source tag stored on import batches created from DOI list files
+pub const DOI_FILE_SOURCE: &str = "doi_file";
+
+/// Result of importing a single line
+pub enum ItemOutcome {
+    Imported { paper_id: i64 },
+    AlreadyExists,
+}
+
+/// Summary of a (possibly resumed) DOI file import
+#[derive(Debug, Clone)]
+pub struct DoiFileImportSummary {
+    pub batch_id: i64,
+    pub total: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub pending: usize,
+    /// Line number the run started from when an existing batch was resumed
+    pub resumed_from_line: Option<usize>,
+    pub cancelled: bool,
+    /// Failed DOIs, written next to the input file for easy retry
+    pub errors_file: Option<PathBuf>,
+}
+
+/// Extract `(line_number, doi)` pairs, skipping blank and comment lines
+pub fn parse_doi_lines(content: &str) -> Vec<(usize, String)> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                None
+            } else {
+                Some((index + 1, line.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// Find the unfinished batch for this file or record a new one.
+/// Returns the batch and whether it was resumed.
+pub async fn prepare_batch(
+    db: &DatabaseConnection,
+    path: &Path,
+    category_id: Option<i64>,
+) -> Result<(import_batch::Model, bool)> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        AppError::file_system(
+            path.to_string_lossy().to_string(),
+            format!("Failed to read DOI list: {}", e),
+        )
+    })?;
+
+    let source_path = path
+        .canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string();
+
+    let mut hasher = Sha1::new();
+    hasher.update(content.as_bytes());
+    let source_hash = format!("{:x}", hasher.finalize());
+
+    if let Some(batch) = ImportHistoryRepository::find_resumable_batch(
+        db,
+        DOI_FILE_SOURCE,
+        &source_path,
+        &source_hash,
+    )
+    .await?
+    {
+        info!("Resuming import batch {} for {}", batch.id, source_path);
+        return Ok((batch, true));
+    }
+
+    let items = parse_doi_lines(&content);
+    if items.is_empty() {
+        return Err(AppError::validation(
+            "path",
+            "The file does not contain any DOIs",
+        ));
+    }
+
+    let batch = ImportHistoryRepository::create_batch(
+        db,
+        DOI_FILE_SOURCE,
+        &source_path,
+        &source_hash,
+        category_id,
+        &items,
+    )
+    .await?;
+
+    Ok((batch, false))
+}
+
+/// Process the pending items of a batch sequentially.
+///
+/// `import_one` performs the actual import for one item. The loop stops
+/// before the next item once `cancel` is triggered, leaving the remaining
+/// items pending so a later run resumes exactly there.
+pub async fn run_batch<F, Fut>(
+    db: &DatabaseConnection,
+    batch: &import_batch::Model,
+    input_path: &Path,
+    rate_limiter: &RateLimiter,
+    cancel: &CancellationToken,
+    mut import_one: F,
+) -> Result<DoiFileImportSummary>
+where
+    F: FnMut(import_batch_item::Model) -> Fut,
+    Fut: Future<Output = Result<ItemOutcome>>,
+{
+    let pending = ImportHistoryRepository::get_pending_items(db, batch.id).await?;
+    let resumed_from_line = if pending.len() < batch.total as usize {
+        pending.first().map(|item| item.line_number as usize)
+    } else {
+        None
+    };
+
+    let mut cancelled = false;
+    for item in pending {
+        if cancel.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+
+        rate_limiter.acquire().await;
+
+        let item_id = item.id;
+        let identifier = item.identifier.clone();
+        match import_one(item).await {
+            Ok(ItemOutcome::Imported { paper_id }) => {
+                ImportHistoryRepository::mark_item(
+                    db,
+                    item_id,
+                    ITEM_IMPORTED,
+                    Some(paper_id),
+                    None,
+                )
+                .await?;
+            }
+            Ok(ItemOutcome::AlreadyExists) => {
+                ImportHistoryRepository::mark_item(db, item_id, ITEM_SKIPPED, None, None).await?;
+            }
+            Err(e) => {
+                warn!("Failed to import DOI {}: {}", identifier, e);
+                ImportHistoryRepository::mark_item(
+                    db,
+                    item_id,
+                    ITEM_FAILED,
+                    None,
+                    Some(e.to_string()),
+                )
+                .await?;
+            }
+        }
+    }
+
+    let items = ImportHistoryRepository::get_items(db, batch.id).await?;
+    let count = |status: &str| items.iter().filter(|i| i.status == status).count();
+
+    let mut summary = DoiFileImportSummary {
+        batch_id: batch.id,
+        total: items.len(),
+        imported: count(ITEM_IMPORTED),
+        skipped: count(ITEM_SKIPPED),
+        failed: count(ITEM_FAILED),
+        pending: count(ITEM_PENDING),
+        resumed_from_line,
+        cancelled,
+        errors_file: None,
+    };
+
+    if !cancelled && summary.pending == 0 {
+        ImportHistoryRepository::complete_batch(db, batch.id).await?;
+        summary.errors_file = write_errors_file(input_path, &items)?;
+    }
+
+    info!(
+        "DOI batch {}: {} imported, {} skipped, {} failed, {} pending",
+        batch.id, summary.imported, summary.skipped, summary.failed, summary.pending
+    );
+
+    Ok(summary)
+}
+
+/// Write failed DOIs to `<input>.errors.txt`, with the error as a comment.
+/// The file can be fed straight back into the importer.
+fn write_errors_file(
+    input_path: &Path,
+    items: &[import_batch_item::Model],
+) -> Result<Option<PathBuf>> {
+    let failed: Vec<_> = items.iter().filter(|i| i.status == ITEM_FAILED).collect();
+    if failed.is_empty() {
+        return Ok(None);
+    }
+
+    let stem = input_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "dois".to_string());
+    let errors_path = input_path.with_file_name(format!("{}.errors.txt", stem));
+
+    let mut content =
+        String::from("# DOIs that failed to import; this file can be imported again\n");
+    for item in failed {
+        content.push_str(&format!(
+            "# line {}: {}\n{}\n",
+            item.line_number,
+            item.error.as_deref().unwrap_or("unknown error"),
+            item.identifier
+        ));
+    }
+
+    std::fs::write(&errors_path, content).map_err(|e| {
+        AppError::file_system(errors_path.to_string_lossy().to_string(), e.to_string())
+    })?;
+
+    Ok(Some(errors_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::database::connection::init_memory_connection;
+
+    #[test]
+    fn test_parse_doi_lines_skips_comments_and_blanks() {
+        let lines = parse_doi_lines("# my list\n10.1000/a\n\n  10.1000/b  \n# done\n");
+        assert_eq!(
+            lines,
+            vec![(2, "10.1000/a".to_string()), (4, "10.1000/b".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_cancellation() {
+        let db = init_memory_connection().await;
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("dois.txt");
+        std::fs::write(
+            &input,
+            "10.1/a\n10.1/b\n# skip me\n10.1/c\n10.1/d\n10.1/e\n",
+        )
+        .unwrap();
+
+        let limiter = RateLimiter::new(Duration::ZERO);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        // First run: cancel after the second DOI has been processed
+        let (batch, resumed) = prepare_batch(&db, &input, None).await.unwrap();
+        assert!(!resumed);
+        let cancel = CancellationToken::new();
+        let summary = run_batch(&db, &batch, &input, &limiter, &cancel, |item| {
+            let seen = seen.clone();
+            let cancel = cancel.clone();
+            async move {
+                let mut seen = seen.lock().unwrap();
+                seen.push(item.identifier.clone());
+                if seen.len() == 2 {
+                    cancel.cancel();
+                }
+                Ok(ItemOutcome::Imported { paper_id: 1 })
+            }
+        })
+        .await
+        .unwrap();
+
+        assert!(summary.cancelled);
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.pending, 3);
+        assert_eq!(*seen.lock().unwrap(), vec!["10.1/a", "10.1/b"]);
+
+        // Second run on the same file resumes from the first unprocessed line
+        seen.lock().unwrap().clear();
+        let (resumed_batch, resumed) = prepare_batch(&db, &input, None).await.unwrap();
+        assert!(resumed);
+        assert_eq!(resumed_batch.id, batch.id);
+
+        let cancel = CancellationToken::new();
+        let summary = run_batch(&db, &resumed_batch, &input, &limiter, &cancel, |item| {
+            let seen = seen.clone();
+            async move {
+                seen.lock().unwrap().push(item.identifier.clone());
+                if item.identifier == "10.1/d" {
+                    Err(AppError::not_found("DOI", item.identifier))
+                } else {
+                    Ok(ItemOutcome::AlreadyExists)
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(*seen.lock().unwrap(), vec!["10.1/c", "10.1/d", "10.1/e"]);
+        assert_eq!(summary.resumed_from_line, Some(4));
+        assert!(!summary.cancelled);
+        assert_eq!(
+            (summary.imported, summary.skipped, summary.failed),
+            (2, 2, 1)
+        );
+
+        let errors_file = summary.errors_file.expect("errors file written");
+        let retry = parse_doi_lines(&std::fs::read_to_string(errors_file).unwrap());
+        assert_eq!(retry.len(), 1);
+        assert_eq!(retry[0].1, "10.1/d");
+
+        // A completed batch is not resumed again
+        let (_, resumed) = prepare_batch(&db, &input, None).await.unwrap();
+        assert!(!resumed);
+    }
+}
@@ -0,0 +1,733 @@
+//! Resumable attachment downloads
+//!
+//! A download streams into `<target>.<url key>.part`, next to a `.part.json`
+//! sidecar recording the URL, the server's validators and whether it
+//! supports byte ranges. The part file is keyed by URL, so downloads of
+//! different URLs never share one. A later attempt, even after a restart,
+//! continues from the bytes already on disk with a `Range` request guarded
+//! by `If-Range`, so a changed file on the server restarts the download
+//! instead of corrupting it; without an ETag or Last-Modified to guard it,
+//! the download starts over. A resumed response must start at the requested
+//! byte and describe the same file, and the part file only replaces the
+//! target once the whole announced length has arrived. An existing target
+//! is never overwritten.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use reqwest::header::{
+    ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::sys::config::AppConfig;
+use crate::sys::dirs::AppDirs;
+use crate::sys::error::{AppError, Result};
+
+/// Suffix of in-progress download files
+pub const PART_SUFFIX: &str = ".part";
+
+/// Suffix of the bookkeeping sidecar, appended to the part file's name
+const PART_INFO_SUFFIX: &str = ".json";
+
+/// Hex characters of the URL hash in a part file's name
+const URL_KEY_LEN: usize = 16;
+
+/// Attempts per download before giving up; later attempts resume
+const MAX_ATTEMPTS: u32 = 3;
+
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// A stalled connection fails after this long without data, rather than
+/// bounding the whole transfer, so large files on slow links still finish
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// What is known about a partial download, persisted next to the part file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PartInfo {
+    url: String,
+    etag: Option<String>,
+    #[serde(default)]
+    last_modified: Option<String>,
+    accept_ranges: bool,
+    total_bytes: Option<u64>,
+}
+
+impl PartInfo {
+    /// Validator for `If-Range`. Weak ETags are not allowed there, so
+    /// Last-Modified is used instead.
+    fn if_range(&self) -> Option<&str> {
+        self.etag
+            .as_deref()
+            .filter(|etag| !etag.starts_with("W/"))
+            .or(self.last_modified.as_deref())
+    }
+}
+
+/// Progress of a download that is currently running
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub id: String,
+    pub url: String,
+    pub file_name: String,
+    pub bytes_received: u64,
+    pub total_bytes: Option<u64>,
+    pub started_at: DateTime<Utc>,
+}
+
+struct ActiveDownload {
+    progress: DownloadProgress,
+    part: PathBuf,
+    cancel: CancellationToken,
+}
+
+/// In-memory registry of running downloads.
+/// Shared between Tauri commands and the Axum server.
+#[derive(Clone, Default)]
+pub struct DownloadRegistry {
+    downloads: Arc<Mutex<HashMap<String, ActiveDownload>>>,
+}
+
+impl DownloadRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Running downloads, oldest first
+    pub fn list(&self) -> Vec<DownloadProgress> {
+        let downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        let mut list: Vec<DownloadProgress> =
+            downloads.values().map(|d| d.progress.clone()).collect();
+        list.sort_by_key(|p| p.started_at);
+        list
+    }
+
+    /// Cancel a running download. Returns `false` if no download has this id.
+    pub fn cancel(&self, id: &str) -> bool {
+        let downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        match downloads.get(id) {
+            Some(download) => {
+                download.cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Register a download into `part`, unless one is already running
+    fn start(
+        &self,
+        url: &str,
+        file_name: &str,
+        part: &Path,
+    ) -> Result<(String, CancellationToken)> {
+        let mut downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        if downloads.values().any(|d| d.part == part) {
+            return Err(AppError::validation(
+                "url",
+                format!("{} is already being downloaded", url),
+            ));
+        }
+
+        let mut bytes = [0u8; 8];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let cancel = CancellationToken::new();
+        downloads.insert(
+            id.clone(),
+            ActiveDownload {
+                progress: DownloadProgress {
+                    id: id.clone(),
+                    url: url.to_string(),
+                    file_name: file_name.to_string(),
+                    bytes_received: 0,
+                    total_bytes: None,
+                    started_at: Utc::now(),
+                },
+                part: part.to_path_buf(),
+                cancel: cancel.clone(),
+            },
+        );
+
+        Ok((id, cancel))
+    }
+
+    fn update(&self, id: &str, bytes_received: u64, total_bytes: Option<u64>) {
+        let mut downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(download) = downloads.get_mut(id) {
+            download.progress.bytes_received = bytes_received;
+            download.progress.total_bytes = total_bytes;
+        }
+    }
+
+    fn finish(&self, id: &str) {
+        let mut downloads = self.downloads.lock().unwrap_or_else(|e| e.into_inner());
+        downloads.remove(id);
+    }
+}
+
+fn with_suffix(target: &Path, suffix: &str) -> PathBuf {
+    let mut path = target.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Path the in-progress download of `url` to `target` is written to
+pub fn part_path(target: &Path, url: &str) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+    with_suffix(target, &format!(".{}{}", &hash[..URL_KEY_LEN], PART_SUFFIX))
+}
+
+fn part_info_path(part: &Path) -> PathBuf {
+    with_suffix(part, PART_INFO_SUFFIX)
+}
+
+fn read_part_info(part: &Path) -> Option<PartInfo> {
+    let content = std::fs::read_to_string(part_info_path(part)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_part_info(part: &Path, info: &PartInfo) -> Result<()> {
+    let path = part_info_path(part);
+    let content = serde_json::to_string(info)
+        .map_err(|e| AppError::generic(format!("Failed to serialize download state: {}", e)))?;
+    std::fs::write(&path, content)
+        .map_err(|e| AppError::file_system(path.to_string_lossy().to_string(), e.to_string()))
+}
+
+fn remove_partial(part: &Path) {
+    let _ = std::fs::remove_file(part);
+    let _ = std::fs::remove_file(part_info_path(part));
+}
+
+/// Byte offset a download of `url` can resume from, given the recorded
+/// state and the current size of the part file. Resuming needs a validator
+/// for `If-Range`.
+fn resume_offset(info: Option<&PartInfo>, url: &str, part_len: u64) -> u64 {
+    match info {
+        Some(info) if info.url == url && info.accept_ranges && info.if_range().is_some() => {
+            match info.total_bytes {
+                Some(total) if part_len > total => 0,
+                _ => part_len,
+            }
+        }
+        _ => 0,
+    }
+}
+
+/// Start and total length from a `Content-Range: bytes start-end/total`
+/// header; the total is `None` when the server sends `*`
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    let total = match total.trim() {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((start, total))
+}
+
+/// HTTP client suited to `download_resumable`
+pub fn download_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .read_timeout(READ_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::generic(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Download `url` to `target`, resuming an earlier partial download when
+/// possible. Returns the size of the finished file in bytes. Fails without
+/// downloading when `target` already exists.
+///
+/// The download is listed in `registry` while it runs and can be cancelled
+/// through it; a cancelled download leaves no partial files behind.
+pub async fn download_resumable(
+    client: &reqwest::Client,
+    registry: &DownloadRegistry,
+    url: &str,
+    target: &Path,
+) -> Result<u64> {
+    if target.exists() {
+        return Err(AppError::file_system(
+            target.to_string_lossy().to_string(),
+            "File already exists",
+        ));
+    }
+
+    let file_name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let part = part_path(target, url);
+    let (id, cancel) = registry.start(url, &file_name, &part)?;
+
+    let mut attempt = 1;
+    let result = loop {
+        let result = download_once(client, registry, &id, &cancel, url, &part, target).await;
+        let Err(e) = &result else {
+            break result;
+        };
+        if cancel.is_cancelled() || attempt == MAX_ATTEMPTS {
+            break result;
+        }
+
+        warn!(
+            "Download of {} failed (attempt {}/{}), retrying: {}",
+            url, attempt, MAX_ATTEMPTS, e
+        );
+        attempt += 1;
+        tokio::select! {
+            _ = cancel.cancelled() => break result,
+            _ = tokio::time::sleep(RETRY_DELAY) => {}
+        }
+    };
+
+    registry.finish(&id);
+
+    if cancel.is_cancelled() {
+        remove_partial(&part);
+        return Err(AppError::network_error(url, "Download cancelled"));
+    }
+    result
+}
+
+fn header_str(
+    headers: &reqwest::header::HeaderMap,
+    name: reqwest::header::HeaderName,
+) -> Option<&str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+async fn download_once(
+    client: &reqwest::Client,
+    registry: &DownloadRegistry,
+    id: &str,
+    cancel: &CancellationToken,
+    url: &str,
+    part: &Path,
+    target: &Path,
+) -> Result<u64> {
+    let recorded = read_part_info(part);
+    let part_len = std::fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let offset = resume_offset(recorded.as_ref(), url, part_len);
+
+    let mut request = client.get(url);
+    if let Some(validator) = recorded
+        .as_ref()
+        .and_then(PartInfo::if_range)
+        .filter(|_| offset > 0)
+    {
+        request = request
+            .header(RANGE, format!("bytes={}-", offset))
+            .header(IF_RANGE, validator);
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| AppError::network_error(url, format!("Failed to download: {}", e)))?;
+
+    let status = response.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE {
+        // The recorded state no longer matches the server; start over next time
+        remove_partial(part);
+    }
+    if !status.is_success() {
+        return Err(AppError::network_error(
+            url,
+            format!("Failed to download: HTTP {}", status),
+        ));
+    }
+
+    let headers = response.headers();
+    let etag = header_str(headers, ETAG).map(str::to_string);
+    let last_modified = header_str(headers, LAST_MODIFIED).map(str::to_string);
+    let content_length = header_str(headers, CONTENT_LENGTH).and_then(|v| v.parse::<u64>().ok());
+
+    // Anything but 206 is the full body, even if a range was asked for
+    let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+    let total_bytes = if resumed {
+        let range = header_str(headers, CONTENT_RANGE).and_then(parse_content_range);
+        let same_file = recorded
+            .as_ref()
+            .is_some_and(|info| etag.is_none() || info.etag.is_none() || etag == info.etag);
+        let Some((start, total)) = range.filter(|(start, _)| *start == offset && same_file) else {
+            // The range does not continue the part file; start over next time
+            remove_partial(part);
+            return Err(AppError::network_error(
+                url,
+                "Server answered the resume with a different range or file",
+            ));
+        };
+        total.or(content_length.map(|len| len + start))
+    } else {
+        content_length
+    };
+    let offset = if resumed { offset } else { 0 };
+
+    let info = PartInfo {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        accept_ranges: resumed
+            || header_str(headers, ACCEPT_RANGES).is_some_and(|v| v.eq_ignore_ascii_case("bytes")),
+        total_bytes,
+    };
+    write_part_info(part, &info)?;
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .await
+        .map_err(|e| AppError::file_system(part.to_string_lossy().to_string(), e.to_string()))?;
+
+    if resumed {
+        info!("Resuming download of {} at byte {}", url, offset);
+    }
+
+    let mut received = offset;
+    registry.update(id, received, info.total_bytes);
+    loop {
+        let chunk = tokio::select! {
+            _ = cancel.cancelled() => {
+                return Err(AppError::network_error(url, "Download cancelled"));
+            }
+            chunk = response.chunk() => chunk.map_err(|e| {
+                AppError::network_error(url, format!("Failed to read response: {}", e))
+            })?,
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+        file.write_all(&chunk).await.map_err(|e| {
+            AppError::file_system(part.to_string_lossy().to_string(), e.to_string())
+        })?;
+        received += chunk.len() as u64;
+        registry.update(id, received, info.total_bytes);
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::file_system(part.to_string_lossy().to_string(), e.to_string()))?;
+    drop(file);
+
+    if let Some(total) = info.total_bytes {
+        if received != total {
+            return Err(AppError::network_error(
+                url,
+                format!("Download incomplete: {} of {} bytes", received, total),
+            ));
+        }
+    }
+
+    if target.exists() {
+        return Err(AppError::file_system(
+            target.to_string_lossy().to_string(),
+            "File already exists",
+        ));
+    }
+    std::fs::rename(part, target)
+        .map_err(|e| AppError::file_system(target.to_string_lossy().to_string(), e.to_string()))?;
+    let _ = std::fs::remove_file(part_info_path(part));
+
+    Ok(received)
+}
+
+/// Remove part files, and their sidecars, that have not been written to for
+/// longer than `max_age`. Attachments live one directory below `files_dir`,
+/// so only that level is scanned. Returns the number of part files removed.
+pub fn cleanup_stale_parts(files_dir: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = std::fs::read_dir(files_dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for dir in entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let Ok(files) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in files.flatten().map(|e| e.path()) {
+            let is_part = path
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().ends_with(PART_SUFFIX));
+            if !is_part {
+                continue;
+            }
+
+            let age = std::fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok());
+            if age.is_some_and(|age| age >= max_age) {
+                remove_partial(&path);
+                removed += 1;
+            }
+        }
+    }
+
+    if removed > 0 {
+        info!("Removed {} stale partial downloads", removed);
+    }
+    removed
+}
+
+/// Spawn a one-off task removing partial downloads older than
+/// `paper.partial_download_max_age_hours`
+pub fn spawn_stale_part_cleanup(app_dirs: AppDirs) {
+    tauri::async_runtime::spawn_blocking(move || {
+        let max_age_hours = AppConfig::load(&app_dirs.config)
+            .unwrap_or_default()
+            .paper
+            .partial_download_max_age_hours;
+        cleanup_stale_parts(
+            Path::new(&app_dirs.files),
+            Duration::from_secs(max_age_hours * 60 * 60),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part_info(url: &str, accept_ranges: bool, total_bytes: Option<u64>) -> PartInfo {
+        PartInfo {
+            url: url.to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            accept_ranges,
+            total_bytes,
+        }
+    }
+
+    #[test]
+    fn test_resume_offset() {
+        let url = "https://arxiv.org/pdf/2401.00001";
+        let info = part_info(url, true, Some(100));
+
+        assert_eq!(resume_offset(Some(&info), url, 40), 40);
+        assert_eq!(resume_offset(None, url, 40), 0);
+        assert_eq!(resume_offset(Some(&info), "https://other", 40), 0);
+        assert_eq!(resume_offset(Some(&info), url, 120), 0);
+        assert_eq!(
+            resume_offset(Some(&part_info(url, false, Some(100))), url, 40),
+            0
+        );
+
+        // Nothing to send in If-Range
+        let weak = PartInfo {
+            etag: Some("W/\"abc\"".to_string()),
+            ..info.clone()
+        };
+        assert_eq!(resume_offset(Some(&weak), url, 40), 0);
+        let dated = PartInfo {
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_string()),
+            ..weak
+        };
+        assert_eq!(resume_offset(Some(&dated), url, 40), 40);
+    }
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 400-999/1000"),
+            Some((400, Some(1000)))
+        );
+        assert_eq!(parse_content_range("bytes 400-999/*"), Some((400, None)));
+        assert_eq!(parse_content_range("items 1-2/3"), None);
+    }
+
+    #[test]
+    fn test_part_file_is_keyed_by_url() {
+        let target = Path::new("/files/abc/paper.pdf");
+        let a = part_path(target, "https://example.org/a.pdf");
+        assert_ne!(a, part_path(target, "https://example.org/b.pdf"));
+        assert!(a.to_string_lossy().starts_with("/files/abc/paper.pdf."));
+        assert!(a.to_string_lossy().ends_with(PART_SUFFIX));
+    }
+
+    #[test]
+    fn test_registry_cancel() {
+        let registry = DownloadRegistry::new();
+        let part = Path::new("a.pdf.part");
+        let (id, cancel) = registry
+            .start("https://example.org/a.pdf", "a.pdf", part)
+            .unwrap();
+        assert!(registry
+            .start("https://example.org/a.pdf", "a.pdf", part)
+            .is_err());
+        registry.update(&id, 10, Some(20));
+
+        let listed = registry.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].bytes_received, 10);
+
+        assert!(registry.cancel(&id));
+        assert!(cancel.is_cancelled());
+        assert!(!registry.cancel("missing"));
+
+        registry.finish(&id);
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_stale_parts() {
+        let dir = tempfile::tempdir().unwrap();
+        let paper_dir = dir.path().join("abc123");
+        std::fs::create_dir(&paper_dir).unwrap();
+        let part = part_path(&paper_dir.join("paper.pdf"), "https://example.org/a.pdf");
+        std::fs::write(&part, b"partial").unwrap();
+        std::fs::write(part_info_path(&part), b"{}").unwrap();
+        std::fs::write(paper_dir.join("other.pdf"), b"done").unwrap();
+
+        assert_eq!(
+            cleanup_stale_parts(dir.path(), Duration::from_secs(3600)),
+            0
+        );
+        assert_eq!(cleanup_stale_parts(dir.path(), Duration::ZERO), 1);
+        assert!(!part.exists());
+        assert!(!part_info_path(&part).exists());
+        assert!(paper_dir.join("other.pdf").exists());
+    }
+
+    /// Serves `body` with ETag `etag`, honouring `Range` when `If-Range`
+    /// matches, and records the `Range` headers it receives
+    async fn serve_file(
+        body: &'static [u8],
+        etag: &'static str,
+    ) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        use axum::http::{HeaderMap, StatusCode as AxumStatus};
+        use axum::response::IntoResponse;
+
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = ranges.clone();
+        let app = axum::Router::new().route(
+            "/paper.pdf",
+            axum::routing::get(move |headers: HeaderMap| {
+                let seen = seen.clone();
+                async move {
+                    let range = headers
+                        .get("range")
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    seen.lock().unwrap().push(range.clone());
+                    let if_range_matches =
+                        headers.get("if-range").and_then(|v| v.to_str().ok()) == Some(etag);
+                    let start = range
+                        .as_deref()
+                        .and_then(|r| r.strip_prefix("bytes="))
+                        .and_then(|r| r.trim_end_matches('-').parse::<usize>().ok())
+                        .filter(|_| if_range_matches);
+                    match start {
+                        Some(start) => (
+                            AxumStatus::PARTIAL_CONTENT,
+                            [
+                                ("etag", etag.to_string()),
+                                (
+                                    "content-range",
+                                    format!("bytes {}-{}/{}", start, body.len() - 1, body.len()),
+                                ),
+                            ],
+                            &body[start..],
+                        )
+                            .into_response(),
+                        None => (
+                            AxumStatus::OK,
+                            [
+                                ("etag", etag.to_string()),
+                                ("accept-ranges", "bytes".to_string()),
+                            ],
+                            body,
+                        )
+                            .into_response(),
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .unwrap();
+        let url = format!("http://{}/paper.pdf", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, ranges)
+    }
+
+    fn write_partial(part: &Path, url: &str, body: &[u8], etag: &str) {
+        std::fs::write(part, body).unwrap();
+        write_part_info(
+            part,
+            &PartInfo {
+                url: url.to_string(),
+                etag: Some(etag.to_string()),
+                last_modified: None,
+                accept_ranges: true,
+                total_bytes: Some(1000),
+            },
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_from_part_file() {
+        static BODY: [u8; 1000] = {
+            let mut body = [0u8; 1000];
+            let mut i = 0;
+            while i < body.len() {
+                body[i] = (i % 251) as u8;
+                i += 1;
+            }
+            body
+        };
+        let (url, ranges) = serve_file(&BODY, "\"v1\"").await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("paper.pdf");
+        let part = part_path(&target, &url);
+        write_partial(&part, &url, &BODY[..400], "\"v1\"");
+
+        let client = download_client().unwrap();
+        let registry = DownloadRegistry::new();
+        let size = download_resumable(&client, &registry, &url, &target)
+            .await
+            .unwrap();
+
+        assert_eq!(size, 1000);
+        assert_eq!(std::fs::read(&target).unwrap(), BODY);
+        assert_eq!(*ranges.lock().unwrap(), [Some("bytes=400-".to_string())]);
+        assert!(!part.exists());
+        assert!(!part_info_path(&part).exists());
+
+        // The finished file is not overwritten
+        assert!(download_resumable(&client, &registry, &url, &target)
+            .await
+            .is_err());
+        assert_eq!(ranges.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_changed_file_restarts_download() {
+        let (url, ranges) = serve_file(b"new content", "\"v2\"").await;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("paper.pdf");
+        write_partial(&part_path(&target, &url), &url, b"old", "\"v1\"");
+
+        let client = download_client().unwrap();
+        download_resumable(&client, &DownloadRegistry::new(), &url, &target)
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&target).unwrap(), b"new content");
+        assert_eq!(*ranges.lock().unwrap(), [Some("bytes=3-".to_string())]);
+    }
+}
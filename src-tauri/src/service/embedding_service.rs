@@ -0,0 +1,313 @@
+//! Semantic search over paper embeddings
+//!
+//! Each paper's title and abstract are embedded with the model from the
+//! `embedding` config and stored in `paper_embedding`. Queries are embedded
+//! the same way and compared against every stored vector by cosine
+//! similarity, which stays fast for libraries of a few thousand papers.
+//! Hybrid search blends that similarity with the FTS5 BM25 score.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use tracing::{info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::llm::embedding::EmbeddingClient;
+use crate::models::Paper;
+use crate::repository::{EmbeddingRepository, NewEmbedding, PaperRepository, SearchRepository};
+use crate::sys::config::EmbeddingConfig;
+use crate::sys::error::{AppError, Result};
+
+/// Results returned when the caller does not ask for a number
+pub const DEFAULT_SEMANTIC_LIMIT: usize = 20;
+
+/// Most results a semantic search returns
+pub const MAX_SEMANTIC_LIMIT: usize = 200;
+
+/// Tries per batch before reindexing stops; papers embedded so far are kept
+const MAX_BATCH_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReindexProgress {
+    /// Papers embedded in this run
+    pub embedded: usize,
+    /// Papers whose stored embedding was already current
+    pub skipped: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct SemanticHit {
+    pub paper: Paper,
+    /// Blended score in `0..=1`, higher is better
+    pub score: f32,
+    /// Cosine similarity between the query and the paper, clamped to `0..=1`
+    pub vector_score: f32,
+    /// BM25 score scaled to `0..=1`; only set in hybrid mode
+    pub bm25_score: Option<f32>,
+}
+
+/// The text embedded for a paper
+pub fn embedding_text(paper: &Paper) -> String {
+    match paper.abstract_text.as_deref().map(str::trim) {
+        Some(abstract_text) if !abstract_text.is_empty() => {
+            format!("{}\n\n{}", paper.title.trim(), abstract_text)
+        }
+        _ => paper.title.trim().to_string(),
+    }
+}
+
+/// Hash identifying an embedding of `text` by `model`
+pub fn content_hash(model: &str, text: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0]);
+    hasher.update(text.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Cosine similarity of two vectors; 0 when their lengths differ or either is zero
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// Blend vector and BM25 scores, both in `0..=1`, as
+/// `(1 - bm25_weight) * vector + bm25_weight * bm25`. Papers missing from one
+/// side score 0 there. Sorted best first.
+pub fn merge_scores(
+    vector_scores: &HashMap<i64, f32>,
+    bm25_scores: &HashMap<i64, f32>,
+    bm25_weight: f32,
+) -> Vec<(i64, f32)> {
+    let weight = bm25_weight.clamp(0.0, 1.0);
+    let mut ids: Vec<i64> = vector_scores.keys().copied().collect();
+    if weight > 0.0 {
+        ids.extend(
+            bm25_scores
+                .keys()
+                .filter(|id| !vector_scores.contains_key(id)),
+        );
+    }
+
+    let mut merged: Vec<(i64, f32)> = ids
+        .into_iter()
+        .map(|id| {
+            let vector = vector_scores.get(&id).copied().unwrap_or(0.0);
+            let bm25 = bm25_scores.get(&id).copied().unwrap_or(0.0);
+            (id, (1.0 - weight) * vector + weight * bm25)
+        })
+        .collect();
+    merged.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    merged
+}
+
+/// Embed every paper whose stored embedding is missing or out of date.
+///
+/// Papers are sent `batch_size` at a time and each batch is saved as soon
+/// as it comes back, so a run that fails part way keeps its progress and
+/// the next run only embeds what is left.
+pub async fn reindex_embeddings<F>(
+    db: &DatabaseConnection,
+    config: &EmbeddingConfig,
+    mut on_progress: F,
+) -> Result<ReindexProgress>
+where
+    F: FnMut(&ReindexProgress),
+{
+    let papers = PaperRepository::find_all(db).await?;
+    let stored = EmbeddingRepository::find_hashes(db, &config.model_name).await?;
+
+    let mut progress = ReindexProgress {
+        total: papers.len(),
+        ..Default::default()
+    };
+    let mut pending: Vec<(i64, String, String)> = Vec::new();
+    for paper in &papers {
+        let text = embedding_text(paper);
+        let hash = content_hash(&config.model_name, &text);
+        if stored.get(&paper.id) == Some(&hash) {
+            progress.skipped += 1;
+        } else {
+            pending.push((paper.id, hash, text));
+        }
+    }
+    info!(
+        "Reindexing embeddings with {}: {} of {} papers need embedding",
+        config.model_name,
+        pending.len(),
+        progress.total
+    );
+    on_progress(&progress);
+
+    let client = EmbeddingClient::new();
+    for batch in pending.chunks(config.batch_size.max(1)) {
+        let inputs: Vec<String> = batch.iter().map(|(_, _, text)| text.clone()).collect();
+
+        let mut attempt = 1;
+        let vectors = loop {
+            match client.embed(config, &inputs).await {
+                Ok(vectors) => break vectors,
+                Err(e) if attempt < MAX_BATCH_ATTEMPTS => {
+                    warn!("Embedding batch failed (attempt {}): {}", attempt, e);
+                    tokio::time::sleep(std::time::Duration::from_secs(2u64.pow(attempt))).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(AppError::ai_error(
+                        "reindex_embeddings",
+                        format!(
+                            "{} ({} of {} papers are indexed; run again to continue)",
+                            e,
+                            progress.embedded + progress.skipped,
+                            progress.total
+                        ),
+                    ));
+                }
+            }
+        };
+
+        let embeddings = batch
+            .iter()
+            .zip(vectors)
+            .map(|((paper_id, hash, _), vector)| NewEmbedding {
+                paper_id: *paper_id,
+                content_hash: hash.clone(),
+                vector,
+            })
+            .collect();
+        EmbeddingRepository::save_batch(db, &config.model_name, embeddings).await?;
+
+        progress.embedded += batch.len();
+        on_progress(&progress);
+    }
+
+    info!(
+        "Embedded {} papers, {} already current",
+        progress.embedded, progress.skipped
+    );
+    Ok(progress)
+}
+
+/// The `limit` papers closest to `query`. With a `bm25_weight` above 0 the
+/// ranking blends in full-text relevance; 1 ranks by BM25 alone.
+pub async fn search_semantic(
+    db: &DatabaseConnection,
+    config: &EmbeddingConfig,
+    query: &str,
+    limit: usize,
+    bm25_weight: f32,
+) -> Result<Vec<SemanticHit>> {
+    let limit = limit.clamp(1, MAX_SEMANTIC_LIMIT);
+    let bm25_weight = bm25_weight.clamp(0.0, 1.0);
+
+    let stored = EmbeddingRepository::find_vectors(db, &config.model_name).await?;
+    if stored.is_empty() {
+        return Err(AppError::validation(
+            "embeddings",
+            "No papers have been embedded yet; reindex embeddings first",
+        ));
+    }
+
+    let query_vector = EmbeddingClient::new()
+        .embed(config, &[query.to_string()])
+        .await
+        .map_err(|e| AppError::ai_error("search_papers_semantic", e.to_string()))?
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+
+    let vector_scores: HashMap<i64, f32> = stored
+        .iter()
+        .map(|(paper_id, vector)| {
+            let similarity = cosine_similarity(&query_vector, vector);
+            (*paper_id, similarity.clamp(0.0, 1.0))
+        })
+        .collect();
+
+    // FTS5 rejects some free text (stray quotes, `-` and so on); hybrid
+    // search then degrades to vector scores alone
+    let mut fts_papers: HashMap<i64, Paper> = HashMap::new();
+    let mut bm25_scores: HashMap<i64, f32> = HashMap::new();
+    if bm25_weight > 0.0 {
+        let candidates = (limit * 5).max(100) as u64;
+        match SearchRepository::fts_search(db, query, Some(candidates)).await {
+            Ok(results) => {
+                for (paper, score) in results {
+                    bm25_scores.insert(paper.id, (score / 100.0) as f32);
+                    fts_papers.insert(paper.id, Paper::from(paper));
+                }
+            }
+            Err(e) => warn!("Full-text part of hybrid search failed: {}", e),
+        }
+    }
+
+    let mut hits = Vec::with_capacity(limit);
+    for (paper_id, score) in merge_scores(&vector_scores, &bm25_scores, bm25_weight) {
+        if hits.len() == limit {
+            break;
+        }
+        let paper = match fts_papers.remove(&paper_id) {
+            Some(paper) => paper,
+            None => match PaperRepository::find_by_id(db, paper_id).await? {
+                Some(paper) => paper,
+                None => continue,
+            },
+        };
+        hits.push(SemanticHit {
+            paper,
+            score,
+            vector_score: vector_scores.get(&paper_id).copied().unwrap_or(0.0),
+            bm25_score: (bm25_weight > 0.0)
+                .then(|| bm25_scores.get(&paper_id).copied().unwrap_or(0.0)),
+        });
+    }
+
+    info!("Semantic search found {} results", hits.len());
+    Ok(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_content_hash_covers_model_and_text() {
+        let hash = content_hash("small", "text");
+        assert_eq!(hash, content_hash("small", "text"));
+        assert_ne!(hash, content_hash("large", "text"));
+        assert_ne!(hash, content_hash("small", "other"));
+    }
+
+    #[test]
+    fn test_merge_scores() {
+        let vector = HashMap::from([(1, 0.9), (2, 0.5)]);
+        let bm25 = HashMap::from([(2, 1.0), (3, 0.8)]);
+
+        let ids =
+            |merged: Vec<(i64, f32)>| merged.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+        assert_eq!(ids(merge_scores(&vector, &bm25, 0.0)), vec![1, 2]);
+        assert_eq!(ids(merge_scores(&vector, &bm25, 0.5)), vec![2, 1, 3]);
+        assert_eq!(ids(merge_scores(&vector, &bm25, 1.0)), vec![2, 3, 1]);
+    }
+}
@@ -0,0 +1,221 @@
+//! Spreadsheet export of the paper library
+//!
+//! One row per paper, with labels, keywords and authors joined by `; ` so
+//! the file opens directly in Excel or `read.csv` in R.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use tracing::info;
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::papers::importer::arxiv::extract_arxiv_id;
+use crate::repository::{
+    AuthorRepository, CategoryRepository, KeywordRepository, LabelRepository, PaperRepository,
+};
+use crate::sys::error::{AppError, Result};
+
+pub const CSV_COLUMNS: [&str; 18] = [
+    "id",
+    "title",
+    "doi",
+    "arxiv_id",
+    "publication_year",
+    "journal_name",
+    "conference_name",
+    "volume",
+    "issue",
+    "pages",
+    "citation_count",
+    "read_status",
+    "labels",
+    "keywords",
+    "authors",
+    "category",
+    "has_pdf",
+    "notes_length",
+];
+
+/// Separator for multi-valued columns
+const LIST_SEPARATOR: &str = "; ";
+
+/// Leading characters a spreadsheet evaluates as the start of a formula
+const FORMULA_PREFIXES: [char; 4] = ['=', '+', '-', '@'];
+
+/// Quote a field when it contains a delimiter, quote or line break, doubling
+/// any quotes inside it. Fields that would be read as a formula get a leading
+/// `'` so Excel shows them as text instead of evaluating them.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn write_row(out: &mut String, fields: &[String]) {
+    let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+    out.push_str(&line.join(","));
+    out.push_str("\r\n");
+}
+
+/// Write `papers` as CSV to `path` and return the number of rows written
+pub async fn export_papers_csv(
+    db: &DatabaseConnection,
+    papers: &[Paper],
+    path: &Path,
+) -> Result<usize> {
+    let paper_ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let labels = LabelRepository::get_paper_labels_batch(db, &paper_ids).await?;
+    let keywords = KeywordRepository::get_paper_keywords_batch(db, &paper_ids).await?;
+    let authors = AuthorRepository::get_paper_authors_batch(db, &paper_ids).await?;
+    let attachments = PaperRepository::get_attachments_batch(db, &paper_ids).await?;
+    let paper_categories = PaperRepository::get_category_ids_batch(db, &paper_ids).await?;
+    let category_names: HashMap<i64, String> = CategoryRepository::find_all(db)
+        .await?
+        .into_iter()
+        .map(|c| (c.id, c.name))
+        .collect();
+
+    // UTF-8 BOM so Excel does not mis-detect non-ASCII titles
+    let mut out = String::from("\u{feff}");
+    let header: Vec<String> = CSV_COLUMNS.iter().map(|c| c.to_string()).collect();
+    write_row(&mut out, &header);
+
+    for paper in papers {
+        let join = |names: Vec<String>| names.join(LIST_SEPARATOR);
+        let has_pdf = attachments.get(&paper.id).is_some_and(|list| {
+            list.iter().any(|a| {
+                a.file_type
+                    .as_deref()
+                    .unwrap_or("")
+                    .eq_ignore_ascii_case("pdf")
+                    || a.file_name.as_deref().unwrap_or("").ends_with(".pdf")
+            })
+        });
+        let arxiv_id = paper
+            .url
+            .as_deref()
+            .filter(|url| url.contains("arxiv.org/"))
+            .and_then(extract_arxiv_id);
+
+        let row = vec![
+            paper.id.to_string(),
+            paper.title.clone(),
+            paper.doi.clone().unwrap_or_default(),
+            arxiv_id.unwrap_or_default(),
+            paper
+                .publication_year
+                .map(|y| y.to_string())
+                .unwrap_or_default(),
+            paper.journal_name.clone().unwrap_or_default(),
+            paper.conference_name.clone().unwrap_or_default(),
+            paper.volume.clone().unwrap_or_default(),
+            paper.issue.clone().unwrap_or_default(),
+            paper.pages.clone().unwrap_or_default(),
+            paper.citation_count.to_string(),
+            paper.read_status.clone(),
+            join(
+                labels
+                    .get(&paper.id)
+                    .map(|list| list.iter().map(|l| l.name.clone()).collect())
+                    .unwrap_or_default(),
+            ),
+            join(
+                keywords
+                    .get(&paper.id)
+                    .map(|list| list.iter().map(|k| k.word.clone()).collect())
+                    .unwrap_or_default(),
+            ),
+            join(
+                authors
+                    .get(&paper.id)
+                    .map(|list| list.iter().map(|a| a.full_name()).collect())
+                    .unwrap_or_default(),
+            ),
+            join(
+                paper_categories
+                    .get(&paper.id)
+                    .map(|ids| {
+                        ids.iter()
+                            .filter_map(|id| category_names.get(id).cloned())
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            has_pdf.to_string(),
+            paper
+                .notes
+                .as_deref()
+                .map(|n| n.chars().count())
+                .unwrap_or(0)
+                .to_string(),
+        ];
+        write_row(&mut out, &row);
+    }
+
+    let path_str = path.to_string_lossy().to_string();
+    tokio::fs::write(path, out).await.map_err(|e| {
+        AppError::file_system(path_str.clone(), format!("Failed to write CSV: {}", e))
+    })?;
+    info!("Wrote {} papers to {}", papers.len(), path_str);
+
+    Ok(papers.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_csv_field_neutralizes_formulas() {
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("+1"), "'+1");
+        assert_eq!(csv_field("-cmd"), "'-cmd");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a=b"), "a=b");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[tokio::test]
+    async fn test_export_papers_csv() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Deep \"Learning\", Revisited")
+            .with_doi("10.1000/dl")
+            .with_url("https://arxiv.org/abs/2101.00001")
+            .with_authors(&["Ada Lovelace", "Alan Turing"])
+            .with_label("ml")
+            .with_keyword("neural")
+            .with_category("AI")
+            .insert(&db)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("papers.csv");
+        let written = export_papers_csv(&db, &[paper], &path).await.unwrap();
+        assert_eq!(written, 1);
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let mut lines = text.trim_start_matches('\u{feff}').lines();
+        assert_eq!(lines.next().unwrap(), CSV_COLUMNS.join(","));
+        let row = lines.next().unwrap();
+        assert!(row.contains("\"Deep \"\"Learning\"\", Revisited\""));
+        assert!(row.contains(",2101.00001,"));
+        assert!(row.contains(",ml,neural,Ada Lovelace; Alan Turing,AI,false,0"));
+    }
+}
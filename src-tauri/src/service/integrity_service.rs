@@ -0,0 +1,258 @@
+//! Database integrity checks
+//!
+//! Looks for rows that point at missing records, attachments whose file is
+//! gone, authors and keywords no paper uses any more, and cached counts that
+//! disagree with the rows they summarize. Problems are only reported; nothing
+//! is changed.
+
+use sea_orm::{DbBackend, FromQueryResult, Statement};
+use serde::Serialize;
+use tracing::info;
+
+use crate::database::DatabaseConnection;
+use crate::service::attachment_service;
+use crate::sys::error::{AppError, Result};
+
+/// Issues listed per check; the rest are only counted
+pub const SAMPLE_LIMIT: usize = 10;
+
+/// Outcome of one integrity check
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityCheckResult {
+    pub check_name: String,
+    pub passed: bool,
+    pub issue_count: u32,
+    /// Up to `SAMPLE_LIMIT` issues, described for the user
+    pub sample_issues: Vec<String>,
+}
+
+impl IntegrityCheckResult {
+    fn new(check_name: &str, issues: Vec<String>) -> Self {
+        Self {
+            check_name: check_name.to_string(),
+            passed: issues.is_empty(),
+            issue_count: issues.len() as u32,
+            sample_issues: issues.into_iter().take(SAMPLE_LIMIT).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReportDto {
+    pub checks: Vec<IntegrityCheckResult>,
+    pub total_issues: u32,
+}
+
+#[derive(FromQueryResult)]
+struct Issue {
+    issue: String,
+}
+
+/// Checks answered by one query each, returning one row per issue
+const SQL_CHECKS: &[(&str, &str)] = &[
+    (
+        "paper_author_references",
+        r#"
+        SELECT 'paper_author ' || pa.id || ': ' ||
+            CASE WHEN p.id IS NULL THEN 'missing paper ' || pa.paper_id
+                 ELSE 'missing author ' || pa.author_id END AS issue
+        FROM paper_author pa
+        LEFT JOIN paper p ON p.id = pa.paper_id
+        LEFT JOIN author a ON a.id = pa.author_id
+        WHERE p.id IS NULL OR a.id IS NULL
+        ORDER BY pa.id
+        "#,
+    ),
+    (
+        "paper_category_references",
+        r#"
+        SELECT 'paper_category ' || pc.id || ': ' ||
+            CASE WHEN p.id IS NULL THEN 'missing paper ' || pc.paper_id
+                 ELSE 'missing category ' || pc.category_id END AS issue
+        FROM paper_category pc
+        LEFT JOIN paper p ON p.id = pc.paper_id
+        LEFT JOIN category c ON c.id = pc.category_id
+        WHERE p.id IS NULL OR c.id IS NULL
+        ORDER BY pc.id
+        "#,
+    ),
+    (
+        "orphaned_authors",
+        r#"
+        SELECT 'author ' || a.id || ': ' ||
+            TRIM(a.first_name || ' ' || COALESCE(a.last_name, '')) AS issue
+        FROM author a
+        WHERE NOT EXISTS (SELECT 1 FROM paper_author pa WHERE pa.author_id = a.id)
+        ORDER BY a.id
+        "#,
+    ),
+    (
+        "orphaned_keywords",
+        r#"
+        SELECT 'keyword ' || k.id || ': ' || k.word AS issue
+        FROM keyword k
+        WHERE NOT EXISTS (SELECT 1 FROM paper_keyword pk WHERE pk.keyword_id = k.id)
+        ORDER BY k.id
+        "#,
+    ),
+    (
+        "label_document_counts",
+        r#"
+        SELECT 'label ' || id || ' (' || name || '): stored ' || document_count ||
+            ', actual ' || actual AS issue
+        FROM (
+            SELECT l.id, l.name, l.document_count,
+                (SELECT COUNT(*) FROM paper_label pl WHERE pl.label_id = l.id) +
+                (SELECT COUNT(*) FROM clip_label cl WHERE cl.label_id = l.id) AS actual
+            FROM label l
+        )
+        WHERE document_count != actual
+        ORDER BY id
+        "#,
+    ),
+    (
+        "paper_attachment_counts",
+        r#"
+        SELECT 'paper ' || id || ': stored ' || attachment_count ||
+            ', actual ' || actual AS issue
+        FROM (
+            SELECT p.id, p.attachment_count,
+                (SELECT COUNT(*) FROM attachment a WHERE a.paper_id = p.id) AS actual
+            FROM paper p
+        )
+        WHERE attachment_count != actual
+        ORDER BY id
+        "#,
+    ),
+];
+
+async fn query_issues(db: &DatabaseConnection, check_name: &str, sql: &str) -> Result<Vec<String>> {
+    let issues = Issue::find_by_statement(Statement::from_string(DbBackend::Sqlite, sql))
+        .all(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to run check {}: {}", check_name, e)))?;
+    Ok(issues.into_iter().map(|i| i.issue).collect())
+}
+
+/// Run every integrity check against the database and the attachment files
+/// under `files_dir`
+pub async fn check_integrity(
+    db: &DatabaseConnection,
+    files_dir: &str,
+) -> Result<IntegrityReportDto> {
+    let mut checks = Vec::with_capacity(SQL_CHECKS.len() + 1);
+    for (check_name, sql) in SQL_CHECKS {
+        let issues = query_issues(db, check_name, sql).await?;
+        checks.push(IntegrityCheckResult::new(check_name, issues));
+    }
+
+    let attachments = attachment_service::validate_attachments(db, files_dir, false).await?;
+    let missing_files = attachments
+        .missing
+        .into_iter()
+        .map(|m| {
+            format!(
+                "attachment {} of paper {}: {}",
+                m.attachment_id, m.paper_id, m.expected_path
+            )
+        })
+        .collect();
+    checks.insert(
+        1,
+        IntegrityCheckResult::new("attachment_files", missing_files),
+    );
+
+    let total_issues = checks.iter().map(|c| c.issue_count).sum();
+    info!(
+        "Integrity check found {} issues in {} checks",
+        total_issues,
+        checks.len()
+    );
+    Ok(IntegrityReportDto {
+        checks,
+        total_issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::ConnectionTrait;
+
+    use super::*;
+    use crate::repository::{KeywordRepository, PaperRepository};
+    use crate::testing::{test_db, PaperFixture};
+
+    fn check<'a>(report: &'a IntegrityReportDto, name: &str) -> &'a IntegrityCheckResult {
+        report.checks.iter().find(|c| c.check_name == name).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_clean_library_passes() {
+        let db = test_db().await;
+        PaperFixture::new("Attention Is All You Need")
+            .with_authors(&["Ashish Vaswani"])
+            .with_label("to-read")
+            .with_keyword("transformer")
+            .with_category("NLP")
+            .insert(&db)
+            .await;
+        let files = tempfile::tempdir().unwrap();
+
+        let report = check_integrity(&db, &files.path().to_string_lossy())
+            .await
+            .unwrap();
+        assert_eq!(report.checks.len(), 7);
+        assert_eq!(report.total_issues, 0, "{:?}", report.checks);
+    }
+
+    #[tokio::test]
+    async fn test_problems_are_reported_without_fixing() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Deep Residual Learning")
+            .with_authors(&["Kaiming He"])
+            .with_label("vision")
+            .insert(&db)
+            .await;
+        KeywordRepository::create_or_find(&db, "unused")
+            .await
+            .unwrap();
+        PaperRepository::add_attachment(&db, paper.id, Some("paper.pdf".into()), None, None)
+            .await
+            .unwrap();
+        PaperRepository::update_attachment_count(&db, paper.id, 2)
+            .await
+            .unwrap();
+        db.execute_unprepared("PRAGMA foreign_keys = OFF")
+            .await
+            .unwrap();
+        db.execute_unprepared(&format!(
+            "INSERT INTO paper_category (paper_id, category_id) VALUES ({}, 999)",
+            paper.id
+        ))
+        .await
+        .unwrap();
+        db.execute_unprepared("UPDATE label SET document_count = 5")
+            .await
+            .unwrap();
+        let files = tempfile::tempdir().unwrap();
+
+        let report = check_integrity(&db, &files.path().to_string_lossy())
+            .await
+            .unwrap();
+        assert!(check(&report, "paper_author_references").passed);
+        let categories = check(&report, "paper_category_references");
+        assert_eq!(categories.issue_count, 1);
+        assert!(categories.sample_issues[0].ends_with("missing category 999"));
+        assert_eq!(check(&report, "attachment_files").issue_count, 1);
+        assert_eq!(check(&report, "orphaned_keywords").issue_count, 1);
+        assert_eq!(check(&report, "label_document_counts").issue_count, 1);
+        assert_eq!(check(&report, "paper_attachment_counts").issue_count, 1);
+        assert_eq!(report.total_issues, 5);
+
+        // Reporting leaves the data as it was
+        let report = check_integrity(&db, &files.path().to_string_lossy())
+            .await
+            .unwrap();
+        assert_eq!(report.total_issues, 5);
+    }
+}
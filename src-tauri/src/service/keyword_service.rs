@@ -0,0 +1,229 @@
+//! Keyword extraction from paper abstracts
+//!
+//! Terms are scored with TF-IDF against the abstracts of the other papers in
+//! the library. With an empty library every term gets the same IDF, so the
+//! ranking falls back to term frequency after stop-word removal.
+
+use std::collections::{HashMap, HashSet};
+
+use tracing::{info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::repository::{KeywordRepository, PaperRepository};
+use crate::sys::config::AppConfig;
+use crate::sys::error::{AppError, Result};
+
+/// Number of keywords stored when extraction runs automatically on import
+pub const AUTO_EXTRACT_KEYWORD_COUNT: usize = 8;
+
+/// Shortest token considered a keyword candidate
+const MIN_TERM_LENGTH: usize = 3;
+
+const STOP_WORDS: &[&str] = &[
+    "about", "above", "across", "after", "again", "against", "all", "also", "although", "among",
+    "and", "another", "any", "are", "based", "because", "been", "before", "being", "between",
+    "both", "but", "can", "could", "did", "does", "done", "due", "during", "each", "either", "et",
+    "etc", "even", "ever", "every", "few", "for", "from", "further", "had", "has", "have",
+    "having", "here", "how", "however", "into", "its", "itself", "just", "less", "many", "may",
+    "might", "more", "most", "much", "must", "new", "not", "now", "novel", "our", "ours", "over",
+    "paper", "per", "present", "propose", "proposed", "rather", "results", "same", "several",
+    "shall", "should", "show", "shown", "shows", "since", "some", "such", "than", "that", "the",
+    "their", "them", "then", "there", "these", "they", "this", "those", "through", "thus", "too",
+    "two", "under", "until", "upon", "use", "used", "uses", "using", "very", "via", "was", "way",
+    "well", "were", "what", "when", "where", "whether", "which", "while", "who", "whose", "why",
+    "will", "with", "within", "without", "would", "yet", "you", "your", "study", "approach",
+    "method", "methods", "work",
+];
+
+/// Split text into lowercase keyword candidates, dropping stop words,
+/// numbers and very short tokens
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .map(|t| t.trim_matches('-').to_lowercase())
+        .filter(|t| t.chars().count() >= MIN_TERM_LENGTH)
+        .filter(|t| !t.chars().all(|c| c.is_ascii_digit() || c == '-'))
+        .filter(|t| !STOP_WORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// Top `max_keywords` terms of `text` by TF-IDF over `corpus`
+pub fn rank_keywords(text: &str, corpus: &[String], max_keywords: usize) -> Vec<String> {
+    let terms = tokenize(text);
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut term_counts: HashMap<&str, usize> = HashMap::new();
+    for term in &terms {
+        *term_counts.entry(term.as_str()).or_default() += 1;
+    }
+
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for document in corpus {
+        let document_terms: HashSet<String> = tokenize(document).into_iter().collect();
+        for term in term_counts.keys() {
+            if document_terms.contains(*term) {
+                *document_frequency.entry(*term).or_default() += 1;
+            }
+        }
+    }
+
+    let total_terms = terms.len() as f64;
+    let corpus_size = corpus.len() as f64;
+    let mut scored: Vec<(&str, f64)> = term_counts
+        .iter()
+        .map(|(term, count)| {
+            let tf = *count as f64 / total_terms;
+            let df = document_frequency.get(term).copied().unwrap_or(0) as f64;
+            let idf = ((corpus_size + 1.0) / (df + 1.0)).ln() + 1.0;
+            (*term, tf * idf)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    scored
+        .into_iter()
+        .take(max_keywords)
+        .map(|(term, _)| term.to_string())
+        .collect()
+}
+
+/// Extract keywords from a paper's abstract without storing them
+pub async fn extract_keywords(
+    db: &DatabaseConnection,
+    paper_id: i64,
+    max_keywords: usize,
+) -> Result<Vec<String>> {
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let abstract_text = paper
+        .abstract_text
+        .filter(|a| !a.trim().is_empty())
+        .ok_or_else(|| AppError::validation("paper_id", "Paper has no abstract"))?;
+
+    let corpus = PaperRepository::find_other_abstracts(db, paper_id).await?;
+    Ok(rank_keywords(&abstract_text, &corpus, max_keywords))
+}
+
+/// Extract keywords from a paper's abstract and link them to the paper,
+/// reusing keywords that already exist
+pub async fn extract_and_store_keywords(
+    db: &DatabaseConnection,
+    paper_id: i64,
+    max_keywords: usize,
+) -> Result<Vec<String>> {
+    let keywords = extract_keywords(db, paper_id, max_keywords).await?;
+    store_keywords(db, paper_id, &keywords).await?;
+    Ok(keywords)
+}
+
+/// Link `words` to a paper, creating keywords that do not exist yet. Blank
+/// words are skipped and words differing only in case are stored once.
+/// Returns the number of distinct keywords linked.
+pub async fn store_keywords(
+    db: &DatabaseConnection,
+    paper_id: i64,
+    words: &[String],
+) -> Result<usize> {
+    let mut seen = HashSet::new();
+    for word in words.iter().map(|w| w.trim()) {
+        if word.is_empty() || !seen.insert(word.to_lowercase()) {
+            continue;
+        }
+        let keyword = KeywordRepository::create_or_find(db, word).await?;
+        KeywordRepository::add_to_paper(db, paper_id, keyword.id).await?;
+    }
+
+    info!("Stored {} keywords for paper {}", seen.len(), paper_id);
+    Ok(seen.len())
+}
+
+/// Store keywords for a newly imported paper when
+/// `paper.auto_extract_keywords` is on. Failures are logged, not returned,
+/// so they never fail the import.
+pub async fn auto_extract_keywords(db: &DatabaseConnection, config_dir: &str, paper_id: i64) {
+    let enabled = AppConfig::load(config_dir)
+        .map(|c| c.paper.auto_extract_keywords)
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    if let Err(e) = extract_and_store_keywords(db, paper_id, AUTO_EXTRACT_KEYWORD_COUNT).await {
+        warn!(
+            "Automatic keyword extraction failed for paper {}: {}",
+            paper_id, e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_tokenize_drops_stop_words_and_numbers() {
+        assert_eq!(
+            tokenize("We propose a Graph-Based model for the 2024 benchmark, using GNNs."),
+            vec!["graph-based", "model", "benchmark", "gnns"]
+        );
+    }
+
+    #[test]
+    fn test_rank_keywords_prefers_distinctive_terms() {
+        let text = "Protein folding with transformers. Transformers predict protein folding.";
+        let corpus = vec![
+            "Transformers for machine translation.".to_string(),
+            "Vision transformers for image classification.".to_string(),
+        ];
+
+        let keywords = rank_keywords(text, &corpus, 2);
+        assert_eq!(keywords, vec!["folding", "protein"]);
+
+        // Without a corpus the ranking is by frequency
+        let keywords = rank_keywords(text, &[], 3);
+        assert_eq!(keywords, vec!["folding", "protein", "transformers"]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_and_store_keywords_dedups() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Protein folding")
+            .with_abstract("Protein folding prediction with protein language models")
+            .insert(&db)
+            .await;
+
+        extract_and_store_keywords(&db, paper.id, 3).await.unwrap();
+        let keywords = extract_and_store_keywords(&db, paper.id, 3).await.unwrap();
+
+        assert_eq!(keywords[0], "protein");
+        let stored = KeywordRepository::get_paper_keywords(&db, paper.id)
+            .await
+            .unwrap();
+        assert_eq!(stored.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_store_keywords_dedups_case_insensitively() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Paper").insert(&db).await;
+
+        let words: Vec<String> = ["Neoplasms", "neoplasms ", "", "Humans"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(store_keywords(&db, paper.id, &words).await.unwrap(), 2);
+
+        let mut stored: Vec<String> = KeywordRepository::get_paper_keywords(&db, paper.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|k| k.word)
+            .collect();
+        stored.sort();
+        assert_eq!(stored, vec!["Humans", "Neoplasms"]);
+    }
+}
@@ -0,0 +1,215 @@
+//! Language detection of papers
+//!
+//! The abstract is run through whatlang, falling back to the title for papers
+//! without one. Only the languages in [`DETECTED_LANGUAGES`] are considered,
+//! which avoids wild guesses on short texts. The ISO 639-1 code of the result
+//! is stored in `paper.language`.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use tracing::{info, warn};
+use whatlang::{Detector, Lang};
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::PaperRepository;
+use crate::sys::error::{AppError, Result};
+
+/// `(language, ISO 639-1 code, English name)` of every detected language
+pub const DETECTED_LANGUAGES: &[(Lang, &str, &str)] = &[
+    (Lang::Cmn, "zh", "Chinese"),
+    (Lang::Eng, "en", "English"),
+    (Lang::Fra, "fr", "French"),
+    (Lang::Deu, "de", "German"),
+    (Lang::Ita, "it", "Italian"),
+    (Lang::Jpn, "ja", "Japanese"),
+    (Lang::Kor, "ko", "Korean"),
+    (Lang::Por, "pt", "Portuguese"),
+    (Lang::Rus, "ru", "Russian"),
+    (Lang::Spa, "es", "Spanish"),
+];
+
+/// Texts shorter than this are not detected
+const MIN_TEXT_CHARS: usize = 10;
+
+/// Papers loaded from the database at a time by `detect_missing_languages`
+const PAPER_BATCH_SIZE: u64 = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code, e.g. `en`
+    pub code: &'static str,
+    pub name: &'static str,
+    /// Between 0 and 1
+    pub confidence: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LanguageDetectionSummary {
+    /// Papers that had no language
+    pub processed: usize,
+    pub detected: usize,
+    /// Papers whose text was too short or not in a detected language
+    pub undetected: usize,
+    /// `(code, papers)` of the detected languages, most papers first
+    pub languages: Vec<(String, usize)>,
+}
+
+fn detector() -> &'static Detector {
+    static DETECTOR: OnceLock<Detector> = OnceLock::new();
+    DETECTOR.get_or_init(|| {
+        let languages: Vec<Lang> = DETECTED_LANGUAGES.iter().map(|(l, _, _)| *l).collect();
+        Detector::with_allowlist(languages)
+    })
+}
+
+/// English name of a detected language code
+pub fn language_name(code: &str) -> Option<&'static str> {
+    DETECTED_LANGUAGES
+        .iter()
+        .find(|(_, c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, _, name)| *name)
+}
+
+/// Language of `text`, or `None` when it is too short or not one of
+/// [`DETECTED_LANGUAGES`]
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let text = text.trim();
+    if text.chars().count() < MIN_TEXT_CHARS {
+        return None;
+    }
+
+    let info = detector().detect(text)?;
+    let (_, code, name) = DETECTED_LANGUAGES
+        .iter()
+        .find(|(l, _, _)| *l == info.lang())?;
+    Some(DetectedLanguage {
+        code,
+        name,
+        confidence: info.confidence() as f32,
+    })
+}
+
+/// Abstract of the paper, or its title when it has no abstract
+fn detection_text(paper: &Paper) -> &str {
+    paper
+        .abstract_text
+        .as_deref()
+        .filter(|a| !a.trim().is_empty())
+        .unwrap_or(&paper.title)
+}
+
+/// Detect the language of a paper and store it. A paper whose language
+/// cannot be detected keeps its current language.
+pub async fn detect_and_store(
+    db: &DatabaseConnection,
+    paper_id: i64,
+) -> Result<Option<DetectedLanguage>> {
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let detected = detect_language(detection_text(&paper));
+    match &detected {
+        Some(language) => PaperRepository::update_language(db, paper_id, language.code).await?,
+        None => warn!("Could not detect the language of paper {}", paper_id),
+    }
+    Ok(detected)
+}
+
+/// Detect and store the language of every paper that has none
+pub async fn detect_missing_languages(db: &DatabaseConnection) -> Result<LanguageDetectionSummary> {
+    let mut summary = LanguageDetectionSummary::default();
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    let mut last_id = 0;
+    loop {
+        let batch =
+            PaperRepository::find_without_language_after(db, last_id, PAPER_BATCH_SIZE).await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for paper in &batch {
+            summary.processed += 1;
+            match detect_language(detection_text(paper)) {
+                Some(language) => {
+                    PaperRepository::update_language(db, paper.id, language.code).await?;
+                    *counts.entry(language.code).or_default() += 1;
+                    summary.detected += 1;
+                }
+                None => summary.undetected += 1,
+            }
+        }
+    }
+
+    let mut languages: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(code, papers)| (code.to_string(), papers))
+        .collect();
+    languages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary.languages = languages;
+
+    info!(
+        "Detected the language of {} of {} papers",
+        summary.detected, summary.processed
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_detect_language() {
+        let english = detect_language(
+            "We present a method for measuring surface roughness with white light interferometry.",
+        )
+        .unwrap();
+        assert_eq!(english.code, "en");
+        assert_eq!(english.name, "English");
+        assert!(english.confidence > 0.5);
+
+        let chinese = detect_language("本文提出了一种基于白光干涉的表面粗糙度测量方法。").unwrap();
+        assert_eq!(chinese.code, "zh");
+
+        assert_eq!(detect_language("Short"), None);
+        assert_eq!(language_name("ZH"), Some("Chinese"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_languages_are_detected() {
+        let db = test_db().await;
+        let english = PaperFixture::new("Surface metrology")
+            .with_abstract(
+                "This paper studies the calibration of coordinate measuring machines in detail.",
+            )
+            .insert(&db)
+            .await;
+        let chinese = PaperFixture::new("三坐标测量机的标定方法研究")
+            .insert(&db)
+            .await;
+        PaperFixture::new("X").insert(&db).await;
+
+        let summary = detect_missing_languages(&db).await.unwrap();
+        assert_eq!(summary.processed, 3);
+        assert_eq!(summary.detected, 2);
+        assert_eq!(summary.undetected, 1);
+
+        let (papers, total) = PaperRepository::find_by_language_paginated(&db, "zh", 0, 10)
+            .await
+            .unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(papers[0].id, chinese.id);
+
+        // Only papers without a language are processed again
+        let again = detect_missing_languages(&db).await.unwrap();
+        assert_eq!(again.processed, 1);
+
+        let detected = detect_and_store(&db, english.id).await.unwrap().unwrap();
+        assert_eq!(detected.code, "en");
+    }
+}
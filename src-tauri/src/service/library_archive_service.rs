@@ -0,0 +1,494 @@
+//! Portable library archives
+//!
+//! A library archive is one zip file with everything needed to move the
+//! library to another machine: a snapshot of the database, the config
+//! directory and the attachment files, plus `manifest.json` listing every
+//! entry with its size and SHA-256. Entries are streamed into the zip one
+//! file at a time and the manifest is written last, once all checksums are
+//! known. Validation reads the archive back the same way without writing
+//! anything to disk.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::service::backup_service::DATABASE_FILE;
+use crate::sys::dirs::{AppDirs, MigrationPhase, MigrationStatus};
+use crate::sys::error::{AppError, Result};
+
+/// Name of the manifest entry
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Manifest format written by this version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Archive directories for the database, config and attachments
+pub const ARCHIVE_DATA_DIR: &str = "data";
+pub const ARCHIVE_CONFIG_DIR: &str = "config";
+pub const ARCHIVE_FILES_DIR: &str = "files";
+
+/// Emit progress every this many files
+pub const PROGRESS_INTERVAL: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    /// Path inside the archive, `/`-separated
+    pub path: String,
+    pub size: u64,
+    /// Lowercase hex SHA-256 of the content
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub manifest_version: u32,
+    pub app_version: String,
+    /// Name of the newest database migration in the snapshot
+    pub schema_version: String,
+    pub created_at: String,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveValidation {
+    pub manifest: ArchiveManifest,
+    /// Entries whose size or checksum differ from the manifest
+    pub mismatched: Vec<String>,
+    /// Entries listed in the manifest but absent from the archive
+    pub missing: Vec<String>,
+}
+
+impl ArchiveValidation {
+    pub fn is_valid(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty()
+    }
+}
+
+fn zip_error(path: &Path, e: impl std::fmt::Display) -> AppError {
+    AppError::file_system(path.to_string_lossy().to_string(), e.to_string())
+}
+
+/// Reject targets inside the data, config or files directory, which would
+/// make the archive include itself or pollute the library
+pub fn ensure_outside_library(target: &Path, app_dirs: &AppDirs) -> Result<()> {
+    let parent = match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let parent = parent
+        .canonicalize()
+        .map_err(|e| AppError::file_system(parent.to_string_lossy().to_string(), e.to_string()))?;
+
+    let inside = [&app_dirs.data, &app_dirs.config, &app_dirs.files]
+        .iter()
+        .filter_map(|dir| Path::new(dir).canonicalize().ok())
+        .any(|dir| parent.starts_with(dir));
+    if inside {
+        return Err(AppError::validation(
+            "target_path",
+            "The archive cannot be written inside the data directory",
+        ));
+    }
+    Ok(())
+}
+
+/// Every regular file under `dir` as `(archive path, file path)`, with
+/// archive paths starting with `prefix/`. Sorted by archive path.
+pub(crate) fn collect_files(dir: &Path, prefix: &str) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in std::fs::read_dir(&current)? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                let relative = path
+                    .strip_prefix(dir)
+                    .unwrap_or(&path)
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                files.push((format!("{}/{}", prefix, relative), path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Copy `reader` into `writer`, returning the byte count and SHA-256
+fn copy_hashed(reader: &mut impl Read, writer: &mut impl Write) -> std::io::Result<(u64, String)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+fn phase_for(archive_path: &str) -> MigrationPhase {
+    match archive_path.split('/').next() {
+        Some(ARCHIVE_DATA_DIR) => MigrationPhase::CopyingDatabase,
+        Some(ARCHIVE_CONFIG_DIR) => MigrationPhase::CopyingConfig,
+        _ => MigrationPhase::CopyingFiles,
+    }
+}
+
+/// Write a library archive to `target`.
+///
+/// `database_snapshot` is a consistent copy of the database (see
+/// `backup_service::create_backup`); it is stored as `data/xuan-brain.sqlite`.
+/// The zip is written to `<target>.part` and renamed once complete.
+pub fn write_library_archive<F>(
+    database_snapshot: &Path,
+    app_dirs: &AppDirs,
+    schema_version: &str,
+    target: &Path,
+    mut on_progress: F,
+) -> Result<ArchiveManifest>
+where
+    F: FnMut(&MigrationStatus),
+{
+    let mut sources = vec![(
+        format!("{}/{}", ARCHIVE_DATA_DIR, DATABASE_FILE),
+        database_snapshot.to_path_buf(),
+    )];
+    for (dir, prefix) in [
+        (&app_dirs.config, ARCHIVE_CONFIG_DIR),
+        (&app_dirs.files, ARCHIVE_FILES_DIR),
+    ] {
+        sources.extend(
+            collect_files(Path::new(dir), prefix)
+                .map_err(|e| AppError::file_system(dir.clone(), e.to_string()))?,
+        );
+    }
+
+    let total_files = sources.len() as u32;
+    on_progress(&MigrationStatus {
+        phase: MigrationPhase::Preparing,
+        current_file: None,
+        total_files,
+        processed_files: 0,
+        error: None,
+    });
+
+    let part_path = PathBuf::from(format!("{}.part", target.to_string_lossy()));
+    let manifest = match write_zip(&part_path, &sources, schema_version, &mut on_progress) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = std::fs::remove_file(&part_path);
+            return Err(e);
+        }
+    };
+    std::fs::rename(&part_path, target).map_err(|e| zip_error(target, e))?;
+
+    on_progress(&MigrationStatus {
+        phase: MigrationPhase::Completed,
+        current_file: None,
+        total_files,
+        processed_files: total_files,
+        error: None,
+    });
+    info!(
+        "Wrote library archive with {} files to {:?}",
+        manifest.entries.len(),
+        target
+    );
+    Ok(manifest)
+}
+
+/// Stream `sources` into a new zip at `path`, followed by the manifest
+fn write_zip<F>(
+    path: &Path,
+    sources: &[(String, PathBuf)],
+    schema_version: &str,
+    on_progress: &mut F,
+) -> Result<ArchiveManifest>
+where
+    F: FnMut(&MigrationStatus),
+{
+    let file = File::create(path).map_err(|e| zip_error(path, e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    let total_files = sources.len() as u32;
+    let mut entries = Vec::with_capacity(sources.len());
+    for (index, (archive_path, source)) in sources.iter().enumerate() {
+        let processed_files = index as u32;
+        if processed_files.is_multiple_of(PROGRESS_INTERVAL) || total_files < 50 {
+            on_progress(&MigrationStatus {
+                phase: phase_for(archive_path),
+                current_file: Some(archive_path.clone()),
+                total_files,
+                processed_files,
+                error: None,
+            });
+        }
+
+        zip.start_file(archive_path.as_str(), options)
+            .map_err(|e| zip_error(path, e))?;
+        let mut reader = BufReader::new(File::open(source).map_err(|e| zip_error(source, e))?);
+        let (size, sha256) =
+            copy_hashed(&mut reader, &mut zip).map_err(|e| zip_error(source, e))?;
+        entries.push(ArchiveEntry {
+            path: archive_path.clone(),
+            size,
+            sha256,
+        });
+    }
+
+    let manifest = ArchiveManifest {
+        manifest_version: MANIFEST_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version: schema_version.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        entries,
+    };
+    zip.start_file(MANIFEST_FILE, options)
+        .map_err(|e| zip_error(path, e))?;
+    serde_json::to_writer_pretty(&mut zip, &manifest)
+        .map_err(|e| AppError::generic(format!("Failed to write manifest: {}", e)))?;
+    zip.finish()
+        .map_err(|e| zip_error(path, e))?
+        .flush()
+        .map_err(|e| zip_error(path, e))?;
+
+    Ok(manifest)
+}
+
+/// Read the manifest of a library archive
+pub fn read_manifest<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    path: &Path,
+) -> Result<ArchiveManifest> {
+    let manifest = archive.by_name(MANIFEST_FILE).map_err(|_| {
+        AppError::validation("path", "Not a library archive: manifest.json is missing")
+    })?;
+    let manifest: ArchiveManifest = serde_json::from_reader(manifest).map_err(|e| {
+        AppError::validation(
+            "path",
+            format!("Invalid manifest in {}: {}", path.display(), e),
+        )
+    })?;
+    if manifest.manifest_version > MANIFEST_VERSION {
+        return Err(AppError::validation(
+            "path",
+            format!(
+                "Archive was written by a newer version (manifest version {})",
+                manifest.manifest_version
+            ),
+        ));
+    }
+    Ok(manifest)
+}
+
+/// Open a library archive for reading
+pub fn open_library_archive(path: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    let file = File::open(path).map_err(|e| zip_error(path, e))?;
+    ZipArchive::new(BufReader::new(file))
+        .map_err(|e| AppError::validation("path", format!("Not a zip archive: {}", e)))
+}
+
+/// The part of an archive path after `prefix/` as a relative file path, or
+/// `None` when the path is outside `prefix` or would escape the directory it
+/// is extracted into
+pub fn archive_relative_path(archive_path: &str, prefix: &str) -> Option<PathBuf> {
+    let relative = archive_path.strip_prefix(prefix)?.strip_prefix('/')?;
+    let mut path = PathBuf::new();
+    for part in relative.split('/') {
+        if part.is_empty() || part == "." || part == ".." || part.contains('\\') {
+            return None;
+        }
+        path.push(part);
+    }
+    Some(path).filter(|p| p.is_relative() && p.components().count() > 0)
+}
+
+/// Extract one archive entry to `target`, checking it against the manifest.
+/// A partly written or mismatching file is removed again.
+pub fn extract_entry<R: Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    entry: &ArchiveEntry,
+    target: &Path,
+) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| zip_error(parent, e))?;
+    }
+
+    let result = (|| -> Result<()> {
+        let mut file = archive.by_name(&entry.path).map_err(|_| {
+            AppError::validation(
+                "path",
+                format!("{} is missing from the archive", entry.path),
+            )
+        })?;
+        let mut writer = BufWriter::new(File::create(target).map_err(|e| zip_error(target, e))?);
+        let (size, sha256) =
+            copy_hashed(&mut file, &mut writer).map_err(|e| zip_error(target, e))?;
+        writer.flush().map_err(|e| zip_error(target, e))?;
+        if size != entry.size || sha256 != entry.sha256 {
+            return Err(AppError::validation(
+                "path",
+                format!("{} does not match the manifest checksum", entry.path),
+            ));
+        }
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(target);
+    }
+    result
+}
+
+/// Check every entry of a library archive against its manifest. Entries are
+/// decompressed and hashed in memory; nothing is extracted.
+pub fn validate_library_archive(path: &Path) -> Result<ArchiveValidation> {
+    let mut archive = open_library_archive(path)?;
+    let manifest = read_manifest(&mut archive, path)?;
+
+    let mut mismatched = Vec::new();
+    let mut missing = Vec::new();
+    for entry in &manifest.entries {
+        let mut file = match archive.by_name(&entry.path) {
+            Ok(file) => file,
+            Err(_) => {
+                missing.push(entry.path.clone());
+                continue;
+            }
+        };
+        match copy_hashed(&mut file, &mut std::io::sink()) {
+            Ok((size, sha256)) if size == entry.size && sha256 == entry.sha256 => {}
+            _ => mismatched.push(entry.path.clone()),
+        }
+    }
+
+    info!(
+        "Validated library archive {:?}: {} entries, {} mismatched, {} missing",
+        path,
+        manifest.entries.len(),
+        mismatched.len(),
+        missing.len()
+    );
+    Ok(ArchiveValidation {
+        manifest,
+        mismatched,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::test_app_dirs;
+
+    #[test]
+    fn test_archive_round_trip_and_tamper_detection() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = test_app_dirs(root.path());
+        let snapshot = root.path().join("cache").join("snapshot.sqlite");
+        std::fs::write(&snapshot, b"SQLite format 3\0 snapshot").unwrap();
+        std::fs::write(Path::new(&dirs.config).join("config.json"), b"{}").unwrap();
+        let attachment_dir = Path::new(&dirs.files).join("abc123");
+        std::fs::create_dir_all(&attachment_dir).unwrap();
+        std::fs::write(attachment_dir.join("paper.pdf"), b"%PDF-1.4").unwrap();
+
+        let out = tempfile::tempdir().unwrap();
+        let target = out.path().join("library.zip");
+        let mut phases = Vec::new();
+        let manifest = write_library_archive(&snapshot, &dirs, "m1", &target, |s| {
+            phases.push(s.phase.clone())
+        })
+        .unwrap();
+
+        let paths: Vec<&str> = manifest.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "data/xuan-brain.sqlite",
+                "config/config.json",
+                "files/abc123/paper.pdf"
+            ]
+        );
+        assert_eq!(phases.last(), Some(&MigrationPhase::Completed));
+        assert!(!out.path().join("library.zip.part").exists());
+
+        let validation = validate_library_archive(&target).unwrap();
+        assert!(validation.is_valid());
+        assert_eq!(validation.manifest.schema_version, "m1");
+
+        // Same manifest, different attachment content
+        let tampered = out.path().join("tampered.zip");
+        let mut zip = ZipWriter::new(File::create(&tampered).unwrap());
+        let options = SimpleFileOptions::default();
+        zip.start_file("files/abc123/paper.pdf", options).unwrap();
+        zip.write_all(b"%PDF-1.5").unwrap();
+        zip.start_file(MANIFEST_FILE, options).unwrap();
+        serde_json::to_writer(&mut zip, &manifest).unwrap();
+        zip.finish().unwrap();
+
+        let validation = validate_library_archive(&tampered).unwrap();
+        assert_eq!(validation.mismatched, vec!["files/abc123/paper.pdf"]);
+        assert_eq!(
+            validation.missing,
+            vec!["data/xuan-brain.sqlite", "config/config.json"]
+        );
+    }
+
+    #[test]
+    fn test_archive_relative_path_rejects_escapes() {
+        assert_eq!(
+            archive_relative_path("files/abc123/paper.pdf", ARCHIVE_FILES_DIR),
+            Some(Path::new("abc123").join("paper.pdf"))
+        );
+        assert_eq!(
+            archive_relative_path("filesx/a.pdf", ARCHIVE_FILES_DIR),
+            None
+        );
+        assert_eq!(
+            archive_relative_path("files/../a.pdf", ARCHIVE_FILES_DIR),
+            None
+        );
+        assert_eq!(
+            archive_relative_path("files//a.pdf", ARCHIVE_FILES_DIR),
+            None
+        );
+        assert_eq!(archive_relative_path("files/", ARCHIVE_FILES_DIR), None);
+    }
+
+    #[test]
+    fn test_refuses_target_inside_data_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dirs = test_app_dirs(root.path());
+
+        let inside = Path::new(&dirs.data).join("library.zip");
+        assert!(matches!(
+            ensure_outside_library(&inside, &dirs),
+            Err(AppError::ValidationError { .. })
+        ));
+        assert!(ensure_outside_library(&root.path().join("library.zip"), &dirs).is_ok());
+    }
+}
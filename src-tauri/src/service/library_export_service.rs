@@ -0,0 +1,308 @@
+//! Portable library export
+//!
+//! Writes the library as a zip that other tools can read without this app:
+//! `library.json` with every paper, author, label and category, `bibtex.bib`
+//! for reference managers, and optionally the attachment files of the
+//! exported papers under `files/`. Unlike a library backup it holds no
+//! database snapshot, so it is meant for sharing and archiving rather than
+//! restoring. The zip is written to `<target>.part` and renamed once
+//! complete.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tracing::info;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::database::DatabaseConnection;
+use crate::models::paper::{Attachment as PaperAttachment, Label as PaperLabel};
+use crate::models::{Author, AuthorWithOrder, Category, Label, Paper};
+use crate::papers::exporter::bibtex::paper_to_bibtex;
+use crate::papers::exporter::csl::citation_key;
+use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository, PaperRepository};
+use crate::service::library_archive_service::collect_files;
+use crate::sys::error::{AppError, Result};
+
+pub const LIBRARY_JSON_FILE: &str = "library.json";
+pub const BIBTEX_FILE: &str = "bibtex.bib";
+pub const FILES_DIR: &str = "files";
+
+/// `library.json` layout written by this version
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A paper with its ordered authors, labels and attachments filled in
+#[derive(Debug, Serialize)]
+pub struct LibraryExportPaper {
+    #[serde(flatten)]
+    pub paper: Paper,
+    /// The category the paper was filed under first
+    pub category_id: Option<i64>,
+    /// Every category of the paper, `category_id` first
+    pub category_ids: Vec<i64>,
+}
+
+/// Content of `library.json`
+#[derive(Debug, Serialize)]
+pub struct LibraryExport {
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub papers: Vec<LibraryExportPaper>,
+    pub authors: Vec<Author>,
+    pub labels: Vec<Label>,
+    pub categories: Vec<Category>,
+}
+
+/// Progress event DTO for library zip export
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportProgress {
+    pub total_files: usize,
+    pub processed: usize,
+    pub current_file: String,
+}
+
+/// Everything that goes into the zip, gathered from the database up front
+#[derive(Debug)]
+pub struct LibraryExportContent {
+    pub library: LibraryExport,
+    pub bibtex: String,
+    /// Attachment files as `(archive path, file path)`
+    pub attachments: Vec<(String, PathBuf)>,
+}
+
+/// Load every non-trashed paper with its relations. Attachment files under
+/// `files_dir` are listed when it is given.
+pub async fn collect_library(
+    db: &DatabaseConnection,
+    files_dir: Option<&Path>,
+) -> Result<LibraryExportContent> {
+    let papers = PaperRepository::find_all(db).await?;
+    let paper_ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let mut authors_map = AuthorRepository::get_paper_authors_batch(db, &paper_ids).await?;
+    let mut labels_map = LabelRepository::get_paper_labels_batch(db, &paper_ids).await?;
+    let mut attachments_map = PaperRepository::get_attachments_batch(db, &paper_ids).await?;
+    let category_ids = PaperRepository::get_category_ids_batch(db, &paper_ids).await?;
+
+    let mut used_keys = HashSet::new();
+    let mut bibtex = String::new();
+    let mut attachments = Vec::new();
+    let mut library_papers = Vec::with_capacity(papers.len());
+
+    for mut paper in papers {
+        let authors = authors_map.remove(&paper.id).unwrap_or_default();
+        let key = citation_key(&paper, &authors, &mut used_keys);
+        if !bibtex.is_empty() {
+            bibtex.push('\n');
+        }
+        bibtex.push_str(&paper_to_bibtex(&paper, &authors, &key));
+
+        if let (Some(files_dir), Some(hash)) = (files_dir, paper.attachment_path.as_deref()) {
+            let dir = files_dir.join(hash);
+            let prefix = format!("{}/{}", FILES_DIR, hash);
+            attachments.extend(collect_files(&dir, &prefix).map_err(|e| {
+                AppError::file_system(dir.to_string_lossy().to_string(), e.to_string())
+            })?);
+        }
+
+        paper.authors = authors
+            .into_iter()
+            .enumerate()
+            .map(|(order, a)| AuthorWithOrder {
+                id: a.id,
+                name: a.full_name(),
+                affiliation: a.affiliation,
+                email: a.email,
+                author_order: order as i32,
+                is_corresponding: false,
+            })
+            .collect();
+        paper.labels = labels_map
+            .remove(&paper.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|l| PaperLabel {
+                id: l.id,
+                name: l.name,
+                color: l.color,
+                document_count: l.document_count,
+                created_at: l.created_at,
+            })
+            .collect();
+        paper.attachments = attachments_map
+            .remove(&paper.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|a| PaperAttachment {
+                id: a.id,
+                paper_id: a.paper_id,
+                file_name: a.file_name,
+                file_type: a.file_type,
+                file_size: a.file_size,
+                created_at: a.created_at,
+            })
+            .collect();
+        let paper_category_ids = category_ids.get(&paper.id).cloned().unwrap_or_default();
+        library_papers.push(LibraryExportPaper {
+            category_id: paper_category_ids.first().copied(),
+            category_ids: paper_category_ids,
+            paper,
+        });
+    }
+
+    Ok(LibraryExportContent {
+        library: LibraryExport {
+            format_version: FORMAT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            papers: library_papers,
+            authors: AuthorRepository::find_all(db).await?,
+            labels: LabelRepository::find_all(db).await?,
+            categories: CategoryRepository::find_all(db).await?,
+        },
+        bibtex,
+        attachments,
+    })
+}
+
+fn zip_error(path: &Path, e: impl std::fmt::Display) -> AppError {
+    AppError::file_system(path.to_string_lossy().to_string(), e.to_string())
+}
+
+/// Write `content` as a zip at `target` and return its size in bytes
+pub fn write_library_zip<F>(
+    content: &LibraryExportContent,
+    target: &Path,
+    mut on_progress: F,
+) -> Result<u64>
+where
+    F: FnMut(&ExportProgress),
+{
+    let part_path = PathBuf::from(format!("{}.part", target.to_string_lossy()));
+    if let Err(e) = write_zip(&part_path, content, &mut on_progress) {
+        let _ = std::fs::remove_file(&part_path);
+        return Err(e);
+    }
+    std::fs::rename(&part_path, target).map_err(|e| zip_error(target, e))?;
+
+    let size_bytes = std::fs::metadata(target)
+        .map_err(|e| zip_error(target, e))?
+        .len();
+    info!(
+        "Exported {} papers and {} attachment files to {:?}",
+        content.library.papers.len(),
+        content.attachments.len(),
+        target
+    );
+    Ok(size_bytes)
+}
+
+fn write_zip<F>(path: &Path, content: &LibraryExportContent, on_progress: &mut F) -> Result<()>
+where
+    F: FnMut(&ExportProgress),
+{
+    let file = File::create(path).map_err(|e| zip_error(path, e))?;
+    let mut zip = ZipWriter::new(BufWriter::new(file));
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+
+    let total_files = content.attachments.len() + 2;
+    let mut progress = |processed: usize, current_file: &str| {
+        on_progress(&ExportProgress {
+            total_files,
+            processed,
+            current_file: current_file.to_string(),
+        })
+    };
+
+    progress(0, LIBRARY_JSON_FILE);
+    zip.start_file(LIBRARY_JSON_FILE, options)
+        .map_err(|e| zip_error(path, e))?;
+    serde_json::to_writer_pretty(&mut zip, &content.library)
+        .map_err(|e| AppError::generic(format!("Failed to write {}: {}", LIBRARY_JSON_FILE, e)))?;
+
+    progress(1, BIBTEX_FILE);
+    zip.start_file(BIBTEX_FILE, options)
+        .map_err(|e| zip_error(path, e))?;
+    zip.write_all(content.bibtex.as_bytes())
+        .map_err(|e| zip_error(path, e))?;
+
+    for (index, (archive_path, source)) in content.attachments.iter().enumerate() {
+        progress(index + 2, archive_path);
+        zip.start_file(archive_path.as_str(), options)
+            .map_err(|e| zip_error(path, e))?;
+        let mut reader = BufReader::new(File::open(source).map_err(|e| zip_error(source, e))?);
+        std::io::copy(&mut reader, &mut zip).map_err(|e| zip_error(source, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| zip_error(path, e))?
+        .flush()
+        .map_err(|e| zip_error(path, e))?;
+    progress(total_files, "");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use zip::ZipArchive;
+
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_export_zip_with_attachments() {
+        let db = test_db().await;
+        PaperFixture::new("Attention Is All You Need")
+            .with_authors(&["Ashish Vaswani"])
+            .with_label("nlp")
+            .with_category("Transformers")
+            .with_attachment_path("a1b2c3")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Trashed").deleted().insert(&db).await;
+
+        let files = tempfile::tempdir().unwrap();
+        let hash = "a1b2c3";
+        std::fs::create_dir_all(files.path().join(hash)).unwrap();
+        std::fs::write(files.path().join(hash).join("paper.pdf"), b"%PDF-1.7").unwrap();
+        let out = tempfile::tempdir().unwrap();
+        let target = out.path().join("library.zip");
+
+        let content = collect_library(&db, Some(files.path())).await.unwrap();
+        let mut events = Vec::new();
+        let size = write_library_zip(&content, &target, |p| events.push(p.clone())).unwrap();
+
+        assert_eq!(size, std::fs::metadata(&target).unwrap().len());
+        assert!(!out.path().join("library.zip.part").exists());
+        assert_eq!(events.last().unwrap().processed, 3);
+
+        let mut zip = ZipArchive::new(File::open(&target).unwrap()).unwrap();
+        let mut json = String::new();
+        zip.by_name(LIBRARY_JSON_FILE)
+            .unwrap()
+            .read_to_string(&mut json)
+            .unwrap();
+        let library: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(library["papers"].as_array().unwrap().len(), 1);
+        assert_eq!(library["papers"][0]["authors"][0]["name"], "Ashish Vaswani");
+        assert_eq!(library["papers"][0]["labels"][0]["name"], "nlp");
+        assert!(library["papers"][0]["category_id"].is_i64());
+
+        let mut bibtex = String::new();
+        zip.by_name(BIBTEX_FILE)
+            .unwrap()
+            .read_to_string(&mut bibtex)
+            .unwrap();
+        assert!(bibtex.starts_with("@misc{vaswani"));
+        assert!(zip
+            .by_name(&format!("{}/{}/paper.pdf", FILES_DIR, hash))
+            .is_ok());
+    }
+}
@@ -0,0 +1,671 @@
+//! Importing a portable library export
+//!
+//! The counterpart of `library_export_service`: reads the zip written by
+//! `export_library_as_zip` back into the library. The zip is extracted into
+//! a staging directory and `library.json` is read from there. Categories and
+//! labels are matched by name and created when missing, the same way a merge
+//! restore does it, and authors are matched or created by name and ORCID iD.
+//!
+//! Papers match a paper of the library by DOI, then by normalised title, and
+//! the merge strategy decides what happens to a match. Attachment files are
+//! copied into the paper's directory under the files directory. Each paper is
+//! imported on its own, so one failure is reported and the others continue.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use zip::ZipArchive;
+
+use crate::database::entities::paper as paper_entity;
+use crate::models::{Author, AuthorDetails, Category, CreatePaper, Label, Paper, UpdatePaper};
+use crate::repository::{AuthorRepository, LabelRepository, PaperAuthorEntry, PaperRepository};
+use crate::service::activity_service::{self, ACTION_IMPORTED, ENTITY_PAPER};
+use crate::service::attachment_service::{file_size_on_disk, new_attachment_dir};
+use crate::service::library_archive_service::collect_files;
+use crate::service::library_export_service::{FILES_DIR, FORMAT_VERSION, LIBRARY_JSON_FILE};
+use crate::service::library_restore_service::{
+    add_missing_categories, add_missing_labels, doi_key, title_key, MergeReport,
+};
+use crate::sys::error::{AppError, Result};
+
+/// What to do with an imported paper that is already in the library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// Keep the library's paper and ignore the imported one
+    SkipExisting,
+    /// Replace the library paper's metadata, authors, labels and category
+    /// with the imported ones and add its missing attachments
+    OverwriteExisting,
+    /// Import the paper as a new one next to the existing paper
+    CreateDuplicate,
+}
+
+/// A paper of `library.json`
+#[derive(Debug, Deserialize)]
+struct ImportedPaper {
+    #[serde(flatten)]
+    paper: Paper,
+    #[serde(default)]
+    category_id: Option<i64>,
+    /// Every category of the paper; absent from older exports
+    #[serde(default)]
+    category_ids: Vec<i64>,
+}
+
+/// Content of `library.json`
+#[derive(Debug, Deserialize)]
+struct LibraryImport {
+    format_version: u32,
+    papers: Vec<ImportedPaper>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    #[serde(default)]
+    labels: Vec<Label>,
+    #[serde(default)]
+    categories: Vec<Category>,
+}
+
+/// Progress event DTO for library zip import
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub total_papers: usize,
+    pub processed: usize,
+    pub current_title: String,
+}
+
+/// Outcome of a library zip import
+#[derive(Debug, Default)]
+pub struct LibraryImportReport {
+    pub papers_imported: usize,
+    pub papers_skipped: usize,
+    pub papers_updated: usize,
+    /// Categories, labels, papers and files that could not be imported
+    pub errors: Vec<String>,
+}
+
+fn fs_error(path: &Path, e: impl std::fmt::Display) -> AppError {
+    AppError::file_system(path.to_string_lossy().to_string(), e.to_string())
+}
+
+/// Extract the zip at `zip_path` into `target`. Fails when the zip holds no
+/// `library.json`.
+pub fn extract_library_zip(zip_path: &Path, target: &Path) -> Result<()> {
+    let file = File::open(zip_path).map_err(|e| fs_error(zip_path, e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| AppError::validation("zip_path", format!("Not a readable zip file: {}", e)))?;
+    if archive.index_for_name(LIBRARY_JSON_FILE).is_none() {
+        return Err(AppError::validation(
+            "zip_path",
+            format!("The zip contains no {}", LIBRARY_JSON_FILE),
+        ));
+    }
+    archive.extract(target).map_err(|e| fs_error(target, e))
+}
+
+fn read_library(dir: &Path) -> Result<LibraryImport> {
+    let path = dir.join(LIBRARY_JSON_FILE);
+    let json = std::fs::read_to_string(&path).map_err(|e| fs_error(&path, e))?;
+    let library: LibraryImport = serde_json::from_str(&json).map_err(|e| {
+        AppError::validation("zip_path", format!("Invalid {}: {}", LIBRARY_JSON_FILE, e))
+    })?;
+    if library.format_version > FORMAT_VERSION {
+        return Err(AppError::validation(
+            "zip_path",
+            format!(
+                "The export was written by a newer version of the app (format {})",
+                library.format_version
+            ),
+        ));
+    }
+    Ok(library)
+}
+
+/// Import the library extracted into `dir`. Attachment files are copied
+/// into `files_dir`.
+pub async fn import_library<F>(
+    db: &DatabaseConnection,
+    dir: &Path,
+    files_dir: &Path,
+    strategy: MergeStrategy,
+    mut on_progress: F,
+) -> Result<LibraryImportReport>
+where
+    F: FnMut(&ImportProgress),
+{
+    let library = read_library(dir)?;
+    let mut report = LibraryImportReport::default();
+
+    let mut merged = MergeReport::default();
+    let category_ids = add_missing_categories(db, library.categories, &mut merged).await?;
+    let label_ids = add_missing_labels(db, library.labels, &mut merged).await?;
+    report.errors.extend(merged.failed.into_iter().map(|item| {
+        format!(
+            "Failed to import {} '{}': {}",
+            item.entity_type, item.name, item.reason
+        )
+    }));
+
+    // Papers in the trash count too, so an import does not bring them back
+    let existing = paper_entity::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to query papers: {}", e)))?;
+    let mut dois = HashMap::new();
+    let mut titles = HashMap::new();
+    let mut used_dirs = HashSet::new();
+    for paper in existing {
+        if let Some(doi) = doi_key(paper.doi.as_deref()) {
+            dois.insert(doi, paper.id);
+        }
+        titles.insert(title_key(&paper.title), paper.id);
+        used_dirs.extend(paper.attachment_path);
+    }
+
+    let mut import = PaperImport {
+        category_ids,
+        label_ids,
+        authors: library.authors.into_iter().map(|a| (a.id, a)).collect(),
+        source_files: dir.join(FILES_DIR),
+        files_dir: files_dir.to_path_buf(),
+        used_dirs,
+    };
+
+    let total_papers = library.papers.len();
+    for (processed, imported) in library.papers.iter().enumerate() {
+        let paper = &imported.paper;
+        on_progress(&ImportProgress {
+            total_papers,
+            processed,
+            current_title: paper.title.clone(),
+        });
+
+        let doi = doi_key(paper.doi.as_deref());
+        let title = title_key(&paper.title);
+        let found = doi
+            .as_ref()
+            .and_then(|d| dois.get(d))
+            .or_else(|| titles.get(&title).filter(|_| !title.is_empty()))
+            .copied();
+
+        let result = match (found, strategy) {
+            (Some(_), MergeStrategy::SkipExisting) => {
+                report.papers_skipped += 1;
+                continue;
+            }
+            (Some(paper_id), MergeStrategy::OverwriteExisting) => {
+                import.overwrite(db, paper_id, imported, &mut report).await
+            }
+            _ => import.create(db, imported, &mut report).await,
+        };
+        match (result, found) {
+            (Ok(_), Some(_)) if strategy == MergeStrategy::OverwriteExisting => {
+                report.papers_updated += 1;
+            }
+            (Ok(paper_id), _) => {
+                report.papers_imported += 1;
+                if let Some(doi) = doi {
+                    dois.entry(doi).or_insert(paper_id);
+                }
+                titles.entry(title).or_insert(paper_id);
+            }
+            (Err(e), _) => {
+                warn!("Failed to import paper '{}': {}", paper.title, e);
+                report
+                    .errors
+                    .push(format!("Failed to import paper '{}': {}", paper.title, e));
+            }
+        }
+    }
+
+    on_progress(&ImportProgress {
+        total_papers,
+        processed: total_papers,
+        current_title: String::new(),
+    });
+    info!(
+        "Imported library from {:?}: {} papers imported, {} updated, {} skipped, {} errors",
+        dir,
+        report.papers_imported,
+        report.papers_updated,
+        report.papers_skipped,
+        report.errors.len()
+    );
+    Ok(report)
+}
+
+/// Imports papers of one export into the library
+struct PaperImport {
+    /// Exported category id to library category id
+    category_ids: HashMap<i64, i64>,
+    /// Exported label id to library label id
+    label_ids: HashMap<i64, i64>,
+    /// Exported authors by their exported id
+    authors: HashMap<i64, Author>,
+    /// `files/` of the extracted export
+    source_files: PathBuf,
+    files_dir: PathBuf,
+    /// Attachment directories already taken by a paper
+    used_dirs: HashSet<String>,
+}
+
+impl PaperImport {
+    /// Create `imported` as a new paper and return its id
+    async fn create(
+        &mut self,
+        db: &DatabaseConnection,
+        imported: &ImportedPaper,
+        report: &mut LibraryImportReport,
+    ) -> Result<i64> {
+        let paper = &imported.paper;
+        let attachment_dir = self.claim_dir(paper);
+        let created = PaperRepository::create(
+            db,
+            CreatePaper {
+                title: paper.title.clone(),
+                abstract_text: paper.abstract_text.clone(),
+                doi: paper.doi.clone(),
+                publication_year: paper.publication_year,
+                publication_date: paper.publication_date.clone(),
+                journal_name: paper.journal_name.clone(),
+                conference_name: paper.conference_name.clone(),
+                volume: paper.volume.clone(),
+                issue: paper.issue.clone(),
+                pages: paper.pages.clone(),
+                url: paper.url.clone(),
+                attachment_path: Some(attachment_dir.clone()),
+                publisher: paper.publisher.clone(),
+                issn: paper.issn.clone(),
+                language: paper.language.clone(),
+                isbn: paper.isbn.clone(),
+            },
+        )
+        .await?;
+        let update = UpdatePaper {
+            read_status: Some(paper.read_status.clone()),
+            notes: paper.notes.clone(),
+            ..Default::default()
+        };
+        PaperRepository::update(db, created.id, update).await?;
+        activity_service::record(db, ENTITY_PAPER, created.id, ACTION_IMPORTED).await;
+
+        self.link(db, created.id, imported).await?;
+        self.copy_attachments(db, created.id, &attachment_dir, paper, report)
+            .await?;
+        Ok(created.id)
+    }
+
+    /// Replace the metadata and relations of the library paper `paper_id`
+    /// with `imported`. Fields the export leaves empty keep their value.
+    async fn overwrite(
+        &mut self,
+        db: &DatabaseConnection,
+        paper_id: i64,
+        imported: &ImportedPaper,
+        report: &mut LibraryImportReport,
+    ) -> Result<i64> {
+        let paper = &imported.paper;
+        let update = UpdatePaper {
+            title: Some(paper.title.clone()),
+            abstract_text: paper.abstract_text.clone(),
+            doi: paper.doi.clone(),
+            publication_year: paper.publication_year,
+            publication_date: paper.publication_date.clone(),
+            journal_name: paper.journal_name.clone(),
+            conference_name: paper.conference_name.clone(),
+            volume: paper.volume.clone(),
+            issue: paper.issue.clone(),
+            pages: paper.pages.clone(),
+            url: paper.url.clone(),
+            read_status: Some(paper.read_status.clone()),
+            notes: paper.notes.clone(),
+            attachment_path: None,
+            publisher: paper.publisher.clone(),
+            issn: paper.issn.clone(),
+            language: paper.language.clone(),
+        };
+        let updated = PaperRepository::update(db, paper_id, update).await?;
+
+        let attachment_dir = match updated.attachment_path {
+            Some(dir) => dir,
+            None => {
+                let dir = self.claim_dir(paper);
+                PaperRepository::update_attachment_path(db, paper_id, &dir).await?;
+                dir
+            }
+        };
+
+        let current = LabelRepository::get_paper_labels(db, paper_id).await?;
+        let labels = self.library_label_ids(paper);
+        for label in current.iter().filter(|l| !labels.contains(&l.id)) {
+            LabelRepository::remove_from_paper(db, paper_id, label.id).await?;
+        }
+
+        self.link(db, paper_id, imported).await?;
+        self.copy_attachments(db, paper_id, &attachment_dir, paper, report)
+            .await?;
+        Ok(paper_id)
+    }
+
+    /// Pick an attachment directory no paper uses yet, preferring the one
+    /// the paper had in the exported library
+    fn claim_dir(&mut self, paper: &Paper) -> String {
+        let mut candidate = exported_attachment_dir(paper)
+            .map(str::to_string)
+            .unwrap_or_else(new_attachment_dir);
+        while self.used_dirs.contains(&candidate) {
+            candidate = new_attachment_dir();
+        }
+        self.used_dirs.insert(candidate.clone());
+        candidate
+    }
+
+    fn library_label_ids(&self, paper: &Paper) -> Vec<i64> {
+        let mut ids = Vec::new();
+        for label in &paper.labels {
+            if let Some(&id) = self.label_ids.get(&label.id) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+        ids
+    }
+
+    /// Set the authors and category of `paper_id` and add its labels
+    async fn link(
+        &self,
+        db: &DatabaseConnection,
+        paper_id: i64,
+        imported: &ImportedPaper,
+    ) -> Result<()> {
+        let mut ordered: Vec<_> = imported.paper.authors.iter().collect();
+        ordered.sort_by_key(|a| a.author_order);
+        let mut entries: Vec<PaperAuthorEntry> = Vec::with_capacity(ordered.len());
+        for (order, entry) in ordered.into_iter().enumerate() {
+            let author = match self.authors.get(&entry.id) {
+                Some(author) => {
+                    let details = AuthorDetails {
+                        email: author.email.clone(),
+                        affiliation: author.affiliation.clone(),
+                        orcid: author.orcid.clone(),
+                    };
+                    AuthorRepository::create_or_find_from_parts(
+                        db,
+                        Some(&author.first_name),
+                        author.last_name.as_deref(),
+                        &details,
+                    )
+                    .await?
+                }
+                None => {
+                    let details = AuthorDetails {
+                        email: entry.email.clone(),
+                        affiliation: entry.affiliation.clone(),
+                        orcid: None,
+                    };
+                    AuthorRepository::create_or_find(db, &entry.name, &details).await?
+                }
+            };
+            if entries.iter().all(|e| e.author_id != author.id) {
+                entries.push(PaperAuthorEntry {
+                    author_id: author.id,
+                    order: order as i32,
+                    is_corresponding: entry.is_corresponding,
+                });
+            }
+        }
+        AuthorRepository::set_paper_authors(db, paper_id, &entries).await?;
+
+        let mut category_ids = imported
+            .category_id
+            .into_iter()
+            .chain(imported.category_ids.iter().copied())
+            .filter_map(|id| self.category_ids.get(&id).copied());
+        PaperRepository::set_category(db, paper_id, category_ids.next()).await?;
+        for category_id in category_ids {
+            PaperRepository::add_category(db, paper_id, category_id).await?;
+        }
+
+        for label_id in self.library_label_ids(&imported.paper) {
+            LabelRepository::add_to_paper(db, paper_id, label_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the exported files of `paper` into `attachment_dir` and record
+    /// the attachments `paper_id` does not have yet. Files already present
+    /// are kept.
+    async fn copy_attachments(
+        &self,
+        db: &DatabaseConnection,
+        paper_id: i64,
+        attachment_dir: &str,
+        paper: &Paper,
+        report: &mut LibraryImportReport,
+    ) -> Result<()> {
+        if paper.attachment_path.is_none() {
+            return Ok(());
+        }
+        let Some(exported_dir) = exported_attachment_dir(paper) else {
+            report.errors.push(format!(
+                "Skipped the files of '{}': invalid attachment path",
+                paper.title
+            ));
+            return Ok(());
+        };
+        let source = self.source_files.join(exported_dir);
+        let target = self.files_dir.join(attachment_dir);
+        for (_, file) in collect_files(&source, FILES_DIR).map_err(|e| fs_error(&source, e))? {
+            let Ok(relative) = file.strip_prefix(&source) else {
+                continue;
+            };
+            let destination = target.join(relative);
+            if destination.exists() {
+                continue;
+            }
+            let copied = destination
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::copy(&file, &destination));
+            if let Err(e) = copied {
+                report.errors.push(format!(
+                    "Failed to copy '{}' of '{}': {}",
+                    relative.to_string_lossy(),
+                    paper.title,
+                    e
+                ));
+            }
+        }
+
+        let recorded: HashSet<String> = PaperRepository::get_attachments(db, paper_id)
+            .await?
+            .into_iter()
+            .filter_map(|a| a.file_name)
+            .collect();
+        for attachment in &paper.attachments {
+            let Some(file_name) = &attachment.file_name else {
+                continue;
+            };
+            if !is_single_component(file_name)
+                || recorded.contains(file_name)
+                || !target.join(file_name).is_file()
+            {
+                continue;
+            }
+            PaperRepository::add_attachment(
+                db,
+                paper_id,
+                Some(file_name.clone()),
+                attachment.file_type.clone(),
+                file_size_on_disk(&target.join(file_name)).or(attachment.file_size),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `path` names one entry, so joining it stays inside the
+/// directory it is joined to
+fn is_single_component(path: &str) -> bool {
+    let mut components = Path::new(path).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+/// The exported paper's attachment directory, unless library.json names
+/// something that could point outside the files directory
+fn exported_attachment_dir(paper: &Paper) -> Option<&str> {
+    paper
+        .attachment_path
+        .as_deref()
+        .filter(|dir| is_single_component(dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::library_export_service::{collect_library, write_library_zip};
+    use crate::testing::{test_db, PaperFixture};
+
+    async fn export_zip(root: &Path) -> PathBuf {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Attention Is All You Need")
+            .with_doi("10.1000/attention")
+            .with_authors(&["Ashish Vaswani"])
+            .with_label("nlp")
+            .with_category("Transformers")
+            .with_attachment_path("a1b2c3")
+            .insert(&db)
+            .await;
+        PaperRepository::add_attachment(
+            &db,
+            paper.id,
+            Some("paper.pdf".to_string()),
+            Some("pdf".to_string()),
+            Some(8),
+        )
+        .await
+        .unwrap();
+        PaperFixture::new("Deep Residual Learning")
+            .insert(&db)
+            .await;
+
+        let files = root.join("files");
+        std::fs::create_dir_all(files.join("a1b2c3")).unwrap();
+        std::fs::write(files.join("a1b2c3").join("paper.pdf"), b"%PDF-1.7").unwrap();
+        let target = root.join("library.zip");
+        let content = collect_library(&db, Some(&files)).await.unwrap();
+        write_library_zip(&content, &target, |_| {}).unwrap();
+        target
+    }
+
+    async fn import(
+        db: &DatabaseConnection,
+        root: &Path,
+        zip: &Path,
+        strategy: MergeStrategy,
+    ) -> LibraryImportReport {
+        let staging = tempfile::tempdir().unwrap();
+        extract_library_zip(zip, staging.path()).unwrap();
+        import_library(db, staging.path(), &root.join("library"), strategy, |_| {})
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_applies_merge_strategy() {
+        let root = tempfile::tempdir().unwrap();
+        let zip = export_zip(root.path()).await;
+        let db = test_db().await;
+        let existing = PaperFixture::new("Old title")
+            .with_doi("10.1000/ATTENTION")
+            .insert(&db)
+            .await;
+
+        let report = import(&db, root.path(), &zip, MergeStrategy::SkipExisting).await;
+        assert_eq!((report.papers_imported, report.papers_skipped), (1, 1));
+        assert!(report.errors.is_empty());
+
+        let report = import(&db, root.path(), &zip, MergeStrategy::OverwriteExisting).await;
+        assert_eq!((report.papers_updated, report.papers_skipped), (2, 0));
+        let updated = PaperRepository::find_by_id(&db, existing.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.title, "Attention Is All You Need");
+        let labels = LabelRepository::get_paper_labels(&db, existing.id)
+            .await
+            .unwrap();
+        assert_eq!(labels[0].name, "nlp");
+        let attachments = PaperRepository::get_attachments(&db, existing.id)
+            .await
+            .unwrap();
+        assert_eq!(attachments.len(), 1);
+        let dir = updated.attachment_path.unwrap();
+        assert!(root
+            .path()
+            .join("library")
+            .join(dir)
+            .join("paper.pdf")
+            .is_file());
+
+        let report = import(&db, root.path(), &zip, MergeStrategy::CreateDuplicate).await;
+        assert_eq!(report.papers_imported, 2);
+        assert_eq!(PaperRepository::find_all(&db).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_path_outside_files_dir_is_not_used() {
+        let root = tempfile::tempdir().unwrap();
+        let zip = export_zip(root.path()).await;
+        let staging = tempfile::tempdir().unwrap();
+        extract_library_zip(&zip, staging.path()).unwrap();
+        let library_json = staging.path().join(LIBRARY_JSON_FILE);
+        let content = std::fs::read_to_string(&library_json)
+            .unwrap()
+            .replace("\"a1b2c3\"", "\"../../escape\"");
+        std::fs::write(&library_json, content).unwrap();
+
+        let db = test_db().await;
+        let report = import_library(
+            &db,
+            staging.path(),
+            &root.path().join("library"),
+            MergeStrategy::SkipExisting,
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.papers_imported, 2);
+        assert_eq!(report.errors.len(), 1);
+        for paper in PaperRepository::find_all(&db).await.unwrap() {
+            let dir = paper.attachment_path.unwrap();
+            assert!(is_single_component(&dir), "{}", dir);
+        }
+        assert!(!root.path().join("escape").exists());
+    }
+
+    #[test]
+    fn test_zip_without_library_json_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let zip_path = dir.path().join("other.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("notes.txt", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        assert!(matches!(
+            extract_library_zip(&zip_path, &dir.path().join("out")),
+            Err(AppError::ValidationError { .. })
+        ));
+    }
+}
@@ -0,0 +1,919 @@
+//! Restoring the library from a library archive
+//!
+//! A replace restore swaps the whole library for the archive. The archive is
+//! first extracted next to the live directories (`config.restoring`,
+//! `files.restoring` and a staged database) and checked against the
+//! manifest; only then are the directories swapped with renames. The
+//! replaced directories are kept as `config.pre-restore` and
+//! `files.pre-restore`, and if any rename fails the ones already done are
+//! undone. The database is swapped on the next start by
+//! `backup_service::apply_pending_restore`, which keeps the old file as well.
+//!
+//! A merge restore opens the archived database on the side and adds the
+//! categories, labels and papers the library does not have yet. Papers are
+//! matched by DOI, then by title. Each paper is copied with its authors,
+//! labels, categories and attachments in its own transaction, so one failure
+//! does not affect the others, and every skipped or failed entity is
+//! reported.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use tracing::{error, info, warn};
+use zip::ZipArchive;
+
+use crate::database::entities::{
+    attachment, author, category, label, paper, paper_author, paper_category, paper_label,
+};
+use crate::models::{AuthorDetails, Category, CreateCategory, CreateLabel, Label};
+use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository};
+use crate::service::backup_service::{DATABASE_FILE, PENDING_RESTORE_FILE};
+use crate::service::library_archive_service::{
+    archive_relative_path, extract_entry, open_library_archive, ArchiveEntry, ArchiveManifest,
+    ARCHIVE_CONFIG_DIR, ARCHIVE_DATA_DIR, ARCHIVE_FILES_DIR, PROGRESS_INTERVAL,
+};
+use crate::sys::dirs::{AppDirs, MigrationPhase, MigrationStatus};
+use crate::sys::error::{AppError, Result};
+
+/// Suffix of the directories an archive is extracted into before the swap
+const STAGING_SUFFIX: &str = "restoring";
+
+/// Suffix the replaced directories are kept under
+const PREVIOUS_SUFFIX: &str = "pre-restore";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreMode {
+    /// Replace the whole library with the archive
+    Replace,
+    /// Add what the library does not have yet
+    Merge,
+}
+
+/// An entity a merge skipped or failed to copy
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RestoreItem {
+    /// `category`, `label`, `paper` or `attachment`
+    pub entity_type: String,
+    /// Name, title or file name as it appears in the archive
+    pub name: String,
+    pub reason: String,
+}
+
+impl RestoreItem {
+    fn new(entity_type: &str, name: &str, reason: impl Into<String>) -> Self {
+        Self {
+            entity_type: entity_type.to_string(),
+            name: name.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MergeReport {
+    pub categories_added: usize,
+    pub labels_added: usize,
+    pub papers_added: usize,
+    pub files_copied: usize,
+    pub skipped: Vec<RestoreItem>,
+    pub failed: Vec<RestoreItem>,
+}
+
+/// Refuse archives written with a newer schema than this version knows.
+/// Older archives are brought up to date by the migrations.
+pub fn ensure_compatible_schema(manifest: &ArchiveManifest, current: &str) -> Result<()> {
+    // Migration names start with their date, so they sort chronologically
+    if manifest.schema_version.as_str() > current {
+        return Err(AppError::validation(
+            "archive_path",
+            format!(
+                "Archive was written by a newer version of the app (schema {})",
+                manifest.schema_version
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn database_entry(manifest: &ArchiveManifest) -> Result<&ArchiveEntry> {
+    let path = format!("{}/{}", ARCHIVE_DATA_DIR, DATABASE_FILE);
+    manifest
+        .entries
+        .iter()
+        .find(|e| e.path == path)
+        .ok_or_else(|| AppError::validation("archive_path", "The archive contains no database"))
+}
+
+/// Extract the archived database to `target`
+pub fn extract_database(
+    archive_path: &Path,
+    manifest: &ArchiveManifest,
+    target: &Path,
+) -> Result<()> {
+    let entry = database_entry(manifest)?;
+    let mut archive = open_library_archive(archive_path)?;
+    extract_entry(&mut archive, entry, target)
+}
+
+fn fs_error(path: &Path, e: std::io::Error) -> AppError {
+    AppError::file_system(path.to_string_lossy().to_string(), e.to_string())
+}
+
+/// `{dir}.{suffix}`, next to `dir`
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}.{}", dir.to_string_lossy(), suffix))
+}
+
+/// Replace the library with the archive. The archive must have been
+/// validated. The new database takes effect on the next start.
+pub fn replace_library<F>(
+    archive_path: &Path,
+    manifest: &ArchiveManifest,
+    app_dirs: &AppDirs,
+    mut on_progress: F,
+) -> Result<()>
+where
+    F: FnMut(&MigrationStatus),
+{
+    let data_dir = Path::new(&app_dirs.data);
+    let live_dirs = [Path::new(&app_dirs.config), Path::new(&app_dirs.files)];
+    let staged_database = data_dir.join(format!("{}.{}", DATABASE_FILE, STAGING_SUFFIX));
+    let staged_dirs = live_dirs.map(|dir| sibling(dir, STAGING_SUFFIX));
+    let remove_staging = || {
+        let _ = std::fs::remove_file(&staged_database);
+        for dir in &staged_dirs {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    };
+
+    // Leftovers of an earlier attempt
+    remove_staging();
+
+    let total_files = manifest.entries.len() as u32;
+    let status =
+        |phase: MigrationPhase, processed_files: u32, error: Option<String>| MigrationStatus {
+            phase,
+            current_file: None,
+            total_files,
+            processed_files,
+            error,
+        };
+
+    if let Err(e) = extract_library(
+        archive_path,
+        manifest,
+        &staged_database,
+        &staged_dirs,
+        &mut on_progress,
+    ) {
+        remove_staging();
+        on_progress(&status(MigrationPhase::Failed, 0, Some(e.to_string())));
+        return Err(e);
+    }
+
+    let mut renames = Vec::new();
+    let swapped = live_dirs
+        .iter()
+        .zip(&staged_dirs)
+        .try_for_each(|(live, staged)| {
+            let previous = sibling(live, PREVIOUS_SUFFIX);
+            if previous.exists() {
+                std::fs::remove_dir_all(&previous).map_err(|e| fs_error(&previous, e))?;
+            }
+            if live.exists() {
+                rename_logged(live, &previous, &mut renames)?;
+            }
+            rename_logged(staged, live, &mut renames)
+        })
+        .and_then(|_| {
+            rename_logged(
+                &staged_database,
+                &data_dir.join(PENDING_RESTORE_FILE),
+                &mut renames,
+            )
+        });
+
+    if let Err(e) = swapped {
+        error!("Library restore failed, rolling back: {}", e);
+        on_progress(&status(MigrationPhase::RollingBack, total_files, None));
+        for (from, to) in renames.iter().rev() {
+            if let Err(undo) = std::fs::rename(to, from) {
+                error!("Failed to move {:?} back to {:?}: {}", to, from, undo);
+            }
+        }
+        remove_staging();
+        on_progress(&status(
+            MigrationPhase::Failed,
+            total_files,
+            Some(e.to_string()),
+        ));
+        return Err(e);
+    }
+
+    on_progress(&status(MigrationPhase::Completed, total_files, None));
+    info!(
+        "Restored library from {:?}; the database is replaced on the next start",
+        archive_path
+    );
+    Ok(())
+}
+
+/// Rename `from` to `to` and record it so it can be undone
+fn rename_logged(from: &Path, to: &Path, renames: &mut Vec<(PathBuf, PathBuf)>) -> Result<()> {
+    std::fs::rename(from, to).map_err(|e| fs_error(from, e))?;
+    renames.push((from.to_path_buf(), to.to_path_buf()));
+    Ok(())
+}
+
+/// Extract every manifest entry into the staging locations, checking each
+/// against its checksum
+fn extract_library<F>(
+    archive_path: &Path,
+    manifest: &ArchiveManifest,
+    staged_database: &Path,
+    staged_dirs: &[PathBuf; 2],
+    on_progress: &mut F,
+) -> Result<()>
+where
+    F: FnMut(&MigrationStatus),
+{
+    let database = database_entry(manifest)?;
+    let mut archive = open_library_archive(archive_path)?;
+    let [staged_config, staged_files] = staged_dirs;
+    for dir in staged_dirs {
+        std::fs::create_dir_all(dir).map_err(|e| fs_error(dir, e))?;
+    }
+
+    let total_files = manifest.entries.len() as u32;
+    for (index, entry) in manifest.entries.iter().enumerate() {
+        let (phase, target) = if entry.path == database.path {
+            (
+                MigrationPhase::CopyingDatabase,
+                staged_database.to_path_buf(),
+            )
+        } else if let Some(relative) = archive_relative_path(&entry.path, ARCHIVE_CONFIG_DIR) {
+            (MigrationPhase::CopyingConfig, staged_config.join(relative))
+        } else if let Some(relative) = archive_relative_path(&entry.path, ARCHIVE_FILES_DIR) {
+            (MigrationPhase::CopyingFiles, staged_files.join(relative))
+        } else {
+            warn!("Skipping unexpected archive entry {}", entry.path);
+            continue;
+        };
+
+        let processed_files = index as u32;
+        if processed_files.is_multiple_of(PROGRESS_INTERVAL) {
+            on_progress(&MigrationStatus {
+                phase,
+                current_file: Some(entry.path.clone()),
+                total_files,
+                processed_files,
+                error: None,
+            });
+        }
+        extract_entry(&mut archive, entry, &target)?;
+    }
+    Ok(())
+}
+
+/// Lowercased alphanumeric words of a title, joined by single spaces
+pub(crate) fn title_key(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub(crate) fn doi_key(doi: Option<&str>) -> Option<String> {
+    doi.map(|d| d.trim().to_lowercase())
+        .filter(|d| !d.is_empty())
+}
+
+/// Add what `source`, the database of a validated archive, has and `db`
+/// does not. Attachment files are extracted from the archive at
+/// `archive_path` only when missing from the files directory.
+pub async fn merge_library<F>(
+    db: &DatabaseConnection,
+    source: &DatabaseConnection,
+    archive_path: &Path,
+    manifest: &ArchiveManifest,
+    app_dirs: &AppDirs,
+    mut on_progress: F,
+) -> Result<MergeReport>
+where
+    F: FnMut(&MigrationStatus),
+{
+    let mut report = MergeReport::default();
+    let category_ids = merge_categories(db, source, &mut report).await?;
+    let label_ids = merge_labels(db, source, &mut report).await?;
+
+    let mut dois = HashSet::new();
+    let mut titles = HashSet::new();
+    // Papers in the trash count too, so a merge does not bring them back
+    for existing in paper::Entity::find()
+        .all(db)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to query papers: {}", e)))?
+    {
+        dois.extend(doi_key(existing.doi.as_deref()));
+        titles.insert(title_key(&existing.title));
+    }
+
+    let papers = paper::Entity::find()
+        .order_by_asc(paper::Column::Id)
+        .all(source)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to query archived papers: {}", e)))?;
+    let entries: HashMap<&str, &ArchiveEntry> = manifest
+        .entries
+        .iter()
+        .map(|e| (e.path.as_str(), e))
+        .collect();
+    let mut archive = open_library_archive(archive_path)?;
+    let files_dir = Path::new(&app_dirs.files);
+    let mut touched_labels = HashSet::new();
+
+    let total_files = papers.len() as u32;
+    for (index, archived) in papers.iter().enumerate() {
+        let processed_files = index as u32;
+        if processed_files.is_multiple_of(PROGRESS_INTERVAL) {
+            on_progress(&MigrationStatus {
+                phase: MigrationPhase::CopyingDatabase,
+                current_file: Some(archived.title.clone()),
+                total_files,
+                processed_files,
+                error: None,
+            });
+        }
+
+        let doi = doi_key(archived.doi.as_deref());
+        let title = title_key(&archived.title);
+        let skip_reason = if archived.deleted_at.is_some() {
+            Some("In the trash of the archived library")
+        } else if doi.as_ref().is_some_and(|d| dois.contains(d)) {
+            Some("A paper with the same DOI is already in the library")
+        } else if !title.is_empty() && titles.contains(&title) {
+            Some("A paper with the same title is already in the library")
+        } else {
+            None
+        };
+        if let Some(reason) = skip_reason {
+            report
+                .skipped
+                .push(RestoreItem::new("paper", &archived.title, reason));
+            continue;
+        }
+
+        let copy = PaperCopy {
+            category_ids: &category_ids,
+            label_ids: &label_ids,
+            entries: &entries,
+            files_dir,
+        };
+        match copy
+            .run(db, source, archived, &mut archive, &mut report)
+            .await
+        {
+            Ok(labels) => {
+                report.papers_added += 1;
+                touched_labels.extend(labels);
+                dois.extend(doi);
+                titles.insert(title);
+            }
+            Err(e) => {
+                warn!("Failed to restore paper {}: {}", archived.id, e);
+                report
+                    .failed
+                    .push(RestoreItem::new("paper", &archived.title, e.to_string()));
+            }
+        }
+    }
+
+    for label_id in touched_labels {
+        if let Err(e) = LabelRepository::update_document_count(db, label_id).await {
+            warn!(
+                "Failed to update document count of label {}: {}",
+                label_id, e
+            );
+        }
+    }
+
+    on_progress(&MigrationStatus {
+        phase: MigrationPhase::Completed,
+        current_file: None,
+        total_files,
+        processed_files: total_files,
+        error: None,
+    });
+    info!(
+        "Merged library archive {:?}: {} papers added, {} skipped, {} failed",
+        archive_path,
+        report.papers_added,
+        report.skipped.len(),
+        report.failed.len()
+    );
+    Ok(report)
+}
+
+async fn merge_categories(
+    db: &DatabaseConnection,
+    source: &DatabaseConnection,
+    report: &mut MergeReport,
+) -> Result<HashMap<i64, i64>> {
+    let archived = category::Entity::find()
+        .all(source)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to query archived categories: {}", e)))?;
+    add_missing_categories(
+        db,
+        archived.into_iter().map(Category::from).collect(),
+        report,
+    )
+    .await
+}
+
+/// Map archived category ids to library category ids, creating the
+/// categories that are missing. Categories match by name under the same
+/// parent.
+pub(crate) async fn add_missing_categories(
+    db: &DatabaseConnection,
+    mut archived: Vec<Category>,
+    report: &mut MergeReport,
+) -> Result<HashMap<i64, i64>> {
+    let mut existing: HashMap<(Option<i64>, String), i64> = CategoryRepository::find_all(db)
+        .await?
+        .into_iter()
+        .map(|c| ((c.parent_id, c.name.to_lowercase()), c.id))
+        .collect();
+
+    // Parents must be created before their children
+    let parents: HashMap<i64, Option<i64>> = archived.iter().map(|c| (c.id, c.parent_id)).collect();
+    let depth = |mut id: i64| {
+        let mut depth = 0;
+        while let Some(Some(parent)) = parents.get(&id) {
+            depth += 1;
+            id = *parent;
+            if depth > parents.len() {
+                break;
+            }
+        }
+        depth
+    };
+    archived.sort_by_key(|c| (depth(c.id), c.sort_order, c.id));
+
+    let mut mapped = HashMap::new();
+    for archived in archived {
+        let parent_id = match archived.parent_id {
+            None => None,
+            Some(parent) => match mapped.get(&parent) {
+                Some(id) => Some(*id),
+                None => {
+                    report.failed.push(RestoreItem::new(
+                        "category",
+                        &archived.name,
+                        "Its parent category could not be restored",
+                    ));
+                    continue;
+                }
+            },
+        };
+
+        let key = (parent_id, archived.name.to_lowercase());
+        if let Some(id) = existing.get(&key) {
+            mapped.insert(archived.id, *id);
+            report.skipped.push(RestoreItem::new(
+                "category",
+                &archived.name,
+                "Already in the library",
+            ));
+            continue;
+        }
+
+        let create = CreateCategory {
+            name: archived.name.clone(),
+            parent_id,
+        };
+        match CategoryRepository::create(db, create).await {
+            Ok(created) => {
+                existing.insert(key, created.id);
+                mapped.insert(archived.id, created.id);
+                report.categories_added += 1;
+            }
+            Err(e) => {
+                report
+                    .failed
+                    .push(RestoreItem::new("category", &archived.name, e.to_string()));
+            }
+        }
+    }
+    Ok(mapped)
+}
+
+async fn merge_labels(
+    db: &DatabaseConnection,
+    source: &DatabaseConnection,
+    report: &mut MergeReport,
+) -> Result<HashMap<i64, i64>> {
+    let archived = label::Entity::find()
+        .order_by_asc(label::Column::Id)
+        .all(source)
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to query archived labels: {}", e)))?;
+    add_missing_labels(db, archived.into_iter().map(Label::from).collect(), report).await
+}
+
+/// Map archived label ids to library label ids, creating the labels that
+/// are missing. Labels match by name, ignoring case.
+pub(crate) async fn add_missing_labels(
+    db: &DatabaseConnection,
+    archived: Vec<Label>,
+    report: &mut MergeReport,
+) -> Result<HashMap<i64, i64>> {
+    let mut existing: HashMap<String, i64> = LabelRepository::find_all(db)
+        .await?
+        .into_iter()
+        .map(|l| (l.name.to_lowercase(), l.id))
+        .collect();
+
+    let mut mapped = HashMap::new();
+    for archived in archived {
+        let key = archived.name.to_lowercase();
+        if let Some(id) = existing.get(&key) {
+            mapped.insert(archived.id, *id);
+            report.skipped.push(RestoreItem::new(
+                "label",
+                &archived.name,
+                "Already in the library",
+            ));
+            continue;
+        }
+
+        let create = CreateLabel {
+            name: archived.name.clone(),
+            color: archived.color.clone(),
+            parent_id: None,
+        };
+        match LabelRepository::create(db, create).await {
+            Ok(created) => {
+                existing.insert(key, created.id);
+                mapped.insert(archived.id, created.id);
+                report.labels_added += 1;
+            }
+            Err(e) => {
+                report
+                    .failed
+                    .push(RestoreItem::new("label", &archived.name, e.to_string()));
+            }
+        }
+    }
+    Ok(mapped)
+}
+
+/// Copies one archived paper into the library
+struct PaperCopy<'a> {
+    category_ids: &'a HashMap<i64, i64>,
+    label_ids: &'a HashMap<i64, i64>,
+    entries: &'a HashMap<&'a str, &'a ArchiveEntry>,
+    files_dir: &'a Path,
+}
+
+impl PaperCopy<'_> {
+    /// Copy `archived` and return the library ids of its labels. Files
+    /// extracted for the paper are removed again if it cannot be inserted.
+    async fn run<R: Read + Seek>(
+        &self,
+        db: &DatabaseConnection,
+        source: &DatabaseConnection,
+        archived: &paper::Model,
+        archive: &mut ZipArchive<R>,
+        report: &mut MergeReport,
+    ) -> Result<Vec<i64>> {
+        let load_error = |e: DbErr| AppError::generic(format!("Failed to read archive: {}", e));
+
+        // Authors are shared between papers, so they are matched or created
+        // outside the paper's transaction
+        let relations = paper_author::Entity::find()
+            .filter(paper_author::Column::PaperId.eq(archived.id))
+            .order_by_asc(paper_author::Column::AuthorOrder)
+            .all(source)
+            .await
+            .map_err(load_error)?;
+        let mut authors = Vec::with_capacity(relations.len());
+        for relation in relations {
+            let Some(person) = author::Entity::find_by_id(relation.author_id)
+                .one(source)
+                .await
+                .map_err(load_error)?
+            else {
+                continue;
+            };
+            let details = AuthorDetails {
+                email: person.email,
+                affiliation: person.affiliation,
+                orcid: person.orcid,
+            };
+            let found = AuthorRepository::create_or_find_from_parts(
+                db,
+                Some(&person.first_name),
+                person.last_name.as_deref(),
+                &details,
+            )
+            .await?;
+            authors.push((found.id, relation));
+        }
+
+        let category_ids: Vec<i64> = paper_category::Entity::find()
+            .filter(paper_category::Column::PaperId.eq(archived.id))
+            .all(source)
+            .await
+            .map_err(load_error)?
+            .into_iter()
+            .filter_map(|r| self.category_ids.get(&r.category_id).copied())
+            .collect();
+        let label_ids: Vec<i64> = paper_label::Entity::find()
+            .filter(paper_label::Column::PaperId.eq(archived.id))
+            .all(source)
+            .await
+            .map_err(load_error)?
+            .into_iter()
+            .filter_map(|r| self.label_ids.get(&r.label_id).copied())
+            .collect();
+
+        let attachments = attachment::Entity::find()
+            .filter(attachment::Column::PaperId.eq(archived.id))
+            .order_by_asc(attachment::Column::Id)
+            .all(source)
+            .await
+            .map_err(load_error)?;
+        let mut kept = Vec::with_capacity(attachments.len());
+        let mut extracted = Vec::new();
+        for attachment in attachments {
+            if let (Some(dir), Some(file_name)) = (&archived.attachment_path, &attachment.file_name)
+            {
+                let archive_path = format!("{}/{}/{}", ARCHIVE_FILES_DIR, dir, file_name);
+                let target = archive_relative_path(&archive_path, ARCHIVE_FILES_DIR)
+                    .map(|relative| self.files_dir.join(relative));
+                let copied = match (target, self.entries.get(archive_path.as_str())) {
+                    // Only missing files are copied
+                    (Some(target), _) if target.exists() => Ok(()),
+                    (Some(target), Some(entry)) => {
+                        extract_entry(archive, entry, &target).map(|_| extracted.push(target))
+                    }
+                    _ => Err(AppError::validation(
+                        "archive_path",
+                        "The file is missing from the archive",
+                    )),
+                };
+                if let Err(e) = copied {
+                    report
+                        .failed
+                        .push(RestoreItem::new("attachment", file_name, e.to_string()));
+                    continue;
+                }
+            }
+            kept.push(attachment);
+        }
+
+        let inserted = insert_paper(db, archived, &authors, &category_ids, &label_ids, &kept).await;
+        match inserted {
+            Ok(()) => {
+                report.files_copied += extracted.len();
+                Ok(label_ids)
+            }
+            Err(e) => {
+                for file in extracted {
+                    let _ = std::fs::remove_file(file);
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Insert a paper and its relations in one transaction
+async fn insert_paper(
+    db: &DatabaseConnection,
+    archived: &paper::Model,
+    authors: &[(i64, paper_author::Model)],
+    category_ids: &[i64],
+    label_ids: &[i64],
+    attachments: &[attachment::Model],
+) -> Result<()> {
+    let insert_error = |e: DbErr| AppError::generic(format!("Failed to restore paper: {}", e));
+    let txn = db
+        .begin()
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to begin transaction: {}", e)))?;
+
+    let inserted = paper::ActiveModel {
+        title: Set(archived.title.clone()),
+        abstract_text: Set(archived.abstract_text.clone()),
+        doi: Set(archived.doi.clone()),
+        publication_year: Set(archived.publication_year),
+        publication_date: Set(archived.publication_date.clone()),
+        journal_name: Set(archived.journal_name.clone()),
+        conference_name: Set(archived.conference_name.clone()),
+        volume: Set(archived.volume.clone()),
+        issue: Set(archived.issue.clone()),
+        pages: Set(archived.pages.clone()),
+        url: Set(archived.url.clone()),
+        citation_count: Set(archived.citation_count),
+        read_status: Set(archived.read_status.clone()),
+        notes: Set(archived.notes.clone()),
+        attachment_path: Set(archived.attachment_path.clone()),
+        publisher: Set(archived.publisher.clone()),
+        issn: Set(archived.issn.clone()),
+        language: Set(archived.language.clone()),
+        isbn: Set(archived.isbn.clone()),
+        attachment_count: Set(attachments.len() as i32),
+        is_starred: Set(archived.is_starred),
+        starred_at: Set(archived.starred_at),
+        created_at: Set(archived.created_at),
+        updated_at: Set(archived.updated_at),
+        deleted_at: Set(None),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await
+    .map_err(insert_error)?;
+    let paper_id = inserted.id;
+
+    if !authors.is_empty() {
+        paper_author::Entity::insert_many(authors.iter().map(|(author_id, relation)| {
+            paper_author::ActiveModel {
+                paper_id: Set(paper_id),
+                author_id: Set(*author_id),
+                author_order: Set(relation.author_order),
+                is_corresponding: Set(relation.is_corresponding),
+                ..Default::default()
+            }
+        }))
+        .exec(&txn)
+        .await
+        .map_err(insert_error)?;
+    }
+    if !category_ids.is_empty() {
+        paper_category::Entity::insert_many(category_ids.iter().map(|category_id| {
+            paper_category::ActiveModel {
+                paper_id: Set(paper_id),
+                category_id: Set(*category_id),
+                ..Default::default()
+            }
+        }))
+        .exec(&txn)
+        .await
+        .map_err(insert_error)?;
+    }
+    if !label_ids.is_empty() {
+        paper_label::Entity::insert_many(label_ids.iter().map(|label_id| {
+            paper_label::ActiveModel {
+                paper_id: Set(paper_id),
+                label_id: Set(*label_id),
+                ..Default::default()
+            }
+        }))
+        .exec(&txn)
+        .await
+        .map_err(insert_error)?;
+    }
+    if !attachments.is_empty() {
+        attachment::Entity::insert_many(attachments.iter().map(|a| attachment::ActiveModel {
+            paper_id: Set(paper_id),
+            file_name: Set(a.file_name.clone()),
+            file_type: Set(a.file_type.clone()),
+            file_size: Set(a.file_size),
+            created_at: Set(a.created_at),
+            ..Default::default()
+        }))
+        .exec(&txn)
+        .await
+        .map_err(insert_error)?;
+    }
+
+    txn.commit()
+        .await
+        .map_err(|e| AppError::generic(format!("Failed to commit transaction: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::library_archive_service::write_library_archive;
+    use crate::testing::{test_app_dirs, test_db, PaperFixture};
+
+    fn write_archive(root: &Path, dirs: &AppDirs) -> (PathBuf, ArchiveManifest) {
+        let snapshot = root.join("snapshot.sqlite");
+        std::fs::write(&snapshot, b"SQLite format 3\0 archived").unwrap();
+        let target = root.join("library.zip");
+        let manifest = write_library_archive(&snapshot, dirs, "m1", &target, |_| {}).unwrap();
+        (target, manifest)
+    }
+
+    #[test]
+    fn test_replace_stages_database_and_keeps_previous_dirs() {
+        let source = tempfile::tempdir().unwrap();
+        let source_dirs = test_app_dirs(source.path());
+        std::fs::write(Path::new(&source_dirs.config).join("settings.json"), b"{}").unwrap();
+        let attachment_dir = Path::new(&source_dirs.files).join("abc123");
+        std::fs::create_dir_all(&attachment_dir).unwrap();
+        std::fs::write(attachment_dir.join("paper.pdf"), b"%PDF-1.4").unwrap();
+        let (archive, manifest) = write_archive(source.path(), &source_dirs);
+
+        let library = tempfile::tempdir().unwrap();
+        let dirs = test_app_dirs(library.path());
+        std::fs::write(Path::new(&dirs.files).join("old.pdf"), b"old").unwrap();
+
+        replace_library(&archive, &manifest, &dirs, |_| {}).unwrap();
+
+        assert!(Path::new(&dirs.files).join("abc123/paper.pdf").exists());
+        assert!(Path::new(&dirs.config).join("settings.json").exists());
+        assert!(sibling(Path::new(&dirs.files), PREVIOUS_SUFFIX)
+            .join("old.pdf")
+            .exists());
+        assert_eq!(
+            std::fs::read(Path::new(&dirs.data).join(PENDING_RESTORE_FILE)).unwrap(),
+            b"SQLite format 3\0 archived"
+        );
+        assert!(!sibling(Path::new(&dirs.files), STAGING_SUFFIX).exists());
+    }
+
+    #[test]
+    fn test_replace_leaves_library_untouched_on_bad_archive() {
+        let source = tempfile::tempdir().unwrap();
+        let source_dirs = test_app_dirs(source.path());
+        let (archive, mut manifest) = write_archive(source.path(), &source_dirs);
+        manifest.entries[0].sha256 = "0".repeat(64);
+
+        let library = tempfile::tempdir().unwrap();
+        let dirs = test_app_dirs(library.path());
+        std::fs::write(Path::new(&dirs.files).join("old.pdf"), b"old").unwrap();
+
+        let mut phases = Vec::new();
+        let result = replace_library(&archive, &manifest, &dirs, |s| phases.push(s.phase.clone()));
+
+        assert!(result.is_err());
+        assert_eq!(phases.last(), Some(&MigrationPhase::Failed));
+        assert!(Path::new(&dirs.files).join("old.pdf").exists());
+        assert!(!Path::new(&dirs.data).join(PENDING_RESTORE_FILE).exists());
+        assert!(!sibling(Path::new(&dirs.files), STAGING_SUFFIX).exists());
+    }
+
+    #[tokio::test]
+    async fn test_merge_adds_missing_papers_and_reports_duplicates() {
+        let source = test_db().await;
+        PaperFixture::new("Attention Is All You Need")
+            .with_doi("10.48550/arXiv.1706.03762")
+            .with_label("to-read")
+            .insert(&source)
+            .await;
+        PaperFixture::new("Deep Residual Learning")
+            .with_authors(&["Kaiming He"])
+            .with_label("vision")
+            .with_category("CNN")
+            .insert(&source)
+            .await;
+
+        let db = test_db().await;
+        PaperFixture::new("Attention is all you need!")
+            .with_label("to-read")
+            .insert(&db)
+            .await;
+
+        let root = tempfile::tempdir().unwrap();
+        let dirs = test_app_dirs(root.path());
+        let (archive, manifest) = write_archive(root.path(), &dirs);
+
+        let report = merge_library(&db, &source, &archive, &manifest, &dirs, |_| {})
+            .await
+            .unwrap();
+
+        assert_eq!(report.papers_added, 1);
+        assert_eq!(report.labels_added, 1);
+        assert_eq!(report.categories_added, 1);
+        assert!(report.failed.is_empty());
+        assert!(report.skipped.contains(&RestoreItem::new(
+            "paper",
+            "Attention Is All You Need",
+            "A paper with the same title is already in the library",
+        )));
+
+        let vision = LabelRepository::find_by_name(&db, "vision")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(vision.document_count, 1);
+        let titles: Vec<String> = paper::Entity::find()
+            .all(db.as_ref())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.title)
+            .collect();
+        assert!(titles.contains(&"Deep Residual Learning".to_string()));
+    }
+}
@@ -0,0 +1,258 @@
+//! Markdown export of papers
+//!
+//! Writes one Markdown file per paper, named after its title, into a target
+//! folder such as an Obsidian vault. File names are made valid on Windows as
+//! well, and a file that already exists is skipped, overwritten or kept
+//! next to a new file with a short hash of the paper id in its name.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use tracing::info;
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::papers::exporter::markdown::{paper_context, Template};
+use crate::repository::{AuthorRepository, CategoryRepository, LabelRepository, PaperRepository};
+use crate::service::share_service::load_highlights;
+use crate::sys::error::{AppError, Result};
+
+/// Longest file name stem, in bytes, leaving room for a hash and `.md`
+const MAX_STEM_BYTES: usize = 150;
+
+/// Names Windows reserves for devices, with or without an extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// What to do when the Markdown file of a paper already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionStrategy {
+    /// Leave the existing file alone
+    Skip,
+    /// Replace the existing file
+    Overwrite,
+    /// Write `<title>-<hash>.md` instead, where the hash is derived from the
+    /// paper id, so exporting the same paper again updates that file
+    #[default]
+    AppendHash,
+}
+
+/// Where a paper was written, or the existing file it was skipped for
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarkdownWrite {
+    Written(PathBuf),
+    Skipped(PathBuf),
+}
+
+/// Make `title` usable as a file name on Windows, macOS and Linux
+pub fn sanitize_file_name(title: &str) -> String {
+    let replaced: String = title
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+    let mut name = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if name.len() > MAX_STEM_BYTES {
+        let mut end = MAX_STEM_BYTES;
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name.truncate(end);
+    }
+    // Windows drops trailing dots and spaces
+    let name = name.trim_end_matches(['.', ' ']).to_string();
+
+    if name.is_empty() {
+        return "Untitled".to_string();
+    }
+    let base = name.split('.').next().unwrap_or("");
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(base)) {
+        return format!("{}_{}", base, &name[base.len()..]);
+    }
+    name
+}
+
+fn paper_hash(paper_id: i64) -> String {
+    let digest = Sha1::digest(paper_id.to_string().as_bytes());
+    digest[..4].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Render `paper` with its authors, labels, categories, notes and highlights
+pub async fn render_paper(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper: &Paper,
+    template: &Template,
+) -> Result<String> {
+    let authors: Vec<String> = AuthorRepository::get_paper_authors(db, paper.id)
+        .await?
+        .iter()
+        .map(|a| a.full_name())
+        .collect();
+    let labels: Vec<String> = LabelRepository::get_paper_labels(db, paper.id)
+        .await?
+        .into_iter()
+        .map(|l| l.name)
+        .collect();
+    let mut categories = Vec::new();
+    for category_id in PaperRepository::get_category_ids(db, paper.id).await? {
+        if let Some(category) = CategoryRepository::find_by_id(db, category_id).await? {
+            categories.push(category.name);
+        }
+    }
+    let highlights = load_highlights(db, files_dir, paper).await?;
+
+    let context = paper_context(paper, &authors, &labels, &categories, &highlights);
+    Ok(template.render(&context))
+}
+
+/// Write `content` as the Markdown file of `paper` in `target_dir`.
+///
+/// `claimed` holds the files written earlier in the same export; they are
+/// never replaced, so two papers with the same title both end up on disk.
+pub fn write_markdown(
+    target_dir: &Path,
+    paper: &Paper,
+    content: &str,
+    strategy: CollisionStrategy,
+    claimed: &mut HashSet<PathBuf>,
+) -> Result<MarkdownWrite> {
+    let stem = sanitize_file_name(&paper.title);
+    let plain = target_dir.join(format!("{}.md", stem));
+    let hashed = target_dir.join(format!("{}-{}.md", stem, paper_hash(paper.id)));
+
+    let path = if claimed.contains(&plain) {
+        hashed
+    } else if !plain.exists() {
+        plain
+    } else {
+        match strategy {
+            CollisionStrategy::Skip => return Ok(MarkdownWrite::Skipped(plain)),
+            CollisionStrategy::Overwrite => plain,
+            CollisionStrategy::AppendHash => hashed,
+        }
+    };
+
+    std::fs::write(&path, content).map_err(|e| {
+        AppError::file_system(
+            path.to_string_lossy().to_string(),
+            format!("Failed to write Markdown: {}", e),
+        )
+    })?;
+    claimed.insert(path.clone());
+    Ok(MarkdownWrite::Written(path))
+}
+
+/// Render and write one paper
+pub async fn export_paper(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper: &Paper,
+    target_dir: &Path,
+    template: &Template,
+    strategy: CollisionStrategy,
+    claimed: &mut HashSet<PathBuf>,
+) -> Result<MarkdownWrite> {
+    let content = render_paper(db, files_dir, paper, template).await?;
+    let written = write_markdown(target_dir, paper, &content, strategy, claimed)?;
+    if let MarkdownWrite::Written(path) = &written {
+        info!("Exported paper {} to {:?}", paper.id, path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::papers::exporter::markdown::DEFAULT_TEMPLATE;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(
+            sanitize_file_name("BERT: Pre-training <of> Deep/Bidirectional?"),
+            "BERT_ Pre-training _of_ Deep_Bidirectional_"
+        );
+        assert_eq!(
+            sanitize_file_name("  A\ttitle\n spread out. "),
+            "A title spread out"
+        );
+        assert_eq!(sanitize_file_name("con"), "con_");
+        assert_eq!(sanitize_file_name("Aux.notes"), "Aux_.notes");
+        assert_eq!(sanitize_file_name("..."), "Untitled");
+        let long = sanitize_file_name(&"注意力".repeat(100));
+        assert!(long.len() <= MAX_STEM_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_export_with_collision_strategies() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Attention: Is All You Need")
+            .with_authors(&["Ashish Vaswani"])
+            .with_label("nlp")
+            .with_category("Transformers")
+            .with_doi("10.5555/3295222")
+            .insert(&db)
+            .await;
+        let files = tempfile::tempdir().unwrap();
+        let files_dir = files.path().to_string_lossy().to_string();
+        let vault = tempfile::tempdir().unwrap();
+        let template = Template::parse(DEFAULT_TEMPLATE).unwrap();
+
+        let export = |strategy| {
+            let db = db.clone();
+            let (files_dir, vault, template, paper) = (
+                files_dir.clone(),
+                vault.path().to_path_buf(),
+                template.clone(),
+                paper.clone(),
+            );
+            async move {
+                export_paper(
+                    &db,
+                    &files_dir,
+                    &paper,
+                    &vault,
+                    &template,
+                    strategy,
+                    &mut HashSet::new(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        let plain = vault.path().join("Attention_ Is All You Need.md");
+        assert_eq!(
+            export(CollisionStrategy::Skip).await,
+            MarkdownWrite::Written(plain.clone())
+        );
+        let content = std::fs::read_to_string(&plain).unwrap();
+        assert!(content.starts_with("---\ntitle: \"Attention: Is All You Need\"\n"));
+        assert!(content.contains("authors:\n  - \"Ashish Vaswani\"\n"));
+        assert!(content.contains("labels:\n  - \"nlp\"\ncategories:\n  - \"Transformers\"\n---\n"));
+
+        assert_eq!(
+            export(CollisionStrategy::Skip).await,
+            MarkdownWrite::Skipped(plain.clone())
+        );
+        assert_eq!(
+            export(CollisionStrategy::Overwrite).await,
+            MarkdownWrite::Written(plain.clone())
+        );
+        match export(CollisionStrategy::AppendHash).await {
+            MarkdownWrite::Written(path) => assert_ne!(path, plain),
+            skipped => panic!("expected a hashed file, got {:?}", skipped),
+        }
+        assert_eq!(std::fs::read_dir(vault.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,401 @@
+//! Refresh paper metadata from Crossref
+//!
+//! The citation count is always replaced with the current Crossref value.
+//! Pages, volume, issue and abstract are only filled in when blank, so
+//! values the user has edited are never overwritten.
+//!
+//! Enrichment from the Crossref works API follows the same rule for the
+//! journal or conference, publisher and ISSN. Crossref subjects are linked
+//! as keywords.
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::database::DatabaseConnection;
+use crate::models::{Paper, UpdatePaper};
+use crate::papers::importer::crossref::CrossrefWork;
+use crate::papers::importer::doi::{DoiError, DoiMetadata};
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::repository::PaperRepository;
+use crate::service::keyword_service;
+use crate::sys::error::{AppError, Result};
+
+/// Papers loaded from the database at a time by `refresh_all`
+const REFRESH_BATCH_SIZE: u64 = 50;
+
+/// Fields changed by a refresh
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRefreshResult {
+    pub paper_id: String,
+    pub updated_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRefreshProgress {
+    pub current: usize,
+    pub total: usize,
+    pub paper_id: String,
+    pub doi: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRefreshFailure {
+    pub paper_id: String,
+    pub doi: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataRefreshReport {
+    pub total: usize,
+    /// Papers with at least one changed field
+    pub updated: usize,
+    pub unchanged: usize,
+    pub failed: Vec<MetadataRefreshFailure>,
+    pub cancelled: bool,
+}
+
+/// Cancellation handle of the running `refresh_all`, if any
+#[derive(Clone, Default)]
+pub struct MetadataRefreshState {
+    running: Arc<Mutex<Option<CancellationToken>>>,
+}
+
+impl MetadataRefreshState {
+    /// Register a new run. Fails if one is already running.
+    pub fn start(&self) -> Result<CancellationToken> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        if running.is_some() {
+            return Err(AppError::validation(
+                "refresh",
+                "A metadata refresh is already running",
+            ));
+        }
+        let token = CancellationToken::new();
+        *running = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Cancel the running refresh. Returns false when none is running.
+    pub fn cancel(&self) -> bool {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        match running.as_ref() {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&self) {
+        *self.running.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+fn is_blank(value: &Option<String>) -> bool {
+    value.as_deref().is_none_or(|v| v.trim().is_empty())
+}
+
+/// Apply fetched metadata to a paper and return the names of the fields
+/// that changed
+pub async fn apply_metadata(
+    db: &DatabaseConnection,
+    paper: &Paper,
+    metadata: &DoiMetadata,
+) -> Result<Vec<String>> {
+    let mut updated_fields = Vec::new();
+
+    if let Some(count) = metadata.citation_count {
+        if count != paper.citation_count {
+            PaperRepository::update_citation_count(db, paper.id, count).await?;
+            updated_fields.push("citation_count".to_string());
+        }
+    }
+
+    let mut update = UpdatePaper::default();
+    let mut fill = |field: &str, current: &Option<String>, fetched: &Option<String>| {
+        if is_blank(current) && !is_blank(fetched) {
+            updated_fields.push(field.to_string());
+            fetched.clone()
+        } else {
+            None
+        }
+    };
+    update.pages = fill("pages", &paper.pages, &metadata.pages);
+    update.volume = fill("volume", &paper.volume, &metadata.volume);
+    update.issue = fill("issue", &paper.issue, &metadata.issue);
+    update.abstract_text = fill(
+        "abstract_text",
+        &paper.abstract_text,
+        &metadata.abstract_text,
+    );
+
+    let fills_blanks = update.pages.is_some()
+        || update.volume.is_some()
+        || update.issue.is_some()
+        || update.abstract_text.is_some();
+    if fills_blanks {
+        PaperRepository::update(db, paper.id, update).await?;
+    }
+
+    Ok(updated_fields)
+}
+
+/// Fill a paper's blank venue, publisher and ISSN from its Crossref record
+/// and link the record's subjects as keywords. Returns the names of the
+/// fields filled in, plus `keywords` when the record lists subjects.
+///
+/// The container title of a `proceedings-article` is the proceedings, so it
+/// fills the conference name; for other types it fills the journal name.
+pub async fn apply_crossref_work(
+    db: &DatabaseConnection,
+    paper: &Paper,
+    work: &CrossrefWork,
+) -> Result<Vec<String>> {
+    let mut updated_fields = Vec::new();
+
+    let mut update = UpdatePaper::default();
+    let mut fill = |field: &str, current: &Option<String>, fetched: &Option<String>| {
+        if is_blank(current) && !is_blank(fetched) {
+            updated_fields.push(field.to_string());
+            fetched.clone()
+        } else {
+            None
+        }
+    };
+    if work.type_.as_deref() == Some("proceedings-article") {
+        update.conference_name = fill(
+            "conference_name",
+            &paper.conference_name,
+            &work.container_title,
+        );
+    } else {
+        update.journal_name = fill("journal_name", &paper.journal_name, &work.container_title);
+    }
+    update.publisher = fill("publisher", &paper.publisher, &work.publisher);
+    let issn = (!work.issn.is_empty()).then(|| work.issn.join(", "));
+    update.issn = fill("issn", &paper.issn, &issn);
+
+    let fills_blanks = update.conference_name.is_some()
+        || update.journal_name.is_some()
+        || update.publisher.is_some()
+        || update.issn.is_some();
+    if fills_blanks {
+        PaperRepository::update(db, paper.id, update).await?;
+    }
+
+    if keyword_service::store_keywords(db, paper.id, &work.subject).await? > 0 {
+        updated_fields.push("keywords".to_string());
+    }
+
+    Ok(updated_fields)
+}
+
+/// Refresh every non-deleted paper that has a DOI.
+///
+/// Papers are loaded in batches and fetched one at a time through
+/// `rate_limiter`. A failure is recorded in the report and the run goes on
+/// with the next paper. Once `cancel` is triggered the run stops before the
+/// next paper.
+pub async fn refresh_all<F, Fut, P>(
+    db: &DatabaseConnection,
+    rate_limiter: &RateLimiter,
+    cancel: &CancellationToken,
+    mut fetch: F,
+    mut on_progress: P,
+) -> Result<MetadataRefreshReport>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = std::result::Result<DoiMetadata, DoiError>>,
+    P: FnMut(&MetadataRefreshProgress),
+{
+    let total = PaperRepository::count_with_doi(db).await? as usize;
+    let mut report = MetadataRefreshReport {
+        total,
+        updated: 0,
+        unchanged: 0,
+        failed: Vec::new(),
+        cancelled: false,
+    };
+
+    let mut current = 0;
+    let mut last_id = 0;
+    'batches: loop {
+        let papers = PaperRepository::find_with_doi_after(db, last_id, REFRESH_BATCH_SIZE).await?;
+        let Some(last) = papers.last() else {
+            break;
+        };
+        last_id = last.id;
+
+        for paper in papers {
+            if cancel.is_cancelled() {
+                report.cancelled = true;
+                break 'batches;
+            }
+
+            let doi = paper.doi.clone().unwrap_or_default();
+            current += 1;
+            on_progress(&MetadataRefreshProgress {
+                current,
+                total,
+                paper_id: paper.id.to_string(),
+                doi: doi.clone(),
+            });
+
+            rate_limiter.acquire().await;
+            let result = match fetch(doi.clone()).await {
+                Ok(metadata) => apply_metadata(db, &paper, &metadata).await,
+                Err(e) => Err(AppError::network_error(&doi, e.to_string())),
+            };
+            match result {
+                Ok(fields) if fields.is_empty() => report.unchanged += 1,
+                Ok(_) => report.updated += 1,
+                Err(e) => {
+                    warn!("Failed to refresh metadata for {}: {}", doi, e);
+                    report.failed.push(MetadataRefreshFailure {
+                        paper_id: paper.id.to_string(),
+                        doi,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    info!(
+        "Metadata refresh: {} updated, {} unchanged, {} failed{}",
+        report.updated,
+        report.unchanged,
+        report.failed.len(),
+        if report.cancelled { " (cancelled)" } else { "" }
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    fn metadata(doi: &str, citation_count: i32) -> DoiMetadata {
+        DoiMetadata {
+            doi: doi.to_string(),
+            title: "Fetched title".to_string(),
+            authors: Vec::new(),
+            publication_year: None,
+            journal_name: None,
+            volume: Some("12".to_string()),
+            issue: None,
+            pages: Some("1-10".to_string()),
+            publisher: None,
+            url: None,
+            abstract_text: Some("Fetched abstract".to_string()),
+            citation_count: Some(citation_count),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_apply_metadata_only_fills_blanks() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Edited")
+            .with_doi("10.1000/a")
+            .with_abstract("My own abstract")
+            .insert(&db)
+            .await;
+
+        let fields = apply_metadata(&db, &paper, &metadata("10.1000/a", 7))
+            .await
+            .unwrap();
+        assert_eq!(fields, vec!["citation_count", "pages", "volume"]);
+
+        let paper = PaperRepository::find_by_id(&db, paper.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paper.citation_count, 7);
+        assert_eq!(paper.abstract_text.as_deref(), Some("My own abstract"));
+        assert_eq!(paper.pages.as_deref(), Some("1-10"));
+
+        let fields = apply_metadata(&db, &paper, &metadata("10.1000/a", 7))
+            .await
+            .unwrap();
+        assert!(fields.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_apply_crossref_work_fills_venue() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Proceedings paper")
+            .with_doi("10.1000/p")
+            .insert(&db)
+            .await;
+        let work = CrossrefWork {
+            doi: "10.1000/p".to_string(),
+            publisher: Some("ACM".to_string()),
+            container_title: Some("Proceedings of SIGIR".to_string()),
+            issn: vec!["1234-5678".to_string(), "8765-4321".to_string()],
+            type_: Some("proceedings-article".to_string()),
+            subject: vec!["Information Systems".to_string()],
+            ..Default::default()
+        };
+
+        let fields = apply_crossref_work(&db, &paper, &work).await.unwrap();
+        assert_eq!(
+            fields,
+            vec!["conference_name", "publisher", "issn", "keywords"]
+        );
+
+        let paper = PaperRepository::find_by_id(&db, paper.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(paper.journal_name, None);
+        assert_eq!(
+            paper.conference_name.as_deref(),
+            Some("Proceedings of SIGIR")
+        );
+        assert_eq!(paper.issn.as_deref(), Some("1234-5678, 8765-4321"));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_all_collects_failures() {
+        let db = test_db().await;
+        PaperFixture::new("Found")
+            .with_doi("10.1000/found")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Gone")
+            .with_doi("10.1000/gone")
+            .insert(&db)
+            .await;
+        PaperFixture::new("No DOI").insert(&db).await;
+
+        let mut progress = Vec::new();
+        let report = refresh_all(
+            &db,
+            &RateLimiter::new(std::time::Duration::ZERO),
+            &CancellationToken::new(),
+            |doi| async move {
+                if doi.ends_with("gone") {
+                    Err(DoiError::NotFound)
+                } else {
+                    Ok(metadata(&doi, 3))
+                }
+            },
+            |p| progress.push(p.current),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.total, 2);
+        assert_eq!(report.updated, 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].doi, "10.1000/gone");
+        assert_eq!(progress, vec![1, 2]);
+    }
+}
@@ -1 +1,35 @@
+pub mod activity_service;
+pub mod attachment_service;
+pub mod author_service;
+pub mod backup_service;
+pub mod category_suggestion_service;
+pub mod citation_count_service;
+pub mod citation_service;
+pub mod clipboard_capture_service;
+pub mod csv_import_service;
 pub mod data_migration_service;
+pub mod database_recovery_service;
+pub mod doi_import_service;
+pub mod download_service;
+pub mod embedding_service;
+pub mod integrity_service;
+pub mod export_service;
+pub mod keyword_service;
+pub mod language_detection_service;
+pub mod markdown_export_service;
+pub mod library_archive_service;
+pub mod library_export_service;
+pub mod library_import_service;
+pub mod library_restore_service;
+pub mod metadata_refresh_service;
+pub mod ocr_service;
+pub mod open_access_service;
+pub mod paper_timeline_service;
+pub mod pdf_import_queue_service;
+pub mod quiet_hours_service;
+pub mod reading_stats_service;
+pub mod related_papers_service;
+pub mod share_service;
+pub mod summary_service;
+pub mod thumbnail_service;
+pub mod tray_menu_service;
@@ -0,0 +1,445 @@
+//! OCR for scanned PDFs
+//!
+//! Recognition is delegated to an external `ocrmypdf` binary, which writes a
+//! copy of the PDF with an invisible text layer so external viewers can
+//! search it. The copy is verified against the original and then renamed
+//! over it; on any failure the original file is left untouched.
+//!
+//! Text can also be extracted without touching the PDF: it is read from the
+//! text layer when there is one, and otherwise recognized by `ocrmypdf`'s
+//! sidecar output or by an OCR API configured in `paper.ocr_api_url`.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use lopdf::content::Content;
+use lopdf::Document;
+use serde::Deserialize;
+use tracing::info;
+
+use crate::sys::config::PaperConfig;
+use crate::sys::error::{AppError, Result};
+
+/// Binary used when `paper.ocrmypdf_path` is not configured
+pub const DEFAULT_OCRMYPDF_BINARY: &str = "ocrmypdf";
+
+/// `paper.pdf` is kept as `paper_original.pdf` before being replaced
+const ORIGINAL_SUFFIX: &str = "_original";
+
+/// `paper_text_content.source` of text read from the PDF's text layer
+pub const TEXT_SOURCE_LAYER: &str = "text_layer";
+
+/// `paper_text_content.source` of text recognized by OCR
+pub const TEXT_SOURCE_OCR: &str = "ocr";
+
+/// Upper bound for one OCR API request; recognizing a long scan can take
+/// minutes
+const OCR_API_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// PDF operators that draw text
+const TEXT_SHOWING_OPERATORS: [&str; 4] = ["Tj", "TJ", "'", "\""];
+
+/// Text of a PDF and where it came from
+#[derive(Debug, Clone)]
+pub struct PdfText {
+    pub page_count: u32,
+    pub text: String,
+    /// Whether the PDF already had selectable text
+    pub has_text_layer: bool,
+}
+
+/// Path the untouched original is preserved at
+pub fn original_backup_path(pdf_path: &Path) -> PathBuf {
+    let stem = pdf_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    pdf_path.with_file_name(format!("{}{}.pdf", stem, ORIGINAL_SUFFIX))
+}
+
+fn load_pdf(path: &Path) -> Result<Document> {
+    Document::load(path).map_err(|e| {
+        AppError::file_system(
+            path.to_string_lossy().to_string(),
+            format!("Failed to parse PDF: {}", e),
+        )
+    })
+}
+
+/// Check that `candidate` is a readable PDF with the same number of pages as
+/// `original` and that every page's content stream can be decoded
+pub fn verify_replacement(original: &Path, candidate: &Path) -> Result<()> {
+    let expected = load_pdf(original)?.get_pages().len();
+    let document = load_pdf(candidate)?;
+    let pages = document.get_pages();
+
+    if pages.len() != expected {
+        return Err(AppError::ocr_error(format!(
+            "OCR output has {} pages, expected {}",
+            pages.len(),
+            expected
+        )));
+    }
+
+    for (number, page_id) in pages {
+        document.get_page_content(page_id).map_err(|e| {
+            AppError::ocr_error(format!(
+                "Page {} of the OCR output is unreadable: {}",
+                number, e
+            ))
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Verify `candidate` and move it over `pdf_path`.
+///
+/// With `keep_original`, the current file is first copied to
+/// `*_original.pdf`, unless an earlier run already preserved it there.
+pub fn replace_with_verified(pdf_path: &Path, candidate: &Path, keep_original: bool) -> Result<()> {
+    verify_replacement(pdf_path, candidate)?;
+
+    if keep_original {
+        let backup = original_backup_path(pdf_path);
+        if !backup.exists() {
+            std::fs::copy(pdf_path, &backup).map_err(|e| {
+                AppError::file_system(
+                    backup.to_string_lossy().to_string(),
+                    format!("Failed to preserve original PDF: {}", e),
+                )
+            })?;
+        }
+    }
+
+    std::fs::rename(candidate, pdf_path).map_err(|e| {
+        AppError::file_system(
+            pdf_path.to_string_lossy().to_string(),
+            format!("Failed to replace PDF: {}", e),
+        )
+    })
+}
+
+/// Number of text-drawing operators across all pages. Image-only scans
+/// have none.
+pub fn count_text_operators(document: &Document) -> usize {
+    document
+        .get_pages()
+        .values()
+        .filter_map(|page_id| document.get_page_content(*page_id).ok())
+        .filter_map(|content| Content::decode(&content).ok())
+        .map(|content| {
+            content
+                .operations
+                .iter()
+                .filter(|op| TEXT_SHOWING_OPERATORS.contains(&op.operator.as_str()))
+                .count()
+        })
+        .sum()
+}
+
+/// Page count, text-operator count and text of the PDF's text layer
+fn read_text_layer(path: &Path) -> Result<(u32, usize, String)> {
+    let document = load_pdf(path)?;
+    let pages: Vec<u32> = document.get_pages().keys().copied().collect();
+    let operators = count_text_operators(&document);
+
+    let mut text = String::new();
+    if operators > 0 {
+        for page in &pages {
+            if let Ok(page_text) = document.extract_text(&[*page]) {
+                text.push_str(&page_text);
+                text.push('\n');
+            }
+        }
+    }
+    Ok((pages.len() as u32, operators, text))
+}
+
+async fn run_ocrmypdf(
+    binary: &str,
+    input: &Path,
+    output: &Path,
+    sidecar: Option<&Path>,
+) -> Result<()> {
+    let mut command = tokio::process::Command::new(binary);
+    if let Some(sidecar) = sidecar {
+        command.arg("--sidecar").arg(sidecar);
+    }
+    let result = command
+        // Leave pages that already have text alone instead of failing
+        .arg("--skip-text")
+        .arg("--output-type")
+        .arg("pdf")
+        .arg(input)
+        .arg(output)
+        .output()
+        .await
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AppError::ocr_error(format!(
+                    "'{}' was not found; install ocrmypdf or set paper.ocrmypdf_path",
+                    binary
+                ))
+            } else {
+                AppError::ocr_error(format!("Failed to run {}: {}", binary, e))
+            }
+        })?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let detail = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("");
+        return Err(AppError::ocr_error(format!(
+            "{} exited with {}: {}",
+            binary, result.status, detail
+        )));
+    }
+
+    Ok(())
+}
+
+/// Run OCR on `pdf_path` and replace it with a copy carrying a searchable
+/// text layer. Returns the new file size in bytes.
+pub async fn embed_text_layer(pdf_path: &Path, binary: &str, keep_original: bool) -> Result<u64> {
+    // Write next to the original so the final rename stays on one filesystem.
+    // The name is unique per run and the file is removed when it is dropped
+    // unless it was renamed over the original.
+    let dir = pdf_path.parent().unwrap_or(Path::new("."));
+    let temp_path = tempfile::Builder::new()
+        .prefix(".ocr-")
+        .suffix(".pdf")
+        .tempfile_in(dir)
+        .map_err(|e| {
+            AppError::file_system(
+                dir.to_string_lossy().to_string(),
+                format!("Failed to create OCR output file: {}", e),
+            )
+        })?
+        .into_temp_path();
+
+    run_ocrmypdf(binary, pdf_path, &temp_path, None).await?;
+    replace_with_verified(pdf_path, &temp_path, keep_original)?;
+
+    let size = std::fs::metadata(pdf_path).map(|m| m.len()).map_err(|e| {
+        AppError::file_system(pdf_path.to_string_lossy().to_string(), e.to_string())
+    })?;
+    info!(
+        "Embedded OCR text layer into {:?} ({} bytes)",
+        pdf_path, size
+    );
+    Ok(size)
+}
+
+/// Recognize the text of `pdf_path` with `ocrmypdf`'s sidecar output,
+/// leaving the PDF itself untouched
+async fn recognize_with_ocrmypdf(pdf_path: &Path, binary: &str) -> Result<String> {
+    // Removed with everything in it when dropped
+    let temp_dir = tempfile::tempdir()
+        .map_err(|e| AppError::ocr_error(format!("Failed to create OCR directory: {}", e)))?;
+    let temp_pdf = temp_dir.path().join("ocr.pdf");
+    let sidecar = temp_dir.path().join("ocr.txt");
+
+    run_ocrmypdf(binary, pdf_path, &temp_pdf, Some(&sidecar)).await?;
+    tokio::fs::read_to_string(&sidecar).await.map_err(|e| {
+        AppError::ocr_error(format!("Failed to read OCR text from {:?}: {}", sidecar, e))
+    })
+}
+
+#[derive(Deserialize)]
+struct OcrApiResponse {
+    text: String,
+}
+
+/// Send the PDF to an OCR API. The API receives the raw PDF bytes and
+/// answers with either `{"text": "..."}` or plain text.
+async fn recognize_with_api(pdf_path: &Path, url: &str, api_key: Option<&str>) -> Result<String> {
+    let bytes = tokio::fs::read(pdf_path).await.map_err(|e| {
+        AppError::file_system(
+            pdf_path.to_string_lossy().to_string(),
+            format!("Failed to read PDF: {}", e),
+        )
+    })?;
+
+    let client = reqwest::Client::builder()
+        .timeout(OCR_API_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::network_error(url, e.to_string()))?;
+    let mut request = client
+        .post(url)
+        .header("Content-Type", "application/pdf")
+        .body(bytes);
+    if let Some(key) = api_key.filter(|k| !k.trim().is_empty()) {
+        request = request.header("Authorization", format!("Bearer {}", key));
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| AppError::network_error(url, e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(AppError::network_error(
+            url,
+            format!("OCR API returned {}", response.status()),
+        ));
+    }
+    let body = response
+        .text()
+        .await
+        .map_err(|e| AppError::network_error(url, e.to_string()))?;
+
+    Ok(parse_ocr_api_response(&body))
+}
+
+fn parse_ocr_api_response(body: &str) -> String {
+    serde_json::from_str::<OcrApiResponse>(body)
+        .map(|r| r.text)
+        .unwrap_or_else(|_| body.to_string())
+}
+
+/// Text of the PDF at `pdf_path`. PDFs with a text layer are read directly;
+/// image-only scans go to the OCR API when `paper.ocr_api_url` is set and to
+/// `ocrmypdf` otherwise.
+pub async fn extract_text(pdf_path: &Path, config: &PaperConfig) -> Result<PdfText> {
+    let path = pdf_path.to_path_buf();
+    let (page_count, operators, text) = tokio::task::spawn_blocking(move || read_text_layer(&path))
+        .await
+        .map_err(|e| AppError::ocr_error(format!("Text extraction failed: {}", e)))??;
+
+    if operators > 0 {
+        info!(
+            "{:?} has a text layer ({} text operators)",
+            pdf_path, operators
+        );
+        return Ok(PdfText {
+            page_count,
+            text,
+            has_text_layer: true,
+        });
+    }
+
+    let api_url = config
+        .ocr_api_url
+        .as_deref()
+        .filter(|u| !u.trim().is_empty());
+    let text = match api_url {
+        Some(url) => {
+            info!("Running OCR on {:?} with {}", pdf_path, url);
+            recognize_with_api(pdf_path, url, config.ocr_api_key().as_deref()).await?
+        }
+        None => {
+            let binary = config
+                .ocrmypdf_path
+                .as_deref()
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or(DEFAULT_OCRMYPDF_BINARY);
+            info!("Running OCR on {:?} with {}", pdf_path, binary);
+            recognize_with_ocrmypdf(pdf_path, binary).await?
+        }
+    };
+
+    Ok(PdfText {
+        page_count,
+        text,
+        has_text_layer: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::{dictionary, Object, Stream};
+
+    fn write_pdf(path: &Path, pages: usize) {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let mut kids: Vec<Object> = Vec::new();
+        for _ in 0..pages {
+            let content_id = doc.add_object(Stream::new(dictionary! {}, b"BT ET".to_vec()));
+            let page_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => content_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            });
+            kids.push(page_id.into());
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => pages as i64,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_original_backup_path() {
+        assert_eq!(
+            original_backup_path(Path::new("/files/abc/paper.pdf")),
+            PathBuf::from("/files/abc/paper_original.pdf")
+        );
+    }
+
+    #[test]
+    fn test_replace_with_verified_keeps_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("paper.pdf");
+        let candidate = dir.path().join("paper.ocr.tmp.pdf");
+        write_pdf(&pdf, 2);
+        write_pdf(&candidate, 2);
+        let original_bytes = std::fs::read(&pdf).unwrap();
+
+        replace_with_verified(&pdf, &candidate, true).unwrap();
+
+        assert!(!candidate.exists());
+        assert_eq!(
+            std::fs::read(original_backup_path(&pdf)).unwrap(),
+            original_bytes
+        );
+    }
+
+    #[test]
+    fn test_replace_with_verified_rejects_page_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("paper.pdf");
+        let candidate = dir.path().join("paper.ocr.tmp.pdf");
+        write_pdf(&pdf, 3);
+        write_pdf(&candidate, 1);
+        let original_bytes = std::fs::read(&pdf).unwrap();
+
+        assert!(replace_with_verified(&pdf, &candidate, true).is_err());
+        assert_eq!(std::fs::read(&pdf).unwrap(), original_bytes);
+        assert!(!original_backup_path(&pdf).exists());
+    }
+
+    #[test]
+    fn test_count_text_operators() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("paper.pdf");
+        write_pdf(&pdf, 2);
+        assert_eq!(count_text_operators(&load_pdf(&pdf).unwrap()), 0);
+
+        let mut doc = load_pdf(&pdf).unwrap();
+        let page_id = *doc.get_pages().values().next().unwrap();
+        doc.change_page_content(page_id, b"BT /F1 12 Tf (Hello) Tj ET".to_vec())
+            .unwrap();
+        assert_eq!(count_text_operators(&doc), 1);
+    }
+
+    #[test]
+    fn test_parse_ocr_api_response() {
+        assert_eq!(parse_ocr_api_response(r#"{"text":"Hello"}"#), "Hello");
+        assert_eq!(parse_ocr_api_response("Plain text"), "Plain text");
+    }
+}
@@ -0,0 +1,343 @@
+//! Open access status from Unpaywall
+//!
+//! Lookups are cached per paper in `open_access_cache` for
+//! [`OA_CACHE_TTL_DAYS`] days. A cached entry is also stale when the paper's
+//! DOI has changed since it was looked up.
+
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::database::entities::open_access_cache;
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::papers::importer::rate_limiter::RateLimiter;
+use crate::repository::open_access_repository::NewOpenAccess;
+use crate::repository::{OpenAccessRepository, PaperRepository};
+use crate::service::citation_count_service::normalize_doi;
+use crate::sys::config::PaperConfig;
+use crate::sys::error::{AppError, Result};
+
+/// Days a cached lookup is used before Unpaywall is asked again
+pub const OA_CACHE_TTL_DAYS: i64 = 7;
+
+const UNPAYWALL_URL: &str = "https://api.unpaywall.org/v2";
+
+/// Papers loaded from the database at a time by `find_papers_with_doi`
+const PAPER_BATCH_SIZE: u64 = 500;
+
+/// Open access status of a DOI as reported by Unpaywall
+#[derive(Debug, Clone)]
+pub struct OpenAccessStatus {
+    pub is_oa: bool,
+    pub oa_status: String,
+    pub oa_url: Option<String>,
+    pub license: Option<String>,
+}
+
+/// Emitted before each paper that has to be looked up
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenAccessProgress {
+    pub current: usize,
+    /// Papers without a fresh cache entry
+    pub total: usize,
+    pub paper_id: String,
+    pub doi: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnpaywallWork {
+    is_oa: bool,
+    oa_status: Option<String>,
+    best_oa_location: Option<UnpaywallLocation>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnpaywallLocation {
+    url: Option<String>,
+    url_for_pdf: Option<String>,
+    license: Option<String>,
+}
+
+/// The contact email Unpaywall requests are sent with
+pub fn unpaywall_email(config: &PaperConfig) -> Result<String> {
+    config
+        .contact_email
+        .as_deref()
+        .map(str::trim)
+        .filter(|email| email.contains('@'))
+        .map(str::to_string)
+        .ok_or_else(|| {
+            AppError::config_error(
+                "paper.contact_email",
+                "Configure a contact email in the settings; Unpaywall requires one",
+            )
+        })
+}
+
+/// Look a DOI up in Unpaywall
+pub async fn fetch_unpaywall(
+    client: &reqwest::Client,
+    email: &str,
+    doi: &str,
+) -> Result<OpenAccessStatus> {
+    let url = format!("{}/{}", UNPAYWALL_URL, normalize_doi(doi));
+    let response = client
+        .get(&url)
+        .query(&[("email", email)])
+        .send()
+        .await
+        .map_err(|e| AppError::network_error(&url, e.to_string()))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(AppError::not_found("Unpaywall record", doi));
+    }
+
+    let work: UnpaywallWork = response
+        .error_for_status()
+        .map_err(|e| AppError::network_error(&url, e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| AppError::network_error(&url, e.to_string()))?;
+
+    let location = work.best_oa_location;
+    Ok(OpenAccessStatus {
+        is_oa: work.is_oa,
+        oa_status: work.oa_status.unwrap_or_else(|| "closed".to_string()),
+        oa_url: location
+            .as_ref()
+            .and_then(|l| l.url_for_pdf.clone().or_else(|| l.url.clone())),
+        license: location.and_then(|l| l.license),
+    })
+}
+
+/// HTTP client for Unpaywall requests, identifying the app by `email`
+pub fn unpaywall_client(email: &str) -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(format!("XuanBrain/0.1.0 (mailto:{})", email))
+        .connect_timeout(std::time::Duration::from_secs(10))
+        .timeout(std::time::Duration::from_secs(30))
+        .build()
+        .map_err(|e| AppError::generic(format!("Failed to create HTTP client: {}", e)))
+}
+
+/// Whether a cached entry can be used for a paper with `doi`
+pub fn is_fresh(entry: &open_access_cache::Model, doi: &str, now: DateTime<Utc>) -> bool {
+    normalize_doi(&entry.doi) == normalize_doi(doi)
+        && entry.updated_at > now - chrono::Duration::days(OA_CACHE_TTL_DAYS)
+}
+
+async fn fetch_and_cache<F, Fut>(
+    db: &DatabaseConnection,
+    paper_id: i64,
+    doi: &str,
+    fetch: &mut F,
+) -> Result<open_access_cache::Model>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<OpenAccessStatus>>,
+{
+    let status = fetch(doi.to_string()).await?;
+    OpenAccessRepository::save(
+        db,
+        NewOpenAccess {
+            paper_id,
+            doi: doi.to_string(),
+            is_oa: status.is_oa,
+            oa_status: status.oa_status,
+            oa_url: status.oa_url,
+            license: status.license,
+        },
+    )
+    .await
+}
+
+/// Open access status of one paper, from the cache when it is fresh
+pub async fn open_access_info<F, Fut>(
+    db: &DatabaseConnection,
+    paper_id: i64,
+    doi: &str,
+    mut fetch: F,
+) -> Result<open_access_cache::Model>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<OpenAccessStatus>>,
+{
+    let cached = OpenAccessRepository::find_by_paper_ids(db, &[paper_id])
+        .await?
+        .remove(&paper_id);
+    match cached {
+        Some(entry) if is_fresh(&entry, doi, Utc::now()) => Ok(entry),
+        _ => fetch_and_cache(db, paper_id, doi, &mut fetch).await,
+    }
+}
+
+/// Every non-deleted paper that has a DOI, in id order
+pub async fn find_papers_with_doi(db: &DatabaseConnection) -> Result<Vec<Paper>> {
+    let mut papers = Vec::new();
+    let mut last_id = 0;
+    loop {
+        let batch = PaperRepository::find_with_doi_after(db, last_id, PAPER_BATCH_SIZE).await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        last_id = last.id;
+        papers.extend(batch);
+    }
+    Ok(papers)
+}
+
+/// Open access status of `papers`. Fresh cache entries are used as they
+/// are; the other papers are looked up one at a time through
+/// `rate_limiter`. When a lookup fails the stale entry is returned if
+/// there is one, otherwise the paper is left out.
+pub async fn open_access_for_papers<F, Fut, P>(
+    db: &DatabaseConnection,
+    papers: &[Paper],
+    rate_limiter: &RateLimiter,
+    mut fetch: F,
+    mut on_progress: P,
+) -> Result<Vec<open_access_cache::Model>>
+where
+    F: FnMut(String) -> Fut,
+    Fut: Future<Output = Result<OpenAccessStatus>>,
+    P: FnMut(&OpenAccessProgress),
+{
+    let ids: Vec<i64> = papers.iter().map(|p| p.id).collect();
+    let mut cache = OpenAccessRepository::find_by_paper_ids(db, &ids).await?;
+
+    let now = Utc::now();
+    let with_doi = papers
+        .iter()
+        .filter_map(|p| Some((p, p.doi.as_deref().filter(|d| !d.trim().is_empty())?)));
+    let (fresh, stale): (Vec<_>, Vec<_>) = with_doi.partition(|(paper, doi)| {
+        cache
+            .get(&paper.id)
+            .is_some_and(|entry| is_fresh(entry, doi, now))
+    });
+
+    let mut results: Vec<open_access_cache::Model> = fresh
+        .iter()
+        .filter_map(|(paper, _)| cache.remove(&paper.id))
+        .collect();
+
+    let total = stale.len();
+    for (current, (paper, doi)) in stale.into_iter().enumerate() {
+        on_progress(&OpenAccessProgress {
+            current: current + 1,
+            total,
+            paper_id: paper.id.to_string(),
+            doi: doi.to_string(),
+        });
+
+        rate_limiter.acquire().await;
+        match fetch_and_cache(db, paper.id, doi, &mut fetch).await {
+            Ok(entry) => results.push(entry),
+            Err(e) => {
+                warn!("Failed to look up open access status of {}: {}", doi, e);
+                results.extend(cache.remove(&paper.id));
+            }
+        }
+    }
+
+    info!(
+        "Open access status of {} papers, {} looked up",
+        results.len(),
+        total
+    );
+    results.sort_by_key(|entry| entry.paper_id);
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    fn status(is_oa: bool) -> OpenAccessStatus {
+        OpenAccessStatus {
+            is_oa,
+            oa_status: if is_oa { "gold" } else { "closed" }.to_string(),
+            oa_url: is_oa.then(|| "https://example.org/paper.pdf".to_string()),
+            license: is_oa.then(|| "cc-by".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_unpaywall_needs_a_contact_email() {
+        let mut config = PaperConfig::default();
+        assert!(matches!(
+            unpaywall_email(&config),
+            Err(AppError::ConfigError { .. })
+        ));
+
+        config.contact_email = Some(" me@example.org ".to_string());
+        assert_eq!(unpaywall_email(&config).unwrap(), "me@example.org");
+    }
+
+    #[tokio::test]
+    async fn test_only_stale_papers_are_fetched() {
+        let db = test_db().await;
+        let cached = PaperFixture::new("Cached")
+            .with_doi("10.1000/cached")
+            .insert(&db)
+            .await;
+        let new = PaperFixture::new("New")
+            .with_doi("10.1000/new")
+            .insert(&db)
+            .await;
+        PaperFixture::new("No DOI").insert(&db).await;
+
+        open_access_info(&db, cached.id, "10.1000/cached", |_| async {
+            Ok(status(true))
+        })
+        .await
+        .unwrap();
+
+        let papers = find_papers_with_doi(&db).await.unwrap();
+        assert_eq!(papers.len(), 2);
+
+        let mut fetched = Vec::new();
+        let results = open_access_for_papers(
+            &db,
+            &papers,
+            &RateLimiter::new(std::time::Duration::ZERO),
+            |doi| {
+                fetched.push(doi);
+                async { Ok(status(false)) }
+            },
+            |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(fetched, vec!["10.1000/new"]);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_oa);
+        assert_eq!(results[1].paper_id, new.id);
+        assert_eq!(results[1].oa_status, "closed");
+    }
+
+    #[test]
+    fn test_cache_is_stale_after_doi_change() {
+        let now = Utc::now();
+        let entry = open_access_cache::Model {
+            id: 1,
+            paper_id: 1,
+            doi: "10.1000/A".to_string(),
+            is_oa: false,
+            oa_status: "closed".to_string(),
+            oa_url: None,
+            license: None,
+            updated_at: now - chrono::Duration::days(1),
+        };
+        assert!(is_fresh(&entry, "10.1000/a", now));
+        assert!(!is_fresh(&entry, "10.1000/b", now));
+        assert!(!is_fresh(
+            &entry,
+            "10.1000/a",
+            now + chrono::Duration::days(7)
+        ));
+    }
+}
@@ -0,0 +1,150 @@
+//! Grouping the library by when papers were added
+//!
+//! Papers fall into one of four buckets by `created_at`: today, earlier
+//! this week, earlier this month and before that. Weeks start on Monday.
+//! Boundaries are midnights in the user's time zone, given as an offset
+//! from UTC, and the buckets are filtered in the database.
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::PaperRepository;
+use crate::sys::error::{AppError, Result};
+
+/// Largest UTC offset in minutes, either way
+pub const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddedBucket {
+    Today,
+    /// This week before today
+    ThisWeek,
+    /// This month before this week
+    ThisMonth,
+    Earlier,
+}
+
+impl AddedBucket {
+    /// Newest first
+    pub const ALL: [AddedBucket; 4] = [
+        AddedBucket::Today,
+        AddedBucket::ThisWeek,
+        AddedBucket::ThisMonth,
+        AddedBucket::Earlier,
+    ];
+}
+
+/// Time zone of a UTC offset in minutes east of UTC
+pub fn time_zone(utc_offset_minutes: i32) -> Result<FixedOffset> {
+    if utc_offset_minutes.abs() > MAX_UTC_OFFSET_MINUTES {
+        return Err(AppError::validation(
+            "utc_offset_minutes",
+            format!(
+                "Must be between -{} and {}",
+                MAX_UTC_OFFSET_MINUTES, MAX_UTC_OFFSET_MINUTES
+            ),
+        ));
+    }
+    FixedOffset::east_opt(utc_offset_minutes * 60)
+        .ok_or_else(|| AppError::validation("utc_offset_minutes", "Invalid UTC offset"))
+}
+
+/// `[from, until)` of `bucket` at `now`; `None` is an open bound. The
+/// range of `ThisMonth` is empty while this week started last month.
+pub fn bucket_range(
+    bucket: AddedBucket,
+    now: DateTime<Utc>,
+    zone: FixedOffset,
+) -> (Option<DateTime<Utc>>, Option<DateTime<Utc>>) {
+    let today = now.with_timezone(&zone).date_naive();
+    let week = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let month = today.with_day(1).unwrap_or(today);
+    let midnight = |date: NaiveDate| {
+        let local = date.and_time(NaiveTime::MIN);
+        Utc.from_utc_datetime(&(local - zone))
+    };
+
+    let (today, week, month) = (midnight(today), midnight(week), midnight(month));
+    match bucket {
+        AddedBucket::Today => (Some(today), None),
+        AddedBucket::ThisWeek => (Some(week), Some(today)),
+        AddedBucket::ThisMonth => (Some(month.min(week)), Some(week)),
+        AddedBucket::Earlier => (None, Some(month.min(week))),
+    }
+}
+
+/// One page of the papers in `bucket`, newest first, and the number of
+/// papers in it
+pub async fn papers_in_bucket(
+    db: &DatabaseConnection,
+    bucket: AddedBucket,
+    now: DateTime<Utc>,
+    zone: FixedOffset,
+    offset: u64,
+    limit: u64,
+) -> Result<(Vec<Paper>, i64)> {
+    let (from, until) = bucket_range(bucket, now, zone);
+    PaperRepository::find_created_between_paginated(db, from, until, offset, limit).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(timestamp)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_bucket_boundaries_use_local_midnight() {
+        // Thursday 2025-03-13 01:30 in UTC+8, still Wednesday in UTC
+        let now = at("2025-03-12T17:30:00Z");
+        let zone = time_zone(8 * 60).unwrap();
+
+        assert_eq!(
+            bucket_range(AddedBucket::Today, now, zone),
+            (Some(at("2025-03-12T16:00:00Z")), None)
+        );
+        assert_eq!(
+            bucket_range(AddedBucket::ThisWeek, now, zone),
+            (
+                Some(at("2025-03-09T16:00:00Z")),
+                Some(at("2025-03-12T16:00:00Z"))
+            )
+        );
+        assert_eq!(
+            bucket_range(AddedBucket::ThisMonth, now, zone),
+            (
+                Some(at("2025-02-28T16:00:00Z")),
+                Some(at("2025-03-09T16:00:00Z"))
+            )
+        );
+        assert_eq!(
+            bucket_range(AddedBucket::Earlier, now, zone),
+            (None, Some(at("2025-02-28T16:00:00Z")))
+        );
+
+        // In UTC it is Wednesday, so Thursday's papers are not from today
+        let (today, _) = bucket_range(AddedBucket::Today, now, time_zone(0).unwrap());
+        assert_eq!(today, Some(at("2025-03-12T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_this_month_is_empty_when_the_week_started_last_month() {
+        // Sunday 2025-06-01; the week started on Monday 2025-05-26
+        let now = at("2025-06-01T12:00:00Z");
+        let zone = time_zone(0).unwrap();
+        let (from, until) = bucket_range(AddedBucket::ThisMonth, now, zone);
+        assert_eq!(from, until);
+        assert_eq!(
+            bucket_range(AddedBucket::Earlier, now, zone).1,
+            Some(at("2025-05-26T00:00:00Z"))
+        );
+        assert!(time_zone(15 * 60).is_err());
+    }
+}
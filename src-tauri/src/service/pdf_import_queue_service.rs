@@ -0,0 +1,367 @@
+//! Queue for importing many PDFs at once
+//!
+//! Files dropped on the window are queued and imported a few at a time: at
+//! most `GROBID_CONCURRENCY` GROBID extractions run at once across all
+//! batches, and saving a paper does not hold a GROBID slot. Every file moves
+//! through `queued → extracting → saving → done`, or ends in `failed` without
+//! stopping the others. The queue lives in memory, so the UI can ask for its
+//! state again after a reload.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+
+use crate::sys::error::{AppError, Result};
+
+/// GROBID requests in flight at once
+pub const GROBID_CONCURRENCY: usize = 2;
+
+/// Where a file is in the import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PdfImportStage {
+    Queued,
+    Extracting,
+    Saving,
+    Done,
+    Failed,
+}
+
+impl PdfImportStage {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+/// One queued file
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfImportItem {
+    pub id: u64,
+    pub file_path: String,
+    pub stage: PdfImportStage,
+    /// Id of the created paper, once done
+    pub paper_id: Option<String>,
+    /// Done without creating a paper because its DOI is already in the
+    /// library
+    pub already_exists: bool,
+    /// Result message when done, error message when failed
+    pub message: Option<String>,
+}
+
+/// What saving a file produced
+#[derive(Debug, Clone)]
+pub struct PdfSaveOutcome {
+    pub paper_id: Option<String>,
+    pub already_exists: bool,
+    pub message: String,
+}
+
+/// Snapshot of the queue
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfImportQueueStatus {
+    pub items: Vec<PdfImportItem>,
+    pub queued: usize,
+    /// Files being extracted or saved
+    pub in_progress: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+/// Outcome of one `import_pdfs_batch` call
+#[derive(Debug, Clone, Serialize)]
+pub struct PdfBatchImportReport {
+    pub total: usize,
+    pub imported: usize,
+    /// Files whose DOI was already in the library
+    pub skipped: usize,
+    pub failed: usize,
+    /// Final state of every file in the batch, in the order given
+    pub items: Vec<PdfImportItem>,
+}
+
+#[derive(Default)]
+struct QueueItems {
+    items: Vec<PdfImportItem>,
+    next_id: u64,
+}
+
+/// In-memory import queue, shared by all batches
+#[derive(Clone)]
+pub struct PdfImportQueue {
+    items: Arc<Mutex<QueueItems>>,
+    grobid_slots: Arc<Semaphore>,
+}
+
+impl Default for PdfImportQueue {
+    fn default() -> Self {
+        Self {
+            items: Arc::default(),
+            grobid_slots: Arc::new(Semaphore::new(GROBID_CONCURRENCY)),
+        }
+    }
+}
+
+impl PdfImportQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue `file_paths`. Files of earlier batches are forgotten once every
+    /// one of them has finished.
+    pub fn enqueue(&self, file_paths: &[String]) -> Vec<PdfImportItem> {
+        let mut queue = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if queue.items.iter().all(|item| item.stage.is_finished()) {
+            queue.items.clear();
+        }
+
+        let mut added = Vec::with_capacity(file_paths.len());
+        for file_path in file_paths {
+            queue.next_id += 1;
+            let item = PdfImportItem {
+                id: queue.next_id,
+                file_path: file_path.clone(),
+                stage: PdfImportStage::Queued,
+                paper_id: None,
+                already_exists: false,
+                message: None,
+            };
+            queue.items.push(item.clone());
+            added.push(item);
+        }
+        added
+    }
+
+    pub fn status(&self) -> PdfImportQueueStatus {
+        let queue = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let count = |stages: &[PdfImportStage]| {
+            queue
+                .items
+                .iter()
+                .filter(|item| stages.contains(&item.stage))
+                .count()
+        };
+        PdfImportQueueStatus {
+            queued: count(&[PdfImportStage::Queued]),
+            in_progress: count(&[PdfImportStage::Extracting, PdfImportStage::Saving]),
+            done: count(&[PdfImportStage::Done]),
+            failed: count(&[PdfImportStage::Failed]),
+            items: queue.items.clone(),
+        }
+    }
+
+    /// Apply `change` to the item with `id` and return its new state
+    fn update(&self, id: u64, change: impl FnOnce(&mut PdfImportItem)) -> Option<PdfImportItem> {
+        let mut queue = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let item = queue.items.iter_mut().find(|item| item.id == id)?;
+        change(item);
+        Some(item.clone())
+    }
+
+    /// Import the queued `items`. `extract` runs while holding a GROBID slot
+    /// and its result is passed to `save`. `on_update` is called with every
+    /// change of stage.
+    pub async fn run<M, E, EFut, S, SFut>(
+        &self,
+        items: Vec<PdfImportItem>,
+        extract: E,
+        save: S,
+        on_update: impl Fn(&PdfImportItem),
+    ) -> PdfBatchImportReport
+    where
+        E: Fn(PathBuf) -> EFut,
+        EFut: Future<Output = Result<M>>,
+        S: Fn(PathBuf, M) -> SFut,
+        SFut: Future<Output = Result<PdfSaveOutcome>>,
+    {
+        let ids: Vec<u64> = items.iter().map(|item| item.id).collect();
+        for item in &items {
+            on_update(item);
+        }
+
+        let (extract, save, on_update) = (&extract, &save, &on_update);
+        let finished: Vec<PdfImportItem> = stream::iter(items)
+            .map(|item| async move {
+                let result = self.import_one(&item, extract, save, on_update).await;
+                let finished = self.update(item.id, |queued| match result {
+                    Ok(outcome) => {
+                        queued.stage = PdfImportStage::Done;
+                        queued.paper_id = outcome.paper_id;
+                        queued.already_exists = outcome.already_exists;
+                        queued.message = Some(outcome.message);
+                    }
+                    Err(e) => {
+                        warn!("Failed to import {}: {}", queued.file_path, e);
+                        queued.stage = PdfImportStage::Failed;
+                        queued.message = Some(e.to_string());
+                    }
+                });
+                if let Some(finished) = &finished {
+                    on_update(finished);
+                }
+                finished
+            })
+            .buffer_unordered(GROBID_CONCURRENCY * 2)
+            .filter_map(|item| async move { item })
+            .collect()
+            .await;
+
+        let mut report = PdfBatchImportReport {
+            total: ids.len(),
+            imported: 0,
+            skipped: 0,
+            failed: 0,
+            items: Vec::with_capacity(ids.len()),
+        };
+        for id in ids {
+            let Some(item) = finished.iter().find(|item| item.id == id) else {
+                continue;
+            };
+            match item.stage {
+                PdfImportStage::Failed => report.failed += 1,
+                _ if item.already_exists => report.skipped += 1,
+                _ => report.imported += 1,
+            }
+            report.items.push(item.clone());
+        }
+
+        info!(
+            "PDF batch import completed: {} imported, {} skipped, {} failed",
+            report.imported, report.skipped, report.failed
+        );
+        report
+    }
+
+    async fn import_one<M, E, EFut, S, SFut>(
+        &self,
+        item: &PdfImportItem,
+        extract: &E,
+        save: &S,
+        on_update: &impl Fn(&PdfImportItem),
+    ) -> Result<PdfSaveOutcome>
+    where
+        E: Fn(PathBuf) -> EFut,
+        EFut: Future<Output = Result<M>>,
+        S: Fn(PathBuf, M) -> SFut,
+        SFut: Future<Output = Result<PdfSaveOutcome>>,
+    {
+        let path = PathBuf::from(&item.file_path);
+        if !path.is_file() {
+            return Err(AppError::file_system(
+                item.file_path.clone(),
+                "File not found",
+            ));
+        }
+
+        let metadata = {
+            let _slot = self
+                .grobid_slots
+                .acquire()
+                .await
+                .map_err(|e| AppError::generic(format!("Import queue closed: {}", e)))?;
+            self.set_stage(item.id, PdfImportStage::Extracting, on_update);
+            extract(path.clone()).await?
+        };
+
+        self.set_stage(item.id, PdfImportStage::Saving, on_update);
+        save(path, metadata).await
+    }
+
+    fn set_stage(&self, id: u64, stage: PdfImportStage, on_update: &impl Fn(&PdfImportItem)) {
+        if let Some(item) = self.update(id, |item| item.stage = stage) {
+            on_update(&item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failures_do_not_stop_the_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for name in ["a.pdf", "corrupt.pdf", "dup.pdf", "b.pdf"] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, b"%PDF").unwrap();
+            paths.push(path.to_string_lossy().to_string());
+        }
+        paths.push(dir.path().join("missing.pdf").to_string_lossy().to_string());
+
+        let queue = PdfImportQueue::new();
+        let items = queue.enqueue(&paths);
+        assert_eq!(queue.status().queued, 5);
+
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let updates = Mutex::new(Vec::new());
+
+        let report = queue
+            .run(
+                items,
+                |path| {
+                    let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+                    async move {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        let name = path.file_name().unwrap().to_string_lossy().to_string();
+                        if name == "corrupt.pdf" {
+                            return Err(AppError::pdf_error("extract", "not a PDF"));
+                        }
+                        Ok(name)
+                    }
+                },
+                |_, name| async move {
+                    Ok(PdfSaveOutcome {
+                        paper_id: (name != "dup.pdf").then(|| name.clone()),
+                        already_exists: name == "dup.pdf",
+                        message: name,
+                    })
+                },
+                |item| updates.lock().unwrap().push((item.id, item.stage)),
+            )
+            .await;
+
+        assert!(max_in_flight.load(Ordering::SeqCst) <= GROBID_CONCURRENCY);
+        assert_eq!(
+            (report.total, report.imported, report.skipped, report.failed),
+            (5, 2, 1, 2)
+        );
+        assert_eq!(report.items[0].paper_id.as_deref(), Some("a.pdf"));
+        assert_eq!(report.items[1].stage, PdfImportStage::Failed);
+
+        let stages: Vec<PdfImportStage> = updates
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(id, _)| *id == report.items[0].id)
+            .map(|(_, stage)| *stage)
+            .collect();
+        assert_eq!(
+            stages,
+            [
+                PdfImportStage::Queued,
+                PdfImportStage::Extracting,
+                PdfImportStage::Saving,
+                PdfImportStage::Done
+            ]
+        );
+
+        let status = queue.status();
+        assert_eq!((status.done, status.failed, status.queued), (3, 2, 0));
+
+        // A new batch starts from an empty queue once the last one finished
+        queue.enqueue(&paths[..1]);
+        assert_eq!(queue.status().items.len(), 1);
+    }
+}
@@ -0,0 +1,165 @@
+//! Quiet hours and focus mode
+//!
+//! While either is active, scheduled background work such as automatic
+//! backups is deferred. Schedulers re-check whether a run is due when they
+//! resume, so a deferred run happens once rather than once per skipped tick.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use chrono::{Local, NaiveTime};
+use tracing::warn;
+
+use crate::sys::config::{AppConfig, QuietHoursConfig};
+use crate::sys::dirs::AppDirs;
+
+/// Manual focus mode toggle, shared between commands and schedulers.
+/// Not persisted; focus mode is off after a restart.
+#[derive(Clone, Default)]
+pub struct FocusModeState {
+    enabled: Arc<AtomicBool>,
+}
+
+impl FocusModeState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn get(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+}
+
+/// Why background work is currently paused
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PauseReason {
+    FocusMode,
+    QuietHours { until: NaiveTime },
+}
+
+impl std::fmt::Display for PauseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PauseReason::FocusMode => write!(f, "paused (focus mode)"),
+            PauseReason::QuietHours { until } => {
+                write!(f, "paused (quiet hours until {})", until.format("%H:%M"))
+            }
+        }
+    }
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()
+}
+
+/// End of the quiet window containing `now`, if any.
+///
+/// A window whose end is earlier than its start crosses midnight. Windows
+/// with an unparsable or equal start and end are ignored.
+pub fn quiet_until(config: &QuietHoursConfig, now: NaiveTime) -> Option<NaiveTime> {
+    if !config.enabled {
+        return None;
+    }
+
+    let (Some(start), Some(end)) = (parse_time(&config.start), parse_time(&config.end)) else {
+        warn!(
+            "Ignoring quiet hours with invalid times {:?}-{:?}",
+            config.start, config.end
+        );
+        return None;
+    };
+
+    let inside = if start < end {
+        start <= now && now < end
+    } else if start > end {
+        now >= start || now < end
+    } else {
+        false
+    };
+
+    inside.then_some(end)
+}
+
+/// Reason background work should be paused at `now`, if any
+pub fn pause_reason(
+    config: &QuietHoursConfig,
+    focus_mode: bool,
+    now: NaiveTime,
+) -> Option<PauseReason> {
+    if focus_mode {
+        return Some(PauseReason::FocusMode);
+    }
+    quiet_until(config, now).map(|until| PauseReason::QuietHours { until })
+}
+
+/// Reason background work should be paused right now, reading the current
+/// quiet hours from the settings
+pub fn current_pause_reason(
+    app_dirs: &AppDirs,
+    focus_mode: &FocusModeState,
+) -> Option<PauseReason> {
+    let config = AppConfig::load(&app_dirs.config)
+        .map(|c| c.quiet_hours)
+        .unwrap_or_default();
+    pause_reason(&config, focus_mode.get(), Local::now().time())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start: &str, end: &str) -> QuietHoursConfig {
+        QuietHoursConfig {
+            enabled: true,
+            start: start.to_string(),
+            end: end.to_string(),
+        }
+    }
+
+    fn at(value: &str) -> NaiveTime {
+        parse_time(value).unwrap()
+    }
+
+    #[test]
+    fn test_quiet_until_same_day_window() {
+        let config = window("12:00", "14:00");
+        assert_eq!(quiet_until(&config, at("11:59")), None);
+        assert_eq!(quiet_until(&config, at("12:00")), Some(at("14:00")));
+        assert_eq!(quiet_until(&config, at("13:30")), Some(at("14:00")));
+        assert_eq!(quiet_until(&config, at("14:00")), None);
+    }
+
+    #[test]
+    fn test_quiet_until_window_crossing_midnight() {
+        let config = window("22:00", "07:00");
+        assert_eq!(quiet_until(&config, at("21:00")), None);
+        assert_eq!(quiet_until(&config, at("23:30")), Some(at("07:00")));
+        assert_eq!(quiet_until(&config, at("03:00")), Some(at("07:00")));
+        assert_eq!(quiet_until(&config, at("07:00")), None);
+    }
+
+    #[test]
+    fn test_pause_reason() {
+        let mut config = window("12:00", "14:00");
+        assert_eq!(
+            pause_reason(&config, false, at("13:00"))
+                .unwrap()
+                .to_string(),
+            "paused (quiet hours until 14:00)"
+        );
+        assert_eq!(
+            pause_reason(&config, true, at("09:00")),
+            Some(PauseReason::FocusMode)
+        );
+
+        config.enabled = false;
+        assert_eq!(pause_reason(&config, false, at("13:00")), None);
+
+        let invalid = window("noon", "14:00");
+        assert_eq!(quiet_until(&invalid, at("13:00")), None);
+    }
+}
@@ -0,0 +1,182 @@
+//! Reading statistics over the last days
+//!
+//! Built from reading sessions, reading progress and the `viewed` entries of
+//! the activity log. A paper counts as read in a week when it was opened or
+//! had a reading session that week. Days and weeks are in UTC.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+
+use crate::database::DatabaseConnection;
+use crate::repository::stats_repository::NamedCount;
+use crate::repository::{ActivityRepository, ReadingProgressRepository, StatsRepository};
+use crate::service::activity_service::{ACTION_VIEWED, ENTITY_PAPER};
+use crate::sys::error::Result;
+
+/// Categories listed in `ReadingStatistics::top_categories`
+pub const TOP_CATEGORY_LIMIT: u64 = 10;
+
+#[derive(Debug, Clone)]
+pub struct ReadingStatistics {
+    /// Distinct papers opened or read
+    pub papers_opened: u64,
+    /// Papers read to their last page
+    pub papers_finished: u64,
+    /// Page changes across all reading sessions
+    pub pages_read: u64,
+    pub average_session_minutes: f32,
+    /// `YYYY-MM-DD` of the day with the most reading time
+    pub most_active_day: Option<String>,
+    /// `(YYYY-Www, papers)` for every ISO week in the period, oldest first
+    pub papers_per_week: Vec<(String, u64)>,
+    /// Categories of the papers opened or read, most papers first
+    pub top_categories: Vec<NamedCount>,
+}
+
+/// ISO week of `date` as `YYYY-Www`
+pub fn iso_week(date: DateTime<Utc>) -> String {
+    let week = date.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// ISO weeks from `since` up to and including the week of `now`, oldest first
+fn weeks_between(since: DateTime<Utc>, now: DateTime<Utc>) -> Vec<String> {
+    let mut weeks = Vec::new();
+    let mut day = since;
+    while day <= now {
+        weeks.push(iso_week(day));
+        day += Duration::days(7);
+    }
+    let current = iso_week(now);
+    if weeks.last() != Some(&current) {
+        weeks.push(current);
+    }
+    weeks
+}
+
+/// Reading statistics of the `period_days` days up to `now`
+pub async fn reading_statistics(
+    db: &DatabaseConnection,
+    period_days: u32,
+    now: DateTime<Utc>,
+) -> Result<ReadingStatistics> {
+    let since = now - Duration::days(period_days as i64);
+    let sessions = ReadingProgressRepository::sessions_since(db, since).await?;
+    let views = ActivityRepository::find_since(db, ENTITY_PAPER, ACTION_VIEWED, since).await?;
+    let papers_finished = ReadingProgressRepository::count_finished_since(db, since).await?;
+
+    let reads: Vec<(i64, DateTime<Utc>)> = sessions
+        .iter()
+        .map(|s| (s.paper_id, s.started_at))
+        .chain(views.iter().map(|v| (v.entity_id, v.created_at)))
+        .collect();
+    let opened: BTreeSet<i64> = reads.iter().map(|(id, _)| *id).collect();
+
+    let mut per_week: HashMap<String, HashSet<i64>> = HashMap::new();
+    for (paper_id, at) in &reads {
+        per_week.entry(iso_week(*at)).or_default().insert(*paper_id);
+    }
+    let papers_per_week = weeks_between(since, now)
+        .into_iter()
+        .map(|week| {
+            let papers = per_week.get(&week).map_or(0, |p| p.len() as u64);
+            (week, papers)
+        })
+        .collect();
+
+    let mut per_day: HashMap<NaiveDate, (Duration, usize)> = HashMap::new();
+    let mut total_length = Duration::zero();
+    for session in &sessions {
+        let length = session.ended_at - session.started_at;
+        total_length += length;
+        let day = per_day
+            .entry(session.started_at.date_naive())
+            .or_insert((Duration::zero(), 0));
+        day.0 += length;
+        day.1 += 1;
+    }
+    let most_active_day = per_day
+        .into_iter()
+        .max_by_key(|(date, (length, count))| (*length, *count, Reverse(*date)))
+        .map(|(date, _)| date.format("%Y-%m-%d").to_string());
+    let average_session_minutes = if sessions.is_empty() {
+        0.0
+    } else {
+        total_length.num_seconds() as f32 / 60.0 / sessions.len() as f32
+    };
+
+    let paper_ids: Vec<i64> = opened.iter().copied().collect();
+    let top_categories =
+        StatsRepository::top_categories_of(db, &paper_ids, TOP_CATEGORY_LIMIT).await?;
+
+    Ok(ReadingStatistics {
+        papers_opened: opened.len() as u64,
+        papers_finished,
+        pages_read: sessions.iter().map(|s| s.pages_read.max(0) as u64).sum(),
+        average_session_minutes,
+        most_active_day,
+        papers_per_week,
+        top_categories,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[tokio::test]
+    async fn test_reading_statistics() {
+        let db = test_db().await;
+        let read = PaperFixture::new("Read to the end")
+            .with_category("Systems")
+            .insert(&db)
+            .await;
+        let opened = PaperFixture::new("Only opened")
+            .with_category("Systems")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Untouched").insert(&db).await;
+
+        // Whole seconds so timestamps compare equal after the SQLite round trip
+        let now = DateTime::from_timestamp(Utc::now().timestamp(), 0).unwrap();
+        let start = now - Duration::hours(2);
+        ReadingProgressRepository::record(&db, read.id, 1, 20, start)
+            .await
+            .unwrap();
+        ReadingProgressRepository::record(&db, read.id, 20, 20, start + Duration::minutes(10))
+            .await
+            .unwrap();
+        ActivityRepository::record(&db, ENTITY_PAPER, opened.id, ACTION_VIEWED)
+            .await
+            .unwrap();
+
+        let stats = reading_statistics(&db, 7, now + Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(stats.papers_opened, 2);
+        assert_eq!(stats.papers_finished, 1);
+        assert_eq!(stats.pages_read, 1);
+        assert_eq!(stats.average_session_minutes, 10.0);
+        assert_eq!(
+            stats.most_active_day,
+            Some(start.format("%Y-%m-%d").to_string())
+        );
+        assert_eq!(
+            stats.papers_per_week.last().unwrap().0,
+            iso_week(now + Duration::minutes(1))
+        );
+        assert_eq!(stats.papers_per_week.iter().map(|(_, n)| n).sum::<u64>(), 2);
+        assert_eq!(stats.top_categories[0].name, "Systems");
+        assert_eq!(stats.top_categories[0].count, 2);
+
+        let empty = reading_statistics(&db, 7, now + Duration::days(30))
+            .await
+            .unwrap();
+        assert_eq!(empty.papers_opened, 0);
+        assert_eq!(empty.most_active_day, None);
+        assert_eq!(empty.papers_per_week.len(), 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! "Related papers" for the paper detail view
+//!
+//! Text similarity comes from the paper's stored embedding when it has one
+//! and from a BM25 query over its title keywords otherwise, so the feature
+//! works before embeddings have ever been computed. Shared authors and
+//! shared labels add a fixed boost on top, and papers by the same authors
+//! are included even when their text is unrelated.
+
+use std::collections::{BTreeSet, HashMap};
+
+use tracing::info;
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::search_repository::fts_phrase;
+use crate::repository::{
+    AuthorRepository, EmbeddingRepository, LabelRepository, PaperRepository, SearchRepository,
+};
+use crate::service::embedding_service::cosine_similarity;
+use crate::service::keyword_service::tokenize;
+use crate::sys::error::{AppError, Result};
+
+/// Results returned when the caller does not ask for a number
+pub const DEFAULT_RELATED_LIMIT: usize = 10;
+
+/// Most results returned
+pub const MAX_RELATED_LIMIT: usize = 50;
+
+/// Text matches considered before boosts are applied
+const TEXT_CANDIDATES: usize = 50;
+
+/// Added per shared author, for up to `MAX_BOOSTED_OVERLAP` authors
+const AUTHOR_BOOST: f32 = 0.15;
+
+/// Added per shared label, for up to `MAX_BOOSTED_OVERLAP` labels
+const LABEL_BOOST: f32 = 0.05;
+
+const MAX_BOOSTED_OVERLAP: usize = 3;
+
+/// Names listed in a reason before the rest are elided
+const REASON_NAME_COUNT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextBasis {
+    /// Cosine similarity of title+abstract embeddings
+    Embedding,
+    /// BM25 over the paper's title keywords
+    TitleKeywords,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelatedPaper {
+    pub paper: Paper,
+    pub score: f32,
+    pub reason: String,
+}
+
+/// Score and explanation for a candidate with text similarity `text_score`
+/// (0..=1) sharing `shared_authors` and `shared_labels` with the paper
+pub fn score_candidate(
+    text_score: f32,
+    basis: TextBasis,
+    shared_authors: &[String],
+    shared_labels: &[String],
+) -> (f32, String) {
+    let score = text_score
+        + AUTHOR_BOOST * shared_authors.len().min(MAX_BOOSTED_OVERLAP) as f32
+        + LABEL_BOOST * shared_labels.len().min(MAX_BOOSTED_OVERLAP) as f32;
+
+    let mut reasons = Vec::new();
+    if !shared_authors.is_empty() {
+        reasons.push(format!("Shared authors: {}", name_list(shared_authors)));
+    }
+    if !shared_labels.is_empty() {
+        reasons.push(format!("Shared labels: {}", name_list(shared_labels)));
+    }
+    if text_score > 0.0 {
+        reasons.push(
+            match basis {
+                TextBasis::Embedding => "Similar abstract",
+                TextBasis::TitleKeywords => "Similar title keywords",
+            }
+            .to_string(),
+        );
+    }
+
+    (score, reasons.join("; "))
+}
+
+fn name_list(names: &[String]) -> String {
+    let mut listed: Vec<&str> = names
+        .iter()
+        .take(REASON_NAME_COUNT)
+        .map(String::as_str)
+        .collect();
+    if names.len() > REASON_NAME_COUNT {
+        listed.push("…");
+    }
+    listed.join(", ")
+}
+
+/// Text similarity of other papers to `paper`, by paper id. Uses the
+/// stored embeddings of `embedding_model` when the paper has one.
+async fn text_scores(
+    db: &DatabaseConnection,
+    embedding_model: &str,
+    paper: &Paper,
+) -> Result<(HashMap<i64, f32>, TextBasis)> {
+    let vectors = EmbeddingRepository::find_vectors(db, embedding_model).await?;
+    if let Some((_, own)) = vectors.iter().find(|(id, _)| *id == paper.id) {
+        let mut scores: Vec<(i64, f32)> = vectors
+            .iter()
+            .filter(|(id, _)| *id != paper.id)
+            .map(|(id, vector)| (*id, cosine_similarity(own, vector).clamp(0.0, 1.0)))
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scores.truncate(TEXT_CANDIDATES);
+        return Ok((scores.into_iter().collect(), TextBasis::Embedding));
+    }
+
+    let mut words = tokenize(&paper.title);
+    words.sort();
+    words.dedup();
+    if words.is_empty() {
+        return Ok((HashMap::new(), TextBasis::TitleKeywords));
+    }
+
+    // Quote every word so punctuation in titles cannot break the FTS5 syntax
+    let query = words
+        .iter()
+        .map(|w| fts_phrase(w))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    let scores = SearchRepository::fts_search(db, &query, Some(TEXT_CANDIDATES as u64 + 1))
+        .await?
+        .into_iter()
+        .filter(|(model, _)| model.id != paper.id)
+        .map(|(model, score)| (model.id, (score / 100.0) as f32))
+        .collect();
+    Ok((scores, TextBasis::TitleKeywords))
+}
+
+/// Papers related to `paper_id`, best first. The paper itself and trashed
+/// papers are never returned.
+pub async fn related_papers(
+    db: &DatabaseConnection,
+    embedding_model: &str,
+    paper_id: i64,
+    limit: usize,
+) -> Result<Vec<RelatedPaper>> {
+    let limit = limit.clamp(1, MAX_RELATED_LIMIT);
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let (text_scores, basis) = text_scores(db, embedding_model, &paper).await?;
+
+    // Papers by the same authors, with the names they share
+    let mut papers: HashMap<i64, Paper> = HashMap::new();
+    let mut shared_authors: HashMap<i64, Vec<String>> = HashMap::new();
+    for author in AuthorRepository::get_paper_authors(db, paper_id).await? {
+        for other in PaperRepository::find_by_author(db, author.id).await? {
+            if other.id == paper_id {
+                continue;
+            }
+            shared_authors
+                .entry(other.id)
+                .or_default()
+                .push(author.full_name());
+            papers.insert(other.id, other);
+        }
+    }
+
+    let candidate_ids: Vec<i64> = text_scores
+        .keys()
+        .chain(shared_authors.keys())
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let own_labels: BTreeSet<i64> = LabelRepository::get_paper_labels(db, paper_id)
+        .await?
+        .into_iter()
+        .map(|l| l.id)
+        .collect();
+    let candidate_labels = LabelRepository::get_paper_labels_batch(db, &candidate_ids).await?;
+
+    let mut scored: Vec<(i64, f32, String)> = candidate_ids
+        .into_iter()
+        .map(|id| {
+            let labels: Vec<String> = candidate_labels
+                .get(&id)
+                .map(|labels| {
+                    labels
+                        .iter()
+                        .filter(|l| own_labels.contains(&l.id))
+                        .map(|l| l.name.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let (score, reason) = score_candidate(
+                text_scores.get(&id).copied().unwrap_or(0.0),
+                basis,
+                shared_authors.get(&id).map(Vec::as_slice).unwrap_or(&[]),
+                &labels,
+            );
+            (id, score, reason)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut related = Vec::with_capacity(limit);
+    for (id, score, reason) in scored {
+        if related.len() == limit {
+            break;
+        }
+        let paper = match papers.remove(&id) {
+            Some(paper) => paper,
+            None => match PaperRepository::find_by_id(db, id).await? {
+                Some(paper) if paper.deleted_at.is_none() => paper,
+                _ => continue,
+            },
+        };
+        related.push(RelatedPaper {
+            paper,
+            score,
+            reason,
+        });
+    }
+
+    info!(
+        "Found {} related papers for paper {} ({:?})",
+        related.len(),
+        paper_id,
+        basis
+    );
+    Ok(related)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_score_candidate() {
+        let authors = vec!["Ada Lovelace".to_string()];
+        let (score, reason) = score_candidate(0.5, TextBasis::Embedding, &authors, &[]);
+        assert!((score - 0.65).abs() < 1e-6);
+        assert_eq!(reason, "Shared authors: Ada Lovelace; Similar abstract");
+
+        let labels: Vec<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+        let (score, reason) = score_candidate(0.0, TextBasis::TitleKeywords, &[], &labels);
+        assert!((score - 3.0 * LABEL_BOOST).abs() < 1e-6);
+        assert_eq!(reason, "Shared labels: a, b, c, …");
+    }
+
+    #[tokio::test]
+    async fn test_related_papers_boosts_shared_authors() {
+        let db = test_db().await;
+        let paper = PaperFixture::new("Protein folding dynamics")
+            .with_authors(&["Ada Lovelace"])
+            .insert(&db)
+            .await;
+        let by_same_author = PaperFixture::new("Analytical engines")
+            .with_authors(&["Ada Lovelace"])
+            .insert(&db)
+            .await;
+        let similar_title = PaperFixture::new("Folding of small proteins")
+            .insert(&db)
+            .await;
+        PaperFixture::new("Protein folding in the trash")
+            .deleted()
+            .insert(&db)
+            .await;
+        PaperFixture::new("Graph networks").insert(&db).await;
+
+        let related = related_papers(&db, "unused-model", paper.id, 10)
+            .await
+            .unwrap();
+        let ids: Vec<i64> = related.iter().map(|r| r.paper.id).collect();
+        assert!(ids.contains(&by_same_author.id));
+        assert!(ids.contains(&similar_title.id));
+        assert_eq!(ids.len(), 2);
+        let author_hit = related
+            .iter()
+            .find(|r| r.paper.id == by_same_author.id)
+            .unwrap();
+        assert!(author_hit
+            .reason
+            .starts_with("Shared authors: Ada Lovelace"));
+    }
+}
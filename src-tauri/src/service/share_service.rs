@@ -0,0 +1,309 @@
+//! Temporary sharing of rendered paper notes
+//!
+//! A shared page is rendered once into a standalone HTML document (inline
+//! CSS, no external assets) and kept in memory under a random token. The
+//! Axum server serves it at `/share/{token}` a single time, or until the
+//! TTL expires, whichever comes first.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde_json::Value;
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::service::attachment_service::find_pdf_path;
+use crate::sys::error::Result;
+
+/// Longest lifetime a share link may have
+pub const MAX_SHARE_TTL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// A highlight taken from the PDF annotations sidecar
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    pub page: Option<u64>,
+    pub text: Option<String>,
+    pub comment: Option<String>,
+}
+
+struct SharedPage {
+    html: String,
+    expires_at: DateTime<Utc>,
+}
+
+/// In-memory registry of one-time share tokens.
+/// Shared between Tauri commands and the Axum server.
+#[derive(Clone, Default)]
+pub struct ShareRegistry {
+    pages: Arc<Mutex<HashMap<String, SharedPage>>>,
+}
+
+impl ShareRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a page and return its token and expiry time
+    pub fn register(&self, html: String, ttl: Duration) -> (String, DateTime<Utc>) {
+        let mut bytes = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        let expires_at = Utc::now() + ttl;
+
+        let mut pages = self.pages.lock().unwrap_or_else(|e| e.into_inner());
+        pages.retain(|_, page| page.expires_at > Utc::now());
+        pages.insert(token.clone(), SharedPage { html, expires_at });
+
+        (token, expires_at)
+    }
+
+    /// Consume a token. Returns the page if the token exists and has not expired.
+    pub fn take(&self, token: &str) -> Option<String> {
+        let mut pages = self.pages.lock().unwrap_or_else(|e| e.into_inner());
+        let page = pages.remove(token)?;
+        (page.expires_at > Utc::now()).then_some(page.html)
+    }
+}
+
+/// Escape text for safe inclusion in HTML element content and attributes
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn string_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| value.get(*k).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// Extract highlights from the annotations JSON saved next to a PDF.
+/// Accepts either a top-level array or an object with an `annotations` array;
+/// entries without any text are skipped.
+pub fn parse_highlights(json: &str) -> Vec<Highlight> {
+    let Ok(value) = serde_json::from_str::<Value>(json) else {
+        return Vec::new();
+    };
+
+    let entries = match &value {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(_) => value
+            .get("annotations")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default(),
+        _ => &[],
+    };
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let page = entry
+                .get("page")
+                .or_else(|| entry.get("pageNumber"))
+                .and_then(Value::as_u64)
+                .or_else(|| {
+                    entry
+                        .get("pageIndex")
+                        .and_then(Value::as_u64)
+                        .map(|i| i + 1)
+                });
+            let text = string_field(entry, &["text", "content", "quote", "highlightedText"]);
+            let comment = string_field(entry, &["comment", "note"]);
+
+            (text.is_some() || comment.is_some()).then_some(Highlight {
+                page,
+                text,
+                comment,
+            })
+        })
+        .collect()
+}
+
+/// Highlights from the annotations sidecar of a paper's PDF; empty when the
+/// paper has no PDF or no sidecar
+pub async fn load_highlights(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper: &Paper,
+) -> Result<Vec<Highlight>> {
+    Ok(match find_pdf_path(db, files_dir, paper).await? {
+        Some(pdf_path) => std::fs::read_to_string(pdf_path.with_extension("json"))
+            .map(|json| parse_highlights(&json))
+            .unwrap_or_default(),
+        None => Vec::new(),
+    })
+}
+
+const PAGE_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;\
+max-width:760px;margin:40px auto;padding:0 20px;color:#222;line-height:1.6}\
+h1{font-size:1.6em;margin-bottom:0.2em}.meta{color:#666;margin-top:0}\
+h2{font-size:1.2em;border-bottom:1px solid #ddd;padding-bottom:4px;margin-top:2em}\
+.notes{white-space:pre-wrap}\
+blockquote{margin:1em 0;padding:0.5em 1em;border-left:4px solid #f5c518;background:#fffbea}\
+.page{color:#888;font-size:0.85em}.comment{margin:0.4em 0 0;color:#444}\
+footer{margin-top:3em;color:#aaa;font-size:0.8em}";
+
+/// Render paper metadata, optional private notes and highlights as a
+/// standalone HTML page. All user content is escaped.
+pub fn render_notes_page(
+    paper: &Paper,
+    authors: &[String],
+    notes: Option<&str>,
+    highlights: &[Highlight],
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">");
+    html.push_str(&format!("<title>{}</title>", escape_html(&paper.title)));
+    html.push_str(&format!("<style>{}</style></head><body>", PAGE_STYLE));
+
+    html.push_str(&format!("<h1>{}</h1>", escape_html(&paper.title)));
+
+    let mut meta = Vec::new();
+    if !authors.is_empty() {
+        meta.push(authors.join(", "));
+    }
+    if let Some(venue) = paper
+        .journal_name
+        .as_ref()
+        .or(paper.conference_name.as_ref())
+    {
+        meta.push(venue.clone());
+    }
+    if let Some(year) = paper.publication_year {
+        meta.push(year.to_string());
+    }
+    if !meta.is_empty() {
+        html.push_str(&format!(
+            "<p class=\"meta\">{}</p>",
+            escape_html(&meta.join(" · "))
+        ));
+    }
+    if let Some(doi) = &paper.doi {
+        html.push_str(&format!("<p class=\"meta\">DOI: {}</p>", escape_html(doi)));
+    }
+
+    if let Some(abstract_text) = paper
+        .abstract_text
+        .as_deref()
+        .filter(|a| !a.trim().is_empty())
+    {
+        html.push_str("<h2>Abstract</h2>");
+        html.push_str(&format!("<p>{}</p>", escape_html(abstract_text)));
+    }
+
+    if let Some(notes) = notes.filter(|n| !n.trim().is_empty()) {
+        html.push_str("<h2>Notes</h2>");
+        html.push_str(&format!(
+            "<div class=\"notes\">{}</div>",
+            escape_html(notes)
+        ));
+    }
+
+    if !highlights.is_empty() {
+        html.push_str("<h2>Highlights</h2>");
+        for highlight in highlights {
+            html.push_str("<blockquote>");
+            if let Some(page) = highlight.page {
+                html.push_str(&format!("<div class=\"page\">Page {}</div>", page));
+            }
+            if let Some(text) = &highlight.text {
+                html.push_str(&format!("<div>{}</div>", escape_html(text)));
+            }
+            if let Some(comment) = &highlight.comment {
+                html.push_str(&format!(
+                    "<p class=\"comment\">{}</p>",
+                    escape_html(comment)
+                ));
+            }
+            html.push_str("</blockquote>");
+        }
+    }
+
+    html.push_str("<footer>Shared from Xuan Brain</footer></body></html>");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_paper() -> Paper {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "title": "Attention <Is> All You Need",
+            "abstract_text": null,
+            "doi": "10.1000/xyz",
+            "publication_year": 2017,
+            "publication_date": null,
+            "journal_name": null,
+            "conference_name": "NeurIPS",
+            "volume": null,
+            "issue": null,
+            "pages": null,
+            "url": null,
+            "citation_count": 0,
+            "read_status": "unread",
+            "notes": null,
+            "attachment_path": null,
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "deleted_at": null,
+            "publisher": null,
+            "issn": null,
+            "language": null,
+            "attachment_count": 0
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_escapes_script_in_notes() {
+        let notes = "Pasted from the web: <script>alert('xss')</script> & more";
+        let html = render_notes_page(&sample_paper(), &[], Some(notes), &[]);
+
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("&lt;script&gt;alert(&#39;xss&#39;)&lt;/script&gt; &amp; more"));
+        assert!(html.contains("Attention &lt;Is&gt; All You Need"));
+    }
+
+    #[test]
+    fn test_render_without_notes_only_shows_highlights() {
+        let highlights = parse_highlights(
+            r#"[{"pageIndex": 2, "text": "key result", "comment": "<b>nice</b>"}, {"page": 1}]"#,
+        );
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].page, Some(3));
+
+        let html = render_notes_page(&sample_paper(), &[], None, &highlights);
+        assert!(!html.contains("<h2>Notes</h2>"));
+        assert!(html.contains("key result"));
+        assert!(html.contains("&lt;b&gt;nice&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn test_share_token_is_single_use() {
+        let registry = ShareRegistry::new();
+        let (token, _) = registry.register("<p>hi</p>".to_string(), Duration::minutes(5));
+        assert_eq!(registry.take(&token).as_deref(), Some("<p>hi</p>"));
+        assert!(registry.take(&token).is_none());
+
+        let (expired, _) = registry.register("old".to_string(), Duration::seconds(-1));
+        assert!(registry.take(&expired).is_none());
+    }
+}
@@ -0,0 +1,350 @@
+//! LLM summaries of papers
+//!
+//! The text sent to the model is the paper's PDF text when it can be
+//! extracted, otherwise its abstract. Nothing leaves the machine unless an
+//! LLM provider with an API key is configured.
+
+use std::path::Path;
+
+use lopdf::Document;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::database::entities::paper_ai_summary;
+use crate::database::DatabaseConnection;
+use crate::llm::client::LlmClient;
+use crate::llm::prompts::{abstract_summary_prompt, SUMMARY_DETAILED_PROMPT, SUMMARY_TLDR_PROMPT};
+use crate::models::Paper;
+use crate::repository::{AiSummaryRepository, PaperRepository};
+use crate::service::attachment_service::find_pdf_path;
+use crate::sys::config::{AppConfig, LlmProvider};
+use crate::sys::error::{AppError, Result};
+
+/// Longest text sent to the model, in characters
+pub const MAX_SUMMARY_INPUT_CHARS: usize = 24_000;
+
+/// PDF text shorter than this is treated as a scanned PDF without a text
+/// layer, and the abstract is used instead
+const MIN_FULL_TEXT_CHARS: usize = 500;
+
+/// Style prefix of cached abstract summaries; the sentence limit follows it
+pub const ABSTRACT_STYLE_PREFIX: &str = "abstract_";
+
+/// Longest abstract summary that can be asked for, in sentences
+pub const MAX_ABSTRACT_SUMMARY_SENTENCES: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryStyle {
+    /// Three sentences
+    Tldr,
+    /// Bullet points for method, results and limitations
+    Detailed,
+}
+
+impl SummaryStyle {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SummaryStyle::Tldr => "tldr",
+            SummaryStyle::Detailed => "detailed",
+        }
+    }
+
+    fn system_prompt(&self) -> &'static str {
+        match self {
+            SummaryStyle::Tldr => SUMMARY_TLDR_PROMPT,
+            SummaryStyle::Detailed => SUMMARY_DETAILED_PROMPT,
+        }
+    }
+}
+
+/// Cut `text` to at most `max_chars` characters
+fn truncate_chars(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+/// Text of a PDF's pages, stopping once `max_chars` is reached
+fn extract_pdf_text(path: &Path, max_chars: usize) -> Option<String> {
+    let document = Document::load(path).ok()?;
+    let mut text = String::new();
+    for page in document.get_pages().keys() {
+        if let Ok(page_text) = document.extract_text(&[*page]) {
+            text.push_str(&page_text);
+            text.push('\n');
+        }
+        if text.chars().count() >= max_chars {
+            break;
+        }
+    }
+    Some(truncate_chars(&text, max_chars).to_string())
+}
+
+/// The text to summarize: title plus PDF text when available, otherwise
+/// title plus abstract
+pub async fn summary_input(
+    db: &DatabaseConnection,
+    files_dir: &str,
+    paper: &Paper,
+) -> Result<String> {
+    if let Some(pdf_path) = find_pdf_path(db, files_dir, paper).await? {
+        let full_text = tokio::task::spawn_blocking(move || {
+            extract_pdf_text(&pdf_path, MAX_SUMMARY_INPUT_CHARS)
+        })
+        .await
+        .ok()
+        .flatten()
+        .filter(|t| t.trim().chars().count() >= MIN_FULL_TEXT_CHARS);
+
+        if let Some(full_text) = full_text {
+            return Ok(format!("Title: {}\n\n{}", paper.title, full_text));
+        }
+    }
+
+    let abstract_text = paper
+        .abstract_text
+        .as_deref()
+        .filter(|a| !a.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::validation("paper_id", "Paper has no abstract or readable PDF text")
+        })?;
+    Ok(format!(
+        "Title: {}\n\nAbstract: {}",
+        paper.title,
+        truncate_chars(abstract_text, MAX_SUMMARY_INPUT_CHARS)
+    ))
+}
+
+/// The default LLM provider with its API key from the keychain, if it has
+/// the credentials it needs
+fn configured_provider(config: &AppConfig) -> Result<LlmProvider> {
+    config
+        .system
+        .default_llm_provider()
+        .cloned()
+        .map(LlmProvider::with_stored_api_key)
+        .filter(LlmProvider::has_credentials)
+        .ok_or_else(|| {
+            AppError::config_error(
+                "system.llm_providers",
+                "No LLM provider with an API key is configured",
+            )
+        })
+}
+
+/// Summarize a paper with the configured LLM provider, passing each piece
+/// of the answer to `on_token`, and store the result
+pub async fn summarize_paper<F>(
+    db: &DatabaseConnection,
+    config_dir: &str,
+    files_dir: &str,
+    paper_id: i64,
+    style: SummaryStyle,
+    on_token: F,
+) -> Result<paper_ai_summary::Model>
+where
+    F: FnMut(&str),
+{
+    let config = AppConfig::load(config_dir)?;
+    let provider = configured_provider(&config)?;
+
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+    let input = summary_input(db, files_dir, &paper).await?;
+
+    info!(
+        "Summarizing paper {} ({}) with {}",
+        paper_id,
+        style.as_str(),
+        provider.model_name
+    );
+    let summary = LlmClient::new()
+        .chat_stream(&provider, style.system_prompt(), &input, on_token)
+        .await
+        .map_err(|e| {
+            warn!("Summarizing paper {} failed: {}", paper_id, e);
+            AppError::ai_error("summarize_paper", e.to_string())
+        })?;
+
+    AiSummaryRepository::save(
+        db,
+        paper_id,
+        style.as_str(),
+        summary.trim(),
+        &provider.model_name,
+    )
+    .await
+}
+
+/// Summarize a paper's abstract in at most `max_sentences` sentences.
+///
+/// The result is cached per sentence limit. A cached summary is returned
+/// without calling the LLM unless the paper was edited after it was written.
+pub async fn summarize_abstract(
+    db: &DatabaseConnection,
+    config_dir: &str,
+    paper_id: i64,
+    max_sentences: u32,
+) -> Result<paper_ai_summary::Model> {
+    if !(1..=MAX_ABSTRACT_SUMMARY_SENTENCES).contains(&max_sentences) {
+        return Err(AppError::validation(
+            "max_sentences",
+            format!("Must be between 1 and {}", MAX_ABSTRACT_SUMMARY_SENTENCES),
+        ));
+    }
+
+    let paper = PaperRepository::find_by_id(db, paper_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Paper", paper_id.to_string()))?;
+
+    let style = format!("{}{}", ABSTRACT_STYLE_PREFIX, max_sentences);
+    if let Some(cached) = AiSummaryRepository::find_by_paper(db, paper_id)
+        .await?
+        .into_iter()
+        .find(|s| s.style == style && s.created_at >= paper.updated_at)
+    {
+        info!("Using cached abstract summary of paper {}", paper_id);
+        return Ok(cached);
+    }
+
+    let abstract_text = paper
+        .abstract_text
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .ok_or_else(|| AppError::validation("paper_id", "Paper has no abstract"))?;
+
+    let config = AppConfig::load(config_dir)?;
+    let provider = configured_provider(&config)?;
+
+    info!(
+        "Summarizing abstract of paper {} in {} sentences with {}",
+        paper_id, max_sentences, provider.model_name
+    );
+    let input = format!(
+        "Title: {}\n\nAbstract: {}",
+        paper.title,
+        truncate_chars(abstract_text, MAX_SUMMARY_INPUT_CHARS)
+    );
+    let summary = LlmClient::new()
+        .chat(&provider, &abstract_summary_prompt(max_sentences), &input)
+        .await
+        .map_err(|e| {
+            warn!("Summarizing abstract of paper {} failed: {}", paper_id, e);
+            AppError::ai_error("summarize_paper_abstract", e.to_string())
+        })?;
+
+    AiSummaryRepository::save(db, paper_id, &style, summary.trim(), &provider.model_name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::UpdatePaper;
+    use crate::testing::{test_db, PaperFixture};
+
+    #[test]
+    fn test_truncate_chars_respects_char_boundaries() {
+        assert_eq!(truncate_chars("摘要文本", 2), "摘要");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+
+    #[tokio::test]
+    async fn test_summary_input_falls_back_to_abstract() {
+        let db = test_db().await;
+        let files = tempfile::tempdir().unwrap();
+        let paper = PaperFixture::new("A paper")
+            .with_abstract("We study things.")
+            .insert(&db)
+            .await;
+
+        let input = summary_input(&db, files.path().to_str().unwrap(), &paper)
+            .await
+            .unwrap();
+        assert_eq!(input, "Title: A paper\n\nAbstract: We study things.");
+
+        let bare = PaperFixture::new("No abstract").insert(&db).await;
+        assert!(summary_input(&db, files.path().to_str().unwrap(), &bare)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_summarize_requires_api_key() {
+        let db = test_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().to_str().unwrap();
+        let paper = PaperFixture::new("A paper")
+            .with_abstract("We study things.")
+            .insert(&db)
+            .await;
+
+        let mut config = AppConfig::default();
+        config.system.llm_providers.push(LlmProvider {
+            id: "openai".to_string(),
+            name: "OpenAI".to_string(),
+            api_key: Some(" ".to_string()),
+            base_url: "http://127.0.0.1:9".to_string(),
+            model_name: "gpt-4o-mini".to_string(),
+            is_default: true,
+            ..Default::default()
+        });
+        config.save(config_dir).unwrap();
+
+        let mut tokens = 0;
+        let result = summarize_paper(&db, config_dir, "", paper.id, SummaryStyle::Tldr, |_| {
+            tokens += 1
+        })
+        .await;
+        assert!(matches!(result, Err(AppError::ConfigError { .. })));
+        assert_eq!(tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_abstract_uses_cache_until_paper_changes() {
+        let db = test_db().await;
+        let dir = tempfile::tempdir().unwrap();
+        let config_dir = dir.path().to_str().unwrap();
+        let paper = PaperFixture::new("A paper")
+            .with_abstract("We study things.")
+            .insert(&db)
+            .await;
+
+        assert!(matches!(
+            summarize_abstract(&db, config_dir, paper.id, 0).await,
+            Err(AppError::ValidationError { .. })
+        ));
+
+        AiSummaryRepository::save(&db, paper.id, "abstract_2", "Cached.", "gpt-4o-mini")
+            .await
+            .unwrap();
+        let cached = summarize_abstract(&db, config_dir, paper.id, 2)
+            .await
+            .unwrap();
+        assert_eq!(cached.summary, "Cached.");
+
+        // Another sentence limit or an edited paper needs the LLM, which is
+        // not configured here
+        assert!(matches!(
+            summarize_abstract(&db, config_dir, paper.id, 3).await,
+            Err(AppError::ConfigError { .. })
+        ));
+        PaperRepository::update(
+            &db,
+            paper.id,
+            UpdatePaper {
+                abstract_text: Some("We study other things.".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(matches!(
+            summarize_abstract(&db, config_dir, paper.id, 2).await,
+            Err(AppError::ConfigError { .. })
+        ));
+    }
+}
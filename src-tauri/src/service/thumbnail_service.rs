@@ -0,0 +1,197 @@
+//! PDF thumbnails
+//!
+//! The first page of a paper's PDF is rendered to a PNG by an external
+//! `pdftoppm` binary (poppler-utils). Thumbnails are cached as
+//! `<cache>/<paper_id>_thumb.png` and count as stale once the PDF has been
+//! modified after them, e.g. by saving annotations or embedding OCR text.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use tracing::info;
+
+use crate::sys::error::{AppError, Result};
+
+/// Binary used when `paper.pdftoppm_path` is not configured
+pub const DEFAULT_PDFTOPPM_BINARY: &str = "pdftoppm";
+
+/// Largest thumbnail width or height, in pixels
+pub const MAX_THUMBNAIL_SIZE: u32 = 2048;
+
+/// Every PNG file starts with this signature
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Cached thumbnail of a paper
+pub fn thumbnail_path(cache_dir: &str, paper_id: i64) -> PathBuf {
+    Path::new(cache_dir).join(format!("{}_thumb.png", paper_id))
+}
+
+/// Whether `thumbnail` exists and was written after `pdf` last changed
+pub fn is_fresh(thumbnail: &Path, pdf: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(thumbnail), modified(pdf)) {
+        (Some(thumbnail), Some(pdf)) => thumbnail >= pdf,
+        _ => false,
+    }
+}
+
+/// Width and height of a PNG, read from its header
+pub fn png_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let mut header = [0u8; 24];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map_err(|e| AppError::file_system(path.to_string_lossy().to_string(), e.to_string()))?;
+
+    // The IHDR chunk always comes first, right after the signature
+    if &header[..8] != PNG_SIGNATURE || &header[12..16] != b"IHDR" {
+        return Err(AppError::pdf_error(
+            "thumbnail",
+            "Rendered file is not a PNG",
+        ));
+    }
+    let width = u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
+    let height = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+    Ok((width, height))
+}
+
+/// Render the first page of `pdf` to `output`, scaled to `width` or
+/// `height` pixels with the other side following the aspect ratio.
+/// Returns the size of the PNG.
+async fn run_pdftoppm(
+    binary: &str,
+    pdf: &Path,
+    output: &Path,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> Result<(u32, u32)> {
+    let scale = |size: Option<u32>| size.map_or("-1".to_string(), |s| s.to_string());
+    // pdftoppm appends `.png` to the prefix it is given
+    let prefix = output.with_extension("");
+
+    let result = tokio::process::Command::new(binary)
+        .args(["-png", "-singlefile", "-f", "1", "-l", "1"])
+        .arg("-scale-to-x")
+        .arg(scale(width))
+        .arg("-scale-to-y")
+        .arg(scale(height))
+        .arg(pdf)
+        .arg(&prefix)
+        .output()
+        .await
+        .map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                AppError::pdf_error(
+                    "thumbnail",
+                    format!(
+                        "'{}' was not found; install poppler-utils or set paper.pdftoppm_path",
+                        binary
+                    ),
+                )
+            } else {
+                AppError::pdf_error("thumbnail", format!("Failed to run {}: {}", binary, e))
+            }
+        })?;
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        let detail = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("");
+        return Err(AppError::pdf_error(
+            "thumbnail",
+            format!("{} exited with {}: {}", binary, result.status, detail),
+        ));
+    }
+
+    png_dimensions(output)
+}
+
+/// Render the first page of `pdf` to `target` as a PNG that fits within
+/// `width`×`height` pixels, keeping the page's aspect ratio. Returns the
+/// size of the thumbnail.
+pub async fn render_thumbnail(
+    binary: &str,
+    pdf: &Path,
+    target: &Path,
+    width: u32,
+    height: u32,
+) -> Result<(u32, u32)> {
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            AppError::file_system(parent.to_string_lossy().to_string(), e.to_string())
+        })?;
+    }
+
+    // Render next to the target and rename, so a cached thumbnail is never
+    // half written
+    let temp_path = target.with_extension("tmp.png");
+    let result = async {
+        let mut size = run_pdftoppm(binary, pdf, &temp_path, Some(width), None).await?;
+        if size.1 > height {
+            // Taller than the box: fit the height instead
+            size = run_pdftoppm(binary, pdf, &temp_path, None, Some(height)).await?;
+        }
+        std::fs::rename(&temp_path, target).map_err(|e| {
+            AppError::file_system(target.to_string_lossy().to_string(), e.to_string())
+        })?;
+        Ok::<_, AppError>(size)
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    let (rendered_width, rendered_height) = result?;
+    info!(
+        "Rendered {}x{} thumbnail of {:?}",
+        rendered_width, rendered_height, pdf
+    );
+    Ok((rendered_width, rendered_height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_dimensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = dir.path().join("thumb.png");
+        let mut header = PNG_SIGNATURE.to_vec();
+        header.extend_from_slice(&[0, 0, 0, 13]);
+        header.extend_from_slice(b"IHDR");
+        header.extend_from_slice(&200u32.to_be_bytes());
+        header.extend_from_slice(&283u32.to_be_bytes());
+        std::fs::write(&png, &header).unwrap();
+
+        assert_eq!(png_dimensions(&png).unwrap(), (200, 283));
+
+        let not_png = dir.path().join("thumb.jpg");
+        std::fs::write(&not_png, [0u8; 32]).unwrap();
+        assert!(png_dimensions(&not_png).is_err());
+    }
+
+    #[test]
+    fn test_thumbnail_is_stale_after_pdf_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("paper.pdf");
+        let thumbnail = thumbnail_path(&dir.path().to_string_lossy(), 7);
+        assert!(thumbnail.ends_with("7_thumb.png"));
+        assert!(!is_fresh(&thumbnail, &pdf));
+
+        std::fs::write(&pdf, b"%PDF-1.4").unwrap();
+        std::fs::write(&thumbnail, b"png").unwrap();
+        assert!(is_fresh(&thumbnail, &pdf));
+
+        let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&pdf)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert!(!is_fresh(&thumbnail, &pdf));
+    }
+}
@@ -0,0 +1,181 @@
+//! System tray menu
+//!
+//! Besides Quit, the tray offers clipboard import, opening the library and
+//! a submenu of the most recently added papers. Picking a paper shows the
+//! main window and emits `tray:open-paper` with the paper id. The submenu
+//! is rebuilt whenever `paper:imported` is emitted.
+
+use std::sync::Arc;
+
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Emitter, Listener, Manager, Wry};
+use tracing::warn;
+
+use crate::database::DatabaseConnection;
+use crate::models::Paper;
+use crate::repository::PaperRepository;
+use crate::service::clipboard_capture_service;
+
+const TRAY_ID: &str = "main";
+
+/// Papers listed in the recent papers submenu
+const RECENT_PAPER_COUNT: u64 = 5;
+
+/// Longest paper title shown in the submenu
+const RECENT_TITLE_CHARS: usize = 40;
+
+/// Menu item id prefix of a recent paper, followed by the paper id
+const RECENT_PAPER_PREFIX: &str = "recent_paper:";
+
+/// Build the tray icon and keep its recent papers in sync with imports
+pub fn setup_tray(app: &AppHandle) -> tauri::Result<()> {
+    let menu = build_menu(app, &[])?;
+
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(app.default_window_icon().unwrap().clone())
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id.as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                let app = tray.app_handle();
+                if let Some(window) = app.get_webview_window("main") {
+                    if window.is_visible().unwrap_or(false) {
+                        let _ = window.hide();
+                    } else {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+            }
+        })
+        .build(app)?;
+
+    refresh_recent_papers(app.clone());
+    let handle = app.clone();
+    app.listen_any("paper:imported", move |_| {
+        refresh_recent_papers(handle.clone())
+    });
+
+    Ok(())
+}
+
+fn build_menu(app: &AppHandle, recent_papers: &[Paper]) -> tauri::Result<Menu<Wry>> {
+    let import_i = MenuItem::with_id(app, "import_clipboard", "从剪贴板导入", true, None::<&str>)?;
+    let open_i = MenuItem::with_id(app, "open_library", "打开文献库", true, None::<&str>)?;
+
+    let recent = Submenu::with_id(app, "recent_papers", "最近添加", true)?;
+    if recent_papers.is_empty() {
+        let empty_i =
+            MenuItem::with_id(app, "recent_papers_empty", "暂无文献", false, None::<&str>)?;
+        recent.append(&empty_i)?;
+    }
+    for paper in recent_papers {
+        let paper_i = MenuItem::with_id(
+            app,
+            format!("{}{}", RECENT_PAPER_PREFIX, paper.id),
+            menu_title(&paper.title),
+            true,
+            None::<&str>,
+        )?;
+        recent.append(&paper_i)?;
+    }
+
+    let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &import_i,
+            &open_i,
+            &PredefinedMenuItem::separator(app)?,
+            &recent,
+            &PredefinedMenuItem::separator(app)?,
+            &quit_i,
+        ],
+    )
+}
+
+/// Re-read the recent papers and replace the tray menu
+fn refresh_recent_papers(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // Not managed when the database failed to open at startup
+        let Some(db) = app.try_state::<Arc<DatabaseConnection>>() else {
+            return;
+        };
+        let papers = match PaperRepository::find_all_paginated(&db, 0, RECENT_PAPER_COUNT).await {
+            Ok(papers) => papers,
+            Err(e) => {
+                warn!("Failed to load recent papers for the tray: {}", e);
+                return;
+            }
+        };
+
+        let result = build_menu(&app, &papers).and_then(|menu| match app.tray_by_id(TRAY_ID) {
+            Some(tray) => tray.set_menu(Some(menu)),
+            None => Ok(()),
+        });
+        if let Err(e) = result {
+            warn!("Failed to rebuild tray menu: {}", e);
+        }
+    });
+}
+
+fn handle_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "quit" => app.exit(0),
+        "import_clipboard" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                clipboard_capture_service::capture_and_notify(&app).await
+            });
+        }
+        "open_library" => show_main_window(app),
+        _ => {
+            if let Some(paper_id) = id.strip_prefix(RECENT_PAPER_PREFIX) {
+                show_main_window(app);
+                let _ = app.emit("tray:open-paper", paper_id);
+            }
+        }
+    }
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Paper title shortened to fit a menu
+fn menu_title(title: &str) -> String {
+    if title.chars().count() <= RECENT_TITLE_CHARS {
+        return title.to_string();
+    }
+    let short: String = title.chars().take(RECENT_TITLE_CHARS - 1).collect();
+    format!("{}…", short.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_title() {
+        assert_eq!(
+            menu_title("Attention Is All You Need"),
+            "Attention Is All You Need"
+        );
+        let long = "A".repeat(60);
+        let title = menu_title(&long);
+        assert_eq!(title.chars().count(), RECENT_TITLE_CHARS);
+        assert!(title.ends_with('…'));
+    }
+}
@@ -1,17 +1,140 @@
 use crate::sys::error::{AppError, Result};
+use crate::sys::secrets;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
+use tracing::warn;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+/// Keychain entry holding `embedding.api_key`
+const EMBEDDING_KEYCHAIN_ENTRY: &str = "embedding";
+
+/// Keychain entry holding `paper.ocr_api_key`
+const OCR_KEYCHAIN_ENTRY: &str = "ocr-api";
+
+/// The secret stored under `entry`; keychain errors are logged and read as
+/// no secret
+fn stored_secret(entry: &str) -> Option<String> {
+    match secrets::get_secret(entry) {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("Failed to read {} from the keychain: {}", entry, e);
+            None
+        }
+    }
+}
+
+/// Update the secret under `entry` with a key saved from the settings:
+/// `None` keeps the stored key and an empty key removes it
+fn save_secret(entry: &str, key: Option<String>) -> Result<()> {
+    match key.as_deref().map(str::trim) {
+        None => Ok(()),
+        Some("") => secrets::delete_secret(entry),
+        Some(key) => secrets::set_secret(entry, key),
+    }
+}
+
+/// Whether `key` holds a key that is not blank
+fn has_key(key: &Option<String>) -> bool {
+    key.as_deref().is_some_and(|k| !k.trim().is_empty())
+}
+
+/// API flavour spoken by an LLM provider
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LlmProviderKind {
+    #[serde(rename = "openai")]
+    OpenAi,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    #[serde(rename = "ollama")]
+    Ollama,
+    /// Any server with an OpenAI-style `/chat/completions` endpoint
+    #[default]
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible,
+}
+
+impl LlmProviderKind {
+    /// Base URL used when none is configured
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            LlmProviderKind::OpenAi => "https://api.openai.com/v1",
+            LlmProviderKind::Anthropic => "https://api.anthropic.com/v1",
+            LlmProviderKind::Ollama => "http://localhost:11434/v1",
+            LlmProviderKind::OpenAiCompatible => "",
+        }
+    }
+
+    /// Local Ollama servers accept requests without a key
+    pub fn requires_api_key(self) -> bool {
+        self != LlmProviderKind::Ollama
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LlmProvider {
     pub id: String,
     pub name: String,
-    pub api_key: String,
+    /// Only set while a key is being saved or used: saved keys live in the
+    /// OS keychain. When saving, `None` keeps the stored key and an empty
+    /// string removes it. Older versions kept the key here in plain text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
     pub base_url: String,
     pub model_name: String,
     pub is_default: bool,
+    #[serde(default)]
+    pub kind: LlmProviderKind,
+    #[serde(default = "default_llm_max_tokens")]
+    pub max_tokens: u32,
+    #[serde(default = "default_llm_temperature")]
+    pub temperature: f32,
+}
+
+fn default_llm_max_tokens() -> u32 {
+    4096
+}
+
+fn default_llm_temperature() -> f32 {
+    0.3
+}
+
+impl Default for LlmProvider {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            api_key: None,
+            base_url: String::new(),
+            model_name: String::new(),
+            is_default: false,
+            kind: LlmProviderKind::default(),
+            max_tokens: default_llm_max_tokens(),
+            temperature: default_llm_temperature(),
+        }
+    }
+}
+
+impl LlmProvider {
+    /// Keychain entry holding this provider's API key
+    pub fn keychain_entry(&self) -> String {
+        format!("llm-provider:{}", self.id)
+    }
+
+    /// This provider with its API key filled in from the keychain when the
+    /// config holds none. Keychain errors are logged and leave the key empty.
+    pub fn with_stored_api_key(mut self) -> Self {
+        if !has_key(&self.api_key) {
+            self.api_key = stored_secret(&self.keychain_entry());
+        }
+        self
+    }
+
+    /// Whether requests can be sent: a key is present unless the provider
+    /// needs none
+    pub fn has_credentials(&self) -> bool {
+        !self.kind.requires_api_key() || has_key(&self.api_key)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -19,6 +142,67 @@ pub struct SystemConfig {
     pub llm_providers: Vec<LlmProvider>,
 }
 
+impl SystemConfig {
+    /// The provider marked as default, or the first one configured
+    pub fn default_llm_provider(&self) -> Option<&LlmProvider> {
+        self.llm_providers
+            .iter()
+            .find(|p| p.is_default)
+            .or_else(|| self.llm_providers.first())
+    }
+}
+
+/// Model used to embed papers for semantic search. Any OpenAI-compatible
+/// `/embeddings` endpoint works, including local servers such as Ollama.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmbeddingConfig {
+    #[serde(default = "default_embedding_base_url")]
+    pub base_url: String,
+    /// Sent as a bearer token when set; local servers usually need none.
+    /// Saved in the OS keychain like `LlmProvider::api_key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(default = "default_embedding_model_name")]
+    pub model_name: String,
+    /// Papers embedded per request
+    #[serde(default = "default_embedding_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_embedding_base_url() -> String {
+    "https://api.openai.com/v1".to_string()
+}
+
+fn default_embedding_model_name() -> String {
+    "text-embedding-3-small".to_string()
+}
+
+fn default_embedding_batch_size() -> usize {
+    32
+}
+
+impl Default for EmbeddingConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_embedding_base_url(),
+            api_key: None,
+            model_name: default_embedding_model_name(),
+            batch_size: default_embedding_batch_size(),
+        }
+    }
+}
+
+impl EmbeddingConfig {
+    /// This config with its API key filled in from the keychain when the
+    /// config holds none
+    pub fn with_stored_api_key(mut self) -> Self {
+        if !has_key(&self.api_key) {
+            self.api_key = stored_secret(EMBEDDING_KEYCHAIN_ENTRY);
+        }
+        self
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GrobidServer {
     pub id: String,
@@ -54,10 +238,271 @@ impl Default for GrobidConfig {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+impl GrobidConfig {
+    /// URL of the active server, or the public HuggingFace Space when none
+    /// is active
+    pub fn active_url(&self) -> String {
+        self.servers
+            .iter()
+            .find(|s| s.is_active)
+            .map(|s| s.url.clone())
+            .unwrap_or_else(|| "https://kermitt2-grobid.hf.space".to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PaperConfig {
     #[serde(default)]
     pub grobid: GrobidConfig,
+    /// Path to the `ocrmypdf` binary; looked up on PATH when unset
+    #[serde(default)]
+    pub ocrmypdf_path: Option<String>,
+    /// Path to the `pdftoppm` binary used for thumbnails; looked up on PATH
+    /// when unset
+    #[serde(default)]
+    pub pdftoppm_path: Option<String>,
+    /// OCR API that scanned PDFs are posted to; `ocrmypdf` is used when unset
+    #[serde(default)]
+    pub ocr_api_url: Option<String>,
+    /// Bearer token sent to `ocr_api_url`, kept in the OS keychain. Only set
+    /// when saving: `None` keeps the stored key and an empty string removes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_api_key: Option<String>,
+    /// Extract keywords from the abstract when a paper is imported by DOI
+    #[serde(default)]
+    pub auto_extract_keywords: bool,
+    /// Unfinished downloads untouched for longer than this are deleted at startup
+    #[serde(default = "default_partial_download_max_age_hours")]
+    pub partial_download_max_age_hours: u64,
+    /// Let a paper belong to several categories. When off, moving a paper to
+    /// a category replaces the category it had.
+    #[serde(default)]
+    pub multi_category: bool,
+    /// Take a paper out of the reading queue once it is marked as read
+    #[serde(default)]
+    pub dequeue_when_read: bool,
+    /// Email address sent with open access lookups; Unpaywall refuses
+    /// requests without one
+    #[serde(default)]
+    pub contact_email: Option<String>,
+}
+
+fn default_partial_download_max_age_hours() -> u64 {
+    72
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self {
+            grobid: GrobidConfig::default(),
+            ocrmypdf_path: None,
+            pdftoppm_path: None,
+            ocr_api_url: None,
+            ocr_api_key: None,
+            auto_extract_keywords: false,
+            partial_download_max_age_hours: default_partial_download_max_age_hours(),
+            multi_category: false,
+            dequeue_when_read: false,
+            contact_email: None,
+        }
+    }
+}
+
+impl PaperConfig {
+    /// Bearer token for `ocr_api_url`, read from the keychain
+    pub fn ocr_api_key(&self) -> Option<String> {
+        stored_secret(OCR_KEYCHAIN_ENTRY)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiConfig {
+    /// Start the local API server; changes take effect after a restart
+    #[serde(default = "default_api_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_api_host")]
+    pub host: String,
+    /// Preferred port; the next few ports are tried when it is taken. 0 lets
+    /// the system pick a free port, which is then reused on later starts.
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+    /// Port picked the last time `port` was 0, tried first on the next start
+    #[serde(default)]
+    pub last_port: Option<u16>,
+}
+
+fn default_api_enabled() -> bool {
+    true
+}
+
+fn default_api_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_api_port() -> u16 {
+    3030
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_api_enabled(),
+            host: default_api_host(),
+            port: default_api_port(),
+            last_port: None,
+        }
+    }
+}
+
+/// How often the database is backed up automatically
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoBackupFrequency {
+    #[default]
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl AutoBackupFrequency {
+    /// Minimum age of the newest backup before a new one is taken
+    pub fn interval(&self) -> Option<chrono::Duration> {
+        match self {
+            AutoBackupFrequency::Never => None,
+            AutoBackupFrequency::Daily => Some(chrono::Duration::days(1)),
+            AutoBackupFrequency::Weekly => Some(chrono::Duration::weeks(1)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupConfig {
+    /// Directory for automatic backups; defaults to `backups` next to the data directory
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub auto_backup: AutoBackupFrequency,
+    /// Number of automatic backups to keep (0 keeps all)
+    #[serde(default = "default_backup_keep_count")]
+    pub keep_count: u32,
+}
+
+fn default_backup_keep_count() -> u32 {
+    10
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            auto_backup: AutoBackupFrequency::default(),
+            keep_count: default_backup_keep_count(),
+        }
+    }
+}
+
+/// Daily window during which scheduled background work is deferred
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuietHoursConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Local start time as `HH:MM`
+    #[serde(default = "default_quiet_hours_start")]
+    pub start: String,
+    /// Local end time as `HH:MM`; earlier than `start` for windows crossing midnight
+    #[serde(default = "default_quiet_hours_end")]
+    pub end: String,
+}
+
+fn default_quiet_hours_start() -> String {
+    "22:00".to_string()
+}
+
+fn default_quiet_hours_end() -> String {
+    "08:00".to_string()
+}
+
+impl Default for QuietHoursConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: default_quiet_hours_start(),
+            end: default_quiet_hours_end(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ActivityConfig {
+    /// Activity log entries older than this are deleted at startup
+    #[serde(default = "default_activity_retention_days")]
+    pub retention_days: u32,
+}
+
+fn default_activity_retention_days() -> u32 {
+    90
+}
+
+impl Default for ActivityConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: default_activity_retention_days(),
+        }
+    }
+}
+
+/// Global hotkeys; changes take effect after a restart
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ShortcutConfig {
+    #[serde(default = "default_shortcut_enabled")]
+    pub enabled: bool,
+    /// Hotkey that saves the clipboard as a clip, e.g. `CommandOrControl+Shift+K`
+    #[serde(default = "default_capture_clipboard_shortcut")]
+    pub capture_clipboard: String,
+}
+
+fn default_shortcut_enabled() -> bool {
+    true
+}
+
+fn default_capture_clipboard_shortcut() -> String {
+    "CommandOrControl+Shift+K".to_string()
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_shortcut_enabled(),
+            capture_clipboard: default_capture_clipboard_shortcut(),
+        }
+    }
+}
+
+/// Scheduled refresh of citation counts from OpenAlex
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CitationRefreshConfig {
+    #[serde(default = "default_citation_refresh_enabled")]
+    pub enabled: bool,
+    /// Papers whose count is younger than this are skipped
+    #[serde(default = "default_citation_refresh_interval_hours")]
+    pub interval_hours: u32,
+}
+
+fn default_citation_refresh_enabled() -> bool {
+    true
+}
+
+fn default_citation_refresh_interval_hours() -> u32 {
+    24
+}
+
+impl Default for CitationRefreshConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_citation_refresh_enabled(),
+            interval_hours: default_citation_refresh_interval_hours(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -66,6 +511,20 @@ pub struct AppConfig {
     pub system: SystemConfig,
     #[serde(default)]
     pub paper: PaperConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+    #[serde(default)]
+    pub embedding: EmbeddingConfig,
+    #[serde(default)]
+    pub activity: ActivityConfig,
+    #[serde(default)]
+    pub shortcut: ShortcutConfig,
+    #[serde(default)]
+    pub citation_refresh: CitationRefreshConfig,
 }
 
 impl AppConfig {
@@ -90,6 +549,48 @@ impl AppConfig {
         })
     }
 
+    /// Move the API keys in this config to the OS keychain and clear them.
+    /// A key left unset keeps the stored one and an empty key removes it.
+    /// Providers in `previous` that this config no longer has lose their
+    /// stored key.
+    pub fn store_secrets(&mut self, previous: &AppConfig) -> Result<()> {
+        for provider in &mut self.system.llm_providers {
+            // A plain-text key from an older version moves to the keychain
+            let key = provider.api_key.take().or_else(|| {
+                previous
+                    .system
+                    .llm_providers
+                    .iter()
+                    .find(|p| p.id == provider.id)
+                    .and_then(|p| p.api_key.clone())
+                    .filter(|k| !k.trim().is_empty())
+            });
+            save_secret(&provider.keychain_entry(), key)?;
+        }
+        for removed in previous
+            .system
+            .llm_providers
+            .iter()
+            .filter(|p| !self.system.llm_providers.iter().any(|q| q.id == p.id))
+        {
+            secrets::delete_secret(&removed.keychain_entry())?;
+        }
+
+        save_secret(EMBEDDING_KEYCHAIN_ENTRY, self.embedding.api_key.take())?;
+        save_secret(OCR_KEYCHAIN_ENTRY, self.paper.ocr_api_key.take())?;
+        Ok(())
+    }
+
+    /// This config with every API key cleared, for handing to the UI
+    pub fn without_secrets(mut self) -> Self {
+        for provider in &mut self.system.llm_providers {
+            provider.api_key = None;
+        }
+        self.embedding.api_key = None;
+        self.paper.ocr_api_key = None;
+        self
+    }
+
     pub fn save(&self, config_dir: &str) -> Result<()> {
         let path = PathBuf::from(config_dir).join("settings.json");
         let content = serde_json::to_string_pretty(self).map_err(|e| {
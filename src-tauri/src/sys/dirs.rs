@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{debug, error, info, warn};
 
 use crate::sys::{
@@ -39,7 +39,7 @@ impl Default for DataPathConfig {
 }
 
 /// Application directory structure
-#[derive(serde::Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppDirs {
     /// Configuration file directory
     pub config: String,
@@ -98,12 +98,15 @@ pub enum MigrationPhase {
     Preparing,
     CopyingDatabase,
     CopyingFiles,
+    CopyingBackups,
     CopyingCache,
     CopyingConfig,
     CopyingLogs,
     Verifying,
     Completed,
     Failed,
+    /// Stopped by `cancel_migration`; migrating again resumes
+    Cancelled,
     RollingBack,
 }
 
@@ -212,7 +215,7 @@ pub fn calculate_data_size(app_dirs: &AppDirs) -> Result<u64> {
 }
 
 /// Recursively calculate directory size
-fn calculate_dir_size(path: &PathBuf) -> Result<u64> {
+pub fn calculate_dir_size(path: &PathBuf) -> Result<u64> {
     let mut size: u64 = 0;
 
     if path.is_dir() {
@@ -458,12 +461,18 @@ pub fn validate_data_folder(path: &str, required_space: u64) -> Result<Validatio
     })
 }
 
-/// Get available disk space for a path (simplified implementation)
-fn get_available_space(_path: &PathBuf) -> Option<u64> {
-    // For cross-platform compatibility, we assume there's enough space
-    // A more robust implementation would use platform-specific APIs
-    // or the `fs2` crate for accurate disk space information
-    Some(u64::MAX)
+/// Free space available to the current user on the drive holding `path`.
+/// A path that does not exist yet is measured at its nearest existing
+/// ancestor. `None` when the space cannot be determined.
+pub fn get_available_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    match fs2::available_space(existing) {
+        Ok(available) => Some(available),
+        Err(e) => {
+            warn!("Failed to get available space of {:?}: {}", existing, e);
+            None
+        }
+    }
 }
 
 /// Get list of system directories that should be avoided
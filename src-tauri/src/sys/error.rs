@@ -39,6 +39,10 @@ pub enum AppError {
     #[error("Network error: {url} - {message}")]
     NetworkError { url: String, message: String },
 
+    /// Request timed out; the same request may succeed when retried
+    #[error("Request timed out: {url} - {message}")]
+    Timeout { url: String, message: String },
+
     /// Validation errors
     #[error("Validation error: {field} - {message}")]
     ValidationError { field: String, message: String },
@@ -54,6 +58,13 @@ pub enum AppError {
         resource_id: String,
     },
 
+    /// Resource that already exists, e.g. a clipping of the same URL
+    #[error("Resource already exists: {resource_type} '{resource_id}'")]
+    Conflict {
+        resource_type: String,
+        resource_id: String,
+    },
+
     /// Invalid input
     #[error("Invalid input: {message}")]
     InvalidInput { message: String },
@@ -252,6 +263,23 @@ impl Serialize for AppError {
                 required: None,
                 available: None,
             },
+            AppError::Timeout { url, message } => ErrorResponse {
+                error_type: "Timeout",
+                message: Some(message),
+                path: None,
+                operation: None,
+                service: None,
+                plugin_name: None,
+                key: None,
+                url: Some(url),
+                field: None,
+                resource: None,
+                resource_type: None,
+                resource_id: None,
+                phase: None,
+                required: None,
+                available: None,
+            },
             AppError::ValidationError { field, message } => ErrorResponse {
                 error_type: "ValidationError",
                 message: Some(message),
@@ -306,6 +334,26 @@ impl Serialize for AppError {
                 required: None,
                 available: None,
             },
+            AppError::Conflict {
+                resource_type,
+                resource_id,
+            } => ErrorResponse {
+                error_type: "Conflict",
+                message: None,
+                path: None,
+                operation: None,
+                service: None,
+                plugin_name: None,
+                key: None,
+                url: None,
+                field: None,
+                resource: None,
+                resource_type: Some(resource_type),
+                resource_id: Some(resource_id),
+                phase: None,
+                required: None,
+                available: None,
+            },
             AppError::InvalidInput { message } => ErrorResponse {
                 error_type: "InvalidInput",
                 message: Some(message),
@@ -514,6 +562,14 @@ impl AppError {
         }
     }
 
+    /// Create a timeout error
+    pub fn timeout(url: impl Into<String>, message: impl Into<String>) -> Self {
+        AppError::Timeout {
+            url: url.into(),
+            message: message.into(),
+        }
+    }
+
     /// Create a validation error
     pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
         AppError::ValidationError {
@@ -537,6 +593,14 @@ impl AppError {
         }
     }
 
+    /// Create a conflict error for a resource that already exists
+    pub fn conflict(resource_type: impl Into<String>, resource_id: impl Into<String>) -> Self {
+        AppError::Conflict {
+            resource_type: resource_type.into(),
+            resource_id: resource_id.into(),
+        }
+    }
+
     /// Create an invalid input error
     pub fn invalid_input(message: impl Into<String>) -> Self {
         AppError::InvalidInput {
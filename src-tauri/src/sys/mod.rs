@@ -4,3 +4,4 @@ pub mod consts;
 pub mod dirs;
 pub mod error;
 pub mod log;
+pub mod secrets;
@@ -0,0 +1,47 @@
+//! Secrets kept in the operating system's credential store
+//!
+//! macOS Keychain, Windows Credential Manager and the Secret Service on
+//! Linux, through `keyring`. Entries are grouped under one service name and
+//! identified by a short entry name such as `llm-provider:<id>`.
+
+use keyring::Entry;
+
+use crate::sys::error::{AppError, Result};
+
+/// Service name all entries are stored under
+const KEYCHAIN_SERVICE: &str = "xuan-brain";
+
+fn entry(name: &str) -> Result<Entry> {
+    Entry::new(KEYCHAIN_SERVICE, name)
+        .map_err(|e| AppError::config_error(name, format!("Keychain unavailable: {}", e)))
+}
+
+/// The secret stored under `name`, or `None` when there is none
+pub fn get_secret(name: &str) -> Result<Option<String>> {
+    match entry(name)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::config_error(
+            name,
+            format!("Failed to read from keychain: {}", e),
+        )),
+    }
+}
+
+/// Store `secret` under `name`, replacing any earlier value
+pub fn set_secret(name: &str, secret: &str) -> Result<()> {
+    entry(name)?
+        .set_password(secret)
+        .map_err(|e| AppError::config_error(name, format!("Failed to write to keychain: {}", e)))
+}
+
+/// Remove the secret stored under `name`; removing a missing one is a no-op
+pub fn delete_secret(name: &str) -> Result<()> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::config_error(
+            name,
+            format!("Failed to delete from keychain: {}", e),
+        )),
+    }
+}
@@ -0,0 +1,309 @@
+//! Test harness for the repository and service layers
+//!
+//! Tests get a fresh in-memory SQLite database with all migrations applied
+//! and build their data with fixtures instead of spelling out every field of
+//! `CreatePaper`:
+//!
+//! ```ignore
+//! let db = test_db().await;
+//! let paper = PaperFixture::new("Attention Is All You Need")
+//!     .with_authors(&["Ashish Vaswani", "Noam Shazeer"])
+//!     .with_label("to-read")
+//!     .with_category("Transformers")
+//!     .insert(&db)
+//!     .await;
+//! ```
+//!
+//! Fixtures look up labels, categories, keywords and authors by name and only
+//! create them when missing, so several fixtures naming the same label share
+//! one row. Every value is fixed, so the same fixtures always produce the
+//! same data.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::database::DatabaseConnection;
+use crate::models::{
+    AuthorDetails, Category, Clipping, CreateCategory, CreateClipping, CreateLabel, CreatePaper,
+    Label, Paper,
+};
+use crate::repository::{
+    AuthorRepository, CategoryRepository, ClippingRepository, KeywordRepository, LabelRepository,
+    PaperRepository,
+};
+use crate::sys::dirs::AppDirs;
+
+/// Color given to labels created by fixtures
+pub const FIXTURE_LABEL_COLOR: &str = "#1976D2";
+
+/// Open an empty in-memory database with all migrations applied
+pub async fn test_db() -> Arc<DatabaseConnection> {
+    crate::database::connection::init_memory_connection().await
+}
+
+/// App directories under `root`, laid out like the real ones and created
+/// on disk
+pub fn test_app_dirs(root: &Path) -> AppDirs {
+    let dir = |name: &str| {
+        let path = root.join(name);
+        std::fs::create_dir_all(&path).unwrap();
+        path.to_string_lossy().to_string()
+    };
+    AppDirs {
+        config: dir("config"),
+        data: dir("data"),
+        cache: dir("cache"),
+        logs: dir("logs"),
+        files: dir("files"),
+        is_custom: false,
+    }
+}
+
+/// Find a label by name or create it
+pub async fn label(db: &DatabaseConnection, name: &str) -> Label {
+    if let Some(label) = LabelRepository::find_by_name(db, name).await.unwrap() {
+        return label;
+    }
+    LabelRepository::create(
+        db,
+        CreateLabel {
+            name: name.to_string(),
+            color: FIXTURE_LABEL_COLOR.to_string(),
+            parent_id: None,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// Find a category by name under `parent_id` or create it
+pub async fn category(db: &DatabaseConnection, name: &str, parent_id: Option<i64>) -> Category {
+    let existing = CategoryRepository::find_all(db)
+        .await
+        .unwrap()
+        .into_iter()
+        .find(|c| c.name == name && c.parent_id == parent_id);
+    if let Some(category) = existing {
+        return category;
+    }
+    CategoryRepository::create(
+        db,
+        CreateCategory {
+            name: name.to_string(),
+            parent_id,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// Builder for a paper together with its authors, labels, keywords and
+/// category
+pub struct PaperFixture {
+    create: CreatePaper,
+    authors: Vec<String>,
+    labels: Vec<String>,
+    keywords: Vec<String>,
+    category: Option<String>,
+    deleted: bool,
+}
+
+impl PaperFixture {
+    pub fn new(title: &str) -> Self {
+        Self {
+            create: CreatePaper {
+                title: title.to_string(),
+                abstract_text: None,
+                doi: None,
+                publication_year: None,
+                publication_date: None,
+                journal_name: None,
+                conference_name: None,
+                volume: None,
+                issue: None,
+                pages: None,
+                url: None,
+                attachment_path: None,
+                publisher: None,
+                issn: None,
+                language: None,
+                isbn: None,
+            },
+            authors: Vec::new(),
+            labels: Vec::new(),
+            keywords: Vec::new(),
+            category: None,
+            deleted: false,
+        }
+    }
+
+    pub fn with_doi(mut self, doi: &str) -> Self {
+        self.create.doi = Some(doi.to_string());
+        self
+    }
+
+    pub fn with_abstract(mut self, abstract_text: &str) -> Self {
+        self.create.abstract_text = Some(abstract_text.to_string());
+        self
+    }
+
+    /// Normalized ISBN-13, for books
+    pub fn with_isbn(mut self, isbn: &str) -> Self {
+        self.create.isbn = Some(isbn.to_string());
+        self
+    }
+
+    pub fn with_year(mut self, year: i32) -> Self {
+        self.create.publication_year = Some(year);
+        self
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.create.url = Some(url.to_string());
+        self
+    }
+
+    /// Attachment directory name under the files directory
+    pub fn with_attachment_path(mut self, hash: &str) -> Self {
+        self.create.attachment_path = Some(hash.to_string());
+        self
+    }
+
+    /// Authors by full name, in author order
+    pub fn with_authors(mut self, names: &[&str]) -> Self {
+        self.authors = names.iter().map(|n| n.to_string()).collect();
+        self
+    }
+
+    pub fn with_label(mut self, name: &str) -> Self {
+        self.labels.push(name.to_string());
+        self
+    }
+
+    pub fn with_keyword(mut self, word: &str) -> Self {
+        self.keywords.push(word.to_string());
+        self
+    }
+
+    /// Top-level category the paper belongs to
+    pub fn with_category(mut self, name: &str) -> Self {
+        self.category = Some(name.to_string());
+        self
+    }
+
+    /// Move the paper to the trash after inserting it
+    pub fn deleted(mut self) -> Self {
+        self.deleted = true;
+        self
+    }
+
+    /// Insert the paper and everything linked to it. Panics on failure.
+    pub async fn insert(self, db: &DatabaseConnection) -> Paper {
+        let paper = PaperRepository::create(db, self.create).await.unwrap();
+
+        for (order, name) in self.authors.iter().enumerate() {
+            let author = AuthorRepository::create_or_find(db, name, &AuthorDetails::default())
+                .await
+                .unwrap();
+            PaperRepository::add_author(db, paper.id, author.id, order as i32)
+                .await
+                .unwrap();
+        }
+        for name in &self.labels {
+            let label = label(db, name).await;
+            LabelRepository::add_to_paper(db, paper.id, label.id)
+                .await
+                .unwrap();
+        }
+        for word in &self.keywords {
+            let keyword = KeywordRepository::create_or_find(db, word).await.unwrap();
+            KeywordRepository::add_to_paper(db, paper.id, keyword.id)
+                .await
+                .unwrap();
+        }
+        if let Some(name) = &self.category {
+            let category = category(db, name, None).await;
+            PaperRepository::set_category(db, paper.id, Some(category.id))
+                .await
+                .unwrap();
+        }
+        if self.deleted {
+            PaperRepository::soft_delete(db, paper.id).await.unwrap();
+        }
+
+        PaperRepository::find_by_id(db, paper.id)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+}
+
+/// Builder for a web clipping and its labels
+pub struct ClipFixture {
+    create: CreateClipping,
+    labels: Vec<String>,
+}
+
+impl ClipFixture {
+    /// A clip whose URL is derived from the title, so clips with different
+    /// titles rarely need `with_url`
+    pub fn new(title: &str) -> Self {
+        let slug: String = title
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        Self {
+            create: CreateClipping {
+                title: title.to_string(),
+                url: format!("https://example.org/{}", slug),
+                content: None,
+                source_domain: Some("example.org".to_string()),
+                author: None,
+                published_date: None,
+                excerpt: None,
+                thumbnail_url: None,
+                tags: Vec::new(),
+                image_paths: Vec::new(),
+            },
+            labels: Vec::new(),
+        }
+    }
+
+    pub fn with_url(mut self, url: &str) -> Self {
+        self.create.url = url.to_string();
+        self
+    }
+
+    pub fn with_domain(mut self, domain: &str) -> Self {
+        self.create.source_domain = Some(domain.to_string());
+        self
+    }
+
+    pub fn with_content(mut self, content: &str) -> Self {
+        self.create.content = Some(content.to_string());
+        self
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.create.tags = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    pub fn with_label(mut self, name: &str) -> Self {
+        self.labels.push(name.to_string());
+        self
+    }
+
+    /// Insert the clip and link its labels. Panics on failure.
+    pub async fn insert(self, db: &DatabaseConnection) -> Clipping {
+        let clip = ClippingRepository::create(db, self.create).await.unwrap();
+        for name in &self.labels {
+            let label = label(db, name).await;
+            LabelRepository::add_to_clip(db, clip.id, label.id)
+                .await
+                .unwrap();
+        }
+        clip
+    }
+}
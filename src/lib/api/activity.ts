@@ -0,0 +1,46 @@
+/**
+ * Activity API functions
+ * Recent activity across papers and clips, and recently viewed papers
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface Activity {
+  id: string;
+  entity_type: 'paper' | 'clip';
+  entity_id: string;
+  action: 'viewed' | 'imported' | 'created' | 'updated' | 'deleted';
+  created_at: string;
+}
+
+export interface RecentPaper {
+  id: string;
+  title: string;
+  publication_year?: number;
+  journal_name?: string;
+  conference_name?: string;
+  first_author?: string;
+  author_count: number;
+  attachment_count: number;
+  attachments: {
+    id: string;
+    paper_id: string;
+    file_name?: string;
+    file_type?: string;
+    created_at?: string;
+  }[];
+}
+
+/**
+ * Most recent activity, newest first (default 20, at most 200)
+ */
+export async function getRecentActivity(limit?: number): Promise<Activity[]> {
+  return invokeCommand<Activity[]>('get_recent_activity', { limit });
+}
+
+/**
+ * Papers most recently opened in the detail view, last viewed first
+ */
+export async function getRecentlyViewedPapers(limit?: number): Promise<RecentPaper[]> {
+  return invokeCommand<RecentPaper[]>('get_recently_viewed_papers', { limit });
+}
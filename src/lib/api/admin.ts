@@ -0,0 +1,59 @@
+/**
+ * Admin API functions
+ * Library maintenance such as database integrity checks and recovery
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface IntegrityCheckResult {
+  check_name: string;
+  passed: boolean;
+  issue_count: number;
+  /** First few issues, described for the user */
+  sample_issues: string[];
+}
+
+export interface IntegrityReport {
+  checks: IntegrityCheckResult[];
+  total_issues: number;
+}
+
+/**
+ * Check the database for dangling references, orphaned records, missing
+ * attachment files and stale counts. Nothing is repaired.
+ */
+export async function checkDatabaseIntegrity(): Promise<IntegrityReport> {
+  return invokeCommand<IntegrityReport>('check_database_integrity');
+}
+
+export interface DatabaseStatus {
+  available: boolean;
+  database_path: string;
+  /** Why the database could not be opened at startup */
+  error: string | null;
+  /** Newest backup, offered for restore when the database is unavailable */
+  latest_backup: string | null;
+}
+
+export interface DatabaseRepairResult {
+  /** Where the unusable database was moved */
+  moved_to: string;
+  /** Backup staged to be restored on the next start */
+  restored_backup: string | null;
+}
+
+/**
+ * Whether the database opened at startup. When it did not, library
+ * commands fail until the database is repaired.
+ */
+export async function getDatabaseStatus(): Promise<DatabaseStatus> {
+  return invokeCommand<DatabaseStatus>('get_database_status');
+}
+
+/**
+ * Move an unusable database aside and optionally stage the newest backup
+ * for restore. Call `restart_app` afterwards to open the repaired library.
+ */
+export async function repairDatabase(restoreLatestBackup: boolean): Promise<DatabaseRepairResult> {
+  return invokeCommand<DatabaseRepairResult>('repair_database', { restoreLatestBackup });
+}
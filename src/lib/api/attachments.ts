@@ -0,0 +1,86 @@
+/**
+ * Attachment API functions
+ * Checking attachment files on disk, naming attachment directories and PDF thumbnails
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface MissingAttachment {
+  paper_id: string;
+  attachment_id: string;
+  expected_path: string;
+}
+
+export interface AttachmentValidationReport {
+  total: number;
+  valid: number;
+  repaired: number;
+  missing: MissingAttachment[];
+}
+
+export interface AttachmentPathConflict {
+  paper_id: string;
+  title: string;
+  attachment_path: string;
+  reason: string;
+}
+
+export interface AttachmentPathReport {
+  /** Papers whose attachment directory was named after the title hash */
+  legacy: number;
+  renamed: number;
+  /** Papers without a directory on disk that were given a new name */
+  reassigned: number;
+  conflicts: AttachmentPathConflict[];
+}
+
+export interface Thumbnail {
+  paper_id: string;
+  path: string;
+  width: number;
+  height: number;
+}
+
+/**
+ * Check that every attachment file still exists
+ * @param attemptRepair - Move missing files found elsewhere in the library back into place
+ */
+export async function validateAllAttachments(
+  attemptRepair: boolean,
+): Promise<AttachmentValidationReport> {
+  return invokeCommand<AttachmentValidationReport>('validate_all_attachments', { attemptRepair });
+}
+
+/**
+ * Rename attachment directories named after the hash of the paper title to stable names.
+ * Directories shared by papers with the same title are reported as conflicts.
+ */
+export async function normalizeAttachmentPaths(): Promise<AttachmentPathReport> {
+  return invokeCommand<AttachmentPathReport>('normalize_attachment_paths');
+}
+
+/**
+ * Render the first page of a paper's PDF to a PNG fitting within width x height
+ */
+export async function generatePdfThumbnail(
+  paperId: string,
+  width: number,
+  height: number,
+): Promise<Thumbnail> {
+  return invokeCommand<Thumbnail>('generate_pdf_thumbnail', { paperId, width, height });
+}
+
+/**
+ * Cached thumbnail of a paper, or null when missing or stale
+ */
+export async function getCachedThumbnail(paperId: string): Promise<Thumbnail | null> {
+  return invokeCommand<Thumbnail | null>('get_cached_thumbnail', { paperId });
+}
+
+/**
+ * Read an attachment's size from its file and store it
+ * @param attachmentId - Attachment ID
+ */
+export async function updateAttachmentFileSize(attachmentId: string): Promise<void> {
+  return invokeCommand<void>('update_attachment_file_size', { attachmentId });
+}
@@ -0,0 +1,127 @@
+/**
+ * Author API functions
+ * Author detail pages, author search, editing and merging duplicate authors
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface Author {
+  id: string;
+  first_name: string;
+  last_name: string | null;
+  full_name: string;
+  affiliation: string | null;
+  email: string | null;
+  /** Bare ORCID iD, e.g. "0000-0002-1825-0097" */
+  orcid: string | null;
+}
+
+export interface AuthorDetail extends Author {
+  created_at: string;
+}
+
+export interface AuthorWithCount extends Author {
+  /** Papers not in the trash */
+  paper_count: number;
+}
+
+/** Author as listed on a paper */
+export interface PaperAuthor {
+  id: string;
+  name: string;
+  /** Zero-based position in the author list */
+  order: number;
+  is_corresponding: boolean;
+}
+
+export interface PaperAuthorInput {
+  author_id: string;
+  order: number;
+  is_corresponding: boolean;
+}
+
+export interface AuthorPapers {
+  author: AuthorDetail;
+  papers: any[];
+  paper_count: number;
+}
+
+/**
+ * Get an author with all of their papers, newest first
+ * @param authorId - The author ID
+ */
+export async function getAuthorPapers(authorId: string): Promise<AuthorPapers> {
+  return invokeCommand<AuthorPapers>('get_author_papers', { authorId });
+}
+
+/**
+ * Search authors by name; each word matches as a prefix
+ * @param query - Search text, e.g. "joh smi"
+ */
+export async function searchAuthors(query: string): Promise<Author[]> {
+  return invokeCommand<Author[]>('search_authors', { query });
+}
+
+/**
+ * Update an author's name, affiliation, email and ORCID iD
+ * @param id - The author ID
+ * @param name - Full name; split into first and last name by the backend
+ * @param orcid - ORCID iD or orcid.org URL; rejected when invalid or used by another author
+ */
+export async function updateAuthor(
+  id: string,
+  name: string,
+  affiliation: string | null,
+  email: string | null,
+  orcid: string | null = null
+): Promise<Author> {
+  return invokeCommand<Author>('update_author', { id, name, affiliation, email, orcid });
+}
+
+/**
+ * List authors with their paper counts
+ * @param query - Optional name search; without it the authors with the most papers come first
+ * @param limit - Maximum number of authors (default 100, at most 1000)
+ */
+export async function listAuthors(query?: string, limit?: number): Promise<AuthorWithCount[]> {
+  return invokeCommand<AuthorWithCount[]>('list_authors', { query, limit });
+}
+
+/**
+ * Rename an author on every paper, keeping affiliation and email
+ * @param authorId - The author ID
+ * @param newName - Full name; split into first and last name by the backend
+ */
+export async function renameAuthor(authorId: string, newName: string): Promise<Author> {
+  return invokeCommand<Author>('rename_author', { authorId, newName });
+}
+
+/**
+ * Merge duplicate authors into one
+ * @param primaryId - The author to keep
+ * @param duplicateIds - Authors whose papers move to the primary author; they are deleted
+ */
+export async function mergeAuthors(primaryId: string, duplicateIds: string[]): Promise<Author> {
+  return invokeCommand<Author>('merge_authors', { primaryId, duplicateIds });
+}
+
+/**
+ * Groups of authors that are probably the same person, e.g. "J. Smith" and "John Smith".
+ * The author with the most papers comes first in each group.
+ */
+export async function suggestAuthorDuplicates(): Promise<AuthorWithCount[][]> {
+  return invokeCommand<AuthorWithCount[][]>('suggest_author_duplicates');
+}
+
+/**
+ * Replace a paper's author list. Authors left out are unlinked from the
+ * paper but not deleted.
+ * @param paperId - The paper ID
+ * @param authors - The new author list; stored in `order` order
+ */
+export async function updatePaperAuthors(
+  paperId: string,
+  authors: PaperAuthorInput[]
+): Promise<void> {
+  return invokeCommand<void>('update_paper_authors', { paperId, authors });
+}
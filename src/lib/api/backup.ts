@@ -0,0 +1,101 @@
+/**
+ * Backup API functions
+ * Portable library archives with checksum validation and restore
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+/** Payload of the `library-backup-progress` event */
+export interface LibraryBackupProgress {
+  phase: 'preparing' | 'copying_database' | 'copying_config' | 'copying_files' | 'completed';
+  current_file: string | null;
+  total_files: number;
+  processed_files: number;
+  error: string | null;
+}
+
+/** Payload of the `library-restore-progress` event */
+export interface LibraryRestoreProgress {
+  phase:
+    | 'verifying'
+    | 'preparing'
+    | 'copying_database'
+    | 'copying_config'
+    | 'copying_files'
+    | 'rolling_back'
+    | 'completed'
+    | 'failed';
+  current_file: string | null;
+  total_files: number;
+  processed_files: number;
+  error: string | null;
+}
+
+export type RestoreMode = 'replace' | 'merge';
+
+export interface RestoreItem {
+  entity_type: 'category' | 'label' | 'paper' | 'attachment';
+  name: string;
+  reason: string;
+}
+
+export interface LibraryRestoreResult {
+  mode: RestoreMode;
+  /** Database snapshot taken before a replace */
+  safety_backup_path: string | null;
+  merge: {
+    categories_added: number;
+    labels_added: number;
+    papers_added: number;
+    files_copied: number;
+    skipped: RestoreItem[];
+    failed: RestoreItem[];
+  } | null;
+}
+
+export interface LibraryBackupResult {
+  path: string;
+  size_bytes: number;
+  file_count: number;
+  app_version: string;
+  schema_version: string;
+  created_at: string;
+}
+
+export interface BackupValidation {
+  valid: boolean;
+  app_version: string;
+  schema_version: string;
+  created_at: string;
+  file_count: number;
+  /** Entries whose size or checksum differ from the manifest */
+  mismatched: string[];
+  /** Manifest entries missing from the archive */
+  missing: string[];
+}
+
+/**
+ * Write the database, config and attachments to one zip archive. The target
+ * must be outside the data directory.
+ */
+export async function exportLibraryBackup(targetPath: string): Promise<LibraryBackupResult> {
+  return invokeCommand<LibraryBackupResult>('export_library_backup', { targetPath });
+}
+
+/**
+ * Verify a library archive against its manifest checksums without extracting it
+ */
+export async function validateBackup(path: string): Promise<BackupValidation> {
+  return invokeCommand<BackupValidation>('validate_backup', { path });
+}
+
+/**
+ * Restore the library from an archive, replacing it or merging in what is
+ * missing. The app must be restarted afterwards (`restart_app`).
+ */
+export async function restoreLibraryBackup(
+  archivePath: string,
+  mode: RestoreMode
+): Promise<LibraryRestoreResult> {
+  return invokeCommand<LibraryRestoreResult>('restore_library_backup', { archivePath, mode });
+}
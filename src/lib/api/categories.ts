@@ -0,0 +1,67 @@
+/**
+ * Category API functions
+ * Category suggestions for papers and the sidebar tree with paper counts
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface CategoryNode {
+  id: number;
+  name: string;
+  parent_id?: number;
+  sort_order: number;
+  children: CategoryNode[];
+}
+
+export interface CategoryNodeWithCount {
+  id: number;
+  name: string;
+  parent_id?: number;
+  sort_order: number;
+  /** Papers filed directly in this category */
+  direct_count: number;
+  /** Papers in this category or any descendant, each counted once */
+  recursive_count: number;
+  children: CategoryNodeWithCount[];
+}
+
+export interface CategorySuggestion {
+  category: CategoryNode;
+  confidence: number;
+  /** Which keywords the paper shares with the category */
+  reason: string;
+}
+
+/**
+ * Suggest categories for a paper from papers sharing its keywords, most confident first
+ * @param paperId - The paper ID
+ */
+export async function suggestCategoriesForPaper(paperId: string): Promise<CategorySuggestion[]> {
+  return invokeCommand<CategorySuggestion[]>('suggest_categories_for_paper', { paperId });
+}
+
+/**
+ * Load the category tree with direct and recursive paper counts per node
+ */
+export async function getCategoryTreeWithCounts(): Promise<CategoryNodeWithCount[]> {
+  return invokeCommand<CategoryNodeWithCount[]>('get_category_tree_with_counts');
+}
+
+/**
+ * File a paper under one more category, keeping its other categories.
+ * Requires the `paper.multi_category` setting.
+ * @param paperId - The paper ID
+ * @param categoryId - The category ID
+ */
+export async function addPaperToCategory(paperId: string, categoryId: string): Promise<void> {
+  return invokeCommand<void>('add_paper_to_category', { paperId, categoryId });
+}
+
+/**
+ * Take a paper out of one category, keeping its other categories
+ * @param paperId - The paper ID
+ * @param categoryId - The category ID
+ */
+export async function removePaperFromCategory(paperId: string, categoryId: string): Promise<void> {
+  return invokeCommand<void>('remove_paper_from_category', { paperId, categoryId });
+}
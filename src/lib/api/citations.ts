@@ -0,0 +1,101 @@
+/**
+ * Citation API functions
+ * Links between papers in the library and the graph they form
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface CitationNode {
+  id: string;
+  title: string;
+  publication_year?: number;
+  read_status: string;
+}
+
+/** `source` cites `target` */
+export interface CitationEdge {
+  source: string;
+  target: string;
+}
+
+export interface CitationGraph {
+  root_id: string;
+  nodes: CitationNode[];
+  edges: CitationEdge[];
+}
+
+/**
+ * Node of a citation network. Entries that are not in the library have an
+ * id of the form `doi:<doi>` or `reference:<id>`.
+ */
+export interface NetworkNode {
+  paper_id: string;
+  title: string;
+  year?: number;
+  doi?: string;
+  in_library: boolean;
+}
+
+/** `from` is related to `to` by `relation` (currently always `cites`) */
+export interface NetworkEdge {
+  from: string;
+  to: string;
+  relation: string;
+}
+
+export interface CitationNetwork {
+  nodes: NetworkNode[];
+  edges: NetworkEdge[];
+}
+
+/**
+ * Record that one paper cites another
+ * @param citingPaperId - Paper whose bibliography contains the other
+ * @param citedPaperId - Paper being cited
+ */
+export async function linkCitation(citingPaperId: string, citedPaperId: string): Promise<void> {
+  return invokeCommand<void>('link_citation', { citingPaperId, citedPaperId });
+}
+
+/**
+ * Remove a citation link
+ * @param citingPaperId - Paper whose bibliography contains the other
+ * @param citedPaperId - Paper being cited
+ */
+export async function unlinkCitation(citingPaperId: string, citedPaperId: string): Promise<void> {
+  return invokeCommand<void>('unlink_citation', { citingPaperId, citedPaperId });
+}
+
+/**
+ * Get the papers around a paper in the citation graph
+ * @param paperId - Paper at the centre of the graph
+ * @param depth - Hops to follow in either direction (default 1, at most 3)
+ */
+export async function getCitationGraph(paperId: string, depth?: number): Promise<CitationGraph> {
+  return invokeCommand<CitationGraph>('get_citation_graph', { paperId, depth });
+}
+
+/**
+ * Get the citation network around a paper, including extracted references
+ * that are not in the library
+ * @param paperId - Paper at the centre of the network
+ * @param depth - Hops to expand (at least 1, at most 3)
+ */
+export async function getPaperCitationNetwork(
+  paperId: string,
+  depth: number
+): Promise<CitationNetwork> {
+  return invokeCommand<CitationNetwork>('get_paper_citation_network', { paperId, depth });
+}
+
+export type CitationFormat = 'apa' | 'mla' | 'chicago' | 'vancouver' | 'harvard' | 'ieee';
+
+/**
+ * Format a paper as a plain-text reference in the given style. Works offline.
+ */
+export async function generateCitationString(
+  paperId: string,
+  format: CitationFormat
+): Promise<string> {
+  return invokeCommand<string>('generate_citation_string', { paperId, format });
+}
@@ -30,6 +30,26 @@ export interface CreateClippingRequest {
   tags?: string[];
 }
 
+/**
+ * Label attached to a clipping
+ */
+export interface ClipLabel {
+  id: string;
+  name: string;
+  color: string;
+}
+
+/**
+ * Request structure for updating a clipping; omitted fields are unchanged
+ */
+export interface UpdateClippingRequest {
+  title?: string;
+  notes?: string;
+  tags?: string[];
+  read_status?: number;
+  excerpt?: string;
+}
+
 /**
  * Response structure for clipping data from the API
  */
@@ -45,6 +65,7 @@ export interface ClippingResponse {
   thumbnail_url: string | null;
   tags: string[];
   comments: Comment[];
+  labels: ClipLabel[];
   created_at: string;
   updated_at: string;
   read_status: number;
@@ -52,17 +73,27 @@ export interface ClippingResponse {
   image_paths: string[];
 }
 
+/**
+ * Result of createClip; saving an already clipped URL returns the existing clip
+ */
+export interface CreateClipResult {
+  already_exists: boolean;
+  /** Notification text, e.g. "Clip already saved" */
+  message: string;
+  clip: ClippingResponse;
+}
+
 /**
  * Create a new clipping using Tauri command
  * @param data - Clipping data to create
- * @returns Promise resolving to the created clipping response
+ * @returns Promise resolving to the created clip, or the existing clip for the same URL
  */
-export async function createClip(data: CreateClippingRequest): Promise<ClippingResponse> {
+export async function createClip(data: CreateClippingRequest): Promise<CreateClipResult> {
   try {
-    const result = await invokeCommand<ClippingResponse>('create_clip', {
+    const result = await invokeCommand<CreateClipResult>('create_clip', {
       payload: data,
     });
-    console.info('Clipping created successfully:', result.id);
+    console.info(result.message, result.clip.id);
     return result;
   } catch (error) {
     console.error('Error creating clipping:', error);
@@ -72,21 +103,50 @@ export async function createClip(data: CreateClippingRequest): Promise<ClippingR
   }
 }
 
+export type ClipSortField = 'created_at' | 'updated_at' | 'title' | 'source_domain';
+
+export type SortDirection = 'asc' | 'desc';
+
 /**
- * List clippings with optional pagination using Tauri command
- * @param params - Optional pagination parameters (limit, offset)
- * @returns Promise resolving to array of clipping responses
+ * Sorting, filtering and paging for listClips; every field is optional
  */
-export async function listClips(params?: {
-  limit?: number;
-  offset?: number;
-}): Promise<ClippingResponse[]> {
+export interface ListClipsParams {
+  sort_by?: ClipSortField;
+  sort_dir?: SortDirection;
+  read_status?: number;
+  source_domain?: string;
+  /** Only clips carrying all of these labels */
+  label_ids?: string[];
+  /** Page number, starting at 1 */
+  page?: number;
+  page_size?: number;
+}
+
+export interface PaginatedClips {
+  clips: ClippingResponse[];
+  total: number;
+  page: number;
+  page_size: number;
+  has_more: boolean;
+  /** Relevance of each clip (0-100, higher is better), set by searchClippings */
+  scores?: number[];
+}
+
+export interface ClipStats {
+  total: number;
+  unread: number;
+  by_domain: { domain: string; count: number }[];
+}
+
+/**
+ * List one page of clippings using Tauri command
+ * @param params - Sort order, filters and page (defaults: newest first, page 1 of 50)
+ * @returns Promise resolving to the page of clippings
+ */
+export async function listClips(params?: ListClipsParams): Promise<PaginatedClips> {
   try {
-    const result = await invokeCommand<ClippingResponse[]>('list_clips', {
-      limit: params?.limit,
-      offset: params?.offset,
-    });
-    console.info('Clippings loaded successfully:', result.length);
+    const result = await invokeCommand<PaginatedClips>('list_clips', { params });
+    console.info('Clippings loaded successfully:', result.clips.length);
     return result;
   } catch (error) {
     console.error('Error listing clippings:', JSON.stringify(error, null, 2));
@@ -96,6 +156,42 @@ export async function listClips(params?: {
   }
 }
 
+/**
+ * Full-text search across clipping title, excerpt and content, best matches
+ * first. Title matches rank above matches in the body.
+ * @param query - Search words; every word must match
+ * @param page - Page number, starting at 1
+ * @param pageSize - Clippings per page (at most 200)
+ */
+export async function searchClippings(
+  query: string,
+  page: number,
+  pageSize: number
+): Promise<PaginatedClips> {
+  return invokeCommand<PaginatedClips>('search_clippings', { query, page, pageSize });
+}
+
+/**
+ * Get a page of the clippings carrying a label, newest first
+ * @param labelId - The label ID
+ * @param page - Page number, starting at 1
+ * @param pageSize - Clippings per page (at most 200)
+ */
+export async function getClipsByLabel(
+  labelId: string,
+  page: number,
+  pageSize: number
+): Promise<PaginatedClips> {
+  return invokeCommand<PaginatedClips>('get_clips_by_label', { labelId, page, pageSize });
+}
+
+/**
+ * Count all clippings, unread clippings and clippings per source domain
+ */
+export async function getClipStats(): Promise<ClipStats> {
+  return invokeCommand<ClipStats>('get_clip_stats');
+}
+
 /**
  * Get a single clipping by ID using Tauri command
  * @param id - The clipping ID to retrieve
@@ -117,6 +213,79 @@ export async function getClip(id: string): Promise<ClippingResponse> {
   }
 }
 
+/**
+ * Update a clipping's title, notes, tags, read status or excerpt
+ * @param id - The clipping ID
+ * @param data - Fields to change
+ * @returns Promise resolving to the updated clipping
+ */
+export async function updateClip(
+  id: string,
+  data: UpdateClippingRequest
+): Promise<ClippingResponse> {
+  try {
+    const result = await invokeCommand<ClippingResponse>('update_clip', {
+      id,
+      payload: data,
+    });
+    console.info('Clipping updated successfully:', id);
+    return result;
+  } catch (error) {
+    console.error(`Error updating clipping ${id}:`, error);
+    throw new Error(
+      `Failed to update clipping: ${error instanceof Error ? error.message : String(error)}`
+    );
+  }
+}
+
+/**
+ * Permanently delete a clipping, its comments and downloaded images
+ * @param id - The clipping ID
+ */
+export async function deleteClip(id: string): Promise<void> {
+  try {
+    await invokeCommand<void>('delete_clip', { id });
+    console.info('Clipping deleted successfully:', id);
+  } catch (error) {
+    console.error(`Error deleting clipping ${id}:`, error);
+    throw new Error(
+      `Failed to delete clipping: ${error instanceof Error ? error.message : String(error)}`
+    );
+  }
+}
+
+/**
+ * Attach a label to a clipping
+ * @param clipId - The clipping ID
+ * @param labelId - The label ID
+ */
+export async function addClipLabel(clipId: string, labelId: string): Promise<void> {
+  try {
+    await invokeCommand<void>('add_clip_label', { clipId, labelId });
+  } catch (error) {
+    console.error(`Error adding label ${labelId} to clipping ${clipId}:`, error);
+    throw new Error(
+      `Failed to add label: ${error instanceof Error ? error.message : String(error)}`
+    );
+  }
+}
+
+/**
+ * Detach a label from a clipping
+ * @param clipId - The clipping ID
+ * @param labelId - The label ID
+ */
+export async function removeClipLabel(clipId: string, labelId: string): Promise<void> {
+  try {
+    await invokeCommand<void>('remove_clip_label', { clipId, labelId });
+  } catch (error) {
+    console.error(`Error removing label ${labelId} from clipping ${clipId}:`, error);
+    throw new Error(
+      `Failed to remove label: ${error instanceof Error ? error.message : String(error)}`
+    );
+  }
+}
+
 /**
  * Add a comment to a clipping
  * @param clipId - The clipping ID
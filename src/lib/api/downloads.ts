@@ -0,0 +1,45 @@
+/**
+ * Download API functions
+ * Attachment downloads from URLs, their progress and cancellation
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface DownloadProgress {
+  id: string;
+  url: string;
+  file_name: string;
+  bytes_received: number;
+  total_bytes: number | null;
+  started_at: string;
+}
+
+/**
+ * Download a file and attach it to a paper. Interrupted downloads resume
+ * when the same URL is downloaded again.
+ * @param paperId - The paper ID
+ * @param url - http(s) URL of the file
+ * @param fileName - Optional file name; defaults to the last URL path segment
+ */
+export async function downloadAttachmentFromUrl(
+  paperId: string,
+  url: string,
+  fileName?: string,
+): Promise<any> {
+  return invokeCommand('download_attachment_from_url', { paperId, url, fileName });
+}
+
+/**
+ * List running downloads, oldest first
+ */
+export async function listActiveDownloads(): Promise<DownloadProgress[]> {
+  return invokeCommand<DownloadProgress[]>('list_active_downloads');
+}
+
+/**
+ * Cancel a running download and discard its partial file
+ * @param id - Download ID from listActiveDownloads
+ */
+export async function cancelDownload(id: string): Promise<void> {
+  return invokeCommand<void>('cancel_download', { id });
+}
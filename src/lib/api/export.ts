@@ -0,0 +1,97 @@
+/**
+ * Export API functions
+ * Markdown export of papers with notes and highlights, and portable
+ * library zips
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+/** What to do when a paper's Markdown file already exists */
+export type CollisionStrategy = 'skip' | 'overwrite' | 'append_hash';
+
+export interface MarkdownExport {
+  path: string;
+  /** False when the file already existed and was skipped */
+  written: boolean;
+}
+
+export interface MarkdownExportResult {
+  total: number;
+  written: number;
+  skipped: number;
+  failed: number;
+  errors: string[];
+}
+
+/** Payload of the `markdown-export:progress` event */
+export interface MarkdownExportProgress {
+  current: number;
+  total: number;
+  title: string;
+}
+
+/**
+ * Export one paper as Markdown with YAML front matter
+ * @param paperId - Paper to export
+ * @param targetDir - Folder to write into, e.g. an Obsidian vault
+ * @param template - Custom template; the built-in layout when omitted
+ * @param onConflict - Strategy for an existing file (default `append_hash`)
+ */
+export async function exportPaperToMarkdown(
+  paperId: string,
+  targetDir: string,
+  template?: string | null,
+  onConflict?: CollisionStrategy
+): Promise<MarkdownExport> {
+  return invokeCommand<MarkdownExport>('export_paper_to_markdown', {
+    paperId,
+    targetDir,
+    template,
+    onConflict,
+  });
+}
+
+/**
+ * Export every paper in the library as Markdown, one file per paper
+ * @param targetDir - Folder to write into
+ * @param template - Custom template; the built-in layout when omitted
+ * @param onConflict - Strategy for existing files (default `append_hash`)
+ */
+export async function exportAllToMarkdown(
+  targetDir: string,
+  template?: string | null,
+  onConflict?: CollisionStrategy
+): Promise<MarkdownExportResult> {
+  return invokeCommand<MarkdownExportResult>('export_all_to_markdown', {
+    targetDir,
+    template,
+    onConflict,
+  });
+}
+
+export interface LibraryExportResult {
+  path: string;
+  size_bytes: number;
+  paper_count: number;
+  /** Attachment files included in the zip */
+  attachment_count: number;
+}
+
+/** Payload of the `export-progress` event */
+export interface ExportProgress {
+  total_files: number;
+  processed: number;
+  current_file: string;
+}
+
+/**
+ * Export the library as a zip with library.json, bibtex.bib and optionally the PDFs
+ * @param outputPath - Path of the zip to write
+ * @param includePdfs - Also include the attachment files
+ */
+export async function exportLibraryAsZip(
+  outputPath: string,
+  includePdfs: boolean
+): Promise<LibraryExportResult> {
+  return invokeCommand<LibraryExportResult>('export_library_as_zip', { outputPath, includePdfs });
+}
@@ -0,0 +1,44 @@
+/**
+ * GROBID API functions
+ * Server list and health checks for PDF metadata extraction
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface GrobidServer {
+  id: string;
+  name: string;
+  url: string;
+  is_active: boolean;
+}
+
+export interface GrobidHealth {
+  url: string;
+  reachable: boolean;
+  /** Only reported by servers whose `isalive` answer includes it */
+  version?: string;
+  response_ms: number;
+}
+
+/**
+ * Ping a GROBID server, waiting at most 5 seconds
+ * @param url - Server base URL, e.g. `http://localhost:8070`
+ */
+export async function checkGrobidServer(url: string): Promise<GrobidHealth> {
+  return invokeCommand<GrobidHealth>('check_grobid_server', { url });
+}
+
+/**
+ * Get the configured GROBID servers
+ */
+export async function getGrobidServers(): Promise<GrobidServer[]> {
+  return invokeCommand<GrobidServer[]>('get_grobid_servers');
+}
+
+/**
+ * Use the configured server with this URL for PDF imports
+ * @param url - URL of a configured server
+ */
+export async function setActiveGrobidServer(url: string): Promise<void> {
+  return invokeCommand<void>('set_active_grobid_server', { url });
+}
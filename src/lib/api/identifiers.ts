@@ -0,0 +1,24 @@
+/**
+ * Identifier API functions
+ * Recognizing DOIs, arXiv IDs, ISBNs and PMIDs to pre-fill import dialogs
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export type IdentifierType = 'doi' | 'arxiv_id' | 'pmid' | 'isbn';
+
+export interface DetectedIdentifier {
+  identifier_type: IdentifierType;
+  /** Identifier without prefixes or URL, e.g. `10.1000/xyz` */
+  value: string;
+  /** 0-1; bare digit strings detected as PMIDs score low */
+  confidence: number;
+}
+
+/**
+ * Look for an identifier in the clipboard; detection runs locally
+ * @returns The identifier, or null when the clipboard holds none
+ */
+export async function detectIdentifierFromClipboard(): Promise<DetectedIdentifier | null> {
+  return invokeCommand<DetectedIdentifier | null>('detect_identifier_from_clipboard');
+}
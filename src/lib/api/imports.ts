@@ -0,0 +1,212 @@
+/**
+ * Import API functions
+ * Batch imports of PDFs from a folder, of CSV files and of library zips,
+ * and imports from the URL of a paper's page
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface BatchImportResult {
+  total: number;
+  imported: number;
+  /** Papers already in the library (same DOI) */
+  skipped: number;
+  failed: number;
+  papers: Array<{ id: string; title: string }>;
+  /** One entry per failed file, prefixed with its path */
+  errors: string[];
+}
+
+/** Payload of the `folder-import-progress` event */
+export interface FolderImportProgress {
+  total: number;
+  processed: number;
+  succeeded: number;
+  failed: number;
+  /** Path relative to the imported folder */
+  current_file: string;
+}
+
+/**
+ * Import every PDF in a folder using GROBID metadata extraction
+ * @param folderPath - Folder to scan
+ * @param categoryId - Category for the imported papers
+ * @param recursive - Also scan subfolders
+ */
+export async function importPapersFromFolder(
+  folderPath: string,
+  categoryId: string | null,
+  recursive: boolean
+): Promise<BatchImportResult> {
+  return invokeCommand<BatchImportResult>('import_papers_from_folder', {
+    folderPath,
+    categoryId,
+    recursive,
+  });
+}
+
+export type PdfImportStage = 'queued' | 'extracting' | 'saving' | 'done' | 'failed';
+
+/** A file in the PDF import queue, also the payload of `pdf-import:progress` */
+export interface PdfImportItem {
+  id: number;
+  file_path: string;
+  stage: PdfImportStage;
+  paper_id: string | null;
+  /** Done without a new paper because the DOI is already in the library */
+  already_exists: boolean;
+  message: string | null;
+}
+
+export interface PdfBatchImportReport {
+  total: number;
+  imported: number;
+  skipped: number;
+  failed: number;
+  items: PdfImportItem[];
+}
+
+export interface PdfImportQueueStatus {
+  items: PdfImportItem[];
+  queued: number;
+  in_progress: number;
+  done: number;
+  failed: number;
+}
+
+/**
+ * Import dropped PDF files through the import queue
+ * @param filePaths - Absolute paths of the PDFs
+ * @param categoryId - Category for the imported papers
+ */
+export async function importPdfsBatch(
+  filePaths: string[],
+  categoryId: string | null
+): Promise<PdfBatchImportReport> {
+  return invokeCommand<PdfBatchImportReport>('import_pdfs_batch', { filePaths, categoryId });
+}
+
+/**
+ * Get the files in the PDF import queue and their stages
+ */
+export async function getImportQueueStatus(): Promise<PdfImportQueueStatus> {
+  return invokeCommand<PdfImportQueueStatus>('get_import_queue_status');
+}
+
+/** Which CSV column holds each paper field; only `title` is required */
+export interface CsvColumnMapping {
+  title: string;
+  /** Authors separated by `;` */
+  authors?: string | null;
+  year?: string | null;
+  doi?: string | null;
+  journal?: string | null;
+  url?: string | null;
+  abstract?: string | null;
+  /** Labels separated by `;` */
+  labels?: string | null;
+}
+
+export interface CsvRowError {
+  /** Line in the file, counting the header as line 1 */
+  line: number;
+  message: string;
+}
+
+export interface CsvImportResult {
+  total: number;
+  /** Papers created, or that would be created in a dry run */
+  imported: number;
+  /** Rows whose DOI is already in the library or earlier in the file */
+  skipped: number;
+  failed: number;
+  dry_run: boolean;
+  paper_ids: string[];
+  errors: CsvRowError[];
+}
+
+/** Payload of the `csv-import:progress` event */
+export interface CsvImportProgress {
+  processed: number;
+  dry_run: boolean;
+}
+
+/**
+ * Import papers from a CSV file such as a Mendeley export
+ * @param filePath - CSV file with a header row
+ * @param mapping - Column name for each paper field
+ * @param dryRun - Only parse and validate, without writing anything
+ */
+export async function importPapersFromCsv(
+  filePath: string,
+  mapping: CsvColumnMapping,
+  dryRun = false
+): Promise<CsvImportResult> {
+  return invokeCommand<CsvImportResult>('import_papers_from_csv', {
+    filePath,
+    mapping,
+    dryRun,
+  });
+}
+
+/** What to do with an imported paper that is already in the library */
+export type MergeStrategy = 'skip_existing' | 'overwrite_existing' | 'create_duplicate';
+
+export interface ImportZipResult {
+  papers_imported: number;
+  papers_skipped: number;
+  papers_updated: number;
+  errors: string[];
+}
+
+/** Payload of the `import-progress` event */
+export interface ImportProgress {
+  total_papers: number;
+  processed: number;
+  current_title: string;
+}
+
+/**
+ * Import a zip written by exportLibraryAsZip
+ * @param zipPath - Zip to import
+ * @param mergeStrategy - How to handle papers already in the library
+ */
+export async function importLibraryFromZip(
+  zipPath: string,
+  mergeStrategy: MergeStrategy
+): Promise<ImportZipResult> {
+  return invokeCommand<ImportZipResult>('import_library_from_zip', { zipPath, mergeStrategy });
+}
+
+/** Result of importing a single paper */
+export interface PaperImportResult {
+  /** A paper with the same DOI or URL is already in the library */
+  already_exists: boolean;
+  message: string;
+  paper: { id: string; title: string } | null;
+  /** Suggested categories for a paper imported without a category */
+  category_suggestions?: Array<{
+    category: { id: string; name: string };
+    confidence: number;
+    reason: string;
+  }>;
+}
+
+/**
+ * Import a paper from the URL of its page. A DOI or arXiv ID in the URL is
+ * imported as such; otherwise the page's metadata tags are read.
+ * @param url - http or https URL of the paper's page
+ * @param categoryId - Category for the imported paper
+ * @param saveAsClipping - Save the page as a clipping when no paper can be imported
+ */
+export async function importPaperByUrl(
+  url: string,
+  categoryId?: string | null,
+  saveAsClipping?: boolean
+): Promise<PaperImportResult> {
+  return invokeCommand<PaperImportResult>('import_paper_by_url', {
+    url,
+    categoryId,
+    saveAsClipping,
+  });
+}
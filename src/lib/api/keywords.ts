@@ -0,0 +1,92 @@
+/**
+ * Keyword API functions
+ * Browsing, editing, searching, deleting and merging paper keywords
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface Keyword {
+  id: string;
+  word: string;
+  paper_count: number;
+}
+
+export interface PaginatedPapers {
+  papers: any[];
+  total: number;
+  offset: number;
+  limit: number;
+  has_more: boolean;
+}
+
+/**
+ * Get all keywords, most used first
+ */
+export async function getAllKeywords(): Promise<Keyword[]> {
+  return invokeCommand<Keyword[]>('get_all_keywords');
+}
+
+/**
+ * Get the keywords of a paper, most used first
+ * @param paperId - The paper ID
+ */
+export async function getPaperKeywords(paperId: string): Promise<Keyword[]> {
+  return invokeCommand<Keyword[]>('get_paper_keywords', { paperId });
+}
+
+/**
+ * Add a keyword to a paper, reusing an existing keyword that differs only in case
+ * @param paperId - The paper ID
+ * @param word - The keyword
+ */
+export async function addPaperKeyword(paperId: string, word: string): Promise<Keyword> {
+  return invokeCommand<Keyword>('add_paper_keyword', { paperId, word });
+}
+
+/**
+ * Remove a keyword from a paper; the keyword itself is kept
+ * @param paperId - The paper ID
+ * @param keywordId - The keyword ID
+ */
+export async function removePaperKeyword(paperId: string, keywordId: string): Promise<void> {
+  return invokeCommand<void>('remove_paper_keyword', { paperId, keywordId });
+}
+
+/**
+ * Search keywords for autocomplete; keywords starting with the query come first
+ * @param query - Search text
+ */
+export async function searchKeywords(query: string): Promise<Keyword[]> {
+  return invokeCommand<Keyword[]>('search_keywords', { query });
+}
+
+/**
+ * Get one page of the papers using a keyword, newest first
+ * @param keywordId - The keyword ID
+ * @param page - Page number, starting at 1
+ * @param pageSize - Papers per page (at most 200)
+ */
+export async function getPapersByKeyword(
+  keywordId: string,
+  page: number,
+  pageSize: number,
+): Promise<PaginatedPapers> {
+  return invokeCommand<PaginatedPapers>('get_papers_by_keyword', { keywordId, page, pageSize });
+}
+
+/**
+ * Delete a keyword and remove it from all papers
+ * @param keywordId - The keyword ID
+ */
+export async function deleteKeyword(keywordId: string): Promise<void> {
+  return invokeCommand<void>('delete_keyword', { keywordId });
+}
+
+/**
+ * Merge synonyms into one keyword
+ * @param primaryId - The keyword to keep
+ * @param secondaryIds - Keywords whose papers move to the primary keyword; they are deleted
+ */
+export async function mergeKeywords(primaryId: string, secondaryIds: string[]): Promise<Keyword> {
+  return invokeCommand<Keyword>('merge_keywords', { primaryId, secondaryIds });
+}
@@ -0,0 +1,106 @@
+/**
+ * Label API functions
+ * Merging, cleanup, reordering, grouping and usage statistics of labels
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+import type { RecentPaper } from './activity';
+
+export interface Label {
+  id: string;
+  name: string;
+  color: string;
+  /** Papers and clips carrying exactly this label */
+  document_count: number;
+  sort_order: number;
+  parent_id: string | null;
+  /** Papers and clips carrying this label or a label below it; set when listing labels */
+  recursive_count?: number;
+}
+
+export interface LabelNode {
+  id: number;
+  name: string;
+  color: string;
+  parent_id: number | null;
+  sort_order: number;
+  document_count: number;
+  recursive_count: number;
+  children: LabelNode[];
+}
+
+export interface LabelMergeResult {
+  label: Label;
+  /** Paper and clip links moved to the primary label */
+  relations_moved: number;
+  /** Links dropped because the document already carried the primary label */
+  duplicates_dropped: number;
+}
+
+export interface LabelUsage {
+  label: Label;
+  paper_count: number;
+  clip_count: number;
+  /** Last update of a paper or clip carrying the label */
+  recently_used_at: string | null;
+}
+
+/**
+ * Move the papers and clips of the secondary labels to the primary label
+ * and delete the secondaries
+ */
+export async function mergeLabels(
+  primaryId: string,
+  secondaryIds: string[]
+): Promise<LabelMergeResult> {
+  return invokeCommand<LabelMergeResult>('merge_labels', { primaryId, secondaryIds });
+}
+
+/**
+ * Labels no paper or clip carries
+ */
+export async function findUnusedLabels(): Promise<Label[]> {
+  return invokeCommand<Label[]>('find_unused_labels');
+}
+
+/**
+ * Delete several labels at once; returns how many were deleted
+ */
+export async function deleteLabels(ids: string[]): Promise<number> {
+  return invokeCommand<number>('delete_labels', { ids });
+}
+
+/**
+ * Store the label order; labels missing from the list follow the listed ones
+ */
+export async function reorderLabels(labelIdsInOrder: string[]): Promise<void> {
+  return invokeCommand('reorder_labels', { labelIdsInOrder });
+}
+
+/**
+ * Paper and clip counts of every label and when it was last used
+ */
+export async function getLabelUsageStats(): Promise<LabelUsage[]> {
+  return invokeCommand<LabelUsage[]>('get_label_usage_stats');
+}
+
+/**
+ * Labels as a tree, with the labels grouped under each one as children
+ */
+export async function getLabelTree(): Promise<LabelNode[]> {
+  return invokeCommand<LabelNode[]>('get_label_tree');
+}
+
+/**
+ * Papers carrying the label or any label below it
+ */
+export async function getPapersByLabel(labelId: string): Promise<RecentPaper[]> {
+  return invokeCommand<RecentPaper[]>('get_papers_by_label', { labelId });
+}
+
+/**
+ * Move a label under another one; pass an empty string to move it to the top level
+ */
+export async function setLabelParent(id: string, parentId: string): Promise<Label> {
+  return invokeCommand<Label>('update_label', { id, parentId });
+}
@@ -0,0 +1,48 @@
+/**
+ * LLM API functions
+ * Default LLM provider settings, keychain-stored API key and connection test
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export type LlmProviderKind = 'openai' | 'anthropic' | 'ollama' | 'openai_compatible';
+
+export interface LlmConfig {
+  provider: LlmProviderKind;
+  /** New key to store; omit to keep the stored key, empty string to remove it */
+  api_key?: string;
+  /** Whether a key is stored (read-only, the key itself is never returned) */
+  has_api_key?: boolean;
+  api_url: string;
+  model: string;
+  max_tokens: number;
+  temperature: number;
+}
+
+export interface LlmConnectionTest {
+  success: boolean;
+  model: string;
+  response_ms: number;
+  message: string;
+}
+
+/**
+ * Get the default LLM provider's settings
+ */
+export async function getLlmConfig(): Promise<LlmConfig> {
+  return invokeCommand<LlmConfig>('get_llm_config');
+}
+
+/**
+ * Save the default LLM provider's settings; the API key goes to the OS keychain
+ */
+export async function saveLlmConfig(config: LlmConfig): Promise<void> {
+  return invokeCommand('save_llm_config', { config });
+}
+
+/**
+ * Send a short probe request to an LLM provider, the default one when no id is given
+ */
+export async function testLlmConnection(providerId?: string): Promise<LlmConnectionTest> {
+  return invokeCommand<LlmConnectionTest>('test_llm_connection', { providerId });
+}
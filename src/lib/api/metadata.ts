@@ -0,0 +1,127 @@
+/**
+ * Metadata API functions
+ * Refreshing citation counts and missing fields from Crossref and OpenAlex,
+ * and open access status from Unpaywall
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface MetadataRefreshResult {
+  paper_id: string;
+  updated_fields: string[];
+}
+
+/** Payload of the `metadata-refresh:progress` event */
+export interface MetadataRefreshProgress {
+  current: number;
+  total: number;
+  paper_id: string;
+  doi: string;
+}
+
+export interface MetadataRefreshFailure {
+  paper_id: string;
+  doi: string;
+  error: string;
+}
+
+export interface MetadataRefreshReport {
+  total: number;
+  updated: number;
+  unchanged: number;
+  failed: MetadataRefreshFailure[];
+  cancelled: boolean;
+}
+
+/**
+ * Re-fetch a paper's metadata by DOI; only blank fields are filled in
+ * @param paperId - Paper ID
+ */
+export async function refreshPaperMetadata(paperId: string): Promise<MetadataRefreshResult> {
+  return invokeCommand<MetadataRefreshResult>('refresh_paper_metadata', { paperId });
+}
+
+/**
+ * Fill a paper's blank journal, publisher and ISSN from Crossref and link its subjects as keywords
+ * @param paperId - Paper ID
+ */
+export async function enrichPaperFromCrossref(paperId: string): Promise<void> {
+  return invokeCommand<void>('enrich_paper_from_crossref', { paperId });
+}
+
+/**
+ * Refresh every paper that has a DOI, about one per second
+ */
+export async function refreshAllMetadata(): Promise<MetadataRefreshReport> {
+  return invokeCommand<MetadataRefreshReport>('refresh_all_metadata');
+}
+
+/**
+ * Stop a running refresh before its next paper
+ */
+export async function cancelMetadataRefresh(): Promise<void> {
+  return invokeCommand<void>('cancel_metadata_refresh');
+}
+
+export interface RefreshJob {
+  job_id: string;
+  paper_count: number;
+}
+
+/** Payload of the `citation-refresh-progress` event */
+export interface CitationRefreshProgress {
+  job_id: string;
+  current: number;
+  total: number;
+  updated: number;
+}
+
+/** Payload of the `citation-refresh-complete` event */
+export interface CitationRefreshSummary {
+  job_id: string;
+  total: number;
+  updated: number;
+  failed: number;
+}
+
+/**
+ * Refresh citation counts from OpenAlex in the background
+ * @param paperIds - Papers to refresh regardless of age; omit for all stale papers
+ */
+export async function triggerCitationCountRefresh(paperIds?: string[]): Promise<RefreshJob> {
+  return invokeCommand<RefreshJob>('trigger_citation_count_refresh', { paperIds: paperIds ?? null });
+}
+
+export interface OpenAccessInfo {
+  paper_id: string;
+  doi: string;
+  is_oa: boolean;
+  /** `gold`, `green`, `hybrid`, `bronze` or `closed` */
+  oa_status: string;
+  oa_url: string | null;
+  license: string | null;
+  updated_at: string;
+}
+
+/** Payload of the `open-access:progress` event */
+export interface OpenAccessProgress {
+  current: number;
+  total: number;
+  paper_id: string;
+  doi: string;
+}
+
+/**
+ * Open access status of a paper from Unpaywall, cached for seven days
+ * @param paperId - Paper ID
+ */
+export async function getPaperOpenAccessInfo(paperId: string): Promise<OpenAccessInfo> {
+  return invokeCommand<OpenAccessInfo>('get_paper_open_access_info', { paperId });
+}
+
+/**
+ * Open access status of every paper with a DOI; only stale entries are looked up
+ */
+export async function getOaStatusForAllPapers(): Promise<OpenAccessInfo[]> {
+  return invokeCommand<OpenAccessInfo[]>('get_oa_status_for_all_papers');
+}
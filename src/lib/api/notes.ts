@@ -0,0 +1,73 @@
+/**
+ * Notes API functions
+ * Versioned notes of papers; every save keeps the earlier versions
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export type NoteFormat = 'plain_text' | 'markdown' | 'html';
+
+export interface NoteVersion {
+  paper_id: string;
+  /** Starts at 1; the highest version is the current note */
+  version: number;
+  content: string;
+  content_format: NoteFormat;
+  created_at: string;
+}
+
+/**
+ * Save new notes for a paper as its next version
+ * @param paperId - The paper ID
+ * @param content - Note content
+ * @param format - Format of `content`
+ */
+export async function updatePaperNotes(
+  paperId: string,
+  content: string,
+  format: NoteFormat
+): Promise<NoteVersion> {
+  return invokeCommand<NoteVersion>('update_paper_notes', { paperId, content, format });
+}
+
+/**
+ * Get the current notes of a paper
+ * @param paperId - The paper ID
+ */
+export async function getPaperNotes(paperId: string): Promise<NoteVersion | null> {
+  return invokeCommand<NoteVersion | null>('get_paper_notes', { paperId });
+}
+
+/**
+ * Get every saved version of a paper's notes, newest first
+ * @param paperId - The paper ID
+ */
+export async function getPaperNotesHistory(paperId: string): Promise<NoteVersion[]> {
+  return invokeCommand<NoteVersion[]>('get_paper_notes_history', { paperId });
+}
+
+/**
+ * Make an earlier version the current notes; it is saved again as a new version
+ * @param paperId - The paper ID
+ * @param version - Version to restore
+ */
+export async function restoreNoteVersion(paperId: string, version: number): Promise<NoteVersion> {
+  return invokeCommand<NoteVersion>('restore_note_version', { paperId, version });
+}
+
+export interface NoteSearchResult {
+  paper_id: string;
+  paper_title: string;
+  /** About 200 characters of the note around the first match */
+  note_excerpt: string;
+  /** Byte spans `[start, end)` of the matches within `note_excerpt` (UTF-8 offsets) */
+  match_positions: [number, number][];
+}
+
+/**
+ * Full-text search across the current notes of all papers
+ * @param query - Search words; every word must match
+ */
+export async function searchPaperNotes(query: string): Promise<NoteSearchResult[]> {
+  return invokeCommand<NoteSearchResult[]>('search_paper_notes', { query });
+}
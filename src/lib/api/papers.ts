@@ -0,0 +1,282 @@
+/**
+ * Paper API functions
+ * Starring papers, listing starred papers, custom fields, the reading list, languages and the timeline
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+import type { PaginatedPapers } from './keywords';
+
+/**
+ * Star or unstar a paper; papers in the trash cannot be starred
+ * @param paperId - The paper ID
+ * @returns Whether the paper is now starred
+ */
+export async function togglePaperStar(paperId: string): Promise<boolean> {
+  return invokeCommand<boolean>('toggle_paper_star', { paperId });
+}
+
+/**
+ * Star a paper; starring a starred paper keeps the time it was starred
+ * @param paperId - The paper ID
+ */
+export async function starPaper(paperId: string): Promise<void> {
+  return invokeCommand<void>('star_paper', { paperId });
+}
+
+/**
+ * Unstar a paper
+ * @param paperId - The paper ID
+ */
+export async function unstarPaper(paperId: string): Promise<void> {
+  return invokeCommand<void>('unstar_paper', { paperId });
+}
+
+/**
+ * Get a page of the starred papers that are not in the trash, most recently starred first
+ * @param page - Page number, starting at 1
+ * @param pageSize - Papers per page, at most 200
+ */
+export async function getStarredPapers(page: number, pageSize: number): Promise<PaginatedPapers> {
+  return invokeCommand<PaginatedPapers>('get_starred_papers', { page, pageSize });
+}
+
+export type MissingField = 'abstract' | 'doi' | 'year' | 'journal' | 'authors' | 'keywords' | 'pdf';
+
+/**
+ * Get papers missing any of the given fields, most recently added first
+ * @param fields - Fields to check; `journal` also accepts a conference name
+ */
+export async function getPapersWithMissingMetadata(fields: MissingField[]): Promise<any[]> {
+  return invokeCommand<any[]>('get_papers_with_missing_metadata', { fields });
+}
+
+/**
+ * Get one page of papers, newest first
+ * @param offset - Papers to skip
+ * @param limit - Page size
+ * @param starredOnly - Only list starred papers
+ */
+export async function getPapersPaginated(
+  offset: number,
+  limit: number,
+  starredOnly?: boolean
+): Promise<PaginatedPapers> {
+  return invokeCommand<PaginatedPapers>('get_papers_paginated', { offset, limit, starredOnly });
+}
+
+export type CustomFieldType = 'text' | 'number' | 'date' | 'bool';
+
+export interface CustomField {
+  paper_id: string;
+  key: string;
+  /** Stored form: numbers like `1.5`, dates as YYYY-MM-DD, booleans as `true` or `false` */
+  value: string;
+  value_type: CustomFieldType;
+  updated_at: string;
+}
+
+export interface CustomFieldKey {
+  key: string;
+  value_type: CustomFieldType;
+  paper_count: number;
+}
+
+/**
+ * Set a custom field on a paper, replacing its earlier value
+ * @param paperId - The paper ID
+ * @param key - Letters, digits, `_`, `-` and `.`
+ * @param value - Checked against `valueType`
+ * @param valueType - Type of the value; must match the key's type while other papers use the key
+ */
+export async function setPaperCustomField(
+  paperId: string,
+  key: string,
+  value: string,
+  valueType: CustomFieldType
+): Promise<CustomField> {
+  return invokeCommand<CustomField>('set_paper_custom_field', { paperId, key, value, valueType });
+}
+
+/**
+ * Remove a custom field from a paper
+ * @param paperId - The paper ID
+ * @param key - The field key
+ */
+export async function deletePaperCustomField(paperId: string, key: string): Promise<void> {
+  return invokeCommand<void>('delete_paper_custom_field', { paperId, key });
+}
+
+/**
+ * Get the custom field keys in use with their type, most used first
+ */
+export async function listCustomFieldKeys(): Promise<CustomFieldKey[]> {
+  return invokeCommand<CustomFieldKey[]>('list_custom_field_keys');
+}
+
+/**
+ * Put a paper at the end of the reading queue; a queued paper moves to the end
+ * @param paperId - The paper ID
+ */
+export async function enqueuePaper(paperId: string): Promise<void> {
+  return invokeCommand<void>('enqueue_paper', { paperId });
+}
+
+/**
+ * Take a paper out of the reading queue
+ * @param paperId - The paper ID
+ */
+export async function dequeuePaper(paperId: string): Promise<void> {
+  return invokeCommand<void>('dequeue_paper', { paperId });
+}
+
+/**
+ * Reorder the reading queue
+ * @param paperIdsInOrder - Every queued paper ID exactly once, next to read first
+ */
+export async function reorderReadingQueue(paperIdsInOrder: string[]): Promise<void> {
+  return invokeCommand<void>('reorder_reading_queue', { paperIdsInOrder });
+}
+
+/**
+ * Get the queued papers in queue order, next to read first
+ */
+export async function getReadingQueue(): Promise<any[]> {
+  return invokeCommand<any[]>('get_reading_queue');
+}
+
+export interface ReadingListItem {
+  paper_id: string;
+  title: string;
+  position: number;
+  added_at: string;
+  due_date: string | null;
+}
+
+/**
+ * Add a paper to the reading list; a listed paper keeps its position and gets the new due date
+ * @param paperId - Paper ID
+ * @param dueDate - YYYY-MM-DD or RFC 3339 timestamp
+ */
+export async function addToReadingList(paperId: string, dueDate?: string): Promise<ReadingListItem> {
+  return invokeCommand<ReadingListItem>('add_to_reading_list', { paperId, dueDate: dueDate ?? null });
+}
+
+/**
+ * Take a paper off the reading list
+ * @param paperId - Paper ID
+ */
+export async function removeFromReadingList(paperId: string): Promise<void> {
+  return invokeCommand<void>('remove_from_reading_list', { paperId });
+}
+
+/**
+ * Reorder the reading list
+ * @param paperIdsInOrder - Every listed paper ID exactly once, next to read first
+ */
+export async function reorderReadingList(paperIdsInOrder: string[]): Promise<void> {
+  return invokeCommand<void>('reorder_reading_list', { paperIdsInOrder });
+}
+
+/**
+ * Get the reading list entries in queue order
+ */
+export async function getReadingList(): Promise<ReadingListItem[]> {
+  return invokeCommand<ReadingListItem[]>('get_reading_list');
+}
+
+/**
+ * Get the overdue paper with the earliest due date, or the head of the reading list
+ */
+export async function getNextPaperToRead(): Promise<any | null> {
+  return invokeCommand<any | null>('get_next_paper_to_read');
+}
+
+export interface LanguageDetection {
+  paper_id: string;
+  /** ISO 639-1 code, e.g. `en` */
+  language_code: string;
+  language_name: string;
+  /** Between 0 and 1 */
+  confidence: number;
+}
+
+export interface LanguageDetectionSummary {
+  processed: number;
+  detected: number;
+  undetected: number;
+  languages: { language_code: string; language_name: string; paper_count: number }[];
+}
+
+/**
+ * Detect the language of a paper from its abstract (or title) and store it
+ * @param paperId - Paper ID
+ */
+export async function detectPaperLanguage(paperId: string): Promise<LanguageDetection> {
+  return invokeCommand<LanguageDetection>('detect_paper_language', { paperId });
+}
+
+/**
+ * Detect the language of every paper that does not have one yet
+ */
+export async function detectLanguageForAllPapers(): Promise<LanguageDetectionSummary> {
+  return invokeCommand<LanguageDetectionSummary>('detect_language_for_all_papers');
+}
+
+/**
+ * Get one page of the papers in a language, newest first
+ * @param languageCode - ISO 639-1 code, e.g. `en`
+ * @param page - Page number, starting at 1
+ * @param pageSize - Papers per page (at most 200)
+ */
+export async function getPapersByLanguage(
+  languageCode: string,
+  page: number,
+  pageSize: number
+): Promise<PaginatedPapers> {
+  return invokeCommand<PaginatedPapers>('get_papers_by_language', { languageCode, page, pageSize });
+}
+
+export type AddedBucket = 'today' | 'this_week' | 'this_month' | 'earlier';
+
+export interface AddedBucketGroup {
+  bucket: AddedBucket;
+  total: number;
+  /** The newest papers of the bucket */
+  papers: any[];
+  has_more: boolean;
+}
+
+/** Minutes east of UTC of the local time zone */
+function localUtcOffset(): number {
+  return -new Date().getTimezoneOffset();
+}
+
+/**
+ * Group papers by when they were added (today, this week, this month, earlier) in the local time zone
+ * @param papersPerBucket - Papers returned per group (at most 200)
+ */
+export async function getPapersGroupedByAdded(papersPerBucket: number): Promise<AddedBucketGroup[]> {
+  return invokeCommand<AddedBucketGroup[]>('get_papers_grouped_by_added', {
+    utcOffsetMinutes: localUtcOffset(),
+    papersPerBucket,
+  });
+}
+
+/**
+ * Get one page of the papers in a date-added group, newest first
+ * @param bucket - The group
+ * @param offset - Papers to skip
+ * @param limit - Page size (at most 200)
+ */
+export async function getPapersInBucket(
+  bucket: AddedBucket,
+  offset: number,
+  limit: number
+): Promise<PaginatedPapers> {
+  return invokeCommand<PaginatedPapers>('get_papers_in_bucket', {
+    bucket,
+    offset,
+    limit,
+    utcOffsetMinutes: localUtcOffset(),
+  });
+}
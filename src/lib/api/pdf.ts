@@ -12,6 +12,14 @@ export interface PdfBlobResponse {
   size_bytes: number;
 }
 
+export interface OcrResult {
+  paper_id: string;
+  page_count: number;
+  extracted_text: string;
+  /** True when the text was read from the PDF rather than recognized by OCR */
+  has_text_layer: boolean;
+}
+
 export interface PdfSaveResponse {
   success: boolean;
   file_path: string;
@@ -168,3 +176,12 @@ export function blobToBase64(blob: Blob): Promise<string> {
 export function revokePdfBlobUrl(blobUrl: string): void {
   URL.revokeObjectURL(blobUrl);
 }
+
+/**
+ * Extract a paper's PDF text and store it for full-text indexing. Scanned
+ * PDFs without a text layer are run through OCR.
+ * @param paperId - The paper ID
+ */
+export async function extractTextFromScannedPdf(paperId: string): Promise<OcrResult> {
+  return invokeCommand<OcrResult>('extract_text_from_scanned_pdf', { paperId });
+}
@@ -0,0 +1,47 @@
+/**
+ * Reading progress API functions
+ * Records the page shown in the PDF viewer and reads back progress per paper
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface ReadingSession {
+  started_at: string;
+  ended_at: string;
+  pages_read: number;
+}
+
+export interface ReadingProgress {
+  paper_id: string;
+  current_page: number;
+  total_pages: number;
+  reading_percentage: number;
+  last_read_at: string;
+  reading_sessions: ReadingSession[];
+}
+
+/**
+ * Record the page currently being read
+ * @param paperId - The paper ID
+ * @param currentPage - 1-based page number
+ * @param totalPages - Number of pages in the document
+ */
+export async function updateReadingProgress(
+  paperId: string,
+  currentPage: number,
+  totalPages: number
+): Promise<ReadingProgress> {
+  return invokeCommand<ReadingProgress>('update_reading_progress', {
+    paperId,
+    currentPage,
+    totalPages,
+  });
+}
+
+/**
+ * Get the reading progress of a paper, or null if it was never opened
+ * @param paperId - The paper ID
+ */
+export async function getReadingProgress(paperId: string): Promise<ReadingProgress | null> {
+  return invokeCommand<ReadingProgress | null>('get_reading_progress', { paperId });
+}
@@ -0,0 +1,40 @@
+/**
+ * Reference API functions
+ * Bibliographies extracted from paper PDFs with GROBID
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface PaperReference {
+  id: string;
+  /** Zero-based position in the bibliography */
+  position: number;
+  title?: string;
+  authors: string[];
+  year?: number;
+  doi?: string;
+  journal?: string;
+  /** Citation as printed in the PDF */
+  raw_text?: string;
+  /** Whether a paper with the same DOI is in the library */
+  is_in_library: boolean;
+  /** Paper in the library with the same DOI, for an internal link */
+  library_paper_id?: string;
+}
+
+/**
+ * Extract the bibliography of a paper's PDF, replacing earlier results.
+ * Fails with error_type 'Timeout' when GROBID is slow or busy; retrying may help.
+ * @param paperId - Paper ID
+ */
+export async function extractReferences(paperId: string): Promise<PaperReference[]> {
+  return invokeCommand<PaperReference[]>('extract_references', { paperId });
+}
+
+/**
+ * Get the references extracted earlier, in bibliography order
+ * @param paperId - Paper ID
+ */
+export async function getPaperReferences(paperId: string): Promise<PaperReference[]> {
+  return invokeCommand<PaperReference[]>('get_paper_references', { paperId });
+}
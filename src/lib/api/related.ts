@@ -0,0 +1,33 @@
+/**
+ * Related papers API functions
+ * Recommendations shown in the paper detail view
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface RelatedPaper {
+  id: string;
+  title: string;
+  publication_year?: number;
+  journal_name?: string;
+  conference_name?: string;
+  authors: string[];
+  labels: { id: string; name: string; color: string }[];
+  attachment_count: number;
+  publisher?: string;
+  issn?: string;
+  language?: string;
+  /** Higher is better; shared authors or labels can push it above 1 */
+  score: number;
+  /** Why the paper was picked, e.g. "Shared authors: …; Similar abstract" */
+  reason: string;
+}
+
+/**
+ * Get papers similar to a paper, best first
+ * @param paperId - The paper ID
+ * @param limit - Maximum number of papers (default 10, at most 50)
+ */
+export async function getRelatedPapers(paperId: string, limit?: number): Promise<RelatedPaper[]> {
+  return invokeCommand<RelatedPaper[]>('get_related_papers', { paperId, limit });
+}
@@ -0,0 +1,114 @@
+/**
+ * Search API functions
+ * Full-text search across papers, across clippings, and across papers and clippings together,
+ * plus semantic search over paper embeddings
+ */
+
+import { Channel } from '@tauri-apps/api/core';
+import { invokeCommand } from '@/lib/tauri';
+
+export interface PaperSearchResult {
+  id: string;
+  title: string;
+  abstract_text?: string;
+  doi?: string;
+  publication_year?: number;
+  journal_name?: string;
+  is_starred: boolean;
+  score: number;
+  matched_labels: string[];
+  matched_attachments: string[];
+}
+
+export interface ClipSearchResult {
+  id: string;
+  title: string;
+  url: string;
+  excerpt?: string;
+  source_domain?: string;
+  score: number;
+}
+
+export interface SemanticSearchResult {
+  id: string;
+  title: string;
+  abstract_text?: string;
+  doi?: string;
+  publication_year?: number;
+  journal_name?: string;
+  /** Combined score, 0-1 */
+  score: number;
+  /** Cosine similarity to the query, 0-1 */
+  vector_score: number;
+  /** Full-text relevance, 0-1; only in hybrid mode */
+  bm25_score?: number;
+}
+
+export interface ReindexProgress {
+  embedded: number;
+  skipped: number;
+  total: number;
+}
+
+export type SearchHit =
+  | ({ kind: 'paper' } & PaperSearchResult)
+  | ({ kind: 'clip' } & ClipSearchResult);
+
+/**
+ * Full-text search over papers, best matches first
+ * @param query - Search words; supports FTS5 syntax such as AND, OR and NOT, and
+ *   `custom:key=value` terms that keep only papers with that custom field value
+ * @param limit - Maximum number of results (default 50)
+ * @param starredOnly - Only return starred papers
+ */
+export async function searchPapersFts(
+  query: string,
+  limit?: number,
+  starredOnly?: boolean
+): Promise<PaperSearchResult[]> {
+  return invokeCommand<PaperSearchResult[]>('search_papers_fts', { query, limit, starredOnly });
+}
+
+/**
+ * Search clipping titles, excerpts and content; every word must match
+ * @param query - Search words
+ * @param limit - Maximum number of results (default 50)
+ */
+export async function searchClips(query: string, limit?: number): Promise<ClipSearchResult[]> {
+  return invokeCommand<ClipSearchResult[]>('search_clips', { query, limit });
+}
+
+/**
+ * Search papers and clippings together, best matches first
+ * @param query - Search words
+ */
+export async function searchAll(query: string): Promise<SearchHit[]> {
+  return invokeCommand<SearchHit[]>('search_all', { query });
+}
+
+/**
+ * Search papers by meaning using their embeddings
+ * @param query - Free text
+ * @param k - Maximum number of results (default 20)
+ * @param bm25Weight - Share of the score from full-text relevance, 0 (default) to 1
+ */
+export async function searchPapersSemantic(
+  query: string,
+  k?: number,
+  bm25Weight?: number
+): Promise<SemanticSearchResult[]> {
+  return invokeCommand<SemanticSearchResult[]>('search_papers_semantic', { query, k, bm25Weight });
+}
+
+/**
+ * Embed papers whose embedding is missing or stale. Safe to call again after
+ * a failure; papers already embedded are skipped.
+ * @param onProgress - Called after each batch
+ */
+export async function reindexEmbeddings(
+  onProgress: (progress: ReindexProgress) => void
+): Promise<ReindexProgress> {
+  const channel = new Channel<ReindexProgress>();
+  channel.onmessage = onProgress;
+  return invokeCommand<ReindexProgress>('reindex_embeddings', { onProgress: channel });
+}
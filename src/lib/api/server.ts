@@ -0,0 +1,138 @@
+/**
+ * Helpers for calling the local Axum API server from the webview.
+ * Every request carries the app's bearer token for this run.
+ */
+
+import { listen } from '@tauri-apps/api/event';
+import { invokeCommand } from '@/lib/tauri';
+
+export interface ApiServerStatus {
+  /** Port actually bound; 0 while the server is not running */
+  port: number;
+  /** e.g. `http://127.0.0.1:3030`; empty while the server is not running */
+  base_url: string;
+  is_running: boolean;
+  /** Bearer token of the desktop app */
+  api_token: string;
+}
+
+export type ApiScope = 'read' | 'write' | 'import' | 'admin';
+
+export interface ApiKey {
+  id: string;
+  name: string;
+  /** First characters of the key, for telling keys apart */
+  key_prefix: string;
+  scopes: ApiScope[];
+  request_count: number;
+  last_used_at: string | null;
+  created_at: string;
+  revoked_at: string | null;
+}
+
+export interface CreatedApiKey {
+  api_key: ApiKey;
+  /** The plaintext key; it is only returned here */
+  key: string;
+}
+
+let cachedToken: string | null = null;
+let cachedBaseUrl: string | null = null;
+let watchingServer = false;
+
+/**
+ * Forget the cached base URL and token whenever the server starts, stops or
+ * fails, so a restart on another port is picked up
+ */
+async function watchServer(): Promise<void> {
+  if (watchingServer) return;
+  watchingServer = true;
+
+  const clearCache = () => {
+    cachedToken = null;
+    cachedBaseUrl = null;
+  };
+  for (const event of ['api-server:started', 'api-server:stopped', 'api-server:failed']) {
+    await listen(event, clearCache);
+  }
+}
+
+/**
+ * Get the API server's port, base URL and running state with the app's token
+ */
+export async function getApiServerStatus(): Promise<ApiServerStatus> {
+  await watchServer();
+  const status = await invokeCommand<ApiServerStatus>('get_api_server_status');
+  cachedToken = status.api_token;
+  cachedBaseUrl = status.is_running ? status.base_url : null;
+  return status;
+}
+
+/**
+ * Get the API server's base URL, or null when it is disabled or not running
+ */
+export async function getApiBaseUrl(): Promise<string | null> {
+  if (cachedBaseUrl) return cachedBaseUrl;
+
+  const status = await getApiServerStatus();
+  return status.is_running ? status.base_url : null;
+}
+
+/**
+ * Get the app's API token for this run
+ */
+export async function getApiToken(): Promise<string> {
+  if (cachedToken) return cachedToken;
+
+  return (await getApiServerStatus()).api_token;
+}
+
+/**
+ * Replace the app's API token; the previous token stops working immediately
+ */
+export async function regenerateApiToken(): Promise<string> {
+  cachedToken = await invokeCommand<string>('regenerate_api_token');
+  return cachedToken;
+}
+
+/**
+ * fetch() against the API server with the Authorization header set
+ * @param path - Path starting with `/`, e.g. `/api/papers`
+ */
+export async function apiFetch(path: string, init: RequestInit = {}): Promise<Response> {
+  const baseUrl = await getApiBaseUrl();
+  if (!baseUrl) {
+    throw new Error('The local API server is not running');
+  }
+
+  const headers = new Headers(init.headers);
+  const token = await getApiToken();
+  if (token) {
+    headers.set('Authorization', `Bearer ${token}`);
+  }
+
+  return fetch(`${baseUrl}${path}`, { ...init, headers });
+}
+
+/**
+ * Create an API key for an external tool
+ * @param name - Label shown in the key list
+ * @param scopes - Scopes granted to the key
+ */
+export async function createApiKey(name: string, scopes: ApiScope[]): Promise<CreatedApiKey> {
+  return invokeCommand<CreatedApiKey>('create_api_key', { name, scopes });
+}
+
+/**
+ * List all API keys, including revoked ones
+ */
+export async function listApiKeys(): Promise<ApiKey[]> {
+  return invokeCommand<ApiKey[]>('list_api_keys');
+}
+
+/**
+ * Revoke an API key; it stops working immediately
+ */
+export async function revokeApiKey(id: string): Promise<void> {
+  return invokeCommand('revoke_api_key', { id });
+}
@@ -0,0 +1,34 @@
+/**
+ * Global shortcut API.
+ *
+ * Pressing the clipboard capture hotkey (`shortcut.capture_clipboard`)
+ * emits `clipboard-capture:identifier` with a detected DOI or arXiv ID to
+ * offer for import, or `clipboard-capture:clip` once a URL or text was
+ * saved as a clip.
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface ShortcutStatus {
+  /** Configured hotkey, e.g. `CommandOrControl+Shift+K` */
+  shortcut: string;
+  enabled: boolean;
+  registered: boolean;
+  /** Why registration failed, e.g. the hotkey is taken by another app */
+  error: string | null;
+}
+
+/** Payload of the `clipboard-capture:clip` event */
+export interface CapturedClipEvent {
+  clip_id: string;
+  title: string;
+  /** The URL was clipped before; the existing clip is announced */
+  already_exists: boolean;
+}
+
+/**
+ * Get whether the clipboard capture hotkey is registered
+ */
+export async function getShortcutStatus(): Promise<ShortcutStatus> {
+  return invokeCommand<ShortcutStatus>('get_shortcut_status');
+}
@@ -0,0 +1,110 @@
+/**
+ * Statistics API functions
+ * Library overview for the statistics dashboard
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface NamedCount {
+  /** Category, label or author id; absent for read statuses and journals */
+  id: string | null;
+  name: string;
+  count: number;
+}
+
+export interface YearCount {
+  year: number;
+  count: number;
+}
+
+export interface MonthCount {
+  /** `YYYY-MM` */
+  month: string;
+  count: number;
+}
+
+export interface LibraryStats {
+  total_papers: number;
+  papers_per_year: YearCount[];
+  papers_without_year: number;
+  by_category: NamedCount[];
+  by_label: NamedCount[];
+  by_read_status: NamedCount[];
+  /** Ten authors with the most papers */
+  top_authors: NamedCount[];
+  /** Ten journals with the most papers */
+  top_journals: NamedCount[];
+  attachment_bytes: number;
+  /** Papers added in each of the last 24 months, oldest first */
+  added_per_month: MonthCount[];
+}
+
+/**
+ * Get library statistics; papers in the trash are not counted
+ */
+export async function getLibraryStats(): Promise<LibraryStats> {
+  return invokeCommand<LibraryStats>('get_library_stats');
+}
+
+export interface MetadataCompletenessReport {
+  total_papers: number;
+  with_abstract: number;
+  with_doi: number;
+  with_year: number;
+  /** Journal or conference name */
+  with_journal: number;
+  with_authors: number;
+  with_keywords: number;
+  with_pdf: number;
+  /** Share of these fields that are filled in, from 0 to 1 */
+  completeness_score: number;
+}
+
+/**
+ * Count the papers that have each kind of metadata
+ */
+export async function getMetadataCompletenessReport(): Promise<MetadataCompletenessReport> {
+  return invokeCommand<MetadataCompletenessReport>('get_metadata_completeness_report');
+}
+
+export interface WeekCount {
+  /** ISO week, `YYYY-Www` */
+  week: string;
+  count: number;
+}
+
+export interface ReadingStatistics {
+  period_days: number;
+  /** Papers opened in the viewer or read */
+  total_papers_opened: number;
+  /** Papers read to their last page */
+  total_papers_finished: number;
+  total_pages_read: number;
+  average_session_length_minutes: number;
+  /** `YYYY-MM-DD` (UTC) of the day with the most reading time */
+  most_active_day: string | null;
+  /** Every week of the period, oldest first, including empty weeks */
+  papers_read_per_week: WeekCount[];
+  /** Categories of the papers opened or read, most papers first */
+  top_categories_by_reading: NamedCount[];
+}
+
+/**
+ * Get reading activity over the last days
+ * @param periodDays - Days to cover, from 1 to 3650
+ */
+export async function getReadingStatistics(periodDays: number): Promise<ReadingStatistics> {
+  return invokeCommand<ReadingStatistics>('get_reading_statistics', { periodDays });
+}
+
+/**
+ * Write the reading statistics of the last days to a JSON file
+ * @param periodDays - Days to cover, from 1 to 3650
+ * @param outputPath - File to write
+ */
+export async function exportReadingStatistics(
+  periodDays: number,
+  outputPath: string
+): Promise<void> {
+  return invokeCommand<void>('export_reading_statistics', { periodDays, outputPath });
+}
@@ -0,0 +1,64 @@
+/**
+ * Storage API functions
+ * Disk usage of the data folder, attachment sizes and data folder migration
+ */
+
+import { invokeCommand } from '@/lib/tauri';
+
+export interface PaperStorage {
+  paper_id: string;
+  title: string;
+  total_size_bytes: number;
+  file_count: number;
+}
+
+export interface DiskUsageBreakdown {
+  data_db_bytes: number;
+  files_bytes: number;
+  cache_bytes: number;
+  config_bytes: number;
+  logs_bytes: number;
+  /** Up to 20 papers whose attachments use the most space, largest first */
+  largest_papers: PaperStorage[];
+}
+
+export interface StoredAttachment {
+  id: string;
+  paper_id: string;
+  file_name: string | null;
+  file_type: string | null;
+  /** Size in bytes; null when the file is missing */
+  file_size: number | null;
+  created_at: string | null;
+}
+
+export interface StorageStats {
+  total_attachment_count: number;
+  total_attachment_size_bytes: number;
+  /** Up to 20 attachments, largest first */
+  largest_attachments: StoredAttachment[];
+  pdf_count: number;
+  other_count: number;
+}
+
+/**
+ * Count and size all attachments; missing sizes are read from disk first
+ */
+export async function getLibraryStorageStats(): Promise<StorageStats> {
+  return invokeCommand<StorageStats>('get_library_storage_stats');
+}
+
+/**
+ * Get the size of each data subdirectory and the largest papers
+ */
+export async function getDiskUsageBreakdown(): Promise<DiskUsageBreakdown> {
+  return invokeCommand<DiskUsageBreakdown>('get_disk_usage_breakdown');
+}
+
+/**
+ * Undo the last data folder migration before restarting. Emits
+ * `migration-rollback-complete` with the source path on success.
+ */
+export async function rollbackDataMigration(sourcePath: string): Promise<void> {
+  return invokeCommand<void>('rollback_data_migration', { sourcePath });
+}
@@ -0,0 +1,72 @@
+/**
+ * Summary API functions
+ * LLM summaries of papers, streamed while they are written
+ */
+
+import { Channel } from '@tauri-apps/api/core';
+import { invokeCommand } from '@/lib/tauri';
+
+/** `tldr` is three sentences; `detailed` is bullets for method, results and limitations */
+export type SummaryStyle = 'tldr' | 'detailed';
+
+export interface PaperSummary {
+  paper_id: string;
+  /** `abstract_<n>` for cached abstract summaries of at most n sentences */
+  style: SummaryStyle | `abstract_${number}`;
+  summary: string;
+  model: string;
+  created_at: string;
+}
+
+/**
+ * Summarize a paper with the configured LLM provider
+ * @param paperId - The paper ID
+ * @param style - Summary style
+ * @param onToken - Called with each piece of the summary as it arrives
+ * @returns The stored summary once the model has finished
+ */
+export async function summarizePaper(
+  paperId: string,
+  style: SummaryStyle,
+  onToken: (token: string) => void
+): Promise<PaperSummary> {
+  const channel = new Channel<string>();
+  channel.onmessage = onToken;
+  return invokeCommand<PaperSummary>('summarize_paper', { paperId, style, onToken: channel });
+}
+
+/**
+ * Get the stored summaries of a paper, newest first
+ * @param paperId - The paper ID
+ */
+export async function getPaperSummaries(paperId: string): Promise<PaperSummary[]> {
+  return invokeCommand<PaperSummary[]>('get_paper_summaries', { paperId });
+}
+
+/** Abstract summary, cached until the paper is edited */
+export interface AbstractSummary {
+  paper_id: string;
+  summary: string;
+  model_used: string;
+  generated_at: string;
+}
+
+/**
+ * Summarize a paper's abstract; returns the cached summary when there is one
+ * @param paperId - The paper ID
+ * @param maxSentences - Longest summary in sentences, 1 to 10
+ */
+export async function summarizePaperAbstract(
+  paperId: string,
+  maxSentences: number
+): Promise<AbstractSummary> {
+  return invokeCommand<AbstractSummary>('summarize_paper_abstract', { paperId, maxSentences });
+}
+
+/**
+ * Get the newest cached abstract summary of a paper, if still current
+ * @param paperId - The paper ID
+ */
+export async function getCachedSummary(paperId: string): Promise<AbstractSummary | null> {
+  return invokeCommand<AbstractSummary | null>('get_cached_summary', { paperId });
+}